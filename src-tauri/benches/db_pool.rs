@@ -0,0 +1,159 @@
+//! Database throughput with several workers and an active UI/SSE client.
+//!
+//! Each worker thread records events and extends its ticket lock the way a
+//! running agent does, while a client thread keeps re-reading the board and
+//! run events like the UI does after every live event. The same load runs
+//! once with every query on the single writer connection and once with the
+//! reader pool.
+//!
+//! Run with `cargo bench --bench db_pool`. `BENCH_WORKERS` (default 4) and
+//! `BENCH_SECS` (default 3) adjust the load. Under `cargo test` each
+//! configuration only runs briefly, as a smoke test.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use agent_kanban::db::{
+    AgentEventPayload, AgentType, CreateRun, CreateTicket, Database, EventType, NormalizedEvent,
+    Priority, DEFAULT_READERS,
+};
+use chrono::Utc;
+
+struct Totals {
+    writes: u64,
+    reads: u64,
+    slowest_read: Duration,
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn run(readers: usize, workers: usize, duration: Duration) -> Totals {
+    let dir = tempfile::tempdir().unwrap();
+    let db = Database::open_with_readers(dir.path().join("bench.db"), readers).unwrap();
+    let board = db.create_board("Bench").unwrap();
+    let column = db.get_columns(&board.id).unwrap().remove(0);
+
+    let runs: Vec<_> = (0..workers)
+        .map(|i| {
+            let ticket = db
+                .create_ticket(&CreateTicket {
+                    board_id: board.id.clone(),
+                    column_id: column.id.clone(),
+                    title: format!("Ticket {}", i),
+                    description_md: "Benchmark ticket".to_string(),
+                    priority: Priority::Medium,
+                    labels: vec![],
+                    project_id: None,
+                    agent_pref: None,
+                    workflow_type: Default::default(),
+                    model: None,
+                    branch_name: None,
+                    is_epic: false,
+                    epic_id: None,
+                    depends_on_epic_id: None,
+                    depends_on_epic_ids: vec![],
+                    scratchpad_id: None,
+                    custom_fields: Default::default(),
+                })
+                .unwrap();
+            let run = db
+                .create_run(&CreateRun {
+                    ticket_id: ticket.id.clone(),
+                    agent_type: AgentType::Claude,
+                    repo_path: "/tmp".to_string(),
+                    parent_run_id: None,
+                    stage: None,
+                })
+                .unwrap();
+            db.lock_ticket(&ticket.id, &run.id, Utc::now() + chrono::Duration::minutes(30))
+                .unwrap();
+            run
+        })
+        .collect();
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let writes = Arc::new(AtomicU64::new(0));
+
+    let worker_threads: Vec<_> = runs
+        .iter()
+        .cloned()
+        .map(|run| {
+            let (db, stop, writes) = (db.clone(), stop.clone(), writes.clone());
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    db.create_event(&NormalizedEvent {
+                        run_id: run.id.clone(),
+                        ticket_id: run.ticket_id.clone(),
+                        agent_type: AgentType::Claude,
+                        event_type: EventType::FileEdited,
+                        payload: AgentEventPayload {
+                            raw: None,
+                            structured: Some(serde_json::json!({ "path": "src/lib.rs" })),
+                        },
+                        timestamp: Utc::now(),
+                    })
+                    .unwrap();
+                    db.extend_lock(&run.ticket_id, &run.id, Utc::now() + chrono::Duration::minutes(30))
+                        .unwrap();
+                    writes.fetch_add(2, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+
+    let client = {
+        let (db, stop, board_id) = (db.clone(), stop.clone(), board.id.clone());
+        std::thread::spawn(move || {
+            let (mut reads, mut slowest) = (0u64, Duration::ZERO);
+            let mut i = 0;
+            while !stop.load(Ordering::Relaxed) {
+                let started = Instant::now();
+                db.get_tickets(&board_id, None).unwrap();
+                db.get_events(&runs[i % runs.len()].id).unwrap();
+                slowest = slowest.max(started.elapsed());
+                reads += 2;
+                i += 1;
+            }
+            (reads, slowest)
+        })
+    };
+
+    std::thread::sleep(duration);
+    stop.store(true, Ordering::Relaxed);
+    for worker in worker_threads {
+        worker.join().unwrap();
+    }
+    let (reads, slowest_read) = client.join().unwrap();
+
+    Totals {
+        writes: writes.load(Ordering::Relaxed),
+        reads,
+        slowest_read,
+    }
+}
+
+fn main() {
+    let workers = env_or("BENCH_WORKERS", 4).max(1) as usize;
+    // `cargo bench` passes --bench; anything else is a test run
+    let duration = if std::env::args().any(|arg| arg == "--bench") {
+        Duration::from_secs(env_or("BENCH_SECS", 3))
+    } else {
+        Duration::from_millis(200)
+    };
+    let secs = duration.as_secs_f64();
+
+    println!("{} workers, 1 client, {:.1}s per configuration", workers, secs);
+    for (label, readers) in [("writer only", 0), ("reader pool", DEFAULT_READERS)] {
+        let totals = run(readers, workers, duration);
+        println!(
+            "{:<12} writes {:>9.0}/s   reads {:>9.0}/s   slowest read {:?}",
+            label,
+            totals.writes as f64 / secs,
+            totals.reads as f64 / secs,
+            totals.slowest_read,
+        );
+    }
+}
@@ -53,12 +53,26 @@ pub fn build_command(config: &AgentRunConfig) -> (String, Vec<String>) {
         args.push(map_model_for_claude(model));
     }
     
+    // Board tools (get_ticket, add_comment, ...) scoped to this run
+    if !config.api_url.is_empty() {
+        args.push("--mcp-config".to_string());
+        args.push(generate_mcp_config(config).to_string());
+    }
+    
     args.push("-p".to_string());
     args.push(config.prompt.clone());
     
     (command, args)
 }
 
+/// `--mcp-config` value registering the Agent Kanban MCP server for a run
+pub fn generate_mcp_config(config: &AgentRunConfig) -> serde_json::Value {
+    let server = crate::api::mcp::server_config(&config.api_url, &config.api_token, &config.run_id);
+    serde_json::json!({
+        "mcpServers": { crate::api::mcp::SERVER_NAME: server }
+    })
+}
+
 pub fn is_claude_available() -> bool {
     Command::new("claude")
         .arg("--version")
@@ -569,6 +583,7 @@ mod tests {
             repo_path: PathBuf::from("/tmp/test"),
             prompt: "Test prompt".to_string(),
             timeout_secs: Some(300),
+            wrap_up_secs: None,
             api_url: "http://localhost:7432".to_string(),
             api_token: "token".to_string(),
             model: None,
@@ -604,6 +619,17 @@ mod tests {
         assert_eq!(prompt_index, p_index + 1, "-p must be immediately followed by the prompt");
     }
 
+    #[test]
+    fn build_command_registers_mcp_server() {
+        let config = create_test_config();
+        let (_, args) = build_command(&config);
+        let idx = args.iter().position(|a| a == "--mcp-config").unwrap();
+        let mcp: serde_json::Value = serde_json::from_str(&args[idx + 1]).unwrap();
+        let server = &mcp["mcpServers"]["agent-kanban"];
+        assert_eq!(server["type"], "http");
+        assert!(server["url"].as_str().unwrap().starts_with("http://localhost:7432/v1/mcp?run_id="));
+    }
+
     #[test]
     fn build_command_includes_model_when_specified() {
         let mut config = create_test_config();
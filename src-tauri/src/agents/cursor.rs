@@ -183,9 +183,86 @@ pub fn install_hooks_with_run_id(
         serde_json::to_string_pretty(&hooks_json).unwrap(),
     )?;
 
+    if let (Some(url), Some(token), Some(run_id)) = (api_url, api_token, run_id) {
+        install_mcp_server(repo_path, url, token, run_id)?;
+    }
+
     Ok(())
 }
 
+/// Register the Agent Kanban MCP server for a run in `.cursor/mcp.json`,
+/// keeping any other servers the project has configured.
+///
+/// The entry carries the API token and run id, so the file is added to the
+/// repository's `info/exclude` to keep it out of the agent's commits. An
+/// existing file that isn't valid JSON is left alone and reported as an error.
+pub fn install_mcp_server(
+    repo_path: &Path,
+    api_url: &str,
+    api_token: &str,
+    run_id: &str,
+) -> std::io::Result<()> {
+    let mcp_path = repo_path.join(".cursor").join("mcp.json");
+
+    let mut config = if mcp_path.exists() {
+        let content = std::fs::read_to_string(&mcp_path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{} is not valid JSON, not registering the MCP server: {}", mcp_path.display(), e),
+            )
+        })?
+    } else {
+        serde_json::json!({})
+    };
+
+    let server = crate::api::mcp::server_config(api_url, api_token, run_id);
+    if let Some(obj) = config.as_object_mut() {
+        let servers = obj
+            .entry("mcpServers")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(servers) = servers.as_object_mut() {
+            servers.insert(crate::api::mcp::SERVER_NAME.to_string(), server);
+        }
+    }
+
+    exclude_from_git(repo_path, "/.cursor/mcp.json")?;
+    std::fs::write(
+        mcp_path,
+        serde_json::to_string_pretty(&config).unwrap(),
+    )
+}
+
+/// Add `pattern` to the repository's `info/exclude`, unless it is already
+/// listed. Worktrees resolve to the exclude file of their main repository,
+/// which git applies to every worktree. Does nothing outside a git repository.
+fn exclude_from_git(repo_path: &Path, pattern: &str) -> std::io::Result<()> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "info/exclude"])
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        return Ok(());
+    }
+
+    let exclude_path = repo_path.join(String::from_utf8_lossy(&output.stdout).trim());
+    let existing = std::fs::read_to_string(&exclude_path).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == pattern) {
+        return Ok(());
+    }
+
+    if let Some(parent) = exclude_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(pattern);
+    content.push('\n');
+    std::fs::write(exclude_path, content)
+}
+
 pub fn global_hooks_path() -> Option<PathBuf> {
     dirs::home_dir().map(|h| h.join(".cursor").join("hooks.json"))
 }
@@ -388,6 +465,7 @@ mod tests {
             repo_path: PathBuf::from("/tmp/test"),
             prompt: "Test prompt".to_string(),
             timeout_secs: Some(300),
+            wrap_up_secs: None,
             api_url: "http://localhost:7432".to_string(),
             api_token: "token".to_string(),
             model: None,
@@ -602,6 +680,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn install_hooks_with_run_id_registers_mcp_server() {
+        let temp_dir = std::env::temp_dir().join(format!("cursor_mcp_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(temp_dir.join(".cursor")).unwrap();
+        std::fs::write(
+            temp_dir.join(".cursor").join("mcp.json"),
+            r#"{"mcpServers":{"other":{"command":"other-server"}}}"#,
+        ).unwrap();
+
+        install_hooks_with_run_id(
+            &temp_dir,
+            "/path/to/hook.js",
+            Some("http://127.0.0.1:7432"),
+            Some("tok"),
+            Some("run-1"),
+        ).unwrap();
+
+        let content = std::fs::read_to_string(temp_dir.join(".cursor").join("mcp.json")).unwrap();
+        let config: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(config["mcpServers"]["other"]["command"], "other-server");
+        assert_eq!(
+            config["mcpServers"]["agent-kanban"]["url"],
+            "http://127.0.0.1:7432/v1/mcp?run_id=run-1"
+        );
+        assert_eq!(config["mcpServers"]["agent-kanban"]["headers"]["X-AgentKanban-Token"], "tok");
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn install_mcp_server_keeps_unparseable_config() {
+        let temp_dir = std::env::temp_dir().join(format!("cursor_mcp_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(temp_dir.join(".cursor")).unwrap();
+        let mcp_path = temp_dir.join(".cursor").join("mcp.json");
+        std::fs::write(&mcp_path, "{ not json").unwrap();
+
+        let err = install_mcp_server(&temp_dir, "http://127.0.0.1:7432", "tok", "run-1").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(std::fs::read_to_string(&mcp_path).unwrap(), "{ not json");
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn install_mcp_server_excludes_config_from_git() {
+        let temp_dir = std::env::temp_dir().join(format!("cursor_mcp_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        Command::new("git").args(["init", "-q"]).current_dir(&temp_dir).status().unwrap();
+
+        install_mcp_server(&temp_dir, "http://127.0.0.1:7432", "tok", "run-1").unwrap();
+        install_mcp_server(&temp_dir, "http://127.0.0.1:7432", "tok", "run-2").unwrap();
+
+        let exclude = std::fs::read_to_string(temp_dir.join(".git").join("info").join("exclude")).unwrap();
+        assert_eq!(exclude.matches("/.cursor/mcp.json").count(), 1);
+        let status = Command::new("git")
+            .args(["status", "--porcelain", "--untracked-files=all"])
+            .current_dir(&temp_dir)
+            .output()
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&status.stdout).contains("mcp.json"));
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
     #[test]
     fn install_hooks_writes_valid_json() {
         let temp_dir = std::env::temp_dir().join(format!("cursor_test_{}", uuid::Uuid::new_v4()));
@@ -6,7 +6,6 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::AppHandle;
 
 use crate::db::{AgentType, AuthorType, CreateComment, CreateRun, Database, RunStatus};
 use super::worktree::{DiagnosticType, WorktreeError};
@@ -137,7 +136,6 @@ pub fn classify_worktree_error(error: &WorktreeError) -> DiagnosticContext {
 #[allow(clippy::too_many_arguments)]
 pub async fn run_diagnostic_agent(
     db: Arc<Database>,
-    _app_handle: Option<AppHandle>,
     ticket_id: &str,
     context: DiagnosticContext,
     api_url: &str,
@@ -186,6 +184,7 @@ pub async fn run_diagnostic_agent(
         repo_path: context.repo_path.clone(),
         prompt,
         timeout_secs: Some(300), // 5 minute timeout for diagnostics
+        wrap_up_secs: None,
         api_url: api_url.to_string(),
         api_token: api_token.to_string(),
         model,
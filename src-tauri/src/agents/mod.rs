@@ -1,6 +1,9 @@
 pub mod spawner;
+pub mod process_tree;
+pub mod slots;
 pub mod cursor;
 pub mod claude;
+pub mod claude_settings;
 pub mod prompt;
 pub mod worker;
 pub mod validation;
@@ -8,6 +11,8 @@ pub mod plan_validation;
 pub mod orchestrator;
 pub mod worktree;
 pub mod runner;
+pub mod recovery;
+pub mod sink;
 pub mod diagnostic;
 pub mod planner;
 pub mod planner_prompts;
@@ -24,8 +29,8 @@ pub struct ClaudeApiConfig {
     pub model_override: Option<String>,
 }
 
-impl From<crate::commands::claude::ClaudeApiSettings> for ClaudeApiConfig {
-    fn from(s: crate::commands::claude::ClaudeApiSettings) -> Self {
+impl From<claude_settings::ClaudeApiSettings> for ClaudeApiConfig {
+    fn from(s: claude_settings::ClaudeApiSettings) -> Self {
         Self {
             auth_token: s.auth_token,
             api_key: s.api_key,
@@ -50,6 +55,14 @@ impl AgentKind {
             AgentKind::Claude => "claude",
         }
     }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "cursor" => Some(AgentKind::Cursor),
+            "claude" => Some(AgentKind::Claude),
+            _ => None,
+        }
+    }
 }
 
 /// Configuration for running an agent
@@ -61,6 +74,9 @@ pub struct AgentRunConfig {
     pub repo_path: PathBuf,
     pub prompt: String,
     pub timeout_secs: Option<u64>,
+    /// Seconds after which the agent is interrupted (SIGINT) so it stops with
+    /// its partial work; the hard kill still comes at `timeout_secs`
+    pub wrap_up_secs: Option<u64>,
     pub api_url: String,
     pub api_token: String,
     pub model: Option<String>,
@@ -79,6 +95,9 @@ pub struct AgentRunResult {
     pub duration_secs: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub captured_stdout: Option<String>,
+    /// The agent was interrupted at its soft time limit (`wrap_up_secs`)
+    /// before it exited; `status` still follows its exit code
+    pub soft_timeout: bool,
 }
 
 /// Outcome of a run
@@ -91,6 +110,17 @@ pub enum RunOutcome {
     Cancelled,
 }
 
+impl RunOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunOutcome::Success => "success",
+            RunOutcome::Error => "error",
+            RunOutcome::Timeout => "timeout",
+            RunOutcome::Cancelled => "cancelled",
+        }
+    }
+}
+
 /// Callback for receiving log output
 pub type LogCallback = Box<dyn Fn(LogLine) + Send + Sync>;
 
@@ -4,13 +4,15 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::{AppHandle, Manager, Window};
 
-use crate::db::{Database, AgentType, CreateRun, RunStatus, Ticket, NormalizedEvent, EventType, AgentEventPayload, CreateComment, AuthorType};
-use crate::db::models::{Task, TaskType};
+use crate::db::{Database, Actor, AgentType, CreateRun, RunStatus, Ticket, NormalizedEvent, EventType, AgentEventPayload, CreateComment, AuthorType};
+use crate::db::models::{CheckpointState, CreateCheckpoint, StageTimeout, Task, TaskType};
+pub use crate::db::models::{ADHOC_STAGE_NAME, MULTI_STAGE_WORKFLOW};
 use crate::lifecycle::epic::{on_child_completed, on_child_blocked};
+use crate::lifecycle::TicketState;
 use super::{AgentKind, AgentRunConfig, AgentRunResult, ClaudeApiConfig, LogCallback, LogLine, LogStream, RunOutcome, extract_text_from_stream_json};
 use super::prompt::{generate_branch_name_generation_prompt, parse_branch_name_from_output, generate_plan_prompt, generate_implement_prompt, generate_command_prompt, generate_task_plan_prompt, generate_task_implement_prompt, generate_task_prompt};
+use super::sink::{emit_to, SharedEventSink};
 use super::spawner::{run_agent_with_capture, CancelHandle};
 use super::claude as claude_hooks;
 use super::cursor as cursor_hooks;
@@ -22,8 +24,8 @@ pub type CancelHandlesMap = Arc<Mutex<HashMap<String, CancelHandle>>>;
 /// Configuration for creating a WorkflowOrchestrator
 pub struct OrchestratorConfig {
     pub db: Arc<Database>,
-    pub window: Option<Window>,
-    pub app_handle: Option<AppHandle>,
+    /// Where stage, log and completion events are sent
+    pub event_sink: Option<SharedEventSink>,
     pub parent_run_id: String,
     pub ticket: Ticket,
     /// The task being executed. If None, falls back to legacy ticket-based workflow.
@@ -43,22 +45,40 @@ pub struct OrchestratorConfig {
     pub is_temp_branch: bool,
     /// Claude API configuration (auth token, api key, base url, model override)
     pub claude_api_config: Option<ClaudeApiConfig>,
+    /// Index into [`MULTI_STAGE_WORKFLOW`] to start at; non-zero when resuming
+    pub start_stage: usize,
+    /// Stage with a custom prompt to run before `start_stage`
+    pub extra_stage: Option<AdhocStage>,
 }
 
-/// The stages in a multi-stage workflow
-pub const MULTI_STAGE_WORKFLOW: &[&str] = &[
-    "branch",
-    "plan", 
-    "implement",
-    "deslop",
-    "cleanup",
-    "unit-tests",
-    "cleanup",
-    "review-changes",
-    "cleanup",
-    "review-changes",
-    "add-and-commit",
-];
+/// A stage with a custom prompt, run once before a restarted workflow continues
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdhocStage {
+    /// Recorded as the stage of its sub-run
+    pub name: String,
+    pub prompt: String,
+}
+
+/// Time limits of a stage when its project does not set any. Stages that
+/// only touch git are short; implementing gets the most time.
+pub fn default_stage_timeout(stage: &str) -> StageTimeout {
+    let (soft_secs, hard_secs) = match stage {
+        "branch-gen" => (60, 120),
+        "branch" | "add-and-commit" => (300, 600),
+        "plan" => (600, 900),
+        "implement" => (2400, 3600),
+        _ => (1500, 1800),
+    };
+    StageTimeout { soft_secs, hard_secs }
+}
+
+/// Position of the plan stage; workflows resumed after it reuse the saved plan
+const PLAN_STAGE: usize = 1;
+/// Position of the implement stage; the ticket is in review after it
+const IMPLEMENT_STAGE: usize = 2;
+
+/// How often a paused workflow checks whether it was resumed or cancelled
+const PAUSE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
 
 /// Event payload for stage updates
 #[derive(Debug, Clone, serde::Serialize)]
@@ -74,8 +94,7 @@ pub struct StageEvent {
 /// Orchestrates a multi-stage workflow for a ticket
 pub struct WorkflowOrchestrator {
     db: Arc<Database>,
-    window: Option<Window>,
-    app_handle: Option<AppHandle>,
+    event_sink: Option<SharedEventSink>,
     parent_run_id: String,
     ticket: Ticket,
     /// The task being executed. If None, falls back to legacy ticket-based workflow.
@@ -98,14 +117,15 @@ pub struct WorkflowOrchestrator {
     is_temp_branch: bool,
     /// Claude API configuration (auth token, api key, base url, model override)
     claude_api_config: Option<ClaudeApiConfig>,
+    start_stage: usize,
+    extra_stage: Option<AdhocStage>,
 }
 
 impl WorkflowOrchestrator {
     pub fn new(config: OrchestratorConfig) -> Self {
         Self {
             db: config.db,
-            window: config.window,
-            app_handle: config.app_handle,
+            event_sink: config.event_sink,
             parent_run_id: config.parent_run_id,
             ticket: config.ticket,
             task: config.task,
@@ -120,22 +140,14 @@ impl WorkflowOrchestrator {
             branch_already_created: config.branch_already_created,
             is_temp_branch: config.is_temp_branch,
             claude_api_config: config.claude_api_config,
+            start_stage: config.start_stage,
+            extra_stage: config.extra_stage,
         }
     }
     
-    /// Emit an event to the frontend, using window if available, otherwise app_handle
-    fn emit_event<S: serde::Serialize + Clone>(&self, event_name: &str, payload: &S) -> Result<(), String> {
-        if let Some(ref window) = self.window {
-            window.emit(event_name, payload)
-                .map_err(|e| format!("Failed to emit {} via window: {}", event_name, e))
-        } else if let Some(ref app_handle) = self.app_handle {
-            app_handle.emit_all(event_name, payload)
-                .map_err(|e| format!("Failed to emit {} via app_handle: {}", event_name, e))
-        } else {
-            // No window or app_handle, just log and continue
-            tracing::debug!("No window or app_handle available to emit {}", event_name);
-            Ok(())
-        }
+    /// Emit an event to the frontend (or SSE clients when running headless)
+    fn emit_event<S: serde::Serialize>(&self, event_name: &str, payload: &S) -> Result<(), String> {
+        emit_to(self.event_sink.as_ref(), event_name, payload)
     }
     
     /// Check if the workflow has been cancelled
@@ -203,13 +215,148 @@ impl WorkflowOrchestrator {
         }
     }
 
-    /// Execute the full multi-stage workflow
+    /// Execute the workflow from `start_stage`, saving a checkpoint after
+    /// every completed stage so an interrupted workflow can resume
     pub async fn execute(&self) -> Result<(), String> {
-        tracing::info!("Starting multi-stage workflow for ticket {}", self.ticket.id);
+        tracing::info!(
+            "Starting multi-stage workflow for ticket {} at stage {}",
+            self.ticket.id,
+            MULTI_STAGE_WORKFLOW.get(self.start_stage).unwrap_or(&"(done)")
+        );
+        
+        let result = self.execute_stages().await;
+        
+        let state = if result.is_ok() { CheckpointState::Finished } else { CheckpointState::Failed };
+        if let Err(e) = self.db.set_checkpoint_state(&self.parent_run_id, state) {
+            tracing::warn!("Failed to update workflow checkpoint of run {}: {}", self.parent_run_id, e);
+        }
+        result
+    }
+    
+    async fn execute_stages(&self) -> Result<(), String> {
+        // Move ticket to "In Progress" when workflow starts, or back to
+        // "Review" when resuming in the QA phase
+        if self.start_stage > IMPLEMENT_STAGE {
+            self.move_ticket_to_column(TicketState::Review);
+        } else {
+            self.move_ticket_to_column(TicketState::InProgress);
+        }
+        
+        let checkpoint = self.db.start_checkpoint(&CreateCheckpoint {
+            parent_run_id: self.parent_run_id.clone(),
+            ticket_id: self.ticket.id.clone(),
+            task_id: self.task.as_ref().map(|t| t.id.clone()),
+            agent_type: self.agent_kind,
+            worktree_path: self.repo_path.to_string_lossy().to_string(),
+            branch_name: self.worktree_branch.clone(),
+            next_stage: self.start_stage,
+        });
+        let mut plan = match checkpoint {
+            // Stages after the plan reuse the one saved before the interruption
+            Ok(checkpoint) if self.start_stage > PLAN_STAGE => checkpoint.plan_md.unwrap_or_default(),
+            Ok(_) => String::new(),
+            Err(e) => {
+                tracing::warn!("Failed to save workflow checkpoint of run {}: {}", self.parent_run_id, e);
+                String::new()
+            }
+        };
+        
+        // The checkpoint stays before `start_stage`, so a workflow interrupted
+        // during an extra stage resumes without it
+        if let Some(extra) = &self.extra_stage {
+            if self.is_cancelled() {
+                return Err("Workflow cancelled".to_string());
+            }
+            self.run_stage(&extra.name, &extra.prompt).await?;
+        }
+        
+        for (index, stage) in MULTI_STAGE_WORKFLOW.iter().enumerate().skip(self.start_stage) {
+            if self.is_cancelled() {
+                return Err("Workflow cancelled".to_string());
+            }
+            
+            let mut branch_name = None;
+            match *stage {
+                "branch" => branch_name = self.run_branch_stage().await?,
+                "plan" => plan = self.run_plan_stage().await?,
+                "implement" => {
+                    self.run_implement_stage(&plan).await?;
+                    // Move ticket to "Review" when entering QA phase
+                    self.move_ticket_to_column(TicketState::Review);
+                }
+                cmd => {
+                    self.run_stage(cmd, &generate_command_prompt(cmd, &self.repo_path)).await?;
+                }
+            }
+            
+            let plan_md = (*stage == "plan").then_some(plan.as_str());
+            if let Err(e) = self.db.advance_checkpoint(&self.parent_run_id, index + 1, branch_name.as_deref(), plan_md) {
+                tracing::warn!("Failed to save workflow checkpoint of run {}: {}", self.parent_run_id, e);
+            }
+            if index + 1 < MULTI_STAGE_WORKFLOW.len() {
+                self.pause_if_requested(stage, &mut plan).await?;
+            }
+        }
+        
+        // Move ticket to "Done" when workflow completes successfully
+        self.move_ticket_to_column(TicketState::Done);
+        
+        // Add workflow completion summary comment
+        self.add_workflow_summary_comment();
+        
+        tracing::info!("Multi-stage workflow completed for ticket {}", self.ticket.id);
+        Ok(())
+    }
+    
+    /// Hold the workflow after `stage` while a requested pause lasts. The
+    /// ticket stays locked since the run's heartbeat keeps going, and the
+    /// stages after it follow the plan as it was edited during the pause.
+    async fn pause_if_requested(&self, stage: &str, plan: &mut String) -> Result<(), String> {
+        match self.db.take_pause(&self.parent_run_id) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => {
+                tracing::warn!("Failed to check for a pause of run {}: {}", self.parent_run_id, e);
+                return Ok(());
+            }
+        }
+        
+        tracing::info!("Run {}: paused after stage '{}'", self.parent_run_id, stage);
+        let summary = format!("Paused after stage '{}'", stage);
+        if let Err(e) = self.db.update_run_status(&self.parent_run_id, RunStatus::Running, None, Some(&summary)) {
+            tracing::warn!("Failed to record pause of run {}: {}", self.parent_run_id, e);
+        }
+        self.emit_stage_event(stage, "paused", None, None);
         
-        // Move ticket to "In Progress" when workflow starts
-        self.move_ticket_to_column("In Progress");
+        loop {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+            if self.is_cancelled() {
+                return Err("Workflow cancelled".to_string());
+            }
+            match self.db.get_checkpoint(&self.parent_run_id) {
+                Ok(Some(checkpoint)) if checkpoint.state == CheckpointState::Paused => {}
+                Ok(Some(checkpoint)) if checkpoint.state == CheckpointState::Active => {
+                    if let Some(edited) = checkpoint.plan_md {
+                        *plan = edited;
+                    }
+                    break;
+                }
+                Ok(_) => return Err("Workflow stopped while paused".to_string()),
+                Err(e) => tracing::warn!("Failed to check whether run {} was resumed: {}", self.parent_run_id, e),
+            }
+        }
         
+        tracing::info!("Run {}: resumed after stage '{}'", self.parent_run_id, stage);
+        if let Err(e) = self.db.update_run_status(&self.parent_run_id, RunStatus::Running, None, None) {
+            tracing::warn!("Failed to record resume of run {}: {}", self.parent_run_id, e);
+        }
+        self.emit_stage_event(stage, "resumed", None, None);
+        Ok(())
+    }
+    
+    /// Name (and if needed create or rename) the ticket's branch; returns the
+    /// branch the workflow works on
+    async fn run_branch_stage(&self) -> Result<Option<String>, String> {
         // Handle branch creation based on whether we already have a branch name
         // and whether it was already created (e.g., via worktree)
         if let Some(ref branch_name) = self.worktree_branch {
@@ -270,10 +417,10 @@ Do NOT start implementing any code changes. Just rename the branch.
                     branch_name, new_branch_name, new_branch_name, branch_name
                 );
                 
-                let _rename_result = self.run_stage("branch", &rename_prompt).await?;
+                self.run_stage("branch", &rename_prompt).await?;
                 
                 // Now that the git rename succeeded, store the NEW branch name on ticket
-                if let Err(e) = self.db.set_ticket_branch(&self.ticket.id, &new_branch_name) {
+                if let Err(e) = self.db.set_ticket_branch(&self.ticket.id, &new_branch_name, &Actor::run(&self.parent_run_id)) {
                     tracing::warn!("Failed to store branch name on ticket: {}", e);
                 } else {
                     tracing::info!("Stored branch name '{}' on ticket {}", new_branch_name, self.ticket.id);
@@ -282,13 +429,14 @@ Do NOT start implementing any code changes. Just rename the branch.
                         "branchName": new_branch_name,
                     }));
                 }
+                Ok(Some(new_branch_name))
             } else {
                 // We have a permanent branch name already
                 tracing::info!("Using pre-determined branch name: {}", branch_name);
                 
                 // Store branch name on ticket if not already set
                 if self.ticket.branch_name.is_none() {
-                    if let Err(e) = self.db.set_ticket_branch(&self.ticket.id, branch_name) {
+                    if let Err(e) = self.db.set_ticket_branch(&self.ticket.id, branch_name, &Actor::run(&self.parent_run_id)) {
                         tracing::warn!("Failed to store branch name on ticket: {}", e);
                     } else {
                         tracing::info!("Stored branch name '{}' on ticket {}", branch_name, self.ticket.id);
@@ -322,8 +470,9 @@ Do NOT start implementing any code changes. Just create the branch.
                         branch_name
                     );
                     
-                    let _branch_result = self.run_stage("branch", &branch_prompt).await?;
+                    self.run_stage("branch", &branch_prompt).await?;
                 }
+                Ok(Some(branch_name.clone()))
             }
         } else {
             // No branch name yet - generate and create a branch
@@ -362,7 +511,7 @@ Do NOT start implementing any code changes. Just create the branch.
             
             // Store branch name on ticket BEFORE creating the branch
             // This allows the UI to show the branch immediately
-            if let Err(e) = self.db.set_ticket_branch(&self.ticket.id, &branch_to_create) {
+            if let Err(e) = self.db.set_ticket_branch(&self.ticket.id, &branch_to_create, &Actor::run(&self.parent_run_id)) {
                 tracing::warn!("Failed to store branch name on ticket: {}", e);
             } else {
                 tracing::info!("Stored branch name '{}' on ticket {}", branch_to_create, self.ticket.id);
@@ -396,14 +545,14 @@ Do NOT start implementing any code changes. Just create the branch.
                 branch_to_create
             );
             
-            let _branch_result = self.run_stage("branch", &branch_prompt).await?;
+            self.run_stage("branch", &branch_prompt).await?;
+            Ok(Some(branch_to_create))
         }
-        
-        // Stage 1: Plan
-        if self.is_cancelled() {
-            return Err("Workflow cancelled".to_string());
-        }
-        
+    }
+    
+    /// Run the plan stage and check whether the plan needs clarification
+    /// from the user; returns the plan text (empty for preset tasks)
+    async fn run_plan_stage(&self) -> Result<String, String> {
         // Use task-based prompts if we have a task, otherwise fall back to ticket-based
         let plan_prompt = if let Some(ref task) = self.task {
             // For preset tasks, we skip the plan stage and go directly to execution
@@ -418,7 +567,11 @@ Do NOT start implementing any code changes. Just create the branch.
             generate_plan_prompt(&self.ticket)
         };
         
-        let plan = if !plan_prompt.is_empty() {
+        if plan_prompt.is_empty() {
+            return Ok(String::new());
+        }
+        
+        let plan = {
             let plan_result = self.run_stage("plan", &plan_prompt).await?;
             // Extract only the text content from stream-json output.
             // The raw captured_stdout contains all tool calls, file reads, grep results, etc.
@@ -435,8 +588,6 @@ Do NOT start implementing any code changes. Just create the branch.
             );
             
             extracted
-        } else {
-            String::new()
         };
         
         if !plan.is_empty() {
@@ -474,7 +625,7 @@ Do NOT start implementing any code changes. Just create the branch.
                         });
                     
                     self.add_clarification_comment(&clarification_message);
-                    self.move_ticket_to_column("Blocked");
+                    self.move_ticket_to_column(TicketState::Blocked);
                     
                     return Err(format!("Plan requires user clarification: {}", result.reason));
                 }
@@ -495,45 +646,22 @@ Do NOT start implementing any code changes. Just create the branch.
             }
         }
         
-        // Stage 2: Implement
-        if self.is_cancelled() {
-            return Err("Workflow cancelled".to_string());
-        }
-        
+        Ok(plan)
+    }
+    
+    async fn run_implement_stage(&self, plan: &str) -> Result<(), String> {
         let implement_prompt = if let Some(ref task) = self.task {
             // For preset tasks, use the preset-specific prompt
             if task.task_type != TaskType::Custom {
                 generate_task_prompt(task, &self.ticket, &self.repo_path)
             } else {
-                generate_task_implement_prompt(task, &self.ticket, &plan)
+                generate_task_implement_prompt(task, &self.ticket, plan)
             }
         } else {
-            generate_implement_prompt(&self.ticket, &plan)
+            generate_implement_prompt(&self.ticket, plan)
         };
         
-        let _impl_result = self.run_stage("implement", &implement_prompt).await?;
-        
-        // Move ticket to "Review" when entering QA phase
-        self.move_ticket_to_column("Review");
-        
-        // Stage 3+: QA Commands
-        let qa_commands = &["deslop", "cleanup", "unit-tests", "cleanup", 
-                           "review-changes", "cleanup", "review-changes", "add-and-commit"];
-        
-        for cmd in qa_commands {
-            if self.is_cancelled() {
-                return Err("Workflow cancelled".to_string());
-            }
-            self.run_stage(cmd, &generate_command_prompt(cmd, &self.repo_path)).await?;
-        }
-        
-        // Move ticket to "Done" when workflow completes successfully
-        self.move_ticket_to_column("Done");
-        
-        // Add workflow completion summary comment
-        self.add_workflow_summary_comment();
-        
-        tracing::info!("Multi-stage workflow completed for ticket {}", self.ticket.id);
+        self.run_stage("implement", &implement_prompt).await?;
         Ok(())
     }
     
@@ -624,24 +752,25 @@ Do NOT start implementing any code changes. Just create the branch.
         }
     }
     
-    /// Move the ticket to a column by name (best effort - logs warning if column not found)
-    fn move_ticket_to_column(&self, column_name: &str) {
-        tracing::info!("Attempting to move ticket {} to column '{}' on board {}", 
-            self.ticket.id, column_name, self.ticket.board_id);
+    /// Move the ticket to the column playing `state` (best effort - logs warning if the board has none)
+    fn move_ticket_to_column(&self, state: TicketState) {
+        tracing::info!("Attempting to move ticket {} to the {} column on board {}", 
+            self.ticket.id, state.as_str(), self.ticket.board_id);
         
-        match self.db.find_column_by_name(&self.ticket.board_id, column_name) {
+        match self.db.find_column_by_role(&self.ticket.board_id, state) {
             Ok(Some(column)) => {
-                tracing::info!("Found column '{}' with id {} for board {}", 
-                    column_name, column.id, self.ticket.board_id);
-                if let Err(e) = self.db.move_ticket(&self.ticket.id, &column.id) {
-                    tracing::error!("Failed to move ticket {} to '{}': {}", self.ticket.id, column_name, e);
+                tracing::info!("Found {} column '{}' with id {} for board {}", 
+                    state.as_str(), column.name, column.id, self.ticket.board_id);
+                if let Err(e) = self.db.move_ticket(&self.ticket.id, &column.id, &Actor::run(&self.parent_run_id)) {
+                    tracing::error!("Failed to move ticket {} to '{}': {}", self.ticket.id, column.name, e);
                 } else {
-                    tracing::info!("Successfully moved ticket {} to column '{}'", self.ticket.id, column_name);
+                    tracing::info!("Successfully moved ticket {} to column '{}'", self.ticket.id, column.name);
                     // Emit event for frontend to update
                     if let Err(e) = self.emit_event("ticket-moved", &serde_json::json!({
                         "ticketId": self.ticket.id,
-                        "columnName": column_name,
+                        "columnName": column.name,
                         "columnId": column.id,
+                        "role": state,
                     })) {
                         tracing::warn!("Failed to emit ticket-moved event: {}", e);
                     } else {
@@ -651,14 +780,14 @@ Do NOT start implementing any code changes. Just create the branch.
                     // Epic lifecycle hooks: when a child ticket moves to Done or Blocked,
                     // trigger epic advancement or blocking
                     if self.ticket.epic_id.is_some() {
-                        match column_name {
-                            "Done" => {
+                        match state {
+                            TicketState::Done => {
                                 // Child completed - try to advance epic
                                 if let Err(e) = on_child_completed(&self.db, &self.ticket) {
                                     tracing::warn!("Epic advancement failed: {}", e);
                                 }
                             }
-                            "Blocked" => {
+                            TicketState::Blocked => {
                                 // Child blocked - block parent epic
                                 if let Err(e) = on_child_blocked(&self.db, &self.ticket) {
                                     tracing::warn!("Epic blocking failed: {}", e);
@@ -670,8 +799,8 @@ Do NOT start implementing any code changes. Just create the branch.
                 }
             }
             Ok(None) => {
-                tracing::error!("Column '{}' not found for board {}. Looking up available columns...", 
-                    column_name, self.ticket.board_id);
+                tracing::error!("Board {} has no {} column. Looking up available columns...", 
+                    self.ticket.board_id, state.as_str());
                 // Log available columns for debugging
                 if let Ok(columns) = self.db.get_columns(&self.ticket.board_id) {
                     let column_names: Vec<_> = columns.iter().map(|c| c.name.as_str()).collect();
@@ -679,11 +808,26 @@ Do NOT start implementing any code changes. Just create the branch.
                 }
             }
             Err(e) => {
-                tracing::error!("Error finding column '{}': {}", column_name, e);
+                tracing::error!("Error finding {} column: {}", state.as_str(), e);
             }
         }
     }
 
+    /// Time limits of `stage`: the project's own, or the stage default
+    fn stage_timeout(&self, stage: &str) -> StageTimeout {
+        let project = match self.ticket.project_id.as_deref().map(|id| self.db.get_project(id)) {
+            Some(Ok(project)) => project,
+            Some(Err(e)) => {
+                tracing::warn!("Failed to load time limits of project for stage '{}': {}", stage, e);
+                None
+            }
+            None => None,
+        };
+        project
+            .and_then(|project| project.stage_timeouts.get(stage).copied())
+            .unwrap_or_else(|| default_stage_timeout(stage))
+    }
+
     /// Run a single stage of the workflow
     async fn run_stage(
         &self,
@@ -720,13 +864,15 @@ Do NOT start implementing any code changes. Just create the branch.
             .map_err(|e| format!("Failed to update sub-run status: {}", e))?;
         
         // Build agent config
+        let timeout = self.stage_timeout(stage);
         let config = AgentRunConfig {
             kind: self.agent_kind,
             ticket_id: self.ticket.id.clone(),
             run_id: sub_run.id.clone(),
             repo_path: self.repo_path.clone(),
             prompt: prompt.to_string(),
-            timeout_secs: Some(1800), // 30 minutes per stage
+            timeout_secs: Some(timeout.hard_secs),
+            wrap_up_secs: Some(timeout.soft_secs),
             api_url: self.api_url.clone(),
             api_token: self.api_token.clone(),
             model: self.ticket.model.clone(),
@@ -737,8 +883,7 @@ Do NOT start implementing any code changes. Just create the branch.
         // Use PARENT run ID for both database storage and frontend events
         // This ensures events can be retrieved using ticket.lockedByRunId
         let db_for_logs = self.db.clone();
-        let window_for_logs = self.window.clone();
-        let app_handle_for_logs = self.app_handle.clone();
+        let sink_for_logs = self.event_sink.clone();
         let parent_run_id_for_logs = self.parent_run_id.clone();
         let ticket_id_for_logs = self.ticket.id.clone();
         let db_agent_type = match self.agent_kind {
@@ -777,7 +922,6 @@ Do NOT start implementing any code changes. Just create the branch.
             }
             
             // Emit to frontend for real-time display
-            // Use window if available (direct agent runs), otherwise use app_handle (worker runs)
             #[derive(serde::Serialize, Clone)]
             #[serde(rename_all = "camelCase")]
             struct AgentLogEvent {
@@ -793,14 +937,8 @@ Do NOT start implementing any code changes. Just create the branch.
                 timestamp: log.timestamp.to_rfc3339(),
             };
             
-            if let Some(ref window) = window_for_logs {
-                if let Err(e) = window.emit("agent-log", &event) {
-                    tracing::error!("Failed to emit agent-log event via window: {}", e);
-                }
-            } else if let Some(ref app_handle) = app_handle_for_logs {
-                if let Err(e) = app_handle.emit_all("agent-log", &event) {
-                    tracing::error!("Failed to emit agent-log event via app_handle: {}", e);
-                }
+            if let Err(e) = emit_to(sink_for_logs.as_ref(), "agent-log", &event) {
+                tracing::error!("Failed to emit agent-log event: {}", e);
             }
         }));
         
@@ -835,7 +973,7 @@ Do NOT start implementing any code changes. Just create the branch.
         
         // Run the agent with capture
         let start_time = std::time::Instant::now();
-        let result = tokio::task::spawn_blocking(move || {
+        let mut result = tokio::task::spawn_blocking(move || {
             run_agent_with_capture(config, Some(on_log), Some(on_spawn))
         }).await
             .map_err(|e| format!("Stage task failed: {}", e))?
@@ -854,6 +992,28 @@ Do NOT start implementing any code changes. Just create the branch.
         }
         
         let duration_secs = start_time.elapsed().as_secs_f64();
+
+        if result.status == RunOutcome::Timeout {
+            tracing::warn!(
+                "Stage '{}' of run {} timed out after {:.0}s (limits: {}s soft, {}s hard)",
+                stage, self.parent_run_id, duration_secs, timeout.soft_secs, timeout.hard_secs
+            );
+            result.summary = Some(format!(
+                "Stage '{}' hit its time limit ({}s soft, {}s hard): {}",
+                stage,
+                timeout.soft_secs,
+                timeout.hard_secs,
+                result.summary.as_deref().unwrap_or("timed out")
+            ));
+        } else if result.soft_timeout {
+            tracing::warn!(
+                "Stage '{}' of run {} was interrupted at its {}s soft limit and exited with {:?}",
+                stage, self.parent_run_id, timeout.soft_secs, result.exit_code
+            );
+        }
+        if let Err(e) = self.db.set_run_outcome(&sub_run.id, result.status.as_str()) {
+            tracing::warn!("Failed to record outcome of stage '{}': {}", stage, e);
+        }
         
         // Update sub-run status
         let status = match result.status {
@@ -879,6 +1039,9 @@ Do NOT start implementing any code changes. Just create the branch.
         );
         
         // Check if stage failed
+        if result.status == RunOutcome::Timeout {
+            return Err(result.summary.unwrap_or_default());
+        }
         if result.status != RunOutcome::Success {
             return Err(format!("Stage '{}' failed with status {:?}", stage, result.status));
         }
@@ -927,4 +1090,20 @@ mod tests {
         assert!(MULTI_STAGE_WORKFLOW.contains(&"implement"));
         assert!(MULTI_STAGE_WORKFLOW.contains(&"add-and-commit"));
     }
+
+    #[test]
+    fn default_stage_timeouts_leave_room_to_wrap_up() {
+        for stage in MULTI_STAGE_WORKFLOW.iter().chain(&["branch-gen", ADHOC_STAGE_NAME]) {
+            let timeout = default_stage_timeout(stage);
+            assert!(timeout.soft_secs > 0 && timeout.soft_secs < timeout.hard_secs, "{}", stage);
+        }
+        assert!(default_stage_timeout("implement").hard_secs > default_stage_timeout("plan").hard_secs);
+    }
+
+    #[test]
+    fn resume_positions_match_the_workflow() {
+        assert_eq!(MULTI_STAGE_WORKFLOW[0], "branch");
+        assert_eq!(MULTI_STAGE_WORKFLOW[PLAN_STAGE], "plan");
+        assert_eq!(MULTI_STAGE_WORKFLOW[IMPLEMENT_STAGE], "implement");
+    }
 }
@@ -187,6 +187,7 @@ pub async fn validate_plan_for_clarification(
         repo_path: config.repo_path.clone(),
         prompt,
         timeout_secs: Some(120),
+        wrap_up_secs: None,
         api_url: config.api_url.clone(),
         api_token: config.api_token.clone(),
         model: config.model.clone(),
@@ -277,6 +278,7 @@ pub async fn generate_clarification_message(
         repo_path: config.repo_path.clone(),
         prompt,
         timeout_secs: Some(120),
+        wrap_up_secs: None,
         api_url: config.api_url.clone(),
         api_token: config.api_token.clone(),
         model: config.model.clone(),
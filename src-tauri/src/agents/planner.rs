@@ -17,6 +17,7 @@ use crate::db::{
 
 #[cfg(test)]
 use crate::db::PlanTicket;
+use crate::lifecycle::TicketState;
 
 use super::planner_prompts;
 use super::spawner;
@@ -188,6 +189,7 @@ impl PlannerAgent {
             repo_path: self.config.repo_path.clone(),
             prompt: prompt.to_string(),
             timeout_secs: Some(300), // 5 min timeout for exploration/planning
+            wrap_up_secs: None,
             api_url: self.config.api_url.clone(),
             api_token: self.config.api_token.clone(),
             model: self.config.model.clone(),
@@ -425,14 +427,10 @@ impl PlannerAgent {
             .unwrap_or(&scratchpad.board_id);
         
         // Get target board's backlog column for creating tickets
-        let columns = self
+        let backlog_column = self
             .db
-            .get_columns(target_board_id)
-            .map_err(|e| PlannerError::Database(e.to_string()))?;
-
-        let backlog_column = columns
-            .iter()
-            .find(|c| c.name == "Backlog")
+            .find_column_by_role(target_board_id, TicketState::Backlog)
+            .map_err(|e| PlannerError::Database(e.to_string()))?
             .ok_or_else(|| PlannerError::ExecutionFailed("Backlog column not found on target board".to_string()))?;
 
         let mut epic_ids = Vec::new();
@@ -503,6 +501,7 @@ impl PlannerAgent {
                     depends_on_epic_id,
                     depends_on_epic_ids,
                     scratchpad_id: Some(scratchpad.id.clone()),
+                    custom_fields: Default::default(),
                 })
                 .map_err(|e| PlannerError::Database(e.to_string()))?;
 
@@ -540,6 +539,7 @@ impl PlannerAgent {
                         depends_on_epic_id: None,
                         depends_on_epic_ids: vec![],
                         scratchpad_id: Some(scratchpad.id.clone()),
+                        custom_fields: Default::default(),
                     })
                     .map_err(|e| PlannerError::Database(e.to_string()))?;
 
@@ -0,0 +1,365 @@
+//! Agents run in their own process group, so stopping a run also stops
+//! whatever the agent started, such as dev servers or test watchers.
+//!
+//! The group of every running agent is recorded on its `agent_runs` row. When
+//! the app dies without cleaning up, the rows keep their ids and
+//! [`reap_orphans`] kills those groups at the next launch.
+
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::{Lazy, OnceCell};
+
+use crate::db::Database;
+
+/// How long a process group has to exit after SIGTERM before it gets SIGKILL
+pub const KILL_GRACE: Duration = Duration::from_secs(5);
+
+/// Where process groups are recorded; unset in tests and remote workers
+static RECORDER: OnceCell<Arc<Database>> = OnceCell::new();
+
+/// Process groups of the agents this process is supervising
+static LIVE: Lazy<Mutex<HashSet<u32>>> = Lazy::new(Default::default);
+
+/// Record the process group of every agent spawned from now on in `db`
+pub fn record_in(db: Arc<Database>) {
+    let _ = RECORDER.set(db);
+}
+
+/// Make `cmd` start as the leader of a new process group
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Note that the agent of `run_id` leads process group `pgid`
+pub(crate) fn track(run_id: &str, pgid: u32) {
+    LIVE.lock().unwrap().insert(pgid);
+    if let Some(db) = RECORDER.get() {
+        if let Err(e) = db.set_run_pid(run_id, Some(pgid)) {
+            tracing::warn!("Failed to record process group {} of run {}: {}", pgid, run_id, e);
+        }
+    }
+}
+
+/// Note that process group `pgid` of `run_id` has exited
+pub(crate) fn untrack(run_id: &str, pgid: u32) {
+    LIVE.lock().unwrap().remove(&pgid);
+    if let Some(db) = RECORDER.get() {
+        if let Err(e) = db.set_run_pid(run_id, None) {
+            tracing::warn!("Failed to clear process group of run {}: {}", run_id, e);
+        }
+    }
+}
+
+/// Stop process group `pgid`: SIGTERM, then SIGKILL for whatever is still
+/// running after `grace`. `reap` is called while waiting so the caller can
+/// collect its own exited child, which would otherwise keep the group alive
+/// as a zombie. Returns false when there was no group to signal.
+pub fn terminate(pgid: u32, grace: Duration, mut reap: impl FnMut()) -> bool {
+    if !signal_group(pgid, Signal::Term) {
+        return false;
+    }
+    let deadline = std::time::Instant::now() + grace;
+    while std::time::Instant::now() < deadline {
+        reap();
+        if !is_alive(pgid) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    tracing::warn!("Process group {} still running {:?} after SIGTERM; killing it", pgid, grace);
+    signal_group(pgid, Signal::Kill);
+    reap();
+    true
+}
+
+/// Interrupt process group `pgid` with SIGINT, the signal a terminal sends
+/// on Ctrl-C. Agent CLIs stop early on it rather than being killed, so the
+/// edits they made and the output they printed so far are kept. Returns
+/// false when there was no group to signal.
+pub fn interrupt(pgid: u32) -> bool {
+    signal_group(pgid, Signal::Int)
+}
+
+/// Kill the recorded process groups of runs nothing in this process is
+/// supervising, which a crash leaves behind. Returns how many were running.
+pub fn reap_orphans(db: &Database) -> usize {
+    let recorded = match db.get_run_pids() {
+        Ok(recorded) => recorded,
+        Err(e) => {
+            tracing::warn!("Failed to load recorded agent processes: {}", e);
+            return 0;
+        }
+    };
+
+    let mut orphans = Vec::new();
+    for (run_id, pgid, started_at) in recorded {
+        if LIVE.lock().unwrap().contains(&pgid) {
+            continue;
+        }
+        if !belongs_to_run(pgid, started_at) {
+            if is_alive(pgid) {
+                tracing::info!(
+                    "Not stopping process group {} of run {}: it cannot be verified as the run's agent",
+                    pgid, run_id
+                );
+            }
+        } else if signal_group(pgid, Signal::Term) {
+            tracing::warn!("Stopping orphaned agent process group {} of run {}", pgid, run_id);
+            orphans.push(pgid);
+        }
+        if let Err(e) = db.set_run_pid(&run_id, None) {
+            tracing::warn!("Failed to clear process group of run {}: {}", run_id, e);
+        }
+    }
+
+    // Orphans are not our children, so there is nothing to reap; they share
+    // one grace period instead of getting one each
+    let deadline = std::time::Instant::now() + KILL_GRACE;
+    while orphans.iter().any(|pgid| is_alive(*pgid)) && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    for pgid in &orphans {
+        if is_alive(*pgid) {
+            signal_group(*pgid, Signal::Kill);
+        }
+    }
+    orphans.len()
+}
+
+/// How far the start of a group leader may be from the recorded start of
+/// the agent for the two to count as the same process
+const START_TOLERANCE_SECS: i64 = 5;
+
+/// A process as listed by `ps`
+#[derive(Debug, Clone, Copy)]
+struct ProcessInfo {
+    pid: u32,
+    pgid: u32,
+    elapsed_secs: i64,
+}
+
+/// Whether `pgid` is still the group that was recorded as started at
+/// `started_at`, rather than an unrelated process that got the same id after
+/// a reboot. Groups recorded without a start time cannot be verified and
+/// count as someone else's.
+fn belongs_to_run(pgid: u32, started_at: Option<DateTime<Utc>>) -> bool {
+    let (Some(started_at), Some(processes)) = (started_at, list_processes()) else {
+        return false;
+    };
+    owns_group(&processes, pgid, started_at, Utc::now())
+}
+
+/// [`belongs_to_run`] against a `ps` listing taken at `now`. A running leader
+/// must have started with the agent. Once the leader has exited its id cannot
+/// be handed out again while the rest of the group runs, so the group is the
+/// agent's as long as none of its members predates the agent and the machine
+/// has not restarted since, which `ps` shows as a younger pid 1.
+fn owns_group(processes: &[ProcessInfo], pgid: u32, started_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    let started = |process: &ProcessInfo| now - chrono::Duration::seconds(process.elapsed_secs);
+    let tolerance = chrono::Duration::seconds(START_TOLERANCE_SECS);
+    let members: Vec<_> = processes.iter().filter(|p| p.pgid == pgid).collect();
+
+    if let Some(leader) = members.iter().find(|p| p.pid == pgid) {
+        return (started(leader) - started_at).abs() <= tolerance;
+    }
+    let booted_before = processes.iter().any(|p| p.pid == 1 && started(p) <= started_at + tolerance);
+    booted_before && !members.is_empty() && members.iter().all(|p| started(p) >= started_at - tolerance)
+}
+
+/// Every process with its group and seconds since it started. `ps -o etime=`
+/// reports the age the same way on Linux and macOS; `-g` does not select by
+/// process group on both, so the whole table is listed.
+#[cfg(unix)]
+fn list_processes() -> Option<Vec<ProcessInfo>> {
+    let output = Command::new("ps")
+        .args(["-A", "-o", "pid=,pgid=,etime="])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let processes = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(ProcessInfo {
+                pid: fields.next()?.parse().ok()?,
+                pgid: fields.next()?.parse().ok()?,
+                elapsed_secs: parse_etime(fields.next()?)?,
+            })
+        })
+        .collect();
+    Some(processes)
+}
+
+#[cfg(not(unix))]
+fn list_processes() -> Option<Vec<ProcessInfo>> {
+    None
+}
+
+/// Parse the `[[dd-]hh:]mm:ss` elapsed time `ps` prints
+fn parse_etime(etime: &str) -> Option<i64> {
+    let (days, clock) = match etime.split_once('-') {
+        Some((days, clock)) => (days.parse::<i64>().ok()?, clock),
+        None => (0, etime),
+    };
+    let mut secs = 0;
+    let mut fields = 0;
+    for field in clock.split(':') {
+        secs = secs * 60 + field.parse::<i64>().ok()?;
+        fields += 1;
+    }
+    (2..=3).contains(&fields).then_some(days * 86_400 + secs)
+}
+
+enum Signal {
+    Probe,
+    Int,
+    Term,
+    Kill,
+}
+
+fn is_alive(pgid: u32) -> bool {
+    signal_group(pgid, Signal::Probe)
+}
+
+#[cfg(unix)]
+fn signal_group(pgid: u32, signal: Signal) -> bool {
+    let Ok(pgid) = libc::pid_t::try_from(pgid) else {
+        return false;
+    };
+    // Group 0 is our own and -1 addresses every process we may signal
+    // SAFETY: getpgrp and kill have no memory-safety preconditions
+    if pgid <= 1 || pgid == unsafe { libc::getpgrp() } {
+        return false;
+    }
+    let signal = match signal {
+        Signal::Probe => 0,
+        Signal::Int => libc::SIGINT,
+        Signal::Term => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    unsafe { libc::kill(-pgid, signal) == 0 }
+}
+
+#[cfg(not(unix))]
+fn signal_group(_pgid: u32, _signal: Signal) -> bool {
+    false
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Child;
+
+    fn spawn_group(script: &str) -> Child {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]);
+        isolate(&mut cmd);
+        cmd.spawn().unwrap()
+    }
+
+    #[test]
+    fn terminate_sends_sigterm_to_the_group() {
+        let mut child = spawn_group("sleep 30 & wait");
+        let pgid = child.id();
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(is_alive(pgid));
+
+        assert!(terminate(pgid, Duration::from_secs(2), || {
+            let _ = child.try_wait();
+        }));
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn groups_ignoring_sigterm_are_killed_after_the_grace_period() {
+        let mut child = spawn_group("trap '' TERM; sleep 30 & wait");
+        let pgid = child.id();
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(terminate(pgid, Duration::from_millis(300), || {
+            let _ = child.try_wait();
+        }));
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn interrupt_sends_sigint_to_the_group() {
+        let mut child = spawn_group("trap 'exit 3' INT; while :; do sleep 0.1; done");
+        let pgid = child.id();
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(interrupt(pgid));
+        assert_eq!(child.wait().unwrap().code(), Some(3));
+    }
+
+    #[test]
+    fn etime_is_parsed_in_every_ps_format() {
+        assert_eq!(parse_etime("00:07"), Some(7));
+        assert_eq!(parse_etime("12:34"), Some(754));
+        assert_eq!(parse_etime("01:02:03"), Some(3723));
+        assert_eq!(parse_etime("2-01:02:03"), Some(2 * 86_400 + 3723));
+        assert_eq!(parse_etime(""), None);
+        assert_eq!(parse_etime("7"), None);
+    }
+
+    #[test]
+    fn only_groups_started_when_recorded_belong_to_the_run() {
+        let mut child = spawn_group("sleep 30");
+        let pgid = child.id();
+
+        assert!(belongs_to_run(pgid, Some(Utc::now())));
+        assert!(!belongs_to_run(pgid, Some(Utc::now() - chrono::Duration::hours(1))));
+        assert!(!belongs_to_run(pgid, None));
+
+        let _ = child.kill();
+        let _ = child.wait();
+        assert!(!belongs_to_run(pgid, Some(Utc::now())));
+    }
+
+    #[test]
+    fn groups_whose_leader_exited_are_verified_through_their_members() {
+        let mut child = spawn_group("sleep 30 & exit 0");
+        let pgid = child.id();
+        let started_at = Utc::now();
+        child.wait().unwrap();
+        assert!(is_alive(pgid));
+
+        assert!(belongs_to_run(pgid, Some(started_at)));
+        assert!(!belongs_to_run(pgid, Some(started_at + chrono::Duration::hours(1))));
+
+        signal_group(pgid, Signal::Kill);
+    }
+
+    #[test]
+    fn leaderless_groups_do_not_survive_a_restart() {
+        let now = Utc::now();
+        let recorded = now - chrono::Duration::hours(2);
+        let member = ProcessInfo { pid: 4321, pgid: 4000, elapsed_secs: 60 };
+        let init = |elapsed_secs| ProcessInfo { pid: 1, pgid: 1, elapsed_secs };
+
+        assert!(owns_group(&[init(86_400), member], 4000, recorded, now));
+        assert!(!owns_group(&[init(3_600), member], 4000, recorded, now));
+        assert!(!owns_group(&[init(86_400)], 4000, recorded, now));
+    }
+
+    #[test]
+    fn reserved_groups_are_never_signalled() {
+        assert!(!signal_group(0, Signal::Probe));
+        assert!(!signal_group(1, Signal::Probe));
+        let own = unsafe { libc::getpgrp() } as u32;
+        assert!(!signal_group(own, Signal::Probe));
+    }
+}
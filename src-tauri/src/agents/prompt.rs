@@ -1,3 +1,4 @@
+use crate::db::custom_fields::value_text;
 use crate::db::models::{Priority, Ticket, Task, TaskType};
 use super::AgentKind;
 
@@ -16,6 +17,18 @@ fn slugify(title: &str) -> String {
         .collect()
 }
 
+/// List the ticket's custom field values, if it has any
+fn push_custom_fields(prompt: &mut String, ticket: &Ticket) {
+    if ticket.custom_fields.is_empty() {
+        return;
+    }
+    prompt.push_str("## Fields\n\n");
+    for (key, value) in &ticket.custom_fields {
+        prompt.push_str(&format!("- {}: {}\n", key, value_text(value)));
+    }
+    prompt.push('\n');
+}
+
 pub fn generate_ticket_prompt(ticket: &Ticket) -> String {
     generate_ticket_prompt_with_workflow(ticket, None)
 }
@@ -58,6 +71,8 @@ pub fn generate_ticket_prompt_full(ticket: &Ticket, agent_kind: Option<AgentKind
         prompt.push('\n');
     }
 
+    push_custom_fields(&mut prompt, ticket);
+
     if let Some(kind) = agent_kind {
         prompt.push_str("## Workflow\n\n");
         
@@ -122,6 +137,9 @@ pub fn generate_custom_prompt(ticket: &Ticket, template: &str) -> String {
     result = result.replace("{{description}}", &ticket.description_md);
     result = result.replace("{{priority}}", ticket.priority.as_str());
     result = result.replace("{{labels}}", &ticket.labels.join(", "));
+    for (key, value) in &ticket.custom_fields {
+        result = result.replace(&format!("{{{{field.{}}}}}", key), &value_text(value));
+    }
     result
 }
 
@@ -143,6 +161,9 @@ pub fn generate_system_prompt(api_url: &str, ticket_id: &str, run_id: &str) -> S
 
 ## Communication
 Your actions are being tracked via hooks. The board will be automatically updated as you work.
+Use the `agent-kanban` MCP tools (`get_ticket`, `add_comment`, `list_sibling_tickets`,
+`create_followup_ticket`, `update_task_status`, `ask_question`) to read the ticket, report
+progress and ask questions instead of calling the API from the shell.
 "#
     )
 }
@@ -328,6 +349,8 @@ pub fn generate_plan_prompt(ticket: &Ticket) -> String {
         prompt.push('\n');
     }
     
+    push_custom_fields(&mut prompt, ticket);
+    
     prompt.push_str(r#"## Instructions
 
 1. Analyze the task requirements
@@ -371,6 +394,8 @@ pub fn generate_implement_prompt(ticket: &Ticket, plan: &str) -> String {
         prompt.push_str("\n\n");
     }
     
+    push_custom_fields(&mut prompt, ticket);
+
     prompt.push_str("## Implementation Plan\n\n");
     prompt.push_str(plan);
     prompt.push_str("\n\n");
@@ -770,6 +795,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }
     }
 
@@ -867,6 +893,21 @@ mod tests {
         assert_eq!(result, "Title: Test Ticket, Priority: medium");
     }
 
+    #[test]
+    fn custom_fields_appear_in_prompts() {
+        let mut ticket = create_test_ticket();
+        ticket.custom_fields.insert("points".to_string(), serde_json::json!(3));
+        ticket.custom_fields.insert("customer".to_string(), serde_json::json!("acme"));
+
+        let prompt = generate_ticket_prompt(&ticket);
+        assert!(prompt.contains("## Fields\n\n- customer: acme\n- points: 3\n"));
+        assert!(generate_plan_prompt(&ticket).contains("- points: 3"));
+
+        let result = generate_custom_prompt(&ticket, "{{field.customer}} ({{field.points}} points)");
+        assert_eq!(result, "acme (3 points)");
+        assert!(!generate_ticket_prompt(&create_test_ticket()).contains("## Fields"));
+    }
+
     #[test]
     fn generate_system_prompt_includes_context() {
         let prompt = generate_system_prompt("http://localhost:7432", "ticket-1", "run-1");
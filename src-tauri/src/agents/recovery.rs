@@ -0,0 +1,578 @@
+//! Resuming multi-stage workflows interrupted by a restart.
+//!
+//! The orchestrator checkpoints every completed stage (see
+//! [`crate::db::checkpoints`]). At launch, workflows still marked active
+//! belonged to the previous process: [`interrupt_workflows`] aborts their
+//! runs and releases their tickets but keeps the worktrees, and
+//! [`resume_workflow`] later continues one from its first incomplete stage,
+//! either on request or automatically when [`RecoverySettings::auto_resume`]
+//! is on.
+//!
+//! Failed workflows keep their worktree too, so [`restart_workflow`] can run
+//! them again from an earlier stage, skip the stage that failed or insert a
+//! stage with a custom prompt.
+//!
+//! A paused workflow waits in its orchestrator between two stages, and
+//! [`resume_workflow`] lets it continue. Paused workflows stay paused across
+//! a restart: they are released like interrupted ones but are never resumed
+//! automatically.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use super::orchestrator::{AdhocStage, MULTI_STAGE_WORKFLOW};
+use super::runner::{self, CancelHandlesMap, RunnerConfig};
+use super::worker::WorkerConfig;
+use super::worktree;
+use super::AgentKind;
+use crate::db::{
+    Actor, AgentType, CheckpointState, CreateRun, Database, DbError, RunStatus, TaskStatus, WorkflowCheckpoint,
+};
+pub use crate::db::models::RecoverySettings;
+
+/// Cancel handles of resumed workflows, checked by `cancel_agent_run`
+pub static RESUMED_RUNS: Lazy<CancelHandlesMap> = Lazy::new(runner::create_cancel_handles);
+
+/// Cancel a resumed workflow running in this process
+pub fn cancel(run_id: &str) -> bool {
+    let handles = RESUMED_RUNS.lock().expect("cancel handles mutex poisoned");
+    match handles.get(run_id) {
+        Some(handle) => {
+            handle.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Mark the workflows left active by the previous process as interrupted
+/// (paused ones stay paused), abort their runs and unlock their tickets.
+/// Must run before anything resets the tasks of aborted runs. Returns how
+/// many can be resumed.
+pub fn interrupt_workflows(db: &Database) -> usize {
+    let mut checkpoints = Vec::new();
+    for state in [CheckpointState::Active, CheckpointState::Paused] {
+        match db.get_checkpoints(state) {
+            Ok(found) => checkpoints.extend(found),
+            Err(e) => {
+                tracing::error!("Failed to load workflow checkpoints: {}", e);
+                return 0;
+            }
+        }
+    }
+
+    let mut resumable = 0;
+    for checkpoint in checkpoints {
+        let worktree_exists = Path::new(&checkpoint.worktree_path).is_dir();
+        let (state, summary) = match (worktree_exists, checkpoint.state, &checkpoint.next_stage_name) {
+            (false, _, _) => (
+                CheckpointState::Failed,
+                format!("Interrupted; worktree {} no longer exists", checkpoint.worktree_path),
+            ),
+            (true, CheckpointState::Paused, _) => (
+                CheckpointState::Paused,
+                match &checkpoint.paused_after {
+                    Some(stage) => format!("Paused after stage '{}'; can be resumed", stage),
+                    None => "Paused; can be resumed".to_string(),
+                },
+            ),
+            (true, _, Some(stage)) => (
+                CheckpointState::Interrupted,
+                format!("Interrupted before stage '{}'; can be resumed", stage),
+            ),
+            (true, _, None) => (
+                CheckpointState::Interrupted,
+                "Interrupted after its last stage; can be resumed".to_string(),
+            ),
+        };
+        if let Err(e) = db.interrupt_checkpoint(&checkpoint.parent_run_id, state, &summary) {
+            tracing::error!("Failed to mark run {} interrupted: {}", checkpoint.parent_run_id, e);
+            continue;
+        }
+        match db.get_ticket(&checkpoint.ticket_id) {
+            Ok(ticket) if ticket.locked_by_run_id.as_deref() == Some(checkpoint.parent_run_id.as_str()) => {
+                if let Err(e) = db.unlock_ticket(&ticket.id, &Actor::system()) {
+                    tracing::warn!("Failed to unlock ticket {}: {}", ticket.id, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to load ticket {}: {}", checkpoint.ticket_id, e),
+        }
+        tracing::info!("Run {}: {}", checkpoint.parent_run_id, summary);
+        if worktree_exists {
+            resumable += 1;
+        }
+    }
+    resumable
+}
+
+/// Resume every interrupted workflow if the saved settings ask for it;
+/// called once at launch after [`interrupt_workflows`]. Paused workflows
+/// wait for the user.
+pub fn recover_interrupted_workflows(db: &Arc<Database>, config: impl Fn(AgentKind) -> WorkerConfig) {
+    let interrupted = match db.get_checkpoints(CheckpointState::Interrupted) {
+        Ok(interrupted) => interrupted,
+        Err(e) => {
+            tracing::error!("Failed to load interrupted workflows: {}", e);
+            return;
+        }
+    };
+    if interrupted.is_empty() {
+        return;
+    }
+    let auto_resume = db.get_recovery_settings().map(|s| s.auto_resume).unwrap_or(false);
+    if !auto_resume {
+        tracing::info!("{} interrupted workflow(s) can be resumed", interrupted.len());
+        return;
+    }
+    for checkpoint in interrupted {
+        match resume_workflow(db, &checkpoint.parent_run_id, &config) {
+            Ok(_) => tracing::info!("Resumed interrupted run {}", checkpoint.parent_run_id),
+            Err(e) => tracing::warn!("Could not resume run {}: {}", checkpoint.parent_run_id, e),
+        }
+    }
+}
+
+/// Continue a paused or interrupted workflow from its first incomplete
+/// stage, in its worktree and under its original run. A workflow paused in
+/// this process simply continues; any other is restarted and this returns
+/// once its ticket is locked again, with the stages running in the background.
+pub fn resume_workflow(
+    db: &Arc<Database>,
+    parent_run_id: &str,
+    config: impl Fn(AgentKind) -> WorkerConfig,
+) -> Result<WorkflowCheckpoint, DbError> {
+    let checkpoint = db
+        .get_checkpoint(parent_run_id)?
+        .ok_or_else(|| DbError::NotFound(format!("Checkpoint of run {}", parent_run_id)))?;
+    if is_held(db, &checkpoint)? {
+        return db.unpause_checkpoint(parent_run_id);
+    }
+    restart_workflow(db, parent_run_id, Restart::Resume, config)
+}
+
+/// Whether the workflow is paused, or about to pause, by an orchestrator
+/// that is still running: its run stays running until it completes
+fn is_held(db: &Database, checkpoint: &WorkflowCheckpoint) -> Result<bool, DbError> {
+    let pausing = match checkpoint.state {
+        CheckpointState::Paused => true,
+        CheckpointState::Active => checkpoint.pause_requested,
+        _ => false,
+    };
+    Ok(pausing && db.get_run(&checkpoint.parent_run_id)?.status == RunStatus::Running)
+}
+
+/// How a stopped workflow continues
+#[derive(Debug, Clone, PartialEq)]
+pub enum Restart {
+    /// From the first incomplete stage; only for interrupted or paused workflows
+    Resume,
+    /// From a stage up to and including the first incomplete one
+    From(usize),
+    /// Record the first incomplete stage as skipped and continue after it
+    Skip,
+    /// Run a stage with a custom prompt, then continue from `before` (by
+    /// default the first incomplete stage)
+    Insert { stage: AdhocStage, before: Option<usize> },
+}
+
+/// Continue a stopped (interrupted, failed, or paused before a restart)
+/// workflow as `restart` says, in its worktree and under its original run;
+/// new stages are recorded as further sub-runs. Returns once the ticket is
+/// locked again; the stages run in the background.
+pub fn restart_workflow(
+    db: &Arc<Database>,
+    parent_run_id: &str,
+    restart: Restart,
+    config: impl Fn(AgentKind) -> WorkerConfig,
+) -> Result<WorkflowCheckpoint, DbError> {
+    let checkpoint = db
+        .get_checkpoint(parent_run_id)?
+        .ok_or_else(|| DbError::NotFound(format!("Checkpoint of run {}", parent_run_id)))?;
+    if is_held(db, &checkpoint)? {
+        return Err(DbError::Conflict(format!(
+            "Run {} is paused; resume or cancel it first",
+            parent_run_id
+        )));
+    }
+    let restartable = match restart {
+        Restart::Resume => matches!(checkpoint.state, CheckpointState::Interrupted | CheckpointState::Paused),
+        _ => matches!(
+            checkpoint.state,
+            CheckpointState::Interrupted | CheckpointState::Paused | CheckpointState::Failed
+        ),
+    };
+    if !restartable {
+        return Err(DbError::Conflict(format!(
+            "Run {} is {} and cannot be {}",
+            parent_run_id,
+            checkpoint.state.as_str(),
+            if restart == Restart::Resume { "resumed" } else { "restarted" }
+        )));
+    }
+    let (start_stage, extra_stage) = plan_restart(&checkpoint, restart.clone())?;
+    let worktree_path = PathBuf::from(&checkpoint.worktree_path);
+    if !worktree_path.is_dir() {
+        db.set_checkpoint_state(parent_run_id, CheckpointState::Failed)?;
+        return Err(DbError::Conflict(format!(
+            "Worktree {} of run {} no longer exists",
+            checkpoint.worktree_path, parent_run_id
+        )));
+    }
+
+    let config = config(checkpoint.agent_type);
+    let ticket = db.get_ticket(&checkpoint.ticket_id)?;
+    let lock_expires = chrono::Utc::now() + chrono::Duration::minutes(config.lock_duration_mins);
+    db.lock_ticket(&ticket.id, parent_run_id, lock_expires)?;
+
+    // Until the workflow is spawned, any failure has to give the lock back
+    let started = (|| {
+        let task = match &checkpoint.task_id {
+            Some(task_id) => Some(db.get_task(task_id).and_then(|task| match task.status {
+                TaskStatus::Pending => db.start_task(task_id, parent_run_id),
+                TaskStatus::Failed => db.reset_task(task_id).and_then(|_| db.start_task(task_id, parent_run_id)),
+                _ => Ok(task),
+            })?),
+            None => None,
+        };
+
+        if restart == Restart::Skip {
+            let stage = &MULTI_STAGE_WORKFLOW[checkpoint.next_stage];
+            let skipped = db.create_run(&CreateRun {
+                ticket_id: ticket.id.clone(),
+                agent_type: match checkpoint.agent_type {
+                    AgentKind::Cursor => AgentType::Cursor,
+                    AgentKind::Claude => AgentType::Claude,
+                },
+                repo_path: checkpoint.worktree_path.clone(),
+                parent_run_id: Some(parent_run_id.to_string()),
+                stage: Some(stage.to_string()),
+            })?;
+            db.update_run_status(&skipped.id, RunStatus::Aborted, None, Some("Skipped"))?;
+            db.advance_checkpoint(parent_run_id, start_stage, None, None)?;
+            tracing::info!("Run {}: skipped stage '{}'", parent_run_id, stage);
+        }
+        let checkpoint = db.set_checkpoint_state(parent_run_id, CheckpointState::Active)?;
+        db.update_run_status(parent_run_id, RunStatus::Running, None, None)?;
+        Ok::<_, DbError>((task, checkpoint))
+    })();
+    let (task, checkpoint) = match started {
+        Ok(started) => started,
+        Err(e) => {
+            let _ = db.unlock_ticket(&ticket.id, &Actor::run(parent_run_id));
+            return Err(e);
+        }
+    };
+
+    // The project checkout the worktree belongs to, for removing it at the end
+    let main_repo = ticket
+        .project_id
+        .as_deref()
+        .and_then(|project_id| db.get_project(project_id).ok().flatten())
+        .map(|project| PathBuf::from(project.path));
+
+    let runner_config = RunnerConfig {
+        db: db.clone(),
+        event_sink: config.event_sink.clone(),
+        ticket: ticket.clone(),
+        task: task.clone(),
+        run_id: parent_run_id.to_string(),
+        repo_path: worktree_path.clone(),
+        agent_kind: checkpoint.agent_type,
+        api_url: config.api_url.clone(),
+        api_token: config.api_token.clone(),
+        hook_script_path: config.hook_script_path.clone(),
+        cancel_handles: RESUMED_RUNS.clone(),
+        worktree_branch: checkpoint.branch_name.clone().or_else(|| ticket.branch_name.clone()),
+        branch_already_created: true,
+        // Only a worker's first run leaves a temporary branch to rename
+        is_temp_branch: ticket.branch_name.is_none(),
+        timeout_secs: config.agent_timeout_secs,
+        claude_api_config: config.claude_api_config.clone(),
+        start_stage,
+        extra_stage,
+    };
+
+    let db = db.clone();
+    let run_id = parent_run_id.to_string();
+    tokio::spawn(async move {
+        let heartbeat = start_heartbeat(&db, &ticket.id, &run_id, &config);
+        let result = runner::execute_agent_run(runner_config).await;
+        heartbeat.abort();
+        RESUMED_RUNS.lock().expect("cancel handles mutex poisoned").remove(&run_id);
+
+        if let Some(task) = &task {
+            // Cancelled runs had their task reset to pending by the cancel handler
+            let update = match &result {
+                Ok(r) if r.status == RunStatus::Finished => Some(db.complete_task(&task.id)),
+                Ok(r) if r.status == RunStatus::Error => Some(db.fail_task(&task.id)),
+                Err(e) if !e.to_lowercase().contains("cancelled") => Some(db.fail_task(&task.id)),
+                _ => None,
+            };
+            if let Some(Err(e)) = update {
+                tracing::warn!("Failed to update task {} status: {}", task.id, e);
+            }
+        }
+        if let Err(e) = &result {
+            tracing::error!("Restarted run {} failed: {}", run_id, e);
+        }
+
+        if let Err(e) = db.unlock_ticket(&ticket.id, &Actor::run(&run_id)) {
+            tracing::error!("Failed to unlock ticket {}: {}", ticket.id, e);
+        }
+        match main_repo {
+            Some(main_repo) if main_repo != worktree_path && !keeps_worktree(&db, &run_id) => {
+                if let Err(e) = worktree::remove_worktree(&worktree_path, &main_repo) {
+                    tracing::warn!("Failed to remove worktree {}: {}", worktree_path.display(), e);
+                }
+            }
+            _ => {}
+        }
+    });
+
+    Ok(checkpoint)
+}
+
+/// The stage a restarted workflow starts at and the extra stage it runs first
+fn plan_restart(checkpoint: &WorkflowCheckpoint, restart: Restart) -> Result<(usize, Option<AdhocStage>), DbError> {
+    let reachable = |stage: usize| {
+        if stage > checkpoint.next_stage || stage >= MULTI_STAGE_WORKFLOW.len() {
+            return Err(DbError::Validation(format!(
+                "Run {} can only restart from one of its first {} stages",
+                checkpoint.parent_run_id,
+                (checkpoint.next_stage + 1).min(MULTI_STAGE_WORKFLOW.len())
+            )));
+        }
+        Ok(stage)
+    };
+    match restart {
+        Restart::Resume => Ok((checkpoint.next_stage, None)),
+        Restart::From(stage) => Ok((reachable(stage)?, None)),
+        Restart::Skip => {
+            if checkpoint.next_stage >= MULTI_STAGE_WORKFLOW.len() {
+                return Err(DbError::Validation(format!(
+                    "Run {} has no stage left to skip",
+                    checkpoint.parent_run_id
+                )));
+            }
+            Ok((checkpoint.next_stage + 1, None))
+        }
+        Restart::Insert { stage, before } => {
+            if stage.name.trim().is_empty() || stage.prompt.trim().is_empty() {
+                return Err(DbError::Validation("An inserted stage needs a name and a prompt".to_string()));
+            }
+            let before = match before {
+                Some(before) => reachable(before)?,
+                None => checkpoint.next_stage,
+            };
+            Ok((before, Some(stage)))
+        }
+    }
+}
+
+/// Whether a workflow that just ended keeps its worktree: failed workflows
+/// do, so they can be restarted from one of their stages
+pub fn keeps_worktree(db: &Database, parent_run_id: &str) -> bool {
+    matches!(
+        db.get_checkpoint(parent_run_id),
+        Ok(Some(checkpoint)) if checkpoint.state == CheckpointState::Failed
+    )
+}
+
+/// Remove the worktrees kept by a ticket's stopped workflows before a new run
+/// checks the ticket's branch out again; those workflows can no longer restart
+pub fn discard_stopped_workflows(db: &Database, ticket_id: &str, main_repo: &Path) {
+    let checkpoints = match db.get_ticket_checkpoints(ticket_id) {
+        Ok(checkpoints) => checkpoints,
+        Err(e) => {
+            tracing::warn!("Failed to load workflow checkpoints of ticket {}: {}", ticket_id, e);
+            return;
+        }
+    };
+    for checkpoint in checkpoints {
+        let worktree_path = Path::new(&checkpoint.worktree_path);
+        let stopped = match checkpoint.state {
+            CheckpointState::Interrupted | CheckpointState::Failed => true,
+            CheckpointState::Paused => !matches!(is_held(db, &checkpoint), Ok(true)),
+            _ => false,
+        };
+        if !stopped || !worktree_path.is_dir() || worktree_path == main_repo {
+            continue;
+        }
+        tracing::info!("Discarding worktree {} of stopped run {}", worktree_path.display(), checkpoint.parent_run_id);
+        if let Err(e) = worktree::remove_worktree(worktree_path, main_repo) {
+            tracing::warn!("Failed to remove worktree {}: {}", worktree_path.display(), e);
+        }
+        if let Err(e) = db.set_checkpoint_state(&checkpoint.parent_run_id, CheckpointState::Failed) {
+            tracing::warn!("Failed to update workflow checkpoint of run {}: {}", checkpoint.parent_run_id, e);
+        }
+    }
+}
+
+fn start_heartbeat(db: &Arc<Database>, ticket_id: &str, run_id: &str, config: &WorkerConfig) -> tokio::task::JoinHandle<()> {
+    let db = db.clone();
+    let ticket_id = ticket_id.to_string();
+    let run_id = run_id.to_string();
+    let interval = Duration::from_secs(config.heartbeat_interval_secs);
+    let lock_mins = config.lock_duration_mins;
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let expires = chrono::Utc::now() + chrono::Duration::minutes(lock_mins);
+            if let Err(e) = db.extend_lock(&ticket_id, &run_id, expires) {
+                tracing::error!("Heartbeat failed for ticket {}: {}", ticket_id, e);
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{AgentType, CreateCheckpoint, CreateRun, CreateTicket};
+
+    fn interrupted_run(db: &Arc<Database>, worktree_path: &str) -> (String, String) {
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket = db.create_ticket(&CreateTicket::for_test(&board.id, &columns[0].id, "Ticket")).unwrap();
+        let run = db.create_run(&CreateRun {
+            ticket_id: ticket.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: worktree_path.to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        db.update_run_status(&run.id, RunStatus::Running, None, None).unwrap();
+        db.lock_ticket(&ticket.id, &run.id, chrono::Utc::now() + chrono::Duration::minutes(30)).unwrap();
+        db.start_checkpoint(&CreateCheckpoint {
+            parent_run_id: run.id.clone(),
+            ticket_id: ticket.id.clone(),
+            task_id: None,
+            agent_type: AgentKind::Claude,
+            worktree_path: worktree_path.to_string(),
+            branch_name: None,
+            next_stage: 3,
+        }).unwrap();
+        (ticket.id, run.id)
+    }
+
+    #[test]
+    fn interrupted_workflows_release_their_tickets() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let worktree = std::env::temp_dir();
+        let (ticket_id, run_id) = interrupted_run(&db, &worktree.to_string_lossy());
+        let (_, gone_run_id) = interrupted_run(&db, "/nonexistent/agent-kanban-worktree");
+
+        assert_eq!(interrupt_workflows(&db), 1);
+
+        let checkpoint = db.get_checkpoint(&run_id).unwrap().unwrap();
+        assert_eq!(checkpoint.state, CheckpointState::Interrupted);
+        let run = db.get_run(&run_id).unwrap();
+        assert_eq!(run.status, RunStatus::Aborted);
+        assert!(run.summary_md.unwrap().contains("'deslop'"));
+        assert_eq!(db.get_ticket(&ticket_id).unwrap().locked_by_run_id, None);
+
+        let gone = db.get_checkpoint(&gone_run_id).unwrap().unwrap();
+        assert_eq!(gone.state, CheckpointState::Failed);
+    }
+
+    #[test]
+    fn only_interrupted_workflows_can_be_resumed() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let (_, run_id) = interrupted_run(&db, "/nonexistent/agent-kanban-worktree");
+
+        let err = resume_workflow(&db, &run_id, |_| WorkerConfig::default()).unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+
+        db.set_checkpoint_state(&run_id, CheckpointState::Interrupted).unwrap();
+        let err = resume_workflow(&db, &run_id, |_| WorkerConfig::default()).unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+        // A workflow whose worktree is gone can never resume
+        let checkpoint = db.get_checkpoint(&run_id).unwrap().unwrap();
+        assert_eq!(checkpoint.state, CheckpointState::Failed);
+
+        let err = resume_workflow(&db, "missing", |_| WorkerConfig::default()).unwrap_err();
+        assert!(matches!(err, DbError::NotFound(_)));
+    }
+
+    #[test]
+    fn paused_workflows_stay_paused_across_a_restart() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let worktree = std::env::temp_dir();
+        let (ticket_id, run_id) = interrupted_run(&db, &worktree.to_string_lossy());
+
+        // Paused in this process: resuming lets the waiting orchestrator go on
+        db.request_pause(&run_id).unwrap();
+        let resumed = resume_workflow(&db, &run_id, |_| WorkerConfig::default()).unwrap();
+        assert_eq!(resumed.state, CheckpointState::Active);
+        assert!(!resumed.pause_requested);
+
+        db.request_pause(&run_id).unwrap();
+        assert!(db.take_pause(&run_id).unwrap());
+        let err = restart_workflow(&db, &run_id, Restart::From(0), |_| WorkerConfig::default()).unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+
+        assert_eq!(interrupt_workflows(&db), 1);
+        let checkpoint = db.get_checkpoint(&run_id).unwrap().unwrap();
+        assert_eq!(checkpoint.state, CheckpointState::Paused);
+        let run = db.get_run(&run_id).unwrap();
+        assert_eq!(run.status, RunStatus::Aborted);
+        assert_eq!(run.summary_md.as_deref(), Some("Paused after stage 'implement'; can be resumed"));
+        assert_eq!(db.get_ticket(&ticket_id).unwrap().locked_by_run_id, None);
+    }
+
+    #[test]
+    fn restarts_stay_within_the_reached_stages() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let (_, run_id) = interrupted_run(&db, "/tmp/worktree");
+        let checkpoint = db.get_checkpoint(&run_id).unwrap().unwrap();
+        let custom = AdhocStage { name: "lint".to_string(), prompt: "Fix the lint errors".to_string() };
+
+        assert_eq!(plan_restart(&checkpoint, Restart::Resume).unwrap(), (3, None));
+        assert_eq!(plan_restart(&checkpoint, Restart::From(1)).unwrap(), (1, None));
+        assert_eq!(plan_restart(&checkpoint, Restart::From(3)).unwrap(), (3, None));
+        assert!(matches!(plan_restart(&checkpoint, Restart::From(4)), Err(DbError::Validation(_))));
+        assert_eq!(plan_restart(&checkpoint, Restart::Skip).unwrap(), (4, None));
+        assert_eq!(
+            plan_restart(&checkpoint, Restart::Insert { stage: custom.clone(), before: None }).unwrap(),
+            (3, Some(custom.clone()))
+        );
+        assert_eq!(
+            plan_restart(&checkpoint, Restart::Insert { stage: custom.clone(), before: Some(2) }).unwrap(),
+            (2, Some(custom.clone()))
+        );
+        let blank = AdhocStage { prompt: " ".to_string(), ..custom };
+        assert!(matches!(
+            plan_restart(&checkpoint, Restart::Insert { stage: blank, before: None }),
+            Err(DbError::Validation(_))
+        ));
+
+        let done = db.advance_checkpoint(&run_id, MULTI_STAGE_WORKFLOW.len(), None, None).unwrap();
+        assert!(matches!(plan_restart(&done, Restart::Skip), Err(DbError::Validation(_))));
+    }
+
+    #[test]
+    fn failed_workflows_keep_their_worktree_until_the_ticket_runs_again() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let main_repo = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        let (ticket_id, run_id) = interrupted_run(&db, &worktree.path().to_string_lossy());
+        assert!(!keeps_worktree(&db, &run_id));
+
+        db.set_checkpoint_state(&run_id, CheckpointState::Failed).unwrap();
+        assert!(keeps_worktree(&db, &run_id));
+
+        discard_stopped_workflows(&db, &ticket_id, main_repo.path());
+        assert!(!worktree.path().exists());
+        assert!(main_repo.path().exists());
+        // With the worktree gone the run can no longer restart
+        let err = restart_workflow(&db, &run_id, Restart::Skip, |_| WorkerConfig::default()).unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+    }
+}
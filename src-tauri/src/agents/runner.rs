@@ -7,13 +7,14 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Window};
 
-use crate::db::{Database, RunStatus, Ticket};
+use crate::db::{Actor, Database, RunStatus, Ticket};
 use crate::db::models::Task;
+use crate::lifecycle::TicketState;
 use super::{AgentKind, ClaudeApiConfig};
+use super::sink::{emit_to, SharedEventSink};
 use super::spawner::CancelHandle;
-use super::orchestrator::{WorkflowOrchestrator, OrchestratorConfig};
+use super::orchestrator::{AdhocStage, WorkflowOrchestrator, OrchestratorConfig};
 use super::claude as claude_hooks;
 use super::cursor as cursor_hooks;
 
@@ -23,8 +24,8 @@ pub type CancelHandlesMap = Arc<std::sync::Mutex<HashMap<String, CancelHandle>>>
 /// Configuration for running an agent
 pub struct RunnerConfig {
     pub db: Arc<Database>,
-    pub window: Option<Window>,
-    pub app_handle: Option<AppHandle>,
+    /// Where log, completion and ticket-move events are sent
+    pub event_sink: Option<SharedEventSink>,
     pub ticket: Ticket,
     /// The task being executed. If None, falls back to legacy ticket-based workflow.
     pub task: Option<Task>,
@@ -43,6 +44,10 @@ pub struct RunnerConfig {
     pub timeout_secs: u64,
     /// Claude API configuration (auth token, api key, base url, model override)
     pub claude_api_config: Option<ClaudeApiConfig>,
+    /// Index of the workflow stage to start at; non-zero when resuming
+    pub start_stage: usize,
+    /// Stage with a custom prompt to run before `start_stage`
+    pub extra_stage: Option<AdhocStage>,
 }
 
 /// Result of an agent run execution
@@ -179,17 +184,14 @@ pub async fn execute_agent_run(config: RunnerConfig) -> Result<RunnerResult, Str
                 Some("Workflow completed successfully"),
             ).map_err(|e| format!("Failed to update run status: {}", e))?;
             
-            // Emit completion event if we have a window
-            if let Some(ref window) = config.window {
-                let event = AgentCompleteEvent {
-                    run_id: config.run_id.clone(),
-                    status: "finished".to_string(),
-                    exit_code: Some(0),
-                    duration_secs,
-                };
-                if let Err(e) = window.emit("agent-complete", &event) {
-                    tracing::error!("Failed to emit agent-complete event: {}", e);
-                }
+            let event = AgentCompleteEvent {
+                run_id: config.run_id.clone(),
+                status: "finished".to_string(),
+                exit_code: Some(0),
+                duration_secs,
+            };
+            if let Err(e) = emit_to(config.event_sink.as_ref(), "agent-complete", &event) {
+                tracing::error!("Failed to emit agent-complete event: {}", e);
             }
             
             Ok(RunnerResult {
@@ -210,17 +212,14 @@ pub async fn execute_agent_run(config: RunnerConfig) -> Result<RunnerResult, Str
             ).map_err(|db_err| format!("Failed to update run status: {}", db_err))?;
             
             // Move ticket to Blocked on error
-            move_ticket_to_column(&config.db, &config.ticket, "Blocked", config.window.as_ref());
+            move_ticket_to_column(&config.db, &config.ticket, TicketState::Blocked, &Actor::run(&config.run_id), config.event_sink.as_ref());
             
-            // Emit error event if we have a window
-            if let Some(ref window) = config.window {
-                let event = AgentErrorEvent {
-                    run_id: config.run_id.clone(),
-                    error: e.clone(),
-                };
-                if let Err(emit_err) = window.emit("agent-error", &event) {
-                    tracing::error!("Failed to emit agent-error event: {}", emit_err);
-                }
+            let event = AgentErrorEvent {
+                run_id: config.run_id.clone(),
+                error: e.clone(),
+            };
+            if let Err(emit_err) = emit_to(config.event_sink.as_ref(), "agent-error", &event) {
+                tracing::error!("Failed to emit agent-error event: {}", emit_err);
             }
             
             Ok(RunnerResult {
@@ -239,8 +238,7 @@ async fn execute_multi_stage_workflow(config: &RunnerConfig) -> Result<(), Strin
     
     let orchestrator = WorkflowOrchestrator::new(OrchestratorConfig {
         db: config.db.clone(),
-        window: config.window.clone(),
-        app_handle: config.app_handle.clone(),
+        event_sink: config.event_sink.clone(),
         parent_run_id: config.run_id.clone(),
         ticket: config.ticket.clone(),
         task: config.task.clone(),
@@ -254,33 +252,34 @@ async fn execute_multi_stage_workflow(config: &RunnerConfig) -> Result<(), Strin
         branch_already_created: config.branch_already_created,
         is_temp_branch: config.is_temp_branch,
         claude_api_config: config.claude_api_config.clone(),
+        start_stage: config.start_stage,
+        extra_stage: config.extra_stage.clone(),
     });
     
     orchestrator.execute().await
 }
 
-/// Move a ticket to a column by name
-fn move_ticket_to_column(db: &Database, ticket: &Ticket, column_name: &str, window: Option<&Window>) {
-    match db.find_column_by_name(&ticket.board_id, column_name) {
+/// Move a ticket to the column playing `state`
+fn move_ticket_to_column(db: &Database, ticket: &Ticket, state: TicketState, actor: &Actor, sink: Option<&SharedEventSink>) {
+    match db.find_column_by_role(&ticket.board_id, state) {
         Ok(Some(column)) => {
-            if let Err(e) = db.move_ticket(&ticket.id, &column.id) {
-                tracing::error!("Failed to move ticket {} to '{}': {}", ticket.id, column_name, e);
+            if let Err(e) = db.move_ticket(&ticket.id, &column.id, actor) {
+                tracing::error!("Failed to move ticket {} to '{}': {}", ticket.id, column.name, e);
             } else {
-                tracing::info!("Moved ticket {} to column '{}'", ticket.id, column_name);
-                if let Some(window) = window {
-                    let _ = window.emit("ticket-moved", serde_json::json!({
-                        "ticketId": ticket.id,
-                        "columnName": column_name,
-                        "columnId": column.id,
-                    }));
-                }
+                tracing::info!("Moved ticket {} to column '{}'", ticket.id, column.name);
+                let _ = emit_to(sink, "ticket-moved", &serde_json::json!({
+                    "ticketId": ticket.id,
+                    "columnName": column.name,
+                    "columnId": column.id,
+                    "role": state,
+                }));
             }
         }
         Ok(None) => {
-            tracing::warn!("Column '{}' not found for board {}", column_name, ticket.board_id);
+            tracing::warn!("Board {} has no {} column", ticket.board_id, state.as_str());
         }
         Err(e) => {
-            tracing::error!("Error finding column '{}': {}", column_name, e);
+            tracing::error!("Error finding {} column: {}", state.as_str(), e);
         }
     }
 }
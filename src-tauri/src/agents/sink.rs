@@ -0,0 +1,132 @@
+//! Event emission for agents.
+//!
+//! The orchestrator, runner and workers report progress (`agent-log`,
+//! `agent-complete`, `ticket-moved`, stage updates, ...) through an
+//! [`EventSink`] instead of talking to Tauri directly. The desktop app plugs
+//! in its window or app handle (the impls live with the Tauri commands); the
+//! headless server forwards everything to SSE clients as
+//! [`LiveEvent::AgentEvent`].
+
+use std::sync::Arc;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::api::state::LiveEvent;
+
+/// Destination for named agent events
+pub trait EventSink: Send + Sync + std::fmt::Debug {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
+
+    /// Emit a [`LiveEvent`] that is not an agent event, such as a worker state
+    /// change. By default it goes out as a named event after its type
+    /// (`worker_state_changed` becomes `worker-state-changed`).
+    fn emit_live(&self, event: LiveEvent) -> Result<(), String> {
+        let payload = serde_json::to_value(&event)
+            .map_err(|e| format!("Failed to serialize live event: {}", e))?;
+        let name = payload["type"].as_str().unwrap_or("live-event").replace('_', "-");
+        self.emit(&name, payload)
+    }
+}
+
+pub type SharedEventSink = Arc<dyn EventSink>;
+
+/// Serialize `payload` and emit it on `sink`, if there is one.
+pub fn emit_to<S: Serialize>(
+    sink: Option<&SharedEventSink>,
+    event: &str,
+    payload: &S,
+) -> Result<(), String> {
+    let Some(sink) = sink else {
+        tracing::debug!("No event sink available to emit {}", event);
+        return Ok(());
+    };
+    let value = serde_json::to_value(payload)
+        .map_err(|e| format!("Failed to serialize {} payload: {}", event, e))?;
+    sink.emit(event, value)
+}
+
+/// Forwards agent events to the API's SSE stream
+#[derive(Debug, Clone)]
+pub struct BroadcastEventSink {
+    event_tx: broadcast::Sender<LiveEvent>,
+}
+
+impl BroadcastEventSink {
+    pub fn new(event_tx: broadcast::Sender<LiveEvent>) -> Self {
+        Self { event_tx }
+    }
+}
+
+impl EventSink for BroadcastEventSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        let field = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(String::from);
+        let run_id = field("runId").or_else(|| field("parentRunId"));
+        let ticket_id = field("ticketId");
+
+        // No subscribers is not an error; the event is simply dropped.
+        let _ = self.event_tx.send(LiveEvent::AgentEvent {
+            name: event.to_string(),
+            run_id,
+            ticket_id,
+            payload,
+        });
+        Ok(())
+    }
+
+    fn emit_live(&self, event: LiveEvent) -> Result<(), String> {
+        let _ = self.event_tx.send(event);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emit_to_without_sink_is_ok() {
+        assert!(emit_to(None, "agent-log", &serde_json::json!({})).is_ok());
+    }
+
+    #[test]
+    fn broadcast_sink_extracts_ids() {
+        let (tx, mut rx) = broadcast::channel(4);
+        let sink: SharedEventSink = Arc::new(BroadcastEventSink::new(tx));
+
+        emit_to(
+            Some(&sink),
+            "stage-update",
+            &serde_json::json!({ "parentRunId": "r1", "stage": "plan" }),
+        ).unwrap();
+
+        match rx.try_recv().unwrap() {
+            LiveEvent::AgentEvent { name, run_id, ticket_id, payload } => {
+                assert_eq!(name, "stage-update");
+                assert_eq!(run_id.as_deref(), Some("r1"));
+                assert!(ticket_id.is_none());
+                assert_eq!(payload["stage"], "plan");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn broadcast_sink_sends_live_events_as_is() {
+        let (tx, mut rx) = broadcast::channel(4);
+        let sink = BroadcastEventSink::new(tx);
+
+        sink.emit_live(LiveEvent::LabelsChanged { board_id: "b1".to_string() }).unwrap();
+
+        match rx.try_recv().unwrap() {
+            LiveEvent::LabelsChanged { board_id } => assert_eq!(board_id, "b1"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn broadcast_sink_without_subscribers_is_ok() {
+        let (tx, _) = broadcast::channel(4);
+        let sink = BroadcastEventSink::new(tx);
+        assert!(sink.emit("agent-log", serde_json::json!({ "runId": "r1" })).is_ok());
+    }
+}
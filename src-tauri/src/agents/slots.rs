@@ -0,0 +1,327 @@
+//! Machine-wide limits on concurrent agent processes.
+//!
+//! Every worker spawns agents on its own, so without a shared cap four Claude
+//! workers hit the provider's rate limits together. The spawner takes a slot
+//! from [`SLOTS`] before starting a process and gives it back when the run
+//! ends; runs that find every matching slot taken wait (and can still be
+//! cancelled) until one is freed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use super::spawner::CancelHandle;
+use super::{AgentKind, AgentRunConfig};
+pub use crate::db::models::ConcurrencyLimits;
+use crate::db::Database;
+
+/// How often a waiting run checks whether it was cancelled
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The slots shared by every worker and manual run in this process
+pub static SLOTS: Lazy<SlotPool> = Lazy::new(SlotPool::default);
+
+/// Apply the limits saved in `db`; called once at launch
+pub fn apply_saved_limits(db: &Database) {
+    match db.get_concurrency_limits() {
+        Ok(limits) => SLOTS.set_limits(limits),
+        Err(e) => tracing::error!("Failed to load concurrency limits: {}", e),
+    }
+}
+
+/// The run asking for a slot
+#[derive(Debug, Clone, Copy)]
+pub struct SlotRequest<'a> {
+    pub agent: AgentKind,
+    pub model: Option<&'a str>,
+    pub run_id: &'a str,
+    pub ticket_id: &'a str,
+}
+
+impl<'a> SlotRequest<'a> {
+    /// The model is the Claude override when one is set, as in the command
+    /// line the spawner builds
+    pub fn for_run(config: &'a AgentRunConfig) -> Self {
+        let model_override = match config.kind {
+            AgentKind::Claude => config.claude_api_config.as_ref().and_then(|c| c.model_override.as_deref()),
+            AgentKind::Cursor => None,
+        };
+        Self {
+            agent: config.kind,
+            model: model_override.filter(|m| !m.is_empty()).or(config.model.as_deref()),
+            run_id: &config.run_id,
+            ticket_id: &config.ticket_id,
+        }
+    }
+}
+
+/// A run waiting for a slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitingRun {
+    pub run_id: String,
+    pub ticket_id: String,
+    pub reason: String,
+}
+
+/// Limits together with what currently holds or waits for a slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyStatus {
+    pub limits: ConcurrencyLimits,
+    /// Agent processes holding a slot
+    pub running: u32,
+    pub running_per_agent: BTreeMap<String, u32>,
+    pub running_per_model: BTreeMap<String, u32>,
+    pub waiting: Vec<WaitingRun>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    limits: ConcurrencyLimits,
+    running: u32,
+    per_agent: BTreeMap<String, u32>,
+    per_model: BTreeMap<String, u32>,
+    /// Keyed by run id
+    waiting: HashMap<String, WaitingRun>,
+}
+
+impl PoolState {
+    /// Why a run of `agent` on `model` cannot start now, if it cannot
+    fn blocked_by(&self, agent: &str, model: Option<&str>) -> Option<String> {
+        if let Some(max) = self.limits.max_total.filter(|&max| self.running >= max) {
+            return Some(format!("All {} agent slots on this machine are in use", max));
+        }
+        if let Some(&max) = self.limits.per_agent.get(agent) {
+            if self.per_agent.get(agent).copied().unwrap_or(0) >= max {
+                return Some(format!("All {} {} slots are in use", max, agent));
+            }
+        }
+        if let Some(model) = model {
+            if let Some(&max) = self.limits.per_model.get(model) {
+                if self.per_model.get(model).copied().unwrap_or(0) >= max {
+                    return Some(format!("All {} slots for model {} are in use", max, model));
+                }
+            }
+        }
+        None
+    }
+
+    fn take(&mut self, agent: &str, model: Option<&str>) {
+        self.running += 1;
+        *self.per_agent.entry(agent.to_string()).or_default() += 1;
+        if let Some(model) = model {
+            *self.per_model.entry(model.to_string()).or_default() += 1;
+        }
+    }
+
+    fn give_back(&mut self, agent: &str, model: Option<&str>) {
+        self.running = self.running.saturating_sub(1);
+        release(&mut self.per_agent, agent);
+        if let Some(model) = model {
+            release(&mut self.per_model, model);
+        }
+    }
+}
+
+fn release(counts: &mut BTreeMap<String, u32>, key: &str) {
+    if let Some(count) = counts.get_mut(key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(key);
+        }
+    }
+}
+
+/// Counting semaphores keyed by agent kind and model, under one total cap
+#[derive(Debug, Default)]
+pub struct SlotPool {
+    state: Mutex<PoolState>,
+    freed: Condvar,
+}
+
+impl SlotPool {
+    /// Replace the limits; waiting runs are re-checked right away. Processes
+    /// already running keep their slot even when the new limit is lower.
+    pub fn set_limits(&self, limits: ConcurrencyLimits) {
+        self.state.lock().expect("slot mutex poisoned").limits = limits;
+        self.freed.notify_all();
+    }
+
+    pub fn status(&self) -> ConcurrencyStatus {
+        let state = self.state.lock().expect("slot mutex poisoned");
+        ConcurrencyStatus {
+            limits: state.limits.clone(),
+            running: state.running,
+            running_per_agent: state.per_agent.clone(),
+            running_per_model: state.per_model.clone(),
+            waiting: state.waiting.values().cloned().collect(),
+        }
+    }
+
+    /// What a run for `ticket_id` is waiting for, if one is waiting for a
+    /// slot. Workers look runs up by ticket since each stage of a workflow
+    /// spawns under its own sub-run id.
+    pub fn waiting_reason(&self, ticket_id: &str) -> Option<String> {
+        let state = self.state.lock().expect("slot mutex poisoned");
+        state.waiting.values().find(|w| w.ticket_id == ticket_id).map(|w| w.reason.clone())
+    }
+
+    /// Block until a slot for the request's agent (and model) is free.
+    /// Returns `None` when `cancel` fires first.
+    pub fn acquire(&self, request: SlotRequest<'_>, cancel: &CancelHandle) -> Option<SlotGuard<'_>> {
+        let (agent, model, run_id) = (request.agent.as_str(), request.model, request.run_id);
+        let mut state = self.state.lock().expect("slot mutex poisoned");
+        loop {
+            if cancel.is_cancelled() {
+                state.waiting.remove(run_id);
+                return None;
+            }
+            match state.blocked_by(agent, model) {
+                None => {
+                    state.take(agent, model);
+                    if state.waiting.remove(run_id).is_some() {
+                        tracing::info!("Run {} got a {} slot", run_id, agent);
+                    }
+                    return Some(SlotGuard {
+                        pool: self,
+                        agent: agent.to_string(),
+                        model: model.map(String::from),
+                    });
+                }
+                Some(reason) => {
+                    if state.waiting.get(run_id).map(|w| &w.reason) != Some(&reason) {
+                        tracing::info!("Run {} is waiting for a slot: {}", run_id, reason);
+                        state.waiting.insert(run_id.to_string(), WaitingRun {
+                            run_id: run_id.to_string(),
+                            ticket_id: request.ticket_id.to_string(),
+                            reason,
+                        });
+                    }
+                    state = self
+                        .freed
+                        .wait_timeout(state, CANCEL_CHECK_INTERVAL)
+                        .expect("slot mutex poisoned")
+                        .0;
+                }
+            }
+        }
+    }
+}
+
+/// A held slot; dropping it frees the slot for the next waiting run
+#[derive(Debug)]
+pub struct SlotGuard<'a> {
+    pool: &'a SlotPool,
+    agent: String,
+    model: Option<String>,
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        self.pool
+            .state
+            .lock()
+            .expect("slot mutex poisoned")
+            .give_back(&self.agent, self.model.as_deref());
+        self.pool.freed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    fn request<'a>(agent: AgentKind, model: Option<&'a str>, run_id: &'a str) -> SlotRequest<'a> {
+        SlotRequest { agent, model, run_id, ticket_id: run_id }
+    }
+
+    fn limits(max_total: Option<u32>, per_agent: &[(&str, u32)], per_model: &[(&str, u32)]) -> ConcurrencyLimits {
+        ConcurrencyLimits {
+            max_total,
+            per_agent: per_agent.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            per_model: per_model.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_zero_and_unknown_agents() {
+        assert!(limits(Some(4), &[("claude", 2)], &[("opus-4.5", 1)]).validate().is_ok());
+        assert!(limits(Some(0), &[], &[]).validate().is_err());
+        assert!(limits(None, &[("gpt", 1)], &[]).validate().is_err());
+        assert!(limits(None, &[], &[("opus-4.5", 0)]).validate().is_err());
+    }
+
+    #[test]
+    fn limits_apply_per_agent_model_and_in_total() {
+        let pool = SlotPool::default();
+        pool.set_limits(limits(Some(3), &[("claude", 2)], &[("opus-4.5", 1)]));
+        let cancel = CancelHandle::new();
+
+        let a = pool.acquire(request(AgentKind::Claude, Some("opus-4.5"), "r1"), &cancel).unwrap();
+        let state = pool.state.lock().unwrap();
+        assert!(state.blocked_by("claude", Some("opus-4.5")).unwrap().contains("model opus-4.5"));
+        assert!(state.blocked_by("claude", None).is_none());
+        drop(state);
+
+        let b = pool.acquire(request(AgentKind::Claude, None, "r2"), &cancel).unwrap();
+        assert!(pool.state.lock().unwrap().blocked_by("claude", None).unwrap().contains("2 claude"));
+        let c = pool.acquire(request(AgentKind::Cursor, None, "r3"), &cancel).unwrap();
+        assert!(pool.state.lock().unwrap().blocked_by("cursor", None).unwrap().contains("3 agent slots"));
+
+        drop((a, b, c));
+        let status = pool.status();
+        assert_eq!(status.running, 0);
+        assert!(status.running_per_agent.is_empty() && status.running_per_model.is_empty());
+    }
+
+    #[test]
+    fn waiting_run_starts_when_a_slot_is_freed() {
+        let pool = SlotPool::default();
+        pool.set_limits(limits(None, &[("claude", 1)], &[]));
+        let cancel = CancelHandle::new();
+        let held = pool.acquire(request(AgentKind::Claude, None, "r1"), &cancel).unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|s| {
+            s.spawn(|| {
+                let guard = pool.acquire(request(AgentKind::Claude, None, "r2"), &CancelHandle::new());
+                tx.send(guard.is_some()).unwrap();
+            });
+            while pool.waiting_reason("r2").is_none() {
+                thread::sleep(Duration::from_millis(10));
+            }
+            assert!(rx.try_recv().is_err());
+            drop(held);
+            assert!(rx.recv().unwrap());
+        });
+        assert!(pool.waiting_reason("r2").is_none());
+    }
+
+    #[test]
+    fn raising_the_limit_or_cancelling_releases_waiters() {
+        let pool = SlotPool::default();
+        pool.set_limits(limits(Some(1), &[], &[]));
+        let _held = pool.acquire(request(AgentKind::Cursor, None, "r1"), &CancelHandle::new()).unwrap();
+
+        let cancel = CancelHandle::new();
+        thread::scope(|s| {
+            let cancelled = s.spawn(|| pool.acquire(request(AgentKind::Cursor, None, "r2"), &cancel).is_some());
+            let raised = s.spawn(|| pool.acquire(request(AgentKind::Claude, None, "r3"), &CancelHandle::new()).is_some());
+            while pool.status().waiting.len() < 2 {
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            cancel.cancel();
+            assert!(!cancelled.join().unwrap());
+            pool.set_limits(limits(Some(2), &[], &[]));
+            assert!(raised.join().unwrap());
+        });
+        assert!(pool.status().waiting.is_empty());
+    }
+}
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use super::process_tree;
+use super::slots::{SlotRequest, SLOTS};
 use super::{AgentKind, AgentRunConfig, AgentRunResult, LogCallback, LogLine, LogStream, RunOutcome};
 
 /// Maximum number of retries for transient errors
@@ -61,6 +63,17 @@ impl AgentProcess {
         args: &[&str],
         working_dir: &std::path::Path,
         env_vars: &[(&str, &str)],
+    ) -> Result<Self, SpawnError> {
+        Self::spawn_with_cancel(command, args, working_dir, env_vars, &CancelHandle::new())
+    }
+
+    /// Start a new agent process that `cancel` stops
+    pub fn spawn_with_cancel(
+        command: &str,
+        args: &[&str],
+        working_dir: &std::path::Path,
+        env_vars: &[(&str, &str)],
+        cancel: &CancelHandle,
     ) -> Result<Self, SpawnError> {
         let mut cmd = Command::new(command);
 
@@ -69,6 +82,7 @@ impl AgentProcess {
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        process_tree::isolate(&mut cmd);
 
         for (key, value) in env_vars {
             cmd.env(key, value);
@@ -84,7 +98,7 @@ impl AgentProcess {
 
         Ok(Self {
             child,
-            cancelled: Arc::new(AtomicBool::new(false)),
+            cancelled: cancel.cancelled.clone(),
         })
     }
 
@@ -95,25 +109,46 @@ impl AgentProcess {
         }
     }
 
+    /// Process id of the agent, which is also the id of its process group
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Stop the agent and everything it started: SIGTERM to its process
+    /// group, then SIGKILL after [`process_tree::KILL_GRACE`]
+    fn kill_tree(&mut self) {
+        let pgid = self.child.id();
+        let child = &mut self.child;
+        if !process_tree::terminate(pgid, process_tree::KILL_GRACE, || {
+            let _ = child.try_wait();
+        }) {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+
     /// Wait for the process to complete, streaming output
     pub fn wait_with_output(
         self,
         timeout: Option<Duration>,
         on_log: Option<Arc<LogCallback>>,
     ) -> Result<(Option<i32>, RunOutcome), SpawnError> {
-        let (exit_code, outcome, _, _) = self.wait_with_capture(timeout, on_log, false)?;
+        let (exit_code, outcome, _, _, _) = self.wait_with_capture(timeout, None, on_log, false)?;
         Ok((exit_code, outcome))
     }
 
-    /// Wait for the process to complete, streaming output and optionally capturing stdout
-    /// Returns (exit_code, outcome, captured_stdout, captured_stderr)
+    /// Wait for the process to complete, streaming output and optionally capturing stdout.
+    /// After `wrap_up` the process group gets SIGINT once; the outcome still
+    /// follows the exit code, and `wrapped_up` reports that it was sent.
+    /// Returns (exit_code, outcome, wrapped_up, captured_stdout, captured_stderr)
     #[allow(clippy::type_complexity)]
     pub fn wait_with_capture(
         mut self,
         timeout: Option<Duration>,
+        wrap_up: Option<Duration>,
         on_log: Option<Arc<LogCallback>>,
         capture_stdout: bool,
-    ) -> Result<(Option<i32>, RunOutcome, Option<String>, Option<String>), SpawnError> {
+    ) -> Result<(Option<i32>, RunOutcome, bool, Option<String>, Option<String>), SpawnError> {
         let stdout = self.child.stdout.take();
         let stderr = self.child.stderr.take();
         let cancelled = self.cancelled.clone();
@@ -124,16 +159,18 @@ impl AgentProcess {
         });
 
         // Always capture stderr for transient error detection
-        let on_log_stderr = on_log;
+        let on_log_stderr = on_log.clone();
         let stderr_handle = stderr.map(|err| {
             thread::spawn(move || read_stream_with_capture(err, LogStream::Stderr, on_log_stderr, true))
         });
 
         let deadline = timeout.map(|t| Instant::now() + t);
+        let mut wrap_up_deadline = wrap_up.map(|t| Instant::now() + t);
+        let mut wrapped_up = false;
 
         loop {
             if cancelled.load(Ordering::Relaxed) {
-                let _ = self.child.kill();
+                self.kill_tree();
                 // Wait for reader threads to finish before returning
                 if let Some(h) = stdout_handle {
                     let _ = h.join();
@@ -146,7 +183,7 @@ impl AgentProcess {
 
             if let Some(deadline) = deadline {
                 if Instant::now() >= deadline {
-                    let _ = self.child.kill();
+                    self.kill_tree();
                     // Wait for reader threads to finish before returning
                     if let Some(h) = stdout_handle {
                         let _ = h.join();
@@ -158,8 +195,26 @@ impl AgentProcess {
                 }
             }
 
+            if wrap_up_deadline.is_some_and(|d| Instant::now() >= d) {
+                wrap_up_deadline = None;
+                wrapped_up = process_tree::interrupt(self.child.id());
+                if wrapped_up {
+                    tracing::info!("Interrupted process group {} at its soft time limit", self.child.id());
+                    if let Some(ref callback) = on_log {
+                        callback(LogLine {
+                            stream: LogStream::Stderr,
+                            content: "Soft time limit reached; interrupting the agent".to_string(),
+                            timestamp: chrono::Utc::now(),
+                        });
+                    }
+                }
+            }
+
             match self.child.try_wait() {
                 Ok(Some(status)) => {
+                    // Whatever the agent left running (dev servers, watchers)
+                    // would keep the output pipes open and the readers waiting
+                    process_tree::terminate(self.child.id(), process_tree::KILL_GRACE, || {});
                     let captured_stdout = if let Some(h) = stdout_handle {
                         h.join().ok().flatten()
                     } else {
@@ -178,7 +233,7 @@ impl AgentProcess {
                         RunOutcome::Error
                     };
 
-                    return Ok((exit_code, outcome, captured_stdout, captured_stderr));
+                    return Ok((exit_code, outcome, wrapped_up, captured_stdout, captured_stderr));
                 }
                 Ok(None) => {
                     thread::sleep(Duration::from_millis(100));
@@ -193,12 +248,16 @@ impl AgentProcess {
 }
 
 /// Handle to cancel a running process
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct CancelHandle {
     cancelled: Arc<AtomicBool>,
 }
 
 impl CancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     /// Signal the process to cancel
     pub fn cancel(&self) {
         self.cancelled.store(true, Ordering::Relaxed);
@@ -262,9 +321,28 @@ fn read_stream_with_capture<R: std::io::Read>(
     captured.map(|lines| lines.join("\n"))
 }
 
-/// Callback for receiving the cancel handle after spawn
+/// Callback for receiving the cancel handle; it is called before the run
+/// waits for a concurrency slot, so a waiting run can be cancelled too
 pub type OnSpawnCallback = Box<dyn FnOnce(CancelHandle) + Send>;
 
+/// Wait for a concurrency slot (see [`super::slots`]). `None` means the run
+/// was cancelled while waiting.
+fn acquire_slot(config: &AgentRunConfig, cancel: &CancelHandle) -> Option<super::slots::SlotGuard<'static>> {
+    SLOTS.acquire(SlotRequest::for_run(config), cancel)
+}
+
+fn cancelled_result(run_id: String, start_time: Instant) -> AgentRunResult {
+    AgentRunResult {
+        run_id,
+        exit_code: None,
+        status: RunOutcome::Cancelled,
+        summary: Some("Process was cancelled".to_string()),
+        duration_secs: start_time.elapsed().as_secs_f64(),
+        captured_stdout: None,
+        soft_timeout: false,
+    }
+}
+
 /// Run an agent with the given configuration
 pub fn run_agent(
     config: AgentRunConfig,
@@ -300,10 +378,21 @@ pub fn run_agent_with_cancel_callback(
     tracing::info!("Env vars: {:?}", env_vars.iter().map(|(k, _)| k).collect::<Vec<_>>());
     tracing::info!("Working directory: {:?}", config.repo_path);
 
+    let cancel = CancelHandle::new();
+    if let Some(callback) = on_spawn {
+        callback(cancel.clone());
+    }
+    // The slot is held across retries and released when this returns; the
+    // timeout only starts once the run has one
+    let Some(_slot) = acquire_slot(&config, &cancel) else {
+        return Ok(cancelled_result(config.run_id, start_time));
+    };
+
     // Calculate a global deadline to ensure total execution time respects the timeout contract.
     let global_deadline = config.timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
+    // Retries share the wrap-up deadline too
+    let wrap_up_deadline = config.wrap_up_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
     let mut attempt = 0;
-    let mut on_spawn = on_spawn;
 
     loop {
         attempt += 1;
@@ -326,6 +415,7 @@ pub fn run_agent_with_cancel_callback(
                 deadline - now
             }
         });
+        let remaining_wrap_up = wrap_up_deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
 
         // If we've exhausted our time budget, return a timeout result
         if let Some(remaining) = remaining_timeout {
@@ -345,29 +435,29 @@ pub fn run_agent_with_cancel_callback(
                     )),
                     duration_secs,
                     captured_stdout: None,
+                    soft_timeout: false,
                 });
             }
         }
 
         tracing::info!("Spawning agent process (attempt {})...", attempt);
-        let process = AgentProcess::spawn(
+        let process = AgentProcess::spawn_with_cancel(
             &command,
             &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
             &config.repo_path,
             &env_refs,
+            &cancel,
         )?;
         tracing::info!("Agent process spawned successfully");
-
-        // Provide cancel handle on first attempt only
-        if let Some(callback) = on_spawn.take() {
-            callback(process.cancel_handle());
-        }
+        let pgid = process.id();
+        process_tree::track(&config.run_id, pgid);
 
         // Enable stdout capture for agent summary extraction
-        let result = process.wait_with_capture(remaining_timeout, on_log.clone(), true);
+        let result = process.wait_with_capture(remaining_timeout, remaining_wrap_up, on_log.clone(), true);
+        process_tree::untrack(&config.run_id, pgid);
 
         match result {
-            Ok((exit_code, outcome, captured_stdout, captured_stderr)) => {
+            Ok((exit_code, outcome, soft_timeout, captured_stdout, captured_stderr)) => {
                 // Log stderr output when there's an error
                 if outcome == RunOutcome::Error {
                     if let Some(ref stderr) = captured_stderr {
@@ -390,8 +480,9 @@ pub fn run_agent_with_cancel_callback(
                     }
                 }
 
-                // Check if this is a transient error that should be retried
-                if outcome == RunOutcome::Error && attempt < MAX_TRANSIENT_RETRIES {
+                // Check if this is a transient error that should be retried;
+                // an agent stopped at its soft limit has no time left to retry
+                if outcome == RunOutcome::Error && !soft_timeout && attempt < MAX_TRANSIENT_RETRIES {
                     if let Some(ref stderr) = captured_stderr {
                         if is_transient_error(stderr) {
                             tracing::warn!(
@@ -413,13 +504,15 @@ pub fn run_agent_with_cancel_callback(
                     );
                 }
 
+                let summary = soft_timeout.then(|| wrap_up_summary(&config, exit_code));
                 return Ok(AgentRunResult {
                     run_id: config.run_id,
                     exit_code,
                     status: outcome,
-                    summary: None, // Will be filled in by caller
+                    summary, // Otherwise filled in by caller
                     duration_secs,
                     captured_stdout,
+                    soft_timeout,
                 });
             }
             Err(SpawnError::Timeout(secs)) => {
@@ -431,6 +524,7 @@ pub fn run_agent_with_cancel_callback(
                     summary: Some(format!("Process timed out after {} seconds", secs)),
                     duration_secs,
                     captured_stdout: None,
+                    soft_timeout: false,
                 });
             }
             Err(SpawnError::Cancelled) => {
@@ -442,6 +536,7 @@ pub fn run_agent_with_cancel_callback(
                     summary: Some("Process was cancelled".to_string()),
                     duration_secs,
                     captured_stdout: None,
+                    soft_timeout: false,
                 });
             }
             Err(e) => return Err(e),
@@ -473,11 +568,22 @@ pub fn run_agent_with_capture(
         .map(|(k, v)| (k.as_str(), v.as_str()))
         .collect();
 
+    let cancel = CancelHandle::new();
+    if let Some(callback) = on_spawn {
+        callback(cancel.clone());
+    }
+    // The slot is held across retries and released when this returns; the
+    // timeout only starts once the run has one
+    let Some(_slot) = acquire_slot(&config, &cancel) else {
+        return Ok(cancelled_result(config.run_id, start_time));
+    };
+
     // Calculate a global deadline to ensure total execution time respects the timeout contract.
     // This prevents retry attempts from each getting a fresh timeout window.
     let global_deadline = config.timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
+    // Retries share the wrap-up deadline too
+    let wrap_up_deadline = config.wrap_up_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
     let mut attempt = 0;
-    let mut on_spawn = on_spawn;
 
     loop {
         attempt += 1;
@@ -500,6 +606,7 @@ pub fn run_agent_with_capture(
                 deadline - now
             }
         });
+        let remaining_wrap_up = wrap_up_deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
 
         // If we've exhausted our time budget, return a timeout result
         if let Some(remaining) = remaining_timeout {
@@ -519,26 +626,26 @@ pub fn run_agent_with_capture(
                     )),
                     duration_secs,
                     captured_stdout: None,
+                    soft_timeout: false,
                 });
             }
         }
 
-        let process = AgentProcess::spawn(
+        let process = AgentProcess::spawn_with_cancel(
             &command,
             &args.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
             &config.repo_path,
             &env_refs,
+            &cancel,
         )?;
+        let pgid = process.id();
+        process_tree::track(&config.run_id, pgid);
 
-        // Provide cancel handle on first attempt only
-        if let Some(callback) = on_spawn.take() {
-            callback(process.cancel_handle());
-        }
-
-        let result = process.wait_with_capture(remaining_timeout, on_log.clone(), true);
+        let result = process.wait_with_capture(remaining_timeout, remaining_wrap_up, on_log.clone(), true);
+        process_tree::untrack(&config.run_id, pgid);
 
         match result {
-            Ok((exit_code, outcome, captured_stdout, captured_stderr)) => {
+            Ok((exit_code, outcome, soft_timeout, captured_stdout, captured_stderr)) => {
                 // Log stderr/stdout output when there's an error
                 if outcome == RunOutcome::Error {
                     if let Some(ref stderr) = captured_stderr {
@@ -561,8 +668,9 @@ pub fn run_agent_with_capture(
                     }
                 }
 
-                // Check if this is a transient error that should be retried
-                if outcome == RunOutcome::Error && attempt < MAX_TRANSIENT_RETRIES {
+                // Check if this is a transient error that should be retried;
+                // an agent stopped at its soft limit has no time left to retry
+                if outcome == RunOutcome::Error && !soft_timeout && attempt < MAX_TRANSIENT_RETRIES {
                     if let Some(ref stderr) = captured_stderr {
                         if is_transient_error(stderr) {
                             tracing::warn!(
@@ -584,13 +692,15 @@ pub fn run_agent_with_capture(
                     );
                 }
 
+                let summary = soft_timeout.then(|| wrap_up_summary(&config, exit_code));
                 return Ok(AgentRunResult {
                     run_id: config.run_id,
                     exit_code,
                     status: outcome,
-                    summary: None,
+                    summary,
                     duration_secs,
                     captured_stdout,
+                    soft_timeout,
                 });
             }
             Err(SpawnError::Timeout(secs)) => {
@@ -602,6 +712,7 @@ pub fn run_agent_with_capture(
                     summary: Some(format!("Process timed out after {} seconds", secs)),
                     duration_secs,
                     captured_stdout: None,
+                    soft_timeout: false,
                 });
             }
             Err(SpawnError::Cancelled) => {
@@ -613,6 +724,7 @@ pub fn run_agent_with_capture(
                     summary: Some("Process was cancelled".to_string()),
                     duration_secs,
                     captured_stdout: None,
+                    soft_timeout: false,
                 });
             }
             Err(e) => return Err(e),
@@ -620,6 +732,15 @@ pub fn run_agent_with_capture(
     }
 }
 
+/// Summary of a run that exited after being interrupted at its soft time limit
+fn wrap_up_summary(config: &AgentRunConfig, exit_code: Option<i32>) -> String {
+    format!(
+        "Process stopped at its {} second soft time limit (exit code {})",
+        config.wrap_up_secs.unwrap_or(0),
+        exit_code.map_or_else(|| "none".to_string(), |code| code.to_string())
+    )
+}
+
 /// Check if an error message indicates a transient error that should be retried
 pub fn is_transient_error(output: &str) -> bool {
     let lower = output.to_lowercase();
@@ -676,6 +797,7 @@ mod tests {
             repo_path: PathBuf::from("/tmp/repo"),
             prompt: "test prompt".to_string(),
             timeout_secs: Some(300),
+            wrap_up_secs: None,
             api_url: "http://localhost:7432".to_string(),
             api_token: "test-token".to_string(),
             model: None,
@@ -778,6 +900,7 @@ mod tests {
             repo_path: PathBuf::from("/"),
             prompt: "p".to_string(),
             timeout_secs: None,
+            wrap_up_secs: None,
             api_url: "http://x".to_string(),
             api_token: "tok".to_string(),
             model: None,
@@ -798,6 +921,7 @@ mod tests {
             repo_path: PathBuf::from("/"),
             prompt: "p".to_string(),
             timeout_secs: None,
+            wrap_up_secs: None,
             api_url: "http://x".to_string(),
             api_token: "tok".to_string(),
             model: None,
@@ -830,6 +954,7 @@ mod tests {
             repo_path: PathBuf::from("/"),
             prompt: "p".to_string(),
             timeout_secs: None,
+            wrap_up_secs: None,
             api_url: "http://x".to_string(),
             api_token: "tok".to_string(),
             model: None,
@@ -859,6 +984,7 @@ mod tests {
             repo_path: PathBuf::from("/"),
             prompt: "p".to_string(),
             timeout_secs: None,
+            wrap_up_secs: None,
             api_url: "http://x".to_string(),
             api_token: "tok".to_string(),
             model: None,
@@ -928,4 +1054,44 @@ mod tests {
     fn is_transient_error_empty_string() {
         assert!(!is_transient_error(""));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn agents_interrupted_at_the_soft_limit_keep_their_exit_outcome() {
+        let process = AgentProcess::spawn(
+            "sh",
+            &["-c", "trap 'echo wrapping up; exit 0' INT; while :; do sleep 0.1; done"],
+            std::path::Path::new("/"),
+            &[],
+        ).unwrap();
+
+        let started = Instant::now();
+        let (exit_code, outcome, wrapped_up, stdout, _) = process
+            .wait_with_capture(Some(Duration::from_secs(20)), Some(Duration::from_millis(200)), None, true)
+            .unwrap();
+        assert_eq!(outcome, RunOutcome::Success);
+        assert!(wrapped_up);
+        assert_eq!(exit_code, Some(0));
+        assert_eq!(stdout.as_deref(), Some("wrapping up"));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agents_ignoring_the_wrap_up_are_killed_at_the_hard_timeout() {
+        let process = AgentProcess::spawn(
+            "sh",
+            &["-c", "trap '' INT; sleep 30 & wait"],
+            std::path::Path::new("/"),
+            &[],
+        ).unwrap();
+
+        let result = process.wait_with_capture(
+            Some(Duration::from_millis(600)),
+            Some(Duration::from_millis(200)),
+            None,
+            false,
+        );
+        assert!(matches!(result, Err(SpawnError::Timeout(_))));
+    }
 }
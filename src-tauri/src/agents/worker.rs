@@ -1,27 +1,40 @@
 //! Worker module for continuous, automated ticket processing.
 //!
-//! Workers are automated agents that poll for tickets in the "Ready" column
+//! Workers are automated agents that poll for tickets in the ready column
 //! and process them using the same execution path as manual runs.
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+use once_cell::sync::Lazy;
 use tokio::time::sleep;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use tauri::{AppHandle, Manager};
 
 use super::{AgentKind, ClaudeApiConfig};
+use super::recovery;
 use super::runner::{self, RunnerConfig};
+use super::slots::SLOTS;
+use super::sink::{emit_to, SharedEventSink};
 use super::worktree;
 use super::diagnostic;
-use crate::db::{Database, AgentType, AuthorType, CreateRun, CreateComment, RunStatus, Ticket};
+use crate::api::state::LiveEvent;
+use crate::db::{Database, Actor, AgentType, AuthorType, CreateRun, CreateComment, DbError, LabelFilter, RunStatus, Ticket, WorkerDefinition, WorkerSchedule};
+use crate::db::models::{DEFAULT_WORKER_AGENT_TIMEOUT_SECS, DEFAULT_WORKER_LOCK_DURATION_MINS, DEFAULT_WORKER_POLL_INTERVAL_SECS};
 use crate::lifecycle::epic::on_child_blocked;
+use crate::lifecycle::TicketState;
+
+/// How often a busy worker checks whether its agent waits for a slot
+const SLOT_STATE_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone)]
 pub struct WorkerConfig {
     pub agent_type: AgentKind,
     pub project_id: Option<String>,
+    /// Labels tickets must (or must not) carry for this worker to reserve them
+    pub labels: LabelFilter,
+    /// Local-time windows outside which no tickets are reserved
+    pub schedule: Option<WorkerSchedule>,
     pub api_url: String,
     pub api_token: String,
     pub poll_interval_secs: u64,
@@ -29,7 +42,8 @@ pub struct WorkerConfig {
     pub lock_duration_mins: i64,
     pub agent_timeout_secs: u64,
     pub hook_script_path: Option<String>,
-    pub app_handle: Option<AppHandle>,
+    /// Where agent progress events are sent (Tauri app handle or SSE)
+    pub event_sink: Option<SharedEventSink>,
     /// Claude API configuration (auth token, api key, base url, model override)
     pub claude_api_config: Option<ClaudeApiConfig>,
 }
@@ -39,60 +53,133 @@ impl Default for WorkerConfig {
         Self {
             agent_type: AgentKind::Cursor,
             project_id: None,
+            labels: LabelFilter::default(),
+            schedule: None,
             api_url: "http://127.0.0.1:7432".to_string(),
             api_token: "default-token".to_string(),
-            poll_interval_secs: 10,
+            poll_interval_secs: DEFAULT_WORKER_POLL_INTERVAL_SECS,
             heartbeat_interval_secs: 60,
-            lock_duration_mins: 30,
-            agent_timeout_secs: 3600, // 1 hour
+            lock_duration_mins: DEFAULT_WORKER_LOCK_DURATION_MINS,
+            agent_timeout_secs: DEFAULT_WORKER_AGENT_TIMEOUT_SECS,
             hook_script_path: None,
-            app_handle: None,
+            event_sink: None,
             claude_api_config: None,
         }
     }
 }
 
+impl WorkerConfig {
+    /// The saved settings of `definition` on top of `base`, which supplies the
+    /// API endpoint, event sink and credentials of the process starting it
+    pub fn for_definition(definition: &WorkerDefinition, base: WorkerConfig) -> Self {
+        Self {
+            agent_type: definition.agent_type,
+            project_id: definition.project_id.clone(),
+            labels: definition.labels.clone(),
+            schedule: definition.schedule.clone(),
+            poll_interval_secs: definition.poll_interval_secs,
+            agent_timeout_secs: definition.agent_timeout_secs,
+            lock_duration_mins: definition.lock_duration_mins,
+            ..base
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkerStatus {
     pub id: String,
     pub agent_type: String,
     pub project_id: Option<String>,
+    #[serde(default)]
+    pub labels: LabelFilter,
     pub status: WorkerState,
+    /// Why a paused, draining or off-schedule worker takes no tickets, or
+    /// what a waiting one waits for
+    #[serde(default)]
+    pub state_reason: Option<String>,
     pub current_ticket_id: Option<String>,
     pub current_run_id: Option<String>,
-    pub tickets_processed: u32,
+    /// Lifetime count for saved workers, since start for the others
+    pub tickets_processed: u64,
     pub started_at: DateTime<Utc>,
     pub last_poll_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum WorkerState {
     Idle,
     Running,
+    /// Held by the user (or a restore); a ticket in progress is finished
+    Paused,
+    /// Finishing the current ticket, then exiting
+    Draining,
+    /// Outside the worker's schedule
+    OffSchedule,
+    /// Holding a ticket while its agent waits for a concurrency slot
+    WaitingForSlot,
     Stopped,
 }
 
+impl WorkerState {
+    pub const ALL: [WorkerState; 7] = [
+        WorkerState::Idle,
+        WorkerState::Running,
+        WorkerState::Paused,
+        WorkerState::Draining,
+        WorkerState::OffSchedule,
+        WorkerState::WaitingForSlot,
+        WorkerState::Stopped,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkerState::Idle => "idle",
+            WorkerState::Running => "running",
+            WorkerState::Paused => "paused",
+            WorkerState::Draining => "draining",
+            WorkerState::OffSchedule => "off_schedule",
+            WorkerState::WaitingForSlot => "waiting_for_slot",
+            WorkerState::Stopped => "stopped",
+        }
+    }
+}
+
 pub struct Worker {
     pub id: String,
     config: WorkerConfig,
     db: Arc<Database>,
     running: Arc<AtomicBool>,
+    /// Set while the database is being restored; no new tickets are reserved
+    paused: AtomicBool,
+    /// Paused by the user
+    held: AtomicBool,
+    /// Exit once the current ticket (if any) is done
+    draining: AtomicBool,
     status: Arc<std::sync::Mutex<WorkerStatus>>,
     cancel_handles: runner::CancelHandlesMap,
 }
 
 impl Worker {
     pub fn new(id: String, config: WorkerConfig, db: Arc<Database>) -> Self {
+        let tickets_processed = match db.get_worker_definition(&id) {
+            Ok(definition) => definition.map_or(0, |d| d.tickets_processed),
+            Err(e) => {
+                tracing::warn!("Failed to load stats of worker {}: {}", id, e);
+                0
+            }
+        };
         let status = WorkerStatus {
             id: id.clone(),
             agent_type: config.agent_type.as_str().to_string(),
             project_id: config.project_id.clone(),
+            labels: config.labels.clone(),
             status: WorkerState::Idle,
+            state_reason: None,
             current_ticket_id: None,
             current_run_id: None,
-            tickets_processed: 0,
+            tickets_processed,
             started_at: Utc::now(),
             last_poll_at: None,
         };
@@ -102,6 +189,9 @@ impl Worker {
             config,
             db,
             running: Arc::new(AtomicBool::new(false)),
+            paused: AtomicBool::new(false),
+            held: AtomicBool::new(false),
+            draining: AtomicBool::new(false),
             status: Arc::new(std::sync::Mutex::new(status)),
             cancel_handles: runner::create_cancel_handles(),
         }
@@ -115,29 +205,139 @@ impl Worker {
         self.running.load(Ordering::Relaxed)
     }
 
+    /// Stop (or resume) reserving tickets; a ticket in progress is finished
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    /// Take no new tickets until `resume`; the current one is finished and
+    /// its lock heartbeat keeps running
+    pub fn pause(&self) {
+        tracing::info!("Pausing worker {}", self.id);
+        self.held.store(true, Ordering::Relaxed);
+        self.refresh_idle_state();
+    }
+
+    pub fn resume(&self) {
+        tracing::info!("Resuming worker {}", self.id);
+        self.held.store(false, Ordering::Relaxed);
+        self.refresh_idle_state();
+    }
+
+    /// Finish the current ticket, then exit without reserving another
+    pub fn drain(&self) {
+        tracing::info!("Draining worker {}", self.id);
+        self.draining.store(true, Ordering::Relaxed);
+        if self.is_busy() {
+            self.set_state(WorkerState::Draining, None);
+        }
+    }
+
+    /// Whether the worker is in the middle of a ticket
+    pub fn is_busy(&self) -> bool {
+        self.status.lock().expect("status mutex poisoned").current_ticket_id.is_some()
+    }
+
     pub fn stop(&self) {
         tracing::info!("Stopping worker {}", self.id);
         self.running.store(false, Ordering::Relaxed);
 
         // Cancel any running agent by cancelling all handles
-        let handles = self.cancel_handles.lock().expect("cancel mutex poisoned");
-        for (run_id, handle) in handles.iter() {
-            tracing::info!("Cancelling run {} for worker {}", run_id, self.id);
-            handle.cancel();
+        {
+            let handles = self.cancel_handles.lock().expect("cancel mutex poisoned");
+            for (run_id, handle) in handles.iter() {
+                tracing::info!("Cancelling run {} for worker {}", run_id, self.id);
+                handle.cancel();
+            }
         }
 
-        let mut status = self.status.lock().expect("status mutex poisoned");
-        status.status = WorkerState::Stopped;
+        self.set_state(WorkerState::Stopped, None);
     }
 
-    pub async fn run(&self) {
-        self.running.store(true, Ordering::Relaxed);
-
+    /// Update the reported state and announce changes. While draining, a
+    /// worker busy with a ticket reports `Draining` rather than `Running`.
+    fn set_state(&self, state: WorkerState, reason: Option<&str>) {
+        let (state, reason) = match state {
+            WorkerState::Running | WorkerState::Draining if self.draining.load(Ordering::Relaxed) => {
+                (WorkerState::Draining, Some("Finishing the current ticket before exiting"))
+            }
+            _ => (state, reason),
+        };
         {
             let mut status = self.status.lock().expect("status mutex poisoned");
-            status.status = WorkerState::Idle;
-            status.started_at = Utc::now();
+            if status.status == state && status.state_reason.as_deref() == reason {
+                return;
+            }
+            status.status = state;
+            status.state_reason = reason.map(String::from);
+        }
+
+        tracing::info!("Worker {} is {}", self.id, state.as_str());
+        if let Some(sink) = self.config.event_sink.as_ref() {
+            let event = LiveEvent::WorkerStateChanged {
+                worker_id: self.id.clone(),
+                state,
+                reason: reason.map(String::from),
+            };
+            if let Err(e) = sink.emit_live(event) {
+                tracing::warn!("Failed to announce state of worker {}: {}", self.id, e);
+            }
         }
+    }
+
+    /// Why no ticket should be reserved right now, if anything
+    fn hold(&self) -> Option<(WorkerState, &'static str)> {
+        if self.paused.load(Ordering::Relaxed) {
+            return Some((WorkerState::Paused, "Paused while the database is restored"));
+        }
+        if self.held.load(Ordering::Relaxed) {
+            return Some((WorkerState::Paused, "Paused by the user"));
+        }
+        match &self.config.schedule {
+            Some(schedule) if !schedule.is_open(chrono::Local::now().naive_local()) => {
+                Some((WorkerState::OffSchedule, "Outside the worker's schedule"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Report the state of a worker between tickets right away instead of at
+    /// its next poll
+    fn refresh_idle_state(&self) {
+        if !self.is_running() || self.is_busy() {
+            return;
+        }
+        match self.hold() {
+            Some((state, reason)) => self.set_state(state, Some(reason)),
+            None => self.set_state(WorkerState::Idle, None),
+        }
+    }
+
+    /// Report `WaitingForSlot` while the ticket's agent waits for a
+    /// concurrency slot, and `Running` again once it has one
+    fn refresh_slot_state(&self, ticket_id: &str) {
+        match SLOTS.waiting_reason(ticket_id) {
+            Some(reason) => self.set_state(WorkerState::WaitingForSlot, Some(&reason)),
+            None => self.set_state(WorkerState::Running, None),
+        }
+    }
+
+    /// Sleep between polls, waking early when the worker is stopped or drained
+    async fn wait(&self, duration: Duration) {
+        let deadline = tokio::time::Instant::now() + duration;
+        while self.running.load(Ordering::Relaxed) && !self.draining.load(Ordering::Relaxed) {
+            let left = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            sleep(left.min(Duration::from_secs(1))).await;
+        }
+    }
+
+    pub async fn run(&self) {
+        self.running.store(true, Ordering::Relaxed);
+        self.status.lock().expect("status mutex poisoned").started_at = Utc::now();
+        self.set_state(WorkerState::Idle, None);
 
         tracing::info!(
             "Worker {} started: {:?} agent, project filter: {:?}",
@@ -146,17 +346,27 @@ impl Worker {
             self.config.project_id
         );
 
-        while self.running.load(Ordering::Relaxed) {
+        while self.running.load(Ordering::Relaxed) && !self.draining.load(Ordering::Relaxed) {
+            if let Some((state, reason)) = self.hold() {
+                self.set_state(state, Some(reason));
+                self.wait(Duration::from_secs(1)).await;
+                continue;
+            }
+            self.set_state(WorkerState::Idle, None);
             match self.process_next().await {
                 Ok(true) => {}
-                Ok(false) => sleep(Duration::from_secs(self.config.poll_interval_secs)).await,
+                Ok(false) => self.wait(Duration::from_secs(self.config.poll_interval_secs)).await,
                 Err(e) => {
                     tracing::error!("Worker {} error: {}", self.id, e);
-                    sleep(Duration::from_secs(5)).await;
+                    self.wait(Duration::from_secs(5)).await;
                 }
             }
         }
 
+        if self.draining.load(Ordering::Relaxed) {
+            self.running.store(false, Ordering::Relaxed);
+            self.set_state(WorkerState::Stopped, Some("Drained"));
+        }
         tracing::info!("Worker {} stopped", self.id);
     }
 
@@ -173,8 +383,10 @@ impl Worker {
         let Some(ticket) = self.db.reserve_next_ticket(
             self.config.project_id.as_deref(),
             self.config.agent_type,
+            &self.config.labels,
             &run_id,
             lock_expires,
+            &Actor::worker(&self.id),
         )? else {
             return Ok(false);
         };
@@ -185,7 +397,7 @@ impl Worker {
         let repo_path = match self.get_repo_path(&ticket) {
             Ok(path) => path,
             Err(e) => {
-                self.db.unlock_ticket(&ticket.id)?;
+                self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id))?;
                 return Err(e);
             }
         };
@@ -193,6 +405,7 @@ impl Worker {
         // Create a worktree for isolated execution - ALWAYS use worktrees
         // This ensures agent work never affects the user's main repo/terminal
         let repo_path_buf = std::path::PathBuf::from(&repo_path);
+        recovery::discard_stopped_workflows(&self.db, &ticket.id, &repo_path_buf);
         
         let worktree_info = if let Some(ref existing_branch) = ticket.branch_name {
             // Ticket has a branch - create worktree to reuse it
@@ -217,7 +430,7 @@ impl Worker {
                     
                     // Handle worktree failure with diagnostics
                     self.handle_worktree_failure(&ticket, &repo_path_buf, &e).await;
-                    self.db.unlock_ticket(&ticket.id)?;
+                    self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id))?;
                     return Err(format!("Failed to create worktree: {}", e).into());
                 }
             }
@@ -327,7 +540,7 @@ impl Worker {
                     
                     // Handle worktree failure with diagnostics
                     self.handle_worktree_failure(&ticket, &repo_path_buf, &e).await;
-                    self.db.unlock_ticket(&ticket.id)?;
+                    self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id))?;
                     return Err(format!("Failed to create worktree: {}", e).into());
                 }
             }
@@ -350,7 +563,7 @@ impl Worker {
         }) {
             Ok(run) => run,
             Err(e) => {
-                let _ = self.db.unlock_ticket(&ticket.id);
+                let _ = self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id));
                 let _ = worktree::remove_worktree(&worktree.path, &worktree.repo_path);
                 return Err(e.into());
             }
@@ -368,7 +581,7 @@ impl Worker {
                 None,
                 Some("Failed to transfer ticket lock to actual run ID"),
             );
-            let _ = self.db.unlock_ticket(&ticket.id);
+            let _ = self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id));
             let _ = worktree::remove_worktree(&worktree.path, &worktree.repo_path);
             return Err(e.into());
         }
@@ -376,10 +589,10 @@ impl Worker {
         // Update worker status
         {
             let mut status = self.status.lock().expect("status mutex poisoned");
-            status.status = WorkerState::Running;
             status.current_ticket_id = Some(ticket.id.clone());
             status.current_run_id = Some(run.id.clone());
         }
+        self.set_state(WorkerState::Running, None);
 
         // Start heartbeat to keep the lock alive
         let heartbeat_handle = self.start_heartbeat(&ticket.id, &run.id);
@@ -419,13 +632,12 @@ impl Worker {
                 heartbeat_handle.abort();
                 
                 // Clean up
-                self.db.unlock_ticket(&ticket.id)?;
+                self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id))?;
                 let _ = worktree::remove_worktree(&worktree.path, &worktree.repo_path);
                 
                 // Reset worker status
                 {
                     let mut status = self.status.lock().expect("status mutex poisoned");
-                    status.status = WorkerState::Idle;
                     status.current_ticket_id = None;
                     status.current_run_id = None;
                 }
@@ -455,7 +667,7 @@ impl Worker {
                     None,
                     Some(&format!("Failed to start task: {}", e)),
                 );
-                self.db.unlock_ticket(&ticket.id)?;
+                self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id))?;
                 let _ = worktree::remove_worktree(&worktree.path, &worktree.repo_path);
                 return Err(format!("Failed to start task {}: {}", t.id, e).into());
             }
@@ -484,8 +696,7 @@ impl Worker {
         
         let runner_config = RunnerConfig {
             db: self.db.clone(),
-            window: None, // Workers don't have a window
-            app_handle: self.config.app_handle.clone(), // Use app_handle for global event emission
+            event_sink: self.config.event_sink.clone(),
             ticket: ticket.clone(),
             task: task.clone(),
             run_id: run.id.clone(),
@@ -500,9 +711,20 @@ impl Worker {
             is_temp_branch,
             timeout_secs: self.config.agent_timeout_secs,
             claude_api_config: self.config.claude_api_config.clone(),
+            start_stage: 0,
+            extra_stage: None,
         };
 
-        let result = runner::execute_agent_run(runner_config).await;
+        let result = {
+            let run_future = runner::execute_agent_run(runner_config);
+            tokio::pin!(run_future);
+            loop {
+                tokio::select! {
+                    result = &mut run_future => break result,
+                    _ = sleep(SLOT_STATE_INTERVAL) => self.refresh_slot_state(&ticket.id),
+                }
+            }
+        };
         
         // Stop heartbeat
         heartbeat_handle.abort();
@@ -555,10 +777,12 @@ impl Worker {
         }
 
         // Unlock the ticket
-        self.db.unlock_ticket(&ticket.id)?;
+        self.db.unlock_ticket(&ticket.id, &Actor::worker(&self.id))?;
         
-        // Clean up worktree
-        if let Err(e) = worktree::remove_worktree(&worktree.path, &worktree.repo_path) {
+        // Clean up worktree, unless the workflow failed and can be restarted
+        if recovery::keeps_worktree(&self.db, &run.id) {
+            tracing::info!("Worker {} kept worktree of failed run {} at {}", self.id, run.id, worktree.path.display());
+        } else if let Err(e) = worktree::remove_worktree(&worktree.path, &worktree.repo_path) {
             tracing::warn!("Failed to remove worktree {}: {}", worktree.path.display(), e);
         } else {
             tracing::info!("Worker {} removed worktree at {}", self.id, worktree.path.display());
@@ -567,11 +791,17 @@ impl Worker {
         // Update worker status
         {
             let mut status = self.status.lock().expect("status mutex poisoned");
-            status.status = WorkerState::Idle;
             status.current_ticket_id = None;
             status.current_run_id = None;
             status.tickets_processed += 1;
         }
+        let failed = match &result {
+            Ok(r) => r.status == RunStatus::Error,
+            Err(e) => !e.to_string().to_lowercase().contains("cancelled"),
+        };
+        if let Err(e) = self.db.record_worker_ticket(&self.id, failed) {
+            tracing::warn!("Failed to record stats of worker {}: {}", self.id, e);
+        }
 
         Ok(true)
     }
@@ -645,7 +875,6 @@ impl Worker {
         
         // First, try to spawn a diagnostic agent
         let db_clone = self.db.clone();
-        let app_handle = self.config.app_handle.clone();
         let ticket_id = ticket.id.clone();
         let ticket_model = ticket.model.clone();
         let api_url = self.config.api_url.clone();
@@ -661,7 +890,6 @@ impl Worker {
             
             match diagnostic::run_diagnostic_agent(
                 db_clone.clone(),
-                app_handle,
                 &ticket_id,
                 context_clone.clone(),
                 &api_url,
@@ -703,9 +931,9 @@ impl Worker {
     
     /// Move a ticket to the Blocked column
     fn move_ticket_to_blocked(&self, ticket: &Ticket) {
-        match self.db.find_column_by_name(&ticket.board_id, "Blocked") {
+        match self.db.find_column_by_role(&ticket.board_id, TicketState::Blocked) {
             Ok(Some(column)) => {
-                if let Err(e) = self.db.move_ticket(&ticket.id, &column.id) {
+                if let Err(e) = self.db.move_ticket(&ticket.id, &column.id, &Actor::worker(&self.id)) {
                     tracing::error!(
                         "Worker {} failed to move ticket {} to Blocked: {}",
                         self.id, ticket.id, e
@@ -716,14 +944,12 @@ impl Worker {
                         self.id, ticket.id
                     );
                     
-                    // Emit event if we have an app handle
-                    if let Some(ref app_handle) = self.config.app_handle {
-                        let _ = app_handle.emit_all("ticket-moved", serde_json::json!({
-                            "ticketId": ticket.id,
-                            "columnName": "Blocked",
-                            "columnId": column.id,
-                        }));
-                    }
+                    let _ = emit_to(self.config.event_sink.as_ref(), "ticket-moved", &serde_json::json!({
+                        "ticketId": ticket.id,
+                        "columnName": column.name,
+                        "columnId": column.id,
+                        "role": TicketState::Blocked,
+                    }));
                     
                     // Epic lifecycle: if this ticket is a child, block the parent epic
                     if ticket.epic_id.is_some() {
@@ -752,9 +978,14 @@ impl Worker {
     }
 }
 
+/// The workers running in this process, shared by the app commands, the
+/// REST API and the headless server
+pub static WORKER_MANAGER: Lazy<WorkerManager> = Lazy::new(WorkerManager::new);
+
 pub struct WorkerManager {
     workers: std::sync::Mutex<Vec<Arc<Worker>>>,
     handles: std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>,
+    paused: AtomicBool,
 }
 
 impl WorkerManager {
@@ -762,12 +993,46 @@ impl WorkerManager {
         Self {
             workers: std::sync::Mutex::new(Vec::new()),
             handles: std::sync::Mutex::new(Vec::new()),
+            paused: AtomicBool::new(false),
         }
     }
 
     pub fn start_worker(&self, config: WorkerConfig, db: Arc<Database>) -> String {
-        let id = uuid::Uuid::new_v4().to_string();
+        self.start_worker_with_id(uuid::Uuid::new_v4().to_string(), config, db)
+    }
+
+    /// Start every enabled saved worker that is not running yet; `base` gives
+    /// the process-specific config for an agent. Returns how many started.
+    pub fn start_saved_workers(
+        &self,
+        db: &Arc<Database>,
+        base: impl Fn(AgentKind) -> WorkerConfig,
+    ) -> Result<usize, DbError> {
+        let mut started = 0;
+        for definition in db.get_worker_definitions()? {
+            if definition.enabled && !self.is_running(&definition.id) {
+                let config = WorkerConfig::for_definition(&definition, base(definition.agent_type));
+                self.start_worker_with_id(definition.id.clone(), config, db.clone());
+                tracing::info!("Started saved worker {} ({})", definition.name, definition.id);
+                started += 1;
+            }
+        }
+        Ok(started)
+    }
+
+    /// Apply a changed definition: a running worker is restarted with the new
+    /// settings, or stopped if the definition was disabled
+    pub fn sync_saved_worker(&self, definition: &WorkerDefinition, db: &Arc<Database>, base: WorkerConfig) {
+        self.stop_worker(&definition.id);
+        if definition.enabled {
+            let config = WorkerConfig::for_definition(definition, base);
+            self.start_worker_with_id(definition.id.clone(), config, db.clone());
+        }
+    }
+
+    fn start_worker_with_id(&self, id: String, config: WorkerConfig, db: Arc<Database>) -> String {
         let worker = Arc::new(Worker::new(id.clone(), config, db));
+        worker.set_paused(self.paused.load(Ordering::Relaxed));
         let worker_clone = worker.clone();
 
         let handle = tokio::spawn(async move {
@@ -781,6 +1046,7 @@ impl WorkerManager {
     }
 
     pub fn stop_worker(&self, worker_id: &str) -> bool {
+        self.prune_finished();
         let mut workers = self.workers.lock().expect("workers mutex poisoned");
         let mut handles = self.handles.lock().expect("handles mutex poisoned");
         
@@ -816,7 +1082,53 @@ impl WorkerManager {
         self.workers.lock().expect("workers mutex poisoned").clear();
     }
 
+    pub fn pause_worker(&self, worker_id: &str) -> Option<WorkerStatus> {
+        let worker = self.find(worker_id)?;
+        worker.pause();
+        Some(worker.get_status())
+    }
+
+    pub fn resume_worker(&self, worker_id: &str) -> Option<WorkerStatus> {
+        let worker = self.find(worker_id)?;
+        worker.resume();
+        Some(worker.get_status())
+    }
+
+    /// Let a worker finish its current ticket and exit; it drops out of the
+    /// list once it has
+    pub fn drain_worker(&self, worker_id: &str) -> Option<WorkerStatus> {
+        let worker = self.find(worker_id)?;
+        worker.drain();
+        Some(worker.get_status())
+    }
+
+    fn find(&self, worker_id: &str) -> Option<Arc<Worker>> {
+        self.prune_finished();
+        self.workers
+            .lock()
+            .expect("workers mutex poisoned")
+            .iter()
+            .find(|w| w.id == worker_id)
+            .cloned()
+    }
+
+    /// Forget workers whose task has ended, i.e. drained ones
+    fn prune_finished(&self) {
+        let mut workers = self.workers.lock().expect("workers mutex poisoned");
+        let mut handles = self.handles.lock().expect("handles mutex poisoned");
+        let mut idx = 0;
+        while idx < handles.len() {
+            if handles[idx].is_finished() {
+                handles.remove(idx);
+                workers.remove(idx);
+            } else {
+                idx += 1;
+            }
+        }
+    }
+
     pub fn get_all_status(&self) -> Vec<WorkerStatus> {
+        self.prune_finished();
         self.workers
             .lock()
             .expect("workers mutex poisoned")
@@ -825,9 +1137,44 @@ impl WorkerManager {
             .collect()
     }
 
+    pub fn is_running(&self, worker_id: &str) -> bool {
+        self.prune_finished();
+        self.workers.lock().expect("workers mutex poisoned").iter().any(|w| w.id == worker_id)
+    }
+
     pub fn worker_count(&self) -> usize {
+        self.prune_finished();
         self.workers.lock().expect("workers mutex poisoned").len()
     }
+
+    /// Stop all workers (including ones started later) from reserving
+    /// tickets, then wait up to `timeout` for the ones mid-ticket to finish.
+    /// Returns whether they all did; call `resume_all` either way.
+    pub async fn pause_all(&self, timeout: Duration) -> bool {
+        self.set_paused(true);
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let busy = self.workers.lock().expect("workers mutex poisoned").iter().any(|w| w.is_busy());
+            if !busy {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    pub fn resume_all(&self) {
+        self.set_paused(false);
+    }
+
+    fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+        for worker in self.workers.lock().expect("workers mutex poisoned").iter() {
+            worker.set_paused(paused);
+        }
+    }
 }
 
 impl Default for WorkerManager {
@@ -854,6 +1201,107 @@ mod tests {
         assert_eq!(serde_json::to_string(&WorkerState::Idle).unwrap(), "\"idle\"");
         assert_eq!(serde_json::to_string(&WorkerState::Running).unwrap(), "\"running\"");
         assert_eq!(serde_json::to_string(&WorkerState::Stopped).unwrap(), "\"stopped\"");
+        assert_eq!(serde_json::to_string(&WorkerState::OffSchedule).unwrap(), "\"off_schedule\"");
+        for state in WorkerState::ALL {
+            assert_eq!(serde_json::to_value(state).unwrap(), state.as_str());
+        }
+    }
+
+    #[test]
+    fn schedule_windows_span_midnight() {
+        use chrono::Weekday::*;
+        let schedule = WorkerSchedule {
+            windows: vec![crate::db::ScheduleWindow {
+                days: vec![Mon, Tue, Wed, Thu, Fri],
+                start: "19:00".to_string(),
+                end: "07:00".to_string(),
+            }],
+        };
+        let at = |date: &str, time: &str| {
+            chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+        };
+
+        // 2024-01-05 is a Friday
+        assert!(!schedule.is_open(at("2024-01-05", "18:59")));
+        assert!(schedule.is_open(at("2024-01-05", "19:00")));
+        assert!(schedule.is_open(at("2024-01-06", "06:59")));
+        assert!(!schedule.is_open(at("2024-01-06", "07:00")));
+        // Nothing opens on Saturday evening, and Monday morning belongs to Sunday
+        assert!(!schedule.is_open(at("2024-01-06", "20:00")));
+        assert!(!schedule.is_open(at("2024-01-08", "06:00")));
+        assert!(schedule.is_open(at("2024-01-09", "06:00")));
+    }
+
+    #[test]
+    fn schedule_daytime_window_on_all_days() {
+        let schedule = WorkerSchedule {
+            windows: vec![crate::db::ScheduleWindow {
+                days: vec![],
+                start: "09:00".to_string(),
+                end: "17:30".to_string(),
+            }],
+        };
+        let at = |time: &str| {
+            chrono::NaiveDateTime::parse_from_str(&format!("2024-01-07 {}", time), "%Y-%m-%d %H:%M").unwrap()
+        };
+
+        assert!(schedule.is_open(at("09:00")));
+        assert!(schedule.is_open(at("17:29")));
+        assert!(!schedule.is_open(at("17:30")));
+        assert!(!schedule.is_open(at("08:00")));
+    }
+
+    #[tokio::test]
+    async fn paused_worker_reports_why() {
+        let manager = WorkerManager::new();
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let id = manager.start_worker(WorkerConfig::default(), db);
+        sleep(Duration::from_millis(50)).await;
+
+        let status = manager.pause_worker(&id).unwrap();
+        assert_eq!(status.status, WorkerState::Paused);
+        assert_eq!(status.state_reason.as_deref(), Some("Paused by the user"));
+
+        let status = manager.resume_worker(&id).unwrap();
+        assert_eq!(status.status, WorkerState::Idle);
+        assert!(status.state_reason.is_none());
+        manager.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn drained_worker_exits_and_is_forgotten() {
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let config = WorkerConfig {
+            event_sink: Some(Arc::new(super::super::sink::BroadcastEventSink::new(tx))),
+            ..Default::default()
+        };
+        let manager = WorkerManager::new();
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let id = manager.start_worker(config, db);
+        sleep(Duration::from_millis(50)).await;
+
+        assert!(manager.drain_worker(&id).is_some());
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while manager.is_running(&id) {
+                sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("drained worker should exit");
+        assert_eq!(manager.worker_count(), 0);
+        assert!(manager.drain_worker(&id).is_none());
+
+        let mut states = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let LiveEvent::WorkerStateChanged { worker_id, state, reason } = event {
+                assert_eq!(worker_id, id);
+                states.push((state, reason));
+            }
+        }
+        assert_eq!(
+            states.last(),
+            Some(&(WorkerState::Stopped, Some("Drained".to_string())))
+        );
     }
 
     #[test]
@@ -869,7 +1317,9 @@ mod tests {
             id: "w1".to_string(),
             agent_type: "cursor".to_string(),
             project_id: None,
+            labels: LabelFilter::default(),
             status: WorkerState::Idle,
+            state_reason: None,
             current_ticket_id: None,
             current_run_id: None,
             tickets_processed: 5,
@@ -950,6 +1400,8 @@ mod tests {
         let config = WorkerConfig {
             agent_type: AgentKind::Claude,
             project_id: Some("my-project".to_string()),
+            labels: LabelFilter { include: vec!["frontend".to_string()], ..Default::default() },
+            schedule: None,
             api_url: "http://localhost:8080".to_string(),
             api_token: "secret".to_string(),
             poll_interval_secs: 30,
@@ -957,7 +1409,7 @@ mod tests {
             lock_duration_mins: 60,
             agent_timeout_secs: 7200,
             hook_script_path: Some("/path/to/hook.js".to_string()),
-            app_handle: None,
+            event_sink: None,
             claude_api_config: None,
         };
 
@@ -975,7 +1427,9 @@ mod tests {
             id: "w1".to_string(),
             agent_type: "cursor".to_string(),
             project_id: Some("proj".to_string()),
+            labels: LabelFilter::default(),
             status: WorkerState::Running,
+            state_reason: None,
             current_ticket_id: Some("t1".to_string()),
             current_run_id: Some("r1".to_string()),
             tickets_processed: 10,
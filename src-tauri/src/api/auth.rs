@@ -69,6 +69,23 @@ pub fn generate_token() -> String {
         .collect()
 }
 
+/// Read the API token stored at `path`, generating and saving a new one if
+/// the file is missing or empty.
+pub fn load_or_create_token(path: &std::path::Path) -> std::io::Result<String> {
+    if let Ok(token) = std::fs::read_to_string(path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            tracing::info!("Using existing API token from {}", path.display());
+            return Ok(token.to_string());
+        }
+    }
+
+    tracing::info!("Generating new API token at {}", path.display());
+    let token = generate_token();
+    std::fs::write(path, &token)?;
+    Ok(token)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +100,17 @@ mod tests {
         assert_ne!(token1, token2);
         assert!(token1.chars().all(|c| c.is_ascii_alphanumeric()));
     }
+
+    #[test]
+    fn load_or_create_token_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api_token");
+
+        let created = load_or_create_token(&path).unwrap();
+        assert_eq!(created.len(), 32);
+        assert_eq!(load_or_create_token(&path).unwrap(), created);
+
+        std::fs::write(&path, "  \n").unwrap();
+        assert_ne!(load_or_create_token(&path).unwrap(), created);
+    }
 }
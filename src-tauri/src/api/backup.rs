@@ -0,0 +1,69 @@
+//! Background service taking scheduled database backups, and restoring
+//! them safely while workers are running.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant};
+
+use crate::agents::worker::WORKER_MANAGER;
+use crate::db::backup::BackupPolicy;
+use crate::db::{BackupInfo, Database, DbError};
+
+/// How long a restore waits for workers to finish the ticket they are on
+const RESTORE_WORKER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Restore a backup with all workers paused, so none of them reserves a
+/// ticket or writes results while the database is swapped underneath it.
+/// Returns the `pre-restore` snapshot of the replaced state.
+pub async fn restore_with_workers_paused(db: &Database, file_name: &str) -> Result<BackupInfo, DbError> {
+    let idle = WORKER_MANAGER.pause_all(RESTORE_WORKER_TIMEOUT).await;
+    let result = if idle {
+        db.restore_backup(file_name)
+    } else {
+        Err(DbError::Validation(
+            "Workers are still processing tickets; stop them or try again later".to_string(),
+        ))
+    };
+    WORKER_MANAGER.resume_all();
+    result
+}
+
+pub fn start_backup_service(db: Arc<Database>, policy: BackupPolicy) {
+    if policy.interval_mins == 0 {
+        tracing::info!("Scheduled backups are disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let period = Duration::from_secs(policy.interval_mins * 60);
+        // The first backup is due one interval after startup; migrations
+        // already took one if the schema changed
+        let mut ticker = interval_at(Instant::now() + period, period);
+
+        tracing::info!(
+            "Backup service started (interval: {}m, keeping {} per reason)",
+            policy.interval_mins,
+            policy.keep_last
+        );
+
+        loop {
+            ticker.tick().await;
+
+            let db = db.clone();
+            let policy = policy.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let backup = db.create_backup("scheduled")?;
+                let pruned = db.prune_backups(&policy)?;
+                Ok::<_, crate::db::DbError>((backup, pruned))
+            }).await;
+
+            match result {
+                Ok(Ok((backup, pruned))) => {
+                    tracing::info!("Scheduled backup {} ({} bytes), removed {} old backup(s)", backup.file_name, backup.size_bytes, pruned);
+                }
+                Ok(Err(e)) => tracing::error!("Scheduled backup failed: {}", e),
+                Err(e) => tracing::error!("Scheduled backup task panicked: {}", e),
+            }
+        }
+    });
+}
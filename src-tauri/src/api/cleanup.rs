@@ -1,19 +1,28 @@
-//! Background service for cleaning up expired ticket locks.
+//! Background service for cleaning up expired ticket locks and applying the
+//! retention policy.
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::interval;
+use tokio::time::{interval, Instant};
 
+use crate::db::history::{record_change, LOCK_FIELD};
+use crate::db::models::{Actor, RetentionReport};
+use crate::db::retention::RetentionPolicy;
 use crate::db::{Database, DbError, RunStatus};
 
+#[derive(Debug, Clone)]
 pub struct CleanupConfig {
     pub check_interval_secs: u64,
+    /// Event compaction and archival, applied every `retention.interval_mins`
+    pub retention: RetentionPolicy,
 }
 
 impl Default for CleanupConfig {
     fn default() -> Self {
         Self {
             check_interval_secs: 60,
+            retention: RetentionPolicy::default(),
         }
     }
 }
@@ -31,22 +40,57 @@ impl CleanupResult {
     }
 }
 
+/// Running totals of everything the cleanup service has recovered since startup.
+/// Exposed as counters on the `/metrics` endpoint.
+#[derive(Debug)]
+pub struct CleanupTotals {
+    pub released_tickets: AtomicU64,
+    pub aborted_runs: AtomicU64,
+    pub released_repo_locks: AtomicU64,
+}
+
+impl CleanupTotals {
+    pub const fn new() -> Self {
+        Self {
+            released_tickets: AtomicU64::new(0),
+            aborted_runs: AtomicU64::new(0),
+            released_repo_locks: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, result: &CleanupResult) {
+        self.released_tickets.fetch_add(result.released_tickets.len() as u64, Ordering::Relaxed);
+        self.aborted_runs.fetch_add(result.aborted_runs.len() as u64, Ordering::Relaxed);
+        self.released_repo_locks.fetch_add(result.released_repo_locks as u64, Ordering::Relaxed);
+    }
+}
+
+impl Default for CleanupTotals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub static CLEANUP_TOTALS: CleanupTotals = CleanupTotals::new();
+
 pub fn cleanup_expired_locks(db: &Database) -> Result<CleanupResult, DbError> {
     let released_repo_locks = db.cleanup_expired_repo_locks()?;
-    db.with_conn(|conn| {
+    db.with_conn_mut(|conn| {
+        let tx = conn.transaction()?;
+        let conn = &*tx;
         let now = chrono::Utc::now().to_rfc3339();
 
-        let mut stmt = conn.prepare(
-            r#"SELECT id, locked_by_run_id 
-               FROM tickets 
-               WHERE locked_by_run_id IS NOT NULL 
-               AND lock_expires_at IS NOT NULL 
-               AND lock_expires_at < ?"#,
-        )?;
-
-        let expired: Vec<(String, String)> = stmt
-            .query_map([&now], |row| Ok((row.get(0)?, row.get(1)?)))?
-            .collect::<Result<Vec<_>, _>>()?;
+        let expired: Vec<(String, String)> = {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, locked_by_run_id 
+                   FROM tickets 
+                   WHERE locked_by_run_id IS NOT NULL 
+                   AND lock_expires_at IS NOT NULL 
+                   AND lock_expires_at < ?"#,
+            )?;
+            let rows = stmt.query_map([&now], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
 
         if expired.is_empty() {
             return Ok(CleanupResult {
@@ -69,6 +113,10 @@ pub fn cleanup_expired_locks(db: &Database) -> Result<CleanupResult, DbError> {
             rusqlite::params![&now, &now],
         )?;
 
+        for (ticket_id, run_id) in &expired {
+            record_change(conn, ticket_id, LOCK_FIELD, Some(run_id), None, &Actor::system())?;
+        }
+
         for run_id in &run_ids {
             conn.execute(
                 r#"UPDATE agent_runs 
@@ -80,6 +128,7 @@ pub fn cleanup_expired_locks(db: &Database) -> Result<CleanupResult, DbError> {
             )?;
         }
 
+        tx.commit()?;
         Ok(CleanupResult {
             released_tickets: ticket_ids,
             aborted_runs: run_ids,
@@ -97,11 +146,22 @@ pub fn start_cleanup_service(db: Arc<Database>, config: CleanupConfig) {
             config.check_interval_secs
         );
 
+        let retention_period = Duration::from_secs(config.retention.interval_mins * 60);
+        // The first retention pass runs on the first tick
+        let mut next_retention = (config.retention.interval_mins > 0).then(Instant::now);
+
         loop {
             ticker.tick().await;
 
+            if next_retention.is_some_and(|due| due <= Instant::now()) {
+                next_retention = Some(Instant::now() + retention_period);
+                run_retention(&db, &config.retention).await;
+            }
+
             match cleanup_expired_locks(&db) {
                 Ok(result) => {
+                    CLEANUP_TOTALS.record(&result);
+
                     if !result.is_empty() {
                         tracing::info!(
                             "Cleanup: released {} expired ticket locks, {} repo locks, aborted {} runs",
@@ -131,6 +191,27 @@ pub fn start_cleanup_service(db: Arc<Database>, config: CleanupConfig) {
     });
 }
 
+async fn run_retention(db: &Arc<Database>, policy: &RetentionPolicy) {
+    let (db, policy) = (db.clone(), policy.clone());
+    // Compaction and VACUUM can take a while on large databases
+    match tokio::task::spawn_blocking(move || db.apply_retention(&policy)).await {
+        Ok(Ok(report)) => {
+            if report != RetentionReport::default() {
+                tracing::info!(
+                    "Retention: compacted {} events from {} runs, archived {} tickets, removed {} orphaned rows{}",
+                    report.compacted_events,
+                    report.compacted_runs,
+                    report.archived_tickets,
+                    report.orphans_removed,
+                    if report.vacuumed { ", vacuumed" } else { "" }
+                );
+            }
+        }
+        Ok(Err(e)) => tracing::error!("Retention error: {}", e),
+        Err(e) => tracing::error!("Retention task panicked: {}", e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +253,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
 
         // Create a run and lock the ticket with an expired time
@@ -222,6 +304,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
 
         // Create a run and lock the ticket with a future expiration
@@ -270,6 +353,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
 
         // Create a run with running status
@@ -327,10 +411,28 @@ mod tests {
         assert!(!with_repo_locks.is_empty());
     }
 
+    #[test]
+    fn cleanup_totals_accumulate() {
+        let totals = CleanupTotals::new();
+        let result = CleanupResult {
+            released_tickets: vec!["t1".to_string(), "t2".to_string()],
+            aborted_runs: vec!["r1".to_string()],
+            released_repo_locks: 3,
+        };
+
+        totals.record(&result);
+        totals.record(&result);
+
+        assert_eq!(totals.released_tickets.load(Ordering::Relaxed), 4);
+        assert_eq!(totals.aborted_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(totals.released_repo_locks.load(Ordering::Relaxed), 6);
+    }
+
     #[test]
     fn cleanup_config_default() {
         let config = CleanupConfig::default();
         assert_eq!(config.check_interval_secs, 60);
+        assert_eq!(config.retention.interval_mins, 1440);
     }
 
     #[test]
@@ -357,6 +459,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
 
         let ticket2 = db.create_ticket(&CreateTicket {
@@ -376,6 +479,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
 
         let run1 = db.create_run(&CreateRun {
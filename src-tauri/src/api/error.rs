@@ -137,11 +137,24 @@ impl From<crate::db::DbError> for AppError {
         match &err {
             crate::db::DbError::NotFound(msg) => Self::not_found(msg),
             crate::db::DbError::Validation(msg) => Self::validation(msg.clone()),
+            crate::db::DbError::Conflict(msg) => Self::conflict(msg.clone()),
             _ => Self::database(err),
         }
     }
 }
 
+impl From<crate::agents::planner::PlannerError> for AppError {
+    fn from(err: crate::agents::planner::PlannerError) -> Self {
+        use crate::agents::planner::PlannerError;
+        match &err {
+            PlannerError::ScratchpadNotFound(_) => Self::not_found("Scratchpad"),
+            PlannerError::InvalidState(msg) => Self::conflict(msg.clone()),
+            PlannerError::Database(msg) => Self::database(msg),
+            _ => Self::internal(err.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,6 +200,14 @@ mod tests {
         assert!(matches!(app_err.body.code, ErrorCode::ValidationError));
     }
 
+    #[test]
+    fn from_db_error_conflict() {
+        let db_err = DbError::Conflict("Lease 3 is no longer held".to_string());
+        let app_err: AppError = db_err.into();
+        assert_eq!(app_err.status, StatusCode::CONFLICT);
+        assert_eq!(app_err.body.error, "Lease 3 is no longer held");
+    }
+
     #[test]
     fn from_db_error_sqlite() {
         let db_err = DbError::Sqlite(rusqlite::Error::InvalidQuery);
@@ -194,4 +215,12 @@ mod tests {
         assert_eq!(app_err.status, StatusCode::INTERNAL_SERVER_ERROR);
         assert!(matches!(app_err.body.code, ErrorCode::DatabaseError));
     }
+
+    #[test]
+    fn from_planner_error_invalid_state() {
+        let err = crate::agents::planner::PlannerError::InvalidState("not approved".to_string());
+        let app_err: AppError = err.into();
+        assert_eq!(app_err.status, StatusCode::CONFLICT);
+        assert_eq!(app_err.body.error, "not approved");
+    }
 }
@@ -98,6 +98,7 @@ fn event_matches_filter(
             LiveEvent::TicketUpdated { .. } => "ticket_updated",
             LiveEvent::TicketMoved { .. } => "ticket_moved",
             LiveEvent::TicketDeleted { .. } => "ticket_deleted",
+            LiveEvent::TicketRestored { .. } => "ticket_restored",
             LiveEvent::CommentAdded { .. } => "comment_added",
             LiveEvent::RunStarted { .. } => "run_started",
             LiveEvent::RunUpdated { .. } => "run_updated",
@@ -105,15 +106,23 @@ fn event_matches_filter(
             LiveEvent::EventReceived { .. } => "event_received",
             LiveEvent::TicketLocked { .. } => "ticket_locked",
             LiveEvent::TicketUnlocked { .. } => "ticket_unlocked",
+            LiveEvent::ColumnsChanged { .. } => "columns_changed",
+            LiveEvent::CustomFieldsChanged { .. } => "custom_fields_changed",
+            LiveEvent::LabelsChanged { .. } => "labels_changed",
+            LiveEvent::BoardRestored { .. } => "board_restored",
             LiveEvent::ScratchpadCreated { .. } => "scratchpad_created",
             LiveEvent::ScratchpadUpdated { .. } => "scratchpad_updated",
             LiveEvent::ScratchpadDeleted { .. } => "scratchpad_deleted",
+            LiveEvent::ScratchpadRestored { .. } => "scratchpad_restored",
             LiveEvent::ExplorationProgress { .. } => "exploration_progress",
             LiveEvent::PlanGenerated { .. } => "plan_generated",
             LiveEvent::PlanApproved { .. } => "plan_approved",
             LiveEvent::PlanExecutionStarted { .. } => "plan_execution_started",
             LiveEvent::PlanExecutionCompleted { .. } => "plan_execution_completed",
             LiveEvent::PlannerLogEntry { .. } => "planner_log_entry",
+            LiveEvent::WorkerStateChanged { .. } => "worker_state_changed",
+            LiveEvent::RemoteWorkersChanged { .. } => "remote_workers_changed",
+            LiveEvent::AgentEvent { .. } => "agent_event",
         };
 
         if !types.iter().any(|t| t == event_type) {
@@ -127,11 +136,13 @@ fn event_matches_filter(
             LiveEvent::TicketUpdated { ticket_id } => Some(ticket_id),
             LiveEvent::TicketMoved { ticket_id, .. } => Some(ticket_id),
             LiveEvent::TicketDeleted { ticket_id, .. } => Some(ticket_id),
+            LiveEvent::TicketRestored { ticket_id, .. } => Some(ticket_id),
             LiveEvent::CommentAdded { ticket_id, .. } => Some(ticket_id),
             LiveEvent::RunStarted { ticket_id, .. } => Some(ticket_id),
             LiveEvent::RunCompleted { ticket_id, .. } => Some(ticket_id),
             LiveEvent::TicketLocked { ticket_id, .. } => Some(ticket_id),
             LiveEvent::TicketUnlocked { ticket_id } => Some(ticket_id),
+            LiveEvent::AgentEvent { ticket_id, .. } => ticket_id.as_ref(),
             _ => None,
         };
 
@@ -147,6 +158,7 @@ fn event_matches_filter(
             LiveEvent::RunCompleted { run_id, .. } => Some(run_id),
             LiveEvent::EventReceived { run_id, .. } => Some(run_id),
             LiveEvent::TicketLocked { run_id, .. } => Some(run_id),
+            LiveEvent::AgentEvent { run_id, .. } => run_id.as_ref(),
             _ => None,
         };
 
@@ -1,20 +1,49 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use chrono::{Duration, Utc};
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use super::error::{ApiResult, AppError};
 use super::state::{AppState, LiveEvent};
+use super::backup::restore_with_workers_paused;
 use super::types::*;
+use crate::agents::orchestrator::{AdhocStage, ADHOC_STAGE_NAME};
+use crate::agents::planner::{PlannerAgent, PlannerConfig};
+use crate::agents::recovery::{self, RecoverySettings, Restart};
+use crate::agents::sink::BroadcastEventSink;
+use crate::agents::slots::{ConcurrencyLimits, ConcurrencyStatus, SLOTS};
+use crate::agents::worker::{WorkerConfig, WorkerStatus, WORKER_MANAGER};
+use crate::agents::{AgentKind, ClaudeApiConfig};
 use crate::db::{
     AgentEvent, AgentEventPayload, AgentRun, Board, Column, Comment,
     CreateRun, CreateTicket, CreateComment, UpdateTicket, EventType,
-    NormalizedEvent, RunStatus, Ticket, AuthorType,
+    NormalizedEvent, RunStatus, Ticket, AuthorType, Project, CreateProject,
+    UpdateProject, Scratchpad, CreateScratchpad, ScratchpadStatus, CreateColumn, UpdateColumn,
 };
-use crate::lifecycle::{TicketState, TransitionPermission, can_transition};
+use crate::db::custom_fields::field_matches;
+use crate::db::scheduling::{QueueExplanation, QueueFilter, SchedulingPolicy};
+use crate::db::models::{Actor, ArchivedTicket, CheckpointState, WorkflowCheckpoint, LabelFilter, CreateCustomField, CustomField, UpdateCustomField, CreateLabel, Label, UpdateLabel, CreateWorkerDefinition, UpdateWorkerDefinition, WorkerDefinition, LeaseResult, RegisterRemoteWorker, RemoteWorker, RunArtifact, TicketLease, BackupInfo, BoardBundle, RetentionReport, BoardImport, Task, CreateTask, TaskType, TicketHistoryEntry, TrashEntry, TrashItemType};
+use crate::lifecycle::{TicketState, TransitionPermission, can_transition, move_to_ready_if_completed};
+
+/// Run the database work of a handler on the blocking thread pool through
+/// [`crate::db::Database::blocking`], so SQLite and the writer lock never stall the
+/// runtime's worker threads
+async fn blocking<T, F>(state: &AppState, f: F) -> ApiResult<T>
+where
+    F: FnOnce(&AppState) -> ApiResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let state = state.clone();
+    let db = state.db.clone();
+    db.blocking(move |_| Ok(f(&state))).await?
+}
 
 pub async fn health() -> &'static str {
     "ok"
@@ -23,22 +52,25 @@ pub async fn health() -> &'static str {
 pub async fn health_detailed(
     State(state): State<AppState>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let board_count = state.db.get_boards()
-        .map(|b| b.len())
-        .unwrap_or(0);
-
-    Ok(Json(serde_json::json!({
-        "status": "ok",
-        "version": env!("CARGO_PKG_VERSION"),
-        "database": "connected",
-        "boardCount": board_count
-    })))
+    blocking(&state, move |state| {
+        let board_count = state.db.get_boards()
+            .map(|b| b.len())
+            .unwrap_or(0);
+
+        Ok(Json(serde_json::json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION"),
+            "database": "connected",
+            "boardCount": board_count
+        })))
+    })
+    .await
 }
 
 pub async fn list_boards(
     State(state): State<AppState>,
 ) -> ApiResult<Json<Vec<Board>>> {
-    let boards = state.db.get_boards()?;
+    let boards = state.db.blocking(|db| db.get_boards()).await?;
     Ok(Json(boards))
 }
 
@@ -46,9 +78,10 @@ pub async fn get_board(
     State(state): State<AppState>,
     Path(board_id): Path<String>,
 ) -> ApiResult<Json<BoardWithColumns>> {
-    let board = state.db.get_board(&board_id)?
-        .ok_or_else(|| AppError::not_found("Board"))?;
-    let columns = state.db.get_columns(&board_id)?;
+    let (board, columns) = state.db
+        .blocking(move |db| Ok((db.get_board(&board_id)?, db.get_columns(&board_id)?)))
+        .await?;
+    let board = board.ok_or_else(|| AppError::not_found("Board"))?;
     
     Ok(Json(BoardWithColumns {
         id: board.id,
@@ -64,25 +97,435 @@ pub async fn list_columns(
     State(state): State<AppState>,
     Path(board_id): Path<String>,
 ) -> ApiResult<Json<Vec<Column>>> {
-    state.db.get_board(&board_id)?
-        .ok_or_else(|| AppError::not_found("Board"))?;
-    let columns = state.db.get_columns(&board_id)?;
-    Ok(Json(columns))
+    blocking(&state, move |state| {
+        state.db.get_board(&board_id)?
+            .ok_or_else(|| AppError::not_found("Board"))?;
+        let columns = state.db.get_columns(&board_id)?;
+        Ok(Json(columns))
+    })
+    .await
+}
+
+pub async fn create_column(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+    Json(req): Json<CreateColumn>,
+) -> ApiResult<(StatusCode, Json<Column>)> {
+    blocking(&state, move |state| {
+        let column = state.db.create_column(&board_id, &req)?;
+        state.broadcast(LiveEvent::ColumnsChanged { board_id });
+        Ok((StatusCode::CREATED, Json(column)))
+    })
+    .await
+}
+
+/// Resolve a column that must belong to `board_id`
+fn board_column(state: &AppState, board_id: &str, column_id: &str) -> ApiResult<Column> {
+    state.db.get_column(column_id)?
+        .filter(|c| c.board_id == board_id)
+        .ok_or_else(|| AppError::not_found("Column"))
+}
+
+pub async fn update_column(
+    State(state): State<AppState>,
+    Path((board_id, column_id)): Path<(String, String)>,
+    Json(req): Json<UpdateColumn>,
+) -> ApiResult<Json<Column>> {
+    blocking(&state, move |state| {
+        board_column(state, &board_id, &column_id)?;
+        let column = state.db.update_column(&column_id, &req)?;
+        state.broadcast(LiveEvent::ColumnsChanged { board_id });
+        Ok(Json(column))
+    })
+    .await
+}
+
+pub async fn list_custom_fields(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+) -> ApiResult<Json<Vec<CustomField>>> {
+    blocking(&state, move |state| {
+        state.db.get_board(&board_id)?
+            .ok_or_else(|| AppError::not_found("Board"))?;
+        let fields = state.db.get_custom_fields(&board_id)?;
+        Ok(Json(fields))
+    })
+    .await
+}
+
+pub async fn create_custom_field(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+    Json(req): Json<CreateCustomField>,
+) -> ApiResult<(StatusCode, Json<CustomField>)> {
+    blocking(&state, move |state| {
+        let field = state.db.create_custom_field(&board_id, &req)?;
+        state.broadcast(LiveEvent::CustomFieldsChanged { board_id });
+        Ok((StatusCode::CREATED, Json(field)))
+    })
+    .await
+}
+
+/// Check that a custom field belongs to `board_id`
+fn board_custom_field(state: &AppState, board_id: &str, field_id: &str) -> ApiResult<()> {
+    state.db.get_custom_fields(board_id)?
+        .iter()
+        .any(|f| f.id == field_id)
+        .then_some(())
+        .ok_or_else(|| AppError::not_found("Custom field"))
+}
+
+pub async fn update_custom_field(
+    State(state): State<AppState>,
+    Path((board_id, field_id)): Path<(String, String)>,
+    Json(req): Json<UpdateCustomField>,
+) -> ApiResult<Json<CustomField>> {
+    blocking(&state, move |state| {
+        board_custom_field(state, &board_id, &field_id)?;
+        let field = state.db.update_custom_field(&field_id, &req)?;
+        state.broadcast(LiveEvent::CustomFieldsChanged { board_id });
+        Ok(Json(field))
+    })
+    .await
+}
+
+/// Delete a custom field and its values on the board's tickets
+pub async fn delete_custom_field(
+    State(state): State<AppState>,
+    Path((board_id, field_id)): Path<(String, String)>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        board_custom_field(state, &board_id, &field_id)?;
+        state.db.delete_custom_field(&field_id)?;
+        state.broadcast(LiveEvent::CustomFieldsChanged { board_id });
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: field_id,
+            trash_id: None,
+        }))
+    })
+    .await
+}
+
+pub async fn list_labels(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+) -> ApiResult<Json<Vec<Label>>> {
+    blocking(&state, move |state| {
+        state.db.get_board(&board_id)?
+            .ok_or_else(|| AppError::not_found("Board"))?;
+        let labels = state.db.get_labels(&board_id)?;
+        Ok(Json(labels))
+    })
+    .await
+}
+
+pub async fn create_label(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+    Json(req): Json<CreateLabel>,
+) -> ApiResult<(StatusCode, Json<Label>)> {
+    blocking(&state, move |state| {
+        let label = state.db.create_label(&board_id, &req)?;
+        state.broadcast(LiveEvent::LabelsChanged { board_id });
+        Ok((StatusCode::CREATED, Json(label)))
+    })
+    .await
+}
+
+/// Check that a label belongs to `board_id`
+fn board_label(state: &AppState, board_id: &str, label_id: &str) -> ApiResult<()> {
+    state.db.get_labels(board_id)?
+        .iter()
+        .any(|l| l.id == label_id)
+        .then_some(())
+        .ok_or_else(|| AppError::not_found("Label"))
+}
+
+/// Change a label; a new name is applied to every ticket carrying it
+pub async fn update_label(
+    State(state): State<AppState>,
+    Path((board_id, label_id)): Path<(String, String)>,
+    Json(req): Json<UpdateLabel>,
+) -> ApiResult<Json<Label>> {
+    blocking(&state, move |state| {
+        board_label(state, &board_id, &label_id)?;
+        let label = state.db.update_label(&label_id, &req, &Actor::user())?;
+        state.broadcast(LiveEvent::LabelsChanged { board_id });
+        Ok(Json(label))
+    })
+    .await
+}
+
+/// Fold the label in the path into another label of the board
+pub async fn merge_label(
+    State(state): State<AppState>,
+    Path((board_id, label_id)): Path<(String, String)>,
+    Json(req): Json<MergeLabelRequest>,
+) -> ApiResult<Json<Label>> {
+    blocking(&state, move |state| {
+        board_label(state, &board_id, &label_id)?;
+        board_label(state, &board_id, &req.into_label_id)?;
+        let label = state.db.merge_labels(&label_id, &req.into_label_id, &Actor::user())?;
+        state.broadcast(LiveEvent::LabelsChanged { board_id });
+        Ok(Json(label))
+    })
+    .await
+}
+
+/// Delete a label and remove it from the board's tickets
+pub async fn delete_label(
+    State(state): State<AppState>,
+    Path((board_id, label_id)): Path<(String, String)>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        board_label(state, &board_id, &label_id)?;
+        state.db.delete_label(&label_id, &Actor::user())?;
+        state.broadcast(LiveEvent::LabelsChanged { board_id });
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: label_id,
+            trash_id: None,
+        }))
+    })
+    .await
+}
+
+pub async fn reorder_columns(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+    Json(req): Json<ReorderColumnsRequest>,
+) -> ApiResult<Json<Vec<Column>>> {
+    blocking(&state, move |state| {
+        state.db.get_board(&board_id)?
+            .ok_or_else(|| AppError::not_found("Board"))?;
+        let columns = state.db.reorder_columns(&board_id, &req.column_ids)?;
+        state.broadcast(LiveEvent::ColumnsChanged { board_id });
+        Ok(Json(columns))
+    })
+    .await
+}
+
+pub async fn delete_column(
+    State(state): State<AppState>,
+    Path((board_id, column_id)): Path<(String, String)>,
+    Query(query): Query<DeleteColumnQuery>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        board_column(state, &board_id, &column_id)?;
+        state.db.delete_column(&column_id, query.move_tickets_to.as_deref())?;
+        state.broadcast(LiveEvent::ColumnsChanged { board_id });
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: column_id,
+            trash_id: None,
+        }))
+    })
+    .await
+}
+
+/// Move a board to the trash with its tickets and scratchpads, or delete it
+/// for good with `?permanent=true`
+pub async fn delete_board(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> ApiResult<Json<DeleteResponse>> {
+    let id = board_id.clone();
+    let trash_id = state.db
+        .blocking(move |db| {
+            if query.permanent {
+                db.delete_board(&id).map(|_| None)
+            } else {
+                db.trash_board(&id).map(|entry| Some(entry.id))
+            }
+        })
+        .await?;
+    Ok(Json(DeleteResponse {
+        deleted: true,
+        id: board_id,
+        trash_id,
+    }))
+}
+
+pub async fn export_board(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+    Query(query): Query<ExportBoardQuery>,
+) -> ApiResult<Json<BoardBundle>> {
+    blocking(&state, move |state| {
+        let bundle = state.db.export_board(&board_id, query.include_runs)?;
+        Ok(Json(bundle))
+    })
+    .await
+}
+
+pub async fn export_board_markdown(
+    State(state): State<AppState>,
+    Path(board_id): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    blocking(&state, move |state| {
+        let markdown = state.db.export_board_markdown(&board_id)?;
+        Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown))
+    })
+    .await
+}
+
+pub async fn import_board(
+    State(state): State<AppState>,
+    Json(req): Json<ImportBoardRequest>,
+) -> ApiResult<(StatusCode, Json<BoardImport>)> {
+    blocking(&state, move |state| {
+        let imported = state.db.import_board(&req.bundle, req.name.as_deref())?;
+        state.broadcast(LiveEvent::ColumnsChanged { board_id: imported.board.id.clone() });
+        Ok((StatusCode::CREATED, Json(imported)))
+    })
+    .await
+}
+
+pub async fn list_backups(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<BackupInfo>>> {
+    blocking(&state, move |state| {
+        Ok(Json(state.db.list_backups()?))
+    })
+    .await
+}
+
+pub async fn create_backup(
+    State(state): State<AppState>,
+) -> ApiResult<(StatusCode, Json<BackupInfo>)> {
+    blocking(&state, move |state| {
+        let backup = state.db.create_backup("manual")?;
+        Ok((StatusCode::CREATED, Json(backup)))
+    })
+    .await
+}
+
+/// Restore a backup; responds with the snapshot taken of the replaced state
+pub async fn restore_backup(
+    State(state): State<AppState>,
+    Path(file_name): Path<String>,
+) -> ApiResult<Json<BackupInfo>> {
+    let snapshot = restore_with_workers_paused(&state.db, &file_name).await?;
+    Ok(Json(snapshot))
+}
+
+pub async fn list_archived_tickets(
+    State(state): State<AppState>,
+    Query(query): Query<ArchiveQuery>,
+) -> ApiResult<Json<Vec<ArchivedTicket>>> {
+    let tickets = state.db
+        .blocking(move |db| db.search_archived_tickets(query.board_id.as_deref(), query.q.as_deref()))
+        .await?;
+    Ok(Json(tickets))
+}
+
+pub async fn restore_archived_ticket(
+    State(state): State<AppState>,
+    Path(ticket_id): Path<String>,
+) -> ApiResult<Json<Ticket>> {
+    blocking(&state, move |state| {
+        let ticket = state.db.restore_archived_ticket(&ticket_id, &Actor::user())?;
+        // The ticket reappears on its board
+        state.broadcast(LiveEvent::TicketCreated {
+            ticket_id: ticket.id.clone(),
+            board_id: ticket.board_id.clone(),
+        });
+        Ok(Json(ticket))
+    })
+    .await
+}
+
+pub async fn list_trash(
+    State(state): State<AppState>,
+    Query(query): Query<TrashQuery>,
+) -> ApiResult<Json<Vec<TrashEntry>>> {
+    let entries = state.db
+        .blocking(move |db| db.list_trash(query.board_id.as_deref()))
+        .await?;
+    Ok(Json(entries))
+}
+
+pub async fn restore_trash(
+    State(state): State<AppState>,
+    Path(trash_id): Path<String>,
+) -> ApiResult<Json<TrashEntry>> {
+    blocking(&state, move |state| {
+        let entry = state.db.restore_trash(&trash_id)?;
+        broadcast_restored(state, &entry);
+        Ok(Json(entry))
+    })
+    .await
+}
+
+/// Delete a trash entry for good
+pub async fn purge_trash(
+    State(state): State<AppState>,
+    Path(trash_id): Path<String>,
+) -> ApiResult<Json<DeleteResponse>> {
+    let id = trash_id.clone();
+    state.db.blocking(move |db| db.purge_trash(&id)).await?;
+    Ok(Json(DeleteResponse {
+        deleted: true,
+        id: trash_id,
+        trash_id: None,
+    }))
+}
+
+fn broadcast_restored(state: &AppState, entry: &TrashEntry) {
+    let board_id = entry.board_id.clone().unwrap_or_default();
+    state.broadcast(match entry.item_type {
+        TrashItemType::Ticket => LiveEvent::TicketRestored {
+            ticket_id: entry.item_id.clone(),
+            board_id,
+        },
+        TrashItemType::Board => LiveEvent::BoardRestored { board_id },
+        TrashItemType::Scratchpad => LiveEvent::ScratchpadRestored {
+            scratchpad_id: entry.item_id.clone(),
+            board_id,
+        },
+    });
+}
+
+/// Apply the retention policy now instead of waiting for the cleanup service
+pub async fn run_retention(
+    State(state): State<AppState>,
+) -> ApiResult<Json<RetentionReport>> {
+    let policy = state.retention.clone();
+    let report = state.db.blocking(move |db| db.apply_retention(&policy)).await?;
+    Ok(Json(report))
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TicketQuery {
     pub column: Option<String>,
+    /// `field.<key>=<value>` filters on custom field values
+    #[serde(flatten)]
+    pub fields: HashMap<String, String>,
 }
 
+/// Query parameter prefix for custom field filters
+const FIELD_FILTER_PREFIX: &str = "field.";
+
 pub async fn list_tickets(
     State(state): State<AppState>,
     Path(board_id): Path<String>,
     Query(query): Query<TicketQuery>,
 ) -> ApiResult<Json<Vec<Ticket>>> {
-    state.db.get_board(&board_id)?
+    let mut tickets = state.db
+        .blocking(move |db| match db.get_board(&board_id)? {
+            Some(_) => db.get_tickets(&board_id, query.column.as_deref()).map(Some),
+            None => Ok(None),
+        })
+        .await?
         .ok_or_else(|| AppError::not_found("Board"))?;
-    let tickets = state.db.get_tickets(&board_id, query.column.as_deref())?;
+
+    for (param, expected) in &query.fields {
+        if let Some(key) = param.strip_prefix(FIELD_FILTER_PREFIX) {
+            tickets.retain(|t| field_matches(t, key, expected));
+        }
+    }
     Ok(Json(tickets))
 }
 
@@ -90,106 +533,139 @@ pub async fn create_ticket(
     State(state): State<AppState>,
     Json(req): Json<CreateTicketRequest>,
 ) -> ApiResult<(StatusCode, Json<Ticket>)> {
-    state.db.get_board(&req.board_id)?
-        .ok_or_else(|| AppError::not_found("Board"))?;
-    
-    let columns = state.db.get_columns(&req.board_id)?;
-    if !columns.iter().any(|c| c.id == req.column_id) {
-        return Err(AppError::not_found("Column"));
-    }
+    blocking(&state, move |state| {
+        state.db.get_board(&req.board_id)?
+            .ok_or_else(|| AppError::not_found("Board"))?;
 
-    if req.title.trim().is_empty() {
-        return Err(AppError::validation("Title cannot be empty"));
-    }
+        let columns = state.db.get_columns(&req.board_id)?;
+        if !columns.iter().any(|c| c.id == req.column_id) {
+            return Err(AppError::not_found("Column"));
+        }
 
-    let ticket = state.db.create_ticket(&CreateTicket {
-        board_id: req.board_id.clone(),
-        column_id: req.column_id,
-        title: req.title,
-        description_md: req.description_md,
-        priority: req.priority,
-        labels: req.labels,
-        project_id: req.project_id,
-        agent_pref: req.agent_pref,
-        workflow_type: req.workflow_type.unwrap_or_default(),
-        model: req.model,
-        branch_name: req.branch_name,
-        is_epic: false,
-        epic_id: None,
-        depends_on_epic_id: None,
-        depends_on_epic_ids: vec![],
-        scratchpad_id: None,
-    })?;
-
-    state.broadcast(LiveEvent::TicketCreated {
-        ticket_id: ticket.id.clone(),
-        board_id: req.board_id,
-    });
+        if req.title.trim().is_empty() {
+            return Err(AppError::validation("Title cannot be empty"));
+        }
+        state.db.check_required_fields(&req.board_id, &req.custom_fields)?;
+
+        let ticket = state.db.create_ticket(&CreateTicket {
+            board_id: req.board_id.clone(),
+            column_id: req.column_id,
+            title: req.title,
+            description_md: req.description_md,
+            priority: req.priority,
+            labels: req.labels,
+            project_id: req.project_id,
+            agent_pref: req.agent_pref,
+            workflow_type: req.workflow_type.unwrap_or_default(),
+            model: req.model,
+            branch_name: req.branch_name,
+            is_epic: false,
+            epic_id: None,
+            depends_on_epic_id: None,
+            depends_on_epic_ids: vec![],
+            scratchpad_id: None,
+            custom_fields: req.custom_fields,
+        })?;
+
+        state.broadcast(LiveEvent::TicketCreated {
+            ticket_id: ticket.id.clone(),
+            board_id: req.board_id,
+        });
 
-    Ok((StatusCode::CREATED, Json(ticket)))
+        Ok((StatusCode::CREATED, Json(ticket)))
+    })
+    .await
 }
 
 pub async fn get_ticket(
     State(state): State<AppState>,
     Path(ticket_id): Path<String>,
 ) -> ApiResult<Json<Ticket>> {
-    let ticket = state.db.get_ticket(&ticket_id)?;
+    let ticket = state.db.blocking(move |db| db.get_ticket(&ticket_id)).await?;
     Ok(Json(ticket))
 }
 
+pub async fn get_ticket_history(
+    State(state): State<AppState>,
+    Path(ticket_id): Path<String>,
+) -> ApiResult<Json<Vec<TicketHistoryEntry>>> {
+    blocking(&state, move |state| {
+        state.db.get_ticket(&ticket_id)?;
+        let history = state.db.get_ticket_history(&ticket_id)?;
+        Ok(Json(history))
+    })
+    .await
+}
+
 pub async fn update_ticket(
     State(state): State<AppState>,
     Path(ticket_id): Path<String>,
     Json(req): Json<UpdateTicketRequest>,
 ) -> ApiResult<Json<Ticket>> {
-    if let Some(ref title) = req.title {
-        if title.trim().is_empty() {
-            return Err(AppError::validation("Title cannot be empty"));
+    blocking(&state, move |state| {
+        if let Some(ref title) = req.title {
+            if title.trim().is_empty() {
+                return Err(AppError::validation("Title cannot be empty"));
+            }
         }
-    }
 
-    let ticket = state.db.update_ticket(&ticket_id, &UpdateTicket {
-        title: req.title,
-        description_md: req.description_md,
-        priority: req.priority,
-        labels: req.labels,
-        project_id: req.project_id,
-        agent_pref: req.agent_pref,
-        workflow_type: req.workflow_type,
-        model: req.model,
-        branch_name: req.branch_name,
-        column_id: req.column_id,
-        is_epic: None,
-        epic_id: None,
-        order_in_epic: None,
-        depends_on_epic_id: None,
-        depends_on_epic_ids: vec![],
-        scratchpad_id: None,
-    })?;
-
-    state.broadcast(LiveEvent::TicketUpdated {
-        ticket_id: ticket.id.clone(),
-    });
+        let ticket = state.db.update_ticket(&ticket_id, &UpdateTicket {
+            title: req.title,
+            description_md: req.description_md,
+            priority: req.priority,
+            labels: req.labels,
+            project_id: req.project_id,
+            agent_pref: req.agent_pref,
+            workflow_type: req.workflow_type,
+            model: req.model,
+            branch_name: req.branch_name,
+            column_id: req.column_id,
+            is_epic: None,
+            epic_id: None,
+            order_in_epic: None,
+            depends_on_epic_id: None,
+            depends_on_epic_ids: vec![],
+            scratchpad_id: None,
+            custom_fields: req.custom_fields,
+        }, &Actor::user())?;
+
+        state.broadcast(LiveEvent::TicketUpdated {
+            ticket_id: ticket.id.clone(),
+        });
 
-    Ok(Json(ticket))
+        Ok(Json(ticket))
+    })
+    .await
 }
 
+/// Move a ticket (with an epic's children) to the trash, or delete it for
+/// good with `?permanent=true`
 pub async fn delete_ticket(
     State(state): State<AppState>,
     Path(ticket_id): Path<String>,
+    Query(query): Query<DeleteQuery>,
 ) -> ApiResult<Json<DeleteResponse>> {
-    let ticket = state.db.get_ticket(&ticket_id)?;
-    state.db.delete_ticket(&ticket_id)?;
+    blocking(&state, move |state| {
+        let ticket = state.db.get_ticket(&ticket_id)?;
+        let trash_id = if query.permanent {
+            state.db.delete_ticket(&ticket_id)?;
+            None
+        } else {
+            Some(state.db.trash_ticket(&ticket_id)?.id)
+        };
 
-    state.broadcast(LiveEvent::TicketDeleted {
-        ticket_id: ticket_id.clone(),
-        board_id: ticket.board_id,
-    });
+        state.broadcast(LiveEvent::TicketDeleted {
+            ticket_id: ticket_id.clone(),
+            board_id: ticket.board_id,
+        });
 
-    Ok(Json(DeleteResponse {
-        deleted: true,
-        id: ticket_id,
-    }))
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: ticket_id,
+            trash_id,
+        }))
+    })
+    .await
 }
 
 pub async fn move_ticket(
@@ -197,51 +673,47 @@ pub async fn move_ticket(
     Path(ticket_id): Path<String>,
     Json(req): Json<MoveTicketRequest>,
 ) -> ApiResult<Json<Ticket>> {
-    let ticket = state.db.get_ticket(&ticket_id)?;
-    let from_column_id = ticket.column_id.clone();
-
-    let columns = state.db.get_columns(&ticket.board_id)?;
-    
-    let current_column = columns.iter()
-        .find(|c| c.id == from_column_id)
-        .ok_or_else(|| AppError::not_found("Current column"))?;
-    
-    let target_column = columns.iter()
-        .find(|c| c.id == req.column_id)
-        .ok_or_else(|| AppError::not_found("Target column"))?;
-
-    let current_state = TicketState::from_column_name(&current_column.name)
-        .ok_or_else(|| AppError::validation(format!(
-            "Unknown column state: {}", current_column.name
-        )))?;
-
-    let target_state = TicketState::from_column_name(&target_column.name)
-        .ok_or_else(|| AppError::validation(format!(
-            "Unknown target state: {}", target_column.name
-        )))?;
-
-    let is_locked = ticket.locked_by_run_id.is_some() 
-        && ticket.lock_expires_at.is_some_and(|exp| exp > Utc::now());
-    match can_transition(current_state, target_state, is_locked, false) {
-        TransitionPermission::Allowed => {}
-        TransitionPermission::RequiresUnlock => {
-            return Err(AppError::conflict("Ticket is locked by an active run"));
-        }
-        TransitionPermission::Denied(reason) => {
-            return Err(AppError::validation(reason));
+    blocking(&state, move |state| {
+        let ticket = state.db.get_ticket(&ticket_id)?;
+        let from_column_id = ticket.column_id.clone();
+
+        let columns = state.db.get_columns(&ticket.board_id)?;
+
+        let current_column = columns.iter()
+            .find(|c| c.id == from_column_id)
+            .ok_or_else(|| AppError::not_found("Current column"))?;
+
+        let target_column = columns.iter()
+            .find(|c| c.id == req.column_id)
+            .ok_or_else(|| AppError::not_found("Target column"))?;
+
+        // Custom columns have no lifecycle state, so there is no rule to check
+        if let (Some(current_state), Some(target_state)) = (current_column.role, target_column.role) {
+            let is_locked = ticket.locked_by_run_id.is_some() 
+                && ticket.lock_expires_at.is_some_and(|exp| exp > Utc::now());
+            match can_transition(current_state, target_state, is_locked, false) {
+                TransitionPermission::Allowed => {}
+                TransitionPermission::RequiresUnlock => {
+                    return Err(AppError::conflict("Ticket is locked by an active run"));
+                }
+                TransitionPermission::Denied(reason) => {
+                    return Err(AppError::validation(reason));
+                }
+            }
         }
-    }
 
-    state.db.move_ticket(&ticket_id, &req.column_id)?;
-    let updated = state.db.get_ticket(&ticket_id)?;
+        state.db.move_ticket(&ticket_id, &req.column_id, &Actor::user())?;
+        let updated = state.db.get_ticket(&ticket_id)?;
 
-    state.broadcast(LiveEvent::TicketMoved {
-        ticket_id,
-        from_column_id,
-        to_column_id: req.column_id,
-    });
+        state.broadcast(LiveEvent::TicketMoved {
+            ticket_id,
+            from_column_id,
+            to_column_id: req.column_id,
+        });
 
-    Ok(Json(updated))
+        Ok(Json(updated))
+    })
+    .await
 }
 
 pub async fn reserve_ticket(
@@ -249,101 +721,105 @@ pub async fn reserve_ticket(
     Path(ticket_id): Path<String>,
     Json(req): Json<ReserveTicketRequest>,
 ) -> ApiResult<Json<ReservationResponse>> {
-    let ticket = state.db.get_ticket(&ticket_id)?;
+    blocking(&state, move |state| {
+        let ticket = state.db.get_ticket(&ticket_id)?;
 
-    if let Some(ref lock_expires) = ticket.lock_expires_at {
-        if *lock_expires > Utc::now() {
-            return Err(AppError::conflict("Ticket is already locked by another run"));
+        if let Some(ref lock_expires) = ticket.lock_expires_at {
+            if *lock_expires > Utc::now() {
+                return Err(AppError::conflict("Ticket is already locked by another run"));
+            }
         }
-    }
 
-    let repo_path = req.repo_path
-        .ok_or_else(|| AppError::validation("repo_path is required"))?;
+        let repo_path = req.repo_path
+            .ok_or_else(|| AppError::validation("repo_path is required"))?;
 
-    let run = state.db.create_run(&CreateRun {
-        ticket_id: ticket_id.clone(),
-        agent_type: req.agent_type,
-        repo_path,
-        parent_run_id: None,
-        stage: None,
-    })?;
+        let run = state.db.create_run(&CreateRun {
+            ticket_id: ticket_id.clone(),
+            agent_type: req.agent_type,
+            repo_path,
+            parent_run_id: None,
+            stage: None,
+        })?;
 
-    let lock_expires_at = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
-    state.db.lock_ticket(&ticket_id, &run.id, lock_expires_at)?;
+        let lock_expires_at = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
+        state.db.lock_ticket(&ticket_id, &run.id, lock_expires_at)?;
 
-    let columns = state.db.get_columns(&ticket.board_id)?;
-    if let Some(in_progress) = columns.iter().find(|c| c.name == "In Progress") {
-        let _ = state.db.move_ticket(&ticket_id, &in_progress.id);
-    }
+        if let Some(in_progress) = state.db.find_column_by_role(&ticket.board_id, TicketState::InProgress)? {
+            let _ = state.db.move_ticket(&ticket_id, &in_progress.id, &Actor::run(&run.id));
+        }
 
-    state.broadcast(LiveEvent::TicketLocked {
-        ticket_id: ticket_id.clone(),
-        run_id: run.id.clone(),
-    });
+        state.broadcast(LiveEvent::TicketLocked {
+            ticket_id: ticket_id.clone(),
+            run_id: run.id.clone(),
+        });
 
-    state.broadcast(LiveEvent::RunStarted {
-        run_id: run.id.clone(),
-        ticket_id: ticket_id.clone(),
-        agent_type: req.agent_type.as_str().to_string(),
-    });
+        state.broadcast(LiveEvent::RunStarted {
+            run_id: run.id.clone(),
+            ticket_id: ticket_id.clone(),
+            agent_type: req.agent_type.as_str().to_string(),
+        });
 
-    Ok(Json(ReservationResponse {
-        run_id: run.id,
-        ticket_id,
-        lock_expires_at,
-        heartbeat_interval_secs: HEARTBEAT_INTERVAL_SECS,
-    }))
+        Ok(Json(ReservationResponse {
+            run_id: run.id,
+            ticket_id,
+            lock_expires_at,
+            heartbeat_interval_secs: HEARTBEAT_INTERVAL_SECS,
+        }))
+    })
+    .await
 }
 
 pub async fn create_run(
     State(state): State<AppState>,
     Json(req): Json<CreateRunRequest>,
 ) -> ApiResult<(StatusCode, Json<AgentRun>)> {
-    let ticket = state.db.get_ticket(&req.ticket_id)?;
+    blocking(&state, move |state| {
+        let ticket = state.db.get_ticket(&req.ticket_id)?;
 
-    if let Some(ref lock_expires) = ticket.lock_expires_at {
-        if *lock_expires > Utc::now() {
-            return Err(AppError::conflict("Ticket is already locked"));
+        if let Some(ref lock_expires) = ticket.lock_expires_at {
+            if *lock_expires > Utc::now() {
+                return Err(AppError::conflict("Ticket is already locked"));
+            }
         }
-    }
 
-    let run = state.db.create_run(&CreateRun {
-        ticket_id: req.ticket_id.clone(),
-        agent_type: req.agent_type,
-        repo_path: req.repo_path,
-        parent_run_id: None,
-        stage: None,
-    })?;
-
-    // Lock the ticket to prevent concurrent runs
-    let lock_expires_at = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
-    state.db.lock_ticket(&req.ticket_id, &run.id, lock_expires_at)?;
-
-    // Move to In Progress if that column exists
-    let columns = state.db.get_columns(&ticket.board_id)?;
-    if let Some(in_progress) = columns.iter().find(|c| c.name == "In Progress") {
-        let _ = state.db.move_ticket(&req.ticket_id, &in_progress.id);
-    }
+        let run = state.db.create_run(&CreateRun {
+            ticket_id: req.ticket_id.clone(),
+            agent_type: req.agent_type,
+            repo_path: req.repo_path,
+            parent_run_id: None,
+            stage: None,
+        })?;
+
+        // Lock the ticket to prevent concurrent runs
+        let lock_expires_at = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
+        state.db.lock_ticket(&req.ticket_id, &run.id, lock_expires_at)?;
+
+        // Move to the in-progress column if the board has one
+        if let Some(in_progress) = state.db.find_column_by_role(&ticket.board_id, TicketState::InProgress)? {
+            let _ = state.db.move_ticket(&req.ticket_id, &in_progress.id, &Actor::run(&run.id));
+        }
 
-    state.broadcast(LiveEvent::TicketLocked {
-        ticket_id: req.ticket_id.clone(),
-        run_id: run.id.clone(),
-    });
+        state.broadcast(LiveEvent::TicketLocked {
+            ticket_id: req.ticket_id.clone(),
+            run_id: run.id.clone(),
+        });
 
-    state.broadcast(LiveEvent::RunStarted {
-        run_id: run.id.clone(),
-        ticket_id: req.ticket_id,
-        agent_type: req.agent_type.as_str().to_string(),
-    });
+        state.broadcast(LiveEvent::RunStarted {
+            run_id: run.id.clone(),
+            ticket_id: req.ticket_id,
+            agent_type: req.agent_type.as_str().to_string(),
+        });
 
-    Ok((StatusCode::CREATED, Json(run)))
+        Ok((StatusCode::CREATED, Json(run)))
+    })
+    .await
 }
 
 pub async fn get_run(
     State(state): State<AppState>,
     Path(run_id): Path<String>,
 ) -> ApiResult<Json<AgentRun>> {
-    let run = state.db.get_run(&run_id)?;
+    let run = state.db.blocking(move |db| db.get_run(&run_id)).await?;
     Ok(Json(run))
 }
 
@@ -351,8 +827,12 @@ pub async fn list_runs(
     State(state): State<AppState>,
     Path(ticket_id): Path<String>,
 ) -> ApiResult<Json<Vec<AgentRun>>> {
-    state.db.get_ticket(&ticket_id)?;
-    let runs = state.db.get_runs(&ticket_id)?;
+    let runs = state.db
+        .blocking(move |db| {
+            db.get_ticket(&ticket_id)?;
+            db.get_runs(&ticket_id)
+        })
+        .await?;
     Ok(Json(runs))
 }
 
@@ -361,53 +841,66 @@ pub async fn update_run(
     Path(run_id): Path<String>,
     Json(req): Json<UpdateRunRequest>,
 ) -> ApiResult<Json<AgentRun>> {
-    let existing = state.db.get_run(&run_id)?;
-
-    let status = req.status
-        .as_ref()
-        .and_then(|s| RunStatus::parse(s))
-        .unwrap_or(existing.status.clone());
-
-    state.db.update_run_status(
-        &run_id,
-        status.clone(),
-        req.exit_code,
-        req.summary_md.as_deref(),
-    )?;
-
-    let updated = state.db.get_run(&run_id)?;
-
-    if matches!(status, RunStatus::Finished | RunStatus::Error | RunStatus::Aborted) {
-        if let Ok(ticket) = state.db.get_ticket(&existing.ticket_id) {
-            if ticket.locked_by_run_id.as_ref() == Some(&run_id) {
-                state.db.unlock_ticket(&existing.ticket_id)?;
-                state.broadcast(LiveEvent::TicketUnlocked {
-                    ticket_id: existing.ticket_id.clone(),
-                });
+    blocking(&state, move |state| {
+        let existing = state.db.get_run(&run_id)?;
+
+        let status = req.status
+            .as_ref()
+            .and_then(|s| RunStatus::parse(s))
+            .unwrap_or(existing.status.clone());
+
+        state.db.update_run_status(
+            &run_id,
+            status.clone(),
+            req.exit_code,
+            req.summary_md.as_deref(),
+        )?;
+
+        let updated = state.db.get_run(&run_id)?;
+
+        if matches!(status, RunStatus::Finished | RunStatus::Error | RunStatus::Aborted) {
+            if let Ok(ticket) = state.db.get_ticket(&existing.ticket_id) {
+                if ticket.locked_by_run_id.as_ref() == Some(&run_id) {
+                    state.db.unlock_ticket(&existing.ticket_id, &Actor::run(&run_id))?;
+                    state.broadcast(LiveEvent::TicketUnlocked {
+                        ticket_id: existing.ticket_id.clone(),
+                    });
+                }
             }
-        }
 
-        state.broadcast(LiveEvent::RunCompleted {
-            run_id: run_id.clone(),
-            ticket_id: existing.ticket_id,
-            status: status.as_str().to_string(),
-            exit_code: req.exit_code,
-        });
-    } else {
-        state.broadcast(LiveEvent::RunUpdated {
-            run_id,
-            status: status.as_str().to_string(),
-        });
-    }
+            state.broadcast(LiveEvent::RunCompleted {
+                run_id: run_id.clone(),
+                ticket_id: existing.ticket_id,
+                status: status.as_str().to_string(),
+                exit_code: req.exit_code,
+            });
+        } else {
+            state.broadcast(LiveEvent::RunUpdated {
+                run_id,
+                status: status.as_str().to_string(),
+            });
+        }
 
-    Ok(Json(updated))
+        Ok(Json(updated))
+    })
+    .await
 }
 
 pub async fn heartbeat(
     State(state): State<AppState>,
     Path(run_id): Path<String>,
 ) -> ApiResult<Json<HeartbeatResponse>> {
-    let run = state.db.get_run(&run_id)?;
+    let new_expiry = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
+    let id = run_id.clone();
+    let run = state.db
+        .blocking(move |db| {
+            let run = db.get_run(&id)?;
+            if matches!(run.status, RunStatus::Queued | RunStatus::Running) {
+                db.extend_lock(&run.ticket_id, &id, new_expiry)?;
+            }
+            Ok(run)
+        })
+        .await?;
 
     if !matches!(run.status, RunStatus::Queued | RunStatus::Running) {
         return Err(AppError::conflict(format!(
@@ -416,9 +909,6 @@ pub async fn heartbeat(
         )));
     }
 
-    let new_expiry = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
-    state.db.extend_lock(&run.ticket_id, &run_id, new_expiry)?;
-
     tracing::debug!("Heartbeat received for run {}, lock extended to {}", run_id, new_expiry);
 
     Ok(Json(HeartbeatResponse {
@@ -432,248 +922,1470 @@ pub async fn release_run(
     State(state): State<AppState>,
     Path(run_id): Path<String>,
 ) -> ApiResult<Json<AgentRun>> {
-    let run = state.db.get_run(&run_id)?;
+    blocking(&state, move |state| {
+        let run = state.db.get_run(&run_id)?;
 
-    state.db.update_run_status(&run_id, RunStatus::Aborted, None, None)?;
+        state.db.update_run_status(&run_id, RunStatus::Aborted, None, None)?;
 
-    if let Ok(ticket) = state.db.get_ticket(&run.ticket_id) {
-        if ticket.locked_by_run_id.as_ref() == Some(&run_id) {
-            state.db.unlock_ticket(&run.ticket_id)?;
-            state.broadcast(LiveEvent::TicketUnlocked {
-                ticket_id: run.ticket_id.clone(),
-            });
+        if let Ok(ticket) = state.db.get_ticket(&run.ticket_id) {
+            if ticket.locked_by_run_id.as_ref() == Some(&run_id) {
+                state.db.unlock_ticket(&run.ticket_id, &Actor::run(&run_id))?;
+                state.broadcast(LiveEvent::TicketUnlocked {
+                    ticket_id: run.ticket_id.clone(),
+                });
+            }
         }
-    }
 
-    let updated = state.db.get_run(&run_id)?;
+        let updated = state.db.get_run(&run_id)?;
 
-    state.broadcast(LiveEvent::RunCompleted {
-        run_id: run_id.clone(),
-        ticket_id: run.ticket_id,
-        status: "aborted".to_string(),
-        exit_code: None,
-    });
+        state.broadcast(LiveEvent::RunCompleted {
+            run_id: run_id.clone(),
+            ticket_id: run.ticket_id,
+            status: "aborted".to_string(),
+            exit_code: None,
+        });
 
-    Ok(Json(updated))
+        Ok(Json(updated))
+    })
+    .await
 }
 
-pub async fn create_event(
+/// Workflows interrupted by a restart that can resume where they stopped
+pub async fn list_interrupted_runs(State(state): State<AppState>) -> ApiResult<Json<Vec<WorkflowCheckpoint>>> {
+    blocking(&state, move |state| {
+        let checkpoints = state.db.get_checkpoints(CheckpointState::Interrupted)?;
+        Ok(Json(checkpoints))
+    })
+    .await
+}
+
+/// Pause a running workflow once its current stage completes
+pub async fn pause_run(
     State(state): State<AppState>,
     Path(run_id): Path<String>,
-    Json(req): Json<CreateEventRequest>,
-) -> ApiResult<(StatusCode, Json<AgentEvent>)> {
-    let run = state.db.get_run(&run_id)?;
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        let checkpoint = state.db.request_pause(&run_id)?;
+        tracing::info!("Run {} will pause after its current stage", run_id);
+        Ok(Json(checkpoint))
+    })
+    .await
+}
 
-    let normalized = NormalizedEvent {
-        run_id: run_id.clone(),
-        ticket_id: run.ticket_id.clone(),
-        agent_type: run.agent_type,
-        event_type: EventType::parse(&req.event_type),
-        payload: AgentEventPayload {
-            raw: None,
-            structured: Some(req.payload),
-        },
-        timestamp: req.timestamp,
-    };
+/// Replace the plan a paused or stopped workflow continues with
+pub async fn update_run_plan(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+    Json(req): Json<UpdatePlanRequest>,
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        Ok(Json(state.db.set_checkpoint_plan(&run_id, &req.plan_md)?))
+    })
+    .await
+}
 
-    let event = state.db.create_event(&normalized)?;
+/// Resume a paused or interrupted workflow from its first incomplete stage;
+/// the stages run in the background under the same run
+pub async fn resume_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        restart_run(state, run_id, Restart::Resume)
+    })
+    .await
+}
 
-    state.broadcast(LiveEvent::EventReceived {
-        run_id,
-        event_id: event.id.clone(),
-        event_type: req.event_type,
-    });
+pub async fn get_run_checkpoint(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        let checkpoint = state.db.get_checkpoint(&run_id)?.ok_or_else(|| AppError::not_found("Checkpoint"))?;
+        Ok(Json(checkpoint))
+    })
+    .await
+}
 
-    Ok((StatusCode::CREATED, Json(event)))
+/// Run a stopped workflow again from a stage, reusing its worktree, branch and plan
+pub async fn rerun_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+    Json(req): Json<RerunRequest>,
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        restart_run(state, run_id, Restart::From(req.stage))
+    })
+    .await
 }
 
-pub async fn list_events(
+/// Skip the stage a stopped workflow stopped at and continue with the next one
+pub async fn skip_run_stage(
     State(state): State<AppState>,
     Path(run_id): Path<String>,
-) -> ApiResult<Json<Vec<AgentEvent>>> {
-    state.db.get_run(&run_id)?;
-    let events = state.db.get_events(&run_id)?;
-    Ok(Json(events))
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        restart_run(state, run_id, Restart::Skip)
+    })
+    .await
 }
 
-pub async fn create_comment(
+/// Run a stage with a custom prompt in a stopped workflow, then continue it
+pub async fn insert_run_stage(
     State(state): State<AppState>,
-    Path(ticket_id): Path<String>,
-    Json(req): Json<CreateCommentRequest>,
-) -> ApiResult<(StatusCode, Json<Comment>)> {
-    state.db.get_ticket(&ticket_id)?;
+    Path(run_id): Path<String>,
+    Json(req): Json<InsertStageRequest>,
+) -> ApiResult<Json<WorkflowCheckpoint>> {
+    blocking(&state, move |state| {
+        let stage = AdhocStage {
+            name: req.name.unwrap_or_else(|| ADHOC_STAGE_NAME.to_string()),
+            prompt: req.prompt,
+        };
+        restart_run(state, run_id, Restart::Insert { stage, before: req.before })
+    })
+    .await
+}
 
-    if req.body_md.trim().is_empty() {
-        return Err(AppError::validation("Comment body cannot be empty"));
-    }
-
-    let author_type = match req.author_type.as_str() {
-        "user" => AuthorType::User,
-        "agent" => AuthorType::Agent,
-        "system" => AuthorType::System,
-        _ => return Err(AppError::validation("Invalid author_type")),
+fn restart_run(state: &AppState, run_id: String, restart: Restart) -> ApiResult<Json<WorkflowCheckpoint>> {
+    let config = |agent| api_worker_config(state, agent);
+    let checkpoint = match restart {
+        Restart::Resume => recovery::resume_workflow(&state.db, &run_id, config)?,
+        restart => recovery::restart_workflow(&state.db, &run_id, restart, config)?,
     };
+    tracing::info!("Restarted run {} at stage {}", run_id, checkpoint.next_stage);
 
-    let comment = state.db.create_comment(&CreateComment {
-        ticket_id: ticket_id.clone(),
-        author_type,
-        body_md: req.body_md,
-        metadata: req.metadata,
-    })?;
+    state.broadcast(LiveEvent::TicketLocked {
+        ticket_id: checkpoint.ticket_id.clone(),
+        run_id: run_id.clone(),
+    });
+    state.broadcast(LiveEvent::RunUpdated {
+        run_id,
+        status: RunStatus::Running.as_str().to_string(),
+    });
+    Ok(Json(checkpoint))
+}
 
-    state.broadcast(LiveEvent::CommentAdded {
-        ticket_id,
-        comment_id: comment.id.clone(),
+pub async fn create_event(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+    Json(req): Json<CreateEventRequest>,
+) -> ApiResult<(StatusCode, Json<AgentEvent>)> {
+    let id = run_id.clone();
+    let event_type = EventType::parse(&req.event_type);
+    let event = state.db
+        .blocking(move |db| {
+            let run = db.get_run(&id)?;
+            db.create_event(&NormalizedEvent {
+                run_id: id,
+                ticket_id: run.ticket_id,
+                agent_type: run.agent_type,
+                event_type,
+                payload: AgentEventPayload {
+                    raw: None,
+                    structured: Some(req.payload),
+                },
+                timestamp: req.timestamp,
+            })
+        })
+        .await?;
+
+    state.broadcast(LiveEvent::EventReceived {
+        run_id,
+        event_id: event.id.clone(),
+        event_type: req.event_type,
     });
 
-    Ok((StatusCode::CREATED, Json(comment)))
+    Ok((StatusCode::CREATED, Json(event)))
+}
+
+pub async fn list_events(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+) -> ApiResult<Json<Vec<AgentEvent>>> {
+    let events = state.db
+        .blocking(move |db| {
+            db.get_run(&run_id)?;
+            db.get_events(&run_id)
+        })
+        .await?;
+    Ok(Json(events))
+}
+
+pub async fn list_run_artifacts(
+    State(state): State<AppState>,
+    Path(run_id): Path<String>,
+) -> ApiResult<Json<Vec<RunArtifact>>> {
+    let artifacts = state.db
+        .blocking(move |db| {
+            db.get_run(&run_id)?;
+            db.list_run_artifacts(&run_id)
+        })
+        .await?;
+    Ok(Json(artifacts))
+}
+
+pub async fn create_comment(
+    State(state): State<AppState>,
+    Path(ticket_id): Path<String>,
+    Json(req): Json<CreateCommentRequest>,
+) -> ApiResult<(StatusCode, Json<Comment>)> {
+    blocking(&state, move |state| {
+        state.db.get_ticket(&ticket_id)?;
+
+        if req.body_md.trim().is_empty() {
+            return Err(AppError::validation("Comment body cannot be empty"));
+        }
+
+        let author_type = match req.author_type.as_str() {
+            "user" => AuthorType::User,
+            "agent" => AuthorType::Agent,
+            "system" => AuthorType::System,
+            _ => return Err(AppError::validation("Invalid author_type")),
+        };
+
+        let comment = state.db.create_comment(&CreateComment {
+            ticket_id: ticket_id.clone(),
+            author_type,
+            body_md: req.body_md,
+            metadata: req.metadata,
+        })?;
+
+        state.broadcast(LiveEvent::CommentAdded {
+            ticket_id,
+            comment_id: comment.id.clone(),
+        });
+
+        Ok((StatusCode::CREATED, Json(comment)))
+    })
+    .await
 }
 
 pub async fn list_comments(
     State(state): State<AppState>,
     Path(ticket_id): Path<String>,
 ) -> ApiResult<Json<Vec<Comment>>> {
-    state.db.get_ticket(&ticket_id)?;
-    let comments = state.db.get_comments(&ticket_id)?;
-    Ok(Json(comments))
+    blocking(&state, move |state| {
+        state.db.get_ticket(&ticket_id)?;
+        let comments = state.db.get_comments(&ticket_id)?;
+        Ok(Json(comments))
+    })
+    .await
 }
 
 pub async fn queue_next(
     State(state): State<AppState>,
     Json(req): Json<QueueNextRequest>,
 ) -> ApiResult<Json<QueueNextResponse>> {
-    let boards = match &req.board_id {
-        Some(id) => {
-            let board = state.db.get_board(id)?
-                .ok_or_else(|| AppError::not_found("Board"))?;
-            vec![board]
-        }
-        None => state.db.get_boards()?,
-    };
+    blocking(&state, move |state| {
+        let boards = match &req.board_id {
+            Some(id) => {
+                let board = state.db.get_board(id)?
+                    .ok_or_else(|| AppError::not_found("Board"))?;
+                vec![board]
+            }
+            None => state.db.get_boards()?,
+        };
 
-    for board in boards {
-        let columns = state.db.get_columns(&board.id)?;
+        for board in boards {
+            let columns = state.db.get_columns(&board.id)?;
 
-        let ready_column = match columns.iter().find(|c| c.name == "Ready") {
-            Some(c) => c,
-            None => continue,
-        };
+            let ready_column = match columns.iter().find(|c| c.role == Some(TicketState::Ready)) {
+                Some(c) => c,
+                None => continue,
+            };
 
-        let tickets = state.db.get_tickets(&board.id, Some(&ready_column.id))?;
+            let tickets = state.db.get_tickets(&board.id, Some(&ready_column.id))?;
 
-        for ticket in tickets {
-            if let Some(ref lock_expires) = ticket.lock_expires_at {
-                if *lock_expires > Utc::now() {
-                    continue;
+            for ticket in tickets {
+                if let Some(ref lock_expires) = ticket.lock_expires_at {
+                    if *lock_expires > Utc::now() {
+                        continue;
+                    }
                 }
-            }
 
-            if let Some(ref filter_path) = req.repo_path {
-                // Look up project by filesystem path to get its ID
-                let project = state.db.get_project_by_path(filter_path)?;
-                let project_id = project.map(|p| p.id);
-                if ticket.project_id != project_id {
-                    continue;
+                if let Some(ref filter_path) = req.repo_path {
+                    // Look up project by filesystem path to get its ID
+                    let project = state.db.get_project_by_path(filter_path)?;
+                    let project_id = project.map(|p| p.id);
+                    if ticket.project_id != project_id {
+                        continue;
+                    }
+                }
+
+                if let Some(ref pref) = ticket.agent_pref {
+                    use crate::db::AgentPref;
+                    match pref {
+                        AgentPref::Cursor if req.agent_type != crate::db::AgentType::Cursor => continue,
+                        AgentPref::Claude if req.agent_type != crate::db::AgentType::Claude => continue,
+                        _ => {}
+                    }
                 }
-            }
 
-            if let Some(ref pref) = ticket.agent_pref {
-                use crate::db::AgentPref;
-                match pref {
-                    AgentPref::Cursor if req.agent_type != crate::db::AgentType::Cursor => continue,
-                    AgentPref::Claude if req.agent_type != crate::db::AgentType::Claude => continue,
-                    _ => {}
+                // Use provided repo_path, or fall back to the ticket's project path
+                let repo_path = req.repo_path.clone().or_else(|| {
+                    ticket.project_id.as_ref()
+                        .and_then(|pid| state.db.get_project(pid).ok().flatten())
+                        .map(|p| p.path)
+                }).ok_or_else(|| AppError::validation(
+                    "repo_path is required when ticket has no associated project"
+                ))?;
+
+                let run = state.db.create_run(&CreateRun {
+                    ticket_id: ticket.id.clone(),
+                    agent_type: req.agent_type,
+                    repo_path,
+                    parent_run_id: None,
+                    stage: None,
+                })?;
+
+                let lock_expires_at = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
+                state.db.lock_ticket(&ticket.id, &run.id, lock_expires_at)?;
+
+                if let Some(in_progress) = columns.iter().find(|c| c.role == Some(TicketState::InProgress)) {
+                    let _ = state.db.move_ticket(&ticket.id, &in_progress.id, &Actor::run(&run.id));
                 }
+
+                state.broadcast(LiveEvent::TicketLocked {
+                    ticket_id: ticket.id.clone(),
+                    run_id: run.id.clone(),
+                });
+
+                state.broadcast(LiveEvent::RunStarted {
+                    run_id: run.id.clone(),
+                    ticket_id: ticket.id.clone(),
+                    agent_type: req.agent_type.as_str().to_string(),
+                });
+
+                return Ok(Json(QueueNextResponse {
+                    ticket,
+                    run_id: run.id,
+                    lock_expires_at,
+                    heartbeat_interval_secs: HEARTBEAT_INTERVAL_SECS,
+                }));
             }
+        }
 
-            // Use provided repo_path, or fall back to the ticket's project path
-            let repo_path = req.repo_path.clone().or_else(|| {
-                ticket.project_id.as_ref()
-                    .and_then(|pid| state.db.get_project(pid).ok().flatten())
-                    .map(|p| p.path)
-            }).ok_or_else(|| AppError::validation(
-                "repo_path is required when ticket has no associated project"
-            ))?;
-
-            let run = state.db.create_run(&CreateRun {
-                ticket_id: ticket.id.clone(),
-                agent_type: req.agent_type,
-                repo_path,
-                parent_run_id: None,
-                stage: None,
-            })?;
+        Err(AppError::queue_empty())
+    })
+    .await
+}
+
+pub async fn queue_status(
+    State(state): State<AppState>,
+) -> ApiResult<Json<QueueStatusResponse>> {
+    blocking(&state, move |state| {
+        let boards = state.db.get_boards()?;
+        let mut total_ready = 0;
+        let mut total_in_progress = 0;
+        let mut board_statuses = Vec::new();
+
+        for board in &boards {
+            let columns = state.db.get_columns(&board.id)?;
+
+            let ready_count = if let Some(ready_col) = columns.iter().find(|c| c.role == Some(TicketState::Ready)) {
+                let tickets = state.db.get_tickets(&board.id, Some(&ready_col.id))?;
+                tickets.iter().filter(|t| {
+                    t.lock_expires_at.is_none_or(|exp| exp <= Utc::now())
+                }).count()
+            } else {
+                0
+            };
+
+            let in_progress_count = if let Some(ip_col) = columns.iter().find(|c| c.role == Some(TicketState::InProgress)) {
+                state.db.get_tickets(&board.id, Some(&ip_col.id))?.len()
+            } else {
+                0
+            };
+
+            total_ready += ready_count;
+            total_in_progress += in_progress_count;
+
+            if ready_count > 0 {
+                board_statuses.push(BoardQueueStatus {
+                    board_id: board.id.clone(),
+                    board_name: board.name.clone(),
+                    ready_count,
+                });
+            }
+        }
+
+        Ok(Json(QueueStatusResponse {
+            ready_count: total_ready,
+            in_progress_count: total_in_progress,
+            boards: board_statuses,
+        }))
+    })
+    .await
+}
+
+/// Rank the ready tickets the way the scheduling policy would for a worker
+/// with the given filters, with the reason behind each position
+pub async fn explain_queue(
+    State(state): State<AppState>,
+    Query(query): Query<QueueExplainQuery>,
+) -> ApiResult<Json<QueueExplanation>> {
+    let split = |labels: Option<String>| -> Vec<String> {
+        labels
+            .map(|l| l.split(',').map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    let filter = QueueFilter {
+        project_ids: query.project_id.into_iter().collect(),
+        agent_type: query.agent_type,
+        labels: LabelFilter {
+            include: split(query.include),
+            exclude: split(query.exclude),
+        },
+    };
+    let limit = query.limit.unwrap_or(20);
+    Ok(Json(state.db.blocking(move |db| db.explain_queue(&filter, limit)).await?))
+}
 
-            let lock_expires_at = Utc::now() + Duration::minutes(LOCK_DURATION_MINUTES);
-            state.db.lock_ticket(&ticket.id, &run.id, lock_expires_at)?;
+pub async fn get_scheduling_policy(
+    State(state): State<AppState>,
+) -> ApiResult<Json<SchedulingPolicy>> {
+    blocking(&state, move |state| {
+        Ok(Json(state.db.get_scheduling_policy()?))
+    })
+    .await
+}
+
+/// Choose how workers order the ready queue; applies to the next reservation
+pub async fn set_scheduling_policy(
+    State(state): State<AppState>,
+    Json(policy): Json<SchedulingPolicy>,
+) -> ApiResult<Json<SchedulingPolicy>> {
+    blocking(&state, move |state| {
+        let policy = state.db.set_scheduling_policy(&policy)?;
+        tracing::info!("Scheduling policy set to {:?}", policy);
+        Ok(Json(policy))
+    })
+    .await
+}
+
+/// Concurrency limits with the slots in use and the runs waiting for one
+pub async fn get_concurrency() -> ApiResult<Json<ConcurrencyStatus>> {
+    Ok(Json(SLOTS.status()))
+}
+
+/// Save new concurrency limits; waiting runs are re-checked right away
+pub async fn set_concurrency_limits(
+    State(state): State<AppState>,
+    Json(limits): Json<ConcurrencyLimits>,
+) -> ApiResult<Json<ConcurrencyStatus>> {
+    blocking(&state, move |state| {
+        let limits = state.db.set_concurrency_limits(&limits)?;
+        tracing::info!("Concurrency limits set to {:?}", limits);
+        SLOTS.set_limits(limits);
+        Ok(Json(SLOTS.status()))
+    })
+    .await
+}
+
+pub async fn get_recovery_settings(State(state): State<AppState>) -> ApiResult<Json<RecoverySettings>> {
+    blocking(&state, move |state| {
+        Ok(Json(state.db.get_recovery_settings()?))
+    })
+    .await
+}
+
+/// Choose whether interrupted workflows resume automatically at launch
+pub async fn set_recovery_settings(
+    State(state): State<AppState>,
+    Json(settings): Json<RecoverySettings>,
+) -> ApiResult<Json<RecoverySettings>> {
+    blocking(&state, move |state| {
+        let settings = state.db.set_recovery_settings(&settings)?;
+        tracing::info!("Recovery settings set to {:?}", settings);
+        Ok(Json(settings))
+    })
+    .await
+}
+
+// ===== Projects =====
+
+pub async fn list_projects(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<Project>>> {
+    blocking(&state, move |state| {
+        let projects = state.db.get_projects()?;
+        Ok(Json(projects))
+    })
+    .await
+}
 
-            if let Some(in_progress) = columns.iter().find(|c| c.name == "In Progress") {
-                let _ = state.db.move_ticket(&ticket.id, &in_progress.id);
+pub async fn get_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> ApiResult<Json<Project>> {
+    blocking(&state, move |state| {
+        let project = state.db.get_project(&project_id)?
+            .ok_or_else(|| AppError::not_found("Project"))?;
+        Ok(Json(project))
+    })
+    .await
+}
+
+pub async fn create_project(
+    State(state): State<AppState>,
+    Json(req): Json<CreateProject>,
+) -> ApiResult<(StatusCode, Json<Project>)> {
+    blocking(&state, move |state| {
+        if req.name.trim().is_empty() {
+            return Err(AppError::validation("Project name cannot be empty"));
+        }
+
+        let project = state.db.create_project(&req)?;
+        Ok((StatusCode::CREATED, Json(project)))
+    })
+    .await
+}
+
+pub async fn update_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    Json(req): Json<UpdateProject>,
+) -> ApiResult<Json<Project>> {
+    blocking(&state, move |state| {
+        state.db.get_project(&project_id)?
+            .ok_or_else(|| AppError::not_found("Project"))?;
+
+        if let Some(ref name) = req.name {
+            if name.trim().is_empty() {
+                return Err(AppError::validation("Project name cannot be empty"));
             }
+        }
 
-            state.broadcast(LiveEvent::TicketLocked {
-                ticket_id: ticket.id.clone(),
-                run_id: run.id.clone(),
-            });
+        state.db.update_project(&project_id, &req)?;
+        let project = state.db.get_project(&project_id)?
+            .ok_or_else(|| AppError::not_found("Project"))?;
+        Ok(Json(project))
+    })
+    .await
+}
 
-            state.broadcast(LiveEvent::RunStarted {
-                run_id: run.id.clone(),
-                ticket_id: ticket.id.clone(),
-                agent_type: req.agent_type.as_str().to_string(),
-            });
+pub async fn delete_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        state.db.get_project(&project_id)?
+            .ok_or_else(|| AppError::not_found("Project"))?;
+        state.db.delete_project(&project_id)?;
+
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: project_id,
+            trash_id: None,
+        }))
+    })
+    .await
+}
+
+// ===== Scratchpads =====
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScratchpadQuery {
+    pub board_id: Option<String>,
+}
+
+pub async fn list_scratchpads(
+    State(state): State<AppState>,
+    Query(query): Query<ScratchpadQuery>,
+) -> ApiResult<Json<Vec<Scratchpad>>> {
+    blocking(&state, move |state| {
+        let scratchpads = match query.board_id {
+            Some(board_id) => state.db.get_scratchpads(&board_id)?,
+            None => state.db.get_all_scratchpads()?,
+        };
+        Ok(Json(scratchpads))
+    })
+    .await
+}
 
-            return Ok(Json(QueueNextResponse {
-                ticket,
-                run_id: run.id,
-                lock_expires_at,
-                heartbeat_interval_secs: HEARTBEAT_INTERVAL_SECS,
-            }));
+pub async fn get_scratchpad(
+    State(state): State<AppState>,
+    Path(scratchpad_id): Path<String>,
+) -> ApiResult<Json<Scratchpad>> {
+    blocking(&state, move |state| {
+        let scratchpad = state.db.get_scratchpad(&scratchpad_id)?;
+        Ok(Json(scratchpad))
+    })
+    .await
+}
+
+pub async fn create_scratchpad(
+    State(state): State<AppState>,
+    Json(req): Json<CreateScratchpadRequest>,
+) -> ApiResult<(StatusCode, Json<Scratchpad>)> {
+    blocking(&state, move |state| {
+        state.db.get_board(&req.board_id)?
+            .ok_or_else(|| AppError::not_found("Board"))?;
+        if let Some(ref target_board_id) = req.target_board_id {
+            state.db.get_board(target_board_id)?
+                .ok_or_else(|| AppError::not_found("Target board"))?;
         }
-    }
+        state.db.get_project(&req.project_id)?
+            .ok_or_else(|| AppError::not_found("Project"))?;
 
-    Err(AppError::queue_empty())
+        if req.name.trim().is_empty() {
+            return Err(AppError::validation("Name cannot be empty"));
+        }
+
+        let scratchpad = state.db.create_scratchpad(&CreateScratchpad {
+            board_id: req.board_id.clone(),
+            target_board_id: req.target_board_id,
+            project_id: req.project_id,
+            name: req.name,
+            user_input: req.user_input,
+            agent_pref: req.agent_pref,
+            model: req.model,
+            settings: serde_json::json!({}),
+        })?;
+
+        state.broadcast(LiveEvent::ScratchpadCreated {
+            scratchpad_id: scratchpad.id.clone(),
+            board_id: req.board_id,
+        });
+
+        Ok((StatusCode::CREATED, Json(scratchpad)))
+    })
+    .await
 }
 
-pub async fn queue_status(
+/// Move a scratchpad to the trash, or delete it for good with
+/// `?permanent=true`; `withTickets=true` includes the tickets it created
+pub async fn delete_scratchpad(
     State(state): State<AppState>,
-) -> ApiResult<Json<QueueStatusResponse>> {
-    let boards = state.db.get_boards()?;
-    let mut total_ready = 0;
-    let mut total_in_progress = 0;
-    let mut board_statuses = Vec::new();
-
-    for board in &boards {
-        let columns = state.db.get_columns(&board.id)?;
-
-        let ready_count = if let Some(ready_col) = columns.iter().find(|c| c.name == "Ready") {
-            let tickets = state.db.get_tickets(&board.id, Some(&ready_col.id))?;
-            tickets.iter().filter(|t| {
-                t.lock_expires_at.is_none_or(|exp| exp <= Utc::now())
-            }).count()
-        } else {
-            0
+    Path(scratchpad_id): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        let scratchpad = state.db.get_scratchpad(&scratchpad_id)?;
+        let trash_id = match (query.permanent, query.with_tickets) {
+            (true, true) => {
+                state.db.delete_scratchpad_with_tickets(&scratchpad_id)?;
+                None
+            }
+            (true, false) => {
+                state.db.delete_scratchpad(&scratchpad_id)?;
+                None
+            }
+            (false, with_tickets) => Some(state.db.trash_scratchpad(&scratchpad_id, with_tickets)?.id),
         };
 
-        let in_progress_count = if let Some(ip_col) = columns.iter().find(|c| c.name == "In Progress") {
-            state.db.get_tickets(&board.id, Some(&ip_col.id))?.len()
-        } else {
-            0
+        state.broadcast(LiveEvent::ScratchpadDeleted {
+            scratchpad_id: scratchpad_id.clone(),
+            board_id: scratchpad.board_id,
+        });
+
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: scratchpad_id,
+            trash_id,
+        }))
+    })
+    .await
+}
+
+fn planner_config(
+    state: &AppState,
+    scratchpad_id: &str,
+    project: &Project,
+    agent_kind: AgentKind,
+) -> PlannerConfig {
+    PlannerConfig {
+        scratchpad_id: scratchpad_id.to_string(),
+        max_explorations: 10,
+        auto_approve: false,
+        model: None,
+        agent_kind,
+        repo_path: PathBuf::from(&project.path),
+        api_url: state.api_url.clone(),
+        api_token: state.api_token.clone(),
+        claude_api_config: (agent_kind == AgentKind::Claude)
+            .then(|| ClaudeApiConfig::from(state.claude_settings.get())),
+    }
+}
+
+/// Look up a scratchpad together with the project it plans for
+async fn scratchpad_project(
+    state: &AppState,
+    scratchpad_id: &str,
+) -> ApiResult<(Scratchpad, Project)> {
+    let scratchpad_id = scratchpad_id.to_string();
+    blocking(state, move |state| {
+        let scratchpad = state.db.get_scratchpad(&scratchpad_id)?;
+        let project = state.db.get_project(&scratchpad.project_id)?
+            .ok_or_else(|| AppError::not_found("Project"))?;
+        Ok((scratchpad, project))
+    })
+    .await
+}
+
+/// Start the planner in the background. Progress is reported through
+/// scratchpad events on the SSE stream.
+pub async fn start_planner(
+    State(state): State<AppState>,
+    Path(scratchpad_id): Path<String>,
+    req: Option<Json<StartPlannerRequest>>,
+) -> ApiResult<(StatusCode, Json<PlannerStartedResponse>)> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+
+    let (scratchpad, project) = scratchpad_project(&state, &scratchpad_id).await?;
+
+    let agent_kind = req.agent_kind.unwrap_or(match scratchpad.agent_pref.as_deref() {
+        Some("cursor") => AgentKind::Cursor,
+        _ => AgentKind::Claude,
+    });
+
+    let config = PlannerConfig {
+        max_explorations: req.max_explorations.unwrap_or(10),
+        auto_approve: req.auto_approve.unwrap_or(false),
+        model: req.model.or(scratchpad.model),
+        ..planner_config(&state, &scratchpad_id, &project, agent_kind)
+    };
+
+    let agent = PlannerAgent::with_events(state.db.clone(), config, state.event_tx.clone());
+    let id = scratchpad_id.clone();
+    tokio::spawn(async move {
+        match agent.run().await {
+            Ok(result) => tracing::info!(
+                "Planner for scratchpad {} finished with status {:?}",
+                id, result.status
+            ),
+            Err(e) => tracing::error!("Planner for scratchpad {} failed: {}", id, e),
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(PlannerStartedResponse {
+        scratchpad_id,
+        agent_kind,
+    })))
+}
+
+pub async fn approve_plan(
+    State(state): State<AppState>,
+    Path(scratchpad_id): Path<String>,
+) -> ApiResult<Json<Scratchpad>> {
+    blocking(&state, move |state| {
+        let scratchpad = state.db.get_scratchpad(&scratchpad_id)?;
+
+        if scratchpad.status != ScratchpadStatus::AwaitingApproval {
+            return Err(AppError::conflict(format!(
+                "Cannot approve plan: scratchpad is in '{}' status, expected 'awaiting_approval'",
+                scratchpad.status.as_str()
+            )));
+        }
+
+        state.db.set_scratchpad_status(&scratchpad_id, ScratchpadStatus::Approved)?;
+        let updated = state.db.get_scratchpad(&scratchpad_id)?;
+
+        state.broadcast(LiveEvent::PlanApproved { scratchpad_id });
+
+        Ok(Json(updated))
+    })
+    .await
+}
+
+pub async fn execute_plan(
+    State(state): State<AppState>,
+    Path(scratchpad_id): Path<String>,
+) -> ApiResult<Json<ExecutePlanResponse>> {
+    let (_, project) = scratchpad_project(&state, &scratchpad_id).await?;
+
+    let config = PlannerConfig {
+        max_explorations: 0,
+        ..planner_config(&state, &scratchpad_id, &project, AgentKind::Claude)
+    };
+    let agent = PlannerAgent::with_events(state.db.clone(), config, state.event_tx.clone());
+    let result = agent.execute_plan().await?;
+
+    Ok(Json(ExecutePlanResponse {
+        scratchpad_id,
+        epic_ids: result.epic_ids,
+        ticket_ids: result.ticket_ids,
+    }))
+}
+
+// ===== Tasks =====
+
+pub async fn list_tasks(
+    State(state): State<AppState>,
+    Path(ticket_id): Path<String>,
+) -> ApiResult<Json<Vec<Task>>> {
+    blocking(&state, move |state| {
+        state.db.get_ticket(&ticket_id)?;
+        let tasks = state.db.get_tasks_for_ticket(&ticket_id)?;
+        Ok(Json(tasks))
+    })
+    .await
+}
+
+/// Add a custom or preset task. A ticket sitting in Done/Review is moved
+/// back to Ready so a worker picks the new task up.
+pub async fn create_task(
+    State(state): State<AppState>,
+    Path(ticket_id): Path<String>,
+    Json(req): Json<CreateTaskRequest>,
+) -> ApiResult<(StatusCode, Json<Task>)> {
+    blocking(&state, move |state| {
+        state.db.get_ticket(&ticket_id)?;
+
+        let (task_type, title, content) = match req.preset_type.as_deref() {
+            Some(preset) => {
+                let task_type = TaskType::parse(preset)
+                    .filter(|t| *t != TaskType::Custom)
+                    .ok_or_else(|| AppError::validation(format!("Unknown preset type: {}", preset)))?;
+                // Preset tasks use their template content
+                let title = task_type.display_name().to_string();
+                (task_type, Some(title), None)
+            }
+            None => (TaskType::Custom, req.title, req.content),
         };
 
-        total_ready += ready_count;
-        total_in_progress += in_progress_count;
+        let task = state.db.create_task(&CreateTask {
+            ticket_id: ticket_id.clone(),
+            task_type,
+            title,
+            content,
+        })?;
+
+        if let Err(e) = move_to_ready_if_completed(&state.db, &ticket_id) {
+            tracing::warn!(
+                "Failed to move ticket {} back to Ready after creating task {}: {}",
+                ticket_id, task.id, e
+            );
+        }
 
-        if ready_count > 0 {
-            board_statuses.push(BoardQueueStatus {
-                board_id: board.id.clone(),
-                board_name: board.name.clone(),
-                ready_count,
-            });
+        state.broadcast(LiveEvent::TicketUpdated { ticket_id });
+
+        Ok((StatusCode::CREATED, Json(task)))
+    })
+    .await
+}
+
+pub async fn get_task(
+    State(state): State<AppState>,
+    Path(task_id): Path<String>,
+) -> ApiResult<Json<Task>> {
+    blocking(&state, move |state| {
+        let task = state.db.get_task(&task_id)?;
+        Ok(Json(task))
+    })
+    .await
+}
+
+pub async fn delete_task(
+    State(state): State<AppState>,
+    Path(task_id): Path<String>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        let task = state.db.get_task(&task_id)?;
+        state.db.delete_task(&task_id)?;
+
+        state.broadcast(LiveEvent::TicketUpdated {
+            ticket_id: task.ticket_id,
+        });
+
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: task_id,
+            trash_id: None,
+        }))
+    })
+    .await
+}
+
+pub async fn reset_task(
+    State(state): State<AppState>,
+    Path(task_id): Path<String>,
+) -> ApiResult<Json<Task>> {
+    blocking(&state, move |state| {
+        let task = state.db.reset_task(&task_id)?;
+
+        if let Err(e) = move_to_ready_if_completed(&state.db, &task.ticket_id) {
+            tracing::warn!(
+                "Failed to move ticket {} back to Ready after resetting task {}: {}",
+                task.ticket_id, task_id, e
+            );
         }
+
+        state.broadcast(LiveEvent::TicketUpdated {
+            ticket_id: task.ticket_id.clone(),
+        });
+
+        Ok(Json(task))
+    })
+    .await
+}
+
+// ===== Workers =====
+
+pub async fn list_workers() -> ApiResult<Json<Vec<WorkerStatus>>> {
+    Ok(Json(WORKER_MANAGER.get_all_status()))
+}
+
+/// Config of workers started through the API; events go to SSE subscribers
+fn api_worker_config(state: &AppState, agent_type: AgentKind) -> WorkerConfig {
+    WorkerConfig {
+        agent_type,
+        api_url: state.api_url.clone(),
+        api_token: state.api_token.clone(),
+        hook_script_path: state.hook_script_path.clone(),
+        event_sink: Some(Arc::new(BroadcastEventSink::new(state.event_tx.clone()))),
+        claude_api_config: (agent_type == AgentKind::Claude)
+            .then(|| ClaudeApiConfig::from(state.claude_settings.get())),
+        ..Default::default()
     }
+}
+
+/// Save a worker definition and start it (unless created disabled); it is
+/// started again whenever the app or server launches
+pub async fn start_worker(
+    State(state): State<AppState>,
+    Json(req): Json<CreateWorkerDefinition>,
+) -> ApiResult<(StatusCode, Json<StartWorkerResponse>)> {
+    blocking(&state, move |state| {
+        let definition = state.db.create_worker_definition(&req)?;
+        WORKER_MANAGER.sync_saved_worker(&definition, &state.db, api_worker_config(state, definition.agent_type));
+        tracing::info!("Worker started via API: {}", definition.id);
+
+        Ok((StatusCode::CREATED, Json(StartWorkerResponse { worker_id: definition.id })))
+    })
+    .await
+}
+
+/// Stop a worker. Saved workers are disabled so they stay stopped after a restart.
+pub async fn stop_worker(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<Json<StopWorkerResponse>> {
+    blocking(&state, move |state| {
+        let stopped = WORKER_MANAGER.stop_worker(&worker_id);
+        let saved = state.db.get_worker_definition(&worker_id)?.is_some();
+        if saved {
+            state.db.update_worker_definition(&worker_id, &UpdateWorkerDefinition {
+                enabled: Some(false),
+                ..Default::default()
+            })?;
+        }
+        if !stopped && !saved {
+            return Err(AppError::not_found("Worker"));
+        }
+
+        Ok(Json(StopWorkerResponse {
+            stopped: true,
+            id: worker_id,
+        }))
+    })
+    .await
+}
+
+/// Keep a worker from taking new tickets; the current one is finished
+pub async fn pause_worker(Path(worker_id): Path<String>) -> ApiResult<Json<WorkerStatus>> {
+    WORKER_MANAGER
+        .pause_worker(&worker_id)
+        .map(Json)
+        .ok_or_else(|| AppError::not_found("Worker"))
+}
+
+pub async fn resume_worker(Path(worker_id): Path<String>) -> ApiResult<Json<WorkerStatus>> {
+    WORKER_MANAGER
+        .resume_worker(&worker_id)
+        .map(Json)
+        .ok_or_else(|| AppError::not_found("Worker"))
+}
+
+/// Let a worker finish its current ticket and exit; like stopping, a saved
+/// worker is disabled
+pub async fn drain_worker(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<Json<WorkerStatus>> {
+    blocking(&state, move |state| {
+        let status = WORKER_MANAGER
+            .drain_worker(&worker_id)
+            .ok_or_else(|| AppError::not_found("Worker"))?;
+        if state.db.get_worker_definition(&worker_id)?.is_some() {
+            state.db.update_worker_definition(&worker_id, &UpdateWorkerDefinition {
+                enabled: Some(false),
+                ..Default::default()
+            })?;
+        }
+        Ok(Json(status))
+    })
+    .await
+}
+
+pub async fn list_worker_definitions(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<WorkerDefinition>>> {
+    blocking(&state, move |state| {
+        Ok(Json(state.db.get_worker_definitions()?))
+    })
+    .await
+}
+
+/// Change a saved worker; a running one restarts with the new settings
+pub async fn update_worker_definition(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+    Json(req): Json<UpdateWorkerDefinition>,
+) -> ApiResult<Json<WorkerDefinition>> {
+    blocking(&state, move |state| {
+        let definition = state.db.update_worker_definition(&worker_id, &req)?;
+        WORKER_MANAGER.sync_saved_worker(&definition, &state.db, api_worker_config(state, definition.agent_type));
+        Ok(Json(definition))
+    })
+    .await
+}
+
+pub async fn delete_worker_definition(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<Json<DeleteResponse>> {
+    blocking(&state, move |state| {
+        state.db.delete_worker_definition(&worker_id)?;
+        WORKER_MANAGER.stop_worker(&worker_id);
+        Ok(Json(DeleteResponse {
+            deleted: true,
+            id: worker_id,
+            trash_id: None,
+        }))
+    })
+    .await
+}
 
-    Ok(Json(QueueStatusResponse {
-        ready_count: total_ready,
-        in_progress_count: total_in_progress,
-        boards: board_statuses,
+pub async fn stop_all_workers() -> ApiResult<StatusCode> {
+    WORKER_MANAGER.stop_all().await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ===== Remote Workers =====
+
+pub async fn list_remote_workers(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<RemoteWorker>>> {
+    Ok(Json(state.db.blocking(|db| db.get_remote_workers()).await?))
+}
+
+/// Register a machine as a remote worker. Registering again with the same id
+/// updates its capabilities and gives back any tickets it still held.
+pub async fn register_remote_worker(
+    State(state): State<AppState>,
+    Json(req): Json<RegisterRemoteWorker>,
+) -> ApiResult<(StatusCode, Json<RemoteWorker>)> {
+    let worker = state.db.blocking(move |db| db.register_remote_worker(&req)).await?;
+    tracing::info!("Remote worker registered: {} ({})", worker.name, worker.id);
+    state.broadcast(LiveEvent::RemoteWorkersChanged { worker_id: worker.id.clone() });
+    Ok((StatusCode::CREATED, Json(worker)))
+}
+
+pub async fn delete_remote_worker(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<Json<DeleteResponse>> {
+    let id = worker_id.clone();
+    state.db.blocking(move |db| db.delete_remote_worker(&id)).await?;
+    state.broadcast(LiveEvent::RemoteWorkersChanged { worker_id: worker_id.clone() });
+    Ok(Json(DeleteResponse {
+        deleted: true,
+        id: worker_id,
+        trash_id: None,
     }))
 }
+
+pub async fn remote_worker_heartbeat(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<Json<RemoteWorker>> {
+    Ok(Json(state.db.blocking(move |db| db.touch_remote_worker(&worker_id)).await?))
+}
+
+/// Lease the next ticket the worker can take; 204 when there is none
+pub async fn lease_ticket(
+    State(state): State<AppState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<axum::response::Response> {
+    let id = worker_id.clone();
+    let grant = state.db
+        .blocking(move |db| db.lease_next_ticket(&id, Duration::seconds(REMOTE_LEASE_SECS)))
+        .await?;
+    let Some(grant) = grant else {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    };
+
+    state.broadcast(LiveEvent::TicketLocked {
+        ticket_id: grant.ticket.id.clone(),
+        run_id: grant.run.id.clone(),
+    });
+    state.broadcast(LiveEvent::RunStarted {
+        run_id: grant.run.id.clone(),
+        ticket_id: grant.ticket.id.clone(),
+        agent_type: grant.run.agent_type.as_str().to_string(),
+    });
+    state.broadcast(LiveEvent::RemoteWorkersChanged { worker_id });
+    Ok(Json(grant).into_response())
+}
+
+/// Extend a lease; 409 once the lease was lost to a newer one
+pub async fn renew_lease(
+    State(state): State<AppState>,
+    Path((worker_id, token)): Path<(String, i64)>,
+) -> ApiResult<Json<TicketLease>> {
+    let lease = state.db
+        .blocking(move |db| db.renew_lease(&worker_id, token, Duration::seconds(REMOTE_LEASE_SECS)))
+        .await?;
+    Ok(Json(lease))
+}
+
+/// Report how a lease ended, with the diff and any artifacts; 409 once the
+/// lease was lost to a newer one
+pub async fn submit_lease_result(
+    State(state): State<AppState>,
+    Path((worker_id, token)): Path<(String, i64)>,
+    Json(req): Json<LeaseResult>,
+) -> ApiResult<Json<LeaseResultResponse>> {
+    let id = worker_id.clone();
+    let (lease, run) = state.db
+        .blocking(move |db| db.complete_lease(&id, token, &req))
+        .await?;
+
+    state.broadcast(LiveEvent::RunCompleted {
+        run_id: run.id.clone(),
+        ticket_id: run.ticket_id.clone(),
+        status: run.status.as_str().to_string(),
+        exit_code: run.exit_code,
+    });
+    state.broadcast(LiveEvent::TicketUnlocked { ticket_id: lease.ticket_id.clone() });
+    state.broadcast(LiveEvent::TicketUpdated { ticket_id: lease.ticket_id.clone() });
+    state.broadcast(LiveEvent::RemoteWorkersChanged { worker_id });
+    Ok(Json(LeaseResultResponse { lease, run }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn test_state() -> AppState {
+        AppState::new(Arc::new(Database::open_in_memory().unwrap()), "token".to_string())
+    }
+
+    fn create_test_ticket(state: &AppState, column_name: &str) -> Ticket {
+        let board = state.db.create_board("Board").unwrap();
+        let column = state.db.find_column_by_name(&board.id, column_name).unwrap().unwrap();
+        state.db.create_ticket(&CreateTicket::for_test(&board.id, &column.id, "Ticket")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn project_crud_round_trip() {
+        let state = test_state();
+        let dir = std::env::temp_dir();
+
+        let (status, Json(project)) = create_project(
+            State(state.clone()),
+            Json(CreateProject {
+                name: "Repo".to_string(),
+                path: dir.to_string_lossy().to_string(),
+                preferred_agent: None,
+                requires_git: false,
+            }),
+        ).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let Json(updated) = update_project(
+            State(state.clone()),
+            Path(project.id.clone()),
+            Json(UpdateProject {
+                name: Some("Renamed".to_string()),
+                preferred_agent: None,
+                allow_shell_commands: None,
+                allow_file_writes: None,
+                blocked_patterns: None,
+                requires_git: None,
+                stage_timeouts: None,
+            }),
+        ).await.unwrap();
+        assert_eq!(updated.name, "Renamed");
+
+        let Json(deleted) = delete_project(State(state.clone()), Path(project.id.clone())).await.unwrap();
+        assert!(deleted.deleted);
+        let err = get_project(State(state), Path(project.id)).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn create_task_with_preset_moves_done_ticket_to_ready() {
+        let state = test_state();
+        let ticket = create_test_ticket(&state, "Done");
+
+        let (status, Json(task)) = create_task(
+            State(state.clone()),
+            Path(ticket.id.clone()),
+            Json(CreateTaskRequest {
+                title: None,
+                content: Some("ignored".to_string()),
+                preset_type: Some("add_tests".to_string()),
+            }),
+        ).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(task.task_type, TaskType::AddTests);
+        assert_eq!(task.title.as_deref(), Some("Add Tests"));
+
+        let ready = state.db.find_column_by_name(&ticket.board_id, "Ready").unwrap().unwrap();
+        assert_eq!(state.db.get_ticket(&ticket.id).unwrap().column_id, ready.id);
+    }
+
+    #[tokio::test]
+    async fn create_task_rejects_unknown_preset() {
+        let state = test_state();
+        let ticket = create_test_ticket(&state, "Backlog");
+
+        let err = create_task(
+            State(state),
+            Path(ticket.id),
+            Json(CreateTaskRequest {
+                title: None,
+                content: None,
+                preset_type: Some("custom".to_string()),
+            }),
+        ).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn approve_plan_requires_awaiting_approval() {
+        let state = test_state();
+        let board = state.db.create_board("Board").unwrap();
+        let project = state.db.create_project(&CreateProject {
+            name: "Repo".to_string(),
+            path: std::env::temp_dir().to_string_lossy().to_string(),
+            preferred_agent: None,
+            requires_git: false,
+        }).unwrap();
+
+        let (_, Json(scratchpad)) = create_scratchpad(
+            State(state.clone()),
+            Json(CreateScratchpadRequest {
+                board_id: board.id,
+                target_board_id: None,
+                project_id: project.id,
+                name: "Plan".to_string(),
+                user_input: "Build it".to_string(),
+                agent_pref: None,
+                model: None,
+            }),
+        ).await.unwrap();
+
+        let err = approve_plan(State(state.clone()), Path(scratchpad.id.clone())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+
+        state.db.set_scratchpad_status(&scratchpad.id, ScratchpadStatus::AwaitingApproval).unwrap();
+        let Json(approved) = approve_plan(State(state), Path(scratchpad.id)).await.unwrap();
+        assert_eq!(approved.status, ScratchpadStatus::Approved);
+    }
+
+    #[tokio::test]
+    async fn stop_unknown_worker_is_not_found() {
+        let err = stop_worker(State(test_state()), Path("missing".to_string())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn workers_are_saved_and_stopping_disables_them() {
+        let state = test_state();
+        let (status, Json(started)) = start_worker(
+            State(state.clone()),
+            Json(serde_json::from_value(serde_json::json!({ "agentType": "claude", "enabled": false })).unwrap()),
+        ).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(!WORKER_MANAGER.is_running(&started.worker_id));
+
+        let Json(definitions) = list_worker_definitions(State(state.clone())).await.unwrap();
+        assert_eq!(definitions.len(), 1);
+
+        let Json(enabled) = update_worker_definition(
+            State(state.clone()),
+            Path(started.worker_id.clone()),
+            Json(UpdateWorkerDefinition { enabled: Some(true), ..Default::default() }),
+        ).await.unwrap();
+        assert!(enabled.enabled);
+        assert!(WORKER_MANAGER.is_running(&started.worker_id));
+
+        let Json(stopped) = stop_worker(State(state.clone()), Path(started.worker_id.clone())).await.unwrap();
+        assert!(stopped.stopped);
+        assert!(!WORKER_MANAGER.is_running(&started.worker_id));
+        assert!(!state.db.get_worker_definition(&started.worker_id).unwrap().unwrap().enabled);
+    }
+
+    #[tokio::test]
+    async fn draining_a_saved_worker_disables_it() {
+        let state = test_state();
+        let (_, Json(started)) = start_worker(
+            State(state.clone()),
+            Json(serde_json::from_value(serde_json::json!({ "agentType": "cursor" })).unwrap()),
+        ).await.unwrap();
+
+        let Json(paused) = pause_worker(Path(started.worker_id.clone())).await.unwrap();
+        assert_eq!(paused.id, started.worker_id);
+
+        let Json(drained) = drain_worker(State(state.clone()), Path(started.worker_id.clone())).await.unwrap();
+        assert_eq!(drained.id, started.worker_id);
+        assert!(!state.db.get_worker_definition(&started.worker_id).unwrap().unwrap().enabled);
+
+        WORKER_MANAGER.stop_worker(&started.worker_id);
+        let err = resume_worker(Path(started.worker_id.clone())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn explain_queue_follows_the_policy() {
+        let state = test_state();
+        let ticket = create_test_ticket(&state, "Ready");
+
+        let Json(policy) = set_scheduling_policy(
+            State(state.clone()),
+            Json(serde_json::from_value(serde_json::json!({ "policy": "priority_aging" })).unwrap()),
+        ).await.unwrap();
+        assert_eq!(policy, SchedulingPolicy::PriorityAging { hours_per_level: 24.0 });
+
+        let Json(explanation) = explain_queue(
+            State(state.clone()),
+            Query(QueueExplainQuery { include: Some("frontend, api".to_string()), ..Default::default() }),
+        ).await.unwrap();
+        assert_eq!(explanation.eligible, 0);
+
+        let Json(explanation) = explain_queue(State(state), Query(QueueExplainQuery::default())).await.unwrap();
+        assert_eq!(explanation.next.unwrap().ticket_id, ticket.id);
+    }
+
+    #[tokio::test]
+    async fn concurrency_limits_are_validated_and_applied() {
+        let state = test_state();
+        let err = set_concurrency_limits(
+            State(state.clone()),
+            Json(serde_json::from_value(serde_json::json!({ "perAgent": { "gpt": 1 } })).unwrap()),
+        ).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+
+        // A model no test runs, so other tests sharing the slots are unaffected
+        let limits: ConcurrencyLimits =
+            serde_json::from_value(serde_json::json!({ "perModel": { "handler-test-model": 1 } })).unwrap();
+        let Json(status) = set_concurrency_limits(State(state.clone()), Json(limits.clone())).await.unwrap();
+        assert_eq!(status.limits, limits);
+        assert_eq!(state.db.get_concurrency_limits().unwrap(), limits);
+
+        SLOTS.set_limits(ConcurrencyLimits::default());
+    }
+
+    #[tokio::test]
+    async fn runs_without_checkpoint_cannot_be_resumed() {
+        let state = test_state();
+        let Json(interrupted) = list_interrupted_runs(State(state.clone())).await.unwrap();
+        assert!(interrupted.is_empty());
+
+        let err = resume_run(State(state.clone()), Path("missing".to_string())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = get_run_checkpoint(State(state.clone()), Path("missing".to_string())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = rerun_run(
+            State(state.clone()),
+            Path("missing".to_string()),
+            Json(RerunRequest { stage: 0 }),
+        ).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = skip_run_stage(State(state.clone()), Path("missing".to_string())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let err = pause_run(State(state.clone()), Path("missing".to_string())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+        let Json(settings) = set_recovery_settings(
+            State(state.clone()),
+            Json(serde_json::from_value(serde_json::json!({ "autoResume": true })).unwrap()),
+        ).await.unwrap();
+        assert!(settings.auto_resume);
+        let Json(settings) = get_recovery_settings(State(state)).await.unwrap();
+        assert!(settings.auto_resume);
+    }
+
+    #[tokio::test]
+    async fn remote_worker_without_work_gets_no_content() {
+        let state = test_state();
+        let project = state.db.create_project(&CreateProject {
+            name: "Repo".to_string(),
+            path: std::env::temp_dir().to_string_lossy().to_string(),
+            preferred_agent: None,
+            requires_git: false,
+        }).unwrap();
+
+        let (status, Json(worker)) = register_remote_worker(
+            State(state.clone()),
+            Json(serde_json::from_value(serde_json::json!({
+                "name": "build-box",
+                "agentKinds": ["claude"],
+                "projectIds": [project.id],
+            })).unwrap()),
+        ).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let response = lease_ticket(State(state.clone()), Path(worker.id.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let err = renew_lease(State(state), Path((worker.id, 42))).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn move_is_recorded_in_ticket_history() {
+        let state = test_state();
+        let ticket = create_test_ticket(&state, "Backlog");
+        let ready = state.db.find_column_by_name(&ticket.board_id, "Ready").unwrap().unwrap();
+
+        let Json(moved) = move_ticket(
+            State(state.clone()),
+            Path(ticket.id.clone()),
+            Json(MoveTicketRequest { column_id: ready.id.clone() }),
+        ).await.unwrap();
+        assert_eq!(moved.column_id, ready.id);
+
+        let Json(history) = get_ticket_history(State(state.clone()), Path(ticket.id.clone())).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].field, "columnId");
+        assert_eq!(history[0].new_value.as_deref(), Some(ready.id.as_str()));
+        assert_eq!(history[0].actor, Actor::user());
+
+        let err = get_ticket_history(State(state), Path("missing".to_string())).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn exported_board_imports_as_a_new_board() {
+        let state = test_state();
+        let ticket = create_test_ticket(&state, "Ready");
+
+        let Json(bundle) = export_board(
+            State(state.clone()),
+            Path(ticket.board_id.clone()),
+            Query(ExportBoardQuery::default()),
+        ).await.unwrap();
+        let (status, Json(imported)) = import_board(
+            State(state.clone()),
+            Json(ImportBoardRequest { bundle, name: Some("Imported".to_string()) }),
+        ).await.unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(imported.board.name, "Imported");
+
+        let tickets = state.db.get_tickets(&imported.board.id, None).unwrap();
+        assert_eq!(tickets.len(), 1);
+        assert_ne!(tickets[0].id, ticket.id);
+        let column = state.db.get_column(&tickets[0].column_id).unwrap().unwrap();
+        assert_eq!((column.board_id.as_str(), column.name.as_str()), (imported.board.id.as_str(), "Ready"));
+
+        let err = export_board(State(state), Path("missing".to_string()), Query(ExportBoardQuery::default()))
+            .await.unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+}
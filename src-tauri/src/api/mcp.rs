@@ -0,0 +1,616 @@
+//! Model Context Protocol server.
+//!
+//! Exposes the board to agents as MCP tools over the Streamable HTTP
+//! transport (`POST /v1/mcp`, one JSON-RPC message per request, plain JSON
+//! responses). `bored mcp` bridges stdio clients to the same endpoint.
+//!
+//! A session is scoped to one ticket: the ticket of `run_id` when the query
+//! names a run (a different `ticket_id` is rejected), otherwise the
+//! `ticket_id` query parameter. Tools never touch other tickets except
+//! to read the ticket's epic siblings and to create follow-ups on its board.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::state::{AppState, LiveEvent};
+use crate::db::{
+    AuthorType, CreateComment, CreateTicket, DbError, Priority, TaskStatus, Ticket,
+};
+use crate::lifecycle::TicketState;
+
+/// Newest protocol revision we implement
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Name agents see the server registered under
+pub const SERVER_NAME: &str = "agent-kanban";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct McpScopeQuery {
+    pub ticket_id: Option<String>,
+    pub run_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcRequest {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// JSON-RPC level failure (as opposed to a tool reporting an error)
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+/// Why a tool call failed; reported to the agent with `isError: true`
+#[derive(Debug)]
+struct ToolError(String);
+
+impl From<DbError> for ToolError {
+    fn from(e: DbError) -> Self {
+        ToolError(e.to_string())
+    }
+}
+
+type ToolResult = Result<Value, ToolError>;
+
+pub async fn mcp_post(
+    State(state): State<AppState>,
+    Query(scope): Query<McpScopeQuery>,
+    body: String,
+) -> Response {
+    let request: JsonRpcRequest = match serde_json::from_str(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = RpcError::new(PARSE_ERROR, format!("Invalid JSON-RPC message: {}", e));
+            return Json(error_response(Value::Null, error)).into_response();
+        }
+    };
+
+    // Notifications and client responses need no answer
+    let Some(id) = request.id.clone() else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    // Tools hit SQLite, so run the request on the blocking pool
+    let db = state.db.clone();
+    let handled = db
+        .blocking(move |_| Ok(handle_request(&state, &scope, request)))
+        .await
+        .unwrap_or_else(|e| Err(RpcError::new(INTERNAL_ERROR, e.to_string())));
+    let response = match handled {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    };
+    Json(response).into_response()
+}
+
+/// We never push server-initiated messages, so there is no SSE stream to open
+pub async fn mcp_get() -> StatusCode {
+    StatusCode::METHOD_NOT_ALLOWED
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn handle_request(state: &AppState, scope: &McpScopeQuery, request: JsonRpcRequest) -> Result<Value, RpcError> {
+    if request.jsonrpc != "2.0" {
+        return Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+
+    match request.method.as_str() {
+        "initialize" => {
+            let requested = request.params.get("protocolVersion").and_then(|v| v.as_str());
+            let version = requested
+                .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+                .unwrap_or(PROTOCOL_VERSION);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": { "name": SERVER_NAME, "version": env!("CARGO_PKG_VERSION") },
+                "instructions": "Tools for the Kanban ticket this agent run is working on. \
+                                 Use them to read the ticket, report progress and ask questions.",
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => {
+            let name = request.params.get("name").and_then(|v| v.as_str())
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+            let args = request.params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+            let result = match resolve_scope(state, scope) {
+                Ok(ctx) => ctx.call(name, &args)?,
+                Err(e) => Err(e),
+            };
+            Ok(match result {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": to_text(&value) }],
+                    "isError": false,
+                }),
+                Err(ToolError(message)) => json!({
+                    "content": [{ "type": "text", "text": message }],
+                    "isError": true,
+                }),
+            })
+        }
+        other => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method: {}", other))),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "get_ticket",
+            "description": "Get the ticket you are working on, with its column, comments and task queue.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "add_comment",
+            "description": "Add a comment to your ticket, e.g. a progress note or a summary of what you changed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "body": { "type": "string", "description": "Comment text (Markdown)" },
+                },
+                "required": ["body"],
+            },
+        },
+        {
+            "name": "list_sibling_tickets",
+            "description": "List the other tickets in the same epic as your ticket, with their columns.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "create_followup_ticket",
+            "description": "Create a follow-up ticket in the Backlog of your board for work that is out of scope for this ticket.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string", "description": "Ticket description (Markdown)" },
+                    "priority": { "type": "string", "enum": ["low", "medium", "high", "urgent"] },
+                    "labels": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "update_task_status",
+            "description": "Update a task in your ticket's task queue (see get_ticket for task ids).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "task_id": { "type": "string" },
+                    "status": { "type": "string", "enum": ["in_progress", "completed", "failed"] },
+                    "note": { "type": "string", "description": "Optional comment to add to the ticket" },
+                },
+                "required": ["task_id", "status"],
+            },
+        },
+        {
+            "name": "ask_question",
+            "description": "Ask the humans watching the board a question. It is posted as a comment on your ticket; answers arrive as comments, so check get_ticket later rather than waiting.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "question": { "type": "string" },
+                },
+                "required": ["question"],
+            },
+        },
+    ])
+}
+
+fn resolve_scope<'a>(state: &'a AppState, scope: &McpScopeQuery) -> Result<ToolContext<'a>, ToolError> {
+    // A run is scoped to its own ticket, whatever ticket_id claims
+    let ticket_id = match (&scope.ticket_id, &scope.run_id) {
+        (claimed, Some(run_id)) => {
+            let ticket_id = state.db.get_run(run_id)?.ticket_id;
+            if claimed.as_ref().is_some_and(|claimed| *claimed != ticket_id) {
+                return Err(ToolError(format!(
+                    "Run {} does not belong to ticket {}",
+                    run_id,
+                    claimed.as_deref().unwrap_or_default()
+                )));
+            }
+            ticket_id
+        }
+        (Some(ticket_id), None) => ticket_id.clone(),
+        (None, None) => {
+            return Err(ToolError(
+                "This MCP session is not scoped to a ticket (pass ticket_id or run_id)".to_string(),
+            ))
+        }
+    };
+    let ticket = state.db.get_ticket(&ticket_id)?;
+    Ok(ToolContext { state, ticket, run_id: scope.run_id.clone() })
+}
+
+struct ToolContext<'a> {
+    state: &'a AppState,
+    ticket: Ticket,
+    run_id: Option<String>,
+}
+
+impl ToolContext<'_> {
+    /// Run a tool. The outer error is for unknown tools and bad arguments.
+    fn call(&self, name: &str, args: &Value) -> Result<ToolResult, RpcError> {
+        let result = match name {
+            "get_ticket" => self.get_ticket(),
+            "add_comment" => self.add_comment(required_str(args, "body")?, None),
+            "list_sibling_tickets" => self.list_sibling_tickets(),
+            "create_followup_ticket" => self.create_followup_ticket(args)?,
+            "update_task_status" => self.update_task_status(
+                required_str(args, "task_id")?,
+                required_str(args, "status")?,
+                args.get("note").and_then(|v| v.as_str()),
+            ),
+            "ask_question" => self.add_comment(
+                required_str(args, "question")?,
+                Some(json!({ "kind": "question", "runId": self.run_id })),
+            ),
+            other => return Err(RpcError::new(INVALID_PARAMS, format!("Unknown tool: {}", other))),
+        };
+        Ok(result)
+    }
+
+    fn column_name(&self, column_id: &str) -> Result<String, ToolError> {
+        let columns = self.state.db.get_columns(&self.ticket.board_id)?;
+        Ok(columns
+            .into_iter()
+            .find(|c| c.id == column_id)
+            .map(|c| c.name)
+            .unwrap_or_else(|| column_id.to_string()))
+    }
+
+    fn get_ticket(&self) -> ToolResult {
+        let comments = self.state.db.get_comments(&self.ticket.id)?;
+        let tasks = self.state.db.get_tasks_for_ticket(&self.ticket.id)?;
+        Ok(json!({
+            "ticket": self.ticket,
+            "column": self.column_name(&self.ticket.column_id)?,
+            "comments": comments,
+            "tasks": tasks,
+        }))
+    }
+
+    fn add_comment(&self, body: &str, metadata: Option<Value>) -> ToolResult {
+        if body.trim().is_empty() {
+            return Err(ToolError("Comment body cannot be empty".to_string()));
+        }
+        let metadata = metadata.or_else(|| self.run_id.as_ref().map(|id| json!({ "runId": id })));
+        let comment = self.state.db.create_comment(&CreateComment {
+            ticket_id: self.ticket.id.clone(),
+            author_type: AuthorType::Agent,
+            body_md: body.to_string(),
+            metadata,
+        })?;
+        self.state.broadcast(LiveEvent::CommentAdded {
+            ticket_id: self.ticket.id.clone(),
+            comment_id: comment.id.clone(),
+        });
+        Ok(json!({ "commentId": comment.id }))
+    }
+
+    fn list_sibling_tickets(&self) -> ToolResult {
+        let Some(epic_id) = &self.ticket.epic_id else {
+            return Ok(json!({ "epicId": null, "tickets": [] }));
+        };
+        let columns = self.state.db.get_columns(&self.ticket.board_id)?;
+        let siblings: Vec<Value> = self.state.db.get_epic_children(epic_id)?
+            .into_iter()
+            .filter(|t| t.id != self.ticket.id)
+            .map(|t| {
+                let column = columns.iter().find(|c| c.id == t.column_id).map(|c| c.name.as_str());
+                json!({
+                    "id": t.id,
+                    "title": t.title,
+                    "column": column,
+                    "orderInEpic": t.order_in_epic,
+                    "branchName": t.branch_name,
+                })
+            })
+            .collect();
+        Ok(json!({ "epicId": epic_id, "tickets": siblings }))
+    }
+
+    fn create_followup_ticket(&self, args: &Value) -> Result<ToolResult, RpcError> {
+        let title = required_str(args, "title")?.trim().to_string();
+        let priority = match args.get("priority").and_then(|v| v.as_str()) {
+            Some(p) => Priority::parse(p)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Invalid priority: {}", p)))?,
+            None => Priority::Medium,
+        };
+        let labels: Vec<String> = match args.get("labels") {
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|_| RpcError::new(INVALID_PARAMS, "labels must be an array of strings"))?,
+            None => Vec::new(),
+        };
+        let description = args.get("description").and_then(|v| v.as_str()).unwrap_or("");
+
+        Ok(self.create_followup(title, description, priority, labels))
+    }
+
+    fn create_followup(&self, title: String, description: &str, priority: Priority, labels: Vec<String>) -> ToolResult {
+        if title.is_empty() {
+            return Err(ToolError("Title cannot be empty".to_string()));
+        }
+        let backlog = self.state.db.find_column_by_role(&self.ticket.board_id, TicketState::Backlog)?
+            .ok_or_else(|| ToolError("Board has no backlog column".to_string()))?;
+
+        let description_md = format!(
+            "{}\n\n_Follow-up from \"{}\" ({})_",
+            description.trim_end(),
+            self.ticket.title,
+            self.ticket.id,
+        );
+        let ticket = self.state.db.create_ticket(&CreateTicket {
+            board_id: self.ticket.board_id.clone(),
+            column_id: backlog.id.clone(),
+            title,
+            description_md: description_md.trim_start().to_string(),
+            priority,
+            labels,
+            project_id: self.ticket.project_id.clone(),
+            agent_pref: self.ticket.agent_pref.clone(),
+            workflow_type: self.ticket.workflow_type.clone(),
+            model: self.ticket.model.clone(),
+            branch_name: None,
+            is_epic: false,
+            epic_id: None,
+            depends_on_epic_id: None,
+            depends_on_epic_ids: vec![],
+            scratchpad_id: None,
+            custom_fields: Default::default(),
+        })?;
+        self.state.broadcast(LiveEvent::TicketCreated {
+            ticket_id: ticket.id.clone(),
+            board_id: ticket.board_id.clone(),
+        });
+        Ok(json!({ "ticketId": ticket.id, "column": backlog.name }))
+    }
+
+    fn update_task_status(&self, task_id: &str, status: &str, note: Option<&str>) -> ToolResult {
+        let task = self.state.db.get_task(task_id)?;
+        if task.ticket_id != self.ticket.id {
+            return Err(ToolError(format!("Task {} does not belong to this ticket", task_id)));
+        }
+
+        let task = match TaskStatus::parse(status) {
+            Some(TaskStatus::InProgress) => {
+                let run_id = self.run_id.as_deref()
+                    .ok_or_else(|| ToolError("Starting a task requires a run_id scope".to_string()))?;
+                self.state.db.start_task(task_id, run_id)?
+            }
+            Some(TaskStatus::Completed) => self.state.db.complete_task(task_id)?,
+            Some(TaskStatus::Failed) => self.state.db.fail_task(task_id)?,
+            _ => return Err(ToolError(format!("Invalid status: {}", status))),
+        };
+        self.state.broadcast(LiveEvent::TicketUpdated { ticket_id: self.ticket.id.clone() });
+
+        if let Some(note) = note.filter(|n| !n.trim().is_empty()) {
+            self.add_comment(note, None)?;
+        }
+        Ok(json!({ "taskId": task.id, "status": task.status.as_str() }))
+    }
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str, RpcError> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing string argument '{}'", key)))
+}
+
+/// Server entry for agent MCP configs (Claude's `--mcp-config`, Cursor's
+/// `.cursor/mcp.json`), scoped to one run.
+pub fn server_config(api_url: &str, api_token: &str, run_id: &str) -> Value {
+    let url = format!("{}/v1/mcp?run_id={}", api_url.trim_end_matches('/'), run_id);
+    json!({
+        "type": "http",
+        "url": url,
+        "headers": { "X-AgentKanban-Token": api_token },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::db::{AgentType, CreateRun, CreateTask, Database, TaskType};
+
+    fn setup() -> (AppState, Ticket, String) {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket = db.create_ticket(&CreateTicket {
+            priority: Priority::High,
+            ..CreateTicket::for_test(&board.id, &columns[1].id, "Add login")
+        }).unwrap();
+        let run = db.create_run(&CreateRun {
+            ticket_id: ticket.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp".to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        (AppState::new(db, "token".to_string()), ticket, run.id)
+    }
+
+    fn rpc(state: &AppState, scope: &McpScopeQuery, method: &str, params: Value) -> Result<Value, RpcError> {
+        handle_request(state, scope, JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(json!(1)),
+            method: method.to_string(),
+            params,
+        })
+    }
+
+    fn call_tool(state: &AppState, scope: &McpScopeQuery, name: &str, args: Value) -> Value {
+        rpc(state, scope, "tools/call", json!({ "name": name, "arguments": args })).unwrap()
+    }
+
+    #[test]
+    fn initialize_negotiates_version() {
+        let (state, _, _) = setup();
+        let scope = McpScopeQuery::default();
+
+        let result = rpc(&state, &scope, "initialize", json!({ "protocolVersion": "2025-03-26" })).unwrap();
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        assert_eq!(result["serverInfo"]["name"], SERVER_NAME);
+
+        let result = rpc(&state, &scope, "initialize", json!({ "protocolVersion": "1999-01-01" })).unwrap();
+        assert_eq!(result["protocolVersion"], PROTOCOL_VERSION);
+
+        let tools = rpc(&state, &scope, "tools/list", json!({})).unwrap();
+        assert_eq!(tools["tools"].as_array().unwrap().len(), 6);
+        assert_eq!(rpc(&state, &scope, "nope", json!({})).unwrap_err().code, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn tools_are_scoped_to_the_run_ticket() {
+        let (state, ticket, run_id) = setup();
+        let scope = McpScopeQuery { ticket_id: None, run_id: Some(run_id) };
+
+        let result = call_tool(&state, &scope, "add_comment", json!({ "body": "Halfway there" }));
+        assert_eq!(result["isError"], false);
+
+        let result = call_tool(&state, &scope, "get_ticket", json!({}));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let value: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(value["ticket"]["id"], ticket.id.as_str());
+        assert_eq!(value["column"], "Ready");
+        assert_eq!(value["comments"][0]["bodyMd"], "Halfway there");
+        assert_eq!(value["comments"][0]["authorType"], "agent");
+    }
+
+    #[test]
+    fn unscoped_calls_report_tool_errors() {
+        let (state, _, _) = setup();
+        let result = call_tool(&state, &McpScopeQuery::default(), "get_ticket", json!({}));
+        assert_eq!(result["isError"], true);
+
+        let scope = McpScopeQuery { ticket_id: Some("missing".to_string()), run_id: None };
+        let result = call_tool(&state, &scope, "get_ticket", json!({}));
+        assert_eq!(result["isError"], true);
+    }
+
+    #[test]
+    fn runs_cannot_claim_another_ticket() {
+        let (state, ticket, run_id) = setup();
+        let scope = McpScopeQuery { ticket_id: Some("other-ticket".to_string()), run_id: Some(run_id.clone()) };
+        let result = call_tool(&state, &scope, "add_comment", json!({ "body": "Wrong ticket" }));
+        assert_eq!(result["isError"], true);
+        assert!(state.db.get_comments(&ticket.id).unwrap().is_empty());
+
+        let scope = McpScopeQuery { ticket_id: Some(ticket.id.clone()), run_id: Some(run_id) };
+        let result = call_tool(&state, &scope, "get_ticket", json!({}));
+        assert_eq!(result["isError"], false);
+    }
+
+    #[test]
+    fn create_followup_goes_to_backlog() {
+        let (state, ticket, run_id) = setup();
+        let scope = McpScopeQuery { ticket_id: Some(ticket.id.clone()), run_id: Some(run_id) };
+        // A renamed backlog column is still found by its role
+        let backlog = state.db.find_column_by_name(&ticket.board_id, "Backlog").unwrap().unwrap();
+        state.db.update_column(&backlog.id, &crate::db::UpdateColumn {
+            name: Some("Icebox".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let result = call_tool(&state, &scope, "create_followup_ticket", json!({
+            "title": "Add logout",
+            "priority": "low",
+        }));
+        assert_eq!(result["isError"], false);
+
+        let tickets = state.db.get_tickets(&ticket.board_id, Some(&backlog.id)).unwrap();
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].priority, Priority::Low);
+        assert!(tickets[0].description_md.contains(&ticket.id));
+
+        let err = rpc(&state, &scope, "tools/call", json!({
+            "name": "create_followup_ticket",
+            "arguments": { "title": "x", "priority": "asap" },
+        })).unwrap_err();
+        assert_eq!(err.code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn update_task_status_checks_ownership_and_transitions() {
+        let (state, ticket, run_id) = setup();
+        let task = state.db.create_task(&CreateTask {
+            ticket_id: ticket.id.clone(),
+            task_type: TaskType::Custom,
+            title: Some("Write tests".to_string()),
+            content: None,
+        }).unwrap();
+        let scope = McpScopeQuery { ticket_id: None, run_id: Some(run_id) };
+
+        let result = call_tool(&state, &scope, "update_task_status", json!({
+            "task_id": task.id, "status": "in_progress",
+        }));
+        assert_eq!(result["isError"], false);
+        let result = call_tool(&state, &scope, "update_task_status", json!({
+            "task_id": task.id, "status": "completed", "note": "Done",
+        }));
+        assert_eq!(result["isError"], false);
+        assert_eq!(state.db.get_task(&task.id).unwrap().status, TaskStatus::Completed);
+        assert_eq!(state.db.get_comments(&ticket.id).unwrap().len(), 1);
+
+        // A session scoped to another ticket cannot touch this ticket's tasks
+        let result = call_tool(&state, &scope, "create_followup_ticket", json!({ "title": "Other" }));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let other_id = serde_json::from_str::<Value>(text).unwrap()["ticketId"].as_str().unwrap().to_string();
+        let other = McpScopeQuery { ticket_id: Some(other_id), run_id: None };
+        let result = call_tool(&state, &other, "update_task_status", json!({
+            "task_id": task.id, "status": "failed",
+        }));
+        assert_eq!(result["isError"], true);
+        assert!(result["content"][0]["text"].as_str().unwrap().contains("does not belong"));
+    }
+
+    #[test]
+    fn server_config_includes_scope() {
+        let config = server_config("http://127.0.0.1:7432/", "tok", "r1");
+        assert_eq!(config["url"], "http://127.0.0.1:7432/v1/mcp?run_id=r1");
+        assert_eq!(config["headers"]["X-AgentKanban-Token"], "tok");
+    }
+}
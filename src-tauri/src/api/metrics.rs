@@ -0,0 +1,313 @@
+//! Prometheus `/metrics` endpoint.
+//!
+//! Everything is rendered by hand in the Prometheus text exposition format
+//! (version 0.0.4). Board, queue and run figures are read from the database on
+//! each scrape; worker state comes from the in-process `WorkerManager` and the
+//! lock-recovery counters from the cleanup service.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+
+use super::cleanup::CLEANUP_TOTALS;
+use super::error::ApiResult;
+use super::spool::count_spooled_events;
+use super::state::AppState;
+use crate::agents::worker::{WorkerState, WorkerStatus, WORKER_MANAGER};
+use crate::db::{Database, DbError};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Process-local values that are not stored in the database
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    pub workers: Vec<WorkerStatus>,
+    pub spool_backlog: usize,
+    pub sse_subscribers: usize,
+}
+
+pub async fn metrics(State(state): State<AppState>) -> ApiResult<impl IntoResponse> {
+    let snapshot = MetricsSnapshot {
+        workers: WORKER_MANAGER.get_all_status(),
+        spool_backlog: count_spooled_events(&state.spool_dir),
+        sse_subscribers: state.event_tx.receiver_count(),
+    };
+
+    let body = state.db.blocking(move |db| render_metrics(db, &snapshot)).await?;
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], body))
+}
+
+/// Render all metrics as Prometheus text.
+pub fn render_metrics(db: &Database, snapshot: &MetricsSnapshot) -> Result<String, DbError> {
+    let mut w = MetricsWriter::default();
+
+    // Queue depth and ticket states
+    let columns = db.get_column_ticket_counts()?;
+
+    w.header(
+        "agent_kanban_queue_depth",
+        "gauge",
+        "Non-epic tickets without an active lock, per board and column",
+    );
+    for c in &columns {
+        w.sample(
+            "agent_kanban_queue_depth",
+            &[("board_id", &c.board_id), ("board", &c.board_name), ("column", &c.column_name)],
+            c.waiting,
+        );
+    }
+
+    w.header(
+        "agent_kanban_tickets_locked",
+        "gauge",
+        "Tickets held by an active lock, per board and column",
+    );
+    for c in &columns {
+        w.sample(
+            "agent_kanban_tickets_locked",
+            &[("board_id", &c.board_id), ("board", &c.board_name), ("column", &c.column_name)],
+            c.locked,
+        );
+    }
+
+    let mut by_state: BTreeMap<&'static str, i64> = BTreeMap::new();
+    for c in &columns {
+        let state = c.column_role.map(|s| s.as_str()).unwrap_or("custom");
+        *by_state.entry(state).or_default() += c.total;
+    }
+    w.header("agent_kanban_tickets", "gauge", "Tickets per lifecycle state across all boards");
+    for (state, count) in &by_state {
+        w.sample("agent_kanban_tickets", &[("state", state)], count);
+    }
+
+    // Workers
+    let mut workers_by_state: BTreeMap<&'static str, usize> = WorkerState::ALL
+        .iter()
+        .map(|s| (s.as_str(), 0))
+        .collect();
+    for worker in &snapshot.workers {
+        *workers_by_state.entry(worker.status.as_str()).or_default() += 1;
+    }
+    w.header("agent_kanban_workers", "gauge", "In-process workers by state");
+    for (state, count) in &workers_by_state {
+        w.sample("agent_kanban_workers", &[("state", state)], count);
+    }
+
+    w.header(
+        "agent_kanban_worker_info",
+        "gauge",
+        "One series per worker; labels describe its agent, project filter and state",
+    );
+    for worker in &snapshot.workers {
+        w.sample(
+            "agent_kanban_worker_info",
+            &[
+                ("worker_id", &worker.id),
+                ("agent", &worker.agent_type),
+                ("project_id", worker.project_id.as_deref().unwrap_or("")),
+                ("state", worker.status.as_str()),
+                ("ticket_id", worker.current_ticket_id.as_deref().unwrap_or("")),
+            ],
+            1,
+        );
+    }
+
+    w.header(
+        "agent_kanban_worker_tickets_processed_total",
+        "counter",
+        "Tickets processed by each worker since it started",
+    );
+    for worker in &snapshot.workers {
+        w.sample(
+            "agent_kanban_worker_tickets_processed_total",
+            &[("worker_id", &worker.id), ("agent", &worker.agent_type)],
+            worker.tickets_processed,
+        );
+    }
+
+    // Runs
+    let run_stats = db.get_run_stats()?;
+
+    w.header("agent_kanban_runs", "gauge", "Agent runs recorded in the database by agent, stage and outcome");
+    for s in &run_stats {
+        w.sample(
+            "agent_kanban_runs",
+            &[("agent", &s.agent_type), ("stage", s.stage.as_deref().unwrap_or("")), ("outcome", &s.status)],
+            s.count,
+        );
+    }
+
+    w.header(
+        "agent_kanban_run_duration_seconds",
+        "summary",
+        "Wall-clock duration of ended runs by agent, stage and outcome",
+    );
+    for s in run_stats.iter().filter(|s| s.ended_count > 0) {
+        let labels = [
+            ("agent", s.agent_type.as_str()),
+            ("stage", s.stage.as_deref().unwrap_or("")),
+            ("outcome", s.status.as_str()),
+        ];
+        w.sample("agent_kanban_run_duration_seconds_sum", &labels, s.duration_secs_sum);
+        w.sample("agent_kanban_run_duration_seconds_count", &labels, s.ended_count);
+    }
+
+    w.header(
+        "agent_kanban_stage_timeouts",
+        "gauge",
+        "Workflow stage runs that hit their time limit by agent and stage",
+    );
+    for t in db.get_stage_timeout_counts()? {
+        w.sample("agent_kanban_stage_timeouts", &[("agent", &t.agent_type), ("stage", &t.stage)], t.count);
+    }
+
+    // Lock cleanup
+    w.header(
+        "agent_kanban_expired_ticket_locks_recovered_total",
+        "counter",
+        "Expired ticket locks released by the cleanup service",
+    );
+    w.sample(
+        "agent_kanban_expired_ticket_locks_recovered_total",
+        &[],
+        CLEANUP_TOTALS.released_tickets.load(Ordering::Relaxed),
+    );
+
+    w.header(
+        "agent_kanban_expired_repo_locks_recovered_total",
+        "counter",
+        "Expired repository locks released by the cleanup service",
+    );
+    w.sample(
+        "agent_kanban_expired_repo_locks_recovered_total",
+        &[],
+        CLEANUP_TOTALS.released_repo_locks.load(Ordering::Relaxed),
+    );
+
+    w.header(
+        "agent_kanban_runs_aborted_on_lock_expiry_total",
+        "counter",
+        "Runs marked aborted because their ticket lock expired",
+    );
+    w.sample(
+        "agent_kanban_runs_aborted_on_lock_expiry_total",
+        &[],
+        CLEANUP_TOTALS.aborted_runs.load(Ordering::Relaxed),
+    );
+
+    // Event ingestion
+    w.header("agent_kanban_spool_backlog", "gauge", "Spooled hook events waiting to be ingested");
+    w.sample("agent_kanban_spool_backlog", &[], snapshot.spool_backlog);
+
+    w.header("agent_kanban_sse_subscribers", "gauge", "Connected SSE clients");
+    w.sample("agent_kanban_sse_subscribers", &[], snapshot.sse_subscribers);
+
+    Ok(w.finish())
+}
+
+#[derive(Default)]
+struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (i, (key, val)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{}=\"{}\"", key, escape_label_value(val));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {}", value);
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn escape_label_value_handles_special_chars() {
+        assert_eq!(escape_label_value(r#"a "b" \c"#), r#"a \"b\" \\c"#);
+        assert_eq!(escape_label_value("line\nbreak"), "line\\nbreak");
+    }
+
+    #[test]
+    fn writer_formats_samples() {
+        let mut w = MetricsWriter::default();
+        w.header("m", "gauge", "help text");
+        w.sample("m", &[], 3);
+        w.sample("m", &[("a", "1"), ("b", "x")], 4.5);
+
+        assert_eq!(
+            w.finish(),
+            "# HELP m help text\n# TYPE m gauge\nm 3\nm{a=\"1\",b=\"x\"} 4.5\n"
+        );
+    }
+
+    #[test]
+    fn render_includes_queue_workers_and_runtime_values() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("My Board").unwrap();
+
+        let snapshot = MetricsSnapshot {
+            workers: vec![WorkerStatus {
+                id: "w1".to_string(),
+                agent_type: "claude".to_string(),
+                project_id: None,
+                labels: Default::default(),
+                status: WorkerState::Running,
+                state_reason: None,
+                current_ticket_id: Some("t1".to_string()),
+                current_run_id: None,
+                tickets_processed: 7,
+                started_at: Utc::now(),
+                last_poll_at: None,
+            }],
+            spool_backlog: 2,
+            sse_subscribers: 1,
+        };
+
+        let text = render_metrics(&db, &snapshot).unwrap();
+
+        assert!(text.contains(&format!(
+            "agent_kanban_queue_depth{{board_id=\"{}\",board=\"My Board\",column=\"Ready\"}} 0",
+            board.id
+        )));
+        assert!(text.contains("agent_kanban_tickets{state=\"done\"} 0"));
+        assert!(text.contains("agent_kanban_workers{state=\"running\"} 1"));
+        assert!(text.contains("agent_kanban_workers{state=\"idle\"} 0"));
+        assert!(text.contains("agent_kanban_worker_tickets_processed_total{worker_id=\"w1\",agent=\"claude\"} 7"));
+        assert!(text.contains("agent_kanban_spool_backlog 2"));
+        assert!(text.contains("agent_kanban_sse_subscribers 1"));
+        assert!(text.contains("# TYPE agent_kanban_expired_ticket_locks_recovered_total counter"));
+    }
+}
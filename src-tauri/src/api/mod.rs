@@ -1,8 +1,11 @@
 pub mod auth;
+pub mod backup;
 pub mod cleanup;
 pub mod error;
 pub mod events;
 pub mod handlers;
+pub mod mcp;
+pub mod metrics;
 pub mod routes;
 pub mod spool;
 pub mod state;
@@ -11,9 +14,11 @@ pub mod types;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::{broadcast, oneshot};
+use crate::db::backup::BackupPolicy;
 use crate::db::Database;
 
-pub use auth::generate_token;
+pub use auth::{generate_token, load_or_create_token};
+pub use backup::start_backup_service;
 pub use cleanup::{start_cleanup_service, CleanupConfig};
 pub use state::{AppState, LiveEvent};
 pub use error::{ApiError, AppError, ApiResult};
@@ -31,6 +36,8 @@ pub struct ApiConfig {
     pub port: u16,
     pub token: String,
     pub host: [u8; 4],
+    pub cleanup: CleanupConfig,
+    pub backup: BackupPolicy,
 }
 
 impl Default for ApiConfig {
@@ -39,6 +46,8 @@ impl Default for ApiConfig {
             port: 7432,
             token: generate_token(),
             host: [127, 0, 0, 1],
+            cleanup: CleanupConfig::default(),
+            backup: BackupPolicy::default(),
         }
     }
 }
@@ -75,24 +84,27 @@ pub async fn start_server_with_event_tx(
 }
 
 /// Start the API server with a pre-configured AppState
-async fn start_server_with_state(
+pub async fn start_server_with_state(
     db: Arc<Database>,
     config: ApiConfig,
-    state: AppState,
+    mut state: AppState,
 ) -> Result<ServerHandle, Box<dyn std::error::Error + Send + Sync>> {
-    let router = routes::create_router(state);
-
     let addr = SocketAddr::from((config.host, config.port));
     let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
     let actual_addr = listener.local_addr()?;
 
+    state.api_url = format!("http://{}", actual_addr);
+    state.retention = config.cleanup.retention.clone();
+    let router = routes::create_router(state);
+
     tracing::info!("API server listening on http://{}", actual_addr);
     tracing::info!("API token: {}", config.token);
 
-    // Start the lock cleanup background service
-    start_cleanup_service(db, CleanupConfig::default());
+    // Start the lock cleanup and backup background services
+    start_cleanup_service(db.clone(), config.cleanup.clone());
+    start_backup_service(db, config.backup.clone());
 
     tokio::spawn(async move {
         axum::serve(listener, router)
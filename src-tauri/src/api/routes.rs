@@ -1,6 +1,6 @@
 use axum::{
     middleware,
-    routing::{get, post, patch, delete},
+    routing::{get, post, put, patch, delete},
     Router,
 };
 use tower_http::cors::{Any, CorsLayer};
@@ -8,6 +8,8 @@ use tower_http::cors::{Any, CorsLayer};
 use super::auth::auth_middleware;
 use super::handlers::*;
 use super::events::{sse_handler, sse_filtered};
+use super::mcp::{mcp_get, mcp_post};
+use super::metrics::metrics;
 use super::state::AppState;
 
 pub fn create_router(state: AppState) -> Router {
@@ -20,8 +22,34 @@ pub fn create_router(state: AppState) -> Router {
     let protected_routes = Router::new()
         // Boards
         .route("/v1/boards", get(list_boards))
+        .route("/v1/backups", get(list_backups))
+        .route("/v1/backups", post(create_backup))
+        .route("/v1/backups/:file_name/restore", post(restore_backup))
+        .route("/v1/archive", get(list_archived_tickets))
+        .route("/v1/archive/:ticket_id/restore", post(restore_archived_ticket))
+        .route("/v1/retention", post(run_retention))
+        .route("/v1/trash", get(list_trash))
+        .route("/v1/trash/:trash_id", delete(purge_trash))
+        .route("/v1/trash/:trash_id/restore", post(restore_trash))
+        .route("/v1/boards/import", post(import_board))
         .route("/v1/boards/:board_id", get(get_board))
+        .route("/v1/boards/:board_id", delete(delete_board))
+        .route("/v1/boards/:board_id/export", get(export_board))
+        .route("/v1/boards/:board_id/export/markdown", get(export_board_markdown))
         .route("/v1/boards/:board_id/columns", get(list_columns))
+        .route("/v1/boards/:board_id/columns", post(create_column))
+        .route("/v1/boards/:board_id/columns/order", post(reorder_columns))
+        .route("/v1/boards/:board_id/columns/:column_id", patch(update_column))
+        .route("/v1/boards/:board_id/columns/:column_id", delete(delete_column))
+        .route("/v1/boards/:board_id/fields", get(list_custom_fields))
+        .route("/v1/boards/:board_id/fields", post(create_custom_field))
+        .route("/v1/boards/:board_id/fields/:field_id", patch(update_custom_field))
+        .route("/v1/boards/:board_id/fields/:field_id", delete(delete_custom_field))
+        .route("/v1/boards/:board_id/labels", get(list_labels))
+        .route("/v1/boards/:board_id/labels", post(create_label))
+        .route("/v1/boards/:board_id/labels/:label_id", patch(update_label))
+        .route("/v1/boards/:board_id/labels/:label_id", delete(delete_label))
+        .route("/v1/boards/:board_id/labels/:label_id/merge", post(merge_label))
         .route("/v1/boards/:board_id/tickets", get(list_tickets))
         
         // Tickets
@@ -30,23 +58,88 @@ pub fn create_router(state: AppState) -> Router {
         .route("/v1/tickets/:ticket_id", patch(update_ticket))
         .route("/v1/tickets/:ticket_id", delete(delete_ticket))
         .route("/v1/tickets/:ticket_id/move", post(move_ticket))
+        .route("/v1/tickets/:ticket_id/history", get(get_ticket_history))
         .route("/v1/tickets/:ticket_id/reserve", post(reserve_ticket))
         .route("/v1/tickets/:ticket_id/comments", get(list_comments))
         .route("/v1/tickets/:ticket_id/comments", post(create_comment))
         .route("/v1/tickets/:ticket_id/runs", get(list_runs))
+        .route("/v1/tickets/:ticket_id/tasks", get(list_tasks))
+        .route("/v1/tickets/:ticket_id/tasks", post(create_task))
+        
+        // Tasks
+        .route("/v1/tasks/:task_id", get(get_task))
+        .route("/v1/tasks/:task_id", delete(delete_task))
+        .route("/v1/tasks/:task_id/reset", post(reset_task))
+        
+        // Projects
+        .route("/v1/projects", get(list_projects))
+        .route("/v1/projects", post(create_project))
+        .route("/v1/projects/:project_id", get(get_project))
+        .route("/v1/projects/:project_id", patch(update_project))
+        .route("/v1/projects/:project_id", delete(delete_project))
+        
+        // Scratchpads (planner)
+        .route("/v1/scratchpads", get(list_scratchpads))
+        .route("/v1/scratchpads", post(create_scratchpad))
+        .route("/v1/scratchpads/:scratchpad_id", get(get_scratchpad))
+        .route("/v1/scratchpads/:scratchpad_id", delete(delete_scratchpad))
+        .route("/v1/scratchpads/:scratchpad_id/planner", post(start_planner))
+        .route("/v1/scratchpads/:scratchpad_id/approve", post(approve_plan))
+        .route("/v1/scratchpads/:scratchpad_id/execute", post(execute_plan))
         
         // Runs
         .route("/v1/runs", post(create_run))
+        .route("/v1/runs/interrupted", get(list_interrupted_runs))
         .route("/v1/runs/:run_id", get(get_run))
         .route("/v1/runs/:run_id", patch(update_run))
         .route("/v1/runs/:run_id/heartbeat", post(heartbeat))
         .route("/v1/runs/:run_id/release", post(release_run))
+        .route("/v1/runs/:run_id/pause", post(pause_run))
+        .route("/v1/runs/:run_id/resume", post(resume_run))
+        .route("/v1/runs/:run_id/plan", put(update_run_plan))
+        .route("/v1/runs/:run_id/checkpoint", get(get_run_checkpoint))
+        .route("/v1/runs/:run_id/rerun", post(rerun_run))
+        .route("/v1/runs/:run_id/skip", post(skip_run_stage))
+        .route("/v1/runs/:run_id/stages", post(insert_run_stage))
         .route("/v1/runs/:run_id/events", get(list_events))
         .route("/v1/runs/:run_id/events", post(create_event))
+        .route("/v1/runs/:run_id/artifacts", get(list_run_artifacts))
         
         // Queue
         .route("/v1/queue/next", post(queue_next))
         .route("/v1/queue/status", get(queue_status))
+        .route("/v1/queue/explain", get(explain_queue))
+        .route("/v1/queue/policy", get(get_scheduling_policy))
+        .route("/v1/queue/policy", put(set_scheduling_policy))
+        .route("/v1/concurrency", get(get_concurrency))
+        .route("/v1/concurrency", put(set_concurrency_limits))
+        .route("/v1/recovery", get(get_recovery_settings))
+        .route("/v1/recovery", put(set_recovery_settings))
+        
+        // Workers
+        .route("/v1/workers", get(list_workers))
+        .route("/v1/workers", post(start_worker))
+        .route("/v1/workers", delete(stop_all_workers))
+        .route("/v1/workers/:worker_id", delete(stop_worker))
+        .route("/v1/workers/:worker_id/pause", post(pause_worker))
+        .route("/v1/workers/:worker_id/resume", post(resume_worker))
+        .route("/v1/workers/:worker_id/drain", post(drain_worker))
+        .route("/v1/remote-workers", get(list_remote_workers))
+        .route("/v1/remote-workers", post(register_remote_worker))
+        .route("/v1/remote-workers/:worker_id", delete(delete_remote_worker))
+        .route("/v1/remote-workers/:worker_id/heartbeat", post(remote_worker_heartbeat))
+        .route("/v1/remote-workers/:worker_id/lease", post(lease_ticket))
+        .route("/v1/remote-workers/:worker_id/leases/:token/heartbeat", post(renew_lease))
+        .route("/v1/remote-workers/:worker_id/leases/:token/result", post(submit_lease_result))
+        .route("/v1/worker-definitions", get(list_worker_definitions))
+        .route("/v1/worker-definitions/:worker_id", patch(update_worker_definition))
+        .route("/v1/worker-definitions/:worker_id", delete(delete_worker_definition))
+        
+        // Model Context Protocol (Streamable HTTP transport)
+        .route("/v1/mcp", post(mcp_post).get(mcp_get))
+        
+        // Prometheus metrics
+        .route("/metrics", get(metrics))
         
         // Real-time updates (SSE)
         .route("/v1/stream", get(sse_handler))
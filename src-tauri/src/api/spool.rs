@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use std::sync::Arc;
 
@@ -32,6 +32,17 @@ pub fn get_default_spool_dir() -> PathBuf {
     base_dir.join("spool")
 }
 
+/// Count spooled event files that are still waiting to be ingested
+pub fn count_spooled_events(spool_dir: &Path) -> usize {
+    match fs::read_dir(spool_dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().map(|ext| ext == "json").unwrap_or(false))
+            .count(),
+        Err(_) => 0,
+    }
+}
+
 /// Process spooled events in the background
 pub async fn start_spool_processor(db: Arc<Database>, spool_dir: PathBuf) {
     let mut ticker = interval(Duration::from_secs(30));
@@ -41,13 +52,18 @@ pub async fn start_spool_processor(db: Arc<Database>, spool_dir: PathBuf) {
     loop {
         ticker.tick().await;
         
-        if let Err(e) = process_spool(&db, &spool_dir).await {
-            tracing::error!("Spool processing error: {}", e);
+        // Spool files are read and inserted synchronously; keep that off the
+        // async workers
+        let (db, dir) = (db.clone(), spool_dir.clone());
+        match tokio::task::spawn_blocking(move || process_spool(&db, &dir)).await {
+            Ok(Err(e)) => tracing::error!("Spool processing error: {}", e),
+            Err(e) => tracing::error!("Spool processing task failed: {}", e),
+            Ok(Ok(())) => {}
         }
     }
 }
 
-async fn process_spool(db: &Database, spool_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+fn process_spool(db: &Database, spool_dir: &PathBuf) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     if !spool_dir.exists() {
         return Ok(());
     }
@@ -153,6 +169,21 @@ mod tests {
         assert!(spool_dir.to_string_lossy().contains("spool"));
     }
 
+    #[test]
+    fn test_count_spooled_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let spool_dir = temp_dir.path().join("spool");
+
+        // Missing directory counts as empty
+        assert_eq!(count_spooled_events(&spool_dir), 0);
+
+        fs::create_dir_all(&spool_dir).unwrap();
+        create_spool_file(&spool_dir, "{}");
+        fs::write(spool_dir.join("ignored.tmp"), "x").unwrap();
+
+        assert_eq!(count_spooled_events(&spool_dir), 1);
+    }
+
     #[tokio::test]
     async fn test_process_empty_spool() {
         let temp_dir = TempDir::new().unwrap();
@@ -160,7 +191,7 @@ mod tests {
         let db = create_test_db();
         
         // Should not error when spool dir doesn't exist
-        let result = process_spool(&db, &spool_dir.to_path_buf()).await;
+        let result = process_spool(&db, &spool_dir.to_path_buf());
         assert!(result.is_ok());
     }
 
@@ -193,6 +224,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let run = db.create_run(&CreateRun {
@@ -220,7 +252,7 @@ mod tests {
         assert!(file_path.exists());
         
         // Process the spool
-        process_spool(&db, &spool_dir).await.unwrap();
+        process_spool(&db, &spool_dir).unwrap();
         
         // File should be deleted after processing
         assert!(!file_path.exists());
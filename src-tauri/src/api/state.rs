@@ -1,5 +1,9 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::broadcast;
+use crate::agents::worker::WorkerState;
+use crate::agents::claude_settings::ClaudeApiSettingsState;
+use crate::db::retention::RetentionPolicy;
 use crate::db::Database;
 
 /// Event sent to connected clients via SSE
@@ -22,6 +26,11 @@ pub enum LiveEvent {
         ticket_id: String,
         board_id: String,
     },
+    /// A ticket (with an epic's children) came back from the trash
+    TicketRestored {
+        ticket_id: String,
+        board_id: String,
+    },
     CommentAdded {
         ticket_id: String,
         comment_id: String,
@@ -53,6 +62,23 @@ pub enum LiveEvent {
     TicketUnlocked {
         ticket_id: String,
     },
+    /// A column was added, renamed, reordered or deleted
+    ColumnsChanged {
+        board_id: String,
+    },
+    /// A custom field was added, changed or deleted
+    CustomFieldsChanged {
+        board_id: String,
+    },
+    /// A label was added, changed, merged or deleted; renames, merges and
+    /// deletes also rewrite the board's tickets
+    LabelsChanged {
+        board_id: String,
+    },
+    /// A board came back from the trash with its tickets and scratchpads
+    BoardRestored {
+        board_id: String,
+    },
     // Scratchpad / Planner events
     ScratchpadCreated {
         scratchpad_id: String,
@@ -65,6 +91,10 @@ pub enum LiveEvent {
         scratchpad_id: String,
         board_id: String,
     },
+    ScratchpadRestored {
+        scratchpad_id: String,
+        board_id: String,
+    },
     ExplorationProgress {
         scratchpad_id: String,
         query: String,
@@ -95,6 +125,25 @@ pub enum LiveEvent {
         /// Timestamp
         timestamp: String,
     },
+    /// A worker was paused, resumed, started draining, left or entered its
+    /// schedule, or stopped
+    WorkerStateChanged {
+        worker_id: String,
+        state: WorkerState,
+        reason: Option<String>,
+    },
+    /// A remote worker registered, was removed, or its leases changed
+    RemoteWorkersChanged {
+        worker_id: String,
+    },
+    /// Agent progress event (the same events the desktop app receives as
+    /// Tauri events, e.g. "agent-log" or "ticket-moved")
+    AgentEvent {
+        name: String,
+        run_id: Option<String>,
+        ticket_id: Option<String>,
+        payload: serde_json::Value,
+    },
 }
 
 /// Shared application state for the API server
@@ -103,12 +152,22 @@ pub struct AppState {
     pub db: Arc<Database>,
     pub api_token: String,
     pub event_tx: broadcast::Sender<LiveEvent>,
+    /// Base URL agents use to call back into this server
+    pub api_url: String,
+    /// Claude API settings passed to planners and workers started over REST
+    pub claude_settings: ClaudeApiSettingsState,
+    /// Hook script installed into repos by workers started over REST
+    pub hook_script_path: Option<String>,
+    /// Policy applied by `POST /v1/retention`; the cleanup service's copy
+    pub retention: RetentionPolicy,
+    /// Where the spool processor picks up hook events, for the backlog metric
+    pub spool_dir: PathBuf,
 }
 
 impl AppState {
     pub fn new(db: Arc<Database>, api_token: String) -> Self {
         let (event_tx, _) = broadcast::channel(256);
-        Self { db, api_token, event_tx }
+        Self::with_event_tx(db, api_token, event_tx)
     }
     
     /// Create AppState with an externally provided event_tx
@@ -117,7 +176,32 @@ impl AppState {
         api_token: String,
         event_tx: broadcast::Sender<LiveEvent>,
     ) -> Self {
-        Self { db, api_token, event_tx }
+        Self {
+            db,
+            api_token,
+            event_tx,
+            api_url: "http://127.0.0.1:7432".to_string(),
+            claude_settings: ClaudeApiSettingsState::new(),
+            hook_script_path: None,
+            retention: RetentionPolicy::default(),
+            spool_dir: super::spool::get_default_spool_dir(),
+        }
+    }
+
+    /// Share the app's Claude API settings with the API server
+    pub fn with_claude_settings(mut self, settings: ClaudeApiSettingsState) -> Self {
+        self.claude_settings = settings;
+        self
+    }
+
+    pub fn with_hook_script_path(mut self, path: Option<String>) -> Self {
+        self.hook_script_path = path;
+        self
+    }
+
+    pub fn with_spool_dir(mut self, spool_dir: PathBuf) -> Self {
+        self.spool_dir = spool_dir;
+        self
     }
 
     pub fn broadcast(&self, event: LiveEvent) {
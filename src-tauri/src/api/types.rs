@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use crate::db::{Priority, AgentType, AgentPref, Ticket, Column, WorkflowType};
+use crate::agents::AgentKind;
+use crate::db::{Priority, AgentType, AgentPref, AgentRun, Ticket, TicketLease, Column, WorkflowType, BoardBundle, CustomFieldValues};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +22,9 @@ pub struct CreateTicketRequest {
     pub model: Option<String>,
     /// Optional pre-defined branch name (if not provided, will be AI-generated on first run)
     pub branch_name: Option<String>,
+    /// Values of the board's custom fields, keyed by field key
+    #[serde(default)]
+    pub custom_fields: CustomFieldValues,
 }
 
 fn default_priority() -> Priority {
@@ -40,6 +44,8 @@ pub struct UpdateTicketRequest {
     pub model: Option<String>,
     pub branch_name: Option<String>,
     pub column_id: Option<String>,
+    /// Custom field values to set; `null` clears a field
+    pub custom_fields: Option<CustomFieldValues>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,6 +54,78 @@ pub struct MoveTicketRequest {
     pub column_id: String,
 }
 
+// ===== Column Types =====
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderColumnsRequest {
+    /// Every column id of the board, in the new order
+    pub column_ids: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteColumnQuery {
+    /// Where to move the column's tickets; required when it is not empty
+    pub move_tickets_to: Option<String>,
+}
+
+// ===== Label Types =====
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeLabelRequest {
+    /// Label that absorbs the one in the path
+    pub into_label_id: String,
+}
+
+// ===== Archive Types =====
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveQuery {
+    pub board_id: Option<String>,
+    /// Matched against title and description, ignoring case
+    pub q: Option<String>,
+}
+
+// ===== Trash Types =====
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteQuery {
+    /// Delete for good instead of moving to the trash
+    #[serde(default)]
+    pub permanent: bool,
+    /// Scratchpads only: also delete the epics and tickets its plan created
+    #[serde(default)]
+    pub with_tickets: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashQuery {
+    pub board_id: Option<String>,
+}
+
+// ===== Export/Import Types =====
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBoardQuery {
+    /// Also export agent runs and their events
+    #[serde(default)]
+    pub include_runs: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBoardRequest {
+    pub bundle: BoardBundle,
+    /// Name for the new board; defaults to the exported board's name
+    pub name: Option<String>,
+}
+
 // ===== Reservation Types =====
 
 #[derive(Debug, Serialize)]
@@ -83,6 +161,31 @@ pub struct UpdateRunRequest {
     pub summary_md: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunRequest {
+    /// Index of the workflow stage to run again from
+    pub stage: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertStageRequest {
+    pub prompt: String,
+    /// Stage name recorded on the sub-run; `custom` when omitted
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Workflow stage to continue from afterwards; the one the run stopped at when omitted
+    #[serde(default)]
+    pub before: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePlanRequest {
+    pub plan_md: String,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HeartbeatResponse {
@@ -132,7 +235,7 @@ pub struct QueueNextResponse {
     pub heartbeat_interval_secs: u64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueueStatusResponse {
     pub ready_count: usize,
@@ -140,7 +243,21 @@ pub struct QueueStatusResponse {
     pub boards: Vec<BoardQueueStatus>,
 }
 
-#[derive(Debug, Serialize)]
+/// Which worker `GET /v1/queue/explain` ranks the queue for
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueExplainQuery {
+    pub project_id: Option<String>,
+    /// Leave out tickets preferring another agent
+    pub agent_type: Option<AgentKind>,
+    /// Comma-separated labels, as in a worker's label filter
+    pub include: Option<String>,
+    pub exclude: Option<String>,
+    /// Tickets to list; 20 by default
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BoardQueueStatus {
     pub board_id: String,
@@ -159,12 +276,88 @@ pub struct BoardWithColumns {
     pub columns: Vec<Column>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteResponse {
     pub deleted: bool,
     pub id: String,
+    /// Set when the item went to the trash; restore it with this id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_id: Option<String>,
+}
+
+// ===== Scratchpad Types =====
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScratchpadRequest {
+    pub board_id: String,
+    pub target_board_id: Option<String>,
+    pub project_id: String,
+    pub name: String,
+    pub user_input: String,
+    pub agent_pref: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartPlannerRequest {
+    pub agent_kind: Option<AgentKind>,
+    pub max_explorations: Option<usize>,
+    pub auto_approve: Option<bool>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannerStartedResponse {
+    pub scratchpad_id: String,
+    pub agent_kind: AgentKind,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutePlanResponse {
+    pub scratchpad_id: String,
+    pub epic_ids: Vec<String>,
+    pub ticket_ids: Vec<String>,
+}
+
+// ===== Task Types =====
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTaskRequest {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    /// Preset to add instead of a custom task (e.g. "add_tests")
+    pub preset_type: Option<String>,
+}
+
+// ===== Worker Types =====
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartWorkerResponse {
+    pub worker_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopWorkerResponse {
+    pub stopped: bool,
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaseResultResponse {
+    pub lease: TicketLease,
+    pub run: AgentRun,
 }
 
 pub const LOCK_DURATION_MINUTES: i64 = 30;
 pub const HEARTBEAT_INTERVAL_SECS: u64 = 60;
+/// How long a remote worker's lease lasts without a heartbeat
+pub const REMOTE_LEASE_SECS: i64 = 120;
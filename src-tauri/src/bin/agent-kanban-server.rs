@@ -0,0 +1,72 @@
+//! Headless Agent Kanban server: API, workers and planner without a window.
+//!
+//! Usage: `agent-kanban-server [--config <path>] [--check-migrations]`
+
+use std::path::PathBuf;
+
+use agent_kanban::db::migrations;
+use agent_kanban::headless::{self, ServerConfig};
+use agent_kanban::logging;
+
+const USAGE: &str = "Usage: agent-kanban-server [--config <path>] [--check-migrations]";
+
+fn parse_args() -> Result<Option<PathBuf>, String> {
+    let mut args = std::env::args().skip(1);
+    let mut config_path = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--config" => {
+                let path = args.next().ok_or("--config requires a path")?;
+                config_path = Some(PathBuf::from(path));
+            }
+            "--check-migrations" => check_migrations(),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+
+    Ok(config_path.or_else(|| std::env::var_os("AGENT_KANBAN_SERVER_CONFIG").map(PathBuf::from)))
+}
+
+/// Verify that upgrading an old database yields the same schema as a fresh one
+fn check_migrations() -> ! {
+    match migrations::check_migrations() {
+        Ok(()) => {
+            println!("Migrations OK: schema version {}", migrations::latest_version());
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match parse_args() {
+        Ok(Some(path)) => ServerConfig::load(&path),
+        Ok(None) => Ok(ServerConfig::default()),
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let config = config.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+
+    if let Err(e) = logging::init_logging(config.data_dir.clone()) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    if let Err(e) = headless::run(config).await {
+        tracing::error!("{}", e);
+        std::process::exit(1);
+    }
+}
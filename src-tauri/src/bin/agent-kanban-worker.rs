@@ -0,0 +1,56 @@
+//! Remote Agent Kanban worker: leases tickets from a server over the REST API
+//! and runs agents on this machine.
+//!
+//! Usage: `agent-kanban-worker --config <path>`
+
+use std::path::PathBuf;
+
+use agent_kanban::remote_worker::{self, RemoteWorkerConfig};
+
+const USAGE: &str = "Usage: agent-kanban-worker --config <path>";
+
+fn parse_args() -> Result<PathBuf, String> {
+    let mut args = std::env::args().skip(1);
+    let mut config_path = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--config" => {
+                let path = args.next().ok_or("--config requires a path")?;
+                config_path = Some(PathBuf::from(path));
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+
+    config_path
+        .or_else(|| std::env::var_os("AGENT_KANBAN_WORKER_CONFIG").map(PathBuf::from))
+        .ok_or_else(|| "A config file is required".to_string())
+}
+
+#[tokio::main]
+async fn main() {
+    let config = parse_args()
+        .map_err(|e| format!("{}\n{}", e, USAGE))
+        .and_then(|path| RemoteWorkerConfig::load(&path).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        });
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    if let Err(e) = remote_worker::run(config).await {
+        tracing::error!("{}", e);
+        std::process::exit(1);
+    }
+}
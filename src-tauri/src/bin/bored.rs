@@ -0,0 +1,16 @@
+//! `bored`: command-line client for the Agent Kanban API.
+//!
+//! Usage: `bored [--json] <ticket|run|queue|worker|plan> ...`
+
+use clap::Parser;
+
+use agent_kanban::cli::{self, Cli};
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = cli::run(cli).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,182 @@
+//! Thin HTTP client for the local API.
+
+use futures::StreamExt;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const TOKEN_HEADER: &str = "X-AgentKanban-Token";
+
+/// Error response from the API. Client methods return it inside
+/// `anyhow::Error`; callers that act on the status get it back with
+/// [`ApiError::status_of`].
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    /// Status of the API response behind `error`, if it came from one
+    pub fn status_of(error: &anyhow::Error) -> Option<StatusCode> {
+        error.downcast_ref::<ApiError>().map(|e| e.status)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.status)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    pub fn new(base_url: &str, token: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}{}", self.base_url, path))
+            .header(TOKEN_HEADER, &self.token)
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let response = send(self.request(Method::GET, path)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> anyhow::Result<T> {
+        let response = send(self.request(Method::POST, path).json(body)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> anyhow::Result<T> {
+        let response = send(self.request(Method::PUT, path).json(body)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let response = send(self.request(Method::DELETE, path)).await?;
+        Ok(response.json().await?)
+    }
+
+    /// `POST` a raw JSON body; returns `None` when the response has no body
+    pub async fn post_raw(&self, path: &str, body: String) -> anyhow::Result<Option<String>> {
+        let response = send(
+            self.request(Method::POST, path)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body),
+        ).await?;
+        let text = response.text().await?;
+        Ok((!text.trim().is_empty()).then_some(text))
+    }
+
+    /// `DELETE` for endpoints that answer with an empty body
+    pub async fn delete_empty(&self, path: &str) -> anyhow::Result<()> {
+        send(self.request(Method::DELETE, path)).await?;
+        Ok(())
+    }
+
+    /// Open an SSE stream and return a receiver of `data:` payloads.
+    ///
+    /// The receiver closes when the connection drops.
+    pub async fn stream(&self, path: &str) -> anyhow::Result<tokio::sync::mpsc::Receiver<String>> {
+        let response = send(self.request(Method::GET, path)).await?;
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+
+        tokio::spawn(async move {
+            let mut parser = SseParser::default();
+            let mut body = response.bytes_stream();
+            while let Some(Ok(chunk)) = body.next().await {
+                for message in parser.push(&String::from_utf8_lossy(&chunk)) {
+                    if tx.send(message).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+}
+
+async fn send(request: RequestBuilder) -> anyhow::Result<Response> {
+    let response = request.send().await.map_err(|e| {
+        if e.is_connect() {
+            anyhow::anyhow!("Cannot reach the Agent Kanban API ({}). Is the app or agent-kanban-server running?", e)
+        } else {
+            e.into()
+        }
+    })?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
+        .unwrap_or(body);
+    Err(ApiError { status, message }.into())
+}
+
+/// Incremental parser for `text/event-stream` bodies.
+///
+/// Only `data:` fields are kept; multi-line data is joined with `\n` and
+/// comments (`: ping`) are ignored.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: String,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &str) -> Vec<String> {
+        self.buffer.push_str(chunk);
+        let mut messages = Vec::new();
+
+        while let Some(pos) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=pos).collect();
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    messages.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_parser_splits_messages_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push("data: {\"a\"").is_empty());
+        assert_eq!(parser.push(":1}\n\n: ping\n\ndata: x\r\n"), vec!["{\"a\":1}"]);
+        assert_eq!(parser.push("data: y\n\n"), vec!["x\ny"]);
+    }
+}
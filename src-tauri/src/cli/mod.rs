@@ -0,0 +1,895 @@
+//! `bored` command-line client.
+//!
+//! Everything goes through the local REST API, so the terminal and the
+//! desktop app (or `agent-kanban-server`) work on the same board. The API URL
+//! and token are resolved with [`resolve_api_config`], the same lookup the app
+//! uses for `get_api_config`.
+
+pub mod client;
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::agents::slots::ConcurrencyStatus;
+use crate::agents::AgentKind;
+use crate::api::types::{
+    PlannerStartedResponse, QueueStatusResponse, StartWorkerResponse, StopWorkerResponse,
+};
+use crate::agents::worker::WorkerStatus;
+use crate::commands::resolve_api_config;
+use crate::db::scheduling::{QueueExplanation, SchedulingPolicy};
+use crate::db::{
+    AgentEvent, AgentPref, AgentRun, Board, Column, Comment, Priority, Project, RunStatus,
+    Scratchpad, Ticket,
+};
+use client::ApiClient;
+
+/// How often `run tail` re-reads the run when the SSE stream is quiet
+const TAIL_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Parser)]
+#[command(name = "bored", version, about = "Command-line client for the Agent Kanban board")]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Print JSON instead of human-readable output
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// API base URL (defaults to the running app's)
+    #[arg(long, global = true)]
+    pub url: Option<String>,
+
+    /// API token (defaults to the running app's)
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+
+    /// App data directory containing `api_port` and `api_token`
+    #[arg(long, global = true)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Create, list, move and inspect tickets
+    #[command(subcommand)]
+    Ticket(TicketCommand),
+    /// Follow agent runs
+    #[command(subcommand)]
+    Run(RunCommand),
+    /// Inspect the work queue
+    #[command(subcommand)]
+    Queue(QueueCommand),
+    /// Start and stop workers
+    #[command(subcommand)]
+    Worker(WorkerCommand),
+    /// Plan work with the planner agent
+    #[command(subcommand)]
+    Plan(PlanCommand),
+    /// Serve the board's MCP tools over stdio, scoped to one ticket
+    Mcp {
+        /// Ticket id (defaults to $AGENT_KANBAN_TICKET_ID)
+        #[arg(long)]
+        ticket: Option<String>,
+        /// Run id (defaults to $AGENT_KANBAN_RUN_ID)
+        #[arg(long)]
+        run: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TicketCommand {
+    /// Create a ticket
+    Create {
+        title: String,
+        /// Board id or name (optional when there is only one board)
+        #[arg(long)]
+        board: Option<String>,
+        /// Column id, name or role (`backlog`, `ready`, ...)
+        #[arg(long, default_value = "backlog")]
+        column: String,
+        #[arg(long, short = 'd', default_value = "")]
+        description: String,
+        #[arg(long, default_value = "medium", value_parser = parse_priority)]
+        priority: Priority,
+        /// Label to attach (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+        /// Project id or name
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long, value_parser = parse_agent_pref)]
+        agent: Option<AgentPref>,
+    },
+    /// List tickets on a board
+    List {
+        /// Board id or name (optional when there is only one board)
+        #[arg(long)]
+        board: Option<String>,
+        /// Only show tickets in this column (id, name or role)
+        #[arg(long)]
+        column: Option<String>,
+    },
+    /// Move a ticket to another column
+    Move {
+        ticket_id: String,
+        /// Column id, name or role
+        column: String,
+    },
+    /// Show a ticket with its comments and runs
+    Show { ticket_id: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RunCommand {
+    /// Print a run's events and follow it until it ends
+    Tail { run_id: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QueueCommand {
+    /// Ready and in-progress counts per board
+    Status,
+    /// Show which ticket a worker would take next, and why
+    Explain {
+        /// Only consider tickets for this project (id or name)
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long, value_parser = parse_agent_kind)]
+        agent: Option<AgentKind>,
+        /// How many ranked tickets to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show the scheduling policy
+    Policy,
+    /// Replace the scheduling policy with a JSON object,
+    /// e.g. '{"policy":"priority_aging","hoursPerLevel":12}'
+    SetPolicy { policy: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WorkerCommand {
+    /// List running workers
+    List,
+    /// Start a worker
+    Start {
+        #[arg(value_parser = parse_agent_kind)]
+        agent: AgentKind,
+        /// Only pick up tickets for this project (id or name)
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Stop a worker, or all of them with --all
+    Stop {
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        worker_id: Option<String>,
+        #[arg(long)]
+        all: bool,
+    },
+    /// Keep a worker from taking new tickets
+    Pause {
+        worker_id: String,
+    },
+    /// Let a paused worker take tickets again
+    Resume {
+        worker_id: String,
+    },
+    /// Finish the current ticket, then stop
+    Drain {
+        worker_id: String,
+    },
+    /// Show the concurrency limits and slot usage, or replace the limits
+    /// with a JSON object, e.g. '{"maxTotal":4,"perAgent":{"claude":2}}'
+    Limits {
+        #[arg(long)]
+        set: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PlanCommand {
+    /// Create a scratchpad from a prompt and start the planner on it
+    New {
+        prompt: String,
+        /// Board id or name (optional when there is only one board)
+        #[arg(long)]
+        board: Option<String>,
+        /// Project id or name (defaults to the board's default project)
+        #[arg(long)]
+        project: Option<String>,
+        /// Scratchpad name (defaults to the start of the prompt)
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long, value_parser = parse_agent_kind)]
+        agent: Option<AgentKind>,
+        #[arg(long)]
+        model: Option<String>,
+    },
+}
+
+fn parse_priority(s: &str) -> Result<Priority, String> {
+    Priority::parse(s).ok_or_else(|| "expected low, medium, high or urgent".to_string())
+}
+
+fn parse_agent_pref(s: &str) -> Result<AgentPref, String> {
+    AgentPref::parse(s).ok_or_else(|| "expected cursor, claude or any".to_string())
+}
+
+fn parse_agent_kind(s: &str) -> Result<AgentKind, String> {
+    AgentKind::parse(s).ok_or_else(|| "expected cursor or claude".to_string())
+}
+
+/// Run a parsed command line.
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let config = resolve_api_config(Some(
+        &cli.global.data_dir.clone().unwrap_or_else(crate::headless::default_data_dir),
+    ));
+    let url = cli.global.url.clone().unwrap_or(config.url);
+    let token = cli.global.token.clone().unwrap_or(config.token);
+    let ctx = Context { api: ApiClient::new(&url, &token), json: cli.global.json };
+
+    match cli.command {
+        Command::Ticket(command) => ctx.ticket(command).await,
+        Command::Run(RunCommand::Tail { run_id }) => ctx.tail_run(&run_id).await,
+        Command::Queue(command) => ctx.queue(command).await,
+        Command::Worker(command) => ctx.worker(command).await,
+        Command::Plan(command) => ctx.plan(command).await,
+        Command::Mcp { ticket, run } => {
+            let ticket = ticket.or_else(|| std::env::var("AGENT_KANBAN_TICKET_ID").ok());
+            let run = run.or_else(|| std::env::var("AGENT_KANBAN_RUN_ID").ok());
+            ctx.serve_mcp(ticket.as_deref(), run.as_deref()).await
+        }
+    }
+}
+
+struct Context {
+    api: ApiClient,
+    json: bool,
+}
+
+impl Context {
+    /// Print `value` as JSON, or `text` otherwise
+    fn output<T: Serialize>(&self, value: &T, text: impl FnOnce() -> String) -> anyhow::Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        } else {
+            print!("{}", text());
+        }
+        Ok(())
+    }
+
+    async fn ticket(&self, command: TicketCommand) -> anyhow::Result<()> {
+        match command {
+            TicketCommand::Create { title, board, column, description, priority, labels, project, agent } => {
+                let board = self.resolve_board(board.as_deref()).await?;
+                let columns = self.columns(&board.id).await?;
+                let column = find_column(&columns, &column)?;
+                let project_id = match project {
+                    Some(p) => Some(self.resolve_project(&p).await?.id),
+                    None => None,
+                };
+
+                let ticket: Ticket = self.api.post("/v1/tickets", &json!({
+                    "boardId": board.id,
+                    "columnId": column.id,
+                    "title": title,
+                    "descriptionMd": description,
+                    "priority": priority,
+                    "labels": labels,
+                    "projectId": project_id,
+                    "agentPref": agent,
+                })).await?;
+                self.output(&ticket, || format!("Created ticket {} in {}\n", ticket.id, column.name))
+            }
+            TicketCommand::List { board, column } => {
+                let board = self.resolve_board(board.as_deref()).await?;
+                let columns = self.columns(&board.id).await?;
+                let path = match column {
+                    Some(c) => format!("/v1/boards/{}/tickets?column={}", board.id, find_column(&columns, &c)?.id),
+                    None => format!("/v1/boards/{}/tickets", board.id),
+                };
+                let tickets: Vec<Ticket> = self.api.get(&path).await?;
+                self.output(&tickets, || format_ticket_table(&tickets, &columns))
+            }
+            TicketCommand::Move { ticket_id, column } => {
+                let ticket: Ticket = self.api.get(&format!("/v1/tickets/{}", ticket_id)).await?;
+                let columns = self.columns(&ticket.board_id).await?;
+                let column = find_column(&columns, &column)?;
+                let moved: Ticket = self.api.post(
+                    &format!("/v1/tickets/{}/move", ticket_id),
+                    &json!({ "columnId": column.id }),
+                ).await?;
+                self.output(&moved, || format!("Moved {} to {}\n", moved.id, column.name))
+            }
+            TicketCommand::Show { ticket_id } => {
+                let ticket: Ticket = self.api.get(&format!("/v1/tickets/{}", ticket_id)).await?;
+                let columns = self.columns(&ticket.board_id).await?;
+                let comments: Vec<Comment> = self.api.get(&format!("/v1/tickets/{}/comments", ticket_id)).await?;
+                let runs: Vec<AgentRun> = self.api.get(&format!("/v1/tickets/{}/runs", ticket_id)).await?;
+
+                let value = json!({ "ticket": ticket, "comments": comments, "runs": runs });
+                self.output(&value, || format_ticket_details(&ticket, &columns, &comments, &runs))
+            }
+        }
+    }
+
+    async fn tail_run(&self, run_id: &str) -> anyhow::Result<()> {
+        let mut seen = HashSet::new();
+        let mut stream = self.api
+            .stream(&format!("/v1/stream/filtered?run_id={}", run_id))
+            .await
+            .ok();
+
+        loop {
+            let events: Vec<AgentEvent> = self.api.get(&format!("/v1/runs/{}/events", run_id)).await?;
+            for event in events.iter().filter(|e| seen.insert(e.id.clone())) {
+                if self.json {
+                    println!("{}", serde_json::to_string(event)?);
+                } else {
+                    println!("{}", format_event(event));
+                }
+            }
+
+            let run: AgentRun = self.api.get(&format!("/v1/runs/{}", run_id)).await?;
+            if is_terminal(&run.status) {
+                if !self.json {
+                    println!("Run {} {}", run.id, run.status.as_str());
+                }
+                return Ok(());
+            }
+
+            // Wait for the next live message (printing agent output as it
+            // arrives) or fall back to polling.
+            let deadline = tokio::time::sleep(TAIL_POLL_INTERVAL);
+            tokio::pin!(deadline);
+            loop {
+                let message = match stream.as_mut() {
+                    Some(rx) => tokio::select! {
+                        message = rx.recv() => message,
+                        _ = &mut deadline => break,
+                    },
+                    None => {
+                        deadline.as_mut().await;
+                        break;
+                    }
+                };
+                let Some(message) = message else {
+                    stream = None;
+                    continue;
+                };
+                let Ok(live) = serde_json::from_str::<serde_json::Value>(&message) else {
+                    continue;
+                };
+                if live["type"] == "agent_event" && live["name"] == "agent-log" {
+                    if self.json {
+                        println!("{}", message);
+                    } else if let Some(content) = live["payload"]["content"].as_str() {
+                        println!("{}", content.trim_end());
+                    }
+                } else if live["type"] != "agent_event" {
+                    // Stored events or status changed; re-read them now
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn queue(&self, command: QueueCommand) -> anyhow::Result<()> {
+        match command {
+            QueueCommand::Status => {
+                let status: QueueStatusResponse = self.api.get("/v1/queue/status").await?;
+                self.output(&status, || {
+                    let mut out = format!("Ready: {}  In progress: {}\n", status.ready_count, status.in_progress_count);
+                    for board in &status.boards {
+                        out.push_str(&format!("  {:<30} {}\n", board.board_name, board.ready_count));
+                    }
+                    out
+                })
+            }
+            QueueCommand::Explain { project, agent, limit } => {
+                let mut path = format!("/v1/queue/explain?limit={}", limit);
+                if let Some(p) = project {
+                    path.push_str(&format!("&projectId={}", self.resolve_project(&p).await?.id));
+                }
+                if let Some(agent) = agent {
+                    path.push_str(&format!("&agentType={}", agent.as_str()));
+                }
+                let explanation: QueueExplanation = self.api.get(&path).await?;
+                self.output(&explanation, || format_queue_explanation(&explanation))
+            }
+            QueueCommand::Policy => {
+                let policy: SchedulingPolicy = self.api.get("/v1/queue/policy").await?;
+                self.output(&policy, || format!("{}\n", serde_json::to_string(&policy).unwrap_or_default()))
+            }
+            QueueCommand::SetPolicy { policy } => {
+                let body: serde_json::Value = serde_json::from_str(&policy)
+                    .map_err(|e| anyhow::anyhow!("policy must be a JSON object: {}", e))?;
+                let policy: SchedulingPolicy = self.api.put("/v1/queue/policy", &body).await?;
+                self.output(&policy, || format!("Scheduling policy set to {}\n", serde_json::to_string(&policy).unwrap_or_default()))
+            }
+        }
+    }
+
+    async fn worker(&self, command: WorkerCommand) -> anyhow::Result<()> {
+        match command {
+            WorkerCommand::List => {
+                let workers: Vec<WorkerStatus> = self.api.get("/v1/workers").await?;
+                self.output(&workers, || format_worker_table(&workers))
+            }
+            WorkerCommand::Start { agent, project } => {
+                let project_id = match project {
+                    Some(p) => Some(self.resolve_project(&p).await?.id),
+                    None => None,
+                };
+                let started: StartWorkerResponse = self.api.post(
+                    "/v1/workers",
+                    &json!({ "agentType": agent, "projectId": project_id }),
+                ).await?;
+                self.output(&started, || format!("Started {} worker {}\n", agent.as_str(), started.worker_id))
+            }
+            WorkerCommand::Stop { all: true, .. } => {
+                self.api.delete_empty("/v1/workers").await?;
+                self.output(&json!({ "stopped": true }), || "Stopped all workers\n".to_string())
+            }
+            WorkerCommand::Stop { worker_id, .. } => {
+                let worker_id = worker_id.unwrap_or_default();
+                let stopped: StopWorkerResponse = self.api.delete(&format!("/v1/workers/{}", worker_id)).await?;
+                self.output(&stopped, || format!("Stopped worker {}\n", stopped.id))
+            }
+            WorkerCommand::Pause { worker_id } => {
+                let status: WorkerStatus = self.api.post(&format!("/v1/workers/{}/pause", worker_id), &json!({})).await?;
+                self.output(&status, || format!("Paused worker {}\n", status.id))
+            }
+            WorkerCommand::Resume { worker_id } => {
+                let status: WorkerStatus = self.api.post(&format!("/v1/workers/{}/resume", worker_id), &json!({})).await?;
+                self.output(&status, || format!("Resumed worker {} ({})\n", status.id, status.status.as_str()))
+            }
+            WorkerCommand::Drain { worker_id } => {
+                let status: WorkerStatus = self.api.post(&format!("/v1/workers/{}/drain", worker_id), &json!({})).await?;
+                self.output(&status, || format!("Draining worker {}; it stops after the current ticket\n", status.id))
+            }
+            WorkerCommand::Limits { set } => {
+                let status: ConcurrencyStatus = match set {
+                    Some(limits) => {
+                        let body: serde_json::Value = serde_json::from_str(&limits)
+                            .map_err(|e| anyhow::anyhow!("limits must be a JSON object: {}", e))?;
+                        self.api.put("/v1/concurrency", &body).await?
+                    }
+                    None => self.api.get("/v1/concurrency").await?,
+                };
+                self.output(&status, || format_concurrency(&status))
+            }
+        }
+    }
+
+    async fn plan(&self, command: PlanCommand) -> anyhow::Result<()> {
+        let PlanCommand::New { prompt, board, project, name, agent, model } = command;
+
+        let board = self.resolve_board(board.as_deref()).await?;
+        let project_id = match project.or_else(|| board.default_project_id.clone()) {
+            Some(p) => self.resolve_project(&p).await?.id,
+            None => anyhow::bail!("Board '{}' has no default project; pass --project", board.name),
+        };
+
+        let scratchpad: Scratchpad = self.api.post("/v1/scratchpads", &json!({
+            "boardId": board.id,
+            "projectId": project_id,
+            "name": name.unwrap_or_else(|| scratchpad_name(&prompt)),
+            "userInput": prompt,
+            "agentPref": agent.map(|a| a.as_str()),
+            "model": model,
+        })).await?;
+
+        let started: PlannerStartedResponse = self.api.post(
+            &format!("/v1/scratchpads/{}/planner", scratchpad.id),
+            &json!({ "agentKind": agent, "model": model }),
+        ).await?;
+
+        self.output(&started, || format!(
+            "Planner ({}) started on scratchpad {}\n",
+            started.agent_kind.as_str(),
+            started.scratchpad_id,
+        ))
+    }
+
+    /// Bridge newline-delimited JSON-RPC on stdin/stdout to `/v1/mcp`
+    async fn serve_mcp(&self, ticket_id: Option<&str>, run_id: Option<&str>) -> anyhow::Result<()> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let path = mcp_path(ticket_id, run_id);
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let reply = match self.api.post_raw(&path, line.clone()).await {
+                Ok(reply) => reply,
+                Err(e) => mcp_transport_error(&line, &e.to_string()),
+            };
+            if let Some(reply) = reply {
+                // stdio framing is one message per line
+                let reply: serde_json::Value = serde_json::from_str(&reply)?;
+                stdout.write_all(format!("{}\n", reply).as_bytes()).await?;
+                stdout.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn resolve_board(&self, board: Option<&str>) -> anyhow::Result<Board> {
+        let boards: Vec<Board> = self.api.get("/v1/boards").await?;
+        match board {
+            Some(key) => boards
+                .into_iter()
+                .find(|b| b.id == key || b.name.eq_ignore_ascii_case(key))
+                .ok_or_else(|| anyhow::anyhow!("No board with id or name '{}'", key)),
+            None if boards.len() == 1 => Ok(boards.into_iter().next().unwrap()),
+            None if boards.is_empty() => anyhow::bail!("There are no boards yet"),
+            None => anyhow::bail!(
+                "Several boards exist; pass --board ({})",
+                boards.iter().map(|b| b.name.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    async fn resolve_project(&self, key: &str) -> anyhow::Result<Project> {
+        let projects: Vec<Project> = self.api.get("/v1/projects").await?;
+        projects
+            .into_iter()
+            .find(|p| p.id == key || p.name.eq_ignore_ascii_case(key))
+            .ok_or_else(|| anyhow::anyhow!("No project with id or name '{}'", key))
+    }
+
+    async fn columns(&self, board_id: &str) -> anyhow::Result<Vec<Column>> {
+        self.api.get(&format!("/v1/boards/{}/columns", board_id)).await
+    }
+}
+
+/// Resolve a column by id or name, falling back to its role so `ready`
+/// still works on boards that renamed the column.
+fn find_column<'a>(columns: &'a [Column], key: &str) -> anyhow::Result<&'a Column> {
+    columns
+        .iter()
+        .find(|c| c.id == key || c.name.eq_ignore_ascii_case(key))
+        .or_else(|| columns.iter().find(|c| c.role.is_some_and(|r| r.as_str() == key)))
+        .ok_or_else(|| anyhow::anyhow!(
+            "No column '{}' (have: {})",
+            key,
+            columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+        ))
+}
+
+fn column_name<'a>(columns: &'a [Column], id: &'a str) -> &'a str {
+    columns.iter().find(|c| c.id == id).map(|c| c.name.as_str()).unwrap_or(id)
+}
+
+fn mcp_path(ticket_id: Option<&str>, run_id: Option<&str>) -> String {
+    let params: Vec<String> = [("ticket_id", ticket_id), ("run_id", run_id)]
+        .iter()
+        .filter_map(|(key, value)| value.map(|v| format!("{}={}", key, v)))
+        .collect();
+    if params.is_empty() {
+        "/v1/mcp".to_string()
+    } else {
+        format!("/v1/mcp?{}", params.join("&"))
+    }
+}
+
+/// JSON-RPC error reply for a request the API could not answer
+fn mcp_transport_error(request: &str, message: &str) -> Option<String> {
+    let id = serde_json::from_str::<serde_json::Value>(request).ok()?.get("id")?.clone();
+    Some(json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32603, "message": message },
+    }).to_string())
+}
+
+fn is_terminal(status: &RunStatus) -> bool {
+    matches!(status, RunStatus::Finished | RunStatus::Error | RunStatus::Aborted)
+}
+
+fn scratchpad_name(prompt: &str) -> String {
+    let first_line = prompt.lines().next().unwrap_or("").trim();
+    let mut name: String = first_line.chars().take(60).collect();
+    if name.len() < first_line.len() {
+        name.push_str("...");
+    }
+    if name.is_empty() {
+        "CLI plan".to_string()
+    } else {
+        name
+    }
+}
+
+fn format_ticket_table(tickets: &[Ticket], columns: &[Column]) -> String {
+    if tickets.is_empty() {
+        return "No tickets\n".to_string();
+    }
+    let mut out = format!("{:<36}  {:<12}  {:<8}  {}\n", "ID", "COLUMN", "PRIORITY", "TITLE");
+    for t in tickets {
+        out.push_str(&format!(
+            "{:<36}  {:<12}  {:<8}  {}{}\n",
+            t.id,
+            column_name(columns, &t.column_id),
+            t.priority.as_str(),
+            t.title,
+            if t.locked_by_run_id.is_some() { " [locked]" } else { "" },
+        ));
+    }
+    out
+}
+
+fn format_ticket_details(ticket: &Ticket, columns: &[Column], comments: &[Comment], runs: &[AgentRun]) -> String {
+    let mut out = format!("{}\n{}\n", ticket.title, "=".repeat(ticket.title.chars().count()));
+    out.push_str(&format!("ID:       {}\n", ticket.id));
+    out.push_str(&format!("Column:   {}\n", column_name(columns, &ticket.column_id)));
+    out.push_str(&format!("Priority: {}\n", ticket.priority.as_str()));
+    if !ticket.labels.is_empty() {
+        out.push_str(&format!("Labels:   {}\n", ticket.labels.join(", ")));
+    }
+    if let Some(agent) = &ticket.agent_pref {
+        out.push_str(&format!("Agent:    {}\n", agent.as_str()));
+    }
+    if let Some(run_id) = &ticket.locked_by_run_id {
+        out.push_str(&format!("Locked by run {}\n", run_id));
+    }
+    if !ticket.description_md.trim().is_empty() {
+        out.push_str(&format!("\n{}\n", ticket.description_md.trim_end()));
+    }
+
+    if !runs.is_empty() {
+        out.push_str("\nRuns:\n");
+        for run in runs {
+            out.push_str(&format!(
+                "  {}  {:<8}  {:<10}  {}{}\n",
+                run.id,
+                run.agent_type.as_str(),
+                run.status.as_str(),
+                run.started_at.format("%Y-%m-%d %H:%M"),
+                run.stage.as_deref().map(|s| format!("  ({})", s)).unwrap_or_default(),
+            ));
+        }
+    }
+
+    if !comments.is_empty() {
+        out.push_str("\nComments:\n");
+        for comment in comments {
+            out.push_str(&format!(
+                "  [{}] {}: {}\n",
+                comment.created_at.format("%Y-%m-%d %H:%M"),
+                comment.author_type.as_str(),
+                comment.body_md.trim_end().replace('\n', "\n    "),
+            ));
+        }
+    }
+    out
+}
+
+fn format_worker_table(workers: &[WorkerStatus]) -> String {
+    if workers.is_empty() {
+        return "No workers running\n".to_string();
+    }
+    let mut out = format!("{:<36}  {:<7}  {:<12}  {:>9}  {}\n", "ID", "AGENT", "STATE", "PROCESSED", "TICKET");
+    for w in workers {
+        out.push_str(&format!(
+            "{:<36}  {:<7}  {:<12}  {:>9}  {}\n",
+            w.id,
+            w.agent_type,
+            w.status.as_str(),
+            w.tickets_processed,
+            w.current_ticket_id.as_deref().unwrap_or("-"),
+        ));
+    }
+    out
+}
+
+fn format_concurrency(status: &ConcurrencyStatus) -> String {
+    let limit = |max: Option<u32>| max.map_or("unlimited".to_string(), |m| m.to_string());
+    let mut out = format!("Agents running: {} (limit {})\n", status.running, limit(status.limits.max_total));
+    let running = |counts: &BTreeMap<String, u32>, key: &str| counts.get(key).copied().unwrap_or(0);
+    for (agent, max) in &status.limits.per_agent {
+        out.push_str(&format!("  {:<20} {}/{}\n", agent, running(&status.running_per_agent, agent), max));
+    }
+    for (model, max) in &status.limits.per_model {
+        out.push_str(&format!("  {:<20} {}/{}\n", model, running(&status.running_per_model, model), max));
+    }
+    for waiting in &status.waiting {
+        out.push_str(&format!("Waiting: ticket {}: {}\n", waiting.ticket_id, waiting.reason));
+    }
+    out
+}
+
+fn format_queue_explanation(explanation: &QueueExplanation) -> String {
+    let policy = serde_json::to_value(&explanation.policy)
+        .ok()
+        .and_then(|v| v["policy"].as_str().map(String::from))
+        .unwrap_or_default();
+    let mut out = format!("Policy: {}  Eligible: {}\n", policy, explanation.eligible);
+    match &explanation.next {
+        Some(next) => out.push_str(&format!("Next: {} {} ({})\n", next.ticket_id, next.title, next.board_name)),
+        None => out.push_str("Next: nothing eligible\n"),
+    }
+    for (i, entry) in explanation.ranking.iter().enumerate() {
+        out.push_str(&format!(
+            "{:>3}. {:<36}  {:<8}  {}\n     {}\n",
+            i + 1,
+            entry.ticket_id,
+            entry.effective_priority.as_ref().unwrap_or(&entry.priority).as_str(),
+            entry.title,
+            entry.reason,
+        ));
+    }
+    out
+}
+
+fn format_event(event: &AgentEvent) -> String {
+    let detail = event.payload.structured.as_ref()
+        .and_then(|s| ["command", "filePath", "path", "message", "error"]
+            .iter()
+            .find_map(|key| s.get(*key).and_then(|v| v.as_str()).map(String::from)))
+        .or_else(|| event.payload.raw.clone())
+        .unwrap_or_default();
+    format!(
+        "{}  {:<18}  {}",
+        event.created_at.format("%H:%M:%S"),
+        event.event_type.as_str(),
+        detail.lines().next().unwrap_or(""),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use clap::CommandFactory;
+
+    fn column(id: &str, name: &str) -> Column {
+        Column {
+            id: id.to_string(),
+            board_id: "b1".to_string(),
+            name: name.to_string(),
+            position: 0,
+            wip_limit: None,
+            role: crate::lifecycle::TicketState::from_column_name(name),
+        }
+    }
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_ticket_create() {
+        let cli = Cli::try_parse_from([
+            "bored", "--json", "ticket", "create", "Fix login",
+            "--column", "ready", "--priority", "high", "--label", "bug", "--label", "auth",
+        ]).unwrap();
+
+        assert!(cli.global.json);
+        match cli.command {
+            Command::Ticket(TicketCommand::Create { title, column, priority, labels, agent, .. }) => {
+                assert_eq!(title, "Fix login");
+                assert_eq!(column, "ready");
+                assert_eq!(priority, Priority::High);
+                assert_eq!(labels, vec!["bug", "auth"]);
+                assert!(agent.is_none());
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn worker_stop_needs_id_or_all() {
+        assert!(Cli::try_parse_from(["bored", "worker", "stop"]).is_err());
+        assert!(Cli::try_parse_from(["bored", "worker", "stop", "w1", "--all"]).is_err());
+        assert!(Cli::try_parse_from(["bored", "worker", "stop", "--all"]).is_ok());
+        assert!(Cli::try_parse_from(["bored", "worker", "start", "gpt"]).is_err());
+    }
+
+    #[test]
+    fn parses_queue_subcommands() {
+        let cli = Cli::try_parse_from(["bored", "queue", "explain", "--agent", "claude"]).unwrap();
+        match cli.command {
+            Command::Queue(QueueCommand::Explain { project, agent, limit }) => {
+                assert!(project.is_none());
+                assert_eq!(agent, Some(AgentKind::Claude));
+                assert_eq!(limit, 10);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["bored", "queue", "set-policy"]).is_err());
+    }
+
+    #[test]
+    fn mcp_bridge_helpers() {
+        assert_eq!(mcp_path(None, None), "/v1/mcp");
+        assert_eq!(mcp_path(Some("t1"), Some("r1")), "/v1/mcp?ticket_id=t1&run_id=r1");
+        assert_eq!(mcp_path(None, Some("r1")), "/v1/mcp?run_id=r1");
+
+        let reply = mcp_transport_error(r#"{"jsonrpc":"2.0","id":7,"method":"ping"}"#, "down").unwrap();
+        let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["message"], "down");
+        // Notifications never get a reply
+        assert!(mcp_transport_error(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#, "down").is_none());
+    }
+
+    #[test]
+    fn find_column_matches_id_or_name() {
+        let columns = vec![column("c1", "Backlog"), column("c2", "In Progress")];
+        assert_eq!(find_column(&columns, "in progress").unwrap().id, "c2");
+        assert_eq!(find_column(&columns, "c1").unwrap().name, "Backlog");
+        let err = find_column(&columns, "Done").unwrap_err().to_string();
+        assert!(err.contains("Backlog, In Progress"));
+    }
+
+    #[test]
+    fn find_column_falls_back_to_role() {
+        let mut columns = vec![column("c1", "Backlog"), column("c2", "In Progress")];
+        columns[1].name = "Doing".to_string();
+        assert_eq!(find_column(&columns, "in_progress").unwrap().id, "c2");
+        assert_eq!(find_column(&columns, "backlog").unwrap().id, "c1");
+    }
+
+    #[test]
+    fn scratchpad_name_uses_first_line() {
+        assert_eq!(scratchpad_name("Add dark mode\nwith a toggle"), "Add dark mode");
+        assert_eq!(scratchpad_name(&"x".repeat(80)), format!("{}...", "x".repeat(60)));
+        assert_eq!(scratchpad_name("  "), "CLI plan");
+    }
+
+    #[test]
+    fn ticket_table_shows_column_names() {
+        let now = Utc::now();
+        let ticket = Ticket {
+            id: "t1".to_string(),
+            board_id: "b1".to_string(),
+            column_id: "c2".to_string(),
+            title: "Fix login".to_string(),
+            description_md: String::new(),
+            priority: Priority::Urgent,
+            labels: vec![],
+            created_at: now,
+            updated_at: now,
+            locked_by_run_id: Some("r1".to_string()),
+            lock_expires_at: None,
+            project_id: None,
+            agent_pref: None,
+            workflow_type: Default::default(),
+            model: None,
+            branch_name: None,
+            is_epic: false,
+            epic_id: None,
+            order_in_epic: None,
+            depends_on_epic_id: None,
+            depends_on_epic_ids: vec![],
+            scratchpad_id: None,
+            custom_fields: Default::default(),
+        };
+
+        let table = format_ticket_table(&[ticket], &[column("c2", "Ready")]);
+        let row = table.lines().nth(1).unwrap();
+        assert!(row.starts_with("t1 "));
+        assert!(row.contains("Ready"));
+        assert!(row.contains("urgent"));
+        assert!(row.ends_with("Fix login [locked]"));
+        assert_eq!(format_ticket_table(&[], &[]), "No tickets\n");
+    }
+}
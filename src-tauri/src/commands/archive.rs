@@ -0,0 +1,35 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::retention::RetentionPolicy;
+use crate::db::{Actor, ArchivedTicket, Database, RetentionReport, Ticket};
+
+#[tauri::command]
+pub async fn search_archived_tickets(
+    board_id: Option<String>,
+    query: Option<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<ArchivedTicket>, String> {
+    db.search_archived_tickets(board_id.as_deref(), query.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_archived_ticket(
+    ticket_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Ticket, String> {
+    tracing::info!("Restoring archived ticket: {}", ticket_id);
+    db.restore_archived_ticket(&ticket_id, &Actor::user()).map_err(|e| e.to_string())
+}
+
+/// Apply the default retention policy now
+#[tauri::command]
+pub async fn run_retention(db: State<'_, Arc<Database>>) -> Result<RetentionReport, String> {
+    tracing::info!("Running retention policy");
+    let db = db.inner().clone();
+    tokio::task::spawn_blocking(move || db.apply_retention(&RetentionPolicy::default()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
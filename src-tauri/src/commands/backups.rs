@@ -0,0 +1,25 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::api::backup::restore_with_workers_paused;
+use crate::db::{BackupInfo, Database};
+
+#[tauri::command]
+pub async fn list_backups(db: State<'_, Arc<Database>>) -> Result<Vec<BackupInfo>, String> {
+    db.list_backups().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_backup(db: State<'_, Arc<Database>>) -> Result<BackupInfo, String> {
+    tracing::info!("Creating manual backup");
+    db.create_backup("manual").map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    file_name: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<BackupInfo, String> {
+    tracing::warn!("Restoring backup: {}", file_name);
+    restore_with_workers_paused(&db, &file_name).await.map_err(|e| e.to_string())
+}
@@ -1,7 +1,12 @@
 use std::sync::Arc;
 use tauri::State;
+use tokio::sync::broadcast;
 
-use crate::db::{Board, Column, Database};
+use crate::api::state::LiveEvent;
+use crate::db::{
+    Actor, Board, BoardBundle, BoardImport, Column, CreateColumn, CreateCustomField, CreateLabel, CustomField, Database,
+    Label, TrashEntry, UpdateColumn, UpdateCustomField, UpdateLabel,
+};
 
 #[tauri::command]
 pub async fn get_boards(db: State<'_, Arc<Database>>) -> Result<Vec<Board>, String> {
@@ -18,6 +23,148 @@ pub async fn get_columns(
     db.get_columns(&board_id).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn create_column(
+    board_id: String,
+    input: CreateColumn,
+    db: State<'_, Arc<Database>>,
+    event_tx: State<'_, broadcast::Sender<LiveEvent>>,
+) -> Result<Column, String> {
+    tracing::info!("Creating column {} on board {}", input.name, board_id);
+    let column = db.create_column(&board_id, &input).map_err(|e| e.to_string())?;
+    let _ = event_tx.send(LiveEvent::ColumnsChanged { board_id });
+    Ok(column)
+}
+
+#[tauri::command]
+pub async fn update_column(
+    column_id: String,
+    input: UpdateColumn,
+    db: State<'_, Arc<Database>>,
+    event_tx: State<'_, broadcast::Sender<LiveEvent>>,
+) -> Result<Column, String> {
+    tracing::info!("Updating column {}", column_id);
+    let column = db.update_column(&column_id, &input).map_err(|e| e.to_string())?;
+    let _ = event_tx.send(LiveEvent::ColumnsChanged { board_id: column.board_id.clone() });
+    Ok(column)
+}
+
+#[tauri::command]
+pub async fn reorder_columns(
+    board_id: String,
+    column_ids: Vec<String>,
+    db: State<'_, Arc<Database>>,
+    event_tx: State<'_, broadcast::Sender<LiveEvent>>,
+) -> Result<Vec<Column>, String> {
+    tracing::info!("Reordering columns on board {}", board_id);
+    let columns = db.reorder_columns(&board_id, &column_ids).map_err(|e| e.to_string())?;
+    let _ = event_tx.send(LiveEvent::ColumnsChanged { board_id });
+    Ok(columns)
+}
+
+#[tauri::command]
+pub async fn delete_column(
+    column_id: String,
+    move_tickets_to: Option<String>,
+    db: State<'_, Arc<Database>>,
+    event_tx: State<'_, broadcast::Sender<LiveEvent>>,
+) -> Result<(), String> {
+    tracing::info!("Deleting column {}", column_id);
+    let column = db.get_column(&column_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Column {} not found", column_id))?;
+    db.delete_column(&column_id, move_tickets_to.as_deref()).map_err(|e| e.to_string())?;
+    let _ = event_tx.send(LiveEvent::ColumnsChanged { board_id: column.board_id });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_custom_fields(
+    board_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<CustomField>, String> {
+    db.get_custom_fields(&board_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_custom_field(
+    board_id: String,
+    input: CreateCustomField,
+    db: State<'_, Arc<Database>>,
+) -> Result<CustomField, String> {
+    tracing::info!("Creating custom field {} on board {}", input.key, board_id);
+    db.create_custom_field(&board_id, &input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_custom_field(
+    field_id: String,
+    input: UpdateCustomField,
+    db: State<'_, Arc<Database>>,
+) -> Result<CustomField, String> {
+    tracing::info!("Updating custom field {}", field_id);
+    db.update_custom_field(&field_id, &input).map_err(|e| e.to_string())
+}
+
+/// Delete a custom field and its values on the board's tickets
+#[tauri::command]
+pub async fn delete_custom_field(
+    field_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    tracing::info!("Deleting custom field {}", field_id);
+    db.delete_custom_field(&field_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_labels(
+    board_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<Label>, String> {
+    db.get_labels(&board_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_label(
+    board_id: String,
+    input: CreateLabel,
+    db: State<'_, Arc<Database>>,
+) -> Result<Label, String> {
+    tracing::info!("Creating label {} on board {}", input.name, board_id);
+    db.create_label(&board_id, &input).map_err(|e| e.to_string())
+}
+
+/// Change a label; a new name is applied to every ticket carrying it
+#[tauri::command]
+pub async fn update_label(
+    label_id: String,
+    input: UpdateLabel,
+    db: State<'_, Arc<Database>>,
+) -> Result<Label, String> {
+    tracing::info!("Updating label {}", label_id);
+    db.update_label(&label_id, &input, &Actor::user()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn merge_labels(
+    label_id: String,
+    into_label_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Label, String> {
+    tracing::info!("Merging label {} into {}", label_id, into_label_id);
+    db.merge_labels(&label_id, &into_label_id, &Actor::user()).map_err(|e| e.to_string())
+}
+
+/// Delete a label and remove it from the board's tickets
+#[tauri::command]
+pub async fn delete_label(
+    label_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    tracing::info!("Deleting label {}", label_id);
+    db.delete_label(&label_id, &Actor::user()).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn create_board(
     name: String,
@@ -37,27 +184,56 @@ pub async fn update_board(
     db.update_board(&board_id, &name).map_err(|e| e.to_string())
 }
 
+/// Move a board to the trash with its tickets and scratchpads, or delete it
+/// for good when `permanent`
 #[tauri::command]
 pub async fn delete_board(
     board_id: String,
+    permanent: Option<bool>,
     db: State<'_, Arc<Database>>,
-) -> Result<(), String> {
-    tracing::info!("Deleting board: {}", board_id);
-    db.delete_board(&board_id).map_err(|e| e.to_string())
+) -> Result<Option<TrashEntry>, String> {
+    if permanent.unwrap_or(false) {
+        tracing::info!("Permanently deleting board: {}", board_id);
+        db.delete_board(&board_id).map(|_| None).map_err(|e| e.to_string())
+    } else {
+        tracing::info!("Moving board to trash: {}", board_id);
+        db.trash_board(&board_id).map(Some).map_err(|e| e.to_string())
+    }
 }
 
 #[tauri::command]
-pub async fn factory_reset(
+pub async fn export_board(
+    board_id: String,
+    include_runs: bool,
     db: State<'_, Arc<Database>>,
-) -> Result<(), String> {
-    tracing::warn!("Factory reset requested");
-    db.factory_reset().map_err(|e| e.to_string())
+) -> Result<BoardBundle, String> {
+    tracing::info!("Exporting board {} (runs: {})", board_id, include_runs);
+    db.export_board(&board_id, include_runs).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn repair_scratchpads_table(
+pub async fn export_board_markdown(
+    board_id: String,
     db: State<'_, Arc<Database>>,
 ) -> Result<String, String> {
-    tracing::warn!("Repairing scratchpads table CHECK constraint");
-    db.repair_scratchpads_constraint().map_err(|e| e.to_string())
-}
\ No newline at end of file
+    tracing::info!("Exporting board {} as Markdown", board_id);
+    db.export_board_markdown(&board_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_board(
+    bundle: BoardBundle,
+    name: Option<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<BoardImport, String> {
+    tracing::info!("Importing board: {}", bundle.board.name);
+    db.import_board(&bundle, name.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn factory_reset(
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    tracing::warn!("Factory reset requested");
+    db.factory_reset().map_err(|e| e.to_string())
+}
@@ -1,18 +1,26 @@
+pub mod archive;
+pub mod backups;
 pub mod boards;
 pub mod claude;
 pub mod cursor;
 pub mod projects;
 pub mod runs;
 pub mod scratchpads;
+mod sink;
 pub mod tasks;
 pub mod tickets;
+pub mod trash;
 pub mod workers;
 
 pub use boards::*;
 pub use claude::*;
 pub use cursor::*;
 pub use projects::*;
-pub use runs::{start_agent_run, get_agent_runs, get_recent_runs, get_agent_run, get_run_events, cancel_agent_run};
+pub use runs::{
+    start_agent_run, get_agent_runs, get_recent_runs, get_agent_run, get_run_events, cancel_agent_run,
+    get_interrupted_runs, pause_agent_run, resume_agent_run, update_run_plan, get_run_checkpoint, rerun_agent_run, skip_agent_run_stage,
+    insert_agent_run_stage, get_recovery_settings, set_recovery_settings,
+};
 pub use scratchpads::{
     create_scratchpad, get_scratchpads, get_scratchpad, update_scratchpad, delete_scratchpad,
     set_scratchpad_status, append_exploration, set_scratchpad_plan, approve_plan,
@@ -25,7 +33,10 @@ pub use tasks::{
 };
 pub use tickets::*;
 pub use workers::{
-    start_worker, stop_worker, stop_all_workers, get_workers, get_worker_queue_status,
+    start_worker, stop_worker, pause_worker, resume_worker, drain_worker, stop_all_workers,
+    get_workers, get_worker_queue_status, get_scheduling_policy, set_scheduling_policy, explain_queue,
+    get_concurrency_status, set_concurrency_limits,
+    get_worker_definitions, update_worker_definition, delete_worker_definition, get_remote_workers,
     validate_worker, get_commands_path, get_available_commands, install_commands_to_project,
     install_commands_to_user, check_commands_installed, check_user_commands_installed,
 };
@@ -41,15 +52,72 @@ pub struct ApiConfigResponse {
 /// Get the current API configuration (port, URL, token)
 #[tauri::command]
 pub fn get_api_config() -> Result<ApiConfigResponse, String> {
-    let port_str = std::env::var("AGENT_KANBAN_API_PORT")
-        .unwrap_or_else(|_| "7432".to_string());
-    let port: u16 = port_str.parse().unwrap_or(7432);
+    Ok(resolve_api_config(None))
+}
+
+/// Resolve the API configuration.
+///
+/// The running app exports it through `AGENT_KANBAN_API_*` environment
+/// variables. Other processes, such as the `bored` CLI, fall back to the
+/// `api_port` and `api_token` files the app writes into `data_dir`.
+pub fn resolve_api_config(data_dir: Option<&std::path::Path>) -> ApiConfigResponse {
+    api_config_from(|name| std::env::var(name).ok(), data_dir)
+}
+
+fn api_config_from(
+    env: impl Fn(&str) -> Option<String>,
+    data_dir: Option<&std::path::Path>,
+) -> ApiConfigResponse {
+    let read_file = |name: &str| {
+        data_dir
+            .and_then(|dir| std::fs::read_to_string(dir.join(name)).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let port: u16 = env("AGENT_KANBAN_API_PORT")
+        .or_else(|| read_file("api_port"))
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(7432);
     
-    let url = std::env::var("AGENT_KANBAN_API_URL")
-        .unwrap_or_else(|_| format!("http://127.0.0.1:{}", port));
+    let url = env("AGENT_KANBAN_API_URL")
+        .unwrap_or_else(|| format!("http://127.0.0.1:{}", port));
     
-    let token = std::env::var("AGENT_KANBAN_API_TOKEN")
+    let token = env("AGENT_KANBAN_API_TOKEN")
+        .or_else(|| read_file("api_token"))
         .unwrap_or_default();
     
-    Ok(ApiConfigResponse { url, port, token })
+    ApiConfigResponse { url, port, token }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_config_prefers_env_over_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("api_port"), "7500\n").unwrap();
+        std::fs::write(dir.path().join("api_token"), "file-token\n").unwrap();
+
+        let config = api_config_from(|_| None, Some(dir.path()));
+        assert_eq!(config.port, 7500);
+        assert_eq!(config.url, "http://127.0.0.1:7500");
+        assert_eq!(config.token, "file-token");
+
+        let config = api_config_from(
+            |name| (name == "AGENT_KANBAN_API_TOKEN").then(|| "env-token".to_string()),
+            Some(dir.path()),
+        );
+        assert_eq!(config.token, "env-token");
+        assert_eq!(config.port, 7500);
+    }
+
+    #[test]
+    fn api_config_defaults() {
+        let config = api_config_from(|_| None, None);
+        assert_eq!(config.port, 7432);
+        assert_eq!(config.url, "http://127.0.0.1:7432");
+        assert!(config.token.is_empty());
+    }
 }
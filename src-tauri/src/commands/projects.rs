@@ -3,7 +3,7 @@ use std::sync::Arc;
 use tauri::State;
 
 use crate::agents::worktree::{is_git_repo, repo_has_commits, create_initial_commit};
-use crate::db::{CreateProject, Database, Project, ReadinessCheck, UpdateProject};
+use crate::db::{Actor, CreateProject, Database, Project, ReadinessCheck, UpdateProject};
 
 #[tauri::command]
 pub async fn get_projects(db: State<'_, Arc<Database>>) -> Result<Vec<Project>, String> {
@@ -60,7 +60,7 @@ pub async fn set_ticket_project(
     project_id: Option<String>,
     db: State<'_, Arc<Database>>,
 ) -> Result<(), String> {
-    db.set_ticket_project(&ticket_id, project_id.as_deref())
+    db.set_ticket_project(&ticket_id, project_id.as_deref(), &Actor::user())
         .map_err(|e| e.to_string())
 }
 
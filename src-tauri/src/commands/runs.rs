@@ -6,11 +6,13 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, State, Window};
 
 use crate::agents::{self, cursor, AgentKind, AgentRunConfig, ClaudeApiConfig, extract_text_from_stream_json};
-use crate::commands::claude::ClaudeApiSettingsState;
+use crate::agents::recovery::{RecoverySettings, Restart};
+use crate::agents::claude_settings::ClaudeApiSettingsState;
+use crate::commands::workers::app_worker_config;
 use crate::agents::spawner::{CancelHandle, run_agent_with_capture};
-use crate::agents::orchestrator::{WorkflowOrchestrator, OrchestratorConfig};
+use crate::agents::orchestrator::{AdhocStage, WorkflowOrchestrator, OrchestratorConfig, ADHOC_STAGE_NAME};
 use crate::agents::prompt::{generate_branch_name_generation_prompt, parse_branch_name_from_output};
-use crate::db::models::{AgentRun, AgentType, CreateRun, RunStatus};
+use crate::db::models::{Actor, AgentRun, AgentType, CheckpointState, CreateRun, RunStatus, WorkflowCheckpoint};
 use crate::db::Database;
 
 /// Shared state for tracking running agents
@@ -127,6 +129,7 @@ async fn generate_ai_branch_name(
         repo_path: repo_path.to_path_buf(),
         prompt: prompt.clone(),
         timeout_secs: Some(60), // Short timeout for branch generation
+        wrap_up_secs: None,
         api_url: String::new(),
         api_token: String::new(),
         model,
@@ -308,6 +311,7 @@ pub async fn start_agent_run(
         use agents::worktree::{create_worktree_with_existing_branch, create_worktree, WorktreeConfig, generate_branch_name};
         
         let repo_path_buf = std::path::PathBuf::from(&repo_path);
+        agents::recovery::discard_stopped_workflows(db.inner(), &ticket_id, &repo_path_buf);
         
         // Determine the branch to use
         let branch_to_use = if let Some(ref existing_branch) = ticket.branch_name {
@@ -339,9 +343,9 @@ pub async fn start_agent_run(
             // Store the branch name on the ticket immediately
             // This is critical - if we fail to store, we must abort to prevent
             // orphaned branches and inconsistent state between DB and git
-            if let Err(e) = db.set_ticket_branch(&ticket_id, &branch) {
+            if let Err(e) = db.set_ticket_branch(&ticket_id, &branch, &Actor::run(&run_id)) {
                 // Unlock the ticket before returning error
-                let _ = db.unlock_ticket(&ticket_id);
+                let _ = db.unlock_ticket(&ticket_id, &Actor::run(&run_id));
                 return Err(format!("Failed to store branch name on ticket: {}. Aborting run to prevent inconsistent state.", e));
             }
             tracing::info!("Stored branch name '{}' on ticket {}", branch, ticket_id);
@@ -535,7 +539,7 @@ pub async fn start_agent_run(
                         None,
                         Some(&format!("Failed to start task: {}", e)),
                     );
-                    let _ = db_clone.unlock_ticket(&ticket_id_for_task);
+                    let _ = db_clone.unlock_ticket(&ticket_id_for_task, &Actor::run(&run_id_for_task));
                     
                     // Clean up worktree if created
                     if let Some(ref worktree) = worktree_for_cleanup {
@@ -554,8 +558,7 @@ pub async fn start_agent_run(
             
             let orchestrator = WorkflowOrchestrator::new(OrchestratorConfig {
                 db: db_clone.clone(),
-                window: Some(window_clone.clone()),
-                app_handle: None, // Direct runs use Window for event emission
+                event_sink: Some(Arc::new(window_clone.clone())), // Direct runs emit to their window
                 parent_run_id: run_id_for_task.clone(),
                 ticket: ticket_for_orchestrator.clone(),
                 task: task.clone(),
@@ -571,6 +574,8 @@ pub async fn start_agent_run(
                 // so it's never a temp branch that needs renaming
                 is_temp_branch: false,
                 claude_api_config: claude_api_config_for_orchestrator,
+                start_stage: 0,
+                extra_stage: None,
             });
 
             // Execute workflow - log callbacks are handled per-stage with correct sub-run IDs
@@ -666,16 +671,19 @@ pub async fn start_agent_run(
 
             // Unlock the ticket
             tracing::info!("Unlocking ticket {} after multi-stage workflow", ticket_id_for_task);
-            if let Err(e) = db_clone.unlock_ticket(&ticket_id_for_task) {
+            if let Err(e) = db_clone.unlock_ticket(&ticket_id_for_task, &Actor::run(&run_id_for_task)) {
                 tracing::error!("Failed to unlock ticket: {}", e);
             } else {
                 tracing::info!("Successfully unlocked ticket {}", ticket_id_for_task);
             }
             
-            // Clean up the worktree if we created one
+            // Clean up the worktree if we created one, unless the workflow
+            // failed and can be restarted
             if let Some(ref worktree) = worktree_for_cleanup {
                 use agents::worktree::remove_worktree;
-                if let Err(e) = remove_worktree(&worktree.path, &main_repo_for_cleanup) {
+                if agents::recovery::keeps_worktree(&db_clone, &run_id_for_task) {
+                    tracing::info!("Kept worktree of failed run {} at {}", run_id_for_task, worktree.path.display());
+                } else if let Err(e) = remove_worktree(&worktree.path, &main_repo_for_cleanup) {
                     tracing::error!("Failed to remove worktree {}: {}", worktree.path.display(), e);
                 } else {
                     tracing::info!("Removed worktree at {}", worktree.path.display());
@@ -710,6 +718,9 @@ pub async fn cancel_agent_run(
                 run_id, was_already_cancelled
             );
             true
+        } else if agents::recovery::cancel(&run_id) {
+            tracing::info!("Cancelled resumed run {}", run_id);
+            true
         } else {
             // Log available handles for debugging
             let available_handles: Vec<_> = handles.keys().collect();
@@ -748,7 +759,7 @@ pub async fn cancel_agent_run(
     // Also unlock any ticket that was locked by this run
     // We need to find the ticket first
     if let Ok(run) = db.get_run(&run_id) {
-        if let Err(e) = db.unlock_ticket(&run.ticket_id) {
+        if let Err(e) = db.unlock_ticket(&run.ticket_id, &Actor::user()) {
             tracing::warn!("Failed to unlock ticket after cancel: {}", e);
         }
     }
@@ -772,6 +783,128 @@ pub async fn cleanup_stale_runs(
     Ok(count)
 }
 
+/// Workflows interrupted by a restart that can resume where they stopped
+#[tauri::command]
+pub async fn get_interrupted_runs(db: State<'_, Arc<Database>>) -> Result<Vec<WorkflowCheckpoint>, String> {
+    db.get_checkpoints(CheckpointState::Interrupted).map_err(|e| e.to_string())
+}
+
+/// Pause a running workflow once its current stage completes
+#[tauri::command]
+pub async fn pause_agent_run(
+    run_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<WorkflowCheckpoint, String> {
+    tracing::info!("Pausing agent run {} after its current stage", run_id);
+    db.request_pause(&run_id).map_err(|e| e.to_string())
+}
+
+/// Resume a paused or interrupted workflow from its first incomplete stage
+#[tauri::command]
+pub async fn resume_agent_run(
+    app: AppHandle,
+    run_id: String,
+    db: State<'_, Arc<Database>>,
+    claude_api_state: State<'_, ClaudeApiSettingsState>,
+) -> Result<WorkflowCheckpoint, String> {
+    tracing::info!("Resuming agent run: {}", run_id);
+    agents::recovery::resume_workflow(db.inner(), &run_id, |agent| {
+        app_worker_config(&app, &claude_api_state, agent)
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Replace the plan a paused or stopped workflow continues with
+#[tauri::command]
+pub async fn update_run_plan(
+    run_id: String,
+    plan_md: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<WorkflowCheckpoint, String> {
+    db.set_checkpoint_plan(&run_id, &plan_md).map_err(|e| e.to_string())
+}
+
+/// Stage progress of a workflow run; `None` for runs without stages
+#[tauri::command]
+pub async fn get_run_checkpoint(
+    run_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Option<WorkflowCheckpoint>, String> {
+    db.get_checkpoint(&run_id).map_err(|e| e.to_string())
+}
+
+/// Run a stopped workflow again from `stage`, reusing its worktree, branch and plan
+#[tauri::command]
+pub async fn rerun_agent_run(
+    app: AppHandle,
+    run_id: String,
+    stage: usize,
+    db: State<'_, Arc<Database>>,
+    claude_api_state: State<'_, ClaudeApiSettingsState>,
+) -> Result<WorkflowCheckpoint, String> {
+    tracing::info!("Rerunning agent run {} from stage {}", run_id, stage);
+    restart_agent_run(&app, &run_id, Restart::From(stage), &db, &claude_api_state)
+}
+
+/// Skip the stage a stopped workflow stopped at and continue with the next one
+#[tauri::command]
+pub async fn skip_agent_run_stage(
+    app: AppHandle,
+    run_id: String,
+    db: State<'_, Arc<Database>>,
+    claude_api_state: State<'_, ClaudeApiSettingsState>,
+) -> Result<WorkflowCheckpoint, String> {
+    tracing::info!("Skipping the next stage of agent run {}", run_id);
+    restart_agent_run(&app, &run_id, Restart::Skip, &db, &claude_api_state)
+}
+
+/// Run a stage with a custom prompt in a stopped workflow, then continue it
+/// from `before` (by default the stage it stopped at)
+#[tauri::command]
+pub async fn insert_agent_run_stage(
+    app: AppHandle,
+    run_id: String,
+    prompt: String,
+    name: Option<String>,
+    before: Option<usize>,
+    db: State<'_, Arc<Database>>,
+    claude_api_state: State<'_, ClaudeApiSettingsState>,
+) -> Result<WorkflowCheckpoint, String> {
+    tracing::info!("Inserting a stage into agent run {}", run_id);
+    let stage = AdhocStage {
+        name: name.unwrap_or_else(|| ADHOC_STAGE_NAME.to_string()),
+        prompt,
+    };
+    restart_agent_run(&app, &run_id, Restart::Insert { stage, before }, &db, &claude_api_state)
+}
+
+fn restart_agent_run(
+    app: &AppHandle,
+    run_id: &str,
+    restart: Restart,
+    db: &Arc<Database>,
+    claude_api_state: &ClaudeApiSettingsState,
+) -> Result<WorkflowCheckpoint, String> {
+    agents::recovery::restart_workflow(db, run_id, restart, |agent| {
+        app_worker_config(app, claude_api_state, agent)
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_recovery_settings(db: State<'_, Arc<Database>>) -> Result<RecoverySettings, String> {
+    db.get_recovery_settings().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_recovery_settings(
+    settings: RecoverySettings,
+    db: State<'_, Arc<Database>>,
+) -> Result<RecoverySettings, String> {
+    tracing::info!("Setting recovery settings: {:?}", settings);
+    db.set_recovery_settings(&settings).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_agent_runs(
     ticket_id: String,
@@ -7,11 +7,12 @@ use tauri::State;
 use tokio::sync::broadcast;
 
 use crate::api::state::LiveEvent;
-use crate::db::{Database, Scratchpad, CreateScratchpad, UpdateScratchpad, ScratchpadStatus, Exploration, ScratchpadProgress};
+use crate::db::{Actor, Database, Scratchpad, CreateScratchpad, UpdateScratchpad, ScratchpadStatus, Exploration, ScratchpadProgress, TrashEntry};
 use crate::agents::planner::{PlannerAgent, PlannerConfig};
 use crate::agents::{AgentKind, ClaudeApiConfig};
-use crate::commands::claude::ClaudeApiSettingsState;
+use crate::agents::claude_settings::ClaudeApiSettingsState;
 use crate::lifecycle::epic::on_epic_moved_to_ready;
+use crate::lifecycle::TicketState;
 
 /// Input for creating a scratchpad
 #[derive(Debug, Deserialize)]
@@ -93,23 +94,36 @@ pub async fn update_scratchpad(
     }).map_err(|e| e.to_string())
 }
 
+/// Move a scratchpad to the trash, or delete it for good when `permanent`
 #[tauri::command]
 pub async fn delete_scratchpad(
     id: String,
+    permanent: Option<bool>,
     db: State<'_, Arc<Database>>,
-) -> Result<(), String> {
-    tracing::info!("Deleting scratchpad {}", id);
-    db.delete_scratchpad(&id).map_err(|e| e.to_string())
+) -> Result<Option<TrashEntry>, String> {
+    if permanent.unwrap_or(false) {
+        tracing::info!("Permanently deleting scratchpad {}", id);
+        db.delete_scratchpad(&id).map(|_| None).map_err(|e| e.to_string())
+    } else {
+        tracing::info!("Moving scratchpad {} to trash", id);
+        db.trash_scratchpad(&id, false).map(Some).map_err(|e| e.to_string())
+    }
 }
 
-/// Delete a scratchpad and all its associated tickets (epics, child tickets, and their data)
+/// Delete a scratchpad and all its associated tickets (epics, child tickets, and their data).
+/// They go to the trash as one entry unless `permanent`.
 #[tauri::command]
 pub async fn delete_scratchpad_with_tickets(
     id: String,
+    permanent: Option<bool>,
     db: State<'_, Arc<Database>>,
 ) -> Result<usize, String> {
     tracing::info!("Deleting scratchpad {} with all tickets", id);
-    let count = db.delete_scratchpad_with_tickets(&id).map_err(|e| e.to_string())?;
+    let count = if permanent.unwrap_or(false) {
+        db.delete_scratchpad_with_tickets(&id).map_err(|e| e.to_string())?
+    } else {
+        db.trash_scratchpad(&id, true).map_err(|e| e.to_string())?.ticket_count as usize
+    };
     tracing::info!("Deleted scratchpad {} and {} tickets", id, count);
     Ok(count)
 }
@@ -336,8 +350,8 @@ pub async fn start_scratchpad_work(
     let target_board_id = scratchpad.target_board_id.as_ref()
         .unwrap_or(&scratchpad.board_id);
     
-    // Find the Ready column for the target board
-    let ready_column = db.find_column_by_name(target_board_id, "Ready")
+    // Find the ready column for the target board
+    let ready_column = db.find_column_by_role(target_board_id, TicketState::Ready)
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "Ready column not found on target board".to_string())?;
     
@@ -345,7 +359,7 @@ pub async fn start_scratchpad_work(
     
     for epic in &root_epics {
         // Move epic to Ready
-        db.move_ticket(&epic.id, &ready_column.id)
+        db.move_ticket(&epic.id, &ready_column.id, &Actor::user())
             .map_err(|e| e.to_string())?;
         
         // Trigger on_epic_moved_to_ready to advance its first child
@@ -0,0 +1,20 @@
+//! [`EventSink`] for the desktop app's windows and app handle, kept with the
+//! Tauri commands so the agents layer does not depend on Tauri.
+
+use tauri::{AppHandle, Manager, Window};
+
+use crate::agents::sink::EventSink;
+
+impl EventSink for Window {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        Window::emit(self, event, payload)
+            .map_err(|e| format!("Failed to emit {} via window: {}", event, e))
+    }
+}
+
+impl EventSink for AppHandle {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        self.emit_all(event, payload)
+            .map_err(|e| format!("Failed to emit {} via app_handle: {}", event, e))
+    }
+}
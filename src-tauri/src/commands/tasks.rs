@@ -5,45 +5,7 @@ use tauri::State;
 use crate::db::Database;
 use crate::db::models::{Task, CreateTask, TaskType};
 use crate::db::tasks::TaskCounts;
-
-/// Columns that should trigger a move back to Ready when a new task is added
-const COMPLETED_COLUMNS: &[&str] = &["Done", "Review"];
-
-/// Move ticket back to Ready if it's in a completed column (Done/Review)
-/// This allows workers to pick up the ticket again for the new task
-fn move_to_ready_if_completed(db: &Database, ticket_id: &str) -> Result<(), String> {
-    // Get the ticket to find its current column and board
-    let ticket = db.get_ticket(ticket_id)
-        .map_err(|e| e.to_string())?;
-    
-    // Get all columns for the board
-    let columns = db.get_columns(&ticket.board_id)
-        .map_err(|e| e.to_string())?;
-    
-    // Find the current column
-    let current_column = columns.iter()
-        .find(|c| c.id == ticket.column_id);
-    
-    // Check if ticket is in a completed column
-    let is_completed = current_column
-        .map(|c| COMPLETED_COLUMNS.iter().any(|&name| c.name.eq_ignore_ascii_case(name)))
-        .unwrap_or(false);
-    
-    if is_completed {
-        // Find the Ready column (where workers pick up tickets)
-        if let Some(ready) = columns.iter().find(|c| c.name.eq_ignore_ascii_case("Ready")) {
-            tracing::info!(
-                "Moving ticket {} from {} back to Ready for new task",
-                ticket_id,
-                current_column.map(|c| c.name.as_str()).unwrap_or("unknown")
-            );
-            db.move_ticket(ticket_id, &ready.id)
-                .map_err(|e| e.to_string())?;
-        }
-    }
-    
-    Ok(())
-}
+use crate::lifecycle::move_to_ready_if_completed;
 
 /// Get all tasks for a ticket
 #[tauri::command]
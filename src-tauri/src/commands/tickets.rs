@@ -2,7 +2,8 @@ use std::sync::Arc;
 use serde::Deserialize;
 use tauri::State;
 
-use crate::db::{CreateTicket, Database, Priority, Ticket, AgentPref, UpdateTicket, Comment, CreateComment, AuthorType, WorkflowType, EpicProgress};
+use crate::db::{Actor, CreateTicket, Database, TicketHistoryEntry, Priority, Ticket, AgentPref, UpdateTicket, Comment, CreateComment, AuthorType, WorkflowType, EpicProgress, TrashEntry, CustomFieldValues};
+use crate::lifecycle::TicketState;
 
 /// Input struct for creating tickets via Tauri command.
 /// Allows setting is_epic and epic_id at creation time.
@@ -27,6 +28,9 @@ pub struct CreateTicketInput {
     pub is_epic: bool,
     /// The parent epic ID (when creating a child ticket)
     pub epic_id: Option<String>,
+    /// Values of the board's custom fields, keyed by field key
+    #[serde(default)]
+    pub custom_fields: CustomFieldValues,
 }
 
 /// Input struct for updating tickets via Tauri command.
@@ -48,6 +52,8 @@ pub struct UpdateTicketInput {
     pub model: Option<String>,
     pub branch_name: Option<String>,
     pub column_id: Option<String>,
+    /// Custom field values to set; `null` clears a field
+    pub custom_fields: Option<CustomFieldValues>,
 }
 
 #[tauri::command]
@@ -65,6 +71,8 @@ pub async fn create_ticket(
     db: State<'_, Arc<Database>>,
 ) -> Result<Ticket, String> {
     tracing::info!("Creating ticket: {} (epic: {})", ticket.title, ticket.is_epic);
+    db.check_required_fields(&ticket.board_id, &ticket.custom_fields)
+        .map_err(|e| e.to_string())?;
     let create = CreateTicket {
         board_id: ticket.board_id,
         column_id: ticket.column_id,
@@ -82,6 +90,7 @@ pub async fn create_ticket(
         depends_on_epic_id: None,
         depends_on_epic_ids: vec![],
         scratchpad_id: None,
+        custom_fields: ticket.custom_fields,
     };
     db.create_ticket(&create).map_err(|e| e.to_string())
 }
@@ -97,16 +106,13 @@ pub async fn move_ticket(
     // Get the ticket before moving to check if it's an epic
     let ticket = db.get_ticket(&ticket_id).map_err(|e| e.to_string())?;
     
-    // Get the target column name
-    let columns = db.get_columns(&ticket.board_id).map_err(|e| e.to_string())?;
-    let target_column = columns.iter().find(|c| c.id == column_id);
-    let target_column_name = target_column.map(|c| c.name.as_str()).unwrap_or("");
+    let target_role = db.get_column_role(&column_id).map_err(|e| e.to_string())?;
     
     // Perform the move
-    db.move_ticket(&ticket_id, &column_id).map_err(|e| e.to_string())?;
+    db.move_ticket(&ticket_id, &column_id, &Actor::user()).map_err(|e| e.to_string())?;
     
     // Epic lifecycle: when an epic is moved to Ready, advance its first child
-    if ticket.is_epic && target_column_name.eq_ignore_ascii_case("Ready") {
+    if ticket.is_epic && target_role == Some(TicketState::Ready) {
         // Refresh ticket after move
         let updated_ticket = db.get_ticket(&ticket_id).map_err(|e| e.to_string())?;
         if let Err(e) = crate::lifecycle::epic::on_epic_moved_to_ready(&db, &updated_ticket) {
@@ -152,20 +158,18 @@ pub async fn update_ticket(
         depends_on_epic_id: None,
         depends_on_epic_ids: vec![],
         scratchpad_id: None,
+        custom_fields: updates.custom_fields,
     };
-    db.update_ticket(&ticket_id, &update)
+    db.update_ticket(&ticket_id, &update, &Actor::user())
         .map(|_| ())
         .map_err(|e| e.to_string())?;
     
     // Epic lifecycle: if an epic is moved to Ready via update, advance its first child
     if is_column_changing && ticket.is_epic {
         if let Some(new_column_id) = updates.column_id {
-            // Get the target column name
-            let columns = db.get_columns(&ticket.board_id).map_err(|e| e.to_string())?;
-            let target_column = columns.iter().find(|c| c.id == new_column_id);
-            let target_column_name = target_column.map(|c| c.name.as_str()).unwrap_or("");
+            let target_role = db.get_column_role(&new_column_id).map_err(|e| e.to_string())?;
             
-            if target_column_name.eq_ignore_ascii_case("Ready") {
+            if target_role == Some(TicketState::Ready) {
                 // Refresh ticket after update
                 let updated_ticket = db.get_ticket(&ticket_id).map_err(|e| e.to_string())?;
                 if let Err(e) = crate::lifecycle::epic::on_epic_moved_to_ready(&db, &updated_ticket) {
@@ -179,13 +183,30 @@ pub async fn update_ticket(
     Ok(())
 }
 
+/// Move a ticket to the trash, or delete it for good when `permanent`.
+/// Returns the trash entry for a soft delete.
 #[tauri::command]
 pub async fn delete_ticket(
     ticket_id: String,
+    permanent: Option<bool>,
     db: State<'_, Arc<Database>>,
-) -> Result<(), String> {
-    tracing::info!("Deleting ticket: {}", ticket_id);
-    db.delete_ticket(&ticket_id).map_err(|e| e.to_string())
+) -> Result<Option<TrashEntry>, String> {
+    if permanent.unwrap_or(false) {
+        tracing::info!("Permanently deleting ticket: {}", ticket_id);
+        db.delete_ticket(&ticket_id).map(|_| None).map_err(|e| e.to_string())
+    } else {
+        tracing::info!("Moving ticket to trash: {}", ticket_id);
+        db.trash_ticket(&ticket_id).map(Some).map_err(|e| e.to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn get_ticket_history(
+    ticket_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<TicketHistoryEntry>, String> {
+    tracing::info!("Getting history for ticket: {}", ticket_id);
+    db.get_ticket_history(&ticket_id).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -256,7 +277,7 @@ pub async fn add_ticket_to_epic(
     db: State<'_, Arc<Database>>,
 ) -> Result<(), String> {
     tracing::info!("Adding ticket {} to epic {}", ticket_id, epic_id);
-    db.add_ticket_to_epic(&epic_id, &ticket_id).map_err(|e| e.to_string())
+    db.add_ticket_to_epic(&epic_id, &ticket_id, &Actor::user()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -265,7 +286,7 @@ pub async fn remove_ticket_from_epic(
     db: State<'_, Arc<Database>>,
 ) -> Result<(), String> {
     tracing::info!("Removing ticket {} from epic", ticket_id);
-    db.remove_ticket_from_epic(&ticket_id).map_err(|e| e.to_string())
+    db.remove_ticket_from_epic(&ticket_id, &Actor::user()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -275,5 +296,5 @@ pub async fn reorder_epic_children(
     db: State<'_, Arc<Database>>,
 ) -> Result<(), String> {
     tracing::info!("Reordering children for epic {}: {:?}", epic_id, child_ids);
-    db.reorder_epic_children(&epic_id, &child_ids).map_err(|e| e.to_string())
+    db.reorder_epic_children(&epic_id, &child_ids, &Actor::user()).map_err(|e| e.to_string())
 }
\ No newline at end of file
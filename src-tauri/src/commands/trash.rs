@@ -0,0 +1,35 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::{Database, TrashEntry};
+
+#[tauri::command]
+pub async fn list_trash(
+    board_id: Option<String>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<TrashEntry>, String> {
+    db.list_trash(board_id.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_trash(
+    trash_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<TrashEntry, String> {
+    tracing::info!("Restoring trash entry: {}", trash_id);
+    db.restore_trash(&trash_id).map_err(|e| e.to_string())
+}
+
+/// Delete a trash entry for good
+#[tauri::command]
+pub async fn purge_trash(
+    trash_id: String,
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    tracing::info!("Purging trash entry: {}", trash_id);
+    let db = db.inner().clone();
+    tokio::task::spawn_blocking(move || db.purge_trash(&trash_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
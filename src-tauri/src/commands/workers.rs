@@ -2,16 +2,17 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
-use once_cell::sync::Lazy;
-use tauri::State;
+use tauri::{Manager, State};
 
-use crate::agents::worker::{WorkerConfig, WorkerManager, WorkerStatus};
+use crate::agents::claude_settings::ClaudeApiSettingsState;
+use crate::agents::recovery;
+use crate::agents::slots::{self, ConcurrencyLimits, ConcurrencyStatus, SLOTS};
+use crate::agents::worker::{WorkerConfig, WorkerStatus, WORKER_MANAGER};
 use crate::agents::validation::{ValidationResult, validate_worker_environment};
 use crate::agents::{AgentKind, ClaudeApiConfig, cursor, claude};
-use crate::commands::claude::ClaudeApiSettingsState;
-use crate::db::Database;
-
-pub static WORKER_MANAGER: Lazy<WorkerManager> = Lazy::new(WorkerManager::new);
+use crate::db::scheduling::{QueueExplanation, QueueFilter, SchedulingPolicy};
+use crate::db::{CreateWorkerDefinition, Database, LabelFilter, RemoteWorker, UpdateWorkerDefinition, WorkerDefinition};
+use crate::lifecycle::TicketState;
 
 #[derive(Debug, Clone, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,64 +43,94 @@ fn get_hook_script_path(app: &tauri::AppHandle) -> Option<String> {
         .map(|p| p.to_string_lossy().to_string())
 }
 
+/// Config of workers started from the app; events go to the window
+pub(crate) fn app_worker_config(app: &tauri::AppHandle, claude_api_state: &ClaudeApiSettingsState, agent_type: AgentKind) -> WorkerConfig {
+    let api_url = std::env::var("AGENT_KANBAN_API_URL").unwrap_or_else(|_| {
+        format!(
+            "http://127.0.0.1:{}",
+            std::env::var("AGENT_KANBAN_API_PORT").unwrap_or_else(|_| "7432".to_string())
+        )
+    });
+    let api_token = std::env::var("AGENT_KANBAN_API_TOKEN")
+        .unwrap_or_else(|_| "default-token".to_string());
+
+    WorkerConfig {
+        agent_type,
+        api_url,
+        api_token,
+        hook_script_path: get_hook_script_path(app),
+        event_sink: Some(Arc::new(app.clone())),
+        claude_api_config: (agent_type == AgentKind::Claude)
+            .then(|| ClaudeApiConfig::from(claude_api_state.get())),
+        ..Default::default()
+    }
+}
+
+/// Apply the saved concurrency limits, start the enabled saved workers and
+/// resume interrupted workflows if set to; called once at launch
+pub fn start_saved_workers(app: &tauri::AppHandle, db: &Arc<Database>) {
+    slots::apply_saved_limits(db);
+    let claude_api_state = app.state::<ClaudeApiSettingsState>();
+    match WORKER_MANAGER.start_saved_workers(db, |agent| app_worker_config(app, &claude_api_state, agent)) {
+        Ok(count) if count > 0 => tracing::info!("Started {} saved worker(s)", count),
+        Ok(_) => {}
+        Err(e) => tracing::error!("Failed to start saved workers: {}", e),
+    }
+    recovery::recover_interrupted_workflows(db, |agent| app_worker_config(app, &claude_api_state, agent));
+}
+
+/// Save a worker and start it; it comes back whenever the app launches
 #[tauri::command]
 pub async fn start_worker(
     app: tauri::AppHandle,
     agent_type: String,
     project_id: Option<String>,
+    labels: Option<LabelFilter>,
     db: State<'_, Arc<Database>>,
     claude_api_state: State<'_, ClaudeApiSettingsState>,
 ) -> Result<StartWorkerResponse, String> {
     tracing::info!(
-        "Starting worker: agent_type={}, project_id={:?}",
+        "Starting worker: agent_type={}, project_id={:?}, labels={:?}",
         agent_type,
-        project_id
+        project_id,
+        labels
     );
 
-    let agent_kind = match agent_type.as_str() {
-        "cursor" => AgentKind::Cursor,
-        "claude" => AgentKind::Claude,
-        _ => return Err(format!("Invalid agent type: {}", agent_type)),
-    };
-
-    let api_url = std::env::var("AGENT_KANBAN_API_URL").unwrap_or_else(|_| {
-        format!(
-            "http://127.0.0.1:{}",
-            std::env::var("AGENT_KANBAN_API_PORT").unwrap_or_else(|_| "7432".to_string())
-        )
-    });
-    let api_token = std::env::var("AGENT_KANBAN_API_TOKEN")
-        .unwrap_or_else(|_| "default-token".to_string());
-    
-    // Get the hook script path for the worker to use
-    let hook_script_path = get_hook_script_path(&app);
-    tracing::info!("Worker hook script path: {:?}", hook_script_path);
-
-    let claude_api_config = (agent_kind == AgentKind::Claude)
-        .then(|| ClaudeApiConfig::from(claude_api_state.get()));
+    let agent_kind = AgentKind::parse(&agent_type)
+        .ok_or_else(|| format!("Invalid agent type: {}", agent_type))?;
 
-    let config = WorkerConfig {
+    let definition = db.create_worker_definition(&CreateWorkerDefinition {
         agent_type: agent_kind,
+        name: None,
         project_id,
-        api_url,
-        api_token,
-        hook_script_path,
-        app_handle: Some(app.clone()),
-        claude_api_config,
-        ..Default::default()
-    };
+        labels: labels.unwrap_or_default(),
+        poll_interval_secs: None,
+        agent_timeout_secs: None,
+        lock_duration_mins: None,
+        schedule: None,
+        enabled: true,
+    }).map_err(|e| e.to_string())?;
 
-    let worker_id = WORKER_MANAGER.start_worker(config, db.inner().clone());
+    let config = app_worker_config(&app, &claude_api_state, agent_kind);
+    tracing::info!("Worker hook script path: {:?}", config.hook_script_path);
+    WORKER_MANAGER.sync_saved_worker(&definition, db.inner(), config);
 
-    tracing::info!("Worker started: {}", worker_id);
+    tracing::info!("Worker started: {}", definition.id);
 
-    Ok(StartWorkerResponse { worker_id })
+    Ok(StartWorkerResponse { worker_id: definition.id })
 }
 
+/// Stop a worker; saved workers are disabled so they stay stopped after a restart
 #[tauri::command]
-pub async fn stop_worker(worker_id: String) -> Result<bool, String> {
+pub async fn stop_worker(worker_id: String, db: State<'_, Arc<Database>>) -> Result<bool, String> {
     tracing::info!("Stopping worker: {}", worker_id);
     let stopped = WORKER_MANAGER.stop_worker(&worker_id);
+    if db.get_worker_definition(&worker_id).map_err(|e| e.to_string())?.is_some() {
+        db.update_worker_definition(&worker_id, &UpdateWorkerDefinition {
+            enabled: Some(false),
+            ..Default::default()
+        }).map_err(|e| e.to_string())?;
+    }
     if stopped {
         tracing::info!("Worker stopped: {}", worker_id);
     } else {
@@ -108,6 +139,72 @@ pub async fn stop_worker(worker_id: String) -> Result<bool, String> {
     Ok(stopped)
 }
 
+/// Keep a worker from taking new tickets; the current one is finished
+#[tauri::command]
+pub async fn pause_worker(worker_id: String) -> Result<WorkerStatus, String> {
+    WORKER_MANAGER
+        .pause_worker(&worker_id)
+        .ok_or_else(|| format!("Worker not found: {}", worker_id))
+}
+
+#[tauri::command]
+pub async fn resume_worker(worker_id: String) -> Result<WorkerStatus, String> {
+    WORKER_MANAGER
+        .resume_worker(&worker_id)
+        .ok_or_else(|| format!("Worker not found: {}", worker_id))
+}
+
+/// Let a worker finish its current ticket and exit; a saved worker is disabled
+#[tauri::command]
+pub async fn drain_worker(worker_id: String, db: State<'_, Arc<Database>>) -> Result<WorkerStatus, String> {
+    tracing::info!("Draining worker: {}", worker_id);
+    let status = WORKER_MANAGER
+        .drain_worker(&worker_id)
+        .ok_or_else(|| format!("Worker not found: {}", worker_id))?;
+    if db.get_worker_definition(&worker_id).map_err(|e| e.to_string())?.is_some() {
+        db.update_worker_definition(&worker_id, &UpdateWorkerDefinition {
+            enabled: Some(false),
+            ..Default::default()
+        }).map_err(|e| e.to_string())?;
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_worker_definitions(db: State<'_, Arc<Database>>) -> Result<Vec<WorkerDefinition>, String> {
+    db.get_worker_definitions().map_err(|e| e.to_string())
+}
+
+/// Machines registered over the REST API as remote workers
+#[tauri::command]
+pub async fn get_remote_workers(db: State<'_, Arc<Database>>) -> Result<Vec<RemoteWorker>, String> {
+    db.get_remote_workers().map_err(|e| e.to_string())
+}
+
+/// Change a saved worker; a running one restarts with the new settings
+#[tauri::command]
+pub async fn update_worker_definition(
+    app: tauri::AppHandle,
+    worker_id: String,
+    input: UpdateWorkerDefinition,
+    db: State<'_, Arc<Database>>,
+    claude_api_state: State<'_, ClaudeApiSettingsState>,
+) -> Result<WorkerDefinition, String> {
+    tracing::info!("Updating worker {}", worker_id);
+    let definition = db.update_worker_definition(&worker_id, &input).map_err(|e| e.to_string())?;
+    let config = app_worker_config(&app, &claude_api_state, definition.agent_type);
+    WORKER_MANAGER.sync_saved_worker(&definition, db.inner(), config);
+    Ok(definition)
+}
+
+#[tauri::command]
+pub async fn delete_worker_definition(worker_id: String, db: State<'_, Arc<Database>>) -> Result<(), String> {
+    tracing::info!("Deleting worker {}", worker_id);
+    db.delete_worker_definition(&worker_id).map_err(|e| e.to_string())?;
+    WORKER_MANAGER.stop_worker(&worker_id);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn stop_all_workers() -> Result<(), String> {
     tracing::info!("Stopping all workers");
@@ -121,6 +218,52 @@ pub async fn get_workers() -> Result<Vec<WorkerStatus>, String> {
     Ok(WORKER_MANAGER.get_all_status())
 }
 
+#[tauri::command]
+pub async fn get_scheduling_policy(db: State<'_, Arc<Database>>) -> Result<SchedulingPolicy, String> {
+    db.get_scheduling_policy().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_scheduling_policy(
+    policy: SchedulingPolicy,
+    db: State<'_, Arc<Database>>,
+) -> Result<SchedulingPolicy, String> {
+    tracing::info!("Setting scheduling policy: {:?}", policy);
+    db.set_scheduling_policy(&policy).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_concurrency_status() -> Result<ConcurrencyStatus, String> {
+    Ok(SLOTS.status())
+}
+
+/// Save the concurrency limits and apply them to running workers right away
+#[tauri::command]
+pub async fn set_concurrency_limits(
+    limits: ConcurrencyLimits,
+    db: State<'_, Arc<Database>>,
+) -> Result<ConcurrencyStatus, String> {
+    tracing::info!("Setting concurrency limits: {:?}", limits);
+    let limits = db.set_concurrency_limits(&limits).map_err(|e| e.to_string())?;
+    SLOTS.set_limits(limits);
+    Ok(SLOTS.status())
+}
+
+/// The ready queue as the scheduling policy ranks it, with reasons
+#[tauri::command]
+pub async fn explain_queue(
+    project_id: Option<String>,
+    agent_type: Option<AgentKind>,
+    db: State<'_, Arc<Database>>,
+) -> Result<QueueExplanation, String> {
+    let filter = QueueFilter {
+        project_ids: project_id.into_iter().collect(),
+        agent_type,
+        labels: LabelFilter::default(),
+    };
+    db.explain_queue(&filter, 20).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_worker_queue_status(
     db: State<'_, Arc<Database>>,
@@ -132,10 +275,8 @@ pub async fn get_worker_queue_status(
     let mut in_progress_count = 0;
 
     for board in &boards {
-        let columns = db.get_columns(&board.id).map_err(|e| e.to_string())?;
-
-        // Count tickets in Ready column that are not locked (or have expired locks)
-        if let Some(ready_col) = columns.iter().find(|c| c.name == "Ready") {
+        // Count tickets in the ready column that are not locked (or have expired locks)
+        if let Some(ready_col) = db.find_column_by_role(&board.id, TicketState::Ready).map_err(|e| e.to_string())? {
             let tickets = db
                 .get_tickets(&board.id, Some(&ready_col.id))
                 .map_err(|e| e.to_string())?;
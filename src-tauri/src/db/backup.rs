@@ -0,0 +1,309 @@
+//! Online backups, snapshots and restore.
+//!
+//! Copies are taken with SQLite's online backup API while the app keeps
+//! running and land in `backups/` next to the database file as
+//! `<name>-<reason>-<timestamp>.db`. Scheduled backups rotate according to a
+//! [`BackupPolicy`]; snapshots are taken automatically before deleting
+//! boards or planner output, before restoring and before migrations.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+
+use crate::db::{migrations, Database, DbError};
+use crate::db::models::BackupInfo;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+/// Format used by migration backups before they went through this module
+const LEGACY_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// When scheduled backups run and how many copies are kept.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupPolicy {
+    /// Minutes between scheduled backups; 0 turns them off
+    pub interval_mins: u64,
+    /// Copies kept per reason, newest first
+    pub keep_last: usize,
+    /// Copies older than this are removed, except the newest per reason
+    pub max_age_days: Option<i64>,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self {
+            interval_mins: 360,
+            keep_last: 10,
+            max_age_days: Some(30),
+        }
+    }
+}
+
+/// Directory backups of the database at `db_path` are written to
+pub fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new(".")).join("backups")
+}
+
+/// Copy the database behind `conn` (stored at `db_path`) into its backup
+/// directory. The copy is written under a temporary name and renamed once
+/// complete, so a crash never leaves a truncated backup behind.
+pub fn backup_connection(conn: &Connection, db_path: &Path, reason: &str) -> Result<PathBuf, DbError> {
+    if reason.is_empty() || !reason.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(DbError::Validation(format!("Invalid backup reason '{}'", reason)));
+    }
+
+    let dir = backup_dir(db_path);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| DbError::Validation(format!("Cannot create backup directory {:?}: {}", dir, e)))?;
+
+    let stem = db_path.file_stem().and_then(|s| s.to_str()).unwrap_or("database");
+    let timestamp = Utc::now().format(TIMESTAMP_FORMAT);
+    let path = dir.join(format!("{}-{}-{}.db", stem, reason, timestamp));
+    let partial = path.with_extension("db.partial");
+
+    let mut dest = Connection::open(&partial)?;
+    Backup::new(conn, &mut dest)?.run_to_completion(256, std::time::Duration::ZERO, None)?;
+    // The copy inherits WAL mode; make it a self-contained single file
+    let _: String = dest.query_row("PRAGMA journal_mode = DELETE", [], |row| row.get(0))?;
+    drop(dest);
+    std::fs::rename(&partial, &path)
+        .map_err(|e| DbError::Validation(format!("Cannot finish backup {:?}: {}", path, e)))?;
+    Ok(path)
+}
+
+impl Database {
+    fn require_path(&self) -> Result<&Path, DbError> {
+        self.path.as_deref()
+            .ok_or_else(|| DbError::Validation("In-memory databases have no backups".to_string()))
+    }
+
+    /// Take a backup now
+    pub fn create_backup(&self, reason: &str) -> Result<BackupInfo, DbError> {
+        let db_path = self.require_path()?;
+        let path = self.with_reader(|conn| backup_connection(conn, db_path, reason))?;
+        tracing::info!("Backed up database to {:?}", path);
+        backup_info(db_path, &path)
+            .ok_or_else(|| DbError::Validation(format!("Backup {:?} is unreadable", path)))
+    }
+
+    /// Snapshot before a destructive operation, recorded as `pre-<operation>`.
+    /// In-memory databases are skipped.
+    pub fn snapshot(&self, operation: &str) -> Result<Option<BackupInfo>, DbError> {
+        if self.path.is_none() {
+            return Ok(None);
+        }
+        self.create_backup(&format!("pre-{}", operation)).map(Some)
+    }
+
+    /// [`Database::snapshot`] for deletes that go ahead without it; a failed
+    /// backup is only logged
+    pub fn snapshot_best_effort(&self, operation: &str) {
+        if let Err(e) = self.snapshot(operation) {
+            tracing::warn!("Failed to back up the database before {}: {}", operation, e);
+        }
+    }
+
+    /// Backups of this database, newest first
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>, DbError> {
+        let Some(db_path) = self.path.as_deref() else {
+            return Ok(vec![]);
+        };
+        let entries = match std::fs::read_dir(backup_dir(db_path)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(DbError::Validation(format!("Cannot read backup directory: {}", e))),
+        };
+
+        let mut backups: Vec<BackupInfo> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| backup_info(db_path, &entry.path()))
+            .collect();
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+        Ok(backups)
+    }
+
+    /// Delete backups the policy no longer keeps; returns how many were removed
+    pub fn prune_backups(&self, policy: &BackupPolicy) -> Result<usize, DbError> {
+        let Some(db_path) = self.path.as_deref() else {
+            return Ok(0);
+        };
+        let now = Utc::now();
+        let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        let mut removed = 0;
+
+        for backup in self.list_backups()? {
+            let rank = seen.entry(backup.reason.clone()).or_insert(0);
+            let too_old = policy.max_age_days
+                .is_some_and(|days| now - backup.created_at > chrono::Duration::days(days));
+            let keep = *rank == 0 || (*rank < policy.keep_last && !too_old);
+            *rank += 1;
+            if keep {
+                continue;
+            }
+
+            let path = backup_dir(db_path).join(&backup.file_name);
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                Err(e) => tracing::warn!("Failed to remove old backup {:?}: {}", path, e),
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Replace the live database with the contents of a backup.
+    ///
+    /// The backup is integrity-checked first and the current state is
+    /// snapshotted as `pre-restore`, which is returned so the restore can be
+    /// undone. The copy happens in place through the backup API while the
+    /// connection is held, so other users of this `Database` see either the
+    /// old or the restored data. Older backups are migrated to the current
+    /// schema. Callers are expected to pause workers around this.
+    pub fn restore_backup(&self, file_name: &str) -> Result<BackupInfo, DbError> {
+        let db_path = self.require_path()?;
+        let source_path = backup_dir(db_path).join(file_name);
+        if file_name.contains(['/', '\\']) || backup_info(db_path, &source_path).is_none() {
+            return Err(DbError::NotFound(format!("Backup {}", file_name)));
+        }
+
+        let source = Connection::open_with_flags(&source_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let integrity: String = source.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+        if integrity != "ok" {
+            return Err(DbError::Validation(format!("Backup {} is corrupt: {}", file_name, integrity)));
+        }
+
+        let snapshot = self.create_backup("pre-restore")?;
+        self.with_conn_mut(|conn| {
+            Backup::new(&source, conn)?.run_to_completion(256, std::time::Duration::ZERO, None)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON")?;
+            migrations::run(conn, None)
+        })?;
+        tracing::warn!("Restored database from backup {}", file_name);
+        Ok(snapshot)
+    }
+}
+
+/// Describe `path` if it is a finished backup of the database at `db_path`
+fn backup_info(db_path: &Path, path: &Path) -> Option<BackupInfo> {
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let stem = db_path.file_stem()?.to_str()?;
+    let rest = file_name.strip_suffix(".db")?.strip_prefix(stem)?.strip_prefix('-')?;
+    let (reason, timestamp) = rest.rsplit_once('-')?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, LEGACY_TIMESTAMP_FORMAT))
+        .ok()?
+        .and_utc();
+    let size_bytes = std::fs::metadata(path).ok()?.len();
+
+    Some(BackupInfo {
+        file_name: file_name.clone(),
+        reason: reason.to_string(),
+        created_at,
+        size_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db() -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open(dir.path().join("agent-kanban.db")).unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn restore_brings_back_deleted_board_and_can_be_undone() {
+        let (_dir, db) = temp_db();
+        let board = db.create_board("Keep me").unwrap();
+
+        db.delete_board(&board.id).unwrap();
+        assert!(db.get_board(&board.id).unwrap().is_none());
+
+        // delete_board snapshotted the database first
+        let backups = db.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].reason, "pre-delete-board");
+
+        let undo = db.restore_backup(&backups[0].file_name).unwrap();
+        assert_eq!(undo.reason, "pre-restore");
+        assert_eq!(db.get_board(&board.id).unwrap().unwrap().name, "Keep me");
+
+        // The pre-restore snapshot holds the state from before the restore
+        db.restore_backup(&undo.file_name).unwrap();
+        assert!(db.get_board(&board.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn restore_rejects_unknown_and_corrupt_backups() {
+        let (dir, db) = temp_db();
+        assert!(matches!(db.restore_backup("../agent-kanban.db"), Err(DbError::NotFound(_))));
+        assert!(matches!(db.restore_backup("nope.db"), Err(DbError::NotFound(_))));
+
+        let bogus = "agent-kanban-manual-20260101T000000000Z.db";
+        std::fs::create_dir_all(dir.path().join("backups")).unwrap();
+        std::fs::write(dir.path().join("backups").join(bogus), b"not a database").unwrap();
+        assert!(db.restore_backup(bogus).is_err());
+        // Nothing was snapshotted or changed
+        assert_eq!(db.list_backups().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn prune_keeps_newest_per_reason() {
+        let (dir, db) = temp_db();
+        let backups = backup_dir(&dir.path().join("agent-kanban.db"));
+        std::fs::create_dir_all(&backups).unwrap();
+        let old = (Utc::now() - chrono::Duration::days(90)).format(TIMESTAMP_FORMAT).to_string();
+        let older = (Utc::now() - chrono::Duration::days(91)).format(TIMESTAMP_FORMAT).to_string();
+        for name in [
+            format!("agent-kanban-pre-delete-board-{}.db", old),
+            format!("agent-kanban-scheduled-{}.db", older),
+            "unrelated.db".to_string(),
+        ] {
+            std::fs::write(backups.join(name), b"").unwrap();
+        }
+        for _ in 0..3 {
+            db.create_backup("scheduled").unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let policy = BackupPolicy { interval_mins: 60, keep_last: 2, max_age_days: Some(30) };
+        assert_eq!(db.prune_backups(&policy).unwrap(), 2);
+
+        let left: Vec<_> = db.list_backups().unwrap().into_iter().map(|b| b.reason).collect();
+        // Two newest scheduled backups, and the only (old) pre-delete snapshot
+        assert_eq!(left, vec!["scheduled", "scheduled", "pre-delete-board"]);
+        assert!(backups.join("unrelated.db").exists());
+    }
+
+    #[test]
+    fn deletes_go_ahead_when_the_snapshot_fails() {
+        let (dir, db) = temp_db();
+        let board = db.create_board("Doomed").unwrap();
+        // A file where the backups directory belongs makes every backup fail
+        std::fs::write(dir.path().join("backups"), b"").unwrap();
+        assert!(db.snapshot("delete-board").is_err());
+
+        db.delete_board(&board.id).unwrap();
+        assert!(db.get_board(&board.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn deleting_missing_items_takes_no_snapshot() {
+        let (_dir, db) = temp_db();
+        assert!(matches!(db.delete_board("nope"), Err(DbError::NotFound(_))));
+        assert!(matches!(db.delete_scratchpad_with_tickets("nope"), Err(DbError::NotFound(_))));
+        assert!(db.list_backups().unwrap().is_empty());
+    }
+
+    #[test]
+    fn in_memory_databases_skip_snapshots() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(db.snapshot("delete-board").unwrap().is_none());
+        assert!(db.list_backups().unwrap().is_empty());
+        assert!(db.create_backup("manual").is_err());
+    }
+}
@@ -1,6 +1,12 @@
+use rusqlite::{Connection, OptionalExtension};
+
 use crate::db::{Database, DbError, parse_datetime};
-use crate::db::models::{Board, Column};
+use crate::db::history::record_change;
+use crate::db::models::{Actor, Board, Column, CreateColumn, UpdateColumn};
 use crate::db::schema::DEFAULT_COLUMNS;
+use crate::lifecycle::TicketState;
+
+const COLUMN_SELECT: &str = "SELECT id, board_id, name, position, wip_limit, role FROM columns";
 
 impl Database {
     pub fn create_board(&self, name: &str) -> Result<Board, DbError> {
@@ -15,11 +21,11 @@ impl Database {
                 rusqlite::params![board_id, name, now.to_rfc3339(), now.to_rfc3339()],
             )?;
 
-            for (position, col_name) in DEFAULT_COLUMNS.iter().enumerate() {
+            for (position, (col_name, role)) in DEFAULT_COLUMNS.iter().enumerate() {
                 let col_id = uuid::Uuid::new_v4().to_string();
                 tx.execute(
-                    "INSERT INTO columns (id, board_id, name, position) VALUES (?, ?, ?, ?)",
-                    rusqlite::params![col_id, board_id, col_name, position as i32],
+                    "INSERT INTO columns (id, board_id, name, position, role) VALUES (?, ?, ?, ?, ?)",
+                    rusqlite::params![col_id, board_id, col_name, position as i32, role.as_str()],
                 )?;
             }
 
@@ -36,9 +42,9 @@ impl Database {
     }
 
     pub fn get_boards(&self) -> Result<Vec<Board>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
-                "SELECT id, name, default_project_id, created_at, updated_at FROM boards ORDER BY created_at DESC"
+                "SELECT id, name, default_project_id, created_at, updated_at FROM boards WHERE trash_id IS NULL ORDER BY created_at DESC"
             )?;
             
             let boards = stmt.query_map([], |row| {
@@ -63,25 +69,11 @@ impl Database {
     }
 
     pub fn get_columns(&self, board_id: &str) -> Result<Vec<Column>, DbError> {
-        self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, board_id, name, position, wip_limit 
-                 FROM columns WHERE board_id = ? ORDER BY position"
-            )?;
-            
-            let columns = stmt.query_map([board_id], |row| {
-                Ok(Column {
-                    id: row.get(0)?,
-                    board_id: row.get(1)?,
-                    name: row.get(2)?,
-                    position: row.get(3)?,
-                    wip_limit: row.get(4)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-            
-            Ok(columns)
-        })
+        self.with_reader(|conn| get_columns(conn, board_id))
+    }
+
+    pub fn get_column(&self, column_id: &str) -> Result<Option<Column>, DbError> {
+        self.with_reader(|conn| get_column(conn, column_id))
     }
 
     /// Find a column by name (case-insensitive)
@@ -91,6 +83,214 @@ impl Database {
         Ok(columns.into_iter().find(|c| c.name.to_lowercase() == name_lower))
     }
 
+    /// Find the column that plays `role` on a board, whatever it is called
+    pub fn find_column_by_role(&self, board_id: &str, role: TicketState) -> Result<Option<Column>, DbError> {
+        self.with_reader(|conn| {
+            let column = conn.query_row(
+                &format!("{} WHERE board_id = ? AND role = ?", COLUMN_SELECT),
+                rusqlite::params![board_id, role.as_str()],
+                map_column_row,
+            ).optional()?;
+            Ok(column)
+        })
+    }
+
+    /// Like [`Database::find_column_by_role`], but a missing column is an error
+    pub fn require_column_by_role(&self, board_id: &str, role: TicketState) -> Result<Column, DbError> {
+        self.find_column_by_role(board_id, role)?.ok_or_else(|| {
+            DbError::NotFound(format!("Board {} has no {} column", board_id, role.as_str()))
+        })
+    }
+
+    /// Lifecycle role of the column a ticket is in (`None` for custom columns)
+    pub fn get_column_role(&self, column_id: &str) -> Result<Option<TicketState>, DbError> {
+        Ok(self.get_column(column_id)?.and_then(|c| c.role))
+    }
+
+    pub fn create_column(&self, board_id: &str, input: &CreateColumn) -> Result<Column, DbError> {
+        let name = validate_column_name(&input.name)?;
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM boards WHERE id = ?)",
+                [board_id],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                return Err(DbError::NotFound(format!("Board {}", board_id)));
+            }
+            if let Some(role) = input.role {
+                ensure_role_free(&tx, board_id, role, None)?;
+            }
+
+            let count: i32 = tx.query_row(
+                "SELECT COUNT(*) FROM columns WHERE board_id = ?",
+                [board_id],
+                |row| row.get(0),
+            )?;
+            let position = input.position.unwrap_or(count).clamp(0, count);
+            // Shift later columns right in two steps so UNIQUE(board_id, position)
+            // never sees a duplicate
+            tx.execute(
+                "UPDATE columns SET position = -position - 2 WHERE board_id = ? AND position >= ?",
+                rusqlite::params![board_id, position],
+            )?;
+            tx.execute(
+                "UPDATE columns SET position = -position - 1 WHERE board_id = ? AND position < 0",
+                [board_id],
+            )?;
+
+            let column_id = uuid::Uuid::new_v4().to_string();
+            tx.execute(
+                "INSERT INTO columns (id, board_id, name, position, wip_limit, role) VALUES (?, ?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    column_id,
+                    board_id,
+                    name,
+                    position,
+                    input.wip_limit.filter(|l| *l > 0),
+                    input.role.map(|r| r.as_str()),
+                ],
+            )?;
+
+            let column = get_column(&tx, &column_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Column {}", column_id)))?;
+            tx.commit()?;
+            Ok(column)
+        })
+    }
+
+    /// Rename a column, change its role or WIP limit
+    pub fn update_column(&self, column_id: &str, input: &UpdateColumn) -> Result<Column, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let existing = get_column(&tx, column_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Column {}", column_id)))?;
+
+            let name = match &input.name {
+                Some(name) => validate_column_name(name)?,
+                None => existing.name.clone(),
+            };
+            let role = match input.role {
+                Some(Some(role)) => {
+                    ensure_role_free(&tx, &existing.board_id, role, Some(column_id))?;
+                    Some(role)
+                }
+                Some(None) => None,
+                None => existing.role,
+            };
+            let wip_limit = match input.wip_limit {
+                Some(limit) if limit <= 0 => None,
+                Some(limit) => Some(limit),
+                None => existing.wip_limit,
+            };
+
+            tx.execute(
+                "UPDATE columns SET name = ?, role = ?, wip_limit = ? WHERE id = ?",
+                rusqlite::params![name, role.map(|r| r.as_str()), wip_limit, column_id],
+            )?;
+            let column = get_column(&tx, column_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Column {}", column_id)))?;
+            tx.commit()?;
+            Ok(column)
+        })
+    }
+
+    /// Reorder a board's columns; `column_ids` must list every column exactly once
+    pub fn reorder_columns(&self, board_id: &str, column_ids: &[String]) -> Result<Vec<Column>, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let current = get_columns(&tx, board_id)?;
+
+            let mut expected: Vec<&str> = current.iter().map(|c| c.id.as_str()).collect();
+            let mut given: Vec<&str> = column_ids.iter().map(String::as_str).collect();
+            expected.sort_unstable();
+            given.sort_unstable();
+            if expected != given {
+                return Err(DbError::Validation(
+                    "Column order must list every column of the board exactly once".to_string(),
+                ));
+            }
+
+            tx.execute(
+                "UPDATE columns SET position = -position - 1 WHERE board_id = ?",
+                [board_id],
+            )?;
+            for (position, column_id) in column_ids.iter().enumerate() {
+                tx.execute(
+                    "UPDATE columns SET position = ? WHERE id = ?",
+                    rusqlite::params![position as i32, column_id],
+                )?;
+            }
+
+            let columns = get_columns(&tx, board_id)?;
+            tx.commit()?;
+            Ok(columns)
+        })
+    }
+
+    /// Delete a column. Tickets in it are moved to `move_tickets_to` (a column
+    /// on the same board); deleting a non-empty column without a target fails.
+    pub fn delete_column(&self, column_id: &str, move_tickets_to: Option<&str>) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let column = get_column(&tx, column_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Column {}", column_id)))?;
+
+            let remaining: i32 = tx.query_row(
+                "SELECT COUNT(*) FROM columns WHERE board_id = ? AND id != ?",
+                rusqlite::params![column.board_id, column_id],
+                |row| row.get(0),
+            )?;
+            if remaining == 0 {
+                return Err(DbError::Validation("A board needs at least one column".to_string()));
+            }
+
+            let ticket_count: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM tickets WHERE column_id = ?",
+                [column_id],
+                |row| row.get(0),
+            )?;
+            if ticket_count > 0 {
+                let target_id = move_tickets_to.ok_or_else(|| DbError::Validation(format!(
+                    "Column '{}' still has {} ticket(s); choose a column to move them to",
+                    column.name, ticket_count
+                )))?;
+                let target = get_column(&tx, target_id)?
+                    .filter(|t| t.board_id == column.board_id && t.id != column_id)
+                    .ok_or_else(|| DbError::Validation(format!(
+                        "Column {} is not another column on the same board", target_id
+                    )))?;
+                let moved_ids = {
+                    let mut stmt = tx.prepare("SELECT id FROM tickets WHERE column_id = ?")?;
+                    let rows = stmt.query_map([column_id], |row| row.get::<_, String>(0))?;
+                    rows.collect::<Result<Vec<_>, _>>()?
+                };
+                tx.execute(
+                    "UPDATE tickets SET column_id = ?, updated_at = ? WHERE column_id = ?",
+                    rusqlite::params![target.id, chrono::Utc::now().to_rfc3339(), column_id],
+                )?;
+                for ticket_id in &moved_ids {
+                    record_change(&tx, ticket_id, "columnId", Some(column_id), Some(&target.id), &Actor::user())?;
+                }
+            }
+
+            tx.execute("DELETE FROM columns WHERE id = ?", [column_id])?;
+            // Close the gap left behind
+            tx.execute(
+                "UPDATE columns SET position = -position WHERE board_id = ? AND position > ?",
+                rusqlite::params![column.board_id, column.position],
+            )?;
+            tx.execute(
+                "UPDATE columns SET position = -position - 1 WHERE board_id = ? AND position < 0",
+                [&column.board_id],
+            )?;
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
 
     pub fn update_board(&self, board_id: &str, name: &str) -> Result<Board, DbError> {
         self.with_conn_mut(|conn| {
@@ -124,6 +324,9 @@ impl Database {
     }
 
     pub fn delete_board(&self, board_id: &str) -> Result<(), DbError> {
+        if self.get_board(board_id)?.is_some() {
+            self.snapshot_best_effort("delete-board");
+        }
         self.with_conn_mut(|conn| {
             let tx = conn.transaction()?;
             
@@ -147,6 +350,62 @@ impl Database {
     }
 }
 
+fn map_column_row(row: &rusqlite::Row) -> rusqlite::Result<Column> {
+    let role: Option<String> = row.get(5)?;
+    Ok(Column {
+        id: row.get(0)?,
+        board_id: row.get(1)?,
+        name: row.get(2)?,
+        position: row.get(3)?,
+        wip_limit: row.get(4)?,
+        role: role.as_deref().and_then(TicketState::parse),
+    })
+}
+
+fn get_columns(conn: &Connection, board_id: &str) -> Result<Vec<Column>, DbError> {
+    let mut stmt = conn.prepare(&format!("{} WHERE board_id = ? ORDER BY position", COLUMN_SELECT))?;
+    let columns = stmt.query_map([board_id], map_column_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+fn get_column(conn: &Connection, column_id: &str) -> Result<Option<Column>, DbError> {
+    let column = conn.query_row(
+        &format!("{} WHERE id = ?", COLUMN_SELECT),
+        [column_id],
+        map_column_row,
+    ).optional()?;
+    Ok(column)
+}
+
+fn validate_column_name(name: &str) -> Result<String, DbError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DbError::Validation("Column name cannot be empty".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+/// Roles are unique per board: two "ready" columns would make the queue ambiguous
+fn ensure_role_free(
+    conn: &Connection,
+    board_id: &str,
+    role: TicketState,
+    except_column_id: Option<&str>,
+) -> Result<(), DbError> {
+    let holder: Option<String> = conn.query_row(
+        "SELECT name FROM columns WHERE board_id = ? AND role = ? AND id != ?",
+        rusqlite::params![board_id, role.as_str(), except_column_id.unwrap_or("")],
+        |row| row.get(0),
+    ).optional()?;
+    match holder {
+        Some(name) => Err(DbError::Validation(format!(
+            "Column '{}' already has the {} role", name, role.as_str()
+        ))),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,6 +426,124 @@ mod tests {
         assert_eq!(columns.len(), 6);
         assert_eq!(columns[0].name, "Backlog");
         assert_eq!(columns[5].name, "Done");
+        assert_eq!(columns[0].role, Some(TicketState::Backlog));
+        assert_eq!(columns[2].role, Some(TicketState::InProgress));
+    }
+
+    #[test]
+    fn find_column_by_role_survives_rename() {
+        let db = create_test_db();
+        let board = db.create_board("Test").unwrap();
+        let ready = db.require_column_by_role(&board.id, TicketState::Ready).unwrap();
+
+        db.update_column(&ready.id, &UpdateColumn {
+            name: Some("Up Next".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        assert!(db.find_column_by_name(&board.id, "Ready").unwrap().is_none());
+        let found = db.find_column_by_role(&board.id, TicketState::Ready).unwrap().unwrap();
+        assert_eq!(found.id, ready.id);
+        assert_eq!(found.name, "Up Next");
+    }
+
+    #[test]
+    fn create_column_inserts_at_position() {
+        let db = create_test_db();
+        let board = db.create_board("Test").unwrap();
+
+        let qa = db.create_column(&board.id, &CreateColumn {
+            name: "QA".to_string(),
+            position: Some(4),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(qa.position, 4);
+        assert_eq!(qa.role, None);
+
+        let names: Vec<_> = db.get_columns(&board.id).unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["Backlog", "Ready", "In Progress", "Blocked", "QA", "Review", "Done"]);
+
+        let last = db.create_column(&board.id, &CreateColumn {
+            name: "Archive".to_string(),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(last.position, 7);
+    }
+
+    #[test]
+    fn roles_are_unique_per_board() {
+        let db = create_test_db();
+        let board = db.create_board("Test").unwrap();
+
+        let err = db.create_column(&board.id, &CreateColumn {
+            name: "Also Ready".to_string(),
+            role: Some(TicketState::Ready),
+            ..Default::default()
+        }).unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)));
+
+        // Moving the role frees it for another column
+        let ready = db.require_column_by_role(&board.id, TicketState::Ready).unwrap();
+        db.update_column(&ready.id, &UpdateColumn { role: Some(None), ..Default::default() }).unwrap();
+        let queue = db.create_column(&board.id, &CreateColumn {
+            name: "Queue".to_string(),
+            role: Some(TicketState::Ready),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(db.require_column_by_role(&board.id, TicketState::Ready).unwrap().id, queue.id);
+
+        let err = db.update_column(&queue.id, &UpdateColumn {
+            role: Some(Some(TicketState::Done)),
+            ..Default::default()
+        }).unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)));
+    }
+
+    #[test]
+    fn update_column_role_distinguishes_null_from_missing() {
+        let keep: UpdateColumn = serde_json::from_str(r#"{"name":"Queue"}"#).unwrap();
+        assert_eq!(keep.role, None);
+        let clear: UpdateColumn = serde_json::from_str(r#"{"role":null}"#).unwrap();
+        assert_eq!(clear.role, Some(None));
+        let set: UpdateColumn = serde_json::from_str(r#"{"role":"in_progress"}"#).unwrap();
+        assert_eq!(set.role, Some(Some(TicketState::InProgress)));
+        assert!(serde_json::from_str::<UpdateColumn>(r#"{"role":"qa"}"#).is_err());
+    }
+
+    #[test]
+    fn reorder_columns_requires_every_column() {
+        let db = create_test_db();
+        let board = db.create_board("Test").unwrap();
+        let mut ids: Vec<String> = db.get_columns(&board.id).unwrap().into_iter().map(|c| c.id).collect();
+        ids.reverse();
+
+        let columns = db.reorder_columns(&board.id, &ids).unwrap();
+        assert_eq!(columns[0].name, "Done");
+        assert_eq!(columns[5].name, "Backlog");
+
+        assert!(db.reorder_columns(&board.id, &ids[1..]).is_err());
+    }
+
+    #[test]
+    fn delete_column_moves_tickets_and_closes_gap() {
+        use crate::db::models::CreateTicket;
+
+        let db = create_test_db();
+        let board = db.create_board("Test").unwrap();
+        let blocked = db.require_column_by_role(&board.id, TicketState::Blocked).unwrap();
+        let backlog = db.require_column_by_role(&board.id, TicketState::Backlog).unwrap();
+        let ticket = db.create_ticket(&CreateTicket::for_test(&board.id, &blocked.id, "Stuck")).unwrap();
+
+        let err = db.delete_column(&blocked.id, None).unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)));
+
+        db.delete_column(&blocked.id, Some(&backlog.id)).unwrap();
+        assert_eq!(db.get_ticket(&ticket.id).unwrap().column_id, backlog.id);
+
+        let columns = db.get_columns(&board.id).unwrap();
+        let positions: Vec<_> = columns.iter().map(|c| c.position).collect();
+        assert_eq!(positions, [0, 1, 2, 3, 4]);
+        assert!(db.find_column_by_role(&board.id, TicketState::Blocked).unwrap().is_none());
     }
 
     #[test]
@@ -0,0 +1,721 @@
+//! Board export/import as portable bundles
+//!
+//! `export_board` collects a board into a [`BoardBundle`]; `import_board`
+//! recreates it under fresh ids in one transaction. `render_markdown` turns a
+//! bundle into a readable document of tickets and plans.
+
+use std::collections::HashMap;
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::db::{parse_datetime, Database, DbError};
+use crate::db::labels::catalog_labels;
+use crate::db::models::{
+    Board, BoardBundle, BoardImport, BundleArchiveEntry, Project, RunStatus, TaskStatus, Ticket,
+    BOARD_BUNDLE_VERSION,
+};
+
+impl Database {
+    /// Collect a board into a bundle, archived tickets included. Runs and
+    /// their events are large and machine-specific, so they are only
+    /// included when asked for.
+    pub fn export_board(&self, board_id: &str, include_runs: bool) -> Result<BoardBundle, DbError> {
+        let board = self.get_board(board_id)?
+            .ok_or_else(|| DbError::NotFound(format!("Board {}", board_id)))?;
+        let columns = self.get_columns(board_id)?;
+        let custom_fields = self.get_custom_fields(board_id)?;
+        let labels = self.get_labels(board_id)?;
+        let (tickets, archived) = self.with_reader(|conn| bundle_tickets(conn, board_id))?;
+        let scratchpads = self.get_scratchpads(board_id)?;
+
+        let mut tasks = Vec::new();
+        let mut comments = Vec::new();
+        let mut runs = Vec::new();
+        let mut events = Vec::new();
+        for ticket in &tickets {
+            tasks.extend(self.get_tasks_for_ticket(&ticket.id)?);
+            comments.extend(self.get_comments(&ticket.id)?);
+            if include_runs {
+                for run in self.get_runs(&ticket.id)? {
+                    events.extend(self.get_events(&run.id)?);
+                    runs.push(run);
+                }
+            }
+        }
+
+        let mut project_ids: Vec<&str> = board.default_project_id.iter()
+            .chain(tickets.iter().filter_map(|t| t.project_id.as_ref()))
+            .map(String::as_str)
+            .chain(scratchpads.iter().map(|s| s.project_id.as_str()))
+            .collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        let projects = self.get_projects()?
+            .into_iter()
+            .filter(|p| project_ids.binary_search(&p.id.as_str()).is_ok())
+            .collect();
+
+        Ok(BoardBundle {
+            format_version: BOARD_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now(),
+            board,
+            columns,
+            custom_fields,
+            labels,
+            tickets,
+            archived,
+            tasks,
+            comments,
+            scratchpads,
+            projects,
+            runs,
+            events,
+        })
+    }
+
+    /// Recreate a bundle as a new board, named `name` or after the exported board.
+    ///
+    /// Every record gets a new id and references between them are rewritten,
+    /// so importing the same bundle twice gives two independent boards. Ticket
+    /// locks are dropped and unfinished runs are marked aborted. Projects are
+    /// matched to local ones by id, then by path; references to projects that
+    /// do not exist here are cleared, and scratchpads that need one are skipped.
+    pub fn import_board(&self, bundle: &BoardBundle, name: Option<&str>) -> Result<BoardImport, DbError> {
+        if bundle.format_version > BOARD_BUNDLE_VERSION {
+            return Err(DbError::Validation(format!(
+                "Bundle format version {} is newer than this build supports ({})",
+                bundle.format_version, BOARD_BUNDLE_VERSION
+            )));
+        }
+
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            // Tickets reference each other (epics, dependencies) and runs
+            // reference their parents, so check foreign keys at commit
+            tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+            let conn = &*tx;
+
+            let mut warnings = Vec::new();
+            let now = chrono::Utc::now();
+
+            let mut projects = HashMap::new();
+            for project in &bundle.projects {
+                match find_local_project(conn, project)? {
+                    Some(local_id) => {
+                        projects.insert(project.id.as_str(), local_id);
+                    }
+                    None => warnings.push(format!(
+                        "Project '{}' ({}) does not exist here; references to it were cleared",
+                        project.name, project.path
+                    )),
+                }
+            }
+            let project = |id: &Option<String>| id.as_ref().and_then(|id| projects.get(id.as_str()).cloned());
+
+            let board = Board {
+                id: new_id(),
+                name: name.map(str::to_string).unwrap_or_else(|| bundle.board.name.clone()),
+                default_project_id: project(&bundle.board.default_project_id),
+                created_at: now,
+                updated_at: now,
+            };
+            conn.execute(
+                "INSERT INTO boards (id, name, default_project_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+                rusqlite::params![board.id, board.name, board.default_project_id, now.to_rfc3339(), now.to_rfc3339()],
+            )?;
+
+            let mut columns = HashMap::new();
+            for column in &bundle.columns {
+                let id = new_id();
+                conn.execute(
+                    "INSERT INTO columns (id, board_id, name, position, wip_limit, role) VALUES (?, ?, ?, ?, ?, ?)",
+                    rusqlite::params![
+                        id, board.id, column.name, column.position, column.wip_limit,
+                        column.role.as_ref().map(|r| r.as_str()),
+                    ],
+                )?;
+                columns.insert(column.id.as_str(), id);
+            }
+
+            for field in &bundle.custom_fields {
+                conn.execute(
+                    r#"INSERT INTO custom_fields
+                       (id, board_id, key, name, field_type, options_json, required, position, created_at)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        new_id(),
+                        board.id,
+                        field.key,
+                        field.name,
+                        field.field_type.as_str(),
+                        to_json(&field.options),
+                        field.required,
+                        field.position,
+                        now.to_rfc3339(),
+                    ],
+                )?;
+            }
+
+            for label in &bundle.labels {
+                conn.execute(
+                    r#"INSERT OR IGNORE INTO labels (id, board_id, name, color, description, created_at)
+                       VALUES (?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![new_id(), board.id, label.name, label.color, label.description, now.to_rfc3339()],
+                )?;
+            }
+
+            let ticket_ids: HashMap<&str, String> = bundle.tickets.iter()
+                .map(|t| (t.id.as_str(), new_id()))
+                .collect();
+            let ticket_ref = |id: &Option<String>| id.as_ref().and_then(|id| ticket_ids.get(id.as_str()).cloned());
+
+            let mut scratchpads = HashMap::new();
+            for pad in &bundle.scratchpads {
+                let Some(project_id) = projects.get(pad.project_id.as_str()) else {
+                    warnings.push(format!("Skipped scratchpad '{}': its project does not exist here", pad.name));
+                    continue;
+                };
+                let id = new_id();
+                // Plans aimed at the exported board now target the imported one
+                let target_board_id = match &pad.target_board_id {
+                    Some(target) if *target == bundle.board.id => Some(board.id.clone()),
+                    Some(target) => target_board_exists(conn, target)?.then(|| target.clone()),
+                    None => None,
+                };
+                conn.execute(
+                    r#"INSERT INTO scratchpads
+                       (id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model,
+                        exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        id,
+                        board.id,
+                        target_board_id,
+                        project_id,
+                        pad.name,
+                        pad.user_input,
+                        pad.status.as_str(),
+                        pad.agent_pref,
+                        pad.model,
+                        to_json(&pad.exploration_log),
+                        pad.plan_markdown,
+                        pad.plan_json.as_ref().map(to_json),
+                        to_json(&pad.settings),
+                        pad.created_at.to_rfc3339(),
+                        pad.updated_at.to_rfc3339(),
+                    ],
+                )?;
+                scratchpads.insert(pad.id.as_str(), id);
+            }
+
+            for ticket in &bundle.tickets {
+                let column_id = columns.get(ticket.column_id.as_str()).ok_or_else(|| {
+                    DbError::Validation(format!("Ticket '{}' is in a column missing from the bundle", ticket.title))
+                })?;
+                let depends_on_epic_ids: Vec<String> = ticket.depends_on_epic_ids.iter()
+                    .filter_map(|id| ticket_ids.get(id.as_str()).cloned())
+                    .collect();
+                conn.execute(
+                    r#"INSERT INTO tickets
+                       (id, board_id, column_id, title, description_md, priority, labels_json,
+                        created_at, updated_at, project_id, agent_pref, workflow_type, model, branch_name,
+                        is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id,
+                        custom_fields_json)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        ticket_ids[ticket.id.as_str()],
+                        board.id,
+                        column_id,
+                        ticket.title,
+                        ticket.description_md,
+                        ticket.priority.as_str(),
+                        to_json(&catalog_labels(conn, &board.id, &ticket.labels)?),
+                        ticket.created_at.to_rfc3339(),
+                        ticket.updated_at.to_rfc3339(),
+                        project(&ticket.project_id),
+                        ticket.agent_pref.as_ref().map(|p| p.as_str()),
+                        ticket.workflow_type.as_str(),
+                        ticket.model,
+                        ticket.branch_name,
+                        ticket.is_epic,
+                        ticket_ref(&ticket.epic_id),
+                        ticket.order_in_epic,
+                        ticket_ref(&ticket.depends_on_epic_id),
+                        (!depends_on_epic_ids.is_empty()).then(|| to_json(&depends_on_epic_ids)),
+                        ticket.scratchpad_id.as_ref().and_then(|id| scratchpads.get(id.as_str())),
+                        to_json(&ticket.custom_fields),
+                    ],
+                )?;
+            }
+
+            for entry in &bundle.archived {
+                let Some(ticket_id) = ticket_ids.get(entry.ticket_id.as_str()) else { continue };
+                conn.execute(
+                    "INSERT INTO ticket_archive (ticket_id, archived_at) VALUES (?, ?)",
+                    rusqlite::params![ticket_id, entry.archived_at.to_rfc3339()],
+                )?;
+            }
+
+            let run_ids: HashMap<&str, String> = bundle.runs.iter()
+                .filter(|r| ticket_ids.contains_key(r.ticket_id.as_str()))
+                .map(|r| (r.id.as_str(), new_id()))
+                .collect();
+            for run in &bundle.runs {
+                let Some(id) = run_ids.get(run.id.as_str()) else { continue };
+                let (status, ended_at) = match run.status {
+                    RunStatus::Queued | RunStatus::Running => (RunStatus::Aborted, Some(run.ended_at.unwrap_or(now))),
+                    ref status => (status.clone(), run.ended_at),
+                };
+                conn.execute(
+                    r#"INSERT INTO agent_runs
+                       (id, ticket_id, agent_type, repo_path, status, started_at, ended_at, exit_code,
+                        summary_md, metadata_json, parent_run_id, stage)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        id,
+                        ticket_ids[run.ticket_id.as_str()],
+                        run.agent_type.as_str(),
+                        run.repo_path,
+                        status.as_str(),
+                        run.started_at.to_rfc3339(),
+                        ended_at.map(|t| t.to_rfc3339()),
+                        run.exit_code,
+                        run.summary_md,
+                        run.metadata.as_ref().map(to_json),
+                        run.parent_run_id.as_ref().and_then(|p| run_ids.get(p.as_str())),
+                        run.stage,
+                    ],
+                )?;
+            }
+
+            for event in &bundle.events {
+                let (Some(run_id), Some(ticket_id)) = (
+                    run_ids.get(event.run_id.as_str()),
+                    ticket_ids.get(event.ticket_id.as_str()),
+                ) else {
+                    continue;
+                };
+                conn.execute(
+                    r#"INSERT INTO agent_events (id, run_id, ticket_id, event_type, payload_json, created_at)
+                       VALUES (?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        new_id(),
+                        run_id,
+                        ticket_id,
+                        event.event_type.as_str(),
+                        to_json(&event.payload),
+                        event.created_at.to_rfc3339(),
+                    ],
+                )?;
+            }
+
+            for task in &bundle.tasks {
+                let Some(ticket_id) = ticket_ids.get(task.ticket_id.as_str()) else { continue };
+                // Nothing is working on an imported task yet
+                let status = match task.status {
+                    TaskStatus::InProgress => TaskStatus::Pending,
+                    ref status => status.clone(),
+                };
+                conn.execute(
+                    r#"INSERT INTO tasks
+                       (id, ticket_id, order_index, task_type, title, content, status, run_id,
+                        created_at, started_at, completed_at)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        new_id(),
+                        ticket_id,
+                        task.order_index,
+                        task.task_type.as_str(),
+                        task.title,
+                        task.content,
+                        status.as_str(),
+                        task.run_id.as_ref().and_then(|r| run_ids.get(r.as_str())),
+                        task.created_at.to_rfc3339(),
+                        task.started_at.map(|t| t.to_rfc3339()),
+                        task.completed_at.map(|t| t.to_rfc3339()),
+                    ],
+                )?;
+            }
+
+            for comment in &bundle.comments {
+                let Some(ticket_id) = ticket_ids.get(comment.ticket_id.as_str()) else { continue };
+                conn.execute(
+                    r#"INSERT INTO comments (id, ticket_id, author_type, body_md, created_at, metadata_json)
+                       VALUES (?, ?, ?, ?, ?, ?)"#,
+                    rusqlite::params![
+                        new_id(),
+                        ticket_id,
+                        comment.author_type.as_str(),
+                        comment.body_md,
+                        comment.created_at.to_rfc3339(),
+                        comment.metadata.as_ref().map(to_json),
+                    ],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(BoardImport { board, warnings })
+        })
+    }
+
+    /// A board as a Markdown document of its tickets and plans.
+    pub fn export_board_markdown(&self, board_id: &str) -> Result<String, DbError> {
+        self.export_board(board_id, false).map(|bundle| render_markdown(&bundle))
+    }
+}
+
+/// Render a bundle as Markdown: one section per column with its tickets
+/// (metadata, description, task checklist), followed by the scratchpad plans.
+/// Archived tickets are left out, as they are on the board.
+pub fn render_markdown(bundle: &BoardBundle) -> String {
+    let archived: Vec<&str> = bundle.archived.iter().map(|a| a.ticket_id.as_str()).collect();
+    let titles: HashMap<&str, &str> = bundle.tickets.iter()
+        .map(|t| (t.id.as_str(), t.title.as_str()))
+        .collect();
+    let title_of = |id: &str| titles.get(id).copied().unwrap_or(id).to_string();
+
+    let mut out = format!("# {}\n", bundle.board.name);
+
+    let mut columns: Vec<_> = bundle.columns.iter().collect();
+    columns.sort_by_key(|c| c.position);
+    for column in columns {
+        let tickets: Vec<&Ticket> = bundle.tickets.iter()
+            .filter(|t| t.column_id == column.id && !archived.contains(&t.id.as_str()))
+            .collect();
+        out.push_str(&format!("\n## {} ({})\n", column.name, tickets.len()));
+
+        for ticket in tickets {
+            let kind = if ticket.is_epic { "Epic: " } else { "" };
+            out.push_str(&format!("\n### {}{}\n\n", kind, ticket.title));
+
+            let mut meta = vec![format!("Priority: {}", ticket.priority.as_str())];
+            if !ticket.labels.is_empty() {
+                meta.push(format!("Labels: {}", ticket.labels.join(", ")));
+            }
+            if let Some(epic_id) = &ticket.epic_id {
+                meta.push(format!("Epic: {}", title_of(epic_id)));
+            }
+            let depends_on: Vec<String> = ticket.depends_on_epic_id.iter()
+                .chain(ticket.depends_on_epic_ids.iter())
+                .map(|id| title_of(id))
+                .collect();
+            if !depends_on.is_empty() {
+                meta.push(format!("Depends on: {}", depends_on.join(", ")));
+            }
+            if let Some(branch) = &ticket.branch_name {
+                meta.push(format!("Branch: `{}`", branch));
+            }
+            out.push_str(&format!("{}\n", meta.join(" · ")));
+
+            let description = ticket.description_md.trim();
+            if !description.is_empty() {
+                out.push_str(&format!("\n{}\n", description));
+            }
+
+            let mut tasks: Vec<_> = bundle.tasks.iter().filter(|t| t.ticket_id == ticket.id).collect();
+            tasks.sort_by_key(|t| t.order_index);
+            if !tasks.is_empty() {
+                out.push_str("\n**Tasks**\n\n");
+                for task in tasks {
+                    let mark = if task.status == TaskStatus::Completed { "x" } else { " " };
+                    let title = task.title.as_deref().unwrap_or(task.task_type.display_name());
+                    out.push_str(&format!("- [{}] {}\n", mark, title));
+                }
+            }
+        }
+    }
+
+    let plans: Vec<_> = bundle.scratchpads.iter().filter(|s| s.plan_markdown.is_some()).collect();
+    if !plans.is_empty() {
+        out.push_str("\n## Plans\n");
+        for pad in plans {
+            out.push_str(&format!("\n### {}\n\n{}\n", pad.name, pad.plan_markdown.as_deref().unwrap_or("").trim()));
+        }
+    }
+
+    out
+}
+
+/// A board's tickets, archived ones included, with the archived ones listed
+/// separately. Trashed tickets are left out.
+fn bundle_tickets(conn: &Connection, board_id: &str) -> Result<(Vec<Ticket>, Vec<BundleArchiveEntry>), DbError> {
+    let mut stmt = conn.prepare(
+        r#"SELECT t.id, t.board_id, t.column_id, t.title, t.description_md, t.priority,
+                  t.labels_json, t.created_at, t.updated_at, t.locked_by_run_id,
+                  t.lock_expires_at, t.project_id, t.agent_pref, t.workflow_type, t.model, t.branch_name,
+                  t.is_epic, t.epic_id, t.order_in_epic, t.depends_on_epic_id, t.depends_on_epic_ids_json, t.scratchpad_id, t.custom_fields_json,
+                  a.archived_at
+           FROM tickets t
+           LEFT JOIN ticket_archive a ON a.ticket_id = t.id
+           WHERE t.board_id = ? AND t.trash_id IS NULL
+           ORDER BY t.created_at"#,
+    )?;
+    let rows = stmt.query_map([board_id], |row| {
+        Ok((Database::map_ticket_row(row)?, row.get::<_, Option<String>>(23)?))
+    })?;
+
+    let mut tickets = Vec::new();
+    let mut archived = Vec::new();
+    for row in rows {
+        let (ticket, archived_at) = row?;
+        if let Some(archived_at) = archived_at {
+            archived.push(BundleArchiveEntry { ticket_id: ticket.id.clone(), archived_at: parse_datetime(archived_at) });
+        }
+        tickets.push(ticket);
+    }
+    Ok((tickets, archived))
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
+
+/// The local id of a bundled project: the same id if it exists here,
+/// otherwise a project registered at the same path.
+fn find_local_project(conn: &Connection, project: &Project) -> Result<Option<String>, DbError> {
+    let by_id = conn.query_row("SELECT id FROM projects WHERE id = ?", [&project.id], |row| row.get(0))
+        .optional()?;
+    if by_id.is_some() {
+        return Ok(by_id);
+    }
+    Ok(conn.query_row("SELECT id FROM projects WHERE path = ?", [&project.path], |row| row.get(0))
+        .optional()?)
+}
+
+fn target_board_exists(conn: &Connection, board_id: &str) -> Result<bool, DbError> {
+    Ok(conn.query_row("SELECT 1 FROM boards WHERE id = ?", [board_id], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{
+        AgentType, CreateComment, CreateProject, CreateRun, CreateScratchpad, CreateTask, CreateTicket,
+        AuthorType, Priority, TaskType, UpdateTask,
+    };
+    use crate::lifecycle::TicketState;
+
+    fn create_test_db() -> Database {
+        Database::open_in_memory().unwrap()
+    }
+
+    fn ticket(board: &Board, column_id: &str, title: &str) -> CreateTicket {
+        CreateTicket {
+            description_md: format!("About {}", title),
+            priority: Priority::High,
+            labels: vec!["backend".to_string()],
+            ..CreateTicket::for_test(&board.id, column_id, title)
+        }
+    }
+
+    /// A board with an epic, a dependent epic with a child, a comment, a run
+    /// and a scratchpad plan.
+    fn populated_board(db: &Database) -> Board {
+        let board = db.create_board("Source").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let project = db.create_project(&CreateProject {
+            name: "app".to_string(),
+            path: dir.path().to_string_lossy().to_string(),
+            preferred_agent: None,
+            requires_git: false,
+        }).unwrap();
+
+        let first = db.create_ticket(&CreateTicket { is_epic: true, ..ticket(&board, &columns[0].id, "First epic") }).unwrap();
+        let second = db.create_ticket(&CreateTicket {
+            is_epic: true,
+            depends_on_epic_id: Some(first.id.clone()),
+            ..ticket(&board, &columns[0].id, "Second epic")
+        }).unwrap();
+        let child = db.create_ticket(&CreateTicket {
+            epic_id: Some(second.id.clone()),
+            project_id: Some(project.id.clone()),
+            ..ticket(&board, &columns[1].id, "Child")
+        }).unwrap();
+        db.create_task(&CreateTask {
+            ticket_id: child.id.clone(),
+            task_type: TaskType::AddTests,
+            title: None,
+            content: None,
+        }).unwrap();
+        db.create_comment(&CreateComment {
+            ticket_id: child.id.clone(),
+            author_type: AuthorType::User,
+            body_md: "Looks good".to_string(),
+            metadata: None,
+        }).unwrap();
+        let run = db.create_run(&CreateRun {
+            ticket_id: child.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: project.path.clone(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        let task = db.get_tasks_for_ticket(&child.id).unwrap().remove(0);
+        db.update_task(&task.id, &UpdateTask {
+            title: None,
+            content: None,
+            status: Some(TaskStatus::Completed),
+            run_id: Some(run.id),
+        }).unwrap();
+
+        let pad = db.create_scratchpad(&CreateScratchpad {
+            board_id: board.id.clone(),
+            target_board_id: Some(board.id.clone()),
+            project_id: project.id,
+            name: "Roadmap".to_string(),
+            user_input: "Plan it".to_string(),
+            agent_pref: None,
+            model: None,
+            settings: serde_json::json!({}),
+        }).unwrap();
+        db.set_scratchpad_plan(&pad.id, "1. Build it", None).unwrap();
+        board
+    }
+
+    #[test]
+    fn import_twice_gives_independent_boards() {
+        let db = create_test_db();
+        let source = populated_board(&db);
+        let bundle = db.export_board(&source.id, true).unwrap();
+        assert_eq!(bundle.tickets.len(), 3);
+        assert_eq!(bundle.runs.len(), 1);
+        assert_eq!(bundle.projects.len(), 1);
+
+        // Through JSON, as a file would be
+        let bundle: BoardBundle = serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        let first = db.import_board(&bundle, None).unwrap();
+        let second = db.import_board(&bundle, Some("Copy")).unwrap();
+        assert!(first.warnings.is_empty(), "{:?}", first.warnings);
+        assert_eq!(first.board.name, "Source");
+        assert_eq!(second.board.name, "Copy");
+
+        for imported in [&first.board, &second.board] {
+            let copy = db.export_board(&imported.id, true).unwrap();
+            assert_eq!(copy.columns.len(), bundle.columns.len());
+            assert!(copy.columns.iter().all(|c| c.board_id == imported.id));
+            assert!(copy.tickets.iter().all(|t| !titles_with_ids(&bundle.tickets).contains(&(t.title.clone(), t.id.clone()))));
+
+            let by_title = |title: &str| copy.tickets.iter().find(|t| t.title == title).unwrap().clone();
+            let (epic_one, epic_two, child) = (by_title("First epic"), by_title("Second epic"), by_title("Child"));
+            assert_eq!(epic_two.depends_on_epic_id.as_deref(), Some(epic_one.id.as_str()));
+            assert_eq!(child.epic_id.as_deref(), Some(epic_two.id.as_str()));
+            assert_eq!(child.order_in_epic, Some(0));
+            assert_eq!(child.project_id, bundle.tickets.iter().find(|t| t.title == "Child").unwrap().project_id);
+
+            let tasks: Vec<_> = copy.tasks.iter().filter(|t| t.ticket_id == child.id).collect();
+            assert_eq!(tasks.len(), 2);
+            assert_eq!(tasks[0].status, TaskStatus::Completed);
+            assert_eq!(tasks[0].run_id.as_deref(), Some(copy.runs[0].id.as_str()));
+            assert_eq!(copy.runs[0].status, RunStatus::Aborted);
+            assert_eq!(copy.comments.len(), 1);
+            assert_eq!(copy.scratchpads[0].target_board_id.as_deref(), Some(imported.id.as_str()));
+        }
+
+        // The source board is untouched
+        assert_eq!(db.get_tickets(&source.id, None).unwrap().len(), 3);
+    }
+
+    fn titles_with_ids(tickets: &[Ticket]) -> Vec<(String, String)> {
+        tickets.iter().map(|t| (t.title.clone(), t.id.clone())).collect()
+    }
+
+    #[test]
+    fn import_without_project_skips_scratchpads() {
+        let source_db = create_test_db();
+        let source = populated_board(&source_db);
+        let bundle = source_db.export_board(&source.id, false).unwrap();
+        assert!(bundle.runs.is_empty());
+
+        let db = create_test_db();
+        let imported = db.import_board(&bundle, None).unwrap();
+        assert_eq!(imported.warnings.len(), 2, "{:?}", imported.warnings);
+
+        let copy = db.export_board(&imported.board.id, false).unwrap();
+        assert_eq!(copy.tickets.len(), 3);
+        assert!(copy.scratchpads.is_empty());
+        assert!(copy.tickets.iter().all(|t| t.project_id.is_none()));
+        // The task's run was not exported
+        assert!(copy.tasks.iter().all(|t| t.run_id.is_none()));
+    }
+
+    #[test]
+    fn archived_tickets_round_trip_as_archived() {
+        let db = create_test_db();
+        let source = populated_board(&db);
+        let done = db.require_column_by_role(&source.id, TicketState::Done).unwrap();
+        let shipped = db.create_ticket(&ticket(&source, &done.id, "Shipped")).unwrap();
+        db.create_comment(&CreateComment {
+            ticket_id: shipped.id.clone(),
+            author_type: AuthorType::User,
+            body_md: "Released".to_string(),
+            metadata: None,
+        }).unwrap();
+        db.create_run(&CreateRun {
+            ticket_id: shipped.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp".to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        assert_eq!(db.archive_done_tickets(chrono::Utc::now() + chrono::Duration::minutes(1)).unwrap(), 1);
+
+        let bundle = db.export_board(&source.id, true).unwrap();
+        assert_eq!(bundle.tickets.len(), 4);
+        assert_eq!(bundle.archived.len(), 1);
+        assert_eq!(bundle.archived[0].ticket_id, shipped.id);
+        assert!(bundle.comments.iter().any(|c| c.ticket_id == shipped.id));
+        assert!(bundle.runs.iter().any(|r| r.ticket_id == shipped.id));
+        assert!(bundle.tasks.iter().any(|t| t.ticket_id == shipped.id));
+        assert!(!render_markdown(&bundle).contains("Shipped"));
+
+        let bundle: BoardBundle = serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        let imported = db.import_board(&bundle, Some("Copy")).unwrap();
+
+        // Off the board, but in the archive with its comments and runs
+        assert!(db.get_tickets(&imported.board.id, None).unwrap().iter().all(|t| t.title != "Shipped"));
+        let archived = db.search_archived_tickets(Some(&imported.board.id), None).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].ticket.title, "Shipped");
+        assert_eq!(archived[0].archived_at.timestamp(), bundle.archived[0].archived_at.timestamp());
+        let copy_id = archived[0].ticket.id.clone();
+        assert_eq!(db.get_comments(&copy_id).unwrap().len(), 1);
+        assert_eq!(db.get_runs(&copy_id).unwrap().len(), 1);
+
+        let restored = db.restore_archived_ticket(&copy_id, &crate::db::Actor::system()).unwrap();
+        assert_eq!(restored.board_id, imported.board.id);
+    }
+
+    #[test]
+    fn rejects_newer_bundle_format() {
+        let db = create_test_db();
+        let board = db.create_board("Board").unwrap();
+        let mut bundle = db.export_board(&board.id, false).unwrap();
+        bundle.format_version = BOARD_BUNDLE_VERSION + 1;
+        assert!(matches!(db.import_board(&bundle, None), Err(DbError::Validation(_))));
+        assert_eq!(db.get_boards().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn markdown_lists_tickets_by_column_and_plans() {
+        let db = create_test_db();
+        let board = populated_board(&db);
+        let markdown = db.export_board_markdown(&board.id).unwrap();
+
+        assert!(markdown.starts_with("# Source\n"));
+        let backlog = markdown.find("## Backlog (2)").unwrap();
+        let ready = markdown.find("## Ready (1)").unwrap();
+        let child = markdown.find("### Child").unwrap();
+        assert!(backlog < ready && ready < child);
+        assert!(markdown.contains("### Epic: Second epic\n\nPriority: high · Labels: backend · Depends on: First epic"));
+        assert!(markdown.contains("Epic: Second epic"));
+        assert!(markdown.contains("- [x] Child\n- [ ] Add Tests"));
+        assert!(markdown.contains("## Plans\n\n### Roadmap\n\n1. Build it\n"));
+    }
+}
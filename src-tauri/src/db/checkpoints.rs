@@ -0,0 +1,380 @@
+//! Stage checkpoints of multi-stage workflows.
+//!
+//! The orchestrator saves the index of the first stage not yet completed
+//! (plus the plan and branch) after every stage. When the app stops
+//! mid-workflow the checkpoint is marked interrupted at the next launch and
+//! the workflow can resume from that stage in the same worktree.
+//!
+//! A workflow can also be asked to pause: it finishes its current stage and
+//! then waits as `paused`, holding its ticket, until it is resumed.
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::agents::AgentKind;
+use crate::db::models::{CheckpointState, CreateCheckpoint, RunStatus, WorkflowCheckpoint, MULTI_STAGE_WORKFLOW};
+use crate::db::{parse_datetime, Database, DbError};
+
+const CHECKPOINT_COLUMNS: &str = r#"parent_run_id, ticket_id, task_id, agent_type, worktree_path, branch_name,
+    next_stage, plan_md, state, pause_requested, created_at, updated_at"#;
+
+impl Database {
+    /// Record that a workflow starts (or resumes) at `next_stage`. A resumed
+    /// workflow keeps the plan saved by its earlier attempt.
+    pub fn start_checkpoint(&self, checkpoint: &CreateCheckpoint) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn(|conn| {
+            let now = chrono::Utc::now().to_rfc3339();
+            conn.execute(
+                &format!(
+                    r#"INSERT INTO workflow_checkpoints ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL, 'active', 0, ?8, ?8)
+                       ON CONFLICT(parent_run_id) DO UPDATE SET
+                           task_id = excluded.task_id, worktree_path = excluded.worktree_path,
+                           branch_name = COALESCE(excluded.branch_name, branch_name),
+                           next_stage = excluded.next_stage, state = 'active', pause_requested = 0,
+                           updated_at = excluded.updated_at"#,
+                    CHECKPOINT_COLUMNS
+                ),
+                rusqlite::params![
+                    checkpoint.parent_run_id,
+                    checkpoint.ticket_id,
+                    checkpoint.task_id,
+                    checkpoint.agent_type.as_str(),
+                    checkpoint.worktree_path,
+                    checkpoint.branch_name,
+                    checkpoint.next_stage as i64,
+                    now,
+                ],
+            )?;
+            require_checkpoint(conn, &checkpoint.parent_run_id)
+        })
+    }
+
+    /// Save progress after a completed stage; `None` keeps the stored branch or plan
+    pub fn advance_checkpoint(
+        &self,
+        parent_run_id: &str,
+        next_stage: usize,
+        branch_name: Option<&str>,
+        plan_md: Option<&str>,
+    ) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                r#"UPDATE workflow_checkpoints
+                   SET next_stage = ?, branch_name = COALESCE(?, branch_name), plan_md = COALESCE(?, plan_md),
+                       updated_at = ?
+                   WHERE parent_run_id = ?"#,
+                rusqlite::params![
+                    next_stage as i64,
+                    branch_name,
+                    plan_md,
+                    chrono::Utc::now().to_rfc3339(),
+                    parent_run_id,
+                ],
+            )?;
+            require_checkpoint(conn, parent_run_id)
+        })
+    }
+
+    pub fn set_checkpoint_state(&self, parent_run_id: &str, state: CheckpointState) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE workflow_checkpoints SET state = ?, updated_at = ? WHERE parent_run_id = ?",
+                rusqlite::params![state.as_str(), chrono::Utc::now().to_rfc3339(), parent_run_id],
+            )?;
+            require_checkpoint(conn, parent_run_id)
+        })
+    }
+
+    /// Ask an active workflow to pause once its current stage completes
+    pub fn request_pause(&self, parent_run_id: &str) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE workflow_checkpoints SET pause_requested = 1, updated_at = ? WHERE parent_run_id = ? AND state = 'active'",
+                rusqlite::params![chrono::Utc::now().to_rfc3339(), parent_run_id],
+            )?;
+            let checkpoint = require_checkpoint(conn, parent_run_id)?;
+            if updated == 0 {
+                return Err(DbError::Conflict(format!(
+                    "Run {} is {} and cannot be paused",
+                    parent_run_id,
+                    checkpoint.state.as_str()
+                )));
+            }
+            Ok(checkpoint)
+        })
+    }
+
+    /// Pause the workflow if a pause was requested; called between stages.
+    /// Returns whether it is now paused.
+    pub fn take_pause(&self, parent_run_id: &str) -> Result<bool, DbError> {
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                r#"UPDATE workflow_checkpoints SET state = 'paused', pause_requested = 0, updated_at = ?
+                   WHERE parent_run_id = ? AND state = 'active' AND pause_requested = 1"#,
+                rusqlite::params![chrono::Utc::now().to_rfc3339(), parent_run_id],
+            )?;
+            Ok(updated > 0)
+        })
+    }
+
+    /// Let a paused workflow continue, or withdraw a pause not yet taken
+    pub fn unpause_checkpoint(&self, parent_run_id: &str) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                r#"UPDATE workflow_checkpoints SET state = 'active', pause_requested = 0, updated_at = ?
+                   WHERE parent_run_id = ? AND (state = 'paused' OR (state = 'active' AND pause_requested = 1))"#,
+                rusqlite::params![chrono::Utc::now().to_rfc3339(), parent_run_id],
+            )?;
+            let checkpoint = require_checkpoint(conn, parent_run_id)?;
+            if updated == 0 {
+                return Err(DbError::Conflict(format!("Run {} is not paused", parent_run_id)));
+            }
+            Ok(checkpoint)
+        })
+    }
+
+    /// Replace the saved plan of a workflow that is not running a stage, so
+    /// the stages after a resume follow the edited plan
+    pub fn set_checkpoint_plan(&self, parent_run_id: &str, plan_md: &str) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn(|conn| {
+            let updated = conn.execute(
+                r#"UPDATE workflow_checkpoints SET plan_md = ?, updated_at = ?
+                   WHERE parent_run_id = ? AND state IN ('paused', 'interrupted', 'failed')"#,
+                rusqlite::params![plan_md, chrono::Utc::now().to_rfc3339(), parent_run_id],
+            )?;
+            let checkpoint = require_checkpoint(conn, parent_run_id)?;
+            if updated == 0 {
+                return Err(DbError::Conflict(format!(
+                    "Run {} is {}; its plan can only be edited while it is stopped",
+                    parent_run_id,
+                    checkpoint.state.as_str()
+                )));
+            }
+            Ok(checkpoint)
+        })
+    }
+
+    pub fn get_checkpoint(&self, parent_run_id: &str) -> Result<Option<WorkflowCheckpoint>, DbError> {
+        self.with_reader(|conn| get_checkpoint(conn, parent_run_id))
+    }
+
+    /// Checkpoints in `state`, oldest first
+    pub fn get_checkpoints(&self, state: CheckpointState) -> Result<Vec<WorkflowCheckpoint>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM workflow_checkpoints WHERE state = ? ORDER BY created_at, rowid",
+                CHECKPOINT_COLUMNS
+            ))?;
+            let rows = stmt.query_map([state.as_str()], map_checkpoint_row)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    /// Checkpoints of a ticket's workflows, oldest first
+    pub fn get_ticket_checkpoints(&self, ticket_id: &str) -> Result<Vec<WorkflowCheckpoint>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM workflow_checkpoints WHERE ticket_id = ? ORDER BY created_at, rowid",
+                CHECKPOINT_COLUMNS
+            ))?;
+            let rows = stmt.query_map([ticket_id], map_checkpoint_row)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    /// Move a workflow left active or paused by a previous process to
+    /// `state` and abort its run and unfinished stage runs with `summary`
+    pub fn interrupt_checkpoint(&self, parent_run_id: &str, state: CheckpointState, summary: &str) -> Result<WorkflowCheckpoint, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let now = chrono::Utc::now().to_rfc3339();
+            tx.execute(
+                "UPDATE workflow_checkpoints SET state = ?, pause_requested = 0, updated_at = ? WHERE parent_run_id = ?",
+                rusqlite::params![state.as_str(), now, parent_run_id],
+            )?;
+            tx.execute(
+                r#"UPDATE agent_runs SET status = ?1, ended_at = ?2, summary_md = ?3
+                   WHERE (id = ?4 OR parent_run_id = ?4) AND status IN (?5, ?6)"#,
+                rusqlite::params![
+                    RunStatus::Aborted.as_str(),
+                    now,
+                    summary,
+                    parent_run_id,
+                    RunStatus::Running.as_str(),
+                    RunStatus::Queued.as_str(),
+                ],
+            )?;
+            let checkpoint = require_checkpoint(&tx, parent_run_id)?;
+            tx.commit()?;
+            Ok(checkpoint)
+        })
+    }
+}
+
+fn get_checkpoint(conn: &Connection, parent_run_id: &str) -> Result<Option<WorkflowCheckpoint>, DbError> {
+    conn.query_row(
+        &format!("SELECT {} FROM workflow_checkpoints WHERE parent_run_id = ?", CHECKPOINT_COLUMNS),
+        [parent_run_id],
+        map_checkpoint_row,
+    )
+    .optional()
+    .map_err(DbError::from)
+}
+
+fn require_checkpoint(conn: &Connection, parent_run_id: &str) -> Result<WorkflowCheckpoint, DbError> {
+    get_checkpoint(conn, parent_run_id)?
+        .ok_or_else(|| DbError::NotFound(format!("Checkpoint of run {}", parent_run_id)))
+}
+
+fn map_checkpoint_row(row: &rusqlite::Row) -> rusqlite::Result<WorkflowCheckpoint> {
+    let agent_type: String = row.get(3)?;
+    let next_stage = row.get::<_, i64>(6)?.max(0) as usize;
+    let state: String = row.get(8)?;
+    let state = CheckpointState::parse(&state).unwrap_or(CheckpointState::Failed);
+    Ok(WorkflowCheckpoint {
+        parent_run_id: row.get(0)?,
+        ticket_id: row.get(1)?,
+        task_id: row.get(2)?,
+        agent_type: AgentKind::parse(&agent_type).unwrap_or(AgentKind::Claude),
+        worktree_path: row.get(4)?,
+        branch_name: row.get(5)?,
+        next_stage,
+        next_stage_name: MULTI_STAGE_WORKFLOW.get(next_stage).map(|stage| stage.to_string()),
+        stages: MULTI_STAGE_WORKFLOW.iter().map(|stage| stage.to_string()).collect(),
+        plan_md: row.get(7)?,
+        state,
+        pause_requested: row.get(9)?,
+        paused_after: match state {
+            CheckpointState::Paused => next_stage
+                .checked_sub(1)
+                .and_then(|stage| MULTI_STAGE_WORKFLOW.get(stage))
+                .map(|stage| stage.to_string()),
+            _ => None,
+        },
+        created_at: parse_datetime(row.get(10)?),
+        updated_at: parse_datetime(row.get(11)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{AgentType, CreateRun, CreateTicket};
+
+    fn setup(db: &Database) -> (String, String) {
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket = db.create_ticket(&CreateTicket::for_test(&board.id, &columns[0].id, "Ticket")).unwrap();
+        let run = db.create_run(&CreateRun {
+            ticket_id: ticket.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp/worktree".to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        (ticket.id, run.id)
+    }
+
+    fn create(ticket_id: &str, run_id: &str, next_stage: usize) -> CreateCheckpoint {
+        CreateCheckpoint {
+            parent_run_id: run_id.to_string(),
+            ticket_id: ticket_id.to_string(),
+            task_id: None,
+            agent_type: AgentKind::Claude,
+            worktree_path: "/tmp/worktree".to_string(),
+            branch_name: Some("agent-work/temp".to_string()),
+            next_stage,
+        }
+    }
+
+    #[test]
+    fn checkpoint_advances_and_keeps_plan_on_resume() {
+        let db = Database::open_in_memory().unwrap();
+        let (ticket_id, run_id) = setup(&db);
+
+        let checkpoint = db.start_checkpoint(&create(&ticket_id, &run_id, 0)).unwrap();
+        assert_eq!(checkpoint.state, CheckpointState::Active);
+        assert_eq!(checkpoint.next_stage_name.as_deref(), Some("branch"));
+
+        db.advance_checkpoint(&run_id, 1, Some("feature/login"), None).unwrap();
+        let checkpoint = db.advance_checkpoint(&run_id, 2, None, Some("1. Do it")).unwrap();
+        assert_eq!(checkpoint.next_stage_name.as_deref(), Some("implement"));
+        assert_eq!(checkpoint.branch_name.as_deref(), Some("feature/login"));
+
+        db.set_checkpoint_state(&run_id, CheckpointState::Interrupted).unwrap();
+        let resumed = db.start_checkpoint(&CreateCheckpoint { branch_name: None, ..create(&ticket_id, &run_id, 2) }).unwrap();
+        assert_eq!(resumed.state, CheckpointState::Active);
+        assert_eq!(resumed.plan_md.as_deref(), Some("1. Do it"));
+        assert_eq!(resumed.branch_name.as_deref(), Some("feature/login"));
+
+        let done = db.advance_checkpoint(&run_id, MULTI_STAGE_WORKFLOW.len(), None, None).unwrap();
+        assert_eq!(done.next_stage_name, None);
+    }
+
+    #[test]
+    fn interrupting_aborts_the_run_and_its_running_stages() {
+        let db = Database::open_in_memory().unwrap();
+        let (ticket_id, run_id) = setup(&db);
+        db.update_run_status(&run_id, RunStatus::Running, None, None).unwrap();
+        let finished = db.create_run(&CreateRun {
+            ticket_id: ticket_id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp/worktree".to_string(),
+            parent_run_id: Some(run_id.clone()),
+            stage: Some("branch".to_string()),
+        }).unwrap();
+        db.update_run_status(&finished.id, RunStatus::Finished, Some(0), None).unwrap();
+        let running = db.create_run(&CreateRun {
+            ticket_id: ticket_id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp/worktree".to_string(),
+            parent_run_id: Some(run_id.clone()),
+            stage: Some("plan".to_string()),
+        }).unwrap();
+        db.update_run_status(&running.id, RunStatus::Running, None, None).unwrap();
+        db.start_checkpoint(&create(&ticket_id, &run_id, 1)).unwrap();
+
+        let checkpoint = db.interrupt_checkpoint(&run_id, CheckpointState::Interrupted, "Interrupted").unwrap();
+        assert_eq!(checkpoint.state, CheckpointState::Interrupted);
+        assert_eq!(db.get_checkpoints(CheckpointState::Interrupted).unwrap().len(), 1);
+        assert!(db.get_checkpoints(CheckpointState::Active).unwrap().is_empty());
+
+        assert_eq!(db.get_run(&run_id).unwrap().status, RunStatus::Aborted);
+        assert_eq!(db.get_run(&running.id).unwrap().status, RunStatus::Aborted);
+        let finished = db.get_run(&finished.id).unwrap();
+        assert_eq!(finished.status, RunStatus::Finished);
+        assert_eq!(finished.summary_md, None);
+    }
+
+    #[test]
+    fn pause_is_taken_between_stages_and_lifted_on_resume() {
+        let db = Database::open_in_memory().unwrap();
+        let (ticket_id, run_id) = setup(&db);
+        db.start_checkpoint(&create(&ticket_id, &run_id, 2)).unwrap();
+        assert!(!db.take_pause(&run_id).unwrap());
+        assert!(matches!(db.set_checkpoint_plan(&run_id, "2. Edited"), Err(DbError::Conflict(_))));
+
+        let requested = db.request_pause(&run_id).unwrap();
+        assert!(requested.pause_requested);
+        assert_eq!(requested.state, CheckpointState::Active);
+
+        db.advance_checkpoint(&run_id, 3, None, None).unwrap();
+        assert!(db.take_pause(&run_id).unwrap());
+        let paused = db.get_checkpoint(&run_id).unwrap().unwrap();
+        assert_eq!(paused.state, CheckpointState::Paused);
+        assert!(!paused.pause_requested);
+        assert_eq!(paused.paused_after.as_deref(), Some("implement"));
+        assert!(matches!(db.request_pause(&run_id), Err(DbError::Conflict(_))));
+
+        db.set_checkpoint_plan(&run_id, "2. Edited").unwrap();
+        let resumed = db.unpause_checkpoint(&run_id).unwrap();
+        assert_eq!(resumed.state, CheckpointState::Active);
+        assert_eq!(resumed.paused_after, None);
+        assert_eq!(resumed.plan_md.as_deref(), Some("2. Edited"));
+        assert!(matches!(db.unpause_checkpoint(&run_id), Err(DbError::Conflict(_))));
+
+        // A request not yet taken can be withdrawn
+        db.request_pause(&run_id).unwrap();
+        db.unpause_checkpoint(&run_id).unwrap();
+        assert!(!db.take_pause(&run_id).unwrap());
+    }
+}
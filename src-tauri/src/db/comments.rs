@@ -36,7 +36,7 @@ impl Database {
     }
 
     pub fn get_comments(&self, ticket_id: &str) -> Result<Vec<Comment>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, author_type, body_md, created_at, metadata_json
                    FROM comments WHERE ticket_id = ? ORDER BY created_at"#
@@ -137,6 +137,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let comment = db.create_comment(&CreateComment {
@@ -177,6 +178,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let comment = db.create_comment(&CreateComment {
@@ -224,6 +226,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let metadata = serde_json::json!({"stage": "plan", "key": "value"});
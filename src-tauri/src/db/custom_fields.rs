@@ -0,0 +1,386 @@
+//! Custom ticket fields.
+//!
+//! A board defines typed fields (text, number, date, enum, user); each ticket
+//! keeps its values in `custom_fields_json`, keyed by field key. Values are
+//! checked against the board's definitions whenever a ticket is created or
+//! updated, so tickets only ever hold keys their board knows about.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::Value;
+
+use crate::db::models::{CreateCustomField, CustomField, CustomFieldType, CustomFieldValues, Ticket, UpdateCustomField};
+use crate::db::{parse_datetime, Database, DbError};
+
+const MAX_KEY_LEN: usize = 64;
+
+impl Database {
+    /// Fields of a board in display order
+    pub fn get_custom_fields(&self, board_id: &str) -> Result<Vec<CustomField>, DbError> {
+        self.with_reader(|conn| board_fields(conn, board_id))
+    }
+
+    pub fn create_custom_field(&self, board_id: &str, field: &CreateCustomField) -> Result<CustomField, DbError> {
+        validate_key(&field.key)?;
+        let name = field.name.trim();
+        if name.is_empty() {
+            return Err(DbError::Validation("Field name cannot be empty".to_string()));
+        }
+        validate_options(field.field_type, &field.options)?;
+
+        self.with_conn(|conn| {
+            let board_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM boards WHERE id = ? AND trash_id IS NULL)",
+                [board_id],
+                |row| row.get(0),
+            )?;
+            if !board_exists {
+                return Err(DbError::NotFound(format!("Board {}", board_id)));
+            }
+            let taken: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM custom_fields WHERE board_id = ? AND key = ?)",
+                rusqlite::params![board_id, field.key],
+                |row| row.get(0),
+            )?;
+            if taken {
+                return Err(DbError::Validation(format!("Field '{}' already exists on this board", field.key)));
+            }
+
+            let position: i32 = conn.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM custom_fields WHERE board_id = ?",
+                [board_id],
+                |row| row.get(0),
+            )?;
+            let created = CustomField {
+                id: uuid::Uuid::new_v4().to_string(),
+                board_id: board_id.to_string(),
+                key: field.key.clone(),
+                name: name.to_string(),
+                field_type: field.field_type,
+                options: field.options.clone(),
+                required: field.required,
+                position,
+                created_at: chrono::Utc::now(),
+            };
+            conn.execute(
+                r#"INSERT INTO custom_fields
+                   (id, board_id, key, name, field_type, options_json, required, position, created_at)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                rusqlite::params![
+                    created.id,
+                    created.board_id,
+                    created.key,
+                    created.name,
+                    created.field_type.as_str(),
+                    serde_json::to_string(&created.options).unwrap_or_else(|_| "[]".to_string()),
+                    created.required,
+                    created.position,
+                    created.created_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(created)
+        })
+    }
+
+    /// Rename a field, change whether it is required or replace the options of
+    /// an enum field. Options still used by a ticket cannot be removed.
+    pub fn update_custom_field(&self, field_id: &str, updates: &UpdateCustomField) -> Result<CustomField, DbError> {
+        self.with_conn(|conn| {
+            let mut field = get_field(conn, field_id)?;
+            if let Some(name) = &updates.name {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(DbError::Validation("Field name cannot be empty".to_string()));
+                }
+                field.name = name.to_string();
+            }
+            if let Some(options) = &updates.options {
+                validate_options(field.field_type, options)?;
+                for removed in field.options.iter().filter(|o| !options.contains(o)) {
+                    let in_use: i64 = conn.query_row(
+                        "SELECT COUNT(*) FROM tickets WHERE board_id = ? AND json_extract(custom_fields_json, ?) = ?",
+                        rusqlite::params![field.board_id, json_path(&field.key), removed],
+                        |row| row.get(0),
+                    )?;
+                    if in_use > 0 {
+                        return Err(DbError::Validation(format!(
+                            "Option '{}' is still used by {} ticket(s)", removed, in_use
+                        )));
+                    }
+                }
+                field.options = options.clone();
+            }
+            if let Some(required) = updates.required {
+                field.required = required;
+            }
+
+            conn.execute(
+                "UPDATE custom_fields SET name = ?, options_json = ?, required = ? WHERE id = ?",
+                rusqlite::params![
+                    field.name,
+                    serde_json::to_string(&field.options).unwrap_or_else(|_| "[]".to_string()),
+                    field.required,
+                    field_id,
+                ],
+            )?;
+            Ok(field)
+        })
+    }
+
+    /// Delete a field together with its values on every ticket of the board
+    pub fn delete_custom_field(&self, field_id: &str) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let field = get_field(&tx, field_id)?;
+            tx.execute(
+                "UPDATE tickets SET custom_fields_json = json_remove(custom_fields_json, ?) WHERE board_id = ?",
+                rusqlite::params![json_path(&field.key), field.board_id],
+            )?;
+            tx.execute("DELETE FROM custom_fields WHERE id = ?", [field_id])?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Fail when a required field of the board is missing from `values`.
+    /// Checked when users create tickets; tickets generated by plans and
+    /// imports may leave required fields empty.
+    pub fn check_required_fields(&self, board_id: &str, values: &CustomFieldValues) -> Result<(), DbError> {
+        let fields = self.get_custom_fields(board_id)?;
+        match fields.iter().find(|f| f.required && values.get(&f.key).filter(|v| !v.is_null()).is_none()) {
+            Some(missing) => Err(DbError::Validation(format!("Field '{}' is required", missing.name))),
+            None => Ok(()),
+        }
+    }
+}
+
+pub(crate) fn board_fields(conn: &Connection, board_id: &str) -> Result<Vec<CustomField>, DbError> {
+    let mut stmt = conn.prepare(
+        r#"SELECT id, board_id, key, name, field_type, options_json, required, position, created_at
+           FROM custom_fields WHERE board_id = ? ORDER BY position"#,
+    )?;
+    let rows = stmt.query_map([board_id], map_field_row)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+}
+
+fn get_field(conn: &Connection, field_id: &str) -> Result<CustomField, DbError> {
+    conn.query_row(
+        r#"SELECT id, board_id, key, name, field_type, options_json, required, position, created_at
+           FROM custom_fields WHERE id = ?"#,
+        [field_id],
+        map_field_row,
+    ).optional()?
+    .ok_or_else(|| DbError::NotFound(format!("Custom field {}", field_id)))
+}
+
+fn map_field_row(row: &rusqlite::Row) -> rusqlite::Result<CustomField> {
+    let field_type: String = row.get(4)?;
+    let options_json: String = row.get(5)?;
+    Ok(CustomField {
+        id: row.get(0)?,
+        board_id: row.get(1)?,
+        key: row.get(2)?,
+        name: row.get(3)?,
+        field_type: CustomFieldType::parse(&field_type).unwrap_or(CustomFieldType::Text),
+        options: serde_json::from_str(&options_json).unwrap_or_default(),
+        required: row.get(6)?,
+        position: row.get(7)?,
+        created_at: parse_datetime(row.get(8)?),
+    })
+}
+
+/// Check each value against its field's type. `null` is accepted for every
+/// field; on update it clears the value.
+pub(crate) fn validate_values(fields: &[CustomField], values: &CustomFieldValues) -> Result<(), DbError> {
+    for (key, value) in values {
+        let field = fields.iter().find(|f| &f.key == key)
+            .ok_or_else(|| DbError::Validation(format!("Unknown custom field '{}'", key)))?;
+        if value.is_null() {
+            continue;
+        }
+        let valid = match field.field_type {
+            CustomFieldType::Text => value.is_string(),
+            CustomFieldType::Number => value.is_number(),
+            CustomFieldType::Date => value.as_str()
+                .is_some_and(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()),
+            CustomFieldType::Enum => value.as_str().is_some_and(|s| field.options.iter().any(|o| o == s)),
+            CustomFieldType::User => value.as_str().is_some_and(|s| !s.trim().is_empty()),
+        };
+        if !valid {
+            let expected = match field.field_type {
+                CustomFieldType::Text => "a string".to_string(),
+                CustomFieldType::Number => "a number".to_string(),
+                CustomFieldType::Date => "a date (YYYY-MM-DD)".to_string(),
+                CustomFieldType::Enum => format!("one of {}", field.options.join(", ")),
+                CustomFieldType::User => "a user name".to_string(),
+            };
+            return Err(DbError::Validation(format!("Field '{}' must be {}", field.name, expected)));
+        }
+    }
+    Ok(())
+}
+
+/// Apply `updates` to `current`; `null` removes a value. Required fields
+/// cannot be cleared.
+pub(crate) fn merge_values(
+    fields: &[CustomField],
+    current: &CustomFieldValues,
+    updates: &CustomFieldValues,
+) -> Result<CustomFieldValues, DbError> {
+    validate_values(fields, updates)?;
+    let mut merged = current.clone();
+    for (key, value) in updates {
+        if value.is_null() {
+            if let Some(field) = fields.iter().find(|f| &f.key == key && f.required) {
+                return Err(DbError::Validation(format!("Field '{}' is required", field.name)));
+            }
+            merged.remove(key);
+        } else {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(merged)
+}
+
+/// Plain-text form of a value, as used by filters and prompt variables
+pub fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether the ticket's value of field `key` reads as `expected`
+pub fn field_matches(ticket: &Ticket, key: &str, expected: &str) -> bool {
+    ticket.custom_fields.get(key).is_some_and(|value| value_text(value) == expected)
+}
+
+fn validate_key(key: &str) -> Result<(), DbError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(DbError::Validation(format!(
+            "Field key '{}' must start with a letter and use only a-z, 0-9 and _ (at most {} characters)",
+            key, MAX_KEY_LEN
+        )))
+    }
+}
+
+fn validate_options(field_type: CustomFieldType, options: &[String]) -> Result<(), DbError> {
+    match field_type {
+        CustomFieldType::Enum => {
+            if options.is_empty() || options.iter().any(|o| o.trim().is_empty()) {
+                return Err(DbError::Validation("Enum fields need at least one non-empty option".to_string()));
+            }
+            if options.iter().enumerate().any(|(i, o)| options[..i].contains(o)) {
+                return Err(DbError::Validation("Enum options must be unique".to_string()));
+            }
+            Ok(())
+        }
+        _ if !options.is_empty() => Err(DbError::Validation("Only enum fields have options".to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// JSON path of a field in `custom_fields_json`; keys are validated, so
+/// quoting them is enough
+fn json_path(key: &str) -> String {
+    format!("$.\"{}\"", key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{CreateTicket, UpdateTicket, Actor};
+    use serde_json::json;
+
+    fn field(db: &Database, board_id: &str, key: &str, field_type: CustomFieldType, options: &[&str]) -> CustomField {
+        db.create_custom_field(board_id, &CreateCustomField {
+            key: key.to_string(),
+            name: key.to_string(),
+            field_type,
+            options: options.iter().map(|o| o.to_string()).collect(),
+            required: false,
+        }).unwrap()
+    }
+
+    fn create_ticket(db: &Database, board_id: &str, column_id: &str, values: CustomFieldValues) -> Result<Ticket, DbError> {
+        db.create_ticket(&CreateTicket {
+            custom_fields: values,
+            ..CreateTicket::for_test(board_id, column_id, "Ticket")
+        })
+    }
+
+    #[test]
+    fn values_are_validated_against_field_types() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let column = db.get_columns(&board.id).unwrap().remove(0);
+        field(&db, &board.id, "points", CustomFieldType::Number, &[]);
+        field(&db, &board.id, "due", CustomFieldType::Date, &[]);
+        field(&db, &board.id, "component", CustomFieldType::Enum, &["api", "ui"]);
+
+        let values = CustomFieldValues::from([
+            ("points".to_string(), json!(3)),
+            ("due".to_string(), json!("2026-11-01")),
+            ("component".to_string(), json!("api")),
+        ]);
+        let ticket = create_ticket(&db, &board.id, &column.id, values.clone()).unwrap();
+        assert_eq!(db.get_ticket(&ticket.id).unwrap().custom_fields, values);
+
+        for (key, value) in [("points", json!("3")), ("due", json!("next week")), ("component", json!("db")), ("customer", json!("x"))] {
+            let values = CustomFieldValues::from([(key.to_string(), value)]);
+            assert!(matches!(create_ticket(&db, &board.id, &column.id, values), Err(DbError::Validation(_))), "{}", key);
+        }
+    }
+
+    #[test]
+    fn updates_merge_and_clear_values() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let column = db.get_columns(&board.id).unwrap().remove(0);
+        field(&db, &board.id, "points", CustomFieldType::Number, &[]);
+        let customer = field(&db, &board.id, "customer", CustomFieldType::Text, &[]);
+
+        let ticket = create_ticket(&db, &board.id, &column.id, CustomFieldValues::from([
+            ("points".to_string(), json!(5)),
+            ("customer".to_string(), json!("acme")),
+        ])).unwrap();
+
+        let updated = db.update_ticket(&ticket.id, &UpdateTicket {
+            custom_fields: Some(CustomFieldValues::from([("points".to_string(), Value::Null)])),
+            ..Default::default()
+        }, &Actor::user()).unwrap();
+        assert_eq!(updated.custom_fields, CustomFieldValues::from([("customer".to_string(), json!("acme"))]));
+        assert!(field_matches(&updated, "customer", "acme"));
+
+        db.delete_custom_field(&customer.id).unwrap();
+        assert!(db.get_ticket(&ticket.id).unwrap().custom_fields.is_empty());
+    }
+
+    #[test]
+    fn enum_options_in_use_cannot_be_removed() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let column = db.get_columns(&board.id).unwrap().remove(0);
+        let component = field(&db, &board.id, "component", CustomFieldType::Enum, &["api", "ui"]);
+        create_ticket(&db, &board.id, &column.id, CustomFieldValues::from([("component".to_string(), json!("api"))])).unwrap();
+
+        let remove_api = UpdateCustomField { options: Some(vec!["ui".to_string()]), ..Default::default() };
+        assert!(matches!(db.update_custom_field(&component.id, &remove_api), Err(DbError::Validation(_))));
+        let remove_ui = UpdateCustomField { options: Some(vec!["api".to_string()]), ..Default::default() };
+        assert_eq!(db.update_custom_field(&component.id, &remove_ui).unwrap().options, vec!["api"]);
+
+        assert!(db.create_custom_field(&board.id, &CreateCustomField {
+            key: "Bad Key".to_string(),
+            name: "Bad".to_string(),
+            field_type: CustomFieldType::Text,
+            options: vec![],
+            required: false,
+        }).is_err());
+    }
+}
@@ -34,7 +34,7 @@ impl Database {
     }
 
     pub fn get_events(&self, run_id: &str) -> Result<Vec<AgentEvent>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, run_id, ticket_id, event_type, payload_json, created_at
                    FROM agent_events WHERE run_id = ? ORDER BY created_at"#
@@ -94,6 +94,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let run = db.create_run(&CreateRun {
@@ -0,0 +1,222 @@
+-- Schema of a database created by the v13 release (before the migration
+-- registry), with a little data, used to check that upgrading an old
+-- database ends up with the same schema as a fresh one.
+-- Projects table (registered repositories for agent work)
+CREATE TABLE IF NOT EXISTS projects (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL UNIQUE,
+    
+    -- Hook installation status
+    cursor_hooks_installed INTEGER NOT NULL DEFAULT 0,
+    claude_hooks_installed INTEGER NOT NULL DEFAULT 0,
+    
+    -- Agent preferences for this project
+    preferred_agent TEXT CHECK(preferred_agent IN ('cursor', 'claude', 'any')),
+    
+    -- Safety settings
+    allow_shell_commands INTEGER NOT NULL DEFAULT 1,
+    allow_file_writes INTEGER NOT NULL DEFAULT 1,
+    blocked_patterns_json TEXT NOT NULL DEFAULT '[]',
+    
+    -- General settings
+    settings_json TEXT NOT NULL DEFAULT '{}',
+    
+    -- Whether this project requires git (default true for backward compatibility)
+    requires_git INTEGER NOT NULL DEFAULT 1,
+    
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_projects_path ON projects(path);
+
+-- Boards table
+CREATE TABLE IF NOT EXISTS boards (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    default_project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Columns table (kanban columns within a board)
+CREATE TABLE IF NOT EXISTS columns (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    wip_limit INTEGER,
+    UNIQUE(board_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_columns_board ON columns(board_id);
+
+-- Scratchpads table (for planner agent)
+-- Note: Must be created before tickets table since tickets references scratchpads(id)
+CREATE TABLE IF NOT EXISTS scratchpads (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    user_input TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
+    agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
+    model TEXT,
+    exploration_log TEXT,
+    plan_markdown TEXT,
+    plan_json TEXT,
+    settings_json TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
+CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
+CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
+CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
+
+-- Tickets table
+-- Note: locked_by_run_id intentionally omits FK constraint to avoid circular
+-- dependency with agent_runs table. Referential integrity is maintained at
+-- the application level.
+CREATE TABLE IF NOT EXISTS tickets (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    column_id TEXT NOT NULL REFERENCES columns(id) ON DELETE RESTRICT,
+    title TEXT NOT NULL,
+    description_md TEXT NOT NULL DEFAULT '',
+    priority TEXT NOT NULL DEFAULT 'medium' CHECK(priority IN ('low', 'medium', 'high', 'urgent')),
+    labels_json TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    locked_by_run_id TEXT,
+    lock_expires_at TEXT,
+    project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
+    agent_pref TEXT CHECK(agent_pref IN ('cursor', 'claude', 'any')),
+    workflow_type TEXT NOT NULL DEFAULT 'multi_stage' CHECK(workflow_type IN ('multi_stage')),
+    model TEXT,
+    branch_name TEXT,
+    -- Epic support: is_epic marks this ticket as an epic, epic_id references parent epic
+    is_epic INTEGER NOT NULL DEFAULT 0,
+    epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL,
+    order_in_epic INTEGER,
+    -- Cross-epic dependency: which epic must complete before this epic can start (primary dependency)
+    depends_on_epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL,
+    -- All epic dependencies as JSON array of IDs (for display purposes)
+    depends_on_epic_ids_json TEXT,
+    -- Link back to scratchpad that created this ticket
+    scratchpad_id TEXT REFERENCES scratchpads(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tickets_board ON tickets(board_id);
+CREATE INDEX IF NOT EXISTS idx_tickets_column ON tickets(column_id);
+CREATE INDEX IF NOT EXISTS idx_tickets_locked ON tickets(locked_by_run_id) WHERE locked_by_run_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tickets_project ON tickets(project_id);
+CREATE INDEX IF NOT EXISTS idx_tickets_epic ON tickets(epic_id, order_in_epic) WHERE epic_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tickets_depends_on ON tickets(depends_on_epic_id) WHERE depends_on_epic_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tickets_scratchpad ON tickets(scratchpad_id) WHERE scratchpad_id IS NOT NULL;
+
+-- Comments table
+CREATE TABLE IF NOT EXISTS comments (
+    id TEXT PRIMARY KEY NOT NULL,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    author_type TEXT NOT NULL CHECK(author_type IN ('user', 'agent', 'system')),
+    body_md TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    metadata_json TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_ticket ON comments(ticket_id);
+
+-- Agent runs table
+CREATE TABLE IF NOT EXISTS agent_runs (
+    id TEXT PRIMARY KEY NOT NULL,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    agent_type TEXT NOT NULL CHECK(agent_type IN ('cursor', 'claude')),
+    repo_path TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued' CHECK(status IN ('queued', 'running', 'finished', 'error', 'aborted')),
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    ended_at TEXT,
+    exit_code INTEGER,
+    summary_md TEXT,
+    metadata_json TEXT,
+    parent_run_id TEXT REFERENCES agent_runs(id) ON DELETE CASCADE,
+    stage TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_runs_ticket ON agent_runs(ticket_id);
+CREATE INDEX IF NOT EXISTS idx_runs_status ON agent_runs(status);
+CREATE INDEX IF NOT EXISTS idx_runs_parent ON agent_runs(parent_run_id) WHERE parent_run_id IS NOT NULL;
+
+-- Agent events table (audit trail for hook events)
+CREATE TABLE IF NOT EXISTS agent_events (
+    id TEXT PRIMARY KEY NOT NULL,
+    run_id TEXT NOT NULL REFERENCES agent_runs(id) ON DELETE CASCADE,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL,
+    payload_json TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_run ON agent_events(run_id);
+CREATE INDEX IF NOT EXISTS idx_events_ticket ON agent_events(ticket_id);
+CREATE INDEX IF NOT EXISTS idx_events_type ON agent_events(event_type);
+
+-- Schema version tracking
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY,
+    applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Repository-level locks to prevent multiple workers processing same repo
+CREATE TABLE IF NOT EXISTS repo_locks (
+    project_id TEXT PRIMARY KEY NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    locked_by_run_id TEXT NOT NULL,
+    lock_expires_at TEXT NOT NULL,
+    locked_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_repo_locks_expires ON repo_locks(lock_expires_at);
+
+-- Tasks table (task queue for tickets)
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY NOT NULL,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    order_index INTEGER NOT NULL,
+    task_type TEXT NOT NULL DEFAULT 'custom' CHECK(task_type IN ('custom', 'sync_with_main', 'add_tests', 'review_polish', 'fix_lint')),
+    title TEXT,
+    content TEXT,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'in_progress', 'completed', 'failed')),
+    run_id TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    started_at TEXT,
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_tasks_ticket ON tasks(ticket_id);
+CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+CREATE INDEX IF NOT EXISTS idx_tasks_order ON tasks(ticket_id, order_index);
+
+INSERT INTO schema_version (version) VALUES (13);
+
+INSERT INTO projects (id, name, path) VALUES ('p1', 'Repo', '/tmp/repo');
+INSERT INTO boards (id, name, default_project_id) VALUES ('b1', 'Board', 'p1');
+INSERT INTO columns (id, board_id, name, position) VALUES
+    ('c1', 'b1', 'Backlog', 0),
+    ('c2', 'b1', 'Ready', 1),
+    ('c3', 'b1', 'In Progress', 2),
+    ('c4', 'b1', 'Blocked', 3),
+    ('c5', 'b1', 'Review', 4),
+    ('c6', 'b1', 'Done', 5);
+INSERT INTO tickets (id, board_id, column_id, title, project_id, is_epic) VALUES
+    ('t1', 'b1', 'c1', 'Epic', 'p1', 1),
+    ('t2', 'b1', 'c6', 'Shipped', 'p1', 0);
+UPDATE tickets SET epic_id = 't1', order_in_epic = 0 WHERE id = 't2';
+INSERT INTO agent_runs (id, ticket_id, agent_type, repo_path, status) VALUES
+    ('r1', 't2', 'claude', '/tmp/repo', 'finished');
+INSERT INTO comments (id, ticket_id, author_type, body_md) VALUES
+    ('m1', 't2', 'agent', 'Done');
+INSERT INTO tasks (id, ticket_id, order_index, title, status) VALUES
+    ('k1', 't2', 0, 'Shipped', 'completed');
@@ -0,0 +1,202 @@
+//! Ticket audit history.
+//!
+//! Every mutation of a ticket row records the fields it changed through
+//! [`record_change`] on the same connection (and transaction) as the change.
+
+use rusqlite::Connection;
+
+use crate::db::{Database, DbError, parse_datetime};
+use crate::db::models::{Actor, ActorType, Ticket, TicketHistoryEntry};
+
+/// Field name used for lock acquisition and release; values are run ids
+pub const LOCK_FIELD: &str = "lock";
+
+/// Record a change to one ticket field. Does nothing when the value is unchanged.
+pub(crate) fn record_change(
+    conn: &Connection,
+    ticket_id: &str,
+    field: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    actor: &Actor,
+) -> Result<(), DbError> {
+    if old_value == new_value {
+        return Ok(());
+    }
+    conn.execute(
+        r#"INSERT INTO ticket_history
+           (id, ticket_id, field, old_value, new_value, actor_type, actor_id, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            ticket_id,
+            field,
+            old_value,
+            new_value,
+            actor.actor_type.as_str(),
+            actor.actor_id,
+            chrono::Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Record every field that differs between two versions of a ticket
+pub(crate) fn record_ticket_changes(
+    conn: &Connection,
+    before: &Ticket,
+    after: &Ticket,
+    actor: &Actor,
+) -> Result<(), DbError> {
+    for (field, old, new) in ticket_field_changes(before, after) {
+        record_change(conn, &after.id, field, old.as_deref(), new.as_deref(), actor)?;
+    }
+    Ok(())
+}
+
+/// `(field, old, new)` for each user-visible field that changed
+fn ticket_field_changes(before: &Ticket, after: &Ticket) -> Vec<(&'static str, Option<String>, Option<String>)> {
+    fn list(values: &[String]) -> Option<String> {
+        Some(serde_json::to_string(values).unwrap_or_default())
+    }
+
+    let fields = [
+        ("title", Some(before.title.clone()), Some(after.title.clone())),
+        ("descriptionMd", Some(before.description_md.clone()), Some(after.description_md.clone())),
+        ("priority", Some(before.priority.as_str().to_string()), Some(after.priority.as_str().to_string())),
+        ("labels", list(&before.labels), list(&after.labels)),
+        ("columnId", Some(before.column_id.clone()), Some(after.column_id.clone())),
+        ("projectId", before.project_id.clone(), after.project_id.clone()),
+        (
+            "agentPref",
+            before.agent_pref.as_ref().map(|p| p.as_str().to_string()),
+            after.agent_pref.as_ref().map(|p| p.as_str().to_string()),
+        ),
+        (
+            "workflowType",
+            Some(before.workflow_type.as_str().to_string()),
+            Some(after.workflow_type.as_str().to_string()),
+        ),
+        ("model", before.model.clone(), after.model.clone()),
+        ("branchName", before.branch_name.clone(), after.branch_name.clone()),
+        ("isEpic", Some(before.is_epic.to_string()), Some(after.is_epic.to_string())),
+        ("epicId", before.epic_id.clone(), after.epic_id.clone()),
+        ("orderInEpic", before.order_in_epic.map(|o| o.to_string()), after.order_in_epic.map(|o| o.to_string())),
+        ("dependsOnEpicIds", list(&before.depends_on_epic_ids), list(&after.depends_on_epic_ids)),
+        ("scratchpadId", before.scratchpad_id.clone(), after.scratchpad_id.clone()),
+        (
+            "customFields",
+            Some(serde_json::to_string(&before.custom_fields).unwrap_or_default()),
+            Some(serde_json::to_string(&after.custom_fields).unwrap_or_default()),
+        ),
+    ];
+
+    fields.into_iter().filter(|(_, old, new)| old != new).collect()
+}
+
+impl Database {
+    /// History of a ticket, oldest first
+    pub fn get_ticket_history(&self, ticket_id: &str) -> Result<Vec<TicketHistoryEntry>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, ticket_id, field, old_value, new_value, actor_type, actor_id, created_at
+                   FROM ticket_history
+                   WHERE ticket_id = ?
+                   ORDER BY created_at ASC, rowid ASC"#
+            )?;
+
+            let entries = stmt.query_map([ticket_id], |row| {
+                let actor_type: String = row.get(5)?;
+                Ok(TicketHistoryEntry {
+                    id: row.get(0)?,
+                    ticket_id: row.get(1)?,
+                    field: row.get(2)?,
+                    old_value: row.get(3)?,
+                    new_value: row.get(4)?,
+                    actor: Actor {
+                        actor_type: ActorType::parse(&actor_type).unwrap_or(ActorType::System),
+                        actor_id: row.get(6)?,
+                    },
+                    created_at: parse_datetime(row.get(7)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(entries)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{AgentType, CreateRun, CreateTicket, Priority, UpdateTicket};
+
+    fn setup_ticket(db: &Database) -> (Vec<crate::db::models::Column>, Ticket) {
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket = db.create_ticket(&CreateTicket::for_test(&board.id, &columns[0].id, "Audited")).unwrap();
+        (columns, ticket)
+    }
+
+    #[test]
+    fn move_records_actor_and_columns() {
+        let db = Database::open_in_memory().unwrap();
+        let (columns, ticket) = setup_ticket(&db);
+        let done = columns.last().unwrap();
+
+        db.move_ticket(&ticket.id, &done.id, &Actor::worker("worker-1")).unwrap();
+
+        let history = db.get_ticket_history(&ticket.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].field, "columnId");
+        assert_eq!(history[0].old_value.as_deref(), Some(columns[0].id.as_str()));
+        assert_eq!(history[0].new_value.as_deref(), Some(done.id.as_str()));
+        assert_eq!(history[0].actor, Actor::worker("worker-1"));
+    }
+
+    #[test]
+    fn update_records_only_changed_fields() {
+        let db = Database::open_in_memory().unwrap();
+        let (_, ticket) = setup_ticket(&db);
+
+        db.update_ticket(&ticket.id, &UpdateTicket {
+            title: Some("Audited".to_string()),
+            priority: Some(Priority::High),
+            ..Default::default()
+        }, &Actor::user()).unwrap();
+
+        let history = db.get_ticket_history(&ticket.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].field, "priority");
+        assert_eq!(history[0].old_value.as_deref(), Some("medium"));
+        assert_eq!(history[0].new_value.as_deref(), Some("high"));
+        assert_eq!(history[0].actor.actor_type, ActorType::User);
+    }
+
+    #[test]
+    fn lock_and_unlock_are_recorded() {
+        let db = Database::open_in_memory().unwrap();
+        let (_, ticket) = setup_ticket(&db);
+        let run = db.create_run(&CreateRun {
+            ticket_id: ticket.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: std::env::temp_dir().to_string_lossy().to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+
+        let expires = chrono::Utc::now() + chrono::Duration::minutes(5);
+        db.lock_ticket(&ticket.id, &run.id, expires).unwrap();
+        db.unlock_ticket(&ticket.id, &Actor::system()).unwrap();
+
+        let history = db.get_ticket_history(&ticket.id).unwrap();
+        let locks: Vec<_> = history.iter().filter(|e| e.field == LOCK_FIELD).collect();
+        assert_eq!(locks.len(), 2);
+        assert_eq!(locks[0].new_value.as_deref(), Some(run.id.as_str()));
+        assert_eq!(locks[0].actor, Actor::run(&run.id));
+        assert_eq!(locks[1].old_value.as_deref(), Some(run.id.as_str()));
+        assert_eq!(locks[1].new_value, None);
+        assert_eq!(locks[1].actor.actor_type, ActorType::System);
+    }
+}
@@ -0,0 +1,348 @@
+//! Board label catalog.
+//!
+//! Tickets keep their labels by name in `labels_json`; the catalog gives each
+//! name a color and description and keeps spellings consistent. Names are
+//! unique per board ignoring case, labels set on a ticket are rewritten to
+//! the catalog's spelling, and unknown ones are added to the catalog. Renaming,
+//! merging and deleting a label rewrite every ticket of the board carrying it.
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::db::history::record_change;
+use crate::db::models::{Actor, CreateLabel, Label, UpdateLabel};
+use crate::db::{parse_datetime, Database, DbError};
+
+/// Color of labels created without one
+pub const DEFAULT_LABEL_COLOR: &str = "#6b7280";
+
+const MAX_NAME_LEN: usize = 64;
+
+const LABEL_SELECT: &str = r#"
+    SELECT l.id, l.board_id, l.name, l.color, l.description, l.created_at,
+           (SELECT COUNT(*) FROM tickets t
+            WHERE t.board_id = l.board_id AND t.trash_id IS NULL
+              AND EXISTS (SELECT 1 FROM json_each(t.labels_json) j WHERE l.name = j.value))
+    FROM labels l"#;
+
+impl Database {
+    /// Labels of a board by name, with how many tickets use each
+    pub fn get_labels(&self, board_id: &str) -> Result<Vec<Label>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(&format!("{} WHERE l.board_id = ? ORDER BY l.name", LABEL_SELECT))?;
+            let rows = stmt.query_map([board_id], map_label_row)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    pub fn create_label(&self, board_id: &str, label: &CreateLabel) -> Result<Label, DbError> {
+        let name = validate_name(&label.name)?;
+        let color = match &label.color {
+            Some(color) => validate_color(color)?,
+            None => DEFAULT_LABEL_COLOR.to_string(),
+        };
+
+        self.with_conn(|conn| {
+            let board_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM boards WHERE id = ? AND trash_id IS NULL)",
+                [board_id],
+                |row| row.get(0),
+            )?;
+            if !board_exists {
+                return Err(DbError::NotFound(format!("Board {}", board_id)));
+            }
+            if let Some(existing) = find_label(conn, board_id, name)? {
+                return Err(DbError::Validation(format!("Label '{}' already exists on this board", existing)));
+            }
+            let id = insert_label(conn, board_id, name, &color, label.description.trim())?;
+            get_label(conn, &id)
+        })
+    }
+
+    /// Change a label's color or description, or rename it on every ticket of
+    /// the board. Renaming onto another label's name is refused; merge instead.
+    pub fn update_label(&self, label_id: &str, updates: &UpdateLabel, actor: &Actor) -> Result<Label, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let mut label = get_label(&tx, label_id)?;
+
+            if let Some(name) = &updates.name {
+                let name = validate_name(name)?;
+                if name != label.name {
+                    if !name.eq_ignore_ascii_case(&label.name) {
+                        if let Some(existing) = find_label(&tx, &label.board_id, name)? {
+                            return Err(DbError::Validation(format!(
+                                "Label '{}' already exists; merge the labels instead", existing
+                            )));
+                        }
+                    }
+                    relabel(&tx, &label.board_id, &label.name, Some(name), actor)?;
+                    label.name = name.to_string();
+                }
+            }
+            if let Some(color) = &updates.color {
+                label.color = validate_color(color)?;
+            }
+            if let Some(description) = &updates.description {
+                label.description = description.trim().to_string();
+            }
+
+            tx.execute(
+                "UPDATE labels SET name = ?, color = ?, description = ? WHERE id = ?",
+                rusqlite::params![label.name, label.color, label.description, label_id],
+            )?;
+            let updated = get_label(&tx, label_id)?;
+            tx.commit()?;
+            Ok(updated)
+        })
+    }
+
+    /// Replace `source_id` with `target_id` on every ticket and drop the
+    /// source label. Both must belong to the same board.
+    pub fn merge_labels(&self, source_id: &str, target_id: &str, actor: &Actor) -> Result<Label, DbError> {
+        if source_id == target_id {
+            return Err(DbError::Validation("Cannot merge a label into itself".to_string()));
+        }
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let source = get_label(&tx, source_id)?;
+            let target = get_label(&tx, target_id)?;
+            if source.board_id != target.board_id {
+                return Err(DbError::Validation("Labels belong to different boards".to_string()));
+            }
+
+            relabel(&tx, &source.board_id, &source.name, Some(&target.name), actor)?;
+            tx.execute("DELETE FROM labels WHERE id = ?", [source_id])?;
+            let merged = get_label(&tx, target_id)?;
+            tx.commit()?;
+            Ok(merged)
+        })
+    }
+
+    /// Delete a label and remove it from every ticket of the board
+    pub fn delete_label(&self, label_id: &str, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let label = get_label(&tx, label_id)?;
+            relabel(&tx, &label.board_id, &label.name, None, actor)?;
+            tx.execute("DELETE FROM labels WHERE id = ?", [label_id])?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+}
+
+/// Spell `labels` the way the board's catalog does, adding names it does not
+/// know yet. Blank and duplicate labels are dropped.
+pub(crate) fn catalog_labels(conn: &Connection, board_id: &str, labels: &[String]) -> Result<Vec<String>, DbError> {
+    let mut names: Vec<String> = Vec::with_capacity(labels.len());
+    for label in labels {
+        let label = label.trim();
+        if label.is_empty() || names.iter().any(|n| n.eq_ignore_ascii_case(label)) {
+            continue;
+        }
+        let name = match find_label(conn, board_id, label)? {
+            Some(name) => name,
+            None => {
+                let name = validate_name(label)?;
+                insert_label(conn, board_id, name, DEFAULT_LABEL_COLOR, "")?;
+                name.to_string()
+            }
+        };
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// Rename label `from` to `to` on every ticket of the board, or remove it
+/// when `to` is `None`. Returns the number of tickets changed.
+fn relabel(conn: &Connection, board_id: &str, from: &str, to: Option<&str>, actor: &Actor) -> Result<usize, DbError> {
+    let tickets: Vec<(String, String)> = {
+        let mut stmt = conn.prepare(
+            r#"SELECT id, labels_json FROM tickets
+               WHERE board_id = ?1
+                 AND EXISTS (SELECT 1 FROM json_each(labels_json) j WHERE j.value = ?2 COLLATE NOCASE)"#,
+        )?;
+        let rows = stmt.query_map(rusqlite::params![board_id, from], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    for (ticket_id, labels_json) in &tickets {
+        let before: Vec<String> = serde_json::from_str(labels_json).unwrap_or_default();
+        let mut after: Vec<String> = Vec::with_capacity(before.len());
+        for label in &before {
+            let label = match (label.eq_ignore_ascii_case(from), to) {
+                (true, Some(to)) => to,
+                (true, None) => continue,
+                (false, _) => label.as_str(),
+            };
+            if !after.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+                after.push(label.to_string());
+            }
+        }
+
+        let before_json = serde_json::to_string(&before).unwrap_or_else(|_| "[]".to_string());
+        let after_json = serde_json::to_string(&after).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "UPDATE tickets SET labels_json = ?, updated_at = ? WHERE id = ?",
+            rusqlite::params![after_json, now, ticket_id],
+        )?;
+        record_change(conn, ticket_id, "labels", Some(&before_json), Some(&after_json), actor)?;
+    }
+    Ok(tickets.len())
+}
+
+/// Catalog spelling of `name` on the board, if it has one
+fn find_label(conn: &Connection, board_id: &str, name: &str) -> Result<Option<String>, DbError> {
+    conn.query_row(
+        "SELECT name FROM labels WHERE board_id = ? AND name = ?",
+        rusqlite::params![board_id, name],
+        |row| row.get(0),
+    ).optional().map_err(DbError::from)
+}
+
+fn insert_label(conn: &Connection, board_id: &str, name: &str, color: &str, description: &str) -> Result<String, DbError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO labels (id, board_id, name, color, description, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        rusqlite::params![id, board_id, name, color, description, chrono::Utc::now().to_rfc3339()],
+    )?;
+    Ok(id)
+}
+
+fn get_label(conn: &Connection, label_id: &str) -> Result<Label, DbError> {
+    conn.query_row(&format!("{} WHERE l.id = ?", LABEL_SELECT), [label_id], map_label_row)
+        .optional()?
+        .ok_or_else(|| DbError::NotFound(format!("Label {}", label_id)))
+}
+
+fn map_label_row(row: &rusqlite::Row) -> rusqlite::Result<Label> {
+    Ok(Label {
+        id: row.get(0)?,
+        board_id: row.get(1)?,
+        name: row.get(2)?,
+        color: row.get(3)?,
+        description: row.get(4)?,
+        created_at: parse_datetime(row.get(5)?),
+        ticket_count: row.get(6)?,
+    })
+}
+
+fn validate_name(name: &str) -> Result<&str, DbError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DbError::Validation("Label name cannot be empty".to_string()));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(DbError::Validation(format!("Label names are at most {} characters", MAX_NAME_LEN)));
+    }
+    Ok(name)
+}
+
+/// Accepts `#rrggbb` in either case and returns it in lowercase
+fn validate_color(color: &str) -> Result<String, DbError> {
+    let color = color.trim();
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid {
+        Ok(color.to_ascii_lowercase())
+    } else {
+        Err(DbError::Validation(format!("Label color '{}' must look like #rrggbb", color)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{CreateTicket, Ticket, UpdateTicket};
+
+    fn create_ticket(db: &Database, board_id: &str, labels: &[&str]) -> Ticket {
+        let column = db.get_columns(board_id).unwrap().remove(0);
+        db.create_ticket(&CreateTicket {
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            ..CreateTicket::for_test(board_id, &column.id, "Ticket")
+        }).unwrap()
+    }
+
+    fn label(db: &Database, board_id: &str, name: &str) -> Label {
+        db.get_labels(board_id).unwrap().into_iter().find(|l| l.name == name).unwrap()
+    }
+
+    #[test]
+    fn ticket_labels_use_catalog_spelling() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        db.create_label(&board.id, &CreateLabel {
+            name: "Frontend".to_string(),
+            color: Some("#3B82F6".to_string()),
+            description: "UI work".to_string(),
+        }).unwrap();
+
+        let ticket = create_ticket(&db, &board.id, &["frontend", " bug ", "FRONTEND", ""]);
+        assert_eq!(ticket.labels, vec!["Frontend", "bug"]);
+
+        let labels = db.get_labels(&board.id).unwrap();
+        assert_eq!(labels.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["bug", "Frontend"]);
+        assert_eq!(label(&db, &board.id, "Frontend").color, "#3b82f6");
+        assert_eq!(label(&db, &board.id, "bug").color, DEFAULT_LABEL_COLOR);
+        assert!(labels.iter().all(|l| l.ticket_count == 1));
+
+        let updated = db.update_ticket(&ticket.id, &UpdateTicket {
+            labels: Some(vec!["BUG".to_string()]),
+            ..Default::default()
+        }, &Actor::user()).unwrap();
+        assert_eq!(updated.labels, vec!["bug"]);
+
+        assert!(db.create_label(&board.id, &CreateLabel {
+            name: "BUG".to_string(),
+            color: None,
+            description: String::new(),
+        }).is_err());
+    }
+
+    #[test]
+    fn rename_and_merge_rewrite_tickets() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let both = create_ticket(&db, &board.id, &["fronted", "frontend"]);
+        let typo = create_ticket(&db, &board.id, &["fronted", "bug"]);
+
+        let renamed = db.update_label(&label(&db, &board.id, "bug").id, &UpdateLabel {
+            name: Some("defect".to_string()),
+            ..Default::default()
+        }, &Actor::user()).unwrap();
+        assert_eq!(renamed.ticket_count, 1);
+        assert_eq!(db.get_ticket(&typo.id).unwrap().labels, vec!["fronted", "defect"]);
+
+        let taken = UpdateLabel { name: Some("Frontend".to_string()), ..Default::default() };
+        assert!(matches!(
+            db.update_label(&label(&db, &board.id, "fronted").id, &taken, &Actor::user()),
+            Err(DbError::Validation(_))
+        ));
+
+        let merged = db.merge_labels(
+            &label(&db, &board.id, "fronted").id,
+            &label(&db, &board.id, "frontend").id,
+            &Actor::user(),
+        ).unwrap();
+        assert_eq!(merged.ticket_count, 2);
+        assert_eq!(db.get_ticket(&both.id).unwrap().labels, vec!["frontend"]);
+        assert_eq!(db.get_ticket(&typo.id).unwrap().labels, vec!["frontend", "defect"]);
+        assert_eq!(db.get_labels(&board.id).unwrap().len(), 2);
+
+        let history = db.get_ticket_history(&typo.id).unwrap();
+        assert_eq!(history.iter().filter(|h| h.field == "labels").count(), 2);
+    }
+
+    #[test]
+    fn deleting_a_label_removes_it_from_tickets() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let ticket = create_ticket(&db, &board.id, &["needs-human", "backend"]);
+
+        db.delete_label(&label(&db, &board.id, "needs-human").id, &Actor::user()).unwrap();
+        assert_eq!(db.get_ticket(&ticket.id).unwrap().labels, vec!["backend"]);
+        assert_eq!(db.get_labels(&board.id).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::db::{Database, DbError};
+use crate::db::models::{ColumnTicketCount, RunStats, StageTimeoutCount};
+use crate::lifecycle::TicketState;
+
+impl Database {
+    /// Count tickets in every column of every board.
+    ///
+    /// Columns without tickets are included with zero counts so that gauges
+    /// drop back to 0 instead of disappearing when a column empties.
+    pub fn get_column_ticket_counts(&self) -> Result<Vec<ColumnTicketCount>, DbError> {
+        self.with_reader(|conn| {
+            let now = chrono::Utc::now().to_rfc3339();
+            let mut stmt = conn.prepare(
+                r#"SELECT b.id, b.name, c.id, c.name, c.role,
+                          COUNT(t.id),
+                          COALESCE(SUM(CASE
+                              WHEN t.is_epic = 0
+                               AND (t.locked_by_run_id IS NULL OR t.lock_expires_at < ?1)
+                              THEN 1 ELSE 0 END), 0),
+                          COALESCE(SUM(CASE
+                              WHEN t.locked_by_run_id IS NOT NULL AND t.lock_expires_at >= ?1
+                              THEN 1 ELSE 0 END), 0)
+                   FROM columns c
+                   JOIN boards b ON b.id = c.board_id AND b.trash_id IS NULL
+                   LEFT JOIN tickets t ON t.column_id = c.id AND t.trash_id IS NULL
+                   GROUP BY c.id
+                   ORDER BY b.created_at, c.position"#,
+            )?;
+
+            let counts = stmt.query_map([&now], |row| {
+                let role: Option<String> = row.get(4)?;
+                Ok(ColumnTicketCount {
+                    board_id: row.get(0)?,
+                    board_name: row.get(1)?,
+                    column_id: row.get(2)?,
+                    column_name: row.get(3)?,
+                    column_role: role.as_deref().and_then(TicketState::parse),
+                    total: row.get(5)?,
+                    waiting: row.get(6)?,
+                    locked: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(counts)
+        })
+    }
+
+    /// Aggregate run counts and durations grouped by agent, stage and status.
+    ///
+    /// Durations are only summed for runs that have ended; `ended_count` is the
+    /// matching denominator.
+    pub fn get_run_stats(&self) -> Result<Vec<RunStats>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT agent_type, stage, status,
+                          COUNT(*),
+                          COALESCE(SUM(CASE WHEN ended_at IS NOT NULL THEN 1 ELSE 0 END), 0),
+                          COALESCE(SUM(CASE WHEN ended_at IS NOT NULL
+                              THEN MAX(0.0, (julianday(ended_at) - julianday(started_at)) * 86400.0)
+                              ELSE 0.0 END), 0.0)
+                   FROM agent_runs
+                   GROUP BY agent_type, stage, status
+                   ORDER BY agent_type, stage, status"#,
+            )?;
+
+            let stats = stmt.query_map([], |row| {
+                Ok(RunStats {
+                    agent_type: row.get(0)?,
+                    stage: row.get(1)?,
+                    status: row.get(2)?,
+                    count: row.get(3)?,
+                    ended_count: row.get(4)?,
+                    duration_secs_sum: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(stats)
+        })
+    }
+
+    /// Count the stage runs that timed out, by agent and stage
+    pub fn get_stage_timeout_counts(&self) -> Result<Vec<StageTimeoutCount>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT agent_type, stage, COUNT(*)
+                   FROM agent_runs
+                   WHERE outcome = 'timeout' AND stage IS NOT NULL
+                   GROUP BY agent_type, stage
+                   ORDER BY agent_type, stage"#,
+            )?;
+
+            let counts = stmt.query_map([], |row| {
+                Ok(StageTimeoutCount {
+                    agent_type: row.get(0)?,
+                    stage: row.get(1)?,
+                    count: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(counts)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{Database, CreateRun, CreateTicket, AgentType, RunStatus};
+    use chrono::{Duration, Utc};
+
+    fn create_ticket(db: &Database, board_id: &str, column_id: &str) -> String {
+        db.create_ticket(&CreateTicket::for_test(board_id, column_id, "Ticket")).unwrap().id
+    }
+
+    #[test]
+    fn column_counts_include_empty_columns() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+
+        let counts = db.get_column_ticket_counts().unwrap();
+        assert_eq!(counts.len(), 6);
+        assert!(counts.iter().all(|c| c.total == 0 && c.board_id == board.id));
+        assert_eq!(counts[1].column_name, "Ready");
+    }
+
+    #[test]
+    fn column_counts_split_waiting_and_locked() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let ready = db.find_column_by_name(&board.id, "Ready").unwrap().unwrap();
+
+        let t1 = create_ticket(&db, &board.id, &ready.id);
+        create_ticket(&db, &board.id, &ready.id);
+        db.lock_ticket(&t1, "run-1", Utc::now() + Duration::minutes(30)).unwrap();
+
+        let counts = db.get_column_ticket_counts().unwrap();
+        let ready_counts = counts.iter().find(|c| c.column_id == ready.id).unwrap();
+        assert_eq!(ready_counts.total, 2);
+        assert_eq!(ready_counts.waiting, 1);
+        assert_eq!(ready_counts.locked, 1);
+    }
+
+    #[test]
+    fn run_stats_group_by_agent_stage_and_status() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket_id = create_ticket(&db, &board.id, &columns[0].id);
+
+        let parent = db.create_run(&CreateRun {
+            ticket_id: ticket_id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp".to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        for _ in 0..2 {
+            let sub = db.create_run(&CreateRun {
+                ticket_id: ticket_id.clone(),
+                agent_type: AgentType::Claude,
+                repo_path: "/tmp".to_string(),
+                parent_run_id: Some(parent.id.clone()),
+                stage: Some("plan".to_string()),
+            }).unwrap();
+            db.update_run_status(&sub.id, RunStatus::Finished, Some(0), None).unwrap();
+        }
+
+        let stats = db.get_run_stats().unwrap();
+        let plan = stats.iter().find(|s| s.stage.as_deref() == Some("plan")).unwrap();
+        assert_eq!(plan.agent_type, "claude");
+        assert_eq!(plan.status, "finished");
+        assert_eq!(plan.count, 2);
+        assert_eq!(plan.ended_count, 2);
+        assert!(plan.duration_secs_sum >= 0.0);
+
+        let parent_stats = stats.iter().find(|s| s.stage.is_none()).unwrap();
+        assert_eq!(parent_stats.status, "queued");
+        assert_eq!(parent_stats.ended_count, 0);
+    }
+
+    #[test]
+    fn stage_timeouts_are_counted_by_agent_and_stage() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket_id = create_ticket(&db, &board.id, &columns[0].id);
+
+        for (stage, outcome) in [("implement", "timeout"), ("implement", "timeout"), ("implement", "error"), ("plan", "success")] {
+            let sub = db.create_run(&CreateRun {
+                ticket_id: ticket_id.clone(),
+                agent_type: AgentType::Cursor,
+                repo_path: "/tmp".to_string(),
+                parent_run_id: None,
+                stage: Some(stage.to_string()),
+            }).unwrap();
+            db.set_run_outcome(&sub.id, outcome).unwrap();
+        }
+
+        let counts = db.get_stage_timeout_counts().unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].agent_type, "cursor");
+        assert_eq!(counts[0].stage, "implement");
+        assert_eq!(counts[0].count, 2);
+    }
+}
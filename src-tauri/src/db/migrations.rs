@@ -0,0 +1,740 @@
+//! Versioned schema migrations.
+//!
+//! Every schema change is a [`Migration`] in [`MIGRATIONS`]. Fresh and
+//! existing databases run the same chain: each pending migration is applied
+//! in its own transaction and recorded in `schema_migrations` together with a
+//! checksum, so a migration edited after release is reported instead of
+//! silently leaving old databases behind. Existing files are copied to a
+//! timestamped backup before anything is applied.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::db::{backfill_column_roles, backup, schema, DbError};
+
+/// Data fix-up run inside a migration's transaction
+pub type Backfill = fn(&Connection) -> Result<(), DbError>;
+
+/// One step of the schema history
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    /// `(table, column, definition)` added with `ALTER TABLE`; columns that
+    /// already exist are skipped, since databases upgraded before the registry
+    /// existed may have received some of them
+    pub add_columns: &'static [(&'static str, &'static str, &'static str)],
+    /// Data fix-ups run after `add_columns` and before `sql`
+    pub backfill: Option<Backfill>,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// Stable checksum of everything the migration changes.
+    /// `backfill` functions are identified by the migration name only.
+    pub fn checksum(&self) -> String {
+        // FNV-1a: stable across Rust versions, unlike `DefaultHasher`
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |bytes: &[u8]| {
+            for byte in bytes.iter().chain(std::iter::once(&0u8)) {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        feed(self.name.as_bytes());
+        for (table, column, definition) in self.add_columns {
+            feed(table.as_bytes());
+            feed(column.as_bytes());
+            feed(definition.as_bytes());
+        }
+        feed(self.sql.as_bytes());
+        format!("{:016x}", hash)
+    }
+}
+
+/// All migrations, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial schema",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V1,
+    },
+    Migration {
+        version: 3,
+        name: "repo_locks and requires_git",
+        add_columns: &[("projects", "requires_git", "INTEGER NOT NULL DEFAULT 1")],
+        backfill: None,
+        sql: schema::MIGRATION_V3,
+    },
+    Migration {
+        version: 4,
+        name: "workflow_type, parent_run_id, stage",
+        add_columns: &[
+            ("tickets", "workflow_type", "TEXT NOT NULL DEFAULT 'basic'"),
+            ("agent_runs", "parent_run_id", "TEXT REFERENCES agent_runs(id) ON DELETE CASCADE"),
+            ("agent_runs", "stage", "TEXT"),
+        ],
+        backfill: None,
+        sql: schema::MIGRATION_V4,
+    },
+    Migration {
+        version: 5,
+        name: "model column for tickets",
+        add_columns: &[("tickets", "model", "TEXT")],
+        backfill: None,
+        sql: "",
+    },
+    Migration {
+        version: 6,
+        name: "convert basic workflow to multi_stage",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V6,
+    },
+    Migration {
+        version: 7,
+        name: "branch_name column for tickets",
+        add_columns: &[("tickets", "branch_name", "TEXT")],
+        backfill: None,
+        sql: "",
+    },
+    Migration {
+        version: 8,
+        name: "tasks table",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V8,
+    },
+    Migration {
+        version: 9,
+        name: "epic support columns",
+        add_columns: &[
+            ("tickets", "is_epic", "INTEGER NOT NULL DEFAULT 0"),
+            ("tickets", "epic_id", "TEXT REFERENCES tickets(id) ON DELETE SET NULL"),
+            ("tickets", "order_in_epic", "INTEGER"),
+        ],
+        backfill: None,
+        sql: schema::MIGRATION_V9,
+    },
+    Migration {
+        version: 10,
+        name: "scratchpads table and epic dependencies",
+        add_columns: &[
+            ("tickets", "depends_on_epic_id", "TEXT REFERENCES tickets(id) ON DELETE SET NULL"),
+            ("tickets", "scratchpad_id", "TEXT REFERENCES scratchpads(id) ON DELETE SET NULL"),
+        ],
+        backfill: None,
+        sql: schema::MIGRATION_V10,
+    },
+    Migration {
+        version: 11,
+        name: "target_board_id for scratchpads",
+        add_columns: &[("scratchpads", "target_board_id", "TEXT REFERENCES boards(id) ON DELETE SET NULL")],
+        backfill: None,
+        sql: schema::MIGRATION_V11,
+    },
+    Migration {
+        version: 12,
+        name: "scratchpads status CHECK constraint",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V12,
+    },
+    Migration {
+        version: 13,
+        name: "depends_on_epic_ids_json column",
+        add_columns: &[("tickets", "depends_on_epic_ids_json", "TEXT")],
+        backfill: None,
+        sql: "",
+    },
+    Migration {
+        version: 14,
+        name: "column roles",
+        add_columns: &[(
+            "columns",
+            "role",
+            "TEXT CHECK(role IS NULL OR role IN ('backlog', 'ready', 'in_progress', 'blocked', 'review', 'done'))",
+        )],
+        backfill: Some(backfill_column_roles),
+        sql: schema::MIGRATION_V14,
+    },
+    Migration {
+        version: 15,
+        name: "ticket_history table",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V15,
+    },
+    Migration {
+        version: 16,
+        name: "canonical tickets table",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V16,
+    },
+    Migration {
+        version: 17,
+        name: "ticket_archive table",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V17,
+    },
+    Migration {
+        version: 18,
+        name: "trash for soft-deleted tickets, boards and scratchpads",
+        add_columns: &[
+            ("tickets", "trash_id", "TEXT"),
+            ("boards", "trash_id", "TEXT"),
+            ("scratchpads", "trash_id", "TEXT"),
+        ],
+        backfill: None,
+        sql: schema::MIGRATION_V18,
+    },
+    Migration {
+        version: 19,
+        name: "custom ticket fields",
+        add_columns: &[("tickets", "custom_fields_json", "TEXT NOT NULL DEFAULT '{}'")],
+        backfill: None,
+        sql: schema::MIGRATION_V19,
+    },
+    Migration {
+        version: 20,
+        name: "label catalog",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V20,
+    },
+    Migration {
+        version: 21,
+        name: "saved workers",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V21,
+    },
+    Migration {
+        version: 22,
+        name: "remote workers",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V22,
+    },
+    Migration {
+        version: 23,
+        name: "settings",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V23,
+    },
+    Migration {
+        version: 24,
+        name: "workflow checkpoints",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V24,
+    },
+    Migration {
+        version: 25,
+        name: "agent process ids",
+        add_columns: &[("agent_runs", "pid", "INTEGER")],
+        backfill: None,
+        sql: "",
+    },
+    Migration {
+        version: 26,
+        name: "workflow pauses",
+        add_columns: &[],
+        backfill: None,
+        sql: schema::MIGRATION_V26,
+    },
+    Migration {
+        version: 27,
+        name: "stage timeouts",
+        add_columns: &[
+            ("projects", "stage_timeouts_json", "TEXT NOT NULL DEFAULT '{}'"),
+            ("agent_runs", "outcome", "TEXT"),
+        ],
+        backfill: None,
+        sql: "",
+    },
+    Migration {
+        version: 28,
+        name: "agent process start times",
+        add_columns: &[("agent_runs", "pid_started_at", "TEXT")],
+        backfill: None,
+        sql: "",
+    },
+];
+
+/// Version of the newest migration
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+const CREATE_MIGRATIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    applied_at TEXT NOT NULL
+);
+"#;
+
+/// Bring the database up to [`latest_version`].
+///
+/// `db_path` is the file backing `conn`; when set, an existing database is
+/// backed up before the first pending migration runs.
+pub(crate) fn run(conn: &mut Connection, db_path: Option<&Path>) -> Result<(), DbError> {
+    let legacy_version = legacy_version(conn)?;
+    let applied = if table_exists(conn, "schema_migrations")? {
+        applied_migrations(conn)?
+    } else {
+        BTreeMap::new()
+    };
+    verify_applied(&applied)?;
+    if let Some(version) = legacy_version.filter(|v| *v > latest_version()) {
+        return Err(DbError::Migration(format!(
+            "database is at schema version {}, newer than this build ({})",
+            version, latest_version()
+        )));
+    }
+
+    let current = applied.keys().next_back().copied().or(legacy_version).unwrap_or(0);
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.version > current).collect();
+    if pending.is_empty() && legacy_version.is_none() {
+        return Ok(());
+    }
+
+    if current > 0 && !pending.is_empty() {
+        tracing::info!("Migrating database from version {} to {}", current, latest_version());
+        if let Some(path) = db_path {
+            let backup = backup::backup_connection(conn, path, &format!("pre-migration-v{}", current))
+                .map_err(|e| DbError::Migration(format!("backup before upgrade failed: {}", e)))?;
+            tracing::info!("Backed up database to {:?}", backup);
+        }
+    }
+
+    conn.execute_batch(CREATE_MIGRATIONS_TABLE)?;
+    if let Some(legacy_version) = legacy_version {
+        adopt_legacy_version(conn, legacy_version)?;
+    }
+
+    // Table rebuilds must not fire ON DELETE actions; integrity is checked
+    // per migration with foreign_key_check instead
+    conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+    let result = pending.iter().try_for_each(|migration| apply(conn, migration));
+    conn.execute_batch("PRAGMA foreign_keys = ON")?;
+    result?;
+
+    tracing::info!("Database migration complete");
+    Ok(())
+}
+
+fn apply(conn: &mut Connection, migration: &Migration) -> Result<(), DbError> {
+    tracing::info!("Applying migration v{}: {}", migration.version, migration.name);
+    let fail = |e: DbError| DbError::Migration(format!(
+        "migration v{} ({}) failed: {}", migration.version, migration.name, e
+    ));
+
+    let tx = conn.transaction()?;
+    for (table, column, definition) in migration.add_columns {
+        if !has_column(&tx, table, column).map_err(fail)? {
+            tx.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                .map_err(|e| fail(e.into()))?;
+        }
+    }
+    if let Some(backfill) = migration.backfill {
+        backfill(&tx).map_err(fail)?;
+    }
+    tx.execute_batch(migration.sql).map_err(|e| fail(e.into()))?;
+
+    let violations: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM pragma_foreign_key_check",
+        [],
+        |row| row.get(0),
+    )?;
+    if violations > 0 {
+        return Err(fail(DbError::Validation(format!("{} foreign key violation(s)", violations))));
+    }
+
+    tx.execute(
+        "INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (?, ?, ?, ?)",
+        rusqlite::params![
+            migration.version,
+            migration.name,
+            migration.checksum(),
+            chrono::Utc::now().to_rfc3339(),
+        ],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+fn table_exists(conn: &Connection, name: &str) -> Result<bool, DbError> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [name],
+        |row| row.get(0),
+    ).map_err(DbError::from)
+}
+
+/// Version recorded by databases from before the registry, which only kept
+/// a number in `schema_version`
+fn legacy_version(conn: &Connection) -> Result<Option<i32>, DbError> {
+    if !table_exists(conn, "schema_version")? {
+        return Ok(None);
+    }
+    let version: Option<i32> = conn.query_row(
+        "SELECT MAX(version) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(version)
+}
+
+/// Mark every migration up to the legacy version as applied and drop
+/// `schema_version`
+fn adopt_legacy_version(conn: &mut Connection, legacy_version: i32) -> Result<(), DbError> {
+    tracing::info!("Adopting legacy schema version {} into schema_migrations", legacy_version);
+    let tx = conn.transaction()?;
+    let applied_at = chrono::Utc::now().to_rfc3339();
+    for migration in MIGRATIONS.iter().filter(|m| m.version <= legacy_version) {
+        tx.execute(
+            "INSERT OR IGNORE INTO schema_migrations (version, name, checksum, applied_at) VALUES (?, ?, ?, ?)",
+            rusqlite::params![migration.version, migration.name, migration.checksum(), applied_at],
+        )?;
+    }
+    tx.execute_batch("DROP TABLE schema_version")?;
+    tx.commit()?;
+    Ok(())
+}
+
+fn applied_migrations(conn: &Connection) -> Result<BTreeMap<i32, String>, DbError> {
+    let mut stmt = conn.prepare("SELECT version, checksum FROM schema_migrations")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect::<Result<BTreeMap<_, _>, _>>().map_err(DbError::from)
+}
+
+/// Every applied migration must still exist with the same checksum
+fn verify_applied(applied: &BTreeMap<i32, String>) -> Result<(), DbError> {
+    for (version, checksum) in applied {
+        let migration = MIGRATIONS.iter().find(|m| m.version == *version).ok_or_else(|| {
+            DbError::Migration(format!(
+                "database has migration v{} applied, which this build does not know; \
+                 it was probably written by a newer version",
+                version
+            ))
+        })?;
+        if migration.checksum() != *checksum {
+            return Err(DbError::Migration(format!(
+                "migration v{} ({}) was changed after it was applied (checksum {} != {})",
+                version, migration.name, checksum, migration.checksum()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool, DbError> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?",
+        [table, column],
+        |row| row.get(0),
+    ).map_err(DbError::from)
+}
+
+/// Normalized description of a schema: one line per column, foreign key,
+/// CHECK constraint and index, sorted. Column order and formatting of the
+/// original `CREATE` statements do not matter.
+pub fn schema_fingerprint(conn: &Connection) -> Result<Vec<String>, DbError> {
+    let mut lines = Vec::new();
+
+    let tables: Vec<(String, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+
+    for (table, sql) in &tables {
+        let mut stmt = conn.prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?)")?;
+        let columns = stmt.query_map([table], |row| {
+            Ok(format!(
+                "{} column {} {} notnull={} default={} pk={}",
+                table,
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                row.get::<_, i32>(4)?,
+            ))
+        })?;
+        for column in columns {
+            lines.push(column?);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT \"from\", \"table\", \"to\", on_update, on_delete FROM pragma_foreign_key_list(?)"
+        )?;
+        let keys = stmt.query_map([table], |row| {
+            Ok(format!(
+                "{} fk {} -> {}({}) on_update={} on_delete={}",
+                table,
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for key in keys {
+            lines.push(key?);
+        }
+
+        for check in check_constraints(sql) {
+            lines.push(format!("{} check {}", table, check));
+        }
+
+        let mut stmt = conn.prepare("SELECT name, \"unique\", origin FROM pragma_index_list(?)")?;
+        let indexes = stmt.query_map([table], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?, row.get::<_, String>(2)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        for (index, unique, origin) in indexes {
+            let mut stmt = conn.prepare("SELECT name FROM pragma_index_info(?) ORDER BY seqno")?;
+            let columns = stmt.query_map([&index], |row| row.get::<_, Option<String>>(0))?
+                .map(|c| c.map(Option::unwrap_or_default))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ");
+            let index_sql: Option<String> = conn.query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'index' AND name = ?",
+                [&index],
+                |row| row.get(0),
+            ).optional()?.flatten();
+            let filter = index_sql
+                .as_deref()
+                .and_then(|sql| sql.to_ascii_uppercase().find(" WHERE ").map(|at| normalize_sql(&sql[at + 7..])))
+                .unwrap_or_default();
+            // Automatic indexes are named after creation order, so identify them by columns
+            let name = if origin == "c" { index } else { format!("({})", origin) };
+            lines.push(format!(
+                "{} index {} on ({}) unique={} where={}",
+                table, name, columns, unique, filter
+            ));
+        }
+    }
+
+    lines.sort();
+    Ok(lines)
+}
+
+/// The `CHECK (...)` expressions in a `CREATE TABLE` statement, normalized
+fn check_constraints(create_sql: &str) -> Vec<String> {
+    let sql: String = create_sql
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let upper = sql.to_ascii_uppercase();
+    let bytes = sql.as_bytes();
+    let mut checks = Vec::new();
+    let mut from = 0;
+
+    while let Some(found) = upper[from..].find("CHECK") {
+        let mut i = from + found + "CHECK".len();
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= bytes.len() || bytes[i] != b'(' {
+            from = i;
+            continue;
+        }
+        let start = i;
+        let mut depth = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        checks.push(normalize_sql(&sql[start..=i.min(bytes.len() - 1)]));
+        from = i;
+    }
+    checks.sort();
+    checks
+}
+
+fn normalize_sql(sql: &str) -> String {
+    sql.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .trim_end_matches(';')
+        .to_string()
+}
+
+/// Schema of a database created by the last release without the registry
+const LEGACY_V13_FIXTURE: &str = include_str!("fixtures/v13.sql");
+
+/// Check that upgrading an old database produces exactly the schema of a
+/// fresh one. Returns the differing lines on failure.
+pub fn check_migrations() -> Result<(), DbError> {
+    let mut fresh = Connection::open_in_memory()?;
+    run(&mut fresh, None)?;
+
+    let mut upgraded = Connection::open_in_memory()?;
+    upgraded.execute_batch(LEGACY_V13_FIXTURE)?;
+    run(&mut upgraded, None)?;
+
+    let fresh = schema_fingerprint(&fresh)?;
+    let upgraded = schema_fingerprint(&upgraded)?;
+    if fresh == upgraded {
+        return Ok(());
+    }
+
+    let mut diff = Vec::new();
+    diff.extend(fresh.iter().filter(|l| !upgraded.contains(l)).map(|l| format!("  only in fresh:    {}", l)));
+    diff.extend(upgraded.iter().filter(|l| !fresh.contains(l)).map(|l| format!("  only in upgraded: {}", l)));
+    Err(DbError::Migration(format!(
+        "fresh and upgraded schemas differ:\n{}",
+        diff.join("\n")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_strictly_increasing() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version, "v{} before v{}", pair[0].version, pair[1].version);
+        }
+    }
+
+    #[test]
+    fn check_migrations_fresh_and_upgraded_schemas_match() {
+        if let Err(e) = check_migrations() {
+            panic!("{}", e);
+        }
+    }
+
+    #[test]
+    fn legacy_database_is_adopted_and_upgraded() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(LEGACY_V13_FIXTURE).unwrap();
+        run(&mut conn, None).unwrap();
+
+        let applied = applied_migrations(&conn).unwrap();
+        assert_eq!(applied.keys().next_back(), Some(&latest_version()));
+        assert_eq!(applied.len(), MIGRATIONS.len());
+
+        let legacy_table: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'schema_version'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(legacy_table, 0);
+
+        // Data survives the table rebuilds and the v14 role backfill
+        let (title, epic_id): (String, Option<String>) = conn.query_row(
+            "SELECT title, epic_id FROM tickets WHERE id = 't2'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap();
+        assert_eq!(title, "Shipped");
+        assert_eq!(epic_id.as_deref(), Some("t1"));
+        let role: Option<String> = conn.query_row(
+            "SELECT role FROM columns WHERE id = 'c6'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(role.as_deref(), Some("done"));
+        let comments: i32 = conn.query_row("SELECT COUNT(*) FROM comments", [], |row| row.get(0)).unwrap();
+        assert_eq!(comments, 1);
+    }
+
+    #[test]
+    fn changed_migration_is_rejected() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, None).unwrap();
+        conn.execute("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 8", []).unwrap();
+
+        let err = run(&mut conn, None).unwrap_err();
+        assert!(matches!(err, DbError::Migration(ref msg) if msg.contains("v8")), "{}", err);
+    }
+
+    #[test]
+    fn unknown_applied_migration_is_rejected() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, None).unwrap();
+        conn.execute(
+            "INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES (999, 'future', 'x', 'now')",
+            [],
+        ).unwrap();
+
+        let err = run(&mut conn, None).unwrap_err();
+        assert!(matches!(err, DbError::Migration(ref msg) if msg.contains("v999")), "{}", err);
+    }
+
+    #[test]
+    fn failing_migration_rolls_back_and_reports() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(CREATE_MIGRATIONS_TABLE).unwrap();
+        let broken = Migration {
+            version: 1,
+            name: "broken",
+            add_columns: &[],
+            backfill: None,
+            sql: "CREATE TABLE a (id TEXT); CREATE TABLE a (id TEXT);",
+        };
+
+        let err = apply(&mut conn, &broken).unwrap_err();
+        assert!(matches!(err, DbError::Migration(ref msg) if msg.contains("v1 (broken)")), "{}", err);
+        assert!(applied_migrations(&conn).unwrap().is_empty());
+        let tables: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'a'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(tables, 0);
+    }
+
+    #[test]
+    fn existing_file_is_backed_up_before_upgrade() {
+        let dir = std::env::temp_dir().join(format!("agent-kanban-migrate-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("agent-kanban.db");
+
+        let mut conn = Connection::open(&path).unwrap();
+        conn.execute_batch(LEGACY_V13_FIXTURE).unwrap();
+        run(&mut conn, Some(&path)).unwrap();
+
+        let backups: Vec<_> = std::fs::read_dir(dir.join("backups")).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        let name = backups[0].file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("agent-kanban-pre-migration-v13-"), "{}", name);
+
+        let backup = Connection::open(&backups[0]).unwrap();
+        let version: i32 = backup.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, 13);
+
+        // Nothing pending, no second backup
+        run(&mut conn, Some(&path)).unwrap();
+        assert_eq!(std::fs::read_dir(dir.join("backups")).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -8,14 +8,29 @@ mod events;
 mod comments;
 pub mod tasks;
 mod scratchpads;
-
-use std::path::PathBuf;
+mod metrics;
+pub mod history;
+pub mod migrations;
+pub mod bundle;
+pub mod backup;
+pub mod retention;
+pub mod trash;
+pub mod custom_fields;
+pub mod labels;
+pub mod workers;
+pub mod remote;
+pub mod scheduling;
+pub mod settings;
+pub mod checkpoints;
+pub mod pool;
+
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use rusqlite::Connection;
+use pool::ReaderPool;
 use thiserror::Error;
 
 pub use models::*;
-use schema::{CREATE_TABLES, SCHEMA_VERSION};
 
 #[derive(Error, Debug)]
 pub enum DbError {
@@ -30,15 +45,38 @@ pub enum DbError {
     
     #[error("Validation error: {0}")]
     Validation(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Migration error: {0}")]
+    Migration(String),
 }
 
+/// Read-only connections opened next to the writer by `Database::open`
+pub const DEFAULT_READERS: usize = 4;
+
+/// Handle to the database: one writer connection plus a pool of read-only
+/// connections. Cloning is cheap and shares both.
 #[derive(Clone)]
 pub struct Database {
+    /// The writer; every statement that modifies data runs here
     conn: Arc<Mutex<Connection>>,
+    /// `None` for in-memory databases, which readers cannot share, and when
+    /// opened without readers; reads then use the writer
+    readers: Option<Arc<ReaderPool>>,
+    /// File the database lives in; `None` for in-memory databases
+    path: Option<PathBuf>,
 }
 
 impl Database {
     pub fn open(db_path: PathBuf) -> Result<Self, DbError> {
+        Self::open_with_readers(db_path, DEFAULT_READERS)
+    }
+
+    /// Open with `readers` read-only connections; 0 sends every query
+    /// through the writer
+    pub fn open_with_readers(db_path: PathBuf, readers: usize) -> Result<Self, DbError> {
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)
                 .map_err(|_e| DbError::Validation(format!("Failed to create directory: {:?}", parent)))?;
@@ -46,14 +84,21 @@ impl Database {
 
         let conn = Connection::open(&db_path)?;
         conn.execute("PRAGMA foreign_keys = ON", [])?;
+        conn.busy_timeout(pool::BUSY_TIMEOUT)?;
         let _: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
         
-        let db = Self {
+        let mut db = Self {
             conn: Arc::new(Mutex::new(conn)),
+            readers: None,
+            path: Some(db_path.clone()),
         };
-        db.migrate()?;
+        db.migrate(Some(&db_path))?;
+        // Readers open after migrating so they never see a half-built schema
+        if readers > 0 {
+            db.readers = Some(Arc::new(ReaderPool::open(&db_path, readers)?));
+        }
         
-        tracing::info!("Database opened at {:?}", db_path);
+        tracing::info!("Database opened at {:?} ({} readers)", db_path, readers);
         Ok(db)
     }
 
@@ -63,329 +108,21 @@ impl Database {
         
         let db = Self {
             conn: Arc::new(Mutex::new(conn)),
+            readers: None,
+            path: None,
         };
         
-        db.migrate()?;
+        db.migrate(None)?;
         Ok(db)
     }
 
-    fn migrate(&self) -> Result<(), DbError> {
-        let conn = self.conn.lock()
+    fn migrate(&self, db_path: Option<&Path>) -> Result<(), DbError> {
+        let mut conn = self.conn.lock()
             .map_err(|e| DbError::Lock(e.to_string()))?;
-
-        let current_version: i32 = conn
-            .query_row(
-                "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
-
-        if current_version < SCHEMA_VERSION {
-            tracing::info!(
-                "Migrating database from version {} to {}",
-                current_version,
-                SCHEMA_VERSION
-            );
-
-            // For fresh databases (version 0), create all tables
-            if current_version == 0 {
-                conn.execute_batch(CREATE_TABLES)?;
-            }
-            
-            // Apply incremental migrations
-            if current_version < 3 && current_version > 0 {
-                tracing::info!("Applying migration v3: repo_locks and requires_git");
-                // Split migration to handle potential errors gracefully
-                // Add requires_git column if it doesn't exist
-                let _ = conn.execute(
-                    "ALTER TABLE projects ADD COLUMN requires_git INTEGER NOT NULL DEFAULT 1",
-                    [],
-                );
-                // Create repo_locks table
-                conn.execute_batch(
-                    r#"
-                    CREATE TABLE IF NOT EXISTS repo_locks (
-                        project_id TEXT PRIMARY KEY NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                        locked_by_run_id TEXT NOT NULL,
-                        lock_expires_at TEXT NOT NULL,
-                        locked_at TEXT NOT NULL
-                    );
-                    CREATE INDEX IF NOT EXISTS idx_repo_locks_expires ON repo_locks(lock_expires_at);
-                    "#
-                )?;
-            }
-            
-            if current_version < 4 && current_version > 0 {
-                tracing::info!("Applying migration v4: workflow_type, parent_run_id, stage");
-                // Add columns one at a time to handle potential errors gracefully
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN workflow_type TEXT NOT NULL DEFAULT 'basic'",
-                    [],
-                );
-                let _ = conn.execute(
-                    "ALTER TABLE agent_runs ADD COLUMN parent_run_id TEXT REFERENCES agent_runs(id) ON DELETE CASCADE",
-                    [],
-                );
-                let _ = conn.execute(
-                    "ALTER TABLE agent_runs ADD COLUMN stage TEXT",
-                    [],
-                );
-                let _ = conn.execute(
-                    "CREATE INDEX IF NOT EXISTS idx_runs_parent ON agent_runs(parent_run_id) WHERE parent_run_id IS NOT NULL",
-                    [],
-                );
-            }
-            
-            if current_version < 5 && current_version > 0 {
-                tracing::info!("Applying migration v5: model column for tickets");
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN model TEXT",
-                    [],
-                );
-            }
-            
-            if current_version < 6 && current_version > 0 {
-                tracing::info!("Applying migration v6: convert basic workflow to multi_stage");
-                let _ = conn.execute(
-                    "UPDATE tickets SET workflow_type = 'multi_stage' WHERE workflow_type = 'basic'",
-                    [],
-                );
-            }
-            
-            if current_version < 7 && current_version > 0 {
-                tracing::info!("Applying migration v7: branch_name column for tickets");
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN branch_name TEXT",
-                    [],
-                );
-            }
-            
-            if current_version < 8 && current_version > 0 {
-                tracing::info!("Applying migration v8: tasks table for task queue system");
-                conn.execute_batch(schema::MIGRATION_V8)?;
-                
-                // Create Task 1 for existing tickets based on column status
-                // 
-                // Note on UTF-8 handling: SQLite's length() and substr() functions count
-                // Unicode code points (not bytes) for TEXT columns, which is consistent with
-                // Rust's chars().count() and chars().take() used in create_ticket().
-                // Both correctly handle multi-byte UTF-8 characters like emoji.
-                tracing::info!("Creating initial tasks for existing tickets...");
-                let task_count = conn.execute(
-                    r#"INSERT INTO tasks (id, ticket_id, order_index, task_type, title, content, status, created_at)
-                       SELECT 
-                           lower(hex(randomblob(16))),
-                           t.id,
-                           0,
-                           'custom',
-                           CASE 
-                               WHEN length(t.title) > 50 THEN substr(t.title, 1, 47) || '...'
-                               ELSE t.title
-                           END,
-                           CASE WHEN t.description_md = '' THEN NULL ELSE t.description_md END,
-                           CASE 
-                               WHEN c.name IN ('Done', 'Review') THEN 'completed'
-                               WHEN c.name = 'In Progress' THEN 'in_progress'
-                               ELSE 'pending'
-                           END,
-                           t.created_at
-                       FROM tickets t
-                       JOIN columns c ON t.column_id = c.id
-                       WHERE NOT EXISTS (SELECT 1 FROM tasks WHERE ticket_id = t.id)"#,
-                    [],
-                )?;
-                tracing::info!("Created {} initial tasks for existing tickets", task_count);
-            }
-            
-            if current_version < 9 && current_version > 0 {
-                tracing::info!("Applying migration v9: epic support columns");
-                // Add columns one at a time to handle potential errors gracefully
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN is_epic INTEGER NOT NULL DEFAULT 0",
-                    [],
-                );
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL",
-                    [],
-                );
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN order_in_epic INTEGER",
-                    [],
-                );
-                let _ = conn.execute(
-                    "CREATE INDEX IF NOT EXISTS idx_tickets_epic ON tickets(epic_id, order_in_epic) WHERE epic_id IS NOT NULL",
-                    [],
-                );
-            }
-            
-            if current_version < 10 && current_version > 0 {
-                tracing::info!("Applying migration v10: scratchpads table and epic dependencies");
-                // Create scratchpads table first (tickets references it)
-                conn.execute_batch(
-                    r#"
-                    CREATE TABLE IF NOT EXISTS scratchpads (
-                        id TEXT PRIMARY KEY NOT NULL,
-                        board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-                        project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                        name TEXT NOT NULL,
-                        user_input TEXT NOT NULL,
-                        status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-                        agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-                        model TEXT,
-                        exploration_log TEXT,
-                        plan_markdown TEXT,
-                        plan_json TEXT,
-                        settings_json TEXT NOT NULL DEFAULT '{}',
-                        created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                        updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                    );
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
-                    "#
-                )?;
-                
-                // Add new columns to tickets
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN depends_on_epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL",
-                    [],
-                );
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN scratchpad_id TEXT REFERENCES scratchpads(id) ON DELETE SET NULL",
-                    [],
-                );
-                let _ = conn.execute(
-                    "CREATE INDEX IF NOT EXISTS idx_tickets_depends_on ON tickets(depends_on_epic_id) WHERE depends_on_epic_id IS NOT NULL",
-                    [],
-                );
-                let _ = conn.execute(
-                    "CREATE INDEX IF NOT EXISTS idx_tickets_scratchpad ON tickets(scratchpad_id) WHERE scratchpad_id IS NOT NULL",
-                    [],
-                );
-            }
-            
-            if current_version < 11 && current_version > 0 {
-                tracing::info!("Applying migration v11: target_board_id for scratchpads, new status values");
-                // Add target_board_id column
-                let _ = conn.execute(
-                    "ALTER TABLE scratchpads ADD COLUMN target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL",
-                    [],
-                );
-                let _ = conn.execute(
-                    "CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id)",
-                    [],
-                );
-            }
-            
-            if current_version < 12 && current_version > 0 {
-                tracing::info!("Applying migration v12: fix scratchpads status CHECK constraint");
-                // SQLite doesn't support ALTER TABLE to change CHECK constraints,
-                // so we need to recreate the table with the correct constraint
-                
-                // First check if target_board_id column exists (added in v11)
-                let has_target_board: bool = conn
-                    .query_row(
-                        "SELECT COUNT(*) FROM pragma_table_info('scratchpads') WHERE name = 'target_board_id'",
-                        [],
-                        |row| row.get::<_, i32>(0),
-                    )
-                    .unwrap_or(0) > 0;
-                
-                if has_target_board {
-                    // Table has target_board_id, use full column list
-                    conn.execute_batch(
-                        r#"
-                        CREATE TABLE scratchpads_new (
-                            id TEXT PRIMARY KEY NOT NULL,
-                            board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-                            target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
-                            project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                            name TEXT NOT NULL,
-                            user_input TEXT NOT NULL,
-                            status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-                            agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-                            model TEXT,
-                            exploration_log TEXT,
-                            plan_markdown TEXT,
-                            plan_json TEXT,
-                            settings_json TEXT NOT NULL DEFAULT '{}',
-                            created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                        );
-
-                        INSERT INTO scratchpads_new (id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at)
-                        SELECT id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at FROM scratchpads;
-
-                        DROP TABLE scratchpads;
-                        ALTER TABLE scratchpads_new RENAME TO scratchpads;
-
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
-                        "#
-                    )?;
-                } else {
-                    // Table doesn't have target_board_id yet, add it during migration
-                    conn.execute_batch(
-                        r#"
-                        CREATE TABLE scratchpads_new (
-                            id TEXT PRIMARY KEY NOT NULL,
-                            board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-                            target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
-                            project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                            name TEXT NOT NULL,
-                            user_input TEXT NOT NULL,
-                            status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-                            agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-                            model TEXT,
-                            exploration_log TEXT,
-                            plan_markdown TEXT,
-                            plan_json TEXT,
-                            settings_json TEXT NOT NULL DEFAULT '{}',
-                            created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                        );
-
-                        INSERT INTO scratchpads_new (id, board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at)
-                        SELECT id, board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at FROM scratchpads;
-
-                        DROP TABLE scratchpads;
-                        ALTER TABLE scratchpads_new RENAME TO scratchpads;
-
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-                        CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
-                        "#
-                    )?;
-                }
-                
-                tracing::info!("Migration v12 completed successfully");
-            }
-            
-            if current_version < 13 && current_version > 0 {
-                tracing::info!("Applying migration v13: depends_on_epic_ids_json column");
-                // Add depends_on_epic_ids_json column to store all epic dependencies as JSON array
-                let _ = conn.execute(
-                    "ALTER TABLE tickets ADD COLUMN depends_on_epic_ids_json TEXT",
-                    [],
-                );
-                tracing::info!("Migration v13 completed successfully");
-            }
-            
-            conn.execute(
-                "INSERT OR REPLACE INTO schema_version (version) VALUES (?)",
-                [SCHEMA_VERSION],
-            )?;
-
-            tracing::info!("Database migration complete");
-        }
-
-        Ok(())
+        migrations::run(&mut conn, db_path)
     }
 
+    /// Run `f` on the writer connection
     pub fn with_conn<F, T>(&self, f: F) -> Result<T, DbError>
     where
         F: FnOnce(&Connection) -> Result<T, DbError>,
@@ -395,6 +132,31 @@ impl Database {
         f(&conn)
     }
 
+    /// Run a read-only `f` on a pooled reader, so it neither waits for nor
+    /// holds up writes. Readers see everything committed before `f` starts.
+    pub fn with_reader<F, T>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(&Connection) -> Result<T, DbError>,
+    {
+        match &self.readers {
+            Some(readers) => readers.with(f),
+            None => self.with_conn(f),
+        }
+    }
+
+    /// Run blocking database work from async code on the blocking thread
+    /// pool instead of stalling the runtime's worker threads
+    pub async fn blocking<F, T>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(&Database) -> Result<T, DbError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.clone();
+        tokio::task::spawn_blocking(move || f(&db))
+            .await
+            .map_err(|e| DbError::Lock(format!("database task failed: {}", e)))?
+    }
+
     pub fn with_conn_mut<F, T>(&self, f: F) -> Result<T, DbError>
     where
         F: FnOnce(&mut Connection) -> Result<T, DbError>,
@@ -405,6 +167,35 @@ impl Database {
     }
 }
 
+/// Give existing columns the role their (default) name implies.
+///
+/// Only the first column per board (by position) gets a given role, so
+/// boards with duplicate names still satisfy the unique role index.
+fn backfill_column_roles(conn: &Connection) -> Result<(), DbError> {
+    let columns: Vec<(String, String, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, board_id, name FROM columns WHERE role IS NULL ORDER BY board_id, position"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let mut taken = std::collections::HashSet::new();
+    for (id, board_id, name) in columns {
+        let Some(role) = crate::lifecycle::TicketState::from_column_name(&name) else {
+            continue;
+        };
+        if taken.insert((board_id, role)) {
+            conn.execute(
+                "UPDATE columns SET role = ? WHERE id = ?",
+                rusqlite::params![role.as_str(), id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn parse_datetime(s: String) -> chrono::DateTime<chrono::Utc> {
     chrono::DateTime::parse_from_rfc3339(&s)
         .map(|dt| dt.with_timezone(&chrono::Utc))
@@ -528,7 +319,7 @@ impl Database {
     }
 
     pub fn get_run_artifacts(&self, run_id: &str) -> Result<Option<RunArtifacts>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let metadata: Option<String> = conn.query_row(
                 "SELECT metadata_json FROM agent_runs WHERE id = ?",
                 [run_id],
@@ -539,139 +330,48 @@ impl Database {
     }
 
     pub fn release_lock(&self, ticket_id: &str, run_id: &str) -> Result<(), DbError> {
-        self.with_conn(|conn| {
-            conn.execute(
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let released = tx.execute(
                 "UPDATE tickets SET locked_by_run_id = NULL, lock_expires_at = NULL 
                  WHERE id = ? AND locked_by_run_id = ?",
                 rusqlite::params![ticket_id, run_id],
             )?;
-            Ok(())
-        })
-    }
-
-    /// Repair the scratchpads table CHECK constraint.
-    /// This recreates the table with the correct constraint including 'executed' and 'working' status values.
-    pub fn repair_scratchpads_constraint(&self) -> Result<String, DbError> {
-        self.with_conn(|conn| {
-            tracing::warn!("Repairing scratchpads table CHECK constraint");
-            
-            // Check if the table exists
-            let table_exists: bool = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='scratchpads'",
-                    [],
-                    |row| row.get::<_, i32>(0),
-                )
-                .unwrap_or(0) > 0;
-            
-            if !table_exists {
-                return Ok("Scratchpads table does not exist, nothing to repair".to_string());
-            }
-            
-            // Get current row count
-            let row_count: i32 = conn
-                .query_row("SELECT COUNT(*) FROM scratchpads", [], |row| row.get(0))
-                .unwrap_or(0);
-            
-            // Check if target_board_id column exists
-            let has_target_board: bool = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM pragma_table_info('scratchpads') WHERE name = 'target_board_id'",
-                    [],
-                    |row| row.get::<_, i32>(0),
-                )
-                .unwrap_or(0) > 0;
-            
-            tracing::info!("Scratchpads table has {} rows, target_board_id={}", row_count, has_target_board);
-            
-            // Recreate the table with the correct constraint
-            if has_target_board {
-                conn.execute_batch(
-                    r#"
-                    CREATE TABLE scratchpads_repaired (
-                        id TEXT PRIMARY KEY NOT NULL,
-                        board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-                        target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
-                        project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                        name TEXT NOT NULL,
-                        user_input TEXT NOT NULL,
-                        status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-                        agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-                        model TEXT,
-                        exploration_log TEXT,
-                        plan_markdown TEXT,
-                        plan_json TEXT,
-                        settings_json TEXT NOT NULL DEFAULT '{}',
-                        created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                        updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                    );
-
-                    INSERT INTO scratchpads_repaired (id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at)
-                    SELECT id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at FROM scratchpads;
-
-                    DROP TABLE scratchpads;
-                    ALTER TABLE scratchpads_repaired RENAME TO scratchpads;
-
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
-                    "#
-                )?;
-            } else {
-                conn.execute_batch(
-                    r#"
-                    CREATE TABLE scratchpads_repaired (
-                        id TEXT PRIMARY KEY NOT NULL,
-                        board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-                        target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
-                        project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                        name TEXT NOT NULL,
-                        user_input TEXT NOT NULL,
-                        status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-                        agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-                        model TEXT,
-                        exploration_log TEXT,
-                        plan_markdown TEXT,
-                        plan_json TEXT,
-                        settings_json TEXT NOT NULL DEFAULT '{}',
-                        created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                        updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                    );
-
-                    INSERT INTO scratchpads_repaired (id, board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at)
-                    SELECT id, board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at FROM scratchpads;
-
-                    DROP TABLE scratchpads;
-                    ALTER TABLE scratchpads_repaired RENAME TO scratchpads;
-
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-                    CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
-                    "#
+            if released > 0 {
+                history::record_change(
+                    &tx,
+                    ticket_id,
+                    history::LOCK_FIELD,
+                    Some(run_id),
+                    None,
+                    &models::Actor::run(run_id),
                 )?;
             }
-            
-            tracing::info!("Scratchpads table repaired successfully");
-            Ok(format!("Repaired scratchpads table with {} rows", row_count))
+            tx.commit()?;
+            Ok(())
         })
     }
 
     /// Factory reset: delete all user data from the database.
     /// This clears all boards, tickets, projects, runs, scratchpads, etc.
-    /// and also repairs the scratchpads table schema to ensure correct constraints.
     pub fn factory_reset(&self) -> Result<(), DbError> {
+        self.snapshot("factory-reset")?;
         self.with_conn(|conn| {
             tracing::warn!("Factory reset: deleting all user data from database");
             
             // Delete in dependency order to respect foreign key constraints
             // First: tables with no dependents or only CASCADE dependents
             conn.execute("DELETE FROM agent_events", [])?;
+            conn.execute("DELETE FROM run_artifacts", [])?;
+            conn.execute("DELETE FROM ticket_leases", [])?;
             conn.execute("DELETE FROM comments", [])?;
             conn.execute("DELETE FROM tasks", [])?;
+            conn.execute("DELETE FROM ticket_history", [])?;
             conn.execute("DELETE FROM agent_runs", [])?;
             conn.execute("DELETE FROM repo_locks", [])?;
+            conn.execute("DELETE FROM workers", [])?;
+            conn.execute("DELETE FROM remote_workers", [])?;
+            conn.execute("DELETE FROM settings", [])?;
             
             // Tickets must be deleted before scratchpads (scratchpad_id FK)
             // and before columns (column_id FK with RESTRICT)
@@ -690,40 +390,6 @@ impl Database {
             conn.execute("DELETE FROM projects", [])?;
             
             tracing::info!("Factory reset: all user data deleted");
-            
-            // Now recreate the scratchpads table with the correct schema
-            // This ensures the CHECK constraint is correct
-            tracing::info!("Factory reset: recreating scratchpads table with correct schema");
-            
-            // Drop and recreate scratchpads table with correct constraint
-            conn.execute("DROP TABLE IF EXISTS scratchpads", [])?;
-            conn.execute_batch(
-                r#"
-                CREATE TABLE scratchpads (
-                    id TEXT PRIMARY KEY NOT NULL,
-                    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-                    target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
-                    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-                    name TEXT NOT NULL,
-                    user_input TEXT NOT NULL,
-                    status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-                    agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-                    model TEXT,
-                    exploration_log TEXT,
-                    plan_markdown TEXT,
-                    plan_json TEXT,
-                    settings_json TEXT NOT NULL DEFAULT '{}',
-                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                );
-
-                CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-                CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
-                CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-                CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
-                "#
-            )?;
-            
             tracing::info!("Factory reset complete: database ready for fresh start");
             Ok(())
         })
@@ -936,4 +602,32 @@ mod tests {
             assert!(!acquired);
         }
     }
+
+    #[test]
+    fn backfill_column_roles_from_names() {
+        use crate::lifecycle::TicketState;
+
+        let db = create_test_db();
+        let board = db.create_board("Legacy").unwrap();
+        db.with_conn(|conn| {
+            conn.execute("UPDATE columns SET role = NULL", [])?;
+            conn.execute(
+                "INSERT INTO columns (id, board_id, name, position) VALUES ('dup', ?, 'done', 6)",
+                [&board.id],
+            )?;
+            conn.execute(
+                "INSERT INTO columns (id, board_id, name, position) VALUES ('qa', ?, 'QA', 7)",
+                [&board.id],
+            )?;
+            backfill_column_roles(conn)
+        }).unwrap();
+
+        let columns = db.get_columns(&board.id).unwrap();
+        assert_eq!(columns[1].role, Some(TicketState::Ready));
+        assert_eq!(columns[2].role, Some(TicketState::InProgress));
+        assert_eq!(columns[5].role, Some(TicketState::Done));
+        // Only the first "Done" keeps the role; unknown names stay custom
+        assert_eq!(columns[6].role, None);
+        assert_eq!(columns[7].role, None);
+    }
 }
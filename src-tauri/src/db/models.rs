@@ -1,6 +1,12 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::agents::AgentKind;
+use crate::db::DbError;
+use crate::lifecycle::TicketState;
+
 /// Workflow type for ticket execution
 /// Note: Basic workflow has been removed - all tickets now use MultiStage
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -42,10 +48,40 @@ pub struct Project {
     /// Whether this project requires git for agent operations.
     /// When false, workers will skip git validation and git-related workflow steps.
     pub requires_git: bool,
+    /// Time limits by workflow stage name, replacing the defaults of those stages
+    #[serde(default)]
+    pub stage_timeouts: BTreeMap<String, StageTimeout>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// The stages in a multi-stage workflow
+pub const MULTI_STAGE_WORKFLOW: &[&str] = &[
+    "branch",
+    "plan", 
+    "implement",
+    "deslop",
+    "cleanup",
+    "unit-tests",
+    "cleanup",
+    "review-changes",
+    "cleanup",
+    "review-changes",
+    "add-and-commit",
+];
+
+/// Stage name recorded for an inserted stage that was not given one
+pub const ADHOC_STAGE_NAME: &str = "custom";
+
+/// Time limits of a workflow stage. At `soft_secs` the agent is interrupted
+/// so it stops with its partial work; at `hard_secs` it is killed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTimeout {
+    pub soft_secs: u64,
+    pub hard_secs: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateProject {
@@ -70,6 +106,8 @@ pub struct UpdateProject {
     pub allow_file_writes: Option<bool>,
     pub blocked_patterns: Option<Vec<String>>,
     pub requires_git: Option<bool>,
+    /// Replaces all stage time limits of the project
+    pub stage_timeouts: Option<BTreeMap<String, StageTimeout>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +128,180 @@ pub struct Column {
     pub name: String,
     pub position: i32,
     pub wip_limit: Option<i32>,
+    /// Lifecycle state this column represents; `None` for custom columns
+    /// that workers and the epic logic ignore
+    #[serde(default)]
+    pub role: Option<TicketState>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateColumn {
+    pub name: String,
+    #[serde(default)]
+    pub role: Option<TicketState>,
+    /// Insert at this position (shifting later columns right); appends when unset
+    #[serde(default)]
+    pub position: Option<i32>,
+    #[serde(default)]
+    pub wip_limit: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateColumn {
+    pub name: Option<String>,
+    /// `Some(None)` (`null` in JSON) makes it a custom column
+    #[serde(default, deserialize_with = "deserialize_role_change", skip_serializing_if = "Option::is_none")]
+    pub role: Option<Option<TicketState>>,
+    /// `Some(0)` clears the limit
+    pub wip_limit: Option<i32>,
+}
+
+/// Keep an explicit `null` role apart from a missing one, which leaves the role as is
+fn deserialize_role_change<'de, D>(deserializer: D) -> Result<Option<Option<TicketState>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Deserialize;
+
+    Option::<TicketState>::deserialize(deserializer).map(Some)
+}
+
+/// Value type of a board's custom ticket field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    /// Calendar date, `YYYY-MM-DD`
+    Date,
+    /// One of the field's `options`
+    Enum,
+    /// Free-form user name or id
+    User,
+}
+
+impl CustomFieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Enum => "enum",
+            CustomFieldType::User => "user",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(CustomFieldType::Text),
+            "number" => Some(CustomFieldType::Number),
+            "date" => Some(CustomFieldType::Date),
+            "enum" => Some(CustomFieldType::Enum),
+            "user" => Some(CustomFieldType::User),
+            _ => None,
+        }
+    }
+}
+
+/// Custom field values of a ticket, keyed by field key
+pub type CustomFieldValues = BTreeMap<String, serde_json::Value>;
+
+/// A custom ticket field defined on a board
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomField {
+    pub id: String,
+    pub board_id: String,
+    /// Identifier used in ticket values, filters and prompt variables
+    pub key: String,
+    /// Display name
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// Allowed values of an enum field
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// New tickets must set this field
+    #[serde(default)]
+    pub required: bool,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCustomField {
+    pub key: String,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCustomField {
+    pub name: Option<String>,
+    /// Replaces the options of an enum field
+    pub options: Option<Vec<String>>,
+    pub required: Option<bool>,
+}
+
+/// A label in a board's catalog. Ticket labels refer to it by name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    pub id: String,
+    pub board_id: String,
+    /// Unique per board, ignoring case
+    pub name: String,
+    /// `#rrggbb`
+    pub color: String,
+    #[serde(default)]
+    pub description: String,
+    /// Tickets of the board carrying this label
+    #[serde(default)]
+    pub ticket_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLabel {
+    pub name: String,
+    /// Defaults to grey
+    pub color: Option<String>,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLabel {
+    /// Renames the label on every ticket of the board
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Labels a worker requires or refuses when reserving tickets. Names are
+/// compared ignoring case.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LabelFilter {
+    /// Only reserve tickets with at least one of these labels
+    pub include: Vec<String>,
+    /// Never reserve tickets with any of these labels
+    pub exclude: Vec<String>,
+}
+
+impl LabelFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -184,6 +396,9 @@ pub struct Ticket {
     pub depends_on_epic_ids: Vec<String>,
     /// Link back to scratchpad that created this ticket
     pub scratchpad_id: Option<String>,
+    /// Values of the board's custom fields
+    #[serde(default)]
+    pub custom_fields: CustomFieldValues,
 }
 
 impl Ticket {
@@ -389,6 +604,35 @@ pub struct CreateTicket {
     pub depends_on_epic_ids: Vec<String>,
     /// Link back to scratchpad that created this ticket
     pub scratchpad_id: Option<String>,
+    /// Values of the board's custom fields
+    #[serde(default)]
+    pub custom_fields: CustomFieldValues,
+}
+
+#[cfg(test)]
+impl CreateTicket {
+    /// A plain medium-priority ticket; tests set anything else with `..`
+    pub fn for_test(board_id: &str, column_id: &str, title: &str) -> Self {
+        Self {
+            board_id: board_id.to_string(),
+            column_id: column_id.to_string(),
+            title: title.to_string(),
+            description_md: String::new(),
+            priority: Priority::Medium,
+            labels: vec![],
+            project_id: None,
+            agent_pref: None,
+            workflow_type: WorkflowType::default(),
+            model: None,
+            branch_name: None,
+            is_epic: false,
+            epic_id: None,
+            depends_on_epic_id: None,
+            depends_on_epic_ids: vec![],
+            scratchpad_id: None,
+            custom_fields: Default::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -440,6 +684,10 @@ pub struct UpdateTicket {
     pub depends_on_epic_ids: Vec<String>,
     /// Set or clear the scratchpad_id
     pub scratchpad_id: Option<String>,
+    /// Custom field values to set; a `null` value clears that field and
+    /// fields not listed keep their value
+    #[serde(default)]
+    pub custom_fields: Option<CustomFieldValues>,
 }
 
 /// Progress information for an epic's children
@@ -462,6 +710,48 @@ pub struct EpicProgress {
     pub done: i32,
 }
 
+/// Ticket counts for a single board column (used by the metrics endpoint)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnTicketCount {
+    pub board_id: String,
+    pub board_name: String,
+    pub column_id: String,
+    pub column_name: String,
+    #[serde(default)]
+    pub column_role: Option<TicketState>,
+    /// All tickets in the column, including epics and locked tickets
+    pub total: i64,
+    /// Non-epic tickets without an active lock (waiting to be picked up)
+    pub waiting: i64,
+    /// Tickets held by an active (non-expired) lock
+    pub locked: i64,
+}
+
+/// Aggregated run counts and durations for one agent/stage/status combination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStats {
+    pub agent_type: String,
+    /// Workflow stage for sub-runs, None for parent runs
+    pub stage: Option<String>,
+    pub status: String,
+    pub count: i64,
+    /// Number of runs that have ended (and therefore have a duration)
+    pub ended_count: i64,
+    /// Sum of the durations of ended runs, in seconds
+    pub duration_secs_sum: f64,
+}
+
+/// Number of runs of one agent and stage that hit their time limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTimeoutCount {
+    pub agent_type: String,
+    pub stage: String,
+    pub count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ReadinessCheck {
@@ -817,6 +1107,9 @@ pub struct ScratchpadEpicStatus {
     pub id: String,
     pub title: String,
     pub column: String,
+    /// Role of the epic's column; progress is counted by role, not name
+    #[serde(default)]
+    pub column_role: Option<TicketState>,
     /// The epics this one depends on (empty = independent/root epic)
     pub depends_on_ids: Vec<String>,
     /// Titles of the dependency epics (for display, in same order as depends_on_ids)
@@ -843,6 +1136,582 @@ pub struct ScratchpadProgress {
     pub epics: Vec<ScratchpadEpicStatus>,
 }
 
+/// Caps on concurrent agent processes; a missing entry means no limit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConcurrencyLimits {
+    /// Agent processes of any kind on this machine
+    #[serde(default)]
+    pub max_total: Option<u32>,
+    /// Keyed by agent kind (`claude`, `cursor`)
+    #[serde(default)]
+    pub per_agent: BTreeMap<String, u32>,
+    /// Keyed by the model passed to the agent, e.g. `opus-4.5`
+    #[serde(default)]
+    pub per_model: BTreeMap<String, u32>,
+}
+
+impl ConcurrencyLimits {
+    pub fn validate(&self) -> Result<(), DbError> {
+        if let Some(kind) = self.per_agent.keys().find(|k| AgentKind::parse(k).is_none()) {
+            return Err(DbError::Validation(format!("Unknown agent kind '{}'", kind)));
+        }
+        let mut limits = self.max_total.iter().chain(self.per_agent.values()).chain(self.per_model.values());
+        if limits.any(|&limit| limit == 0) {
+            return Err(DbError::Validation("Concurrency limits must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// What happens to workflows interrupted by a restart
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverySettings {
+    /// Resume interrupted workflows at launch instead of waiting for the user
+    #[serde(default)]
+    pub auto_resume: bool,
+}
+
+/// Who made a change recorded in the ticket history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActorType {
+    /// Someone using the desktop app, REST API or CLI
+    User,
+    /// An agent run (`actor_id` is the run id)
+    Agent,
+    /// Lifecycle automation (epics, lock cleanup, ...)
+    System,
+    /// A queue worker (`actor_id` is the worker id)
+    Worker,
+}
+
+impl ActorType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActorType::User => "user",
+            ActorType::Agent => "agent",
+            ActorType::System => "system",
+            ActorType::Worker => "worker",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "user" => Some(ActorType::User),
+            "agent" => Some(ActorType::Agent),
+            "system" => Some(ActorType::System),
+            "worker" => Some(ActorType::Worker),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Actor {
+    pub actor_type: ActorType,
+    pub actor_id: Option<String>,
+}
+
+impl Actor {
+    pub fn user() -> Self {
+        Self { actor_type: ActorType::User, actor_id: None }
+    }
+
+    pub fn system() -> Self {
+        Self { actor_type: ActorType::System, actor_id: None }
+    }
+
+    pub fn run(run_id: &str) -> Self {
+        Self { actor_type: ActorType::Agent, actor_id: Some(run_id.to_string()) }
+    }
+
+    pub fn worker(worker_id: &str) -> Self {
+        Self { actor_type: ActorType::Worker, actor_id: Some(worker_id.to_string()) }
+    }
+}
+
+/// One recorded change to a ticket field.
+///
+/// Moves are recorded as `columnId` changes and locks as `lock` changes whose
+/// values are the run ids holding the lock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketHistoryEntry {
+    pub id: String,
+    pub ticket_id: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub actor: Actor,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Version of the board bundle format written by `export_board`.
+pub const BOARD_BUNDLE_VERSION: u32 = 1;
+
+/// A board with everything hanging off it, as a portable JSON document.
+///
+/// Ids are the exporting database's; `import_board` gives every record a new
+/// id, so a bundle can be imported next to the board it came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub board: Board,
+    pub columns: Vec<Column>,
+    /// Custom field definitions; ticket values live on the tickets
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    /// Label catalog with colors and descriptions
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// Ordered by creation; epic order and dependencies live on the tickets.
+    /// Includes archived tickets, which are listed in `archived`
+    pub tickets: Vec<Ticket>,
+    /// Tickets the retention policy archived, restored as archived on import
+    #[serde(default)]
+    pub archived: Vec<BundleArchiveEntry>,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub scratchpads: Vec<Scratchpad>,
+    /// Projects referenced by the board, matched by id or path on import
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    /// Only present when exported with runs
+    #[serde(default)]
+    pub runs: Vec<AgentRun>,
+    #[serde(default)]
+    pub events: Vec<AgentEvent>,
+}
+
+/// An archived ticket in a [`BoardBundle`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleArchiveEntry {
+    pub ticket_id: String,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// The board created by `import_board`, plus anything that could not be
+/// carried over (e.g. scratchpads whose project does not exist here).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardImport {
+    pub board: Board,
+    pub warnings: Vec<String>,
+}
+
+/// A database copy in the `backups` directory next to the database file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    /// Why it was taken: `scheduled`, `manual`, or `pre-<operation>` for
+    /// snapshots before deletes, restores and migrations
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// A Done ticket moved out of the board by the retention policy. It keeps
+/// its runs, comments and history and can be restored to its column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedTicket {
+    pub ticket: Ticket,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// What one pass of the retention policy removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    /// Runs whose events were replaced by a summary event
+    pub compacted_runs: usize,
+    /// Events removed by compaction
+    pub compacted_events: usize,
+    pub archived_tickets: usize,
+    /// Trash entries deleted for good
+    pub purged_trash: usize,
+    /// Events, history rows and task run references whose owner no longer exists
+    pub orphans_removed: usize,
+    pub vacuumed: bool,
+}
+
+/// Kind of record a trash entry holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashItemType {
+    /// A ticket; for epics, together with their children
+    Ticket,
+    /// A board with its columns, tickets and scratchpads
+    Board,
+    /// A scratchpad, optionally with the tickets its plan created
+    Scratchpad,
+}
+
+impl TrashItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrashItemType::Ticket => "ticket",
+            TrashItemType::Board => "board",
+            TrashItemType::Scratchpad => "scratchpad",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ticket" => Some(TrashItemType::Ticket),
+            "board" => Some(TrashItemType::Board),
+            "scratchpad" => Some(TrashItemType::Scratchpad),
+            _ => None,
+        }
+    }
+}
+
+/// One delete action, restorable until it is purged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub id: String,
+    pub item_type: TrashItemType,
+    pub item_id: String,
+    /// Ticket title, board or scratchpad name at the time of deletion
+    pub name: String,
+    /// Board the item belongs to (the board itself for boards)
+    pub board_id: Option<String>,
+    /// Tickets hidden by this delete, including the item itself
+    pub ticket_count: i64,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Local-time window on some weekdays; `end` before `start` runs past midnight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleWindow {
+    /// Days the window starts on; all days when empty
+    #[serde(default)]
+    pub days: Vec<chrono::Weekday>,
+    /// `HH:MM`
+    pub start: String,
+    /// `HH:MM`
+    pub end: String,
+}
+
+/// When a worker may take tickets
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerSchedule {
+    pub windows: Vec<ScheduleWindow>,
+}
+
+impl ScheduleWindow {
+    fn contains(&self, now: chrono::NaiveDateTime) -> bool {
+        use chrono::Datelike;
+
+        let parse = |time: &str| chrono::NaiveTime::parse_from_str(time, "%H:%M").ok();
+        let (Some(start), Some(end)) = (parse(&self.start), parse(&self.end)) else {
+            return false;
+        };
+        let on = |day: chrono::Weekday| self.days.is_empty() || self.days.contains(&day);
+        let (day, time) = (now.weekday(), now.time());
+        if start < end {
+            on(day) && start <= time && time < end
+        } else {
+            // Overnight: the early hours belong to the window opened the day before
+            (on(day) && time >= start) || (on(day.pred()) && time < end)
+        }
+    }
+}
+
+impl WorkerSchedule {
+    /// Whether the local time `now` falls inside one of the windows
+    pub fn is_open(&self, now: chrono::NaiveDateTime) -> bool {
+        self.windows.iter().any(|w| w.contains(now))
+    }
+}
+
+/// Poll interval of a worker that does not set one
+pub const DEFAULT_WORKER_POLL_INTERVAL_SECS: u64 = 10;
+/// Agent timeout of a worker that does not set one (1 hour)
+pub const DEFAULT_WORKER_AGENT_TIMEOUT_SECS: u64 = 3600;
+/// Ticket lock duration of a worker that does not set one
+pub const DEFAULT_WORKER_LOCK_DURATION_MINS: i64 = 30;
+
+/// A saved worker, started at launch while enabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerDefinition {
+    pub id: String,
+    pub name: String,
+    pub agent_type: AgentKind,
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub labels: LabelFilter,
+    pub poll_interval_secs: u64,
+    pub agent_timeout_secs: u64,
+    pub lock_duration_mins: i64,
+    pub schedule: Option<WorkerSchedule>,
+    pub enabled: bool,
+    /// Tickets worked on over the worker's lifetime
+    pub tickets_processed: u64,
+    /// Of those, tickets whose run failed
+    pub tickets_failed: u64,
+    pub last_ticket_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkerDefinition {
+    pub agent_type: AgentKind,
+    /// Defaults to the agent and project
+    pub name: Option<String>,
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub labels: LabelFilter,
+    pub poll_interval_secs: Option<u64>,
+    pub agent_timeout_secs: Option<u64>,
+    pub lock_duration_mins: Option<i64>,
+    pub schedule: Option<WorkerSchedule>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkerDefinition {
+    pub name: Option<String>,
+    /// `Some("")` clears the project filter
+    pub project_id: Option<String>,
+    pub labels: Option<LabelFilter>,
+    pub poll_interval_secs: Option<u64>,
+    pub agent_timeout_secs: Option<u64>,
+    pub lock_duration_mins: Option<i64>,
+    /// An empty schedule (no windows) removes it
+    pub schedule: Option<WorkerSchedule>,
+    pub enabled: Option<bool>,
+}
+
+/// A machine that takes tickets over the REST API (`agent-kanban-worker`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWorker {
+    pub id: String,
+    pub name: String,
+    pub hostname: Option<String>,
+    pub agent_kinds: Vec<AgentKind>,
+    /// Projects the machine has checkouts of
+    pub project_ids: Vec<String>,
+    #[serde(default)]
+    pub labels: LabelFilter,
+    pub version: Option<String>,
+    /// Seen (registered, polled or heartbeat) within the last two minutes
+    pub online: bool,
+    pub active_leases: i64,
+    pub tickets_processed: u64,
+    pub registered_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterRemoteWorker {
+    /// Id of an earlier registration to take over, e.g. after a restart
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    pub agent_kinds: Vec<AgentKind>,
+    pub project_ids: Vec<String>,
+    #[serde(default)]
+    pub labels: LabelFilter,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaseStatus {
+    Active,
+    Completed,
+    /// Timed out, or the ticket was leased to someone else
+    Expired,
+}
+
+impl LeaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaseStatus::Active => "active",
+            LeaseStatus::Completed => "completed",
+            LeaseStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "active" => Some(LeaseStatus::Active),
+            "completed" => Some(LeaseStatus::Completed),
+            "expired" => Some(LeaseStatus::Expired),
+            _ => None,
+        }
+    }
+}
+
+/// A remote worker's claim on a ticket. Heartbeats and results must quote
+/// the fencing token; they are refused once a newer lease exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketLease {
+    pub fencing_token: i64,
+    pub worker_id: String,
+    pub ticket_id: String,
+    pub run_id: String,
+    pub status: LeaseStatus,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// Everything a remote worker needs to work on a leased ticket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaseGrant {
+    pub lease: TicketLease,
+    pub ticket: Ticket,
+    pub run: AgentRun,
+    pub project: Option<Project>,
+}
+
+/// File produced by a run (test report, log, ...), stored as text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunArtifact {
+    pub id: String,
+    pub run_id: String,
+    pub name: String,
+    pub content_type: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRunArtifact {
+    pub name: String,
+    #[serde(default = "default_artifact_content_type")]
+    pub content_type: String,
+    pub content: String,
+}
+
+fn default_artifact_content_type() -> String {
+    "text/plain".to_string()
+}
+
+/// Outcome a remote worker reports for its lease
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaseResult {
+    /// `finished`, `error` or `aborted` (gives the ticket back to the queue)
+    pub status: RunStatus,
+    pub exit_code: Option<i32>,
+    pub summary: Option<String>,
+    /// Changes made on the ticket's branch, as a unified diff
+    #[serde(default)]
+    pub diff: Option<String>,
+    #[serde(default)]
+    pub artifacts: Vec<CreateRunArtifact>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointState {
+    /// The workflow is running in this process
+    Active,
+    /// Held before its next stage until resumed; still paused after a restart
+    Paused,
+    /// The app stopped mid-workflow; the worktree is kept for a resume
+    Interrupted,
+    Finished,
+    /// Failed, cancelled, or interrupted after its worktree was removed
+    Failed,
+}
+
+impl CheckpointState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckpointState::Active => "active",
+            CheckpointState::Paused => "paused",
+            CheckpointState::Interrupted => "interrupted",
+            CheckpointState::Finished => "finished",
+            CheckpointState::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "active" => Some(CheckpointState::Active),
+            "paused" => Some(CheckpointState::Paused),
+            "interrupted" => Some(CheckpointState::Interrupted),
+            "finished" => Some(CheckpointState::Finished),
+            "failed" => Some(CheckpointState::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Progress of a multi-stage workflow, saved after every completed stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowCheckpoint {
+    pub parent_run_id: String,
+    pub ticket_id: String,
+    pub task_id: Option<String>,
+    pub agent_type: AgentKind,
+    /// Directory the stages run in (the run's worktree)
+    pub worktree_path: String,
+    pub branch_name: Option<String>,
+    /// Index into `MULTI_STAGE_WORKFLOW` of the first stage not yet completed
+    pub next_stage: usize,
+    /// Name of that stage; `None` once every stage completed
+    pub next_stage_name: Option<String>,
+    /// Names of every stage in order, for choosing where to rerun from
+    pub stages: Vec<String>,
+    /// Output of the plan stage, reused by later stages on resume
+    pub plan_md: Option<String>,
+    pub state: CheckpointState,
+    /// The workflow pauses once its current stage completes
+    pub pause_requested: bool,
+    /// Last stage completed before pausing, while paused
+    pub paused_after: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCheckpoint {
+    pub parent_run_id: String,
+    pub ticket_id: String,
+    pub task_id: Option<String>,
+    pub agent_type: AgentKind,
+    pub worktree_path: String,
+    pub branch_name: Option<String>,
+    pub next_stage: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1024,6 +1893,10 @@ mod tests {
                 blocked_patterns: vec!["*.log".to_string()],
                 settings: serde_json::json!({}),
                 requires_git: true,
+                stage_timeouts: BTreeMap::from([(
+                    "implement".to_string(),
+                    StageTimeout { soft_secs: 1200, hard_secs: 1800 },
+                )]),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
@@ -1032,6 +1905,7 @@ mod tests {
             assert!(json.contains("\"allowFileWrites\":false"));
             assert!(json.contains("\"preferredAgent\":\"cursor\""));
             assert!(json.contains("\"requiresGit\":true"));
+            assert!(json.contains("\"stageTimeouts\":{\"implement\":{\"softSecs\":1200,\"hardSecs\":1800}}"));
         }
 
         #[test]
@@ -1090,6 +1964,7 @@ mod tests {
                 depends_on_epic_id: None,
                 depends_on_epic_ids: vec![],
                 scratchpad_id: None,
+                custom_fields: Default::default(),
             }
         }
 
@@ -0,0 +1,154 @@
+//! Read-only connections shared by `Database` readers.
+//!
+//! In WAL mode readers never block the writer or each other, so queries
+//! that only read are spread over a small pool of read-only connections
+//! while all writes go through the single writer connection.
+
+use std::path::Path;
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::db::DbError;
+
+/// How long a connection waits on a lock held by another connection
+/// (or another process) before failing with `SQLITE_BUSY`
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct ReaderPool {
+    idle: Mutex<Vec<Connection>>,
+    available: Condvar,
+}
+
+impl ReaderPool {
+    pub fn open(db_path: &Path, size: usize) -> Result<Self, DbError> {
+        let connections = (0..size)
+            .map(|_| {
+                let conn = Connection::open_with_flags(
+                    db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                conn.busy_timeout(BUSY_TIMEOUT)?;
+                Ok(conn)
+            })
+            .collect::<Result<Vec<_>, DbError>>()?;
+
+        Ok(Self {
+            idle: Mutex::new(connections),
+            available: Condvar::new(),
+        })
+    }
+
+    /// Run `f` on an idle connection, waiting for one if all are in use
+    pub fn with<F, T>(&self, f: F) -> Result<T, DbError>
+    where
+        F: FnOnce(&Connection) -> Result<T, DbError>,
+    {
+        // The idle list stays valid even if a holder of the lock panicked
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        let conn = loop {
+            if let Some(conn) = idle.pop() {
+                break conn;
+            }
+            idle = self.available.wait(idle).unwrap_or_else(PoisonError::into_inner);
+        };
+        drop(idle);
+
+        let checkout = Checkout { pool: self, conn: Some(conn) };
+        f(checkout.conn.as_ref().expect("checked out connection"))
+    }
+}
+
+/// A connection taken from the pool; it goes back when this is dropped, also
+/// when the caller panics
+struct Checkout<'a> {
+    pool: &'a ReaderPool,
+    conn: Option<Connection>,
+}
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.idle.lock().unwrap_or_else(PoisonError::into_inner).push(conn);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{Database, DbError};
+
+    fn temp_db(readers: usize) -> (tempfile::TempDir, Database) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::open_with_readers(dir.path().join("agent-kanban.db"), readers).unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn readers_see_committed_writes_and_reject_writes() {
+        let (_dir, db) = temp_db(2);
+        let board = db.create_board("Pooled").unwrap();
+        assert_eq!(db.get_board(&board.id).unwrap().unwrap().name, "Pooled");
+
+        let err = db.with_reader(|conn| {
+            conn.execute("DELETE FROM boards", [])?;
+            Ok(())
+        });
+        assert!(matches!(err, Err(DbError::Sqlite(_))));
+        assert_eq!(db.get_boards().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reads_do_not_wait_for_the_writer() {
+        let (_dir, db) = temp_db(2);
+        let board = db.create_board("Busy").unwrap();
+
+        let (held_tx, held_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let writer = {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                db.with_conn(|conn| {
+                    conn.execute_batch("BEGIN IMMEDIATE; UPDATE boards SET name = 'Renamed'")?;
+                    held_tx.send(()).unwrap();
+                    // Hold the writer mid-transaction until the reader is done
+                    done_rx.recv().unwrap();
+                    conn.execute_batch("COMMIT")?;
+                    Ok(())
+                })
+                .unwrap();
+            })
+        };
+
+        held_rx.recv().unwrap();
+        let name = db.get_board(&board.id).unwrap().unwrap().name;
+        done_tx.send(()).unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(name, "Busy");
+        assert_eq!(db.get_board(&board.id).unwrap().unwrap().name, "Renamed");
+    }
+
+    #[test]
+    fn connections_return_to_the_pool_when_a_reader_panics() {
+        let (_dir, db) = temp_db(1);
+        db.create_board("Survivor").unwrap();
+
+        for _ in 0..2 {
+            let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let _ = db.with_reader(|_| -> Result<(), DbError> { panic!("reader failed") });
+            }));
+            assert!(panicked.is_err());
+        }
+        assert_eq!(db.get_boards().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn without_readers_queries_use_the_writer() {
+        let (_dir, db) = temp_db(0);
+        db.create_board("Solo").unwrap();
+        assert_eq!(db.get_boards().unwrap().len(), 1);
+    }
+}
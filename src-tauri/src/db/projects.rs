@@ -1,8 +1,32 @@
+use std::collections::BTreeMap;
+
 use crate::db::{Database, DbError, parse_datetime};
 use crate::db::models::{
-    Project, CreateProject, UpdateProject, AgentPref, ReadinessCheck,
+    Project, CreateProject, UpdateProject, AgentPref, ReadinessCheck, StageTimeout,
+    ADHOC_STAGE_NAME, MULTI_STAGE_WORKFLOW,
 };
 
+/// Stages a project may set time limits for: the workflow stages, the
+/// branch-name generation run and inserted stages without a name
+fn is_timed_stage(stage: &str) -> bool {
+    MULTI_STAGE_WORKFLOW.contains(&stage) || stage == "branch-gen" || stage == ADHOC_STAGE_NAME
+}
+
+fn validate_stage_timeouts(timeouts: &BTreeMap<String, StageTimeout>) -> Result<(), DbError> {
+    for (stage, timeout) in timeouts {
+        if !is_timed_stage(stage) {
+            return Err(DbError::Validation(format!("Unknown workflow stage '{}'", stage)));
+        }
+        if timeout.soft_secs == 0 || timeout.soft_secs > timeout.hard_secs {
+            return Err(DbError::Validation(format!(
+                "Stage '{}' needs a soft time limit between 1 second and its hard limit",
+                stage
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl Database {
     pub fn create_project(&self, input: &CreateProject) -> Result<Project, DbError> {
         let path = std::path::Path::new(&input.path);
@@ -56,6 +80,7 @@ impl Database {
                 blocked_patterns: vec![],
                 settings: serde_json::json!({}),
                 requires_git: input.requires_git,
+                stage_timeouts: BTreeMap::new(),
                 created_at: now,
                 updated_at: now,
             })
@@ -63,12 +88,12 @@ impl Database {
     }
 
     pub fn get_projects(&self) -> Result<Vec<Project>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, name, path, cursor_hooks_installed, claude_hooks_installed,
                           preferred_agent, allow_shell_commands, allow_file_writes,
                           blocked_patterns_json, settings_json, created_at, updated_at,
-                          requires_git
+                          requires_git, stage_timeouts_json
                    FROM projects ORDER BY name"#,
             )?;
 
@@ -77,6 +102,7 @@ impl Database {
                     let blocked_json: String = row.get(8)?;
                     let settings_json: String = row.get(9)?;
                     let pref_str: Option<String> = row.get(5)?;
+                    let timeouts_json: String = row.get(13)?;
 
                     Ok(Project {
                         id: row.get(0)?,
@@ -90,6 +116,7 @@ impl Database {
                         blocked_patterns: serde_json::from_str(&blocked_json).unwrap_or_default(),
                         settings: serde_json::from_str(&settings_json).unwrap_or(serde_json::json!({})),
                         requires_git: row.get::<_, i32>(12).unwrap_or(1) != 0,
+                        stage_timeouts: serde_json::from_str(&timeouts_json).unwrap_or_default(),
                         created_at: parse_datetime(row.get(10)?),
                         updated_at: parse_datetime(row.get(11)?),
                     })
@@ -120,6 +147,10 @@ impl Database {
     }
 
     pub fn update_project(&self, project_id: &str, input: &UpdateProject) -> Result<(), DbError> {
+        if let Some(ref timeouts) = input.stage_timeouts {
+            validate_stage_timeouts(timeouts)?;
+        }
+
         self.with_conn(|conn| {
             let now = chrono::Utc::now().to_rfc3339();
 
@@ -166,6 +197,14 @@ impl Database {
                 )?;
             }
 
+            if let Some(ref timeouts) = input.stage_timeouts {
+                let json = serde_json::to_string(timeouts).unwrap_or_else(|_| "{}".to_string());
+                conn.execute(
+                    "UPDATE projects SET stage_timeouts_json = ?, updated_at = ? WHERE id = ?",
+                    rusqlite::params![json, now, project_id],
+                )?;
+            }
+
             Ok(())
         })
     }
@@ -233,7 +272,7 @@ impl Database {
     }
 
     pub fn can_move_to_ready(&self, ticket_id: &str) -> Result<ReadinessCheck, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let result: Result<(Option<String>, String), _> = conn.query_row(
                 "SELECT project_id, board_id FROM tickets WHERE id = ?",
                 [ticket_id],
@@ -437,6 +476,7 @@ mod tests {
             allow_file_writes: None,
             blocked_patterns: Some(vec!["*.log".to_string(), "node_modules".to_string()]),
             requires_git: None,
+            stage_timeouts: None,
         }).unwrap();
         
         let updated = db.get_project(&project.id).unwrap().unwrap();
@@ -474,6 +514,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let check = db.can_move_to_ready(&ticket.id).unwrap();
@@ -515,6 +556,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let check = db.can_move_to_ready(&ticket.id).unwrap();
@@ -548,6 +590,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let check = db.can_move_to_ready(&ticket.id).unwrap();
@@ -593,9 +636,48 @@ mod tests {
             allow_file_writes: None,
             blocked_patterns: None,
             requires_git: Some(false),
+            stage_timeouts: None,
         }).unwrap();
         
         let updated = db.get_project(&project.id).unwrap().unwrap();
         assert!(!updated.requires_git);
     }
+
+    #[test]
+    fn update_project_stage_timeouts() {
+        let db = create_test_db();
+
+        let project = db.create_project(&CreateProject {
+            name: "Test".to_string(),
+            path: temp_dir_path(),
+            preferred_agent: None,
+            requires_git: true,
+        }).unwrap();
+        assert!(project.stage_timeouts.is_empty());
+
+        let update = |stage_timeouts: BTreeMap<String, StageTimeout>| db.update_project(&project.id, &UpdateProject {
+            name: None,
+            preferred_agent: None,
+            allow_shell_commands: None,
+            allow_file_writes: None,
+            blocked_patterns: None,
+            requires_git: None,
+            stage_timeouts: Some(stage_timeouts),
+        });
+
+        let implement = StageTimeout { soft_secs: 1200, hard_secs: 1800 };
+        update(BTreeMap::from([("implement".to_string(), implement)])).unwrap();
+        let updated = db.get_project(&project.id).unwrap().unwrap();
+        assert_eq!(updated.stage_timeouts.get("implement"), Some(&implement));
+
+        let soft_after_hard = StageTimeout { soft_secs: 600, hard_secs: 300 };
+        let err = update(BTreeMap::from([("plan".to_string(), soft_after_hard)])).unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)), "{}", err);
+        let err = update(BTreeMap::from([("deploy".to_string(), implement)])).unwrap_err();
+        assert!(matches!(err, DbError::Validation(ref msg) if msg.contains("deploy")), "{}", err);
+
+        // Rejected updates leave the saved limits alone
+        let unchanged = db.get_project(&project.id).unwrap().unwrap();
+        assert_eq!(unchanged.stage_timeouts, updated.stage_timeouts);
+    }
 }
@@ -0,0 +1,724 @@
+//! Remote workers and their ticket leases.
+//!
+//! A remote worker registers with the agent kinds it can run and the projects
+//! it has checkouts of, then asks for leases. A lease locks the ticket to a new
+//! run like an in-process worker's reservation does, and carries a fencing
+//! token: heartbeats and results are only accepted for the newest lease on a
+//! ticket whose lock it still holds, so a worker that lost its lease (it timed
+//! out and someone else took the ticket) cannot overwrite the newer work.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::agents::AgentKind;
+use crate::db::models::{
+    Actor, AgentRun, AgentType, CreateRun, CreateRunArtifact, LeaseGrant, LeaseResult, LeaseStatus,
+    RegisterRemoteWorker, RemoteWorker, RunArtifact, RunStatus, Ticket, TicketLease,
+};
+use crate::db::history::{record_change, LOCK_FIELD};
+use crate::db::runs::{insert_run, set_run_status};
+use crate::db::scheduling::QueueFilter;
+use crate::db::tickets::transfer_lock;
+use crate::db::workers::check_project;
+use crate::db::{parse_datetime, Database, DbError};
+use crate::lifecycle::TicketState;
+
+/// A remote worker not heard from for this long is shown as offline
+pub const REMOTE_WORKER_ONLINE_SECS: i64 = 120;
+
+const REMOTE_WORKER_COLUMNS: &str = r#"w.id, w.name, w.hostname, w.agent_kinds_json, w.project_ids_json, w.labels_json,
+    w.version, w.tickets_processed, w.registered_at, w.last_seen_at,
+    (SELECT COUNT(*) FROM ticket_leases l WHERE l.worker_id = w.id AND l.status = 'active' AND l.expires_at > ?1)"#;
+
+const LEASE_COLUMNS: &str = "fencing_token, worker_id, ticket_id, run_id, status, expires_at, created_at, ended_at";
+
+impl Database {
+    /// Register a remote worker, or update the registration named by `id`.
+    /// Leases still held under that id are given back: the worker restarted
+    /// and is no longer working on them.
+    pub fn register_remote_worker(&self, worker: &RegisterRemoteWorker) -> Result<RemoteWorker, DbError> {
+        let name = worker.name.trim();
+        if name.is_empty() {
+            return Err(DbError::Validation("Remote worker name cannot be empty".to_string()));
+        }
+        let agent_kinds = dedup(&worker.agent_kinds);
+        let project_ids = dedup(&worker.project_ids);
+        if agent_kinds.is_empty() {
+            return Err(DbError::Validation("Remote workers must offer at least one agent kind".to_string()));
+        }
+        if project_ids.is_empty() {
+            return Err(DbError::Validation("Remote workers must list the projects they have checkouts of".to_string()));
+        }
+
+        if let Some(id) = &worker.id {
+            self.abort_leases_of(id)?;
+        }
+
+        let id = worker.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.with_conn(|conn| {
+            for project_id in &project_ids {
+                check_project(conn, project_id)?;
+            }
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                r#"INSERT INTO remote_workers
+                   (id, name, hostname, agent_kinds_json, project_ids_json, labels_json, version, registered_at, last_seen_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+                   ON CONFLICT(id) DO UPDATE SET
+                       name = excluded.name, hostname = excluded.hostname,
+                       agent_kinds_json = excluded.agent_kinds_json, project_ids_json = excluded.project_ids_json,
+                       labels_json = excluded.labels_json, version = excluded.version,
+                       last_seen_at = excluded.last_seen_at"#,
+                rusqlite::params![
+                    id,
+                    name,
+                    worker.hostname,
+                    serde_json::to_string(&agent_kinds).unwrap_or_else(|_| "[]".to_string()),
+                    serde_json::to_string(&project_ids).unwrap_or_else(|_| "[]".to_string()),
+                    serde_json::to_string(&worker.labels).unwrap_or_else(|_| "{}".to_string()),
+                    worker.version,
+                    now,
+                ],
+            )?;
+            get_remote_worker(conn, &id)?.ok_or_else(|| DbError::NotFound(format!("Remote worker {}", id)))
+        })
+    }
+
+    /// Registered remote workers, most recently seen first
+    pub fn get_remote_workers(&self) -> Result<Vec<RemoteWorker>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM remote_workers w ORDER BY w.last_seen_at DESC",
+                REMOTE_WORKER_COLUMNS
+            ))?;
+            let rows = stmt.query_map([Utc::now().to_rfc3339()], map_remote_worker_row)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    pub fn get_remote_worker(&self, worker_id: &str) -> Result<Option<RemoteWorker>, DbError> {
+        self.with_reader(|conn| get_remote_worker(conn, worker_id))
+    }
+
+    /// Record that a remote worker is alive
+    pub fn touch_remote_worker(&self, worker_id: &str) -> Result<RemoteWorker, DbError> {
+        self.with_conn(|conn| {
+            touch(conn, worker_id)?;
+            get_remote_worker(conn, worker_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Remote worker {}", worker_id)))
+        })
+    }
+
+    /// Forget a remote worker, giving back the tickets it holds
+    pub fn delete_remote_worker(&self, worker_id: &str) -> Result<(), DbError> {
+        self.abort_leases_of(worker_id)?;
+        self.with_conn(|conn| {
+            let affected = conn.execute("DELETE FROM remote_workers WHERE id = ?", [worker_id])?;
+            if affected == 0 {
+                return Err(DbError::NotFound(format!("Remote worker {}", worker_id)));
+            }
+            Ok(())
+        })
+    }
+
+    /// Lease the next ready ticket the worker can take and move it to In
+    /// Progress. Agent kinds are tried in the order the worker registered
+    /// them; for each, the scheduling policy ranks the ready tickets of all
+    /// the worker's projects together, as for an in-process worker.
+    pub fn lease_next_ticket(
+        &self,
+        worker_id: &str,
+        duration: chrono::Duration,
+    ) -> Result<Option<LeaseGrant>, DbError> {
+        let worker = self.touch_remote_worker(worker_id)?;
+        let actor = Actor::worker(worker_id);
+        let expires_at = Utc::now() + duration;
+
+        for kind in &worker.agent_kinds {
+            let filter = QueueFilter {
+                project_ids: worker.project_ids.clone(),
+                agent_type: Some(*kind),
+                labels: worker.labels.clone(),
+            };
+            let reservation = uuid::Uuid::new_v4().to_string();
+            if let Some(ticket) = self.reserve_next_matching(&filter, &reservation, expires_at, &actor)? {
+                return self.grant_lease(&worker, ticket, *kind, &reservation, expires_at).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Create the run for a reserved ticket, hand its lock to a new lease and
+    /// move it to In Progress, all in one transaction. If that fails, the
+    /// reservation is released so the ticket goes back to the queue.
+    fn grant_lease(
+        &self,
+        worker: &RemoteWorker,
+        ticket: Ticket,
+        kind: AgentKind,
+        reservation: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<LeaseGrant, DbError> {
+        let project = match &ticket.project_id {
+            Some(project_id) => self.get_project(project_id)?,
+            None => None,
+        };
+        let create_run = CreateRun {
+            ticket_id: ticket.id.clone(),
+            agent_type: match kind {
+                AgentKind::Cursor => AgentType::Cursor,
+                AgentKind::Claude => AgentType::Claude,
+            },
+            repo_path: project.as_ref().map(|p| p.path.clone()).unwrap_or_default(),
+            parent_run_id: None,
+            stage: None,
+        };
+
+        let granted = self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let run = insert_run(&tx, &create_run)?;
+            transfer_lock(&tx, &ticket.id, reservation, &run.id, Some(expires_at))?;
+            set_run_status(&tx, &run.id, RunStatus::Running, None, None)?;
+
+            let now = Utc::now().to_rfc3339();
+            tx.execute(
+                "UPDATE ticket_leases SET status = 'expired', ended_at = ? WHERE ticket_id = ? AND status = 'active'",
+                rusqlite::params![now, ticket.id],
+            )?;
+            tx.execute(
+                r#"INSERT INTO ticket_leases (worker_id, ticket_id, run_id, status, expires_at, created_at)
+                   VALUES (?, ?, ?, 'active', ?, ?)"#,
+                rusqlite::params![worker.id, ticket.id, run.id, expires_at.to_rfc3339(), now],
+            )?;
+            let lease = get_lease(&tx, tx.last_insert_rowid())?;
+            move_to_role(&tx, &ticket.id, TicketState::InProgress, &Actor::run(&run.id))?;
+            tx.commit()?;
+            Ok((lease, run.id))
+        });
+        let (lease, run_id) = match granted {
+            Ok(granted) => granted,
+            Err(e) => {
+                let released = self.with_conn(|conn| {
+                    release_reservation(conn, &ticket.id, reservation, &Actor::worker(&worker.id))
+                });
+                if let Err(release_error) = released {
+                    tracing::error!(
+                        "Failed to release reservation {} of ticket {}: {}",
+                        reservation, ticket.id, release_error
+                    );
+                }
+                return Err(e);
+            }
+        };
+
+        Ok(LeaseGrant {
+            lease,
+            ticket: self.get_ticket(&ticket.id)?,
+            run: self.get_run(&run_id)?,
+            project,
+        })
+    }
+
+    /// Extend a lease and its ticket lock
+    pub fn renew_lease(
+        &self,
+        worker_id: &str,
+        fencing_token: i64,
+        duration: chrono::Duration,
+    ) -> Result<TicketLease, DbError> {
+        self.with_conn(|conn| {
+            let lease = held_lease(conn, worker_id, fencing_token)?;
+            let now = Utc::now();
+            let expires_at = (now + duration).to_rfc3339();
+            conn.execute(
+                "UPDATE ticket_leases SET expires_at = ? WHERE fencing_token = ?",
+                rusqlite::params![expires_at, fencing_token],
+            )?;
+            conn.execute(
+                "UPDATE tickets SET lock_expires_at = ?, updated_at = ? WHERE id = ? AND locked_by_run_id = ?",
+                rusqlite::params![expires_at, now.to_rfc3339(), lease.ticket_id, lease.run_id],
+            )?;
+            touch(conn, worker_id)?;
+            get_lease(conn, fencing_token)
+        })
+    }
+
+    /// Record the outcome of a lease: artifacts and diff are stored on the
+    /// run, the run is closed and the ticket unlocked and moved to Review
+    /// (finished), Blocked (error) or back to Ready (aborted)
+    pub fn complete_lease(
+        &self,
+        worker_id: &str,
+        fencing_token: i64,
+        result: &LeaseResult,
+    ) -> Result<(TicketLease, AgentRun), DbError> {
+        let target = match result.status {
+            RunStatus::Finished => TicketState::Review,
+            RunStatus::Error => TicketState::Blocked,
+            RunStatus::Aborted => TicketState::Ready,
+            RunStatus::Queued | RunStatus::Running => {
+                return Err(DbError::Validation(
+                    "A lease result must be finished, error or aborted".to_string(),
+                ));
+            }
+        };
+
+        // The fencing check, the run and the ticket change together, so the
+        // ticket cannot be taken by someone else in between
+        let lease = self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let lease = match held_lease(&tx, worker_id, fencing_token) {
+                Ok(lease) => lease,
+                Err(e) => {
+                    // Keep the lease marked expired when it was lost
+                    tx.commit()?;
+                    return Err(e);
+                }
+            };
+            let now = Utc::now().to_rfc3339();
+            tx.execute(
+                "UPDATE ticket_leases SET status = 'completed', ended_at = ? WHERE fencing_token = ?",
+                rusqlite::params![now, fencing_token],
+            )?;
+            let diff = result.diff.as_ref().filter(|d| !d.trim().is_empty()).map(|diff| CreateRunArtifact {
+                name: "diff.patch".to_string(),
+                content_type: "text/x-diff".to_string(),
+                content: diff.clone(),
+            });
+            for artifact in result.artifacts.iter().chain(diff.as_ref()) {
+                insert_artifact(&tx, &lease.run_id, artifact)?;
+            }
+            tx.execute(
+                "UPDATE remote_workers SET tickets_processed = tickets_processed + ?, last_seen_at = ? WHERE id = ?",
+                rusqlite::params![(result.status != RunStatus::Aborted) as i64, now, worker_id],
+            )?;
+            set_run_status(&tx, &lease.run_id, result.status.clone(), result.exit_code, result.summary.as_deref())?;
+            finish_leased_ticket(&tx, &lease, target)?;
+            let lease = get_lease(&tx, fencing_token)?;
+            tx.commit()?;
+            Ok(lease)
+        })?;
+
+        let run = self.get_run(&lease.run_id)?;
+        Ok((lease, run))
+    }
+
+    pub fn list_run_artifacts(&self, run_id: &str) -> Result<Vec<RunArtifact>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, run_id, name, content_type, content, created_at
+                   FROM run_artifacts WHERE run_id = ? ORDER BY created_at, rowid"#,
+            )?;
+            let rows = stmt.query_map([run_id], |row| {
+                Ok(RunArtifact {
+                    id: row.get(0)?,
+                    run_id: row.get(1)?,
+                    name: row.get(2)?,
+                    content_type: row.get(3)?,
+                    content: row.get(4)?,
+                    created_at: parse_datetime(row.get(5)?),
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    /// Abort the runs of a worker's active leases and put their tickets back
+    /// in Ready
+    fn abort_leases_of(&self, worker_id: &str) -> Result<(), DbError> {
+        let leases = self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM ticket_leases WHERE worker_id = ? AND status = 'active'",
+                LEASE_COLUMNS
+            ))?;
+            let leases = stmt
+                .query_map([worker_id], map_lease_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            conn.execute(
+                "UPDATE ticket_leases SET status = 'expired', ended_at = ? WHERE worker_id = ? AND status = 'active'",
+                rusqlite::params![Utc::now().to_rfc3339(), worker_id],
+            )?;
+            Ok::<_, DbError>(leases)
+        })?;
+
+        for lease in leases {
+            self.with_conn_mut(|conn| {
+                let tx = conn.transaction()?;
+                set_run_status(&tx, &lease.run_id, RunStatus::Aborted, None, Some("Remote worker went away"))?;
+                match finish_leased_ticket(&tx, &lease, TicketState::Ready) {
+                    Ok(()) => {}
+                    // The ticket is gone or someone else has it; only the run is aborted
+                    Err(DbError::NotFound(_) | DbError::Conflict(_)) => {}
+                    Err(e) => return Err(e),
+                }
+                tx.commit()?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Unlock the ticket of `lease` and move it to the `target` column. Fails
+/// with Conflict when the ticket is no longer locked to the lease's run.
+fn finish_leased_ticket(conn: &Connection, lease: &TicketLease, target: TicketState) -> Result<(), DbError> {
+    let actor = Actor::run(&lease.run_id);
+    let now = Utc::now().to_rfc3339();
+    let unlocked = conn.execute(
+        r#"UPDATE tickets SET locked_by_run_id = NULL, lock_expires_at = NULL, updated_at = ?
+           WHERE id = ? AND locked_by_run_id = ?"#,
+        rusqlite::params![now, lease.ticket_id, lease.run_id],
+    )?;
+    if unlocked == 0 {
+        return Err(DbError::Conflict(format!(
+            "Ticket {} is no longer locked by run {}",
+            lease.ticket_id, lease.run_id
+        )));
+    }
+    record_change(conn, &lease.ticket_id, LOCK_FIELD, Some(&lease.run_id), None, &actor)?;
+    move_to_role(conn, &lease.ticket_id, target, &actor)
+}
+
+/// Move a ticket to the column with the `target` role on its board, if the
+/// board has one
+fn move_to_role(conn: &Connection, ticket_id: &str, target: TicketState, actor: &Actor) -> Result<(), DbError> {
+    let (board_id, column_id): (String, String) = conn
+        .query_row(
+            "SELECT board_id, column_id FROM tickets WHERE id = ?",
+            [ticket_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| DbError::NotFound(format!("Ticket {}", ticket_id)))?;
+
+    let target_column: Option<String> = conn
+        .query_row(
+            "SELECT id FROM columns WHERE board_id = ? AND role = ?",
+            rusqlite::params![board_id, target.as_str()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match target_column {
+        Some(target_column) if target_column != column_id => {
+            conn.execute(
+                "UPDATE tickets SET column_id = ?, updated_at = ? WHERE id = ?",
+                rusqlite::params![target_column, Utc::now().to_rfc3339(), ticket_id],
+            )?;
+            record_change(conn, ticket_id, "columnId", Some(&column_id), Some(&target_column), actor)?;
+        }
+        Some(_) => {}
+        None => tracing::warn!("Board {} has no {} column", board_id, target.as_str()),
+    }
+    Ok(())
+}
+
+/// Give back a ticket reserved for a lease that could not be granted, unless
+/// the reservation already lost the lock
+fn release_reservation(conn: &Connection, ticket_id: &str, reservation: &str, actor: &Actor) -> Result<(), DbError> {
+    let released = conn.execute(
+        r#"UPDATE tickets SET locked_by_run_id = NULL, lock_expires_at = NULL, updated_at = ?
+           WHERE id = ? AND locked_by_run_id = ?"#,
+        rusqlite::params![Utc::now().to_rfc3339(), ticket_id, reservation],
+    )?;
+    if released > 0 {
+        record_change(conn, ticket_id, LOCK_FIELD, Some(reservation), None, actor)?;
+    }
+    Ok(())
+}
+
+/// The lease, if `worker_id` still holds it: it is active, it is the newest
+/// lease on the ticket and the ticket is still locked to its run. A lease that
+/// fails the last two checks is marked expired.
+fn held_lease(conn: &Connection, worker_id: &str, fencing_token: i64) -> Result<TicketLease, DbError> {
+    let lease = conn
+        .query_row(
+            &format!("SELECT {} FROM ticket_leases WHERE fencing_token = ?", LEASE_COLUMNS),
+            [fencing_token],
+            map_lease_row,
+        )
+        .optional()?
+        .filter(|lease| lease.worker_id == worker_id)
+        .ok_or_else(|| DbError::NotFound(format!("Lease {}", fencing_token)))?;
+    if lease.status != LeaseStatus::Active {
+        return Err(DbError::Conflict(format!("Lease {} is {}", fencing_token, lease.status.as_str())));
+    }
+
+    let holds: bool = conn.query_row(
+        r#"SELECT EXISTS(SELECT 1 FROM tickets WHERE id = ?1 AND locked_by_run_id = ?2)
+              AND NOT EXISTS(SELECT 1 FROM ticket_leases WHERE ticket_id = ?1 AND fencing_token > ?3)"#,
+        rusqlite::params![lease.ticket_id, lease.run_id, fencing_token],
+        |row| row.get(0),
+    )?;
+    if !holds {
+        conn.execute(
+            "UPDATE ticket_leases SET status = 'expired', ended_at = ? WHERE fencing_token = ?",
+            rusqlite::params![Utc::now().to_rfc3339(), fencing_token],
+        )?;
+        return Err(DbError::Conflict(format!(
+            "Lease {} was lost; ticket {} is no longer held by it",
+            fencing_token, lease.ticket_id
+        )));
+    }
+    Ok(lease)
+}
+
+fn touch(conn: &Connection, worker_id: &str) -> Result<(), DbError> {
+    let affected = conn.execute(
+        "UPDATE remote_workers SET last_seen_at = ? WHERE id = ?",
+        rusqlite::params![Utc::now().to_rfc3339(), worker_id],
+    )?;
+    if affected == 0 {
+        return Err(DbError::NotFound(format!("Remote worker {}", worker_id)));
+    }
+    Ok(())
+}
+
+fn insert_artifact(conn: &Connection, run_id: &str, artifact: &CreateRunArtifact) -> Result<(), DbError> {
+    if artifact.name.trim().is_empty() {
+        return Err(DbError::Validation("Artifact name cannot be empty".to_string()));
+    }
+    conn.execute(
+        r#"INSERT INTO run_artifacts (id, run_id, name, content_type, content, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            run_id,
+            artifact.name.trim(),
+            artifact.content_type,
+            artifact.content,
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+fn get_remote_worker(conn: &Connection, worker_id: &str) -> Result<Option<RemoteWorker>, DbError> {
+    conn.query_row(
+        &format!("SELECT {} FROM remote_workers w WHERE w.id = ?2", REMOTE_WORKER_COLUMNS),
+        rusqlite::params![Utc::now().to_rfc3339(), worker_id],
+        map_remote_worker_row,
+    ).optional().map_err(DbError::from)
+}
+
+fn get_lease(conn: &Connection, fencing_token: i64) -> Result<TicketLease, DbError> {
+    conn.query_row(
+        &format!("SELECT {} FROM ticket_leases WHERE fencing_token = ?", LEASE_COLUMNS),
+        [fencing_token],
+        map_lease_row,
+    ).optional()?.ok_or_else(|| DbError::NotFound(format!("Lease {}", fencing_token)))
+}
+
+fn map_remote_worker_row(row: &rusqlite::Row) -> rusqlite::Result<RemoteWorker> {
+    let agent_kinds_json: String = row.get(3)?;
+    let project_ids_json: String = row.get(4)?;
+    let labels_json: String = row.get(5)?;
+    let tickets_processed: i64 = row.get(7)?;
+    let last_seen_at = parse_datetime(row.get(9)?);
+    Ok(RemoteWorker {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        hostname: row.get(2)?,
+        agent_kinds: serde_json::from_str(&agent_kinds_json).unwrap_or_default(),
+        project_ids: serde_json::from_str(&project_ids_json).unwrap_or_default(),
+        labels: serde_json::from_str(&labels_json).unwrap_or_default(),
+        version: row.get(6)?,
+        online: Utc::now() - last_seen_at < chrono::Duration::seconds(REMOTE_WORKER_ONLINE_SECS),
+        active_leases: row.get(10)?,
+        tickets_processed: tickets_processed.max(0) as u64,
+        registered_at: parse_datetime(row.get(8)?),
+        last_seen_at,
+    })
+}
+
+fn map_lease_row(row: &rusqlite::Row) -> rusqlite::Result<TicketLease> {
+    let status: String = row.get(4)?;
+    Ok(TicketLease {
+        fencing_token: row.get(0)?,
+        worker_id: row.get(1)?,
+        ticket_id: row.get(2)?,
+        run_id: row.get(3)?,
+        status: LeaseStatus::parse(&status).unwrap_or(LeaseStatus::Expired),
+        expires_at: parse_datetime(row.get(5)?),
+        created_at: parse_datetime(row.get(6)?),
+        ended_at: row.get::<_, Option<String>>(7)?.map(parse_datetime),
+    })
+}
+
+fn dedup<T: Clone + PartialEq>(items: &[T]) -> Vec<T> {
+    let mut unique = Vec::with_capacity(items.len());
+    for item in items {
+        if !unique.contains(item) {
+            unique.push(item.clone());
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{CreateProject, CreateTicket, LabelFilter, Priority};
+
+    /// A board with one ready ticket in a project, and a worker that can take it
+    fn setup(db: &Database) -> (RemoteWorker, Ticket) {
+        let project = db.create_project(&CreateProject {
+            name: "Repo".to_string(),
+            path: std::env::temp_dir().to_string_lossy().to_string(),
+            preferred_agent: None,
+            requires_git: false,
+        }).unwrap();
+        let board = db.create_board("Board").unwrap();
+        let ready = db.require_column_by_role(&board.id, TicketState::Ready).unwrap();
+        let ticket = db.create_ticket(&CreateTicket {
+            project_id: Some(project.id.clone()),
+            ..CreateTicket::for_test(&board.id, &ready.id, "Ticket")
+        }).unwrap();
+        let worker = db.register_remote_worker(&RegisterRemoteWorker {
+            id: None,
+            name: "build-box".to_string(),
+            hostname: Some("build-box.local".to_string()),
+            agent_kinds: vec![AgentKind::Claude, AgentKind::Claude],
+            project_ids: vec![project.id],
+            labels: LabelFilter::default(),
+            version: None,
+        }).unwrap();
+        (worker, ticket)
+    }
+
+    #[test]
+    fn register_validates_and_dedups() {
+        let db = Database::open_in_memory().unwrap();
+        let (worker, _) = setup(&db);
+        assert_eq!(worker.agent_kinds, vec![AgentKind::Claude]);
+        assert!(worker.online);
+        assert_eq!(db.get_remote_workers().unwrap().len(), 1);
+
+        let err = db.register_remote_worker(&RegisterRemoteWorker {
+            id: None,
+            name: "elsewhere".to_string(),
+            hostname: None,
+            agent_kinds: vec![AgentKind::Cursor],
+            project_ids: vec!["missing".to_string()],
+            labels: LabelFilter::default(),
+            version: None,
+        }).unwrap_err();
+        assert!(matches!(err, DbError::NotFound(_)));
+    }
+
+    #[test]
+    fn lease_heartbeat_and_result_move_the_ticket() {
+        let db = Database::open_in_memory().unwrap();
+        let (worker, ticket) = setup(&db);
+
+        let grant = db.lease_next_ticket(&worker.id, chrono::Duration::minutes(2)).unwrap().unwrap();
+        assert_eq!(grant.ticket.id, ticket.id);
+        assert_eq!(grant.ticket.locked_by_run_id.as_deref(), Some(grant.run.id.as_str()));
+        assert_eq!(grant.run.status, RunStatus::Running);
+        assert!(db.lease_next_ticket(&worker.id, chrono::Duration::minutes(2)).unwrap().is_none());
+
+        let token = grant.lease.fencing_token;
+        db.renew_lease(&worker.id, token, chrono::Duration::minutes(5)).unwrap();
+        assert!(matches!(db.renew_lease("someone-else", token, chrono::Duration::minutes(5)), Err(DbError::NotFound(_))));
+
+        let (lease, run) = db.complete_lease(&worker.id, token, &LeaseResult {
+            status: RunStatus::Finished,
+            exit_code: Some(0),
+            summary: Some("Done".to_string()),
+            diff: Some("diff --git a/x b/x\n".to_string()),
+            artifacts: vec![CreateRunArtifact {
+                name: "agent.log".to_string(),
+                content_type: "text/plain".to_string(),
+                content: "ok".to_string(),
+            }],
+        }).unwrap();
+        assert_eq!(lease.status, LeaseStatus::Completed);
+        assert_eq!(run.status, RunStatus::Finished);
+
+        let names: Vec<_> = db.list_run_artifacts(&run.id).unwrap().into_iter().map(|a| a.name).collect();
+        assert_eq!(names, vec!["agent.log", "diff.patch"]);
+        let ticket = db.get_ticket(&ticket.id).unwrap();
+        assert!(ticket.locked_by_run_id.is_none());
+        let review = db.require_column_by_role(&ticket.board_id, TicketState::Review).unwrap();
+        assert_eq!(ticket.column_id, review.id);
+        assert_eq!(db.get_remote_worker(&worker.id).unwrap().unwrap().tickets_processed, 1);
+
+        // A finished lease cannot report again
+        assert!(matches!(db.renew_lease(&worker.id, token, chrono::Duration::minutes(5)), Err(DbError::Conflict(_))));
+    }
+
+    #[test]
+    fn lease_ranks_tickets_across_all_projects() {
+        let db = Database::open_in_memory().unwrap();
+        let (_, first) = setup(&db);
+        let other = db.create_project(&CreateProject {
+            name: "Other".to_string(),
+            path: std::env::temp_dir().to_string_lossy().to_string(),
+            preferred_agent: None,
+            requires_git: false,
+        }).unwrap();
+        let ready = db.require_column_by_role(&first.board_id, TicketState::Ready).unwrap();
+        let urgent = db.create_ticket(&CreateTicket {
+            project_id: Some(other.id.clone()),
+            priority: Priority::Urgent,
+            ..CreateTicket::for_test(&first.board_id, &ready.id, "Urgent")
+        }).unwrap();
+        let worker = db.register_remote_worker(&RegisterRemoteWorker {
+            id: None,
+            name: "multi-box".to_string(),
+            hostname: None,
+            agent_kinds: vec![AgentKind::Claude],
+            project_ids: vec![first.project_id.clone().unwrap(), other.id],
+            labels: LabelFilter::default(),
+            version: None,
+        }).unwrap();
+
+        // The urgent ticket wins although its project was registered second
+        let grant = db.lease_next_ticket(&worker.id, chrono::Duration::minutes(2)).unwrap().unwrap();
+        assert_eq!(grant.ticket.id, urgent.id);
+        let grant = db.lease_next_ticket(&worker.id, chrono::Duration::minutes(2)).unwrap().unwrap();
+        assert_eq!(grant.ticket.id, first.id);
+    }
+
+    #[test]
+    fn superseded_lease_is_fenced_off() {
+        let db = Database::open_in_memory().unwrap();
+        let (worker, ticket) = setup(&db);
+        let grant = db.lease_next_ticket(&worker.id, chrono::Duration::minutes(2)).unwrap().unwrap();
+
+        // The lock expires and the ticket goes to someone else
+        db.unlock_ticket(&ticket.id, &Actor::system()).unwrap();
+        db.lock_ticket(&ticket.id, "other-run", Utc::now() + chrono::Duration::minutes(5)).unwrap();
+
+        let err = db.complete_lease(&worker.id, grant.lease.fencing_token, &LeaseResult {
+            status: RunStatus::Finished,
+            exit_code: Some(0),
+            summary: None,
+            diff: Some("stale".to_string()),
+            artifacts: vec![],
+        }).unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+        assert!(db.list_run_artifacts(&grant.run.id).unwrap().is_empty());
+        let after = db.get_ticket(&ticket.id).unwrap();
+        assert_eq!(after.locked_by_run_id.as_deref(), Some("other-run"));
+        assert_eq!(after.column_id, grant.ticket.column_id);
+        assert_eq!(db.get_run(&grant.run.id).unwrap().status, RunStatus::Running);
+    }
+
+    #[test]
+    fn dropped_worker_aborts_its_run_even_when_the_ticket_moved_on() {
+        let db = Database::open_in_memory().unwrap();
+        let (worker, ticket) = setup(&db);
+        let grant = db.lease_next_ticket(&worker.id, chrono::Duration::minutes(2)).unwrap().unwrap();
+        let in_progress = db.require_column_by_role(&ticket.board_id, TicketState::InProgress).unwrap();
+        assert_eq!(grant.ticket.column_id, in_progress.id);
+
+        db.unlock_ticket(&ticket.id, &Actor::system()).unwrap();
+        db.lock_ticket(&ticket.id, "other-run", Utc::now() + chrono::Duration::minutes(5)).unwrap();
+        db.delete_remote_worker(&worker.id).unwrap();
+
+        assert_eq!(db.get_run(&grant.run.id).unwrap().status, RunStatus::Aborted);
+        let after = db.get_ticket(&ticket.id).unwrap();
+        assert_eq!(after.locked_by_run_id.as_deref(), Some("other-run"));
+        assert_eq!(after.column_id, in_progress.id);
+    }
+}
@@ -0,0 +1,421 @@
+//! Retention and archival.
+//!
+//! Keeps the database from growing without bound. Events of runs that ended
+//! long ago are compacted into one summary event per run, Done tickets that
+//! have not changed for a while move to `ticket_archive` (hidden from board
+//! queries, but searchable and restorable), rows whose owner no longer
+//! exists are removed, and freed pages are handed back to the file system.
+//! The cleanup service applies a [`RetentionPolicy`] on a schedule.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Deserialize;
+
+use crate::db::history::record_change;
+use crate::db::models::{Actor, AgentEventPayload, ArchivedTicket, RetentionReport, RunStatus, Ticket};
+use crate::db::{parse_datetime, Database, DbError};
+
+/// Event type of the summary left behind when a run's events are compacted
+pub const COMPACTED_EVENT_TYPE: &str = "events_compacted";
+
+/// History field recording archive and restore; values are archive timestamps
+pub const ARCHIVED_FIELD: &str = "archived";
+
+/// Rows whose owner is gone. Foreign keys cascade these away, but databases
+/// written while foreign keys were off (older builds, migrations) can still
+/// hold some.
+const ORPHAN_DELETES: &[&str] = &[
+    "DELETE FROM agent_runs WHERE ticket_id NOT IN (SELECT id FROM tickets)",
+    "DELETE FROM agent_events WHERE run_id NOT IN (SELECT id FROM agent_runs) OR ticket_id NOT IN (SELECT id FROM tickets)",
+    "DELETE FROM comments WHERE ticket_id NOT IN (SELECT id FROM tickets)",
+    "DELETE FROM tasks WHERE ticket_id NOT IN (SELECT id FROM tickets)",
+    "DELETE FROM ticket_history WHERE ticket_id NOT IN (SELECT id FROM tickets)",
+];
+
+/// How long events and finished tickets are kept.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionPolicy {
+    /// Minutes between retention passes; 0 turns them off
+    pub interval_mins: u64,
+    /// Events of runs that ended more than this many days ago are replaced
+    /// by a summary; 0 keeps every event
+    pub compact_events_after_days: u64,
+    /// Done tickets unchanged for this many days are archived; 0 keeps them
+    /// on the board
+    pub archive_done_after_days: u64,
+    /// Trash entries older than this many days are deleted for good; 0
+    /// keeps them until purged by hand
+    pub purge_trash_after_days: u64,
+    /// Return freed pages to the file system after a pass that removed rows
+    pub vacuum: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            interval_mins: 1440,
+            compact_events_after_days: 30,
+            archive_done_after_days: 90,
+            purge_trash_after_days: 30,
+            vacuum: true,
+        }
+    }
+}
+
+impl Database {
+    /// Apply `policy` once
+    pub fn apply_retention(&self, policy: &RetentionPolicy) -> Result<RetentionReport, DbError> {
+        let now = Utc::now();
+        let mut report = RetentionReport::default();
+
+        if policy.compact_events_after_days > 0 {
+            let cutoff = now - chrono::Duration::days(policy.compact_events_after_days as i64);
+            let (runs, events) = self.compact_events(cutoff)?;
+            report.compacted_runs = runs;
+            report.compacted_events = events;
+        }
+        if policy.archive_done_after_days > 0 {
+            let cutoff = now - chrono::Duration::days(policy.archive_done_after_days as i64);
+            report.archived_tickets = self.archive_done_tickets(cutoff)?;
+        }
+        if policy.purge_trash_after_days > 0 {
+            let cutoff = now - chrono::Duration::days(policy.purge_trash_after_days as i64);
+            report.purged_trash = self.purge_trash_before(cutoff)?;
+        }
+        report.orphans_removed = self.remove_orphans()?;
+
+        if policy.vacuum
+            && (report.compacted_events > 0 || report.purged_trash > 0 || report.orphans_removed > 0)
+        {
+            self.vacuum()?;
+            report.vacuumed = true;
+        }
+        Ok(report)
+    }
+
+    /// Replace the events of every run that ended before `ended_before` with
+    /// a single summary event counting them by type. Returns the number of
+    /// runs compacted and events removed.
+    pub fn compact_events(&self, ended_before: DateTime<Utc>) -> Result<(usize, usize), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let runs: Vec<(String, String)> = {
+                let mut stmt = tx.prepare(
+                    r#"SELECT r.id, r.ticket_id FROM agent_runs r
+                       WHERE r.status NOT IN (?, ?) AND r.ended_at IS NOT NULL AND r.ended_at < ?
+                       AND EXISTS (SELECT 1 FROM agent_events e WHERE e.run_id = r.id AND e.event_type != ?)"#,
+                )?;
+                let rows = stmt.query_map(
+                    rusqlite::params![
+                        RunStatus::Queued.as_str(),
+                        RunStatus::Running.as_str(),
+                        ended_before.to_rfc3339(),
+                        COMPACTED_EVENT_TYPE,
+                    ],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                rows.collect::<Result<_, _>>()?
+            };
+
+            let mut removed = 0;
+            for (run_id, ticket_id) in &runs {
+                removed += compact_run_events(&tx, run_id, ticket_id)?;
+            }
+            tx.commit()?;
+            Ok((runs.len(), removed))
+        })
+    }
+
+    /// Archive Done tickets last updated before `updated_before`. Locked
+    /// tickets stay on the board. Returns how many were archived.
+    pub fn archive_done_tickets(&self, updated_before: DateTime<Utc>) -> Result<usize, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let ticket_ids: Vec<String> = {
+                let mut stmt = tx.prepare(
+                    r#"SELECT t.id FROM tickets t
+                       JOIN columns c ON c.id = t.column_id
+                       WHERE c.role = 'done' AND t.updated_at < ? AND t.locked_by_run_id IS NULL
+                       AND t.trash_id IS NULL
+                       AND t.id NOT IN (SELECT ticket_id FROM ticket_archive)"#,
+                )?;
+                let rows = stmt.query_map([updated_before.to_rfc3339()], |row| row.get(0))?;
+                rows.collect::<Result<_, _>>()?
+            };
+
+            let now = Utc::now().to_rfc3339();
+            for ticket_id in &ticket_ids {
+                tx.execute(
+                    "INSERT INTO ticket_archive (ticket_id, archived_at) VALUES (?, ?)",
+                    rusqlite::params![ticket_id, now],
+                )?;
+                record_change(&tx, ticket_id, ARCHIVED_FIELD, None, Some(&now), &Actor::system())?;
+            }
+            tx.commit()?;
+            Ok(ticket_ids.len())
+        })
+    }
+
+    /// Archived tickets, newest first, optionally limited to one board and to
+    /// tickets whose title or description contains `query` (case-insensitive)
+    pub fn search_archived_tickets(
+        &self,
+        board_id: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<Vec<ArchivedTicket>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT t.id, t.board_id, t.column_id, t.title, t.description_md, t.priority,
+                          t.labels_json, t.created_at, t.updated_at, t.locked_by_run_id,
+                          t.lock_expires_at, t.project_id, t.agent_pref, t.workflow_type, t.model, t.branch_name,
+                          t.is_epic, t.epic_id, t.order_in_epic, t.depends_on_epic_id, t.depends_on_epic_ids_json, t.scratchpad_id, t.custom_fields_json,
+                          a.archived_at
+                   FROM ticket_archive a
+                   JOIN tickets t ON t.id = a.ticket_id
+                   WHERE t.trash_id IS NULL AND (?1 IS NULL OR t.board_id = ?1)
+                   AND (?2 IS NULL OR instr(lower(t.title), lower(?2)) > 0
+                        OR instr(lower(t.description_md), lower(?2)) > 0)
+                   ORDER BY a.archived_at DESC"#,
+            )?;
+            let rows = stmt.query_map(rusqlite::params![board_id, query], |row| {
+                Ok(ArchivedTicket {
+                    ticket: Database::map_ticket_row(row)?,
+                    archived_at: parse_datetime(row.get(23)?),
+                })
+            })?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    /// Put an archived ticket back on its board, in the column it was in
+    pub fn restore_archived_ticket(&self, ticket_id: &str, actor: &Actor) -> Result<Ticket, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let archived_at: Option<String> = tx.query_row(
+                "SELECT archived_at FROM ticket_archive WHERE ticket_id = ?",
+                [ticket_id],
+                |row| row.get(0),
+            ).optional()?;
+            let Some(archived_at) = archived_at else {
+                return Err(DbError::NotFound(format!("Archived ticket {}", ticket_id)));
+            };
+
+            tx.execute("DELETE FROM ticket_archive WHERE ticket_id = ?", [ticket_id])?;
+            record_change(&tx, ticket_id, ARCHIVED_FIELD, Some(&archived_at), None, actor)?;
+            tx.commit()?;
+            Ok(())
+        })?;
+        self.get_ticket(ticket_id)
+    }
+
+    /// Delete rows left behind by deleted tickets and runs; returns how many
+    pub fn remove_orphans(&self) -> Result<usize, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let mut removed = 0;
+            for sql in ORPHAN_DELETES {
+                removed += tx.execute(sql, [])?;
+            }
+            tx.commit()?;
+            Ok(removed)
+        })
+    }
+
+    /// Hand free pages back to the file system.
+    ///
+    /// The first call switches the database to incremental auto-vacuum, which
+    /// takes one full `VACUUM`; later calls only run `incremental_vacuum`.
+    pub fn vacuum(&self) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+            if auto_vacuum == 2 {
+                conn.execute_batch("PRAGMA incremental_vacuum")?;
+            } else {
+                conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM")?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Replace one run's events with a summary; returns how many were removed
+fn compact_run_events(conn: &Connection, run_id: &str, ticket_id: &str) -> Result<usize, DbError> {
+    // (event_type, count, first, last)
+    let groups: Vec<(String, i64, String, String)> = {
+        let mut stmt = conn.prepare(
+            r#"SELECT event_type, COUNT(*), MIN(created_at), MAX(created_at)
+               FROM agent_events WHERE run_id = ? AND event_type != ?
+               GROUP BY event_type"#,
+        )?;
+        let rows = stmt.query_map(rusqlite::params![run_id, COMPACTED_EVENT_TYPE], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?;
+        rows.collect::<Result<_, _>>()?
+    };
+    let (Some(first_at), Some(last_at)) = (
+        groups.iter().map(|g| g.2.clone()).min(),
+        groups.iter().map(|g| g.3.clone()).max(),
+    ) else {
+        return Ok(0);
+    };
+    let counts: BTreeMap<String, i64> = groups.into_iter().map(|(event_type, count, ..)| (event_type, count)).collect();
+
+    let removed = conn.execute(
+        "DELETE FROM agent_events WHERE run_id = ? AND event_type != ?",
+        rusqlite::params![run_id, COMPACTED_EVENT_TYPE],
+    )?;
+    let payload = AgentEventPayload {
+        raw: Some(format!("{} events compacted", removed)),
+        structured: Some(serde_json::json!({
+            "compactedEvents": removed,
+            "counts": counts,
+            "firstAt": first_at,
+            "lastAt": last_at,
+        })),
+    };
+    conn.execute(
+        r#"INSERT INTO agent_events (id, run_id, ticket_id, event_type, payload_json, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)"#,
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            run_id,
+            ticket_id,
+            COMPACTED_EVENT_TYPE,
+            serde_json::to_string(&payload).unwrap_or_else(|_| "{}".to_string()),
+            last_at,
+        ],
+    )?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{AgentType, CreateRun, CreateTicket, EventType, NormalizedEvent};
+
+    fn create_ticket(db: &Database, board_id: &str, column_id: &str, title: &str) -> Ticket {
+        db.create_ticket(&CreateTicket {
+            description_md: format!("{} description", title),
+            ..CreateTicket::for_test(board_id, column_id, title)
+        }).unwrap()
+    }
+
+    fn record_event(db: &Database, run_id: &str, ticket_id: &str, event_type: EventType) {
+        db.create_event(&NormalizedEvent {
+            run_id: run_id.to_string(),
+            ticket_id: ticket_id.to_string(),
+            agent_type: AgentType::Claude,
+            event_type,
+            payload: AgentEventPayload { raw: Some("event".to_string()), structured: None },
+            timestamp: Utc::now(),
+        }).unwrap();
+    }
+
+    #[test]
+    fn compacts_events_of_finished_runs_only() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket = create_ticket(&db, &board.id, &columns[0].id, "Ticket");
+
+        let new_run = |status| {
+            let run = db.create_run(&CreateRun {
+                ticket_id: ticket.id.clone(),
+                agent_type: AgentType::Claude,
+                repo_path: "/tmp".to_string(),
+                parent_run_id: None,
+                stage: None,
+            }).unwrap();
+            db.update_run_status(&run.id, status, None, None).unwrap();
+            for event_type in [EventType::FileRead, EventType::FileEdited, EventType::FileEdited] {
+                record_event(&db, &run.id, &ticket.id, event_type);
+            }
+            run
+        };
+        let finished = new_run(RunStatus::Finished);
+        let running = new_run(RunStatus::Running);
+
+        let cutoff = Utc::now() + chrono::Duration::days(1);
+        assert_eq!(db.compact_events(cutoff).unwrap(), (1, 3));
+        // Already compacted runs are left alone
+        assert_eq!(db.compact_events(cutoff).unwrap(), (0, 0));
+
+        let events = db.get_events(&finished.id).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::Custom(COMPACTED_EVENT_TYPE.to_string()));
+        let summary = events[0].payload.structured.as_ref().unwrap();
+        assert_eq!(summary["compactedEvents"], 3);
+        assert_eq!(summary["counts"]["file_edited"], 2);
+
+        assert_eq!(db.get_events(&running.id).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn archived_tickets_leave_the_board_and_can_be_restored() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let done = columns.iter().find(|c| c.name == "Done").unwrap();
+        let shipped = create_ticket(&db, &board.id, &done.id, "Shipped login");
+        create_ticket(&db, &board.id, &columns[0].id, "Still open");
+
+        // Nothing is old enough yet
+        assert_eq!(db.archive_done_tickets(Utc::now() - chrono::Duration::days(1)).unwrap(), 0);
+        assert_eq!(db.archive_done_tickets(Utc::now() + chrono::Duration::days(1)).unwrap(), 1);
+
+        let on_board = db.get_tickets(&board.id, None).unwrap();
+        assert_eq!(on_board.len(), 1);
+        assert_eq!(on_board[0].title, "Still open");
+
+        let found = db.search_archived_tickets(Some(&board.id), Some("LOGIN")).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].ticket.id, shipped.id);
+        assert!(db.search_archived_tickets(None, Some("open")).unwrap().is_empty());
+
+        let restored = db.restore_archived_ticket(&shipped.id, &Actor::user()).unwrap();
+        assert_eq!(restored.column_id, done.id);
+        assert_eq!(db.get_tickets(&board.id, None).unwrap().len(), 2);
+        assert!(matches!(
+            db.restore_archived_ticket(&shipped.id, &Actor::user()),
+            Err(DbError::NotFound(_))
+        ));
+
+        let archive_changes = db.get_ticket_history(&shipped.id).unwrap()
+            .into_iter()
+            .filter(|entry| entry.field == ARCHIVED_FIELD)
+            .count();
+        assert_eq!(archive_changes, 2);
+    }
+
+    #[test]
+    fn removes_orphans_and_vacuums() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+        let ticket = create_ticket(&db, &board.id, &columns[0].id, "Ticket");
+        db.with_conn(|conn| {
+            conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+            conn.execute(
+                "INSERT INTO comments (id, ticket_id, author_type, body_md) VALUES ('c1', 'gone', 'user', 'hi')",
+                [],
+            )?;
+            conn.execute_batch("PRAGMA foreign_keys = ON")?;
+            Ok(())
+        }).unwrap();
+
+        let report = db.apply_retention(&RetentionPolicy::default()).unwrap();
+        assert_eq!(report.orphans_removed, 1);
+        assert!(report.vacuumed);
+        assert_eq!(db.get_ticket(&ticket.id).unwrap().id, ticket.id);
+
+        // The first vacuum switched the database to incremental mode
+        let auto_vacuum: i64 = db.with_conn(|conn| {
+            Ok(conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?)
+        }).unwrap();
+        assert_eq!(auto_vacuum, 2);
+        db.vacuum().unwrap();
+    }
+}
@@ -1,9 +1,12 @@
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
 use crate::db::{Database, DbError, parse_datetime};
 use crate::db::models::{AgentRun, CreateRun, AgentType, RunStatus};
 
 impl Database {
     pub fn get_run(&self, run_id: &str) -> Result<AgentRun, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, agent_type, repo_path, status, 
                           started_at, ended_at, exit_code, summary_md, metadata_json,
@@ -43,41 +46,7 @@ impl Database {
     }
 
     pub fn create_run(&self, run: &CreateRun) -> Result<AgentRun, DbError> {
-        self.with_conn(|conn| {
-            let run_id = uuid::Uuid::new_v4().to_string();
-            let now = chrono::Utc::now();
-            
-            conn.execute(
-                r#"INSERT INTO agent_runs 
-                   (id, ticket_id, agent_type, repo_path, status, started_at, parent_run_id, stage)
-                   VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
-                rusqlite::params![
-                    run_id,
-                    run.ticket_id,
-                    run.agent_type.as_str(),
-                    run.repo_path,
-                    RunStatus::Queued.as_str(),
-                    now.to_rfc3339(),
-                    run.parent_run_id,
-                    run.stage,
-                ],
-            )?;
-
-            Ok(AgentRun {
-                id: run_id,
-                ticket_id: run.ticket_id.clone(),
-                agent_type: run.agent_type,
-                repo_path: run.repo_path.clone(),
-                status: RunStatus::Queued,
-                started_at: now,
-                ended_at: None,
-                exit_code: None,
-                summary_md: None,
-                metadata: None,
-                parent_run_id: run.parent_run_id.clone(),
-                stage: run.stage.clone(),
-            })
-        })
+        self.with_conn(|conn| insert_run(conn, run))
     }
 
     pub fn update_run_status(
@@ -87,24 +56,11 @@ impl Database {
         exit_code: Option<i32>,
         summary_md: Option<&str>,
     ) -> Result<(), DbError> {
-        self.with_conn(|conn| {
-            let now = chrono::Utc::now();
-            let ended_at = if matches!(status, RunStatus::Finished | RunStatus::Error | RunStatus::Aborted) {
-                Some(now.to_rfc3339())
-            } else {
-                None
-            };
-            
-            conn.execute(
-                "UPDATE agent_runs SET status = ?, ended_at = ?, exit_code = ?, summary_md = ? WHERE id = ?",
-                rusqlite::params![status.as_str(), ended_at, exit_code, summary_md, run_id],
-            )?;
-            Ok(())
-        })
+        self.with_conn(|conn| set_run_status(conn, run_id, status, exit_code, summary_md))
     }
 
     pub fn get_runs(&self, ticket_id: &str) -> Result<Vec<AgentRun>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, agent_type, repo_path, status, 
                           started_at, ended_at, exit_code, summary_md, metadata_json,
@@ -143,7 +99,7 @@ impl Database {
 
     /// Get recent runs across all tickets (for the runs view)
     pub fn get_recent_runs(&self, limit: u32) -> Result<Vec<AgentRun>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, agent_type, repo_path, status, 
                           started_at, ended_at, exit_code, summary_md, metadata_json,
@@ -185,7 +141,7 @@ impl Database {
 
     /// Get all sub-runs for a parent run
     pub fn get_sub_runs(&self, parent_run_id: &str) -> Result<Vec<AgentRun>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, agent_type, repo_path, status, 
                           started_at, ended_at, exit_code, summary_md, metadata_json,
@@ -245,6 +201,116 @@ impl Database {
             Ok(count as u32)
         })
     }
+
+    /// Record the process group of the agent a run is executing, together
+    /// with when it was started, or clear both once the group has exited
+    pub fn set_run_pid(&self, run_id: &str, pid: Option<u32>) -> Result<(), DbError> {
+        let started_at = pid.map(|_| chrono::Utc::now().to_rfc3339());
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE agent_runs SET pid = ?, pid_started_at = ? WHERE id = ?",
+                rusqlite::params![pid, started_at, run_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record how the agent of a run ended (see [`crate::agents::RunOutcome`]);
+    /// timeouts are counted per stage from this
+    pub fn set_run_outcome(&self, run_id: &str, outcome: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "UPDATE agent_runs SET outcome = ? WHERE id = ?",
+                rusqlite::params![outcome, run_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Runs that still have an agent process group recorded, with the time
+    /// the group was started where it is known
+    #[allow(clippy::type_complexity)]
+    pub fn get_run_pids(&self) -> Result<Vec<(String, u32, Option<DateTime<Utc>>)>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, pid, pid_started_at FROM agent_runs WHERE pid IS NOT NULL",
+            )?;
+            let pids = stmt
+                .query_map([], |row| {
+                    let started_at: Option<String> = row.get(2)?;
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        started_at
+                            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                            .map(|dt| dt.with_timezone(&Utc)),
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(pids)
+        })
+    }
+}
+
+/// [`Database::create_run`] on `conn`, for callers that create the run in
+/// the same transaction as other rows
+pub(crate) fn insert_run(conn: &Connection, run: &CreateRun) -> Result<AgentRun, DbError> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now();
+    
+    conn.execute(
+        r#"INSERT INTO agent_runs 
+           (id, ticket_id, agent_type, repo_path, status, started_at, parent_run_id, stage)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+        rusqlite::params![
+            run_id,
+            run.ticket_id,
+            run.agent_type.as_str(),
+            run.repo_path,
+            RunStatus::Queued.as_str(),
+            now.to_rfc3339(),
+            run.parent_run_id,
+            run.stage,
+        ],
+    )?;
+
+    Ok(AgentRun {
+        id: run_id,
+        ticket_id: run.ticket_id.clone(),
+        agent_type: run.agent_type,
+        repo_path: run.repo_path.clone(),
+        status: RunStatus::Queued,
+        started_at: now,
+        ended_at: None,
+        exit_code: None,
+        summary_md: None,
+        metadata: None,
+        parent_run_id: run.parent_run_id.clone(),
+        stage: run.stage.clone(),
+    })
+}
+
+/// [`Database::update_run_status`] on `conn`, for callers that change the run
+/// in the same transaction as other rows
+pub(crate) fn set_run_status(
+    conn: &Connection,
+    run_id: &str,
+    status: RunStatus,
+    exit_code: Option<i32>,
+    summary_md: Option<&str>,
+) -> Result<(), DbError> {
+    let now = chrono::Utc::now();
+    let ended_at = if matches!(status, RunStatus::Finished | RunStatus::Error | RunStatus::Aborted) {
+        Some(now.to_rfc3339())
+    } else {
+        None
+    };
+
+    conn.execute(
+        "UPDATE agent_runs SET status = ?, ended_at = ?, exit_code = ?, summary_md = ? WHERE id = ?",
+        rusqlite::params![status.as_str(), ended_at, exit_code, summary_md, run_id],
+    )?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -279,6 +345,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let run = db.create_run(&CreateRun {
@@ -320,6 +387,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let run = db.create_run(&CreateRun {
@@ -362,6 +430,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let created = db.create_run(&CreateRun {
@@ -387,6 +456,52 @@ mod tests {
         assert!(matches!(result, Err(DbError::NotFound(_))));
     }
 
+    #[test]
+    fn recorded_pids_are_listed_until_cleared() {
+        let db = create_test_db();
+        let board = db.create_board("Board").unwrap();
+        let columns = db.get_columns(&board.id).unwrap();
+
+        let ticket = db.create_ticket(&CreateTicket {
+            board_id: board.id.clone(),
+            column_id: columns[0].id.clone(),
+            title: "Ticket".to_string(),
+            description_md: "".to_string(),
+            priority: Priority::Low,
+            labels: vec![],
+            project_id: None,
+            agent_pref: None,
+            workflow_type: WorkflowType::default(),
+            model: None,
+            branch_name: None,
+            is_epic: false,
+            epic_id: None,
+            depends_on_epic_id: None,
+            depends_on_epic_ids: vec![],
+            scratchpad_id: None,
+            custom_fields: Default::default(),
+        }).unwrap();
+
+        let run = db.create_run(&CreateRun {
+            ticket_id: ticket.id.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: "/tmp".to_string(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        assert!(db.get_run_pids().unwrap().is_empty());
+
+        db.set_run_pid(&run.id, Some(4242)).unwrap();
+        let pids = db.get_run_pids().unwrap();
+        assert_eq!(pids.len(), 1);
+        assert_eq!((pids[0].0.as_str(), pids[0].1), (run.id.as_str(), 4242));
+        let started_at = pids[0].2.expect("start time recorded");
+        assert!((chrono::Utc::now() - started_at).num_seconds() < 5);
+
+        db.set_run_pid(&run.id, None).unwrap();
+        assert!(db.get_run_pids().unwrap().is_empty());
+    }
+
     #[test]
     fn update_and_get_run_artifacts() {
         use crate::db::RunArtifacts;
@@ -412,6 +527,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let run = db.create_run(&CreateRun {
@@ -465,6 +581,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let run = db.create_run(&CreateRun {
@@ -0,0 +1,521 @@
+//! Scheduling policy: which ready ticket a worker reserves next.
+//!
+//! `reserve_next_ticket` collects the tickets a worker may take and lets the
+//! installation's policy rank them. [`Database::explain_queue`] returns the
+//! same ranking with the reason behind each position.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::AgentKind;
+use crate::db::models::{CustomFieldValues, LabelFilter, Priority};
+use crate::db::settings::{read_setting, write_setting};
+use crate::db::{parse_datetime, Database, DbError};
+
+const POLICY_KEY: &str = "scheduling_policy";
+
+/// How ready tickets are ordered; one per installation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum SchedulingPolicy {
+    /// Highest priority first, oldest first within a priority
+    #[default]
+    StrictPriority,
+    /// Boards and projects take turns in proportion to their weights (by id
+    /// or name, 1 when unset), judged by the runs started for them in the
+    /// last `window_hours`
+    FairShare {
+        #[serde(default)]
+        board_weights: BTreeMap<String, f64>,
+        #[serde(default)]
+        project_weights: BTreeMap<String, f64>,
+        #[serde(default = "default_window_hours")]
+        window_hours: u32,
+    },
+    /// Every `hours_per_level` in Ready raises a ticket one priority level
+    PriorityAging {
+        #[serde(default = "default_hours_per_level")]
+        hours_per_level: f64,
+    },
+    /// Earliest date in the custom field `field` first; tickets without one
+    /// follow in priority order
+    DeadlineFirst {
+        #[serde(default = "default_deadline_field")]
+        field: String,
+    },
+}
+
+fn default_window_hours() -> u32 {
+    24
+}
+
+fn default_hours_per_level() -> f64 {
+    24.0
+}
+
+fn default_deadline_field() -> String {
+    "deadline".to_string()
+}
+
+impl SchedulingPolicy {
+    pub fn validate(&self) -> Result<(), DbError> {
+        let invalid = |msg: &str| Err(DbError::Validation(msg.to_string()));
+        match self {
+            SchedulingPolicy::StrictPriority => Ok(()),
+            SchedulingPolicy::FairShare { board_weights, project_weights, window_hours } => {
+                if *window_hours == 0 {
+                    return invalid("windowHours must be at least 1");
+                }
+                if board_weights.values().chain(project_weights.values()).any(|w| !w.is_finite() || *w <= 0.0) {
+                    return invalid("Fair-share weights must be positive numbers");
+                }
+                Ok(())
+            }
+            SchedulingPolicy::PriorityAging { hours_per_level } => {
+                if !hours_per_level.is_finite() || *hours_per_level <= 0.0 {
+                    return invalid("hoursPerLevel must be a positive number");
+                }
+                Ok(())
+            }
+            SchedulingPolicy::DeadlineFirst { field } => {
+                if field.trim().is_empty() {
+                    return invalid("The deadline field key cannot be empty");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Which tickets a worker may take
+#[derive(Debug, Clone, Default)]
+pub struct QueueFilter {
+    /// Only tickets of these projects; empty means any project
+    pub project_ids: Vec<String>,
+    /// Skip tickets preferring another agent; `None` ignores agent preferences
+    pub agent_type: Option<AgentKind>,
+    pub labels: LabelFilter,
+}
+
+/// A ready ticket in the order the policy would hand it out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueEntry {
+    pub ticket_id: String,
+    pub title: String,
+    pub board_id: String,
+    pub board_name: String,
+    pub project_id: Option<String>,
+    pub priority: Priority,
+    /// When the ticket entered its Ready column
+    pub waiting_since: DateTime<Utc>,
+    /// Priority after aging (`priority_aging` only)
+    pub effective_priority: Option<Priority>,
+    /// `deadline_first` only
+    pub deadline: Option<NaiveDate>,
+    /// Weighted runs its board and project had in the window (`fair_share` only)
+    pub share: Option<f64>,
+    pub reason: String,
+    #[serde(skip)]
+    created_at: DateTime<Utc>,
+    #[serde(skip)]
+    project_name: Option<String>,
+    #[serde(skip)]
+    custom_fields: CustomFieldValues,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueExplanation {
+    pub policy: SchedulingPolicy,
+    /// Tickets the worker could take
+    pub eligible: usize,
+    /// The ticket a worker would reserve now
+    pub next: Option<QueueEntry>,
+    /// Eligible tickets, best first
+    pub ranking: Vec<QueueEntry>,
+}
+
+impl Database {
+    pub fn get_scheduling_policy(&self) -> Result<SchedulingPolicy, DbError> {
+        self.with_reader(load_policy)
+    }
+
+    pub fn set_scheduling_policy(&self, policy: &SchedulingPolicy) -> Result<SchedulingPolicy, DbError> {
+        policy.validate()?;
+        self.with_conn(|conn| write_setting(conn, POLICY_KEY, policy))?;
+        Ok(policy.clone())
+    }
+
+    /// Rank the tickets a worker with `filter` could take, keeping the best
+    /// `limit`
+    pub fn explain_queue(&self, filter: &QueueFilter, limit: usize) -> Result<QueueExplanation, DbError> {
+        self.with_reader(|conn| {
+            let policy = load_policy(conn)?;
+            let mut ranking = rank(conn, &policy, eligible(conn, filter, Utc::now())?, Utc::now())?;
+            let eligible = ranking.len();
+            ranking.truncate(limit);
+            Ok(QueueExplanation {
+                policy,
+                eligible,
+                next: ranking.first().cloned(),
+                ranking,
+            })
+        })
+    }
+}
+
+/// Id of the ticket the installation's policy hands out next
+pub(crate) fn next_ticket(conn: &Connection, filter: &QueueFilter, now: DateTime<Utc>) -> Result<Option<String>, DbError> {
+    let policy = load_policy(conn)?;
+    let ranking = rank(conn, &policy, eligible(conn, filter, now)?, now)?;
+    Ok(ranking.into_iter().next().map(|entry| entry.ticket_id))
+}
+
+fn load_policy(conn: &Connection) -> Result<SchedulingPolicy, DbError> {
+    read_setting(conn, POLICY_KEY)
+}
+
+/// Unlocked non-epic tickets in Ready columns matching `filter`
+fn eligible(conn: &Connection, filter: &QueueFilter, now: DateTime<Utc>) -> Result<Vec<QueueEntry>, DbError> {
+    let projects_json = serde_json::to_string(&filter.project_ids).unwrap_or_else(|_| "[]".to_string());
+    let include_json = serde_json::to_string(&filter.labels.include).unwrap_or_else(|_| "[]".to_string());
+    let exclude_json = serde_json::to_string(&filter.labels.exclude).unwrap_or_else(|_| "[]".to_string());
+    let mut stmt = conn.prepare(
+        r#"SELECT t.id, t.title, t.board_id, b.name, t.project_id, p.name, t.priority, t.created_at,
+                  COALESCE((SELECT MAX(h.created_at) FROM ticket_history h
+                            WHERE h.ticket_id = t.id AND h.field = 'columnId' AND h.new_value = t.column_id),
+                           t.created_at),
+                  t.custom_fields_json
+           FROM tickets t
+           JOIN columns c ON t.column_id = c.id
+           JOIN boards b ON t.board_id = b.id
+           LEFT JOIN projects p ON t.project_id = p.id
+           WHERE c.role = 'ready'
+             AND t.is_epic = 0
+             AND t.trash_id IS NULL
+             AND (t.locked_by_run_id IS NULL OR t.lock_expires_at < ?1)
+             AND (json_array_length(?2) = 0 OR t.project_id IN (SELECT value FROM json_each(?2)))
+             AND (
+                 ?3 IS NULL
+                 OR t.agent_pref IS NULL
+                 OR t.agent_pref = 'any'
+                 OR t.agent_pref = ?3
+             )
+             AND (
+                 json_array_length(?4) = 0
+                 OR EXISTS (SELECT 1 FROM json_each(t.labels_json) l, json_each(?4) f
+                            WHERE l.value = f.value COLLATE NOCASE)
+             )
+             AND NOT EXISTS (SELECT 1 FROM json_each(t.labels_json) l, json_each(?5) f
+                             WHERE l.value = f.value COLLATE NOCASE)"#,
+    )?;
+    let rows = stmt.query_map(
+        rusqlite::params![
+            now.to_rfc3339(),
+            projects_json,
+            filter.agent_type.map(|k| k.as_str()),
+            include_json,
+            exclude_json,
+        ],
+        |row| {
+            let priority: String = row.get(6)?;
+            let custom_fields: String = row.get(9)?;
+            Ok(QueueEntry {
+                ticket_id: row.get(0)?,
+                title: row.get(1)?,
+                board_id: row.get(2)?,
+                board_name: row.get(3)?,
+                project_id: row.get(4)?,
+                project_name: row.get(5)?,
+                priority: Priority::parse(&priority).unwrap_or(Priority::Medium),
+                created_at: parse_datetime(row.get(7)?),
+                waiting_since: parse_datetime(row.get(8)?),
+                custom_fields: serde_json::from_str(&custom_fields).unwrap_or_default(),
+                effective_priority: None,
+                deadline: None,
+                share: None,
+                reason: String::new(),
+            })
+        },
+    )?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+}
+
+/// 0 for urgent through 3 for low
+fn level(priority: &Priority) -> u32 {
+    match priority {
+        Priority::Urgent => 0,
+        Priority::High => 1,
+        Priority::Medium => 2,
+        Priority::Low => 3,
+    }
+}
+
+fn priority_at(level: u32) -> Priority {
+    match level {
+        0 => Priority::Urgent,
+        1 => Priority::High,
+        2 => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+fn by_priority(a: &QueueEntry, b: &QueueEntry) -> Ordering {
+    level(&a.priority)
+        .cmp(&level(&b.priority))
+        .then(a.created_at.cmp(&b.created_at))
+}
+
+/// Order `entries` by `policy`, best first, filling in each entry's reason
+fn rank(
+    conn: &Connection,
+    policy: &SchedulingPolicy,
+    mut entries: Vec<QueueEntry>,
+    now: DateTime<Utc>,
+) -> Result<Vec<QueueEntry>, DbError> {
+    match policy {
+        SchedulingPolicy::StrictPriority => {
+            entries.sort_by(by_priority);
+            for entry in &mut entries {
+                entry.reason = format!("{} priority, created {}", entry.priority.as_str(), entry.created_at.format("%Y-%m-%d %H:%M"));
+            }
+        }
+        SchedulingPolicy::PriorityAging { hours_per_level } => {
+            for entry in &mut entries {
+                let hours = (now - entry.waiting_since).num_seconds().max(0) as f64 / 3600.0;
+                let raised = (hours / hours_per_level).floor() as u32;
+                let effective = priority_at(level(&entry.priority).saturating_sub(raised));
+                entry.reason = if effective == entry.priority {
+                    format!("{} priority, {:.0}h in Ready", entry.priority.as_str(), hours)
+                } else {
+                    format!(
+                        "{} priority raised to {} after {:.0}h in Ready",
+                        entry.priority.as_str(),
+                        effective.as_str(),
+                        hours
+                    )
+                };
+                entry.effective_priority = Some(effective);
+            }
+            entries.sort_by(|a, b| {
+                let effective = |e: &QueueEntry| e.effective_priority.as_ref().map(level).unwrap_or(3);
+                effective(a)
+                    .cmp(&effective(b))
+                    .then(a.waiting_since.cmp(&b.waiting_since))
+                    .then_with(|| by_priority(a, b))
+            });
+        }
+        SchedulingPolicy::DeadlineFirst { field } => {
+            for entry in &mut entries {
+                entry.deadline = entry
+                    .custom_fields
+                    .get(field)
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+                entry.reason = match entry.deadline {
+                    Some(deadline) if deadline < now.date_naive() => format!("Overdue since {}", deadline),
+                    Some(deadline) => format!("Due {}", deadline),
+                    None => format!("No {} set; {} priority", field, entry.priority.as_str()),
+                };
+            }
+            entries.sort_by(|a, b| match (a.deadline, b.deadline) {
+                (Some(x), Some(y)) => x.cmp(&y).then_with(|| by_priority(a, b)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => by_priority(a, b),
+            });
+        }
+        SchedulingPolicy::FairShare { board_weights, project_weights, window_hours } => {
+            let since = now - chrono::Duration::hours(i64::from(*window_hours));
+            let (board_runs, project_runs) = recent_runs(conn, since)?;
+            let weight = |weights: &BTreeMap<String, f64>, id: &str, name: Option<&str>| {
+                weights
+                    .get(id)
+                    .or_else(|| name.and_then(|n| weights.get(n)))
+                    .copied()
+                    .unwrap_or(1.0)
+            };
+            for entry in &mut entries {
+                let board = *board_runs.get(&entry.board_id).unwrap_or(&0) as f64;
+                let board_share = board / weight(board_weights, &entry.board_id, Some(&entry.board_name));
+                let project_share = match &entry.project_id {
+                    Some(id) => {
+                        *project_runs.get(id).unwrap_or(&0) as f64
+                            / weight(project_weights, id, entry.project_name.as_deref())
+                    }
+                    None => 0.0,
+                };
+                entry.share = Some(board_share + project_share);
+                entry.reason = format!(
+                    "Board {} and its project had {:.2} weighted runs in the last {}h; {} priority",
+                    entry.board_name,
+                    board_share + project_share,
+                    window_hours,
+                    entry.priority.as_str()
+                );
+            }
+            entries.sort_by(|a, b| {
+                a.share
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.share.unwrap_or(0.0))
+                    .then_with(|| by_priority(a, b))
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Run counts keyed by board or project id
+type RunCounts = HashMap<String, i64>;
+
+/// Top-level runs started since `since`, per board and per project
+fn recent_runs(conn: &Connection, since: DateTime<Utc>) -> Result<(RunCounts, RunCounts), DbError> {
+    let mut stmt = conn.prepare(
+        r#"SELECT t.board_id, t.project_id, COUNT(*)
+           FROM agent_runs r JOIN tickets t ON r.ticket_id = t.id
+           WHERE r.parent_run_id IS NULL AND r.started_at >= ?
+           GROUP BY t.board_id, t.project_id"#,
+    )?;
+    let rows = stmt.query_map([since.to_rfc3339()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, i64>(2)?))
+    })?;
+
+    let mut boards = HashMap::new();
+    let mut projects = HashMap::new();
+    for row in rows {
+        let (board_id, project_id, count) = row?;
+        *boards.entry(board_id).or_insert(0) += count;
+        if let Some(project_id) = project_id {
+            *projects.entry(project_id).or_insert(0) += count;
+        }
+    }
+    Ok((boards, projects))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{Actor, CreateRun, CreateTicket, AgentType};
+    use crate::lifecycle::TicketState;
+
+    fn ready_ticket(db: &Database, board_id: &str, title: &str, priority: Priority) -> String {
+        let ready = db.require_column_by_role(board_id, TicketState::Ready).unwrap();
+        db.create_ticket(&CreateTicket {
+            priority,
+            ..CreateTicket::for_test(board_id, &ready.id, title)
+        }).unwrap().id
+    }
+
+    fn next_title(db: &Database) -> String {
+        db.explain_queue(&QueueFilter::default(), 10).unwrap().next.unwrap().title
+    }
+
+    #[test]
+    fn policy_round_trips_and_validates() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_scheduling_policy().unwrap(), SchedulingPolicy::StrictPriority);
+
+        let policy: SchedulingPolicy = serde_json::from_value(serde_json::json!({
+            "policy": "priority_aging",
+            "hoursPerLevel": 6.0,
+        })).unwrap();
+        db.set_scheduling_policy(&policy).unwrap();
+        assert_eq!(db.get_scheduling_policy().unwrap(), policy);
+
+        let err = db.set_scheduling_policy(&SchedulingPolicy::PriorityAging { hours_per_level: 0.0 }).unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)));
+    }
+
+    #[test]
+    fn strict_priority_prefers_urgent_then_oldest() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        ready_ticket(&db, &board.id, "old low", Priority::Low);
+        ready_ticket(&db, &board.id, "urgent", Priority::Urgent);
+
+        let explanation = db.explain_queue(&QueueFilter::default(), 10).unwrap();
+        assert_eq!(explanation.eligible, 2);
+        assert_eq!(explanation.next.unwrap().title, "urgent");
+    }
+
+    #[test]
+    fn aging_raises_long_waiting_tickets() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let old = ready_ticket(&db, &board.id, "old low", Priority::Low);
+        ready_ticket(&db, &board.id, "fresh high", Priority::High);
+        let three_days_ago = (Utc::now() - chrono::Duration::days(3)).to_rfc3339();
+        db.with_conn(|conn| {
+            conn.execute("UPDATE tickets SET created_at = ? WHERE id = ?", rusqlite::params![three_days_ago, old])?;
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(next_title(&db), "fresh high");
+        db.set_scheduling_policy(&SchedulingPolicy::PriorityAging { hours_per_level: 24.0 }).unwrap();
+        let next = db.explain_queue(&QueueFilter::default(), 10).unwrap().next.unwrap();
+        assert_eq!(next.title, "old low");
+        assert_eq!(next.effective_priority, Some(Priority::Urgent));
+    }
+
+    #[test]
+    fn deadline_first_uses_the_date_field() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        ready_ticket(&db, &board.id, "urgent", Priority::Urgent);
+        let due = ready_ticket(&db, &board.id, "due soon", Priority::Low);
+        db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE tickets SET custom_fields_json = '{\"due\":\"2026-01-31\"}' WHERE id = ?",
+                [&due],
+            )?;
+            Ok(())
+        }).unwrap();
+
+        db.set_scheduling_policy(&SchedulingPolicy::DeadlineFirst { field: "due".to_string() }).unwrap();
+        let explanation = db.explain_queue(&QueueFilter::default(), 10).unwrap();
+        assert_eq!(explanation.ranking[0].title, "due soon");
+        assert_eq!(explanation.ranking[1].reason, "No due set; urgent priority");
+    }
+
+    #[test]
+    fn fair_share_lets_a_quiet_board_go_first() {
+        let db = Database::open_in_memory().unwrap();
+        let busy = db.create_board("Busy").unwrap();
+        let quiet = db.create_board("Quiet").unwrap();
+        let served = ready_ticket(&db, &busy.id, "busy done", Priority::Urgent);
+        db.create_run(&CreateRun {
+            ticket_id: served.clone(),
+            agent_type: AgentType::Claude,
+            repo_path: String::new(),
+            parent_run_id: None,
+            stage: None,
+        }).unwrap();
+        let done = db.require_column_by_role(&busy.id, TicketState::Done).unwrap();
+        db.move_ticket(&served, &done.id, &Actor::user()).unwrap();
+        ready_ticket(&db, &busy.id, "busy urgent", Priority::Urgent);
+        ready_ticket(&db, &quiet.id, "quiet low", Priority::Low);
+
+        db.set_scheduling_policy(&SchedulingPolicy::FairShare {
+            board_weights: BTreeMap::new(),
+            project_weights: BTreeMap::new(),
+            window_hours: 24,
+        }).unwrap();
+        assert_eq!(next_title(&db), "quiet low");
+
+        // Reservation follows the same order
+        let reserved = db.reserve_next_ticket(
+            None,
+            AgentKind::Claude,
+            &LabelFilter::default(),
+            "run-1",
+            Utc::now() + chrono::Duration::minutes(5),
+            &Actor::system(),
+        ).unwrap().unwrap();
+        assert_eq!(reserved.title, "quiet low");
+    }
+}
@@ -1,11 +1,12 @@
-//! Database schema definitions and migrations
+//! Schema migration SQL, applied in order by the registry in `migrations.rs`
 
-pub const SCHEMA_VERSION: i32 = 13;
+use crate::lifecycle::TicketState;
 
-/// Initial schema creation SQL
-pub const CREATE_TABLES: &str = r#"
+/// Schema version 1: the tables as they were before versioned migrations.
+/// Versions 1 and 2 were never distinguished, so there is no migration 2.
+pub const MIGRATION_V1: &str = r#"
 -- Projects table (registered repositories for agent work)
-CREATE TABLE IF NOT EXISTS projects (
+CREATE TABLE projects (
     id TEXT PRIMARY KEY NOT NULL,
     name TEXT NOT NULL,
     path TEXT NOT NULL UNIQUE,
@@ -25,17 +26,14 @@ CREATE TABLE IF NOT EXISTS projects (
     -- General settings
     settings_json TEXT NOT NULL DEFAULT '{}',
     
-    -- Whether this project requires git (default true for backward compatibility)
-    requires_git INTEGER NOT NULL DEFAULT 1,
-    
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now'))
 );
 
-CREATE INDEX IF NOT EXISTS idx_projects_path ON projects(path);
+CREATE INDEX idx_projects_path ON projects(path);
 
 -- Boards table
-CREATE TABLE IF NOT EXISTS boards (
+CREATE TABLE boards (
     id TEXT PRIMARY KEY NOT NULL,
     name TEXT NOT NULL,
     default_project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
@@ -44,7 +42,7 @@ CREATE TABLE IF NOT EXISTS boards (
 );
 
 -- Columns table (kanban columns within a board)
-CREATE TABLE IF NOT EXISTS columns (
+CREATE TABLE columns (
     id TEXT PRIMARY KEY NOT NULL,
     board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -53,38 +51,13 @@ CREATE TABLE IF NOT EXISTS columns (
     UNIQUE(board_id, position)
 );
 
-CREATE INDEX IF NOT EXISTS idx_columns_board ON columns(board_id);
-
--- Scratchpads table (for planner agent)
--- Note: Must be created before tickets table since tickets references scratchpads(id)
-CREATE TABLE IF NOT EXISTS scratchpads (
-    id TEXT PRIMARY KEY NOT NULL,
-    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
-    target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
-    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-    name TEXT NOT NULL,
-    user_input TEXT NOT NULL,
-    status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
-    agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
-    model TEXT,
-    exploration_log TEXT,
-    plan_markdown TEXT,
-    plan_json TEXT,
-    settings_json TEXT NOT NULL DEFAULT '{}',
-    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-);
-
-CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
-CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
-CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
-CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
+CREATE INDEX idx_columns_board ON columns(board_id);
 
 -- Tickets table
 -- Note: locked_by_run_id intentionally omits FK constraint to avoid circular
 -- dependency with agent_runs table. Referential integrity is maintained at
 -- the application level.
-CREATE TABLE IF NOT EXISTS tickets (
+CREATE TABLE tickets (
     id TEXT PRIMARY KEY NOT NULL,
     board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
     column_id TEXT NOT NULL REFERENCES columns(id) ON DELETE RESTRICT,
@@ -97,32 +70,16 @@ CREATE TABLE IF NOT EXISTS tickets (
     locked_by_run_id TEXT,
     lock_expires_at TEXT,
     project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
-    agent_pref TEXT CHECK(agent_pref IN ('cursor', 'claude', 'any')),
-    workflow_type TEXT NOT NULL DEFAULT 'multi_stage' CHECK(workflow_type IN ('multi_stage')),
-    model TEXT,
-    branch_name TEXT,
-    -- Epic support: is_epic marks this ticket as an epic, epic_id references parent epic
-    is_epic INTEGER NOT NULL DEFAULT 0,
-    epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL,
-    order_in_epic INTEGER,
-    -- Cross-epic dependency: which epic must complete before this epic can start (primary dependency)
-    depends_on_epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL,
-    -- All epic dependencies as JSON array of IDs (for display purposes)
-    depends_on_epic_ids_json TEXT,
-    -- Link back to scratchpad that created this ticket
-    scratchpad_id TEXT REFERENCES scratchpads(id) ON DELETE SET NULL
+    agent_pref TEXT CHECK(agent_pref IN ('cursor', 'claude', 'any'))
 );
 
-CREATE INDEX IF NOT EXISTS idx_tickets_board ON tickets(board_id);
-CREATE INDEX IF NOT EXISTS idx_tickets_column ON tickets(column_id);
-CREATE INDEX IF NOT EXISTS idx_tickets_locked ON tickets(locked_by_run_id) WHERE locked_by_run_id IS NOT NULL;
-CREATE INDEX IF NOT EXISTS idx_tickets_project ON tickets(project_id);
-CREATE INDEX IF NOT EXISTS idx_tickets_epic ON tickets(epic_id, order_in_epic) WHERE epic_id IS NOT NULL;
-CREATE INDEX IF NOT EXISTS idx_tickets_depends_on ON tickets(depends_on_epic_id) WHERE depends_on_epic_id IS NOT NULL;
-CREATE INDEX IF NOT EXISTS idx_tickets_scratchpad ON tickets(scratchpad_id) WHERE scratchpad_id IS NOT NULL;
+CREATE INDEX idx_tickets_board ON tickets(board_id);
+CREATE INDEX idx_tickets_column ON tickets(column_id);
+CREATE INDEX idx_tickets_locked ON tickets(locked_by_run_id) WHERE locked_by_run_id IS NOT NULL;
+CREATE INDEX idx_tickets_project ON tickets(project_id);
 
 -- Comments table
-CREATE TABLE IF NOT EXISTS comments (
+CREATE TABLE comments (
     id TEXT PRIMARY KEY NOT NULL,
     ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
     author_type TEXT NOT NULL CHECK(author_type IN ('user', 'agent', 'system')),
@@ -131,10 +88,10 @@ CREATE TABLE IF NOT EXISTS comments (
     metadata_json TEXT
 );
 
-CREATE INDEX IF NOT EXISTS idx_comments_ticket ON comments(ticket_id);
+CREATE INDEX idx_comments_ticket ON comments(ticket_id);
 
 -- Agent runs table
-CREATE TABLE IF NOT EXISTS agent_runs (
+CREATE TABLE agent_runs (
     id TEXT PRIMARY KEY NOT NULL,
     ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
     agent_type TEXT NOT NULL CHECK(agent_type IN ('cursor', 'claude')),
@@ -144,17 +101,14 @@ CREATE TABLE IF NOT EXISTS agent_runs (
     ended_at TEXT,
     exit_code INTEGER,
     summary_md TEXT,
-    metadata_json TEXT,
-    parent_run_id TEXT REFERENCES agent_runs(id) ON DELETE CASCADE,
-    stage TEXT
+    metadata_json TEXT
 );
 
-CREATE INDEX IF NOT EXISTS idx_runs_ticket ON agent_runs(ticket_id);
-CREATE INDEX IF NOT EXISTS idx_runs_status ON agent_runs(status);
-CREATE INDEX IF NOT EXISTS idx_runs_parent ON agent_runs(parent_run_id) WHERE parent_run_id IS NOT NULL;
+CREATE INDEX idx_runs_ticket ON agent_runs(ticket_id);
+CREATE INDEX idx_runs_status ON agent_runs(status);
 
 -- Agent events table (audit trail for hook events)
-CREATE TABLE IF NOT EXISTS agent_events (
+CREATE TABLE agent_events (
     id TEXT PRIMARY KEY NOT NULL,
     run_id TEXT NOT NULL REFERENCES agent_runs(id) ON DELETE CASCADE,
     ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
@@ -163,52 +117,14 @@ CREATE TABLE IF NOT EXISTS agent_events (
     created_at TEXT NOT NULL DEFAULT (datetime('now'))
 );
 
-CREATE INDEX IF NOT EXISTS idx_events_run ON agent_events(run_id);
-CREATE INDEX IF NOT EXISTS idx_events_ticket ON agent_events(ticket_id);
-CREATE INDEX IF NOT EXISTS idx_events_type ON agent_events(event_type);
-
--- Schema version tracking
-CREATE TABLE IF NOT EXISTS schema_version (
-    version INTEGER PRIMARY KEY,
-    applied_at TEXT NOT NULL DEFAULT (datetime('now'))
-);
-
--- Repository-level locks to prevent multiple workers processing same repo
-CREATE TABLE IF NOT EXISTS repo_locks (
-    project_id TEXT PRIMARY KEY NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
-    locked_by_run_id TEXT NOT NULL,
-    lock_expires_at TEXT NOT NULL,
-    locked_at TEXT NOT NULL
-);
-
-CREATE INDEX IF NOT EXISTS idx_repo_locks_expires ON repo_locks(lock_expires_at);
-
--- Tasks table (task queue for tickets)
-CREATE TABLE IF NOT EXISTS tasks (
-    id TEXT PRIMARY KEY NOT NULL,
-    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
-    order_index INTEGER NOT NULL,
-    task_type TEXT NOT NULL DEFAULT 'custom' CHECK(task_type IN ('custom', 'sync_with_main', 'add_tests', 'review_polish', 'fix_lint')),
-    title TEXT,
-    content TEXT,
-    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'in_progress', 'completed', 'failed')),
-    run_id TEXT,
-    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-    started_at TEXT,
-    completed_at TEXT
-);
-
-CREATE INDEX IF NOT EXISTS idx_tasks_ticket ON tasks(ticket_id);
-CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
-CREATE INDEX IF NOT EXISTS idx_tasks_order ON tasks(ticket_id, order_index);
+CREATE INDEX idx_events_run ON agent_events(run_id);
+CREATE INDEX idx_events_ticket ON agent_events(ticket_id);
+CREATE INDEX idx_events_type ON agent_events(event_type);
 "#;
 
 /// Migration SQL for schema version 3
-/// Adds repo_locks table and requires_git column to projects
+/// Adds repo_locks table (the requires_git column is added by the registry)
 pub const MIGRATION_V3: &str = r#"
--- Add requires_git column to projects (defaults to true for backward compatibility)
-ALTER TABLE projects ADD COLUMN requires_git INTEGER NOT NULL DEFAULT 1;
-
 -- Repository-level locks to prevent multiple workers processing same repo
 CREATE TABLE IF NOT EXISTS repo_locks (
     project_id TEXT PRIMARY KEY NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
@@ -221,28 +137,12 @@ CREATE INDEX IF NOT EXISTS idx_repo_locks_expires ON repo_locks(lock_expires_at)
 "#;
 
 /// Migration SQL for schema version 4
-/// Adds workflow_type to tickets, parent_run_id and stage to agent_runs
+/// Indexes the parent_run_id column added to agent_runs
 pub const MIGRATION_V4: &str = r#"
--- Add workflow_type column to tickets (defaults to 'basic' for backward compatibility)
-ALTER TABLE tickets ADD COLUMN workflow_type TEXT NOT NULL DEFAULT 'basic';
-
--- Add parent_run_id column to agent_runs for sub-run tracking
-ALTER TABLE agent_runs ADD COLUMN parent_run_id TEXT REFERENCES agent_runs(id) ON DELETE CASCADE;
-
--- Add stage column to agent_runs for tracking workflow stages
-ALTER TABLE agent_runs ADD COLUMN stage TEXT;
-
 -- Index for efficient sub-run queries
 CREATE INDEX IF NOT EXISTS idx_runs_parent ON agent_runs(parent_run_id) WHERE parent_run_id IS NOT NULL;
 "#;
 
-/// Migration SQL for schema version 5
-/// Adds model column to tickets for per-ticket AI model selection
-pub const MIGRATION_V5: &str = r#"
--- Add model column to tickets for AI model selection (e.g., 'claude-opus-4-5')
-ALTER TABLE tickets ADD COLUMN model TEXT;
-"#;
-
 /// Migration SQL for schema version 6
 /// Removes single-shot (basic) workflow - all tickets use multi_stage
 pub const MIGRATION_V6: &str = r#"
@@ -250,15 +150,8 @@ pub const MIGRATION_V6: &str = r#"
 UPDATE tickets SET workflow_type = 'multi_stage' WHERE workflow_type = 'basic';
 "#;
 
-/// Migration SQL for schema version 7
-/// Adds branch_name column to tickets for storing agent-generated branch names
-pub const MIGRATION_V7: &str = r#"
--- Add branch_name column to tickets for persistent branch tracking
-ALTER TABLE tickets ADD COLUMN branch_name TEXT;
-"#;
-
 /// Migration SQL for schema version 8
-/// Adds tasks table for task queue system
+/// Adds tasks table for task queue system, with a first task for every ticket
 pub const MIGRATION_V8: &str = r#"
 -- Tasks table (task queue for tickets)
 CREATE TABLE IF NOT EXISTS tasks (
@@ -278,29 +171,41 @@ CREATE TABLE IF NOT EXISTS tasks (
 CREATE INDEX IF NOT EXISTS idx_tasks_ticket ON tasks(ticket_id);
 CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
 CREATE INDEX IF NOT EXISTS idx_tasks_order ON tasks(ticket_id, order_index);
+
+-- Task 1 for existing tickets, based on their column.
+-- SQLite's length() and substr() count code points like the Rust side does.
+INSERT INTO tasks (id, ticket_id, order_index, task_type, title, content, status, created_at)
+SELECT 
+    lower(hex(randomblob(16))),
+    t.id,
+    0,
+    'custom',
+    CASE 
+        WHEN length(t.title) > 50 THEN substr(t.title, 1, 47) || '...'
+        ELSE t.title
+    END,
+    CASE WHEN t.description_md = '' THEN NULL ELSE t.description_md END,
+    CASE 
+        WHEN c.name IN ('Done', 'Review') THEN 'completed'
+        WHEN c.name = 'In Progress' THEN 'in_progress'
+        ELSE 'pending'
+    END,
+    t.created_at
+FROM tickets t
+JOIN columns c ON t.column_id = c.id
+WHERE NOT EXISTS (SELECT 1 FROM tasks WHERE ticket_id = t.id);
 "#;
 
 /// Migration SQL for schema version 9
-/// Adds epic support: is_epic, epic_id, order_in_epic columns
+/// Indexes the epic columns added to tickets
 pub const MIGRATION_V9: &str = r#"
--- Add epic columns to tickets
-ALTER TABLE tickets ADD COLUMN is_epic INTEGER NOT NULL DEFAULT 0;
-ALTER TABLE tickets ADD COLUMN epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL;
-ALTER TABLE tickets ADD COLUMN order_in_epic INTEGER;
-
 -- Index for efficient epic children queries
 CREATE INDEX IF NOT EXISTS idx_tickets_epic ON tickets(epic_id, order_in_epic) WHERE epic_id IS NOT NULL;
 "#;
 
 /// Migration SQL for schema version 10
-/// Adds scratchpads table and epic dependency columns
+/// Adds scratchpads table and indexes the epic dependency columns
 pub const MIGRATION_V10: &str = r#"
--- Add depends_on_epic_id column to tickets for cross-epic dependencies
-ALTER TABLE tickets ADD COLUMN depends_on_epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL;
-
--- Add scratchpad_id column to tickets to link back to generating scratchpad
-ALTER TABLE tickets ADD COLUMN scratchpad_id TEXT REFERENCES scratchpads(id) ON DELETE SET NULL;
-
 -- Create scratchpads table
 CREATE TABLE IF NOT EXISTS scratchpads (
     id TEXT PRIMARY KEY NOT NULL,
@@ -308,7 +213,7 @@ CREATE TABLE IF NOT EXISTS scratchpads (
     project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
     user_input TEXT NOT NULL,
-        status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
+    status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
     agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
     model TEXT,
     exploration_log TEXT,
@@ -329,19 +234,328 @@ CREATE INDEX IF NOT EXISTS idx_tickets_depends_on ON tickets(depends_on_epic_id)
 CREATE INDEX IF NOT EXISTS idx_tickets_scratchpad ON tickets(scratchpad_id) WHERE scratchpad_id IS NOT NULL;
 "#;
 
-/// Migration SQL for schema version 13
-/// Adds depends_on_epic_ids_json column for storing multiple dependencies
-pub const MIGRATION_V13: &str = r#"
--- Add depends_on_epic_ids_json column to store all epic dependencies as JSON array
-ALTER TABLE tickets ADD COLUMN depends_on_epic_ids_json TEXT;
+/// Migration SQL for schema version 11
+/// Indexes the target_board_id column added to scratchpads
+pub const MIGRATION_V11: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
+"#;
+
+/// Migration SQL for schema version 12
+/// Recreates scratchpads with the full status CHECK constraint (SQLite cannot
+/// alter constraints in place)
+pub const MIGRATION_V12: &str = r#"
+CREATE TABLE scratchpads_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    target_board_id TEXT REFERENCES boards(id) ON DELETE SET NULL,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    user_input TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'draft' CHECK(status IN ('draft', 'exploring', 'planning', 'awaiting_approval', 'approved', 'executing', 'executed', 'working', 'completed', 'failed')),
+    agent_pref TEXT CHECK(agent_pref IS NULL OR agent_pref IN ('cursor', 'claude', 'any')),
+    model TEXT,
+    exploration_log TEXT,
+    plan_markdown TEXT,
+    plan_json TEXT,
+    settings_json TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO scratchpads_new (id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at)
+SELECT id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model, exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at FROM scratchpads;
+
+DROP TABLE scratchpads;
+ALTER TABLE scratchpads_new RENAME TO scratchpads;
+
+CREATE INDEX IF NOT EXISTS idx_scratchpads_board ON scratchpads(board_id);
+CREATE INDEX IF NOT EXISTS idx_scratchpads_target_board ON scratchpads(target_board_id);
+CREATE INDEX IF NOT EXISTS idx_scratchpads_project ON scratchpads(project_id);
+CREATE INDEX IF NOT EXISTS idx_scratchpads_status ON scratchpads(status);
+"#;
+
+/// Migration SQL for schema version 14
+/// Enforces one column per lifecycle role on each board
+pub const MIGRATION_V14: &str = r#"
+-- Each lifecycle role is held by at most one column per board
+CREATE UNIQUE INDEX IF NOT EXISTS idx_columns_board_role ON columns(board_id, role) WHERE role IS NOT NULL;
+"#;
+
+/// Migration SQL for schema version 15
+/// Adds the ticket_history audit table
+pub const MIGRATION_V15: &str = r#"
+-- Ticket audit history: one row per changed field, written in the same
+-- transaction as the change itself
+CREATE TABLE IF NOT EXISTS ticket_history (
+    id TEXT PRIMARY KEY NOT NULL,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    field TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    actor_type TEXT NOT NULL CHECK(actor_type IN ('user', 'agent', 'system', 'worker')),
+    actor_id TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_history_ticket ON ticket_history(ticket_id, created_at);
+"#;
+
+/// Migration SQL for schema version 16
+/// Recreates tickets with the workflow_type default and CHECK that databases
+/// created before v4 never received through `ALTER TABLE`
+pub const MIGRATION_V16: &str = r#"
+CREATE TABLE tickets_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    column_id TEXT NOT NULL REFERENCES columns(id) ON DELETE RESTRICT,
+    title TEXT NOT NULL,
+    description_md TEXT NOT NULL DEFAULT '',
+    priority TEXT NOT NULL DEFAULT 'medium' CHECK(priority IN ('low', 'medium', 'high', 'urgent')),
+    labels_json TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    locked_by_run_id TEXT,
+    lock_expires_at TEXT,
+    project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
+    agent_pref TEXT CHECK(agent_pref IN ('cursor', 'claude', 'any')),
+    workflow_type TEXT NOT NULL DEFAULT 'multi_stage' CHECK(workflow_type IN ('multi_stage')),
+    model TEXT,
+    branch_name TEXT,
+    -- Epic support: is_epic marks this ticket as an epic, epic_id references parent epic
+    is_epic INTEGER NOT NULL DEFAULT 0,
+    epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL,
+    order_in_epic INTEGER,
+    -- Cross-epic dependency: which epic must complete before this epic can start (primary dependency)
+    depends_on_epic_id TEXT REFERENCES tickets(id) ON DELETE SET NULL,
+    -- All epic dependencies as JSON array of IDs (for display purposes)
+    depends_on_epic_ids_json TEXT,
+    -- Link back to scratchpad that created this ticket
+    scratchpad_id TEXT REFERENCES scratchpads(id) ON DELETE SET NULL
+);
+
+INSERT INTO tickets_new (id, board_id, column_id, title, description_md, priority, labels_json, created_at, updated_at, locked_by_run_id, lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name, is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id)
+SELECT id, board_id, column_id, title, description_md, priority, labels_json, created_at, updated_at, locked_by_run_id, lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name, is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id FROM tickets;
+
+DROP TABLE tickets;
+ALTER TABLE tickets_new RENAME TO tickets;
+
+CREATE INDEX idx_tickets_board ON tickets(board_id);
+CREATE INDEX idx_tickets_column ON tickets(column_id);
+CREATE INDEX idx_tickets_locked ON tickets(locked_by_run_id) WHERE locked_by_run_id IS NOT NULL;
+CREATE INDEX idx_tickets_project ON tickets(project_id);
+CREATE INDEX idx_tickets_epic ON tickets(epic_id, order_in_epic) WHERE epic_id IS NOT NULL;
+CREATE INDEX idx_tickets_depends_on ON tickets(depends_on_epic_id) WHERE depends_on_epic_id IS NOT NULL;
+CREATE INDEX idx_tickets_scratchpad ON tickets(scratchpad_id) WHERE scratchpad_id IS NOT NULL;
+"#;
+
+/// Migration SQL for schema version 17
+/// Adds the ticket_archive table used by the retention policy
+pub const MIGRATION_V17: &str = r#"
+-- Archived tickets keep their rows (and runs, comments, history) but are
+-- left out of board queries until restored
+CREATE TABLE IF NOT EXISTS ticket_archive (
+    ticket_id TEXT PRIMARY KEY NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    archived_at TEXT NOT NULL
+);
+"#;
+
+/// Migration SQL for schema version 18
+/// Adds the trash table; deleted tickets, boards and scratchpads point at
+/// their trash entry through the trash_id columns added by the registry
+pub const MIGRATION_V18: &str = r#"
+-- One row per delete action. Everything removed by that action carries its id
+-- in trash_id, so a restore brings back exactly what the delete hid.
+CREATE TABLE IF NOT EXISTS trash (
+    id TEXT PRIMARY KEY NOT NULL,
+    item_type TEXT NOT NULL CHECK(item_type IN ('ticket', 'board', 'scratchpad')),
+    item_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    board_id TEXT,
+    ticket_count INTEGER NOT NULL DEFAULT 0,
+    deleted_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_trash_deleted ON trash(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_tickets_trash ON tickets(trash_id) WHERE trash_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_scratchpads_trash ON scratchpads(trash_id) WHERE trash_id IS NOT NULL;
+"#;
+
+/// Migration SQL for schema version 19
+/// Adds board-level custom field definitions; ticket values live in the
+/// custom_fields_json column added by the registry
+pub const MIGRATION_V19: &str = r#"
+CREATE TABLE IF NOT EXISTS custom_fields (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    name TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK(field_type IN ('text', 'number', 'date', 'enum', 'user')),
+    options_json TEXT NOT NULL DEFAULT '[]',
+    required INTEGER NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE(board_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_fields_board ON custom_fields(board_id, position);
+"#;
+
+/// Migration SQL for schema version 20
+/// Adds the per-board label catalog, seeded from the labels tickets already
+/// carry. Spellings differing only in case become one label.
+pub const MIGRATION_V20: &str = r#"
+CREATE TABLE IF NOT EXISTS labels (
+    id TEXT PRIMARY KEY NOT NULL,
+    board_id TEXT NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+    name TEXT NOT NULL COLLATE NOCASE,
+    color TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    UNIQUE(board_id, name)
+);
+
+INSERT OR IGNORE INTO labels (id, board_id, name, color, created_at)
+SELECT lower(hex(randomblob(16))), t.board_id, trim(l.value), '#6b7280', strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+FROM tickets t, json_each(t.labels_json) l
+WHERE l.type = 'text' AND trim(l.value) <> ''
+ORDER BY t.created_at;
+"#;
+
+/// Migration SQL for schema version 21
+/// Adds worker definitions started at launch, with lifetime stats
+pub const MIGRATION_V21: &str = r#"
+CREATE TABLE IF NOT EXISTS workers (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    agent_type TEXT NOT NULL CHECK(agent_type IN ('cursor', 'claude')),
+    project_id TEXT REFERENCES projects(id) ON DELETE SET NULL,
+    labels_json TEXT NOT NULL DEFAULT '{}',
+    poll_interval_secs INTEGER NOT NULL,
+    agent_timeout_secs INTEGER NOT NULL,
+    lock_duration_mins INTEGER NOT NULL,
+    schedule_json TEXT,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    tickets_processed INTEGER NOT NULL DEFAULT 0,
+    tickets_failed INTEGER NOT NULL DEFAULT 0,
+    last_ticket_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+/// Migration SQL for schema version 22
+/// Adds the remote worker registry, ticket leases and run artifacts
+pub const MIGRATION_V22: &str = r#"
+CREATE TABLE IF NOT EXISTS remote_workers (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    hostname TEXT,
+    agent_kinds_json TEXT NOT NULL DEFAULT '[]',
+    project_ids_json TEXT NOT NULL DEFAULT '[]',
+    labels_json TEXT NOT NULL DEFAULT '{}',
+    version TEXT,
+    tickets_processed INTEGER NOT NULL DEFAULT 0,
+    registered_at TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL
+);
+
+-- The autoincrementing key is the lease's fencing token: a later lease on a
+-- ticket always has a larger one
+CREATE TABLE IF NOT EXISTS ticket_leases (
+    fencing_token INTEGER PRIMARY KEY AUTOINCREMENT,
+    worker_id TEXT NOT NULL REFERENCES remote_workers(id) ON DELETE CASCADE,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    run_id TEXT NOT NULL REFERENCES agent_runs(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'active' CHECK(status IN ('active', 'completed', 'expired')),
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    ended_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_leases_worker ON ticket_leases(worker_id, status);
+CREATE INDEX IF NOT EXISTS idx_ticket_leases_ticket ON ticket_leases(ticket_id);
+
+CREATE TABLE IF NOT EXISTS run_artifacts (
+    id TEXT PRIMARY KEY NOT NULL,
+    run_id TEXT NOT NULL REFERENCES agent_runs(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_artifacts_run ON run_artifacts(run_id);
+"#;
+
+/// Migration SQL for schema version 23
+/// Adds installation-wide settings, such as the scheduling policy
+pub const MIGRATION_V23: &str = r#"
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY NOT NULL,
+    value_json TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+"#;
+
+/// Migration SQL for schema version 24
+/// Adds stage checkpoints of multi-stage workflows, so a workflow
+/// interrupted by a restart can resume in its worktree
+pub const MIGRATION_V24: &str = r#"
+CREATE TABLE IF NOT EXISTS workflow_checkpoints (
+    parent_run_id TEXT PRIMARY KEY NOT NULL REFERENCES agent_runs(id) ON DELETE CASCADE,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    task_id TEXT,
+    agent_type TEXT NOT NULL,
+    worktree_path TEXT NOT NULL,
+    branch_name TEXT,
+    -- Index into MULTI_STAGE_WORKFLOW of the first stage not yet completed
+    next_stage INTEGER NOT NULL DEFAULT 0,
+    plan_md TEXT,
+    state TEXT NOT NULL DEFAULT 'active' CHECK(state IN ('active', 'interrupted', 'finished', 'failed')),
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_checkpoints_state ON workflow_checkpoints(state);
+"#;
+
+/// Migration SQL for schema version 26
+/// Supports workflows paused between stages. Recreates workflow_checkpoints
+/// with the 'paused' state (SQLite cannot alter constraints in place) and the
+/// pending pause request.
+pub const MIGRATION_V26: &str = r#"
+CREATE TABLE workflow_checkpoints_new (
+    parent_run_id TEXT PRIMARY KEY NOT NULL REFERENCES agent_runs(id) ON DELETE CASCADE,
+    ticket_id TEXT NOT NULL REFERENCES tickets(id) ON DELETE CASCADE,
+    task_id TEXT,
+    agent_type TEXT NOT NULL,
+    worktree_path TEXT NOT NULL,
+    branch_name TEXT,
+    -- Index into MULTI_STAGE_WORKFLOW of the first stage not yet completed
+    next_stage INTEGER NOT NULL DEFAULT 0,
+    plan_md TEXT,
+    state TEXT NOT NULL DEFAULT 'active' CHECK(state IN ('active', 'paused', 'interrupted', 'finished', 'failed')),
+    -- Set to pause the workflow once its current stage completes
+    pause_requested INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+INSERT INTO workflow_checkpoints_new (parent_run_id, ticket_id, task_id, agent_type, worktree_path, branch_name, next_stage, plan_md, state, created_at, updated_at)
+SELECT parent_run_id, ticket_id, task_id, agent_type, worktree_path, branch_name, next_stage, plan_md, state, created_at, updated_at FROM workflow_checkpoints;
+
+DROP TABLE workflow_checkpoints;
+ALTER TABLE workflow_checkpoints_new RENAME TO workflow_checkpoints;
+
+CREATE INDEX IF NOT EXISTS idx_workflow_checkpoints_state ON workflow_checkpoints(state);
 "#;
 
-/// Default columns for a new board
-pub const DEFAULT_COLUMNS: &[&str] = &[
-    "Backlog",
-    "Ready",
-    "In Progress",
-    "Blocked",
-    "Review",
-    "Done",
+/// Default columns for a new board, with the lifecycle role each one plays
+pub const DEFAULT_COLUMNS: &[(&str, TicketState)] = &[
+    ("Backlog", TicketState::Backlog),
+    ("Ready", TicketState::Ready),
+    ("In Progress", TicketState::InProgress),
+    ("Blocked", TicketState::Blocked),
+    ("Review", TicketState::Review),
+    ("Done", TicketState::Done),
 ];
@@ -2,6 +2,7 @@
 
 use crate::db::{Database, DbError, parse_datetime};
 use crate::db::models::{Scratchpad, CreateScratchpad, UpdateScratchpad, ScratchpadStatus, Exploration, ScratchpadProgress, ScratchpadEpicStatus, ScratchpadTicketStatus};
+use crate::lifecycle::TicketState;
 
 impl Database {
     pub fn create_scratchpad(&self, input: &CreateScratchpad) -> Result<Scratchpad, DbError> {
@@ -51,11 +52,11 @@ impl Database {
     }
 
     pub fn get_scratchpad(&self, id: &str) -> Result<Scratchpad, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model,
                           exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at
-                   FROM scratchpads WHERE id = ?"#
+                   FROM scratchpads WHERE id = ? AND trash_id IS NULL"#
             )?;
             
             stmt.query_row([id], Self::map_scratchpad_row)
@@ -69,11 +70,11 @@ impl Database {
     }
 
     pub fn get_scratchpads(&self, board_id: &str) -> Result<Vec<Scratchpad>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model,
                           exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at
-                   FROM scratchpads WHERE board_id = ?
+                   FROM scratchpads WHERE board_id = ? AND trash_id IS NULL
                    ORDER BY created_at DESC"#
             )?;
             
@@ -84,11 +85,11 @@ impl Database {
 
     /// Get all scratchpads across all boards
     pub fn get_all_scratchpads(&self) -> Result<Vec<Scratchpad>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, target_board_id, project_id, name, user_input, status, agent_pref, model,
                           exploration_log, plan_markdown, plan_json, settings_json, created_at, updated_at
-                   FROM scratchpads
+                   FROM scratchpads WHERE trash_id IS NULL
                    ORDER BY created_at DESC"#
             )?;
             
@@ -178,36 +179,25 @@ impl Database {
     /// Delete a scratchpad and all tickets created from it (cascade delete)
     /// Returns the number of tickets deleted
     pub fn delete_scratchpad_with_tickets(&self, id: &str) -> Result<usize, DbError> {
-        self.with_conn(|conn| {
-            // First, get all ticket IDs associated with this scratchpad
-            let mut stmt = conn.prepare(
-                "SELECT id FROM tickets WHERE scratchpad_id = ?"
-            )?;
-            let ticket_ids: Vec<String> = stmt.query_map([id], |row| row.get(0))?
-                .collect::<Result<Vec<_>, _>>()?;
-            
-            let ticket_count = ticket_ids.len();
-            
-            // Delete all related data for these tickets
-            for ticket_id in &ticket_ids {
-                // Delete comments
-                conn.execute("DELETE FROM comments WHERE ticket_id = ?", [ticket_id])?;
-                // Delete tasks
-                conn.execute("DELETE FROM tasks WHERE ticket_id = ?", [ticket_id])?;
-                // Delete events
-                conn.execute("DELETE FROM events WHERE ticket_id = ?", [ticket_id])?;
-                // Delete runs
-                conn.execute("DELETE FROM runs WHERE ticket_id = ?", [ticket_id])?;
-            }
-            
-            // Delete all tickets with this scratchpad_id
-            conn.execute(
+        self.get_scratchpad(id)?;
+        self.snapshot_best_effort("delete-scratchpad");
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+
+            let ticket_count: usize = tx.query_row(
+                "SELECT COUNT(*) FROM tickets WHERE scratchpad_id = ?",
+                [id],
+                |row| row.get::<_, i64>(0),
+            )? as usize;
+
+            // Comments, tasks, runs and events go with their tickets via ON DELETE CASCADE
+            tx.execute(
                 "DELETE FROM tickets WHERE scratchpad_id = ?",
                 [id],
             )?;
             
             // Delete the scratchpad itself
-            let affected = conn.execute(
+            let affected = tx.execute(
                 "DELETE FROM scratchpads WHERE id = ?",
                 [id],
             )?;
@@ -215,6 +205,7 @@ impl Database {
             if affected == 0 {
                 return Err(DbError::NotFound(format!("Scratchpad {}", id)));
             }
+            tx.commit()?;
             
             Ok(ticket_count)
         })
@@ -287,62 +278,62 @@ impl Database {
 
     /// Get all tickets created from a scratchpad
     pub fn get_scratchpad_tickets(&self, scratchpad_id: &str) -> Result<Vec<crate::db::models::Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                   FROM tickets WHERE scratchpad_id = ?
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                   FROM tickets WHERE scratchpad_id = ? AND trash_id IS NULL
                    ORDER BY created_at ASC"#
             )?;
             
-            let rows = stmt.query_map([scratchpad_id], Self::map_ticket_row_v10)?;
+            let rows = stmt.query_map([scratchpad_id], Self::map_ticket_row)?;
             rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
         })
     }
 
     /// Get all epics created from a scratchpad
     pub fn get_scratchpad_epics(&self, scratchpad_id: &str) -> Result<Vec<crate::db::models::Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                   FROM tickets WHERE scratchpad_id = ? AND is_epic = 1
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                   FROM tickets WHERE scratchpad_id = ? AND is_epic = 1 AND trash_id IS NULL
                    ORDER BY created_at ASC"#
             )?;
             
-            let rows = stmt.query_map([scratchpad_id], Self::map_ticket_row_v10)?;
+            let rows = stmt.query_map([scratchpad_id], Self::map_ticket_row)?;
             rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
         })
     }
     
     /// Get root epics (no dependencies) for a scratchpad
     pub fn get_scratchpad_root_epics(&self, scratchpad_id: &str) -> Result<Vec<crate::db::models::Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
                    FROM tickets 
-                   WHERE scratchpad_id = ? AND is_epic = 1 AND depends_on_epic_id IS NULL
+                   WHERE scratchpad_id = ? AND is_epic = 1 AND depends_on_epic_id IS NULL AND trash_id IS NULL
                    ORDER BY created_at ASC"#
             )?;
             
-            let rows = stmt.query_map([scratchpad_id], Self::map_ticket_row_v10)?;
+            let rows = stmt.query_map([scratchpad_id], Self::map_ticket_row)?;
             rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
         })
     }
     
     /// Check if all epics for a scratchpad are complete (in Done column)
     pub fn are_all_scratchpad_epics_done(&self, scratchpad_id: &str) -> Result<bool, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             // First check if there are any epics for this scratchpad
             let epic_count: i64 = conn.query_row(
-                "SELECT COUNT(*) FROM tickets WHERE scratchpad_id = ? AND is_epic = 1",
+                "SELECT COUNT(*) FROM tickets WHERE scratchpad_id = ? AND is_epic = 1 AND trash_id IS NULL",
                 [scratchpad_id],
                 |row| row.get(0),
             )?;
@@ -351,11 +342,11 @@ impl Database {
                 return Ok(false); // No epics means not complete
             }
             
-            // Check how many are in the Done column
+            // Check how many are in the done column
             let done_count: i64 = conn.query_row(
                 r#"SELECT COUNT(*) FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.scratchpad_id = ? AND t.is_epic = 1 AND c.name = 'Done'"#,
+                   WHERE t.scratchpad_id = ? AND t.is_epic = 1 AND c.role = 'done' AND t.trash_id IS NULL"#,
                 [scratchpad_id],
                 |row| row.get(0),
             )?;
@@ -366,13 +357,13 @@ impl Database {
     
     /// Get progress stats for a scratchpad's epics
     pub fn get_scratchpad_progress(&self, scratchpad_id: &str) -> Result<ScratchpadProgress, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             // First, get all epics with their dependency info (using JSON array for multiple deps)
             let mut epic_stmt = conn.prepare(
-                r#"SELECT t.id, t.title, c.name as column_name, t.depends_on_epic_ids_json
+                r#"SELECT t.id, t.title, c.name as column_name, t.depends_on_epic_ids_json, c.role
                    FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.scratchpad_id = ? AND t.is_epic = 1
+                   WHERE t.scratchpad_id = ? AND t.is_epic = 1 AND t.trash_id IS NULL
                    ORDER BY t.created_at ASC"#
             )?;
             
@@ -382,14 +373,15 @@ impl Database {
                     row.get::<_, String>(1)?,
                     row.get::<_, String>(2)?,
                     row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?.as_deref().and_then(TicketState::parse),
                 ))
             })?;
             
-            let epic_data: Vec<(String, String, String, Option<String>)> = epic_rows.collect::<Result<Vec<_>, _>>()?;
+            let epic_data = epic_rows.collect::<Result<Vec<_>, _>>()?;
             
             // Build a map of epic id -> title for resolving dependency titles
             let mut epic_title_map: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-            for (id, title, _, _) in &epic_data {
+            for (id, title, _, _, _) in &epic_data {
                 epic_title_map.insert(id.clone(), title.clone());
             }
             
@@ -398,12 +390,12 @@ impl Database {
                 r#"SELECT t.id, t.title, c.name as column_name
                    FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.epic_id = ?
+                   WHERE t.epic_id = ? AND t.trash_id IS NULL
                    ORDER BY t.order_in_epic ASC, t.created_at ASC"#
             )?;
             
             let mut epics = Vec::new();
-            for (epic_id, epic_title, epic_column, depends_on_json) in epic_data {
+            for (epic_id, epic_title, epic_column, depends_on_json, epic_role) in epic_data {
                 let ticket_rows = ticket_stmt.query_map([&epic_id], |row| {
                     Ok(ScratchpadTicketStatus {
                         id: row.get(0)?,
@@ -429,6 +421,7 @@ impl Database {
                     id: epic_id,
                     title: epic_title,
                     column: epic_column,
+                    column_role: epic_role,
                     depends_on_ids,
                     depends_on_titles,
                     tickets,
@@ -436,15 +429,18 @@ impl Database {
             }
             
             let total = epics.len();
-            let done = epics.iter().filter(|e| e.column == "Done").count();
+            let done = epics.iter().filter(|e| e.column_role == Some(TicketState::Done)).count();
             let in_progress = epics.iter().filter(|e| {
-                matches!(e.column.as_str(), "Ready" | "In Progress" | "Review")
+                matches!(
+                    e.column_role,
+                    Some(TicketState::Ready | TicketState::InProgress | TicketState::Review)
+                )
             }).count();
-            let blocked = epics.iter().filter(|e| e.column == "Blocked").count();
+            let blocked = epics.iter().filter(|e| e.column_role == Some(TicketState::Blocked)).count();
             
             // Get total count of ALL tickets (epics + child tickets)
             let total_tickets: usize = conn.query_row(
-                "SELECT COUNT(*) FROM tickets WHERE scratchpad_id = ?",
+                "SELECT COUNT(*) FROM tickets WHERE scratchpad_id = ? AND trash_id IS NULL",
                 [scratchpad_id],
                 |row| row.get::<_, i64>(0),
             )? as usize;
@@ -498,62 +494,6 @@ impl Database {
             updated_at: parse_datetime(row.get(14)?),
         })
     }
-
-    // Temporary helper to map ticket rows with new columns (v10)
-    // This will be consolidated with map_ticket_row once tickets.rs is updated
-    fn map_ticket_row_v10(row: &rusqlite::Row) -> rusqlite::Result<crate::db::models::Ticket> {
-        use crate::db::models::{Ticket, Priority, AgentPref, WorkflowType};
-        
-        let labels_json: String = row.get(6)?;
-        let labels: Vec<String> = serde_json::from_str(&labels_json).unwrap_or_default();
-        
-        let priority_str: String = row.get(5)?;
-        let priority = Priority::parse(&priority_str).unwrap_or(Priority::Medium);
-        
-        let agent_pref_str: Option<String> = row.get(12)?;
-        let agent_pref = agent_pref_str.and_then(|s| AgentPref::parse(&s));
-        
-        let workflow_type_str: String = row.get::<_, Option<String>>(13)?.unwrap_or_else(|| "basic".to_string());
-        let workflow_type = WorkflowType::parse(&workflow_type_str).unwrap_or_default();
-        
-        let model: Option<String> = row.get(14)?;
-        let branch_name: Option<String> = row.get(15)?;
-        
-        let is_epic: bool = row.get::<_, i32>(16).unwrap_or(0) != 0;
-        let epic_id: Option<String> = row.get(17)?;
-        let order_in_epic: Option<i32> = row.get(18)?;
-        let depends_on_epic_id: Option<String> = row.get(19)?;
-        let depends_on_epic_ids_json: Option<String> = row.get(20)?;
-        let depends_on_epic_ids: Vec<String> = depends_on_epic_ids_json
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default();
-        let scratchpad_id: Option<String> = row.get(21)?;
-
-        Ok(Ticket {
-            id: row.get(0)?,
-            board_id: row.get(1)?,
-            column_id: row.get(2)?,
-            title: row.get(3)?,
-            description_md: row.get(4)?,
-            priority,
-            labels,
-            created_at: parse_datetime(row.get(7)?),
-            updated_at: parse_datetime(row.get(8)?),
-            locked_by_run_id: row.get(9)?,
-            lock_expires_at: row.get::<_, Option<String>>(10)?.map(parse_datetime),
-            project_id: row.get(11)?,
-            agent_pref,
-            workflow_type,
-            model,
-            branch_name,
-            is_epic,
-            epic_id,
-            order_in_epic,
-            depends_on_epic_id,
-            depends_on_epic_ids,
-            scratchpad_id,
-        })
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,86 @@
+//! Installation-wide settings kept as JSON in the `settings` table.
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::db::models::{ConcurrencyLimits, RecoverySettings};
+use crate::db::{Database, DbError};
+
+const CONCURRENCY_KEY: &str = "concurrency_limits";
+const RECOVERY_KEY: &str = "recovery";
+
+impl Database {
+    pub fn get_concurrency_limits(&self) -> Result<ConcurrencyLimits, DbError> {
+        self.with_reader(|conn| read_setting(conn, CONCURRENCY_KEY))
+    }
+
+    /// Save the limits; callers apply them to [`crate::agents::slots::SLOTS`]
+    pub fn set_concurrency_limits(&self, limits: &ConcurrencyLimits) -> Result<ConcurrencyLimits, DbError> {
+        limits.validate()?;
+        self.with_conn(|conn| write_setting(conn, CONCURRENCY_KEY, limits))?;
+        Ok(limits.clone())
+    }
+
+    pub fn get_recovery_settings(&self) -> Result<RecoverySettings, DbError> {
+        self.with_reader(|conn| read_setting(conn, RECOVERY_KEY))
+    }
+
+    pub fn set_recovery_settings(&self, settings: &RecoverySettings) -> Result<RecoverySettings, DbError> {
+        self.with_conn(|conn| write_setting(conn, RECOVERY_KEY, settings))?;
+        Ok(settings.clone())
+    }
+}
+
+/// The setting stored under `key`, or the default when it is missing or
+/// cannot be read
+pub(crate) fn read_setting<T: DeserializeOwned + Default>(conn: &Connection, key: &str) -> Result<T, DbError> {
+    let json: Option<String> = conn
+        .query_row("SELECT value_json FROM settings WHERE key = ?", [key], |row| row.get(0))
+        .optional()?;
+    Ok(json
+        .and_then(|json| {
+            serde_json::from_str(&json)
+                .map_err(|e| tracing::warn!("Ignoring unreadable setting {}: {}", key, e))
+                .ok()
+        })
+        .unwrap_or_default())
+}
+
+pub(crate) fn write_setting<T: Serialize>(conn: &Connection, key: &str, value: &T) -> Result<(), DbError> {
+    conn.execute(
+        r#"INSERT INTO settings (key, value_json, updated_at) VALUES (?1, ?2, ?3)
+           ON CONFLICT(key) DO UPDATE SET value_json = excluded.value_json, updated_at = excluded.updated_at"#,
+        rusqlite::params![
+            key,
+            serde_json::to_string(value).unwrap_or_else(|_| "{}".to_string()),
+            Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn concurrency_limits_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_concurrency_limits().unwrap(), ConcurrencyLimits::default());
+
+        let limits = ConcurrencyLimits {
+            max_total: Some(3),
+            per_agent: BTreeMap::from([("claude".to_string(), 2)]),
+            per_model: BTreeMap::new(),
+        };
+        db.set_concurrency_limits(&limits).unwrap();
+        assert_eq!(db.get_concurrency_limits().unwrap(), limits);
+
+        let invalid = ConcurrencyLimits { max_total: Some(0), ..limits.clone() };
+        assert!(matches!(db.set_concurrency_limits(&invalid), Err(DbError::Validation(_))));
+        assert_eq!(db.get_concurrency_limits().unwrap(), limits);
+    }
+}
@@ -51,7 +51,7 @@ impl Database {
 
     /// Get a task by ID
     pub fn get_task(&self, task_id: &str) -> Result<Task, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, order_index, task_type, title, content, 
                           status, run_id, created_at, started_at, completed_at
@@ -70,7 +70,7 @@ impl Database {
 
     /// Get all tasks for a ticket, ordered by order_index
     pub fn get_tasks_for_ticket(&self, ticket_id: &str) -> Result<Vec<Task>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, order_index, task_type, title, content, 
                           status, run_id, created_at, started_at, completed_at
@@ -84,7 +84,7 @@ impl Database {
 
     /// Get the next pending task for a ticket
     pub fn get_next_pending_task(&self, ticket_id: &str) -> Result<Option<Task>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, ticket_id, order_index, task_type, title, content, 
                           status, run_id, created_at, started_at, completed_at
@@ -441,7 +441,7 @@ impl Database {
 
     /// Check if a ticket has any pending tasks
     pub fn has_pending_tasks(&self, ticket_id: &str) -> Result<bool, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let count: i32 = conn.query_row(
                 "SELECT COUNT(*) FROM tasks WHERE ticket_id = ? AND status = 'pending'",
                 [ticket_id],
@@ -453,7 +453,7 @@ impl Database {
 
     /// Get count of tasks by status for a ticket
     pub fn get_task_counts(&self, ticket_id: &str) -> Result<TaskCounts, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT status, COUNT(*) FROM tasks 
                    WHERE ticket_id = ? GROUP BY status"#
@@ -540,6 +540,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         ticket.id
     }
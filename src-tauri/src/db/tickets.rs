@@ -1,18 +1,23 @@
 use chrono::{DateTime, Utc};
-use rusqlite::OptionalExtension;
+use rusqlite::{Connection, OptionalExtension};
 use crate::db::{Database, DbError, parse_datetime};
-use crate::db::models::{Ticket, CreateTicket, UpdateTicket, Priority, AgentPref, WorkflowType, CreateTask, TaskType};
+use crate::db::custom_fields::{board_fields, merge_values, validate_values};
+use crate::db::labels::catalog_labels;
+use crate::db::scheduling::{next_ticket, QueueFilter};
+use crate::db::history::{record_change, record_ticket_changes, LOCK_FIELD};
+use crate::db::models::{Actor, LabelFilter, Ticket, CreateTicket, UpdateTicket, Priority, AgentPref, WorkflowType, CreateTask, TaskType, CustomFieldValues};
 use crate::agents::AgentKind;
+use crate::lifecycle::TicketState;
 
 impl Database {
     pub fn get_ticket(&self, ticket_id: &str) -> Result<Ticket, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                   FROM tickets WHERE id = ?"#
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                   FROM tickets WHERE id = ? AND trash_id IS NULL"#
             )?;
             
             stmt.query_row([ticket_id], Self::map_ticket_row)
@@ -25,16 +30,18 @@ impl Database {
         })
     }
 
-    pub fn update_ticket(&self, ticket_id: &str, updates: &UpdateTicket) -> Result<Ticket, DbError> {
-        self.with_conn(|conn| {
+    pub fn update_ticket(&self, ticket_id: &str, updates: &UpdateTicket, actor: &Actor) -> Result<Ticket, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let conn = &*tx;
             // First get the existing ticket
             let existing = {
                 let mut stmt = conn.prepare(
                     r#"SELECT id, board_id, column_id, title, description_md, priority, 
                               labels_json, created_at, updated_at, locked_by_run_id, 
                               lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                              is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                       FROM tickets WHERE id = ?"#
+                              is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                       FROM tickets WHERE id = ? AND trash_id IS NULL"#
                 )?;
                 stmt.query_row([ticket_id], Self::map_ticket_row)
                     .map_err(|e| match e {
@@ -49,7 +56,10 @@ impl Database {
             let title = updates.title.as_ref().unwrap_or(&existing.title);
             let description_md = updates.description_md.as_ref().unwrap_or(&existing.description_md);
             let priority = updates.priority.as_ref().unwrap_or(&existing.priority);
-            let labels = updates.labels.as_ref().unwrap_or(&existing.labels);
+            let labels = match &updates.labels {
+                Some(labels) => catalog_labels(conn, &existing.board_id, labels)?,
+                None => existing.labels.clone(),
+            };
             // Handle project_id: None means keep existing, Some("") means clear, Some(id) means set
             let project_id = match &updates.project_id {
                 Some(id) if id.is_empty() => None, // Empty string means clear the project
@@ -101,7 +111,13 @@ impl Database {
                 updates.depends_on_epic_ids.clone()
             };
 
-            let labels_json = serde_json::to_string(labels).unwrap_or_else(|_| "[]".to_string());
+            // Handle custom_fields: None keeps every value, listed keys are set (or cleared by null)
+            let custom_fields = match &updates.custom_fields {
+                Some(values) => merge_values(&board_fields(conn, &existing.board_id)?, &existing.custom_fields, values)?,
+                None => existing.custom_fields.clone(),
+            };
+
+            let labels_json = serde_json::to_string(&labels).unwrap_or_else(|_| "[]".to_string());
             let depends_on_epic_ids_json = if depends_on_epic_ids.is_empty() {
                 None
             } else {
@@ -113,7 +129,8 @@ impl Database {
                    SET title = ?, description_md = ?, priority = ?, labels_json = ?,
                        project_id = ?, agent_pref = ?, workflow_type = ?, model = ?, branch_name = ?, 
                        column_id = ?, is_epic = ?, epic_id = ?, order_in_epic = ?, 
-                       depends_on_epic_id = ?, depends_on_epic_ids_json = ?, scratchpad_id = ?, updated_at = ?
+                       depends_on_epic_id = ?, depends_on_epic_ids_json = ?, scratchpad_id = ?,
+                       custom_fields_json = ?, updated_at = ?
                    WHERE id = ?"#,
                 rusqlite::params![
                     title,
@@ -132,21 +149,26 @@ impl Database {
                     depends_on_epic_id,
                     depends_on_epic_ids_json,
                     scratchpad_id,
+                    serde_json::to_string(&custom_fields).unwrap_or_else(|_| "{}".to_string()),
                     now.to_rfc3339(),
                     ticket_id,
                 ],
             )?;
 
             // Re-query within the same connection to avoid deadlock
-            let mut stmt = conn.prepare(
+            let updated = conn.query_row(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                   FROM tickets WHERE id = ?"#
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                   FROM tickets WHERE id = ?"#,
+                [ticket_id],
+                Self::map_ticket_row,
             )?;
-            stmt.query_row([ticket_id], Self::map_ticket_row)
-                .map_err(DbError::Sqlite)
+
+            record_ticket_changes(conn, &existing, &updated, actor)?;
+            tx.commit()?;
+            Ok(updated)
         })
     }
 
@@ -178,9 +200,12 @@ impl Database {
         run_id: &str,
         expires_at: DateTime<Utc>,
     ) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let conn = &*tx;
             let now = chrono::Utc::now();
             let now_str = now.to_rfc3339();
+            let previous_holder = current_lock_holder(conn, ticket_id)?;
             
             // Atomically acquire lock only if not held by another run
             let affected = conn.execute(
@@ -215,18 +240,25 @@ impl Database {
                     ticket_id
                 )));
             }
+
+            record_change(conn, ticket_id, LOCK_FIELD, previous_holder.as_deref(), Some(run_id), &Actor::run(run_id))?;
+            tx.commit()?;
             Ok(())
         })
     }
 
-    pub fn unlock_ticket(&self, ticket_id: &str) -> Result<(), DbError> {
-        self.with_conn(|conn| {
-            conn.execute(
+    pub fn unlock_ticket(&self, ticket_id: &str, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let previous_holder = current_lock_holder(&tx, ticket_id)?;
+            tx.execute(
                 r#"UPDATE tickets 
                    SET locked_by_run_id = NULL, lock_expires_at = NULL, updated_at = ?
                    WHERE id = ?"#,
                 rusqlite::params![chrono::Utc::now().to_rfc3339(), ticket_id],
             )?;
+            record_change(&tx, ticket_id, LOCK_FIELD, previous_holder.as_deref(), None, actor)?;
+            tx.commit()?;
             Ok(())
         })
     }
@@ -267,57 +299,49 @@ impl Database {
         new_run_id: &str,
         new_expires_at: Option<DateTime<Utc>>,
     ) -> Result<(), DbError> {
-        self.with_conn(|conn| {
-            let now = chrono::Utc::now();
-            let affected = if let Some(expires) = new_expires_at {
-                conn.execute(
-                    r#"UPDATE tickets 
-                       SET locked_by_run_id = ?, lock_expires_at = ?, updated_at = ?
-                       WHERE id = ? AND locked_by_run_id = ?"#,
-                    rusqlite::params![
-                        new_run_id,
-                        expires.to_rfc3339(),
-                        now.to_rfc3339(),
-                        ticket_id,
-                        old_run_id,
-                    ],
-                )?
-            } else {
-                conn.execute(
-                    r#"UPDATE tickets 
-                       SET locked_by_run_id = ?, updated_at = ?
-                       WHERE id = ? AND locked_by_run_id = ?"#,
-                    rusqlite::params![
-                        new_run_id,
-                        now.to_rfc3339(),
-                        ticket_id,
-                        old_run_id,
-                    ],
-                )?
-            };
-            
-            if affected == 0 {
-                return Err(DbError::NotFound(format!(
-                    "Ticket lock not found or not owned by run {}",
-                    old_run_id
-                )));
-            }
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            transfer_lock(&tx, ticket_id, old_run_id, new_run_id, new_expires_at)?;
+            tx.commit()?;
             Ok(())
         })
     }
 
     /// Atomically reserve the next available ticket from the Ready column.
     /// 
-    /// This method uses a single UPDATE...WHERE statement to atomically find and lock
-    /// a ticket, preventing race conditions where multiple workers might grab the same ticket.
+    /// The ticket is picked by the installation's scheduling policy
+    /// ([`crate::db::scheduling`]) and locked in the same transaction on the
+    /// writer connection, so two workers can never grab the same ticket.
     /// 
+    /// Only tickets passing `labels` are considered, so workers can be dedicated
+    /// to (or kept away from) tickets with certain labels.
+    ///
     /// Returns Some(ticket) if a ticket was reserved, None if no tickets are available.
     pub fn reserve_next_ticket(
         &self,
         project_filter: Option<&str>,
         agent_type: AgentKind,
+        labels: &LabelFilter,
         run_id: &str,
         lock_expires_at: DateTime<Utc>,
+        actor: &Actor,
+    ) -> Result<Option<Ticket>, DbError> {
+        let filter = QueueFilter {
+            project_ids: project_filter.map(String::from).into_iter().collect(),
+            agent_type: Some(agent_type),
+            labels: labels.clone(),
+        };
+        self.reserve_next_matching(&filter, run_id, lock_expires_at, actor)
+    }
+
+    /// [`Database::reserve_next_ticket`] for any ticket passing `filter`, so
+    /// workers serving several projects rank all of them in one pass
+    pub fn reserve_next_matching(
+        &self,
+        filter: &QueueFilter,
+        run_id: &str,
+        lock_expires_at: DateTime<Utc>,
+        actor: &Actor,
     ) -> Result<Option<Ticket>, DbError> {
         self.with_conn_mut(|conn| {
             let tx = conn.transaction()?;
@@ -325,38 +349,18 @@ impl Database {
             let now_str = now.to_rfc3339();
             let expires_str = lock_expires_at.to_rfc3339();
             
-            let agent_type_str = agent_type.as_str();
-            
-            // Subquery finds next ticket; outer WHERE double-checks lock status for atomicity
-            // NOTE: Epics are excluded (is_epic = 0) because workers should process child tickets,
-            // not the epic container itself. The epic orchestrates its children through lifecycle hooks.
+            // The installation's scheduling policy picks the ticket; the
+            // UPDATE double-checks lock status for atomicity
+            let Some(ticket_id) = next_ticket(&tx, filter, now)? else {
+                tx.commit()?;
+                return Ok(None);
+            };
             let affected = tx.execute(
                 r#"UPDATE tickets 
                    SET locked_by_run_id = ?1, lock_expires_at = ?2, updated_at = ?3
-                   WHERE id = (
-                       SELECT t.id FROM tickets t
-                       JOIN columns c ON t.column_id = c.id
-                       WHERE c.name = 'Ready'
-                         AND t.is_epic = 0
-                         AND (t.locked_by_run_id IS NULL OR t.lock_expires_at < ?3)
-                         AND (?4 IS NULL OR t.project_id = ?4)
-                         AND (
-                             t.agent_pref IS NULL 
-                             OR t.agent_pref = 'any' 
-                             OR t.agent_pref = ?5
-                         )
-                       ORDER BY 
-                         CASE t.priority 
-                           WHEN 'urgent' THEN 0 
-                           WHEN 'high' THEN 1 
-                           WHEN 'medium' THEN 2 
-                           WHEN 'low' THEN 3 
-                         END,
-                         t.created_at ASC
-                       LIMIT 1
-                   )
-                   AND (locked_by_run_id IS NULL OR lock_expires_at < ?3)"#,
-                rusqlite::params![run_id, expires_str, now_str, project_filter, agent_type_str],
+                   WHERE id = ?4
+                     AND (locked_by_run_id IS NULL OR lock_expires_at < ?3)"#,
+                rusqlite::params![run_id, expires_str, now_str, ticket_id],
             )?;
             
             if affected == 0 {
@@ -368,13 +372,15 @@ impl Database {
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
                    FROM tickets WHERE locked_by_run_id = ?1
                    LIMIT 1"#,
                 [run_id],
                 Self::map_ticket_row,
             )?;
-            
+
+            // A lock taken over from an expired holder is recorded as a fresh lock
+            record_change(&tx, &ticket.id, LOCK_FIELD, None, Some(run_id), actor)?;
             tx.commit()?;
             Ok(Some(ticket))
         })
@@ -397,9 +403,17 @@ impl Database {
         };
 
         let created_ticket = self.with_conn(|conn| {
+            let fields = board_fields(conn, &ticket.board_id)?;
+            validate_values(&fields, &ticket.custom_fields)?;
+            let custom_fields: CustomFieldValues = ticket.custom_fields.iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            let labels = catalog_labels(conn, &ticket.board_id, &ticket.labels)?;
+
             let ticket_id = uuid::Uuid::new_v4().to_string();
             let now = chrono::Utc::now();
-            let labels_json = serde_json::to_string(&ticket.labels).unwrap_or_else(|_| "[]".to_string());
+            let labels_json = serde_json::to_string(&labels).unwrap_or_else(|_| "[]".to_string());
             let depends_on_epic_ids_json = if ticket.depends_on_epic_ids.is_empty() {
                 None
             } else {
@@ -410,8 +424,9 @@ impl Database {
                 r#"INSERT INTO tickets 
                    (id, board_id, column_id, title, description_md, priority, labels_json, 
                     created_at, updated_at, project_id, agent_pref, workflow_type, model, branch_name,
-                    is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id)
-                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id,
+                    custom_fields_json)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
                 rusqlite::params![
                     ticket_id,
                     ticket.board_id,
@@ -433,6 +448,7 @@ impl Database {
                     ticket.depends_on_epic_id,
                     depends_on_epic_ids_json,
                     ticket.scratchpad_id,
+                    serde_json::to_string(&custom_fields).unwrap_or_else(|_| "{}".to_string()),
                 ],
             )?;
 
@@ -443,7 +459,7 @@ impl Database {
                 title: ticket.title.clone(),
                 description_md: ticket.description_md.clone(),
                 priority: ticket.priority.clone(),
-                labels: ticket.labels.clone(),
+                labels,
                 created_at: now,
                 updated_at: now,
                 locked_by_run_id: None,
@@ -459,6 +475,7 @@ impl Database {
                 depends_on_epic_id: ticket.depends_on_epic_id.clone(),
                 depends_on_epic_ids: ticket.depends_on_epic_ids.clone(),
                 scratchpad_id: ticket.scratchpad_id.clone(),
+                custom_fields,
             })
         })?;
         
@@ -505,21 +522,27 @@ impl Database {
     }
 
     pub fn get_tickets(&self, board_id: &str, column_id: Option<&str>) -> Result<Vec<Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let sql = match column_id {
                 Some(_) => {
                     "SELECT id, board_id, column_id, title, description_md, priority, 
                             labels_json, created_at, updated_at, locked_by_run_id, 
                             lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                            is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                     FROM tickets WHERE board_id = ? AND column_id = ? ORDER BY created_at"
+                            is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                     FROM tickets WHERE board_id = ? AND column_id = ?
+                       AND trash_id IS NULL
+                       AND id NOT IN (SELECT ticket_id FROM ticket_archive)
+                     ORDER BY created_at"
                 }
                 None => {
                     "SELECT id, board_id, column_id, title, description_md, priority, 
                             labels_json, created_at, updated_at, locked_by_run_id, 
                             lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                            is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                     FROM tickets WHERE board_id = ? ORDER BY created_at"
+                            is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                     FROM tickets WHERE board_id = ?
+                       AND trash_id IS NULL
+                       AND id NOT IN (SELECT ticket_id FROM ticket_archive)
+                     ORDER BY created_at"
                 }
             };
 
@@ -534,33 +557,51 @@ impl Database {
         })
     }
 
-    pub fn move_ticket(&self, ticket_id: &str, column_id: &str) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+    pub fn move_ticket(&self, ticket_id: &str, column_id: &str, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let from_column_id: Option<String> = tx.query_row(
+                "SELECT column_id FROM tickets WHERE id = ?",
+                [ticket_id],
+                |row| row.get(0),
+            ).optional()?;
+            let Some(from_column_id) = from_column_id else {
+                return Err(DbError::NotFound(format!("Ticket {} not found", ticket_id)));
+            };
+
             let now = chrono::Utc::now();
-            let affected = conn.execute(
+            tx.execute(
                 "UPDATE tickets SET column_id = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![column_id, now.to_rfc3339(), ticket_id],
             )?;
-            
-            if affected == 0 {
-                return Err(DbError::NotFound(format!("Ticket {} not found", ticket_id)));
-            }
+            record_change(&tx, ticket_id, "columnId", Some(&from_column_id), Some(column_id), actor)?;
+            tx.commit()?;
             Ok(())
         })
     }
 
-    pub fn set_ticket_project(&self, ticket_id: &str, project_id: Option<&str>) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+    pub fn set_ticket_project(&self, ticket_id: &str, project_id: Option<&str>, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let previous: Option<Option<String>> = tx.query_row(
+                "SELECT project_id FROM tickets WHERE id = ?",
+                [ticket_id],
+                |row| row.get(0),
+            ).optional()?;
             let now = chrono::Utc::now().to_rfc3339();
-            conn.execute(
+            tx.execute(
                 "UPDATE tickets SET project_id = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![project_id, now, ticket_id],
             )?;
+            if let Some(previous) = previous {
+                record_change(&tx, ticket_id, "projectId", previous.as_deref(), project_id, actor)?;
+            }
+            tx.commit()?;
             Ok(())
         })
     }
 
-    fn map_ticket_row(row: &rusqlite::Row) -> rusqlite::Result<Ticket> {
+    pub(crate) fn map_ticket_row(row: &rusqlite::Row) -> rusqlite::Result<Ticket> {
         let labels_json: String = row.get(6)?;
         let labels: Vec<String> = serde_json::from_str(&labels_json).unwrap_or_default();
         
@@ -586,6 +627,9 @@ impl Database {
             .and_then(|s| serde_json::from_str(&s).ok())
             .unwrap_or_default();
         let scratchpad_id: Option<String> = row.get(21)?;
+        let custom_fields = row.get::<_, Option<String>>(22)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
 
         Ok(Ticket {
             id: row.get(0)?,
@@ -610,21 +654,30 @@ impl Database {
             depends_on_epic_id,
             depends_on_epic_ids,
             scratchpad_id,
+            custom_fields,
         })
     }
 
     /// Set the branch name for a ticket (used after agent generates branch name)
-    pub fn set_ticket_branch(&self, ticket_id: &str, branch_name: &str) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+    pub fn set_ticket_branch(&self, ticket_id: &str, branch_name: &str, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let previous: Option<Option<String>> = tx.query_row(
+                "SELECT branch_name FROM tickets WHERE id = ?",
+                [ticket_id],
+                |row| row.get(0),
+            ).optional()?;
+            let Some(previous) = previous else {
+                return Err(DbError::NotFound(format!("Ticket {} not found", ticket_id)));
+            };
+
             let now = chrono::Utc::now().to_rfc3339();
-            let affected = conn.execute(
+            tx.execute(
                 "UPDATE tickets SET branch_name = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![branch_name, now, ticket_id],
             )?;
-            
-            if affected == 0 {
-                return Err(DbError::NotFound(format!("Ticket {} not found", ticket_id)));
-            }
+            record_change(&tx, ticket_id, "branchName", previous.as_deref(), Some(branch_name), actor)?;
+            tx.commit()?;
             Ok(())
         })
     }
@@ -633,13 +686,13 @@ impl Database {
 
     /// Get all children of an epic, ordered by order_in_epic
     pub fn get_epic_children(&self, epic_id: &str) -> Result<Vec<Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                   FROM tickets WHERE epic_id = ?
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                   FROM tickets WHERE epic_id = ? AND trash_id IS NULL
                    ORDER BY order_in_epic ASC, created_at ASC"#
             )?;
             
@@ -648,17 +701,17 @@ impl Database {
         })
     }
 
-    /// Get the next pending child ticket for an epic (first child in Backlog)
+    /// Get the next pending child ticket for an epic (first child in the backlog column)
     pub fn get_next_pending_child(&self, epic_id: &str) -> Result<Option<Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT t.id, t.board_id, t.column_id, t.title, t.description_md, t.priority, 
                           t.labels_json, t.created_at, t.updated_at, t.locked_by_run_id, 
                           t.lock_expires_at, t.project_id, t.agent_pref, t.workflow_type, t.model, t.branch_name,
-                          t.is_epic, t.epic_id, t.order_in_epic, t.depends_on_epic_id, t.depends_on_epic_ids_json, t.scratchpad_id
+                          t.is_epic, t.epic_id, t.order_in_epic, t.depends_on_epic_id, t.depends_on_epic_ids_json, t.scratchpad_id, t.custom_fields_json
                    FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.epic_id = ? AND c.name = 'Backlog'
+                   WHERE t.epic_id = ? AND c.role = 'backlog' AND t.trash_id IS NULL
                    ORDER BY t.order_in_epic ASC, t.created_at ASC
                    LIMIT 1"#
             )?;
@@ -673,34 +726,34 @@ impl Database {
     pub fn get_epic_progress(&self, epic_id: &str) -> Result<crate::db::models::EpicProgress, DbError> {
         use crate::db::models::EpicProgress;
         
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut progress = EpicProgress::default();
             
             let mut stmt = conn.prepare(
-                r#"SELECT c.name, COUNT(*) as cnt
+                r#"SELECT c.role, COUNT(*) as cnt
                    FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.epic_id = ?
-                   GROUP BY c.name"#
+                   WHERE t.epic_id = ? AND t.trash_id IS NULL
+                   GROUP BY c.role"#
             )?;
             
             let rows = stmt.query_map([epic_id], |row| {
-                let name: String = row.get(0)?;
+                let role: Option<String> = row.get(0)?;
                 let count: i32 = row.get(1)?;
-                Ok((name, count))
+                Ok((role, count))
             })?;
             
             for row in rows {
-                let (name, count) = row?;
+                let (role, count) = row?;
                 progress.total += count;
-                match name.as_str() {
-                    "Backlog" => progress.backlog = count,
-                    "Ready" => progress.ready = count,
-                    "In Progress" => progress.in_progress = count,
-                    "Blocked" => progress.blocked = count,
-                    "Review" => progress.review = count,
-                    "Done" => progress.done = count,
-                    _ => {} // Unknown column
+                match role.as_deref().and_then(TicketState::parse) {
+                    Some(TicketState::Backlog) => progress.backlog = count,
+                    Some(TicketState::Ready) => progress.ready = count,
+                    Some(TicketState::InProgress) => progress.in_progress = count,
+                    Some(TicketState::Blocked) => progress.blocked = count,
+                    Some(TicketState::Review) => progress.review = count,
+                    Some(TicketState::Done) => progress.done = count,
+                    None => {} // Custom column
                 }
             }
             
@@ -709,8 +762,10 @@ impl Database {
     }
 
     /// Add an existing ticket to an epic as a child
-    pub fn add_ticket_to_epic(&self, epic_id: &str, ticket_id: &str) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+    pub fn add_ticket_to_epic(&self, epic_id: &str, ticket_id: &str, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let conn = &*tx;
             // Verify epic exists and is actually an epic
             let is_epic: bool = conn.query_row(
                 "SELECT is_epic FROM tickets WHERE id = ?",
@@ -734,60 +789,82 @@ impl Database {
             
             let order = max_order.unwrap_or(-1) + 1;
             let now = chrono::Utc::now().to_rfc3339();
+            let previous_epic_id = current_epic_id(conn, ticket_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Ticket {} not found", ticket_id)))?;
             
-            let affected = conn.execute(
+            conn.execute(
                 "UPDATE tickets SET epic_id = ?, order_in_epic = ?, updated_at = ? WHERE id = ?",
                 rusqlite::params![epic_id, order, now, ticket_id],
             )?;
+            record_change(conn, ticket_id, "epicId", previous_epic_id.as_deref(), Some(epic_id), actor)?;
             
-            if affected == 0 {
-                return Err(DbError::NotFound(format!("Ticket {} not found", ticket_id)));
-            }
-            
+            tx.commit()?;
             Ok(())
         })
     }
 
     /// Remove a ticket from its parent epic
-    pub fn remove_ticket_from_epic(&self, ticket_id: &str) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+    pub fn remove_ticket_from_epic(&self, ticket_id: &str, actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
             let now = chrono::Utc::now().to_rfc3339();
+            let previous_epic_id = current_epic_id(&tx, ticket_id)?.flatten();
             
-            conn.execute(
+            tx.execute(
                 "UPDATE tickets SET epic_id = NULL, order_in_epic = NULL, updated_at = ? WHERE id = ?",
                 rusqlite::params![now, ticket_id],
             )?;
+            record_change(&tx, ticket_id, "epicId", previous_epic_id.as_deref(), None, actor)?;
             
+            tx.commit()?;
             Ok(())
         })
     }
 
     /// Reorder children within an epic
     /// child_ids should be the list of ticket IDs in the desired order
-    pub fn reorder_epic_children(&self, epic_id: &str, child_ids: &[String]) -> Result<(), DbError> {
-        self.with_conn(|conn| {
+    pub fn reorder_epic_children(&self, epic_id: &str, child_ids: &[String], actor: &Actor) -> Result<(), DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
             let now = chrono::Utc::now().to_rfc3339();
             
             for (index, ticket_id) in child_ids.iter().enumerate() {
-                conn.execute(
+                let previous_order: Option<Option<i32>> = tx.query_row(
+                    "SELECT order_in_epic FROM tickets WHERE id = ? AND epic_id = ?",
+                    rusqlite::params![ticket_id, epic_id],
+                    |row| row.get(0),
+                ).optional()?;
+                let Some(previous_order) = previous_order else {
+                    continue;
+                };
+                tx.execute(
                     "UPDATE tickets SET order_in_epic = ?, updated_at = ? WHERE id = ? AND epic_id = ?",
                     rusqlite::params![index as i32, now, ticket_id, epic_id],
                 )?;
+                record_change(
+                    &tx,
+                    ticket_id,
+                    "orderInEpic",
+                    previous_order.map(|o| o.to_string()).as_deref(),
+                    Some(&index.to_string()),
+                    actor,
+                )?;
             }
             
+            tx.commit()?;
             Ok(())
         })
     }
 
     /// Get all epics that depend on the given epic (via depends_on_epic_id)
     pub fn get_epics_depending_on(&self, epic_id: &str) -> Result<Vec<Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             let mut stmt = conn.prepare(
                 r#"SELECT id, board_id, column_id, title, description_md, priority, 
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
-                   FROM tickets WHERE depends_on_epic_id = ? AND is_epic = 1"#
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
+                   FROM tickets WHERE depends_on_epic_id = ? AND is_epic = 1 AND trash_id IS NULL"#
             )?;
             
             let rows = stmt.query_map([epic_id], Self::map_ticket_row)?;
@@ -795,21 +872,21 @@ impl Database {
         })
     }
 
-    /// Check if all children of an epic are in Done column
+    /// Check if all children of an epic are in the done column
     pub fn are_all_epic_children_done(&self, epic_id: &str) -> Result<bool, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             // Count children not in Done
             let not_done: i32 = conn.query_row(
                 r#"SELECT COUNT(*) FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.epic_id = ? AND c.name != 'Done'"#,
+                   WHERE t.epic_id = ? AND c.role IS NOT 'done' AND t.trash_id IS NULL"#,
                 [epic_id],
                 |row| row.get(0),
             )?;
             
             // Also check there's at least one child
             let total: i32 = conn.query_row(
-                "SELECT COUNT(*) FROM tickets WHERE epic_id = ?",
+                "SELECT COUNT(*) FROM tickets WHERE epic_id = ? AND trash_id IS NULL",
                 [epic_id],
                 |row| row.get(0),
             )?;
@@ -822,7 +899,7 @@ impl Database {
     /// Used for cross-epic branching: when an epic depends on another, its first
     /// child should branch from the last child of the dependency epic.
     pub fn get_dependency_base_branch(&self, epic_id: &str) -> Result<Option<String>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             // First get the dependency epic id
             let depends_on: Option<String> = conn.query_row(
                 "SELECT depends_on_epic_id FROM tickets WHERE id = ?",
@@ -842,7 +919,7 @@ impl Database {
             let branch: Option<String> = conn.query_row(
                 r#"SELECT t.branch_name FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.epic_id = ? AND t.branch_name IS NOT NULL AND c.name = 'Done'
+                   WHERE t.epic_id = ? AND t.branch_name IS NOT NULL AND c.role = 'done'
                    ORDER BY t.order_in_epic DESC
                    LIMIT 1"#,
                 [&dependency_id],
@@ -856,13 +933,13 @@ impl Database {
     /// Get the final branch of an epic (the last completed child's branch name).
     /// This is used for consolidation epics to know which branch to merge from.
     pub fn get_epic_final_branch(&self, epic_id: &str) -> Result<Option<String>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             // Get the last child of the epic that has a branch_name and is in Done
             // Order by order_in_epic DESC to get the last child first
             let branch: Option<String> = conn.query_row(
                 r#"SELECT t.branch_name FROM tickets t
                    JOIN columns c ON t.column_id = c.id
-                   WHERE t.epic_id = ? AND t.branch_name IS NOT NULL AND c.name = 'Done'
+                   WHERE t.epic_id = ? AND t.branch_name IS NOT NULL AND c.role = 'done'
                    ORDER BY t.order_in_epic DESC
                    LIMIT 1"#,
                 [epic_id],
@@ -895,7 +972,7 @@ impl Database {
     /// Get the previous sibling of a child ticket in an epic (for chain branching)
     /// Returns the ticket that is one position before this ticket in the epic's order
     pub fn get_previous_epic_sibling(&self, ticket_id: &str) -> Result<Option<Ticket>, DbError> {
-        self.with_conn(|conn| {
+        self.with_reader(|conn| {
             // First, get this ticket's epic_id and order_in_epic
             let ticket_info: Option<(String, i32)> = conn.query_row(
                 "SELECT epic_id, order_in_epic FROM tickets WHERE id = ? AND epic_id IS NOT NULL",
@@ -917,7 +994,7 @@ impl Database {
                 r#"SELECT id, board_id, column_id, title, description_md, priority,
                           labels_json, created_at, updated_at, locked_by_run_id, 
                           lock_expires_at, project_id, agent_pref, workflow_type, model, branch_name,
-                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id
+                          is_epic, epic_id, order_in_epic, depends_on_epic_id, depends_on_epic_ids_json, scratchpad_id, custom_fields_json
                    FROM tickets WHERE epic_id = ? AND order_in_epic = ?"#
             )?;
             
@@ -928,6 +1005,73 @@ impl Database {
     }
 }
 
+/// The run currently holding the ticket lock, if any.
+/// [`Database::update_ticket_lock_owner`] on `conn`, for callers that hand
+/// the lock over in the same transaction as other rows
+pub(crate) fn transfer_lock(
+    conn: &Connection,
+    ticket_id: &str,
+    old_run_id: &str,
+    new_run_id: &str,
+    new_expires_at: Option<DateTime<Utc>>,
+) -> Result<(), DbError> {
+    let now = chrono::Utc::now();
+    let affected = if let Some(expires) = new_expires_at {
+        conn.execute(
+            r#"UPDATE tickets 
+               SET locked_by_run_id = ?, lock_expires_at = ?, updated_at = ?
+               WHERE id = ? AND locked_by_run_id = ?"#,
+            rusqlite::params![
+                new_run_id,
+                expires.to_rfc3339(),
+                now.to_rfc3339(),
+                ticket_id,
+                old_run_id,
+            ],
+        )?
+    } else {
+        conn.execute(
+            r#"UPDATE tickets 
+               SET locked_by_run_id = ?, updated_at = ?
+               WHERE id = ? AND locked_by_run_id = ?"#,
+            rusqlite::params![
+                new_run_id,
+                now.to_rfc3339(),
+                ticket_id,
+                old_run_id,
+            ],
+        )?
+    };
+    
+    if affected == 0 {
+        return Err(DbError::NotFound(format!(
+            "Ticket lock not found or not owned by run {}",
+            old_run_id
+        )));
+    }
+
+    record_change(conn, ticket_id, LOCK_FIELD, Some(old_run_id), Some(new_run_id), &Actor::run(new_run_id))?;
+    Ok(())
+}
+
+fn current_lock_holder(conn: &Connection, ticket_id: &str) -> Result<Option<String>, DbError> {
+    let holder: Option<Option<String>> = conn.query_row(
+        "SELECT locked_by_run_id FROM tickets WHERE id = ?",
+        [ticket_id],
+        |row| row.get(0),
+    ).optional()?;
+    Ok(holder.flatten())
+}
+
+/// The ticket's parent epic; the outer `None` means the ticket does not exist.
+fn current_epic_id(conn: &Connection, ticket_id: &str) -> Result<Option<Option<String>>, DbError> {
+    conn.query_row(
+        "SELECT epic_id FROM tickets WHERE id = ?",
+        [ticket_id],
+        |row| row.get(0),
+    ).optional().map_err(DbError::from)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -963,6 +1107,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         (board.id, ready_column.id.clone(), ticket)
@@ -991,6 +1136,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         assert_eq!(ticket.title, "Test Ticket");
@@ -1023,6 +1169,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let tickets = db.get_tickets(&board.id, None).unwrap();
@@ -1052,9 +1199,10 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
-        db.move_ticket(&ticket.id, &columns[1].id).unwrap();
+        db.move_ticket(&ticket.id, &columns[1].id, &Actor::user()).unwrap();
         
         let tickets = db.get_tickets(&board.id, Some(&columns[1].id)).unwrap();
         assert_eq!(tickets.len(), 1);
@@ -1091,9 +1239,10 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
-        db.set_ticket_project(&ticket.id, Some(&project.id)).unwrap();
+        db.set_ticket_project(&ticket.id, Some(&project.id), &Actor::user()).unwrap();
         
         let tickets = db.get_tickets(&board.id, None).unwrap();
         assert_eq!(tickets[0].project_id, Some(project.id));
@@ -1122,6 +1271,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let fetched = db.get_ticket(&created.id).unwrap();
@@ -1160,6 +1310,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let updated = db.update_ticket(&ticket.id, &UpdateTicket {
@@ -1179,7 +1330,8 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
-        }).unwrap();
+            custom_fields: None,
+        }, &Actor::user()).unwrap();
         
         assert_eq!(updated.title, "Updated Title");
         assert_eq!(updated.description_md, "Desc");
@@ -1206,7 +1358,8 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
-        });
+            custom_fields: None,
+        }, &Actor::user());
         assert!(matches!(result, Err(DbError::NotFound(_))));
     }
 
@@ -1239,6 +1392,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         assert_eq!(ticket.project_id, Some(project.id.clone()));
@@ -1260,7 +1414,8 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
-        }).unwrap();
+            custom_fields: None,
+        }, &Actor::user()).unwrap();
         
         assert_eq!(updated.project_id, None);
     }
@@ -1294,6 +1449,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let updated = db.update_ticket(&ticket.id, &UpdateTicket {
@@ -1313,7 +1469,8 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
-        }).unwrap();
+            custom_fields: None,
+        }, &Actor::user()).unwrap();
         
         assert_eq!(updated.project_id, Some(project.id));
         assert_eq!(updated.title, "Updated Title");
@@ -1342,6 +1499,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         db.delete_ticket(&ticket.id).unwrap();
@@ -1380,6 +1538,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1389,7 +1548,7 @@ mod tests {
         assert_eq!(locked.locked_by_run_id, Some("run-123".to_string()));
         assert!(locked.lock_expires_at.is_some());
         
-        db.unlock_ticket(&ticket.id).unwrap();
+        db.unlock_ticket(&ticket.id, &Actor::user()).unwrap();
         
         let unlocked = db.get_ticket(&ticket.id).unwrap();
         assert!(unlocked.locked_by_run_id.is_none());
@@ -1419,6 +1578,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let initial_expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1454,6 +1614,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1486,6 +1647,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1525,6 +1687,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         // Lock with an already-expired timestamp
@@ -1571,6 +1734,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1609,6 +1773,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1644,6 +1809,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1680,6 +1846,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = chrono::Utc::now() + chrono::Duration::minutes(30);
@@ -1702,7 +1869,7 @@ mod tests {
         let (_board_id, _ready_column_id, ticket) = setup_board_with_ready_ticket(&db);
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
-        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, "run-1", expires).unwrap();
+        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap();
         
         assert!(reserved.is_some());
         let reserved_ticket = reserved.unwrap();
@@ -1735,10 +1902,11 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
-        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, "run-1", expires).unwrap();
+        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap();
         
         assert!(reserved.is_none());
     }
@@ -1753,7 +1921,7 @@ mod tests {
         db.lock_ticket(&ticket.id, "existing-run", expires).unwrap();
         
         // Try to reserve - should return None since the only ticket is locked
-        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, "new-run", expires).unwrap();
+        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "new-run", expires, &Actor::user()).unwrap();
         assert!(reserved.is_none());
     }
     
@@ -1768,7 +1936,7 @@ mod tests {
         
         // Try to reserve - should succeed since the lock is expired
         let new_expires = Utc::now() + chrono::Duration::minutes(30);
-        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, "new-run", new_expires).unwrap();
+        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "new-run", new_expires, &Actor::user()).unwrap();
         
         assert!(reserved.is_some());
         let reserved_ticket = reserved.unwrap();
@@ -1800,19 +1968,65 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Cursor worker should not get this ticket
-        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, "cursor-run", expires).unwrap();
+        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "cursor-run", expires, &Actor::user()).unwrap();
         assert!(cursor_result.is_none());
         
         // Claude worker should get this ticket
-        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, "claude-run", expires).unwrap();
+        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "claude-run", expires, &Actor::user()).unwrap();
         assert!(claude_result.is_some());
     }
     
+    #[test]
+    fn reserve_next_ticket_uses_ready_role_not_name() {
+        let db = create_test_db();
+        let board = db.create_board("Board").unwrap();
+        let ready = db.require_column_by_role(&board.id, TicketState::Ready).unwrap();
+        db.update_column(&ready.id, &crate::db::models::UpdateColumn {
+            name: Some("Up Next".to_string()),
+            ..Default::default()
+        }).unwrap();
+        // A custom column called "Ready" has no role, so workers ignore it
+        let decoy = db.create_column(&board.id, &crate::db::models::CreateColumn {
+            name: "Ready".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        let make_ticket = |column_id: &str, title: &str| {
+            db.create_ticket(&CreateTicket {
+                board_id: board.id.clone(),
+                column_id: column_id.to_string(),
+                title: title.to_string(),
+                description_md: "".to_string(),
+                priority: Priority::Urgent,
+                labels: vec![],
+                project_id: None,
+                agent_pref: None,
+                workflow_type: WorkflowType::default(),
+                model: None,
+                branch_name: None,
+                is_epic: false,
+                epic_id: None,
+                depends_on_epic_id: None,
+                depends_on_epic_ids: vec![],
+                scratchpad_id: None,
+                custom_fields: Default::default(),
+            }).unwrap()
+        };
+        make_ticket(&decoy.id, "Decoy");
+        let queued = make_ticket(&ready.id, "Queued");
+
+        let expires = Utc::now() + chrono::Duration::minutes(30);
+        let reserved = db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap().unwrap();
+        assert_eq!(reserved.id, queued.id);
+        assert!(db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "run-2", expires, &Actor::user()).unwrap().is_none());
+    }
+    
     #[test]
     fn reserve_next_ticket_respects_project_filter() {
         let db = create_test_db();
@@ -1845,19 +2059,67 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Filter for different project should not find ticket
-        let other_result = db.reserve_next_ticket(Some("other-project"), AgentKind::Cursor, "run-1", expires).unwrap();
+        let other_result = db.reserve_next_ticket(Some("other-project"), AgentKind::Cursor, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap();
         assert!(other_result.is_none());
         
         // Filter for correct project should find ticket
-        let correct_result = db.reserve_next_ticket(Some(&project.id), AgentKind::Cursor, "run-2", expires).unwrap();
+        let correct_result = db.reserve_next_ticket(Some(&project.id), AgentKind::Cursor, &LabelFilter::default(), "run-2", expires, &Actor::user()).unwrap();
         assert!(correct_result.is_some());
     }
     
+    #[test]
+    fn reserve_next_ticket_respects_label_filter() {
+        let db = create_test_db();
+        let board = db.create_board("Board").unwrap();
+        let ready = db.require_column_by_role(&board.id, TicketState::Ready).unwrap();
+        let make_ticket = |title: &str, labels: &[&str]| {
+            db.create_ticket(&CreateTicket {
+                board_id: board.id.clone(),
+                column_id: ready.id.clone(),
+                title: title.to_string(),
+                description_md: "".to_string(),
+                priority: Priority::Medium,
+                labels: labels.iter().map(|l| l.to_string()).collect(),
+                project_id: None,
+                agent_pref: None,
+                workflow_type: WorkflowType::default(),
+                model: None,
+                branch_name: None,
+                is_epic: false,
+                epic_id: None,
+                depends_on_epic_id: None,
+                depends_on_epic_ids: vec![],
+                scratchpad_id: None,
+                custom_fields: Default::default(),
+            }).unwrap()
+        };
+        let human = make_ticket("Needs a human", &["frontend", "needs-human"]);
+        let frontend = make_ticket("Frontend", &["frontend"]);
+        let backend = make_ticket("Backend", &["backend"]);
+
+        let expires = Utc::now() + chrono::Duration::minutes(30);
+        let frontend_only = LabelFilter {
+            include: vec!["Frontend".to_string()],
+            exclude: vec!["needs-human".to_string()],
+        };
+        let reserved = db.reserve_next_ticket(None, AgentKind::Claude, &frontend_only, "run-1", expires, &Actor::user()).unwrap();
+        assert_eq!(reserved.map(|t| t.id), Some(frontend.id));
+        assert!(db.reserve_next_ticket(None, AgentKind::Claude, &frontend_only, "run-2", expires, &Actor::user()).unwrap().is_none());
+
+        let no_humans = LabelFilter { exclude: vec!["needs-human".to_string()], ..Default::default() };
+        let reserved = db.reserve_next_ticket(None, AgentKind::Claude, &no_humans, "run-3", expires, &Actor::user()).unwrap();
+        assert_eq!(reserved.map(|t| t.id), Some(backend.id));
+
+        let reserved = db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "run-4", expires, &Actor::user()).unwrap();
+        assert_eq!(reserved.map(|t| t.id), Some(human.id));
+    }
+
     #[test]
     fn reserve_next_ticket_prioritizes_by_priority_and_age() {
         let db = create_test_db();
@@ -1883,6 +2145,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         // Create urgent ticket second
@@ -1903,10 +2166,11 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
-        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, "run-1", expires).unwrap();
+        let reserved = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap();
         
         // Should get the urgent ticket even though low priority was created first
         assert!(reserved.is_some());
@@ -1938,16 +2202,17 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Claude worker should not get this ticket
-        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, "claude-run", expires).unwrap();
+        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "claude-run", expires, &Actor::user()).unwrap();
         assert!(claude_result.is_none());
         
         // Cursor worker should get this ticket
-        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, "cursor-run", expires).unwrap();
+        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "cursor-run", expires, &Actor::user()).unwrap();
         assert!(cursor_result.is_some());
     }
     
@@ -1976,19 +2241,20 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Cursor worker should get the ticket
-        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, "cursor-run", expires).unwrap();
+        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "cursor-run", expires, &Actor::user()).unwrap();
         assert!(cursor_result.is_some());
         assert_eq!(cursor_result.unwrap().id, ticket1.id);
         
         // Unlock and try with Claude
-        db.unlock_ticket(&ticket1.id).unwrap();
+        db.unlock_ticket(&ticket1.id, &Actor::user()).unwrap();
         
-        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, "claude-run", expires).unwrap();
+        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "claude-run", expires, &Actor::user()).unwrap();
         assert!(claude_result.is_some());
         assert_eq!(claude_result.unwrap().id, ticket1.id);
     }
@@ -2018,17 +2284,18 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Both agents should be able to claim it
-        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, "cursor-run", expires).unwrap();
+        let cursor_result = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "cursor-run", expires, &Actor::user()).unwrap();
         assert!(cursor_result.is_some());
         
-        db.unlock_ticket(&ticket.id).unwrap();
+        db.unlock_ticket(&ticket.id, &Actor::user()).unwrap();
         
-        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, "claude-run", expires).unwrap();
+        let claude_result = db.reserve_next_ticket(None, AgentKind::Claude, &LabelFilter::default(), "claude-run", expires, &Actor::user()).unwrap();
         assert!(claude_result.is_some());
     }
     
@@ -2057,12 +2324,13 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Worker should NOT pick up the epic
-        let result = db.reserve_next_ticket(None, AgentKind::Cursor, "run-1", expires).unwrap();
+        let result = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap();
         assert!(result.is_none(), "Epic ticket should not be picked up by workers");
     }
     
@@ -2091,6 +2359,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         // Create a child ticket in Ready - this SHOULD be picked up
@@ -2111,12 +2380,13 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let expires = Utc::now() + chrono::Duration::minutes(30);
         
         // Worker should pick up the child, not the epic
-        let result = db.reserve_next_ticket(None, AgentKind::Cursor, "run-1", expires).unwrap();
+        let result = db.reserve_next_ticket(None, AgentKind::Cursor, &LabelFilter::default(), "run-1", expires, &Actor::user()).unwrap();
         assert!(result.is_some(), "Child ticket should be picked up");
         assert_eq!(result.unwrap().id, child.id, "Should pick up child ticket, not epic");
     }
@@ -2144,11 +2414,12 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         assert!(ticket.branch_name.is_none());
         
-        db.set_ticket_branch(&ticket.id, "feat/abc123/add-feature").unwrap();
+        db.set_ticket_branch(&ticket.id, "feat/abc123/add-feature", &Actor::user()).unwrap();
         
         let updated = db.get_ticket(&ticket.id).unwrap();
         assert_eq!(updated.branch_name, Some("feat/abc123/add-feature".to_string()));
@@ -2157,7 +2428,7 @@ mod tests {
     #[test]
     fn set_ticket_branch_not_found() {
         let db = create_test_db();
-        let result = db.set_ticket_branch("nonexistent-id", "some-branch");
+        let result = db.set_ticket_branch("nonexistent-id", "some-branch", &Actor::user());
         assert!(matches!(result, Err(DbError::NotFound(_))));
     }
 
@@ -2184,6 +2455,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let before = ticket.updated_at;
@@ -2191,7 +2463,7 @@ mod tests {
         // Small delay to ensure timestamp differs
         std::thread::sleep(std::time::Duration::from_millis(10));
         
-        db.set_ticket_branch(&ticket.id, "fix/123/bug-fix").unwrap();
+        db.set_ticket_branch(&ticket.id, "fix/123/bug-fix", &Actor::user()).unwrap();
         
         let updated = db.get_ticket(&ticket.id).unwrap();
         assert!(updated.updated_at >= before);
@@ -2220,6 +2492,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         assert_eq!(ticket.branch_name, Some("feat/preset/my-branch".to_string()));
@@ -2252,6 +2525,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         // Verify Task 1 was automatically created
@@ -2286,6 +2560,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let tasks = db.get_tasks_for_ticket(&ticket.id).unwrap();
@@ -2322,6 +2597,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let tasks = db.get_tasks_for_ticket(&ticket.id).unwrap();
@@ -2357,6 +2633,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let tasks = db.get_tasks_for_ticket(&ticket.id).unwrap();
@@ -2384,6 +2661,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap()
     }
 
@@ -2405,6 +2683,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap()
     }
 
@@ -2558,12 +2837,13 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         assert!(ticket.epic_id.is_none());
         
         // Add to epic
-        db.add_ticket_to_epic(&epic.id, &ticket.id).unwrap();
+        db.add_ticket_to_epic(&epic.id, &ticket.id, &Actor::user()).unwrap();
         
         // Verify
         let updated = db.get_ticket(&ticket.id).unwrap();
@@ -2596,6 +2876,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let ticket = db.create_ticket(&CreateTicket {
@@ -2615,9 +2896,10 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
-        let result = db.add_ticket_to_epic(&not_epic.id, &ticket.id);
+        let result = db.add_ticket_to_epic(&not_epic.id, &ticket.id, &Actor::user());
         assert!(matches!(result, Err(DbError::Validation(_))));
     }
 
@@ -2633,7 +2915,7 @@ mod tests {
         
         assert!(child.epic_id.is_some());
         
-        db.remove_ticket_from_epic(&child.id).unwrap();
+        db.remove_ticket_from_epic(&child.id, &Actor::user()).unwrap();
         
         let updated = db.get_ticket(&child.id).unwrap();
         assert!(updated.epic_id.is_none());
@@ -2653,7 +2935,7 @@ mod tests {
         let child3 = create_child_ticket(&db, &board.id, &backlog.id, &epic.id, "Child 3");
         
         // Reorder: 3, 1, 2
-        db.reorder_epic_children(&epic.id, &[child3.id.clone(), child1.id.clone(), child2.id.clone()]).unwrap();
+        db.reorder_epic_children(&epic.id, &[child3.id.clone(), child1.id.clone(), child2.id.clone()], &Actor::user()).unwrap();
         
         let children = db.get_epic_children(&epic.id).unwrap();
         assert_eq!(children[0].id, child3.id);
@@ -2717,6 +2999,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let prev = db.get_previous_epic_sibling(&ticket.id).unwrap();
@@ -2800,6 +3083,7 @@ mod tests {
             depends_on_epic_id: Some(depends_on.to_string()),
             depends_on_epic_ids: vec![depends_on.to_string()],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap()
     }
 
@@ -2868,6 +3152,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         let _child2 = db.create_ticket(&CreateTicket {
@@ -2887,6 +3172,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap();
         
         // Create the dependent epic
@@ -0,0 +1,371 @@
+//! Soft delete.
+//!
+//! Deleting a ticket, board or scratchpad moves it to the trash: a `trash`
+//! row records the delete and every row it hid carries that row's id in
+//! `trash_id`, which the regular queries filter out. Nothing else about the
+//! rows changes, so a restore brings back epics with their children,
+//! dependencies, runs and comments exactly as they were. Entries are purged
+//! (hard-deleted) explicitly or by the retention policy.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::db::models::{TrashEntry, TrashItemType};
+use crate::db::{parse_datetime, Database, DbError};
+
+/// Entries whose item still exists; hard deletes elsewhere can remove an
+/// item without going through its trash entry
+const LIVE_ENTRY: &str = r#"(
+    (t.item_type = 'ticket' AND t.item_id IN (SELECT id FROM tickets))
+    OR (t.item_type = 'board' AND t.item_id IN (SELECT id FROM boards))
+    OR (t.item_type = 'scratchpad' AND t.item_id IN (SELECT id FROM scratchpads))
+)"#;
+
+impl Database {
+    /// Move a ticket to the trash. An epic takes its children with it.
+    pub fn trash_ticket(&self, ticket_id: &str) -> Result<TrashEntry, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let ticket: Option<(String, String)> = tx.query_row(
+                "SELECT title, board_id FROM tickets WHERE id = ? AND trash_id IS NULL",
+                [ticket_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()?;
+            let Some((title, board_id)) = ticket else {
+                return Err(DbError::NotFound(format!("Ticket {}", ticket_id)));
+            };
+
+            let trash_id = uuid::Uuid::new_v4().to_string();
+            let count = tx.execute(
+                "UPDATE tickets SET trash_id = ?1 WHERE trash_id IS NULL AND (id = ?2 OR epic_id = ?2)",
+                rusqlite::params![trash_id, ticket_id],
+            )?;
+            let entry = insert_entry(&tx, trash_id, TrashItemType::Ticket, ticket_id, title, Some(board_id), count)?;
+            tx.commit()?;
+            Ok(entry)
+        })
+    }
+
+    /// Move a board to the trash together with its tickets and scratchpads
+    pub fn trash_board(&self, board_id: &str) -> Result<TrashEntry, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let name: Option<String> = tx.query_row(
+                "SELECT name FROM boards WHERE id = ? AND trash_id IS NULL",
+                [board_id],
+                |row| row.get(0),
+            ).optional()?;
+            let Some(name) = name else {
+                return Err(DbError::NotFound(format!("Board {}", board_id)));
+            };
+
+            let trash_id = uuid::Uuid::new_v4().to_string();
+            tx.execute("UPDATE boards SET trash_id = ? WHERE id = ?", rusqlite::params![trash_id, board_id])?;
+            let count = tx.execute(
+                "UPDATE tickets SET trash_id = ? WHERE board_id = ? AND trash_id IS NULL",
+                rusqlite::params![trash_id, board_id],
+            )?;
+            tx.execute(
+                "UPDATE scratchpads SET trash_id = ? WHERE board_id = ? AND trash_id IS NULL",
+                rusqlite::params![trash_id, board_id],
+            )?;
+            let entry = insert_entry(&tx, trash_id, TrashItemType::Board, board_id, name, Some(board_id.to_string()), count)?;
+            tx.commit()?;
+            Ok(entry)
+        })
+    }
+
+    /// Move a scratchpad to the trash, and with `with_tickets` the epics and
+    /// tickets its plan created
+    pub fn trash_scratchpad(&self, id: &str, with_tickets: bool) -> Result<TrashEntry, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let scratchpad: Option<(String, String)> = tx.query_row(
+                "SELECT name, board_id FROM scratchpads WHERE id = ? AND trash_id IS NULL",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()?;
+            let Some((name, board_id)) = scratchpad else {
+                return Err(DbError::NotFound(format!("Scratchpad {}", id)));
+            };
+
+            let trash_id = uuid::Uuid::new_v4().to_string();
+            tx.execute("UPDATE scratchpads SET trash_id = ? WHERE id = ?", rusqlite::params![trash_id, id])?;
+            let count = if with_tickets {
+                tx.execute(
+                    r#"UPDATE tickets SET trash_id = ?1
+                       WHERE trash_id IS NULL
+                       AND (scratchpad_id = ?2 OR epic_id IN (SELECT id FROM tickets WHERE scratchpad_id = ?2))"#,
+                    rusqlite::params![trash_id, id],
+                )?
+            } else {
+                0
+            };
+            let entry = insert_entry(&tx, trash_id, TrashItemType::Scratchpad, id, name, Some(board_id), count)?;
+            tx.commit()?;
+            Ok(entry)
+        })
+    }
+
+    /// Trash entries, newest first, optionally only those of one board
+    pub fn list_trash(&self, board_id: Option<&str>) -> Result<Vec<TrashEntry>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT t.id, t.item_type, t.item_id, t.name, t.board_id, t.ticket_count, t.deleted_at
+                   FROM trash t
+                   WHERE (?1 IS NULL OR t.board_id = ?1) AND {}
+                   ORDER BY t.deleted_at DESC"#,
+                LIVE_ENTRY
+            ))?;
+            let rows = stmt.query_map([board_id], map_entry_row)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    pub fn get_trash_entry(&self, id: &str) -> Result<TrashEntry, DbError> {
+        self.with_reader(|conn| get_entry(conn, id))
+    }
+
+    /// Bring back everything a delete hid and drop its trash entry.
+    ///
+    /// Tickets and scratchpads whose board is itself in the trash, and epic
+    /// children whose epic is, cannot be restored on their own.
+    pub fn restore_trash(&self, id: &str) -> Result<TrashEntry, DbError> {
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            let entry = get_entry(&tx, id)?;
+
+            if entry.item_type != TrashItemType::Board {
+                let board_trashed: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM boards WHERE id = ? AND trash_id IS NOT NULL)",
+                    [&entry.board_id],
+                    |row| row.get(0),
+                )?;
+                if board_trashed {
+                    return Err(DbError::Validation(
+                        "Its board is in the trash; restore the board first".to_string(),
+                    ));
+                }
+            }
+            if entry.item_type == TrashItemType::Ticket {
+                let epic_trashed: bool = tx.query_row(
+                    r#"SELECT EXISTS(SELECT 1 FROM tickets t JOIN tickets e ON e.id = t.epic_id
+                       WHERE t.id = ? AND e.trash_id IS NOT NULL)"#,
+                    [&entry.item_id],
+                    |row| row.get(0),
+                )?;
+                if epic_trashed {
+                    return Err(DbError::Validation(
+                        "Its epic is in the trash; restore the epic first".to_string(),
+                    ));
+                }
+            }
+
+            for table in ["tickets", "scratchpads", "boards"] {
+                tx.execute(&format!("UPDATE {} SET trash_id = NULL WHERE trash_id = ?", table), [id])?;
+            }
+            tx.execute("DELETE FROM trash WHERE id = ?", [id])?;
+            tx.commit()?;
+            Ok(entry)
+        })
+    }
+
+    /// Permanently delete everything in a trash entry, with its comments,
+    /// runs and events
+    pub fn purge_trash(&self, id: &str) -> Result<(), DbError> {
+        self.get_trash_entry(id)?;
+        self.snapshot("purge-trash")?;
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            purge_entry(&tx, id)?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Purge entries deleted before `deleted_before`; returns how many
+    pub fn purge_trash_before(&self, deleted_before: DateTime<Utc>) -> Result<usize, DbError> {
+        let cutoff = deleted_before.to_rfc3339();
+        let expired: Vec<String> = self.with_reader(|conn| {
+            let mut stmt = conn.prepare("SELECT id FROM trash WHERE deleted_at < ?")?;
+            let rows = stmt.query_map([&cutoff], |row| row.get(0))?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })?;
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        self.snapshot("purge-trash")?;
+        self.with_conn_mut(|conn| {
+            let tx = conn.transaction()?;
+            for id in &expired {
+                purge_entry(&tx, id)?;
+            }
+            tx.commit()?;
+            Ok(expired.len())
+        })
+    }
+}
+
+fn insert_entry(
+    conn: &Connection,
+    id: String,
+    item_type: TrashItemType,
+    item_id: &str,
+    name: String,
+    board_id: Option<String>,
+    ticket_count: usize,
+) -> Result<TrashEntry, DbError> {
+    let deleted_at = Utc::now();
+    conn.execute(
+        r#"INSERT INTO trash (id, item_type, item_id, name, board_id, ticket_count, deleted_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        rusqlite::params![
+            id,
+            item_type.as_str(),
+            item_id,
+            name,
+            board_id,
+            ticket_count as i64,
+            deleted_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(TrashEntry {
+        id,
+        item_type,
+        item_id: item_id.to_string(),
+        name,
+        board_id,
+        ticket_count: ticket_count as i64,
+        deleted_at,
+    })
+}
+
+fn get_entry(conn: &Connection, id: &str) -> Result<TrashEntry, DbError> {
+    conn.query_row(
+        r#"SELECT id, item_type, item_id, name, board_id, ticket_count, deleted_at
+           FROM trash WHERE id = ?"#,
+        [id],
+        map_entry_row,
+    ).optional()?
+    .ok_or_else(|| DbError::NotFound(format!("Trash entry {}", id)))
+}
+
+fn map_entry_row(row: &rusqlite::Row) -> rusqlite::Result<TrashEntry> {
+    let item_type: String = row.get(1)?;
+    Ok(TrashEntry {
+        id: row.get(0)?,
+        item_type: TrashItemType::parse(&item_type).unwrap_or(TrashItemType::Ticket),
+        item_id: row.get(2)?,
+        name: row.get(3)?,
+        board_id: row.get(4)?,
+        ticket_count: row.get(5)?,
+        deleted_at: parse_datetime(row.get(6)?),
+    })
+}
+
+/// Hard-delete the rows of one entry. A purged board also takes tickets of
+/// other entries with it, so entries left without their item are dropped too.
+fn purge_entry(conn: &Connection, id: &str) -> Result<(), DbError> {
+    conn.execute(
+        r#"DELETE FROM tickets WHERE trash_id = ?1
+           OR board_id IN (SELECT id FROM boards WHERE trash_id = ?1)"#,
+        [id],
+    )?;
+    conn.execute("DELETE FROM scratchpads WHERE trash_id = ?", [id])?;
+    conn.execute(
+        "DELETE FROM columns WHERE board_id IN (SELECT id FROM boards WHERE trash_id = ?)",
+        [id],
+    )?;
+    conn.execute("DELETE FROM boards WHERE trash_id = ?", [id])?;
+    conn.execute(
+        &format!("DELETE FROM trash AS t WHERE t.id = ? OR NOT {}", LIVE_ENTRY),
+        [id],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{AuthorType, CreateComment, CreateTicket};
+
+    fn create_ticket(db: &Database, ticket: CreateTicket) -> String {
+        db.create_ticket(&ticket).unwrap().id
+    }
+
+    #[test]
+    fn trashed_epic_comes_back_with_its_children() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let column = db.get_columns(&board.id).unwrap().remove(0);
+        let epic = create_ticket(&db, CreateTicket {
+            is_epic: true,
+            ..CreateTicket::for_test(&board.id, &column.id, "Epic")
+        });
+        let child = create_ticket(&db, CreateTicket {
+            epic_id: Some(epic.clone()),
+            ..CreateTicket::for_test(&board.id, &column.id, "Child")
+        });
+        db.create_comment(&CreateComment {
+            ticket_id: child.clone(),
+            author_type: AuthorType::User,
+            body_md: "Note".to_string(),
+            metadata: None,
+        }).unwrap();
+
+        let entry = db.trash_ticket(&epic).unwrap();
+        assert_eq!(entry.ticket_count, 2);
+        assert!(db.get_tickets(&board.id, None).unwrap().is_empty());
+        assert!(matches!(db.get_ticket(&child), Err(DbError::NotFound(_))));
+        assert!(matches!(db.trash_ticket(&epic), Err(DbError::NotFound(_))));
+        assert_eq!(db.list_trash(Some(&board.id)).unwrap().len(), 1);
+
+        db.restore_trash(&entry.id).unwrap();
+        let children = db.get_epic_children(&epic).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, child);
+        assert_eq!(db.get_comments(&child).unwrap().len(), 1);
+        assert!(db.list_trash(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn items_on_a_trashed_board_need_the_board_back_first() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let column = db.get_columns(&board.id).unwrap().remove(0);
+        let ticket = create_ticket(&db, CreateTicket::for_test(&board.id, &column.id, "Ticket"));
+
+        let ticket_entry = db.trash_ticket(&ticket).unwrap();
+        let board_entry = db.trash_board(&board.id).unwrap();
+        assert!(db.get_board(&board.id).unwrap().is_none());
+        assert!(matches!(db.restore_trash(&ticket_entry.id), Err(DbError::Validation(_))));
+
+        db.restore_trash(&board_entry.id).unwrap();
+        assert!(db.get_board(&board.id).unwrap().is_some());
+        // The ticket was deleted on its own and stays in the trash
+        assert!(db.get_tickets(&board.id, None).unwrap().is_empty());
+        db.restore_trash(&ticket_entry.id).unwrap();
+        assert_eq!(db.get_tickets(&board.id, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn purging_a_board_drops_entries_of_its_tickets() {
+        let db = Database::open_in_memory().unwrap();
+        let board = db.create_board("Board").unwrap();
+        let column = db.get_columns(&board.id).unwrap().remove(0);
+        let ticket = create_ticket(&db, CreateTicket::for_test(&board.id, &column.id, "Ticket"));
+
+        db.trash_ticket(&ticket).unwrap();
+        let board_entry = db.trash_board(&board.id).unwrap();
+        assert_eq!(db.purge_trash_before(Utc::now() - chrono::Duration::days(1)).unwrap(), 0);
+
+        db.purge_trash(&board_entry.id).unwrap();
+        assert!(db.list_trash(None).unwrap().is_empty());
+        let remaining: i64 = db.with_conn(|conn| {
+            Ok(conn.query_row("SELECT COUNT(*) FROM trash", [], |row| row.get(0))?)
+        }).unwrap();
+        assert_eq!(remaining, 0);
+        assert!(matches!(db.restore_trash(&board_entry.id), Err(DbError::NotFound(_))));
+    }
+}
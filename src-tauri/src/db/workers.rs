@@ -0,0 +1,319 @@
+//! Saved worker definitions.
+//!
+//! Workers started from the app or the API are stored here so they come back
+//! after a restart. A definition's id is also the running worker's id, which
+//! is how the worker updates its lifetime stats after each ticket.
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::agents::AgentKind;
+use crate::db::models::{
+    CreateWorkerDefinition, UpdateWorkerDefinition, WorkerDefinition, WorkerSchedule,
+    DEFAULT_WORKER_AGENT_TIMEOUT_SECS, DEFAULT_WORKER_LOCK_DURATION_MINS, DEFAULT_WORKER_POLL_INTERVAL_SECS,
+};
+use crate::db::{parse_datetime, Database, DbError};
+
+const WORKER_COLUMNS: &str = r#"id, name, agent_type, project_id, labels_json, poll_interval_secs, agent_timeout_secs,
+    lock_duration_mins, schedule_json, enabled, tickets_processed, tickets_failed, last_ticket_at,
+    created_at, updated_at"#;
+
+impl Database {
+    /// Saved workers, oldest first
+    pub fn get_worker_definitions(&self) -> Result<Vec<WorkerDefinition>, DbError> {
+        self.with_reader(|conn| {
+            let mut stmt = conn.prepare(&format!("SELECT {} FROM workers ORDER BY created_at, rowid", WORKER_COLUMNS))?;
+            let rows = stmt.query_map([], map_worker_row)?;
+            rows.collect::<Result<Vec<_>, _>>().map_err(DbError::from)
+        })
+    }
+
+    pub fn get_worker_definition(&self, worker_id: &str) -> Result<Option<WorkerDefinition>, DbError> {
+        self.with_reader(|conn| get_worker(conn, worker_id))
+    }
+
+    pub fn create_worker_definition(&self, worker: &CreateWorkerDefinition) -> Result<WorkerDefinition, DbError> {
+        let name = match worker.name.as_deref().map(str::trim) {
+            Some("") => return Err(DbError::Validation("Worker name cannot be empty".to_string())),
+            Some(name) => name.to_string(),
+            None => format!("{} worker", worker.agent_type.as_str()),
+        };
+        let poll_interval_secs = worker.poll_interval_secs.unwrap_or(DEFAULT_WORKER_POLL_INTERVAL_SECS);
+        let agent_timeout_secs = worker.agent_timeout_secs.unwrap_or(DEFAULT_WORKER_AGENT_TIMEOUT_SECS);
+        let lock_duration_mins = worker.lock_duration_mins.unwrap_or(DEFAULT_WORKER_LOCK_DURATION_MINS);
+        validate_intervals(poll_interval_secs, agent_timeout_secs, lock_duration_mins)?;
+        let schedule = normalize_schedule(worker.schedule.as_ref())?;
+
+        self.with_conn(|conn| {
+            if let Some(project_id) = &worker.project_id {
+                check_project(conn, project_id)?;
+            }
+            let now = chrono::Utc::now();
+            let id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                &format!(
+                    "INSERT INTO workers ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, 0, NULL, ?, ?)",
+                    WORKER_COLUMNS
+                ),
+                rusqlite::params![
+                    id,
+                    name,
+                    worker.agent_type.as_str(),
+                    worker.project_id,
+                    serde_json::to_string(&worker.labels).unwrap_or_else(|_| "{}".to_string()),
+                    poll_interval_secs as i64,
+                    agent_timeout_secs as i64,
+                    lock_duration_mins,
+                    schedule.as_ref().and_then(|s| serde_json::to_string(s).ok()),
+                    worker.enabled,
+                    now.to_rfc3339(),
+                    now.to_rfc3339(),
+                ],
+            )?;
+            get_worker(conn, &id)?.ok_or_else(|| DbError::NotFound(format!("Worker {}", id)))
+        })
+    }
+
+    pub fn update_worker_definition(
+        &self,
+        worker_id: &str,
+        updates: &UpdateWorkerDefinition,
+    ) -> Result<WorkerDefinition, DbError> {
+        self.with_conn(|conn| {
+            let mut worker = get_worker(conn, worker_id)?
+                .ok_or_else(|| DbError::NotFound(format!("Worker {}", worker_id)))?;
+
+            if let Some(name) = &updates.name {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(DbError::Validation("Worker name cannot be empty".to_string()));
+                }
+                worker.name = name.to_string();
+            }
+            match updates.project_id.as_deref() {
+                Some("") => worker.project_id = None,
+                Some(project_id) => {
+                    check_project(conn, project_id)?;
+                    worker.project_id = Some(project_id.to_string());
+                }
+                None => {}
+            }
+            if let Some(labels) = &updates.labels {
+                worker.labels = labels.clone();
+            }
+            worker.poll_interval_secs = updates.poll_interval_secs.unwrap_or(worker.poll_interval_secs);
+            worker.agent_timeout_secs = updates.agent_timeout_secs.unwrap_or(worker.agent_timeout_secs);
+            worker.lock_duration_mins = updates.lock_duration_mins.unwrap_or(worker.lock_duration_mins);
+            validate_intervals(worker.poll_interval_secs, worker.agent_timeout_secs, worker.lock_duration_mins)?;
+            if let Some(schedule) = &updates.schedule {
+                worker.schedule = normalize_schedule(Some(schedule))?;
+            }
+            worker.enabled = updates.enabled.unwrap_or(worker.enabled);
+
+            conn.execute(
+                r#"UPDATE workers
+                   SET name = ?, project_id = ?, labels_json = ?, poll_interval_secs = ?, agent_timeout_secs = ?,
+                       lock_duration_mins = ?, schedule_json = ?, enabled = ?, updated_at = ?
+                   WHERE id = ?"#,
+                rusqlite::params![
+                    worker.name,
+                    worker.project_id,
+                    serde_json::to_string(&worker.labels).unwrap_or_else(|_| "{}".to_string()),
+                    worker.poll_interval_secs as i64,
+                    worker.agent_timeout_secs as i64,
+                    worker.lock_duration_mins,
+                    worker.schedule.as_ref().and_then(|s| serde_json::to_string(s).ok()),
+                    worker.enabled,
+                    chrono::Utc::now().to_rfc3339(),
+                    worker_id,
+                ],
+            )?;
+            get_worker(conn, worker_id)?.ok_or_else(|| DbError::NotFound(format!("Worker {}", worker_id)))
+        })
+    }
+
+    pub fn delete_worker_definition(&self, worker_id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let affected = conn.execute("DELETE FROM workers WHERE id = ?", [worker_id])?;
+            if affected == 0 {
+                return Err(DbError::NotFound(format!("Worker {}", worker_id)));
+            }
+            Ok(())
+        })
+    }
+
+    /// Add a finished ticket to a saved worker's stats. Does nothing for
+    /// workers that are not saved (e.g. ones from the server config file).
+    pub fn record_worker_ticket(&self, worker_id: &str, failed: bool) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                r#"UPDATE workers
+                   SET tickets_processed = tickets_processed + 1,
+                       tickets_failed = tickets_failed + ?,
+                       last_ticket_at = ?
+                   WHERE id = ?"#,
+                rusqlite::params![failed as i64, chrono::Utc::now().to_rfc3339(), worker_id],
+            )?;
+            Ok(())
+        })
+    }
+}
+
+fn get_worker(conn: &Connection, worker_id: &str) -> Result<Option<WorkerDefinition>, DbError> {
+    conn.query_row(
+        &format!("SELECT {} FROM workers WHERE id = ?", WORKER_COLUMNS),
+        [worker_id],
+        map_worker_row,
+    ).optional().map_err(DbError::from)
+}
+
+fn map_worker_row(row: &rusqlite::Row) -> rusqlite::Result<WorkerDefinition> {
+    let agent_type: String = row.get(2)?;
+    let labels_json: String = row.get(4)?;
+    let schedule_json: Option<String> = row.get(8)?;
+    let poll_interval_secs: i64 = row.get(5)?;
+    let agent_timeout_secs: i64 = row.get(6)?;
+    let tickets_processed: i64 = row.get(10)?;
+    let tickets_failed: i64 = row.get(11)?;
+    Ok(WorkerDefinition {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        agent_type: AgentKind::parse(&agent_type).unwrap_or(AgentKind::Cursor),
+        project_id: row.get(3)?,
+        labels: serde_json::from_str(&labels_json).unwrap_or_default(),
+        poll_interval_secs: poll_interval_secs.max(0) as u64,
+        agent_timeout_secs: agent_timeout_secs.max(0) as u64,
+        lock_duration_mins: row.get(7)?,
+        schedule: schedule_json.and_then(|s| serde_json::from_str(&s).ok()),
+        enabled: row.get(9)?,
+        tickets_processed: tickets_processed.max(0) as u64,
+        tickets_failed: tickets_failed.max(0) as u64,
+        last_ticket_at: row.get::<_, Option<String>>(12)?.map(parse_datetime),
+        created_at: parse_datetime(row.get(13)?),
+        updated_at: parse_datetime(row.get(14)?),
+    })
+}
+
+pub(crate) fn check_project(conn: &Connection, project_id: &str) -> Result<(), DbError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?)",
+        [project_id],
+        |row| row.get(0),
+    )?;
+    if exists {
+        Ok(())
+    } else {
+        Err(DbError::NotFound(format!("Project {}", project_id)))
+    }
+}
+
+fn validate_intervals(poll_interval_secs: u64, agent_timeout_secs: u64, lock_duration_mins: i64) -> Result<(), DbError> {
+    if poll_interval_secs == 0 || agent_timeout_secs == 0 || lock_duration_mins <= 0 {
+        return Err(DbError::Validation(
+            "Poll interval, agent timeout and lock duration must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check window times; a schedule without windows means "always"
+pub(crate) fn normalize_schedule(schedule: Option<&WorkerSchedule>) -> Result<Option<WorkerSchedule>, DbError> {
+    let Some(schedule) = schedule.filter(|s| !s.windows.is_empty()) else {
+        return Ok(None);
+    };
+    for window in &schedule.windows {
+        let parse = |time: &str| chrono::NaiveTime::parse_from_str(time, "%H:%M")
+            .map_err(|_| DbError::Validation(format!("Schedule time '{}' must be HH:MM", time)));
+        if parse(&window.start)? == parse(&window.end)? {
+            return Err(DbError::Validation("Schedule windows must not start and end at the same time".to_string()));
+        }
+    }
+    Ok(Some(schedule.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{LabelFilter, ScheduleWindow};
+
+    fn claude_worker() -> CreateWorkerDefinition {
+        CreateWorkerDefinition {
+            agent_type: AgentKind::Claude,
+            name: None,
+            project_id: None,
+            labels: LabelFilter::default(),
+            poll_interval_secs: None,
+            agent_timeout_secs: None,
+            lock_duration_mins: None,
+            schedule: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn definitions_round_trip_with_defaults() {
+        let db = Database::open_in_memory().unwrap();
+        let worker = db.create_worker_definition(&CreateWorkerDefinition {
+            labels: LabelFilter { include: vec!["frontend".to_string()], ..Default::default() },
+            schedule: Some(WorkerSchedule {
+                windows: vec![ScheduleWindow {
+                    days: vec![chrono::Weekday::Mon, chrono::Weekday::Fri],
+                    start: "19:00".to_string(),
+                    end: "07:00".to_string(),
+                }],
+            }),
+            ..claude_worker()
+        }).unwrap();
+
+        assert_eq!(worker.name, "claude worker");
+        assert_eq!(worker.poll_interval_secs, DEFAULT_WORKER_POLL_INTERVAL_SECS);
+        let loaded = db.get_worker_definition(&worker.id).unwrap().unwrap();
+        assert_eq!(loaded.labels.include, vec!["frontend"]);
+        assert_eq!(loaded.schedule, worker.schedule);
+
+        let updated = db.update_worker_definition(&worker.id, &UpdateWorkerDefinition {
+            name: Some("Nightly".to_string()),
+            schedule: Some(WorkerSchedule::default()),
+            enabled: Some(false),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(updated.name, "Nightly");
+        assert!(updated.schedule.is_none());
+        assert!(!updated.enabled);
+
+        db.delete_worker_definition(&worker.id).unwrap();
+        assert!(db.get_worker_definitions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn stats_accumulate_per_worker() {
+        let db = Database::open_in_memory().unwrap();
+        let worker = db.create_worker_definition(&claude_worker()).unwrap();
+
+        db.record_worker_ticket(&worker.id, false).unwrap();
+        db.record_worker_ticket(&worker.id, true).unwrap();
+        db.record_worker_ticket("not-saved", false).unwrap();
+
+        let worker = db.get_worker_definition(&worker.id).unwrap().unwrap();
+        assert_eq!(worker.tickets_processed, 2);
+        assert_eq!(worker.tickets_failed, 1);
+        assert!(worker.last_ticket_at.is_some());
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        let db = Database::open_in_memory().unwrap();
+        let bad_time = CreateWorkerDefinition {
+            schedule: Some(WorkerSchedule {
+                windows: vec![ScheduleWindow { days: vec![], start: "7pm".to_string(), end: "07:00".to_string() }],
+            }),
+            ..claude_worker()
+        };
+        assert!(matches!(db.create_worker_definition(&bad_time), Err(DbError::Validation(_))));
+
+        let no_poll = CreateWorkerDefinition { poll_interval_secs: Some(0), ..claude_worker() };
+        assert!(matches!(db.create_worker_definition(&no_poll), Err(DbError::Validation(_))));
+
+        let unknown_project = CreateWorkerDefinition { project_id: Some("missing".to_string()), ..claude_worker() };
+        assert!(matches!(db.create_worker_definition(&unknown_project), Err(DbError::NotFound(_))));
+    }
+}
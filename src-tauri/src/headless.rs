@@ -0,0 +1,363 @@
+//! Headless server mode.
+//!
+//! Starts the same services as the desktop app (database, API server, lock
+//! cleanup, spool processor and workers) without creating a Tauri window.
+//! Agent events go to SSE clients through a [`BroadcastEventSink`].
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde::Deserialize;
+
+use crate::agents::{process_tree, recovery};
+use crate::agents::claude_settings::ClaudeApiSettingsState;
+use crate::agents::sink::BroadcastEventSink;
+use crate::agents::worker::{WorkerConfig, WORKER_MANAGER};
+use crate::agents::{AgentKind, ClaudeApiConfig};
+use crate::api::{self, ApiConfig, AppState};
+use crate::db::backup::BackupPolicy;
+use crate::db::retention::RetentionPolicy;
+use crate::db::workers::normalize_schedule;
+use crate::db::{Database, LabelFilter, WorkerSchedule};
+
+/// Same identifier as `tauri.conf.json`, so the default data directory is
+/// shared with the desktop app on Linux.
+const APP_IDENTIFIER: &str = "com.agent-kanban.app";
+
+/// Contents of the server config file (TOML)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Holds the database, logs, API token and Claude settings
+    pub data_dir: PathBuf,
+    pub host: String,
+    pub port: u16,
+    /// API token; read from (or generated into) `data_dir/api_token` when unset
+    pub token: Option<String>,
+    /// Directory containing the agent hook scripts (`cursor-hook.js`, ...)
+    pub scripts_dir: Option<PathBuf>,
+    /// Claude API settings file; defaults to `data_dir/claude_api_settings.json`
+    pub claude_settings: Option<PathBuf>,
+    pub cleanup_interval_secs: u64,
+    /// Scheduled backups into `data_dir/backups` and their retention
+    pub backup: BackupPolicy,
+    /// Event compaction, archival of Done tickets and vacuuming
+    pub retention: RetentionPolicy,
+    /// Workers started when the server comes up
+    pub workers: Vec<WorkerSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkerSpec {
+    pub agent: AgentKind,
+    pub project_id: Option<String>,
+    /// `include` / `exclude` label lists routing tickets to these workers
+    #[serde(default)]
+    pub labels: LabelFilter,
+    /// Local-time windows the workers take tickets in; always when unset
+    pub schedule: Option<WorkerSchedule>,
+    #[serde(default = "default_worker_count")]
+    pub count: usize,
+    pub poll_interval_secs: Option<u64>,
+    pub agent_timeout_secs: Option<u64>,
+}
+
+fn default_worker_count() -> usize {
+    1
+}
+
+/// Data directory used by the desktop app (and by default the server)
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_IDENTIFIER)
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            data_dir: default_data_dir(),
+            host: "127.0.0.1".to_string(),
+            port: 7432,
+            token: None,
+            scripts_dir: None,
+            claude_settings: None,
+            cleanup_interval_secs: 60,
+            backup: BackupPolicy::default(),
+            retention: RetentionPolicy::default(),
+            workers: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid config {}: {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        config.host_octets()?;
+        for spec in &config.workers {
+            normalize_schedule(spec.schedule.as_ref())
+                .map_err(|e| anyhow::anyhow!("invalid {} worker schedule: {}", spec.agent.as_str(), e))?;
+        }
+        Ok(config)
+    }
+
+    fn host_octets(&self) -> anyhow::Result<[u8; 4]> {
+        let addr: std::net::Ipv4Addr = self.host.parse()
+            .map_err(|_| anyhow::anyhow!("host must be an IPv4 address, got '{}'", self.host))?;
+        Ok(addr.octets())
+    }
+
+    fn hook_script_path(&self) -> Option<String> {
+        let dir = self.scripts_dir.clone().unwrap_or_else(|| self.data_dir.join("scripts"));
+        let path = dir.join("cursor-hook.js");
+        if path.exists() {
+            Some(path.to_string_lossy().to_string())
+        } else {
+            tracing::warn!("Hook script not found at {}; agent events will not be tracked", path.display());
+            None
+        }
+    }
+}
+
+/// Run the server until Ctrl-C / SIGTERM.
+pub async fn run(config: ServerConfig) -> anyhow::Result<()> {
+    std::fs::create_dir_all(&config.data_dir)?;
+    tracing::info!("Agent Kanban server starting, data directory: {:?}", config.data_dir);
+
+    let database = Arc::new(Database::open(config.data_dir.join("agent-kanban.db"))?);
+    let orphans = process_tree::reap_orphans(&database);
+    if orphans > 0 {
+        tracing::warn!("Startup cleanup: stopped {} orphaned agent process group(s)", orphans);
+    }
+    process_tree::record_in(database.clone());
+    let resumable = recovery::interrupt_workflows(&database);
+    if resumable > 0 {
+        tracing::info!("Startup cleanup: {} interrupted workflow(s) can be resumed", resumable);
+    }
+    match database.cleanup_orphaned_in_progress_tasks() {
+        Ok(count) if count > 0 => {
+            tracing::info!("Startup cleanup: reset {} orphaned in-progress task(s)", count);
+        }
+        Err(e) => tracing::warn!("Startup cleanup failed: {}", e),
+        _ => {}
+    }
+
+    let token = match config.token.clone() {
+        Some(token) => token,
+        None => api::load_or_create_token(&config.data_dir.join("api_token"))?,
+    };
+    let api_config = ApiConfig {
+        port: config.port,
+        token: token.clone(),
+        host: config.host_octets()?,
+        cleanup: api::CleanupConfig {
+            check_interval_secs: config.cleanup_interval_secs,
+            retention: config.retention.clone(),
+        },
+        backup: config.backup.clone(),
+    };
+
+    let claude_settings = ClaudeApiSettingsState::new_with_path(
+        config.claude_settings.clone()
+            .unwrap_or_else(|| config.data_dir.join("claude_api_settings.json")),
+    );
+    let hook_script_path = config.hook_script_path();
+    let spool_dir = config.data_dir.join("spool");
+
+    let event_tx = api::create_event_channel();
+    let state = AppState::with_event_tx(database.clone(), token.clone(), event_tx.clone())
+        .with_claude_settings(claude_settings.clone())
+        .with_hook_script_path(hook_script_path.clone())
+        .with_spool_dir(spool_dir.clone());
+    let handle = api::start_server_with_state(
+        database.clone(),
+        api_config,
+        state,
+    ).await.map_err(|e| anyhow::anyhow!("Failed to start API server: {}", e))?;
+
+    let api_url = format!("http://{}", handle.addr);
+    tracing::info!("API server listening on {}", api_url);
+
+    // The CLI finds the server through this file, like it does for the desktop app
+    std::fs::write(config.data_dir.join("api_port"), handle.addr.port().to_string())?;
+
+    // Hook scripts and agents spawned by workers read these; the hook spools
+    // events it cannot post into our data directory
+    std::env::set_var("AGENT_KANBAN_API_TOKEN", &token);
+    std::env::set_var("AGENT_KANBAN_API_PORT", handle.addr.port().to_string());
+    std::env::set_var("AGENT_KANBAN_API_URL", &api_url);
+    std::env::set_var("AGENT_KANBAN_SPOOL_DIR", &spool_dir);
+
+    let spool_db = database.clone();
+    tokio::spawn(async move {
+        api::start_spool_processor(spool_db, spool_dir).await;
+    });
+
+    let sink = Arc::new(BroadcastEventSink::new(event_tx));
+    for spec in &config.workers {
+        for _ in 0..spec.count {
+            let defaults = WorkerConfig::default();
+            let worker_config = WorkerConfig {
+                agent_type: spec.agent,
+                project_id: spec.project_id.clone(),
+                labels: spec.labels.clone(),
+                schedule: spec.schedule.clone(),
+                api_url: api_url.clone(),
+                api_token: token.clone(),
+                poll_interval_secs: spec.poll_interval_secs.unwrap_or(defaults.poll_interval_secs),
+                agent_timeout_secs: spec.agent_timeout_secs.unwrap_or(defaults.agent_timeout_secs),
+                hook_script_path: hook_script_path.clone(),
+                event_sink: Some(sink.clone()),
+                claude_api_config: (spec.agent == AgentKind::Claude)
+                    .then(|| ClaudeApiConfig::from(claude_settings.get())),
+                ..defaults
+            };
+            let worker_id = WORKER_MANAGER.start_worker(worker_config, database.clone());
+            tracing::info!("Started {} worker {}", spec.agent.as_str(), worker_id);
+        }
+    }
+
+    crate::agents::slots::apply_saved_limits(&database);
+
+    // Workers saved through the API (or in the desktop app on the same data dir)
+    let base_config = |agent: AgentKind| WorkerConfig {
+        agent_type: agent,
+        api_url: api_url.clone(),
+        api_token: token.clone(),
+        hook_script_path: hook_script_path.clone(),
+        event_sink: Some(sink.clone()),
+        claude_api_config: (agent == AgentKind::Claude)
+            .then(|| ClaudeApiConfig::from(claude_settings.get())),
+        ..WorkerConfig::default()
+    };
+    let saved = WORKER_MANAGER.start_saved_workers(&database, base_config)?;
+    tracing::info!("Started {} saved worker(s)", saved);
+    recovery::recover_interrupted_workflows(&database, base_config);
+
+    shutdown_signal().await;
+    tracing::info!("Shutting down");
+
+    WORKER_MANAGER.stop_all().await;
+    handle.shutdown();
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to install SIGTERM handler: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = ServerConfig::parse("").unwrap();
+        assert_eq!(config.port, 7432);
+        assert_eq!(config.host, "127.0.0.1");
+        assert!(config.data_dir.ends_with(APP_IDENTIFIER));
+        assert!(config.workers.is_empty());
+    }
+
+    #[test]
+    fn parses_workers() {
+        let config = ServerConfig::parse(r#"
+            data_dir = "/srv/kanban"
+            host = "0.0.0.0"
+            port = 8080
+
+            [[workers]]
+            agent = "claude"
+            count = 4
+
+            [[workers]]
+            agent = "cursor"
+            project_id = "p1"
+            labels = { include = ["frontend"], exclude = ["needs-human"] }
+            schedule = { windows = [{ days = ["Mon", "Tue"], start = "19:00", end = "07:00" }] }
+        "#).unwrap();
+
+        assert_eq!(config.data_dir, PathBuf::from("/srv/kanban"));
+        assert_eq!(config.host_octets().unwrap(), [0, 0, 0, 0]);
+        assert_eq!(config.workers.len(), 2);
+        assert_eq!(config.workers[0].agent, AgentKind::Claude);
+        assert_eq!(config.workers[0].count, 4);
+        assert_eq!(config.workers[1].count, 1);
+        assert_eq!(config.workers[1].project_id.as_deref(), Some("p1"));
+        assert!(config.workers[0].labels.is_empty());
+        assert_eq!(config.workers[1].labels.exclude, vec!["needs-human"]);
+        assert!(config.workers[0].schedule.is_none());
+        assert_eq!(config.workers[1].schedule.as_ref().unwrap().windows[0].days.len(), 2);
+    }
+
+    #[test]
+    fn rejects_bad_worker_schedule() {
+        let err = ServerConfig::parse(r#"
+            [[workers]]
+            agent = "claude"
+            schedule = { windows = [{ start = "7pm", end = "07:00" }] }
+        "#).unwrap_err();
+        assert!(err.to_string().contains("HH:MM"));
+    }
+
+    #[test]
+    fn parses_backup_policy() {
+        let config = ServerConfig::parse(r#"
+            [backup]
+            interval_mins = 60
+            keep_last = 3
+        "#).unwrap();
+        assert_eq!(config.backup.interval_mins, 60);
+        assert_eq!(config.backup.keep_last, 3);
+        assert_eq!(config.backup.max_age_days, Some(30));
+        assert!(ServerConfig::parse("[backup]\nkeep = 3").is_err());
+    }
+
+    #[test]
+    fn parses_retention_policy() {
+        let config = ServerConfig::parse(r#"
+            [retention]
+            compact_events_after_days = 7
+            vacuum = false
+        "#).unwrap();
+        assert_eq!(config.retention.compact_events_after_days, 7);
+        assert_eq!(config.retention.archive_done_after_days, 90);
+        assert!(!config.retention.vacuum);
+    }
+
+    #[test]
+    fn rejects_bad_host_and_unknown_keys() {
+        assert!(ServerConfig::parse(r#"host = "localhost""#).is_err());
+        assert!(ServerConfig::parse(r#"prot = 1"#).is_err());
+    }
+}
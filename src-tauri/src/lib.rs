@@ -4,3 +4,6 @@ pub mod api;
 pub mod agents;
 pub mod logging;
 pub mod lifecycle;
+pub mod headless;
+pub mod cli;
+pub mod remote_worker;
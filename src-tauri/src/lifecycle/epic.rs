@@ -4,7 +4,7 @@
 //! Also handles cross-epic dependencies (depends_on_epic_id).
 
 use std::sync::Arc;
-use crate::db::{Database, DbError, Ticket, AuthorType, CreateComment, ScratchpadStatus, UpdateTicket};
+use crate::db::{Actor, Database, DbError, Ticket, AuthorType, CreateComment, ScratchpadStatus, UpdateTicket};
 use super::TicketState;
 
 /// Result of epic advancement
@@ -47,7 +47,7 @@ pub fn on_epic_moved_to_ready(
         // Determine if dependency is complete
         // If column lookup fails, treat as incomplete (fail-safe: block the epic)
         let dependency_complete = match dep_column {
-            Some(ref col) => col.name == "Done",
+            Some(ref col) => col.role == Some(TicketState::Done),
             None => {
                 tracing::warn!(
                     "Epic {}: could not find column {} for dependency {}, treating as incomplete",
@@ -60,8 +60,8 @@ pub fn on_epic_moved_to_ready(
         if !dependency_complete {
             // Dependency not complete - try to move epic to Backlog, but always block
             // regardless of whether the column lookup succeeds
-            if let Some(backlog) = db.find_column_by_name(&epic.board_id, "Backlog")? {
-                db.move_ticket(&epic.id, &backlog.id)?;
+            if let Some(backlog) = db.find_column_by_role(&epic.board_id, TicketState::Backlog)? {
+                db.move_ticket(&epic.id, &backlog.id, &Actor::system())?;
                 
                 // Add system comment
                 db.create_comment(&CreateComment {
@@ -101,8 +101,8 @@ pub fn on_epic_moved_to_ready(
     // Get the next pending child (first child in Backlog)
     if let Some(child) = db.get_next_pending_child(&epic.id)? {
         // Find the Ready column for this board
-        if let Some(ready_column) = db.find_column_by_name(&epic.board_id, "Ready")? {
-            db.move_ticket(&child.id, &ready_column.id)?;
+        if let Some(ready_column) = db.find_column_by_role(&epic.board_id, TicketState::Ready)? {
+            db.move_ticket(&child.id, &ready_column.id, &Actor::system())?;
             
             tracing::info!(
                 "Epic {}: advanced child {} to Ready",
@@ -181,7 +181,7 @@ fn populate_consolidation_tickets(
         db.update_ticket(&child.id, &UpdateTicket {
             description_md: Some(description.clone()),
             ..Default::default()
-        })?;
+        }, &Actor::system())?;
         
         tracing::info!(
             "Consolidation epic {}: updated child {} with branch merge instructions",
@@ -224,8 +224,8 @@ pub fn on_child_completed(
     // Check if all children are done
     if db.are_all_epic_children_done(&epic.id)? {
         // Move epic to Done
-        if let Some(done_column) = db.find_column_by_name(&epic.board_id, "Done")? {
-            db.move_ticket(&epic.id, &done_column.id)?;
+        if let Some(done_column) = db.find_column_by_role(&epic.board_id, TicketState::Done)? {
+            db.move_ticket(&epic.id, &done_column.id, &Actor::system())?;
             
             // Add system comment
             db.create_comment(&CreateComment {
@@ -252,8 +252,8 @@ pub fn on_child_completed(
     } else {
         // Get the next pending child
         if let Some(next_child) = db.get_next_pending_child(&epic.id)? {
-            if let Some(ready_column) = db.find_column_by_name(&epic.board_id, "Ready")? {
-                db.move_ticket(&next_child.id, &ready_column.id)?;
+            if let Some(ready_column) = db.find_column_by_role(&epic.board_id, TicketState::Ready)? {
+                db.move_ticket(&next_child.id, &ready_column.id, &Actor::system())?;
                 
                 tracing::info!(
                     "Epic {}: advanced next child {} to Ready after {} completed",
@@ -285,10 +285,10 @@ pub fn advance_dependent_epics(
         let current_column = columns.iter().find(|c| c.id == dependent.column_id);
         
         if let Some(col) = current_column {
-            if col.name == "Backlog" {
+            if col.role == Some(TicketState::Backlog) {
                 // Move to Ready
-                if let Some(ready_column) = db.find_column_by_name(&dependent.board_id, "Ready")? {
-                    db.move_ticket(&dependent.id, &ready_column.id)?;
+                if let Some(ready_column) = db.find_column_by_role(&dependent.board_id, TicketState::Ready)? {
+                    db.move_ticket(&dependent.id, &ready_column.id, &Actor::system())?;
                     
                     // Add system comment
                     db.create_comment(&CreateComment {
@@ -368,12 +368,12 @@ pub fn on_child_blocked(
         .find(|c| c.id == epic.column_id);
     
     if let Some(col) = epic_column {
-        let current_state = TicketState::from_column_name(&col.name);
+        let current_state = col.role;
         
         // Only block epic if it's not already blocked or done
         if current_state != Some(TicketState::Blocked) && current_state != Some(TicketState::Done) {
-            if let Some(blocked_column) = db.find_column_by_name(&epic.board_id, "Blocked")? {
-                db.move_ticket(&epic.id, &blocked_column.id)?;
+            if let Some(blocked_column) = db.find_column_by_role(&epic.board_id, TicketState::Blocked)? {
+                db.move_ticket(&epic.id, &blocked_column.id, &Actor::system())?;
                 
                 // Add system comment explaining why
                 db.create_comment(&CreateComment {
@@ -424,6 +424,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap()
     }
 
@@ -445,6 +446,7 @@ mod tests {
             depends_on_epic_id: None,
             depends_on_epic_ids: vec![],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap()
     }
 
@@ -574,6 +576,7 @@ mod tests {
             depends_on_epic_id: Some(depends_on.to_string()),
             depends_on_epic_ids: vec![depends_on.to_string()],
             scratchpad_id: None,
+            custom_fields: Default::default(),
         }).unwrap()
     }
 
@@ -1,7 +1,9 @@
 pub mod state;
 pub mod rules;
 pub mod epic;
+pub mod tasks;
 
 pub use state::*;
 pub use rules::*;
 pub use epic::*;
+pub use tasks::*;
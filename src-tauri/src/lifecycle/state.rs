@@ -24,6 +24,30 @@ impl TicketState {
         }
     }
 
+    /// Parse the stored form produced by [`TicketState::as_str`]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "backlog" => Some(Self::Backlog),
+            "ready" => Some(Self::Ready),
+            "in_progress" => Some(Self::InProgress),
+            "blocked" => Some(Self::Blocked),
+            "review" => Some(Self::Review),
+            "done" => Some(Self::Done),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Backlog => "backlog",
+            Self::Ready => "ready",
+            Self::InProgress => "in_progress",
+            Self::Blocked => "blocked",
+            Self::Review => "review",
+            Self::Done => "done",
+        }
+    }
+
     pub fn to_column_name(&self) -> &'static str {
         match self {
             Self::Backlog => "Backlog",
@@ -52,6 +76,36 @@ mod tests {
         assert_eq!(TicketState::from_column_name("unknown"), None);
     }
 
+    #[test]
+    fn test_parse_round_trips_as_str() {
+        for state in [
+            TicketState::Backlog,
+            TicketState::Ready,
+            TicketState::InProgress,
+            TicketState::Blocked,
+            TicketState::Review,
+            TicketState::Done,
+        ] {
+            assert_eq!(TicketState::parse(state.as_str()), Some(state));
+        }
+        assert_eq!(TicketState::parse("In Progress"), None);
+    }
+
+    #[test]
+    fn test_as_str_matches_serde() {
+        for state in [
+            TicketState::Backlog,
+            TicketState::Ready,
+            TicketState::InProgress,
+            TicketState::Blocked,
+            TicketState::Review,
+            TicketState::Done,
+        ] {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(json, format!("\"{}\"", state.as_str()));
+        }
+    }
+
     #[test]
     fn test_to_column_name() {
         assert_eq!(TicketState::Backlog.to_column_name(), "Backlog");
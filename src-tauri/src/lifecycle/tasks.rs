@@ -0,0 +1,40 @@
+//! Reopening finished tickets when new work is queued on them
+
+use crate::db::{Actor, Database, DbError};
+use super::TicketState;
+
+/// Column roles that should trigger a move back to Ready when a new task is added
+const COMPLETED_ROLES: &[TicketState] = &[TicketState::Done, TicketState::Review];
+
+/// Move ticket back to Ready if it's in a completed column (Done/Review)
+/// This allows workers to pick up the ticket again for the new task
+pub fn move_to_ready_if_completed(db: &Database, ticket_id: &str) -> Result<(), DbError> {
+    // Get the ticket to find its current column and board
+    let ticket = db.get_ticket(ticket_id)?;
+
+    // Get all columns for the board
+    let columns = db.get_columns(&ticket.board_id)?;
+
+    // Find the current column
+    let current_column = columns.iter()
+        .find(|c| c.id == ticket.column_id);
+
+    // Check if ticket is in a completed column
+    let is_completed = current_column
+        .and_then(|c| c.role)
+        .is_some_and(|role| COMPLETED_ROLES.contains(&role));
+
+    if is_completed {
+        // Find the Ready column (where workers pick up tickets)
+        if let Some(ready) = columns.iter().find(|c| c.role == Some(TicketState::Ready)) {
+            tracing::info!(
+                "Moving ticket {} from {} back to Ready for new task",
+                ticket_id,
+                current_column.map(|c| c.name.as_str()).unwrap_or("unknown")
+            );
+            db.move_ticket(ticket_id, &ready.id, &Actor::system())?;
+        }
+    }
+
+    Ok(())
+}
@@ -3,9 +3,9 @@
 use std::sync::Arc;
 use tauri::{Manager, WindowBuilder, WindowUrl};
 
-use agent_kanban::{api, commands, db, logging};
+use agent_kanban::{agents, api, commands, db, logging};
 use agent_kanban::commands::runs::RunningAgents;
-use agent_kanban::commands::claude::ClaudeApiSettingsState;
+use agent_kanban::agents::claude_settings::ClaudeApiSettingsState;
 
 /// Check if a URL is allowed for navigation within the app
 fn is_allowed_url(url: &url::Url) -> bool {
@@ -116,6 +116,21 @@ fn main() {
             let db_path = app_data_dir.join("agent-kanban.db");
             let database = Arc::new(db::Database::open(db_path).expect("Failed to open database"));
 
+            // Agents the previous process left running would keep editing
+            // worktrees with nobody supervising them
+            let orphans = agents::process_tree::reap_orphans(&database);
+            if orphans > 0 {
+                tracing::warn!("Startup cleanup: stopped {} orphaned agent process group(s)", orphans);
+            }
+            agents::process_tree::record_in(database.clone());
+
+            // Workflows the previous process left mid-way keep their worktrees
+            // so they can resume; this must happen before their tasks are reset
+            let resumable = agents::recovery::interrupt_workflows(&database);
+            if resumable > 0 {
+                tracing::info!("Startup cleanup: {} interrupted workflow(s) can be resumed", resumable);
+            }
+
             // Cleanup orphaned tasks from interrupted runs
             // This handles cases where the app crashed or was killed while a run was in progress
             match database.cleanup_orphaned_in_progress_tasks() {
@@ -133,30 +148,13 @@ fn main() {
             
             // Load Claude API settings from disk (or create fresh if not present)
             let claude_settings_path = app_data_dir.join("claude_api_settings.json");
-            app.manage(ClaudeApiSettingsState::new_with_path(claude_settings_path));
+            let claude_settings = ClaudeApiSettingsState::new_with_path(claude_settings_path);
+            app.manage(claude_settings.clone());
 
             // Configure API server with persistent token
-            // Try to read existing token from file, or generate a new one
             let token_path = app_data_dir.join("api_token");
-            let api_token = if token_path.exists() {
-                match std::fs::read_to_string(&token_path) {
-                    Ok(token) if !token.trim().is_empty() => {
-                        tracing::info!("Using existing API token from {}", token_path.display());
-                        token.trim().to_string()
-                    }
-                    _ => {
-                        tracing::info!("Generating new API token (existing file was empty or unreadable)");
-                        let token = api::generate_token();
-                        std::fs::write(&token_path, &token).expect("Failed to write API token");
-                        token
-                    }
-                }
-            } else {
-                tracing::info!("Generating new API token (no existing token found)");
-                let token = api::generate_token();
-                std::fs::write(&token_path, &token).expect("Failed to write API token");
-                token
-            };
+            let api_token = api::load_or_create_token(&token_path)
+                .expect("Failed to write API token");
             
             let api_config = api::ApiConfig {
                 token: api_token.clone(),
@@ -183,12 +181,19 @@ fn main() {
             app.manage(api_url);
             app.manage(api_token);
 
-            // Start API server with shared event channel
+            // Start API server with shared event channel and settings
             let db_for_api = database.clone();
-            let event_tx_for_api = event_tx;
+            let hook_script_path = app_data_dir
+                .join("scripts")
+                .join("cursor-hook.js")
+                .to_string_lossy()
+                .to_string();
+            let api_state = api::AppState::with_event_tx(database.clone(), api_config.token.clone(), event_tx)
+                .with_claude_settings(claude_settings)
+                .with_hook_script_path(Some(hook_script_path));
             let api_config_clone = api_config.clone();
             tauri::async_runtime::spawn(async move {
-                match api::start_server_with_event_tx(db_for_api, api_config_clone, event_tx_for_api).await {
+                match api::start_server_with_state(db_for_api, api_config_clone, api_state).await {
                     Ok(handle) => {
                         tracing::info!("API server started at {}", handle.addr);
                         // Keep handle alive - server runs until app exits
@@ -200,6 +205,13 @@ fn main() {
                 }
             });
 
+            // Bring back the workers that were running when the app last closed
+            let app_handle = app.handle();
+            let db_for_workers = database.clone();
+            tauri::async_runtime::spawn(async move {
+                commands::workers::start_saved_workers(&app_handle, &db_for_workers);
+            });
+
             // Start spool processor for handling offline events
             let db_for_spool = database.clone();
             let spool_dir = api::get_default_spool_dir();
@@ -238,16 +250,41 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             commands::get_boards,
             commands::get_columns,
+            commands::create_column,
+            commands::update_column,
+            commands::reorder_columns,
+            commands::delete_column,
+            commands::get_custom_fields,
+            commands::create_custom_field,
+            commands::update_custom_field,
+            commands::delete_custom_field,
+            commands::get_labels,
+            commands::create_label,
+            commands::update_label,
+            commands::merge_labels,
+            commands::delete_label,
             commands::create_board,
             commands::update_board,
             commands::delete_board,
+            commands::export_board,
+            commands::export_board_markdown,
+            commands::import_board,
             commands::factory_reset,
-            commands::repair_scratchpads_table,
+            commands::backups::list_backups,
+            commands::backups::create_backup,
+            commands::backups::restore_backup,
+            commands::archive::search_archived_tickets,
+            commands::archive::restore_archived_ticket,
+            commands::archive::run_retention,
+            commands::trash::list_trash,
+            commands::trash::restore_trash,
+            commands::trash::purge_trash,
             commands::get_tickets,
             commands::create_ticket,
             commands::move_ticket,
             commands::update_ticket,
             commands::delete_ticket,
+            commands::get_ticket_history,
             commands::get_comments,
             commands::add_comment,
             commands::update_comment,
@@ -263,6 +300,16 @@ fn main() {
             commands::runs::get_agent_run,
             commands::runs::cancel_agent_run,
             commands::runs::cleanup_stale_runs,
+            commands::runs::get_interrupted_runs,
+            commands::runs::pause_agent_run,
+            commands::runs::resume_agent_run,
+            commands::runs::update_run_plan,
+            commands::runs::get_run_checkpoint,
+            commands::runs::rerun_agent_run,
+            commands::runs::skip_agent_run_stage,
+            commands::runs::insert_agent_run_stage,
+            commands::runs::get_recovery_settings,
+            commands::runs::set_recovery_settings,
             commands::runs::get_run_events,
             commands::get_projects,
             commands::get_project,
@@ -298,9 +345,21 @@ fn main() {
             // Worker management
             commands::workers::start_worker,
             commands::workers::stop_worker,
+            commands::workers::pause_worker,
+            commands::workers::resume_worker,
+            commands::workers::drain_worker,
             commands::workers::stop_all_workers,
             commands::workers::get_workers,
             commands::workers::get_worker_queue_status,
+            commands::workers::get_scheduling_policy,
+            commands::workers::set_scheduling_policy,
+            commands::workers::explain_queue,
+            commands::workers::get_concurrency_status,
+            commands::workers::set_concurrency_limits,
+            commands::workers::get_worker_definitions,
+            commands::workers::update_worker_definition,
+            commands::workers::delete_worker_definition,
+            commands::workers::get_remote_workers,
             // Worker validation and commands
             commands::workers::validate_worker,
             commands::workers::get_commands_path,
@@ -0,0 +1,560 @@
+//! Remote worker mode.
+//!
+//! Runs agents on this machine for tickets leased from an Agent Kanban server
+//! over the REST API. The worker registers the agent kinds it can run and the
+//! projects it has checkouts of, then loops: lease a ticket, run its workflow
+//! in a fresh worktree of the local checkout while heartbeating the lease, and
+//! report the outcome with the diff and the agent log. A lease the server
+//! fenced off (409) cancels the agent; its work is not reported.
+//!
+//! The workflow is the stages of [`MULTI_STAGE_WORKFLOW`] with the same
+//! prompts and time limits as in-process workers, minus what needs the
+//! server's database: the branch stage (the worktree is already on the
+//! ticket's branch), plan clarification, pauses and per-stage sub-runs. The
+//! plan and the log of every stage are reported as artifacts of the lease's run.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tokio::sync::watch;
+
+use crate::agents::orchestrator::default_stage_timeout;
+use crate::agents::spawner::{self, CancelHandle};
+use crate::agents::{
+    extract_agent_text, prompt, worktree, AgentKind, AgentRunConfig, AgentRunResult, LogCallback, LogLine, RunOutcome,
+};
+use crate::cli::client::{ApiClient, ApiError};
+use crate::db::{
+    CreateRunArtifact, LabelFilter, LeaseGrant, LeaseResult, Project, RegisterRemoteWorker, RemoteWorker,
+    RunStatus, StageTimeout, MULTI_STAGE_WORKFLOW,
+};
+
+/// Agent output kept for the `agent.log` artifact
+const MAX_LOG_BYTES: usize = 1024 * 1024;
+
+/// Contents of the worker config file (TOML)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteWorkerConfig {
+    /// Base URL of the server API, e.g. `http://kanban.internal:7432`
+    pub url: String,
+    pub token: String,
+    /// Shown in the app's worker list; defaults to the host name
+    pub name: Option<String>,
+    pub agents: Vec<AgentKind>,
+    /// Project id or name on the server → path of the local checkout
+    pub projects: BTreeMap<String, PathBuf>,
+    /// `include` / `exclude` label lists routing tickets to this worker
+    #[serde(default)]
+    pub labels: LabelFilter,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+    /// Overrides the hard time limit of every workflow stage
+    pub agent_timeout_secs: Option<u64>,
+    /// Files (relative to the worktree) uploaded as run artifacts when present
+    #[serde(default)]
+    pub artifacts: Vec<PathBuf>,
+    /// Where the registered worker id is kept between restarts
+    pub state_file: Option<PathBuf>,
+}
+
+fn default_poll_interval_secs() -> u64 {
+    10
+}
+
+fn default_heartbeat_interval_secs() -> u64 {
+    30
+}
+
+impl RemoteWorkerConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid config {}: {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        if config.agents.is_empty() {
+            anyhow::bail!("agents must list at least one agent kind");
+        }
+        if config.projects.is_empty() {
+            anyhow::bail!("projects must map at least one project to a local checkout");
+        }
+        if config.heartbeat_interval_secs == 0 || config.poll_interval_secs == 0 {
+            anyhow::bail!("poll_interval_secs and heartbeat_interval_secs must be positive");
+        }
+        Ok(config)
+    }
+
+    fn state_file(&self) -> PathBuf {
+        self.state_file.clone().unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("agent-kanban-worker")
+                .join("worker_id")
+        })
+    }
+}
+
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+}
+
+/// Run the worker until Ctrl-C. A ticket being worked on when Ctrl-C arrives
+/// is reported as aborted so it goes back to Ready.
+pub async fn run(config: RemoteWorkerConfig) -> anyhow::Result<()> {
+    let client = ApiClient::new(&config.url, &config.token);
+    let worker = register(&client, &config).await?;
+    tracing::info!("Registered as remote worker {} ({})", worker.name, worker.id);
+
+    let (shutdown_tx, mut shutdown) = watch::channel(false);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            tracing::info!("Shutting down");
+            let _ = shutdown_tx.send(true);
+        }
+        shutdown_tx.closed().await;
+    });
+
+    while !*shutdown.borrow() {
+        let leased = client
+            .post_raw(&format!("/v1/remote-workers/{}/lease", worker.id), "{}".to_string())
+            .await;
+        match leased {
+            Ok(Some(body)) => match serde_json::from_str::<LeaseGrant>(&body) {
+                Ok(grant) => work_on(&client, &config, &worker, grant, shutdown.clone()).await,
+                Err(e) => tracing::error!("Unreadable lease from server: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Lease request failed: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(config.poll_interval_secs)) => {}
+            _ = shutdown.changed() => {}
+        }
+    }
+    Ok(())
+}
+
+/// Register (again) under the saved worker id, saving the id the server assigns
+async fn register(client: &ApiClient, config: &RemoteWorkerConfig) -> anyhow::Result<RemoteWorker> {
+    let state_file = config.state_file();
+    let saved_id = std::fs::read_to_string(&state_file)
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+
+    // The server knows projects by id; names in the config are resolved here
+    let projects: Vec<Project> = client.get("/v1/projects").await?;
+    let mut project_ids = Vec::new();
+    for key in config.projects.keys() {
+        let project = projects
+            .iter()
+            .find(|p| &p.id == key || &p.name == key)
+            .ok_or_else(|| anyhow::anyhow!("Unknown project on the server: {}", key))?;
+        project_ids.push(project.id.clone());
+    }
+
+    let host = hostname();
+    let worker: RemoteWorker = client
+        .post("/v1/remote-workers", &RegisterRemoteWorker {
+            id: saved_id,
+            name: config.name.clone().or_else(|| host.clone()).unwrap_or_else(|| "remote worker".to_string()),
+            hostname: host,
+            agent_kinds: config.agents.clone(),
+            project_ids,
+            labels: config.labels.clone(),
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        })
+        .await?;
+
+    if let Some(dir) = state_file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&state_file, &worker.id)?;
+    Ok(worker)
+}
+
+/// Local checkout for the leased ticket's project
+fn checkout_for(config: &RemoteWorkerConfig, grant: &LeaseGrant) -> Option<PathBuf> {
+    let project = grant.project.as_ref()?;
+    config
+        .projects
+        .get(&project.id)
+        .or_else(|| config.projects.get(&project.name))
+        .cloned()
+}
+
+async fn work_on(
+    client: &ApiClient,
+    config: &RemoteWorkerConfig,
+    worker: &RemoteWorker,
+    grant: LeaseGrant,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let token = grant.lease.fencing_token;
+    let lease_path = format!("/v1/remote-workers/{}/leases/{}", worker.id, token);
+    tracing::info!("Leased ticket {} ({}) as lease {}", grant.ticket.id, grant.ticket.title, token);
+
+    let cancel: Arc<Mutex<Option<CancelHandle>>> = Arc::new(Mutex::new(None));
+    let lost = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    // Heartbeats; losing the lease cancels the agent
+    let heartbeat = {
+        let client = client.clone();
+        let path = format!("{}/heartbeat", lease_path);
+        let cancel = cancel.clone();
+        let lost = lost.clone();
+        let interval = Duration::from_secs(config.heartbeat_interval_secs);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                match client.post::<_, serde_json::Value>(&path, &serde_json::json!({})).await {
+                    Ok(_) => {}
+                    Err(e) if matches!(
+                        ApiError::status_of(&e),
+                        Some(StatusCode::CONFLICT | StatusCode::NOT_FOUND)
+                    ) => {
+                        tracing::warn!("Lost lease: {}", e);
+                        lost.store(true, std::sync::atomic::Ordering::SeqCst);
+                        if let Some(handle) = cancel.lock().expect("cancel mutex poisoned").as_ref() {
+                            handle.cancel();
+                        }
+                        return;
+                    }
+                    Err(e) => tracing::warn!("Lease heartbeat failed: {}", e),
+                }
+            }
+        })
+    };
+
+    // Ctrl-C cancels the agent; the run is reported as aborted
+    let on_shutdown = {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            if shutdown.wait_for(|stop| *stop).await.is_ok() {
+                if let Some(handle) = cancel.lock().expect("cancel mutex poisoned").as_ref() {
+                    handle.cancel();
+                }
+            }
+        })
+    };
+
+    let result = match checkout_for(config, &grant) {
+        Some(repo) => {
+            let config = config.clone();
+            let cancel = cancel.clone();
+            tokio::task::spawn_blocking(move || run_agent(&config, &grant, &repo, cancel))
+                .await
+                .unwrap_or_else(|e| failed(format!("Agent task panicked: {}", e)))
+        }
+        None => failed("No local checkout configured for this ticket's project".to_string()),
+    };
+
+    heartbeat.abort();
+    on_shutdown.abort();
+    if lost.load(std::sync::atomic::Ordering::SeqCst) {
+        tracing::warn!("Discarding the result of lease {}: the ticket was leased again", token);
+        return;
+    }
+
+    let submitted = client
+        .post::<_, serde_json::Value>(&format!("{}/result", lease_path), &result)
+        .await;
+    match submitted {
+        Ok(_) => tracing::info!("Lease {} reported as {}", token, result.status.as_str()),
+        Err(e) if ApiError::status_of(&e) == Some(StatusCode::CONFLICT) => tracing::warn!("Lease {} was lost before its result was accepted: {}", token, e),
+        Err(e) => tracing::error!("Failed to report lease {}: {}", token, e),
+    }
+}
+
+fn failed(summary: String) -> LeaseResult {
+    tracing::error!("{}", summary);
+    LeaseResult {
+        status: RunStatus::Error,
+        exit_code: None,
+        summary: Some(summary),
+        diff: None,
+        artifacts: Vec::new(),
+    }
+}
+
+/// Run the ticket's workflow in a worktree of `repo` and collect what it produced
+fn run_agent(
+    config: &RemoteWorkerConfig,
+    grant: &LeaseGrant,
+    repo: &Path,
+    cancel: Arc<Mutex<Option<CancelHandle>>>,
+) -> LeaseResult {
+    let ticket = &grant.ticket;
+    let run_id = grant.run.id.clone();
+    let created = match &ticket.branch_name {
+        Some(branch) => worktree::create_worktree_with_existing_branch(repo, branch, &run_id, None),
+        None => worktree::create_worktree(&worktree::WorktreeConfig {
+            repo_path: repo.to_path_buf(),
+            branch_name: format!("agent-work/{}/{}", &ticket.id[..8.min(ticket.id.len())], &run_id[..8.min(run_id.len())]),
+            run_id: run_id.clone(),
+            base_dir: None,
+            base_branch: None,
+        }),
+    };
+    let worktree = match created {
+        Ok(info) => info,
+        Err(e) => return failed(format!("Failed to create worktree in {}: {}", repo.display(), e)),
+    };
+    let base = git(&worktree.path, &["rev-parse", "HEAD"]).map(|out| out.trim().to_string());
+
+    let log = Arc::new(Mutex::new(String::new()));
+    let on_log: Arc<LogCallback> = {
+        let log = log.clone();
+        Arc::new(Box::new(move |line: LogLine| {
+            let mut log = log.lock().expect("log mutex poisoned");
+            log.push_str(&line.content);
+            log.push('\n');
+        }))
+    };
+
+    let kind = match grant.run.agent_type {
+        crate::db::AgentType::Cursor => AgentKind::Cursor,
+        crate::db::AgentType::Claude => AgentKind::Claude,
+    };
+    let mut plan = String::new();
+    let mut outcome = Ok(None);
+    for stage in MULTI_STAGE_WORKFLOW.iter().filter(|stage| **stage != "branch") {
+        let stage_prompt = match *stage {
+            "plan" => prompt::generate_plan_prompt(ticket),
+            "implement" => prompt::generate_implement_prompt(ticket, &plan),
+            command => prompt::generate_command_prompt(command, &worktree.path),
+        };
+        log.lock().expect("log mutex poisoned").push_str(&format!("=== Stage: {} ===\n", stage));
+
+        let timeout = stage_timeout(grant.project.as_ref(), config.agent_timeout_secs, stage);
+        let on_spawn: spawner::OnSpawnCallback = {
+            let cancel = cancel.clone();
+            Box::new(move |handle: CancelHandle| {
+                let mut current = cancel.lock().expect("cancel mutex poisoned");
+                // A lease lost or a shutdown between stages also stops this one
+                if current.as_ref().is_some_and(|previous| previous.is_cancelled()) {
+                    handle.cancel();
+                }
+                *current = Some(handle);
+            })
+        };
+        let result = spawner::run_agent_with_capture(
+            AgentRunConfig {
+                kind,
+                ticket_id: ticket.id.clone(),
+                run_id: run_id.clone(),
+                repo_path: worktree.path.clone(),
+                prompt: stage_prompt,
+                timeout_secs: Some(timeout.hard_secs),
+                wrap_up_secs: Some(timeout.soft_secs).filter(|soft| *soft < timeout.hard_secs),
+                api_url: config.url.clone(),
+                api_token: config.token.clone(),
+                model: ticket.model.clone(),
+                claude_api_config: None,
+            },
+            Some(on_log.clone()),
+            Some(on_spawn),
+        );
+        match result {
+            Ok(result) if result.status == RunOutcome::Success => {
+                if *stage == "plan" {
+                    plan = extract_agent_text(result.captured_stdout.as_deref().unwrap_or_default());
+                }
+                outcome = Ok(Some(StageResult { stage, result }));
+            }
+            Ok(result) => {
+                outcome = Ok(Some(StageResult { stage, result }));
+                break;
+            }
+            Err(e) => {
+                outcome = Err(format!("Stage '{}' failed to run: {}", stage, e));
+                break;
+            }
+        }
+    }
+
+    let mut artifacts = Vec::new();
+    if !plan.trim().is_empty() {
+        artifacts.push(CreateRunArtifact {
+            name: "plan.md".to_string(),
+            content_type: "text/markdown".to_string(),
+            content: plan,
+        });
+    }
+    let log = std::mem::take(&mut *log.lock().expect("log mutex poisoned"));
+    if !log.is_empty() {
+        let start = log.len().saturating_sub(MAX_LOG_BYTES);
+        let start = (start..log.len()).find(|i| log.is_char_boundary(*i)).unwrap_or(log.len());
+        artifacts.push(CreateRunArtifact {
+            name: "agent.log".to_string(),
+            content_type: "text/plain".to_string(),
+            content: log[start..].to_string(),
+        });
+    }
+    for name in &config.artifacts {
+        if let Ok(content) = std::fs::read_to_string(worktree.path.join(name)) {
+            artifacts.push(CreateRunArtifact {
+                name: name.to_string_lossy().to_string(),
+                content_type: "text/plain".to_string(),
+                content,
+            });
+        }
+    }
+
+    // Everything the agent changed, committed or not, relative to where it started
+    let diff = base.and_then(|base| {
+        git(&worktree.path, &["add", "-A"])?;
+        git(&worktree.path, &["diff", "--cached", &base])
+    });
+    if let Err(e) = worktree::remove_worktree(&worktree.path, &worktree.repo_path) {
+        tracing::warn!("Failed to remove worktree {}: {}", worktree.path.display(), e);
+    }
+
+    match outcome {
+        Ok(Some(StageResult { stage, result })) => {
+            let status = match result.status {
+                RunOutcome::Success => RunStatus::Finished,
+                RunOutcome::Error | RunOutcome::Timeout => RunStatus::Error,
+                RunOutcome::Cancelled => RunStatus::Aborted,
+            };
+            let summary = match status {
+                RunStatus::Finished => result.summary,
+                _ => Some(format!(
+                    "Stage '{}' {}: {}",
+                    stage,
+                    result.status.as_str(),
+                    result.summary.as_deref().unwrap_or("no summary")
+                )),
+            };
+            LeaseResult {
+                status,
+                exit_code: result.exit_code,
+                summary,
+                diff,
+                artifacts,
+            }
+        }
+        Ok(None) => LeaseResult {
+            artifacts,
+            ..failed("The workflow has no stages to run".to_string())
+        },
+        Err(e) => LeaseResult {
+            artifacts,
+            ..failed(e)
+        },
+    }
+}
+
+/// The last stage that ran and how it went
+struct StageResult {
+    stage: &'static str,
+    result: AgentRunResult,
+}
+
+/// Time limits of `stage`: the project's own or the stage default, with the
+/// hard limit replaced by `agent_timeout_secs` when that is set
+fn stage_timeout(project: Option<&Project>, agent_timeout_secs: Option<u64>, stage: &str) -> StageTimeout {
+    let timeout = project
+        .and_then(|project| project.stage_timeouts.get(stage).copied())
+        .unwrap_or_else(|| default_stage_timeout(stage));
+    match agent_timeout_secs {
+        Some(hard_secs) => StageTimeout { soft_secs: timeout.soft_secs.min(hard_secs), hard_secs },
+        None => timeout,
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output().ok()?;
+    if !output.status.success() {
+        tracing::warn!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_with_defaults() {
+        let config = RemoteWorkerConfig::parse(r#"
+            url = "http://kanban.internal:7432"
+            token = "secret"
+            agents = ["claude"]
+
+            [projects]
+            "web-app" = "/srv/checkouts/web-app"
+
+            [labels]
+            include = ["gpu"]
+        "#).unwrap();
+
+        assert_eq!(config.agents, vec![AgentKind::Claude]);
+        assert_eq!(config.projects["web-app"], PathBuf::from("/srv/checkouts/web-app"));
+        assert_eq!(config.labels.include, vec!["gpu"]);
+        assert_eq!(config.poll_interval_secs, 10);
+        assert_eq!(config.heartbeat_interval_secs, 30);
+    }
+
+    #[test]
+    fn rejects_config_without_projects_or_agents() {
+        let no_projects = RemoteWorkerConfig::parse(r#"
+            url = "http://localhost:7432"
+            token = "secret"
+            agents = ["cursor"]
+            projects = {}
+        "#);
+        assert!(no_projects.unwrap_err().to_string().contains("projects"));
+
+        let no_agents = RemoteWorkerConfig::parse(r#"
+            url = "http://localhost:7432"
+            token = "secret"
+            agents = []
+            projects = { "web-app" = "/srv/web-app" }
+        "#);
+        assert!(no_agents.unwrap_err().to_string().contains("agents"));
+    }
+
+    #[test]
+    fn agent_timeout_caps_every_stage() {
+        assert_eq!(stage_timeout(None, None, "implement"), default_stage_timeout("implement"));
+        assert_eq!(
+            stage_timeout(None, Some(900), "implement"),
+            StageTimeout { soft_secs: 900, hard_secs: 900 }
+        );
+        assert_eq!(
+            stage_timeout(None, Some(900), "plan"),
+            StageTimeout { soft_secs: 600, hard_secs: 900 }
+        );
+    }
+
+    #[test]
+    fn statuses_are_recognised_from_client_errors() {
+        let lost: anyhow::Error = ApiError {
+            status: StatusCode::CONFLICT,
+            message: "Lease 3 was lost".to_string(),
+        }.into();
+        assert_eq!(ApiError::status_of(&lost), Some(StatusCode::CONFLICT));
+        assert_eq!(lost.to_string(), "Lease 3 was lost (409 Conflict)");
+
+        // Only API responses carry a status, whatever the message says
+        assert_eq!(ApiError::status_of(&anyhow::anyhow!("Lease 3 was lost (409 Conflict)")), None);
+    }
+}
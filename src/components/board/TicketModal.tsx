@@ -1149,8 +1149,8 @@ export function TicketModal({
                             <span className={cn(
                               'text-xs px-1.5 py-0.5 rounded',
                               child.lockedByRunId ? 'bg-status-warning/20 text-status-warning' :
-                              columns.find(c => c.id === child.columnId)?.name === 'Done' ? 'bg-status-success/20 text-status-success' :
-                              columns.find(c => c.id === child.columnId)?.name === 'Blocked' ? 'bg-status-error/20 text-status-error' :
+                              columns.find(c => c.id === child.columnId)?.role === 'done' ? 'bg-status-success/20 text-status-success' :
+                              columns.find(c => c.id === child.columnId)?.role === 'blocked' ? 'bg-status-error/20 text-status-error' :
                               'bg-board-surface text-board-text-muted'
                             )}>
                               {columns.find(c => c.id === child.columnId)?.name || 'Unknown'}
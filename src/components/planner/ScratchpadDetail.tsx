@@ -241,7 +241,7 @@ export function ScratchpadDetail({ scratchpad, onClose }: ScratchpadDetailProps)
   const handleDelete = async (deleteTickets = false) => {
     const ticketCount = progress?.totalTickets || 0;
     const message = deleteTickets && ticketCount > 0
-      ? `Are you sure you want to delete this scratchpad AND all ${ticketCount} associated tickets (epics and their children)? This cannot be undone.`
+      ? `Are you sure you want to delete this scratchpad AND all ${ticketCount} associated tickets (epics and their children)? They can be restored from the trash.`
       : 'Are you sure you want to delete this scratchpad? The tickets created from it will remain.';
     
     if (!confirm(message)) return;
@@ -5,9 +5,27 @@ import { RunDetailsPanel } from './RunDetailsPanel';
 vi.mock('../../lib/tauri', () => ({
   getAgentRun: vi.fn(),
   getRunEvents: vi.fn(),
+  getRunCheckpoint: vi.fn(),
+  pauseAgentRun: vi.fn(),
+  resumeAgentRun: vi.fn(),
+  updateRunPlan: vi.fn(),
+  rerunAgentRun: vi.fn(),
+  skipAgentRunStage: vi.fn(),
+  insertAgentRunStage: vi.fn(),
 }));
 
-import { getAgentRun, getRunEvents } from '../../lib/tauri';
+import {
+  getAgentRun,
+  getRunEvents,
+  getRunCheckpoint,
+  pauseAgentRun,
+  resumeAgentRun,
+  updateRunPlan,
+  rerunAgentRun,
+  skipAgentRunStage,
+  insertAgentRunStage,
+} from '../../lib/tauri';
+import type { WorkflowCheckpoint } from '../../types';
 
 const mockRun = {
   id: 'run-123',
@@ -21,10 +39,25 @@ const mockRun = {
   summaryMd: 'Completed successfully',
 };
 
+const mockCheckpoint: WorkflowCheckpoint = {
+  parentRunId: 'run-123',
+  ticketId: 'ticket-1',
+  agentType: 'cursor',
+  worktreePath: '/tmp/worktree',
+  nextStage: 5,
+  nextStageName: 'unit-tests',
+  stages: ['branch', 'plan', 'implement', 'deslop', 'cleanup', 'unit-tests', 'add-and-commit'],
+  state: 'interrupted',
+  pauseRequested: false,
+  createdAt: '2024-01-01T00:00:00Z',
+  updatedAt: '2024-01-01T00:00:00Z',
+};
+
 describe('RunDetailsPanel', () => {
   beforeEach(() => {
     vi.clearAllMocks();
     vi.mocked(getRunEvents).mockResolvedValue([]);
+    vi.mocked(getRunCheckpoint).mockResolvedValue(null);
   });
 
   it('shows loading state initially', () => {
@@ -126,4 +159,123 @@ describe('RunDetailsPanel', () => {
       expect(screen.getByText('/home/user/project')).toBeInTheDocument();
     });
   });
+
+  it('offers to resume an interrupted workflow', async () => {
+    vi.mocked(getAgentRun).mockResolvedValue({ ...mockRun, status: 'aborted' as const });
+    vi.mocked(getRunCheckpoint).mockResolvedValue(mockCheckpoint);
+    vi.mocked(resumeAgentRun).mockResolvedValue({} as never);
+
+    render(<RunDetailsPanel runId="run-123" onClose={() => {}} />);
+
+    await waitFor(() => {
+      expect(screen.getByText(/Interrupted before stage "unit-tests"/)).toBeInTheDocument();
+    });
+    fireEvent.click(screen.getByText('Resume'));
+    await waitFor(() => {
+      expect(resumeAgentRun).toHaveBeenCalledWith('run-123');
+    });
+  });
+
+  it('restarts a failed workflow from an earlier stage', async () => {
+    vi.mocked(getAgentRun).mockResolvedValue({ ...mockRun, status: 'error' as const });
+    vi.mocked(getRunCheckpoint).mockResolvedValue({ ...mockCheckpoint, state: 'failed' });
+    vi.mocked(rerunAgentRun).mockResolvedValue(mockCheckpoint);
+
+    render(<RunDetailsPanel runId="run-123" onClose={() => {}} />);
+
+    await waitFor(() => {
+      expect(screen.getByText(/Stopped before stage "unit-tests"/)).toBeInTheDocument();
+    });
+    expect(screen.queryByText('Resume')).not.toBeInTheDocument();
+    // Stages after the one it stopped at cannot be picked
+    expect(screen.queryByText('7. add-and-commit')).not.toBeInTheDocument();
+    fireEvent.change(screen.getByLabelText('Rerun from'), { target: { value: '2' } });
+    fireEvent.click(screen.getByText('Rerun'));
+    await waitFor(() => {
+      expect(rerunAgentRun).toHaveBeenCalledWith('run-123', 2);
+    });
+  });
+
+  it('skips the stage a workflow stopped at or adds a custom one', async () => {
+    vi.mocked(getAgentRun).mockResolvedValue({ ...mockRun, status: 'error' as const });
+    vi.mocked(getRunCheckpoint).mockResolvedValue({ ...mockCheckpoint, state: 'failed' });
+    vi.mocked(skipAgentRunStage).mockResolvedValue(mockCheckpoint);
+    vi.mocked(insertAgentRunStage).mockResolvedValue(mockCheckpoint);
+
+    const { unmount } = render(<RunDetailsPanel runId="run-123" onClose={() => {}} />);
+    await waitFor(() => {
+      expect(screen.getByText('Skip "unit-tests"')).toBeInTheDocument();
+    });
+    fireEvent.click(screen.getByText('Skip "unit-tests"'));
+    await waitFor(() => {
+      expect(skipAgentRunStage).toHaveBeenCalledWith('run-123');
+    });
+    unmount();
+
+    render(<RunDetailsPanel runId="run-123" onClose={() => {}} />);
+    await waitFor(() => {
+      expect(screen.getByText('Add stage')).toBeInTheDocument();
+    });
+    expect(screen.getByText('Add stage')).toBeDisabled();
+    fireEvent.change(screen.getByPlaceholderText(/Prompt for an extra stage/), {
+      target: { value: 'Fix the lint errors' },
+    });
+    fireEvent.click(screen.getByText('Add stage'));
+    await waitFor(() => {
+      expect(insertAgentRunStage).toHaveBeenCalledWith('run-123', 'Fix the lint errors');
+    });
+  });
+
+  it('pauses a running workflow after its current stage', async () => {
+    vi.mocked(getAgentRun).mockResolvedValue({ ...mockRun, status: 'running' as const, summaryMd: undefined });
+    vi.mocked(getRunCheckpoint).mockResolvedValue({ ...mockCheckpoint, state: 'active' });
+    vi.mocked(pauseAgentRun).mockResolvedValue({ ...mockCheckpoint, state: 'active', pauseRequested: true });
+
+    render(<RunDetailsPanel runId="run-123" onClose={() => {}} />);
+
+    await waitFor(() => {
+      expect(screen.getByText('Pause after this stage')).toBeInTheDocument();
+    });
+    fireEvent.click(screen.getByText('Pause after this stage'));
+    await waitFor(() => {
+      expect(pauseAgentRun).toHaveBeenCalledWith('run-123');
+    });
+  });
+
+  it('edits the plan of a paused workflow before resuming it', async () => {
+    vi.mocked(getAgentRun).mockResolvedValue({
+      ...mockRun,
+      status: 'running' as const,
+      summaryMd: "Paused after stage 'plan'",
+    });
+    vi.mocked(getRunCheckpoint).mockResolvedValue({
+      ...mockCheckpoint,
+      nextStage: 2,
+      nextStageName: 'implement',
+      state: 'paused',
+      pausedAfter: 'plan',
+      planMd: '1. Do it',
+    });
+    vi.mocked(updateRunPlan).mockResolvedValue(mockCheckpoint);
+    vi.mocked(resumeAgentRun).mockResolvedValue(mockCheckpoint);
+
+    render(<RunDetailsPanel runId="run-123" onClose={() => {}} />);
+
+    await waitFor(() => {
+      expect(screen.getByText(/Paused after stage "plan"/)).toBeInTheDocument();
+    });
+    expect(screen.getByText('Save plan')).toBeDisabled();
+    fireEvent.change(screen.getByLabelText('Plan'), { target: { value: '1. Do it differently' } });
+    fireEvent.click(screen.getByText('Save plan'));
+    await waitFor(() => {
+      expect(updateRunPlan).toHaveBeenCalledWith('run-123', '1. Do it differently');
+    });
+    await waitFor(() => {
+      expect(screen.getByText('Resume')).not.toBeDisabled();
+    });
+    fireEvent.click(screen.getByText('Resume'));
+    await waitFor(() => {
+      expect(resumeAgentRun).toHaveBeenCalledWith('run-123');
+    });
+  });
 });
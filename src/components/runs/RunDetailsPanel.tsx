@@ -1,7 +1,16 @@
 import { useEffect, useState, useCallback, useRef } from 'react';
-import { getAgentRun } from '../../lib/tauri';
+import {
+  getAgentRun,
+  getRunCheckpoint,
+  insertAgentRunStage,
+  pauseAgentRun,
+  rerunAgentRun,
+  resumeAgentRun,
+  skipAgentRunStage,
+  updateRunPlan,
+} from '../../lib/tauri';
 import { EventTimeline } from '../timeline/EventTimeline';
-import type { AgentRun, RunStatus } from '../../types';
+import type { AgentRun, RunStatus, WorkflowCheckpoint } from '../../types';
 
 interface AgentLogEvent {
   runId: string;
@@ -17,6 +26,17 @@ interface LogEntry {
   timestamp: Date;
 }
 
+/** Where a stopped workflow stopped */
+function describeStop(checkpoint: WorkflowCheckpoint): string {
+  if (checkpoint.pausedAfter) {
+    return `Paused after stage "${checkpoint.pausedAfter}". The worktree was kept.`;
+  }
+  const stopped = checkpoint.state === 'interrupted' ? 'Interrupted' : 'Stopped';
+  return checkpoint.nextStageName
+    ? `${stopped} before stage "${checkpoint.nextStageName}". The worktree was kept.`
+    : `${stopped} after its last stage. The worktree was kept.`;
+}
+
 interface RunDetailsPanelProps {
   runId: string;
   onClose: () => void;
@@ -53,6 +73,11 @@ export function RunDetailsPanel({ runId, onClose }: RunDetailsPanelProps) {
   const [error, setError] = useState<string | null>(null);
   const [activeTab, setActiveTab] = useState<'timeline' | 'logs'>('timeline');
   const [logs, setLogs] = useState<LogEntry[]>([]);
+  const [checkpoint, setCheckpoint] = useState<WorkflowCheckpoint | null>(null);
+  const [isRestarting, setIsRestarting] = useState(false);
+  const [rerunStage, setRerunStage] = useState(0);
+  const [customPrompt, setCustomPrompt] = useState('');
+  const [planDraft, setPlanDraft] = useState('');
   
   const statusRef = useRef<RunStatus | undefined>(undefined);
   const logsEndRef = useRef<HTMLDivElement>(null);
@@ -139,6 +164,43 @@ export function RunDetailsPanel({ runId, onClose }: RunDetailsPanelProps) {
     };
   }, [runId]);
 
+  const loadCheckpoint = useCallback(async () => {
+    try {
+      const found = await getRunCheckpoint(runId);
+      setCheckpoint(found);
+      setPlanDraft(found?.planMd ?? '');
+      setRerunStage(found ? Math.min(found.nextStage, found.stages.length - 1) : 0);
+    } catch {
+      setCheckpoint(null);
+    }
+  }, [runId]);
+
+  // Running workflows can pause between stages. Workflows interrupted or
+  // paused before a restart, or stopped by a failed stage, keep their
+  // worktree and can restart from one of the stages they reached. The
+  // summary changes when a pause takes hold.
+  const runStatus = run?.status;
+  const runSummary = run?.summaryMd;
+  useEffect(() => {
+    if (runStatus !== undefined) {
+      loadCheckpoint();
+    }
+  }, [loadCheckpoint, runStatus, runSummary]);
+
+  const restart = async (action: () => Promise<WorkflowCheckpoint>) => {
+    setIsRestarting(true);
+    try {
+      await action();
+      setCustomPrompt('');
+      await loadRun();
+      await loadCheckpoint();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsRestarting(false);
+    }
+  };
+
   useEffect(() => {
     loadRun();
     
@@ -152,6 +214,15 @@ export function RunDetailsPanel({ runId, onClose }: RunDetailsPanelProps) {
     return () => clearInterval(interval);
   }, [loadRun]);
 
+  const isRunning = run?.status === 'running';
+  const isStopped = run?.status === 'aborted' || run?.status === 'error';
+  const liveCheckpoint =
+    isRunning && (checkpoint?.state === 'active' || checkpoint?.state === 'paused') ? checkpoint : null;
+  const stoppedCheckpoint =
+    isStopped && (checkpoint?.state === 'interrupted' || checkpoint?.state === 'paused' || checkpoint?.state === 'failed')
+      ? checkpoint
+      : null;
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center h-full bg-board-bg">
@@ -283,6 +354,125 @@ export function RunDetailsPanel({ runId, onClose }: RunDetailsPanelProps) {
         )}
       </div>
 
+      {liveCheckpoint && (
+        <div className="p-4 border-t border-board-border bg-board-surface/50 space-y-3">
+          <div className="flex items-center justify-between gap-4">
+            <p className="text-sm text-board-text-secondary">
+              {liveCheckpoint.state === 'paused'
+                ? `Paused after stage "${liveCheckpoint.pausedAfter ?? ''}". The ticket stays locked until you resume.`
+                : liveCheckpoint.pauseRequested
+                  ? 'Pausing once the current stage completes...'
+                  : `Running stage "${liveCheckpoint.nextStageName ?? ''}".`}
+            </p>
+            {liveCheckpoint.state === 'paused' || liveCheckpoint.pauseRequested ? (
+              <button
+                onClick={() => restart(() => resumeAgentRun(runId))}
+                disabled={isRestarting}
+                className="px-3 py-1.5 bg-board-accent hover:bg-board-accent-hover disabled:opacity-50 rounded text-sm text-white"
+              >
+                Resume
+              </button>
+            ) : (
+              <button
+                onClick={() => restart(() => pauseAgentRun(runId))}
+                disabled={isRestarting}
+                className="px-3 py-1.5 bg-board-surface-raised hover:bg-board-card-hover disabled:opacity-50 rounded text-sm text-board-text"
+              >
+                Pause after this stage
+              </button>
+            )}
+          </div>
+          {liveCheckpoint.state === 'paused' && (
+            <div className="flex items-start gap-2">
+              <textarea
+                aria-label="Plan"
+                value={planDraft}
+                onChange={(e) => setPlanDraft(e.target.value)}
+                rows={6}
+                className="flex-1 px-2 py-1 bg-board-surface-raised rounded text-sm text-board-text font-mono border border-board-border"
+              />
+              <button
+                onClick={() => restart(() => updateRunPlan(runId, planDraft))}
+                disabled={isRestarting || planDraft === (liveCheckpoint.planMd ?? '')}
+                className="px-3 py-1.5 bg-board-surface-raised hover:bg-board-card-hover disabled:opacity-50 rounded text-sm text-board-text"
+              >
+                Save plan
+              </button>
+            </div>
+          )}
+        </div>
+      )}
+
+      {stoppedCheckpoint && (
+        <div className="p-4 border-t border-board-border bg-board-surface/50 space-y-3">
+          <div className="flex items-center justify-between gap-4">
+            <p className="text-sm text-board-text-secondary">
+              {describeStop(stoppedCheckpoint)}
+            </p>
+            <div className="flex items-center gap-2">
+              {(stoppedCheckpoint.state === 'interrupted' || stoppedCheckpoint.state === 'paused') && (
+                <button
+                  onClick={() => restart(() => resumeAgentRun(runId))}
+                  disabled={isRestarting}
+                  className="px-3 py-1.5 bg-board-accent hover:bg-board-accent-hover disabled:opacity-50 rounded text-sm text-white"
+                >
+                  Resume
+                </button>
+              )}
+              {stoppedCheckpoint.nextStageName && (
+                <button
+                  onClick={() => restart(() => skipAgentRunStage(runId))}
+                  disabled={isRestarting}
+                  className="px-3 py-1.5 bg-board-surface-raised hover:bg-board-card-hover disabled:opacity-50 rounded text-sm text-board-text"
+                >
+                  Skip "{stoppedCheckpoint.nextStageName}"
+                </button>
+              )}
+            </div>
+          </div>
+          <div className="flex items-center gap-2">
+            <label htmlFor="rerun-stage" className="text-sm text-board-text-muted">
+              Rerun from
+            </label>
+            <select
+              id="rerun-stage"
+              value={rerunStage}
+              onChange={(e) => setRerunStage(Number(e.target.value))}
+              className="px-2 py-1 bg-board-surface-raised rounded text-sm text-board-text border border-board-border"
+            >
+              {stoppedCheckpoint.stages.slice(0, stoppedCheckpoint.nextStage + 1).map((stage, index) => (
+                <option key={index} value={index}>
+                  {index + 1}. {stage}
+                </option>
+              ))}
+            </select>
+            <button
+              onClick={() => restart(() => rerunAgentRun(runId, rerunStage))}
+              disabled={isRestarting}
+              className="px-3 py-1.5 bg-board-surface-raised hover:bg-board-card-hover disabled:opacity-50 rounded text-sm text-board-text"
+            >
+              Rerun
+            </button>
+          </div>
+          <div className="flex items-start gap-2">
+            <textarea
+              value={customPrompt}
+              onChange={(e) => setCustomPrompt(e.target.value)}
+              rows={2}
+              placeholder="Prompt for an extra stage, e.g. fix the lint errors"
+              className="flex-1 px-2 py-1 bg-board-surface-raised rounded text-sm text-board-text resize-none border border-board-border"
+            />
+            <button
+              onClick={() => restart(() => insertAgentRunStage(runId, customPrompt.trim()))}
+              disabled={isRestarting || !customPrompt.trim()}
+              className="px-3 py-1.5 bg-board-surface-raised hover:bg-board-card-hover disabled:opacity-50 rounded text-sm text-board-text"
+            >
+              Add stage
+            </button>
+          </div>
+        </div>
+      )}
+
       {/* Summary Footer */}
       {run.summaryMd && (
         <div className="p-4 border-t border-board-border bg-board-surface/50">
@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
-import type { WorkerStatus, WorkerQueueStatus, AgentType, Project, ValidationResult } from '../../types';
+import { listen } from '@tauri-apps/api/event';
+import type { WorkerStatus, WorkerQueueStatus, AgentType, Project, RemoteWorker, ValidationResult } from '../../types';
 import { logger } from '../../lib/logger';
 import {
   validateWorker,
@@ -9,6 +10,10 @@ import {
   getClaudeStatus,
   installCursorHooksProject,
   installClaudeHooksProject,
+  pauseWorker,
+  resumeWorker,
+  drainWorker,
+  getRemoteWorkers,
 } from '../../lib/tauri';
 
 interface Props {
@@ -17,6 +22,7 @@ interface Props {
 
 export function WorkerPanel({ projects }: Props) {
   const [workers, setWorkers] = useState<WorkerStatus[]>([]);
+  const [remoteWorkers, setRemoteWorkers] = useState<RemoteWorker[]>([]);
   const [queueStatus, setQueueStatus] = useState<WorkerQueueStatus>({
     readyCount: 0,
     inProgressCount: 0,
@@ -35,11 +41,13 @@ export function WorkerPanel({ projects }: Props) {
 
   const loadStatus = useCallback(async () => {
     try {
-      const [workerData, queueData] = await Promise.all([
+      const [workerData, queueData, remoteData] = await Promise.all([
         invoke<WorkerStatus[]>('get_workers'),
         invoke<WorkerQueueStatus>('get_worker_queue_status'),
+        getRemoteWorkers(),
       ]);
       setWorkers(workerData);
+      setRemoteWorkers(remoteData);
       setQueueStatus(queueData);
       setError(null);
     } catch (err) {
@@ -51,7 +59,12 @@ export function WorkerPanel({ projects }: Props) {
   useEffect(() => {
     loadStatus();
     const interval = setInterval(loadStatus, 5000);
-    return () => clearInterval(interval);
+    // Pauses, drains and schedule changes show up without waiting for the next poll
+    const unlisten = listen('worker-state-changed', () => loadStatus());
+    return () => {
+      clearInterval(interval);
+      unlisten.then((fn) => fn());
+    };
   }, [loadStatus]);
 
   // Validate when project or agent type changes
@@ -162,6 +175,16 @@ export function WorkerPanel({ projects }: Props) {
     }
   };
 
+  const handleWorkerAction = async (action: (workerId: string) => Promise<WorkerStatus>, workerId: string) => {
+    try {
+      await action(workerId);
+      await loadStatus();
+    } catch (err) {
+      logger.error('Failed to change worker state:', err);
+      setError(String(err));
+    }
+  };
+
   const handleStopAll = async () => {
     try {
       await invoke('stop_all_workers');
@@ -178,6 +201,11 @@ export function WorkerPanel({ projects }: Props) {
         return 'bg-status-success';
       case 'idle':
         return 'bg-status-warning';
+      case 'paused':
+      case 'draining':
+      case 'off_schedule':
+      case 'waiting_for_slot':
+        return 'bg-status-info';
       case 'stopped':
         return 'bg-board-text-muted';
     }
@@ -363,9 +391,12 @@ export function WorkerPanel({ projects }: Props) {
                         {worker.agentType === 'cursor' ? 'Cursor' : 'Claude'} Worker
                       </span>
                       <span className="text-xs text-board-text-muted px-2 py-0.5 bg-board-surface rounded-full">
-                        {worker.status}
+                        {worker.status.replace(/_/g, ' ')}
                       </span>
                     </div>
+                    {worker.stateReason && (
+                      <div className="text-xs text-board-text-muted mt-1">{worker.stateReason}</div>
+                    )}
                     <div className="text-xs text-board-text-muted mt-1.5 truncate">
                       {project ? project.name : 'All projects'} •{' '}
                       {worker.ticketsProcessed} processed
@@ -379,8 +410,32 @@ export function WorkerPanel({ projects }: Props) {
                       Last poll: {formatDate(worker.lastPollAt)}
                     </div>
                   </div>
+                  {worker.status === 'paused' ? (
+                    <button
+                      onClick={() => handleWorkerAction(resumeWorker, worker.id)}
+                      className="px-3 py-1.5 bg-board-surface border border-board-border text-sm text-board-text-secondary rounded-lg hover:bg-board-card-hover transition-colors ml-3"
+                    >
+                      Resume
+                    </button>
+                  ) : worker.status !== 'draining' && (
+                    <button
+                      onClick={() => handleWorkerAction(pauseWorker, worker.id)}
+                      className="px-3 py-1.5 bg-board-surface border border-board-border text-sm text-board-text-secondary rounded-lg hover:bg-board-card-hover transition-colors ml-3"
+                    >
+                      Pause
+                    </button>
+                  )}
+                  {worker.currentTicketId && worker.status !== 'draining' && (
+                    <button
+                      onClick={() => handleWorkerAction(drainWorker, worker.id)}
+                      title="Finish the current ticket, then stop"
+                      className="px-3 py-1.5 bg-board-surface border border-board-border text-sm text-board-text-secondary rounded-lg hover:bg-board-card-hover transition-colors ml-3"
+                    >
+                      Drain
+                    </button>
+                  )}
                   <button
-                    onClick={() => handleStopWorker(worker.id, worker.status === 'running' && !!worker.currentTicketId)}
+                    onClick={() => handleStopWorker(worker.id, !!worker.currentTicketId)}
                     className="px-3 py-1.5 bg-board-surface border border-board-border text-sm text-board-text-secondary rounded-lg hover:bg-board-card-hover transition-colors ml-3"
                   >
                     Stop
@@ -392,6 +447,44 @@ export function WorkerPanel({ projects }: Props) {
         )}
       </div>
 
+      {/* Remote Workers */}
+      {remoteWorkers.length > 0 && (
+        <div className="bg-board-surface rounded-xl p-5 border border-board-border">
+          <h3 className="text-sm font-medium text-board-text-muted mb-4">Remote Workers</h3>
+          <div className="space-y-3">
+            {remoteWorkers.map((worker) => (
+              <div
+                key={worker.id}
+                className="bg-board-surface-raised rounded-xl p-4 border border-board-border"
+              >
+                <div className="flex items-center gap-2">
+                  <span
+                    className={`w-2 h-2 rounded-full ${worker.online ? 'bg-status-success' : 'bg-board-text-muted'}`}
+                  />
+                  <span className="font-medium text-board-text">{worker.name}</span>
+                  {worker.hostname && (
+                    <span className="text-xs text-board-text-muted">{worker.hostname}</span>
+                  )}
+                  <span className="text-xs text-board-text-muted px-2 py-0.5 bg-board-surface rounded-full">
+                    {worker.online ? 'online' : 'offline'}
+                  </span>
+                </div>
+                <div className="text-xs text-board-text-muted mt-1.5 truncate">
+                  {worker.agentKinds.join(', ')} •{' '}
+                  {worker.projectIds
+                    .map((id) => projects.find((p) => p.id === id)?.name ?? id.substring(0, 8))
+                    .join(', ')}{' '}
+                  • {worker.activeLeases} active • {worker.ticketsProcessed} processed
+                </div>
+                <div className="text-xs text-board-text-muted/60 mt-1">
+                  Last seen: {formatDate(worker.lastSeenAt)}
+                </div>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       {/* Info Card */}
       <div className="bg-status-info/10 border border-status-info/30 rounded-xl p-4 text-sm">
         <h4 className="font-medium text-status-info mb-2">How Workers Operate</h4>
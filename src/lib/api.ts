@@ -193,6 +193,7 @@ interface Column {
   name: string;
   position: number;
   wipLimit?: number;
+  role?: string | null;
 }
 
 interface Ticket {
@@ -1,9 +1,26 @@
 import { invoke } from '@tauri-apps/api/tauri';
 import type {
   Board,
+  BoardBundle,
+  BoardImport,
+  BackupInfo,
+  ArchivedTicket,
+  RetentionReport,
+  TrashEntry,
   Column,
+  CreateColumnInput,
+  UpdateColumnInput,
+  CustomField,
+  CreateCustomFieldInput,
+  UpdateCustomFieldInput,
+  Label,
+  CreateLabelInput,
+  UpdateLabelInput,
   Ticket,
+  TicketHistoryEntry,
   AgentRun,
+  WorkflowCheckpoint,
+  RecoverySettings,
   Project,
   CreateProjectInput,
   UpdateProjectInput,
@@ -103,10 +120,112 @@ export async function getColumns(boardId: string): Promise<Column[]> {
   return invoke('get_columns', { boardId });
 }
 
+export async function createColumn(boardId: string, input: CreateColumnInput): Promise<Column> {
+  return invoke('create_column', { boardId, input });
+}
+
+export async function updateColumn(columnId: string, input: UpdateColumnInput): Promise<Column> {
+  return invoke('update_column', { columnId, input });
+}
+
+export async function reorderColumns(boardId: string, columnIds: string[]): Promise<Column[]> {
+  return invoke('reorder_columns', { boardId, columnIds });
+}
+
+export async function deleteColumn(columnId: string, moveTicketsTo?: string): Promise<void> {
+  return invoke('delete_column', { columnId, moveTicketsTo });
+}
+
+export async function getCustomFields(boardId: string): Promise<CustomField[]> {
+  return invoke('get_custom_fields', { boardId });
+}
+
+export async function createCustomField(boardId: string, input: CreateCustomFieldInput): Promise<CustomField> {
+  return invoke('create_custom_field', { boardId, input });
+}
+
+export async function updateCustomField(fieldId: string, input: UpdateCustomFieldInput): Promise<CustomField> {
+  return invoke('update_custom_field', { fieldId, input });
+}
+
+export async function deleteCustomField(fieldId: string): Promise<void> {
+  return invoke('delete_custom_field', { fieldId });
+}
+
+export async function getLabels(boardId: string): Promise<Label[]> {
+  return invoke('get_labels', { boardId });
+}
+
+export async function createLabel(boardId: string, input: CreateLabelInput): Promise<Label> {
+  return invoke('create_label', { boardId, input });
+}
+
+export async function updateLabel(labelId: string, input: UpdateLabelInput): Promise<Label> {
+  return invoke('update_label', { labelId, input });
+}
+
+/** Replace `labelId` with `intoLabelId` on every ticket and delete it */
+export async function mergeLabels(labelId: string, intoLabelId: string): Promise<Label> {
+  return invoke('merge_labels', { labelId, intoLabelId });
+}
+
+export async function deleteLabel(labelId: string): Promise<void> {
+  return invoke('delete_label', { labelId });
+}
+
 export async function createBoard(name: string): Promise<Board> {
   return invoke('create_board', { name });
 }
 
+export async function exportBoard(boardId: string, includeRuns = false): Promise<BoardBundle> {
+  return invoke('export_board', { boardId, includeRuns });
+}
+
+export async function exportBoardMarkdown(boardId: string): Promise<string> {
+  return invoke('export_board_markdown', { boardId });
+}
+
+export async function importBoard(bundle: BoardBundle, name?: string): Promise<BoardImport> {
+  return invoke('import_board', { bundle, name });
+}
+
+export async function listBackups(): Promise<BackupInfo[]> {
+  return invoke('list_backups');
+}
+
+export async function createBackup(): Promise<BackupInfo> {
+  return invoke('create_backup');
+}
+
+/** Returns the snapshot taken of the state that was replaced */
+export async function restoreBackup(fileName: string): Promise<BackupInfo> {
+  return invoke('restore_backup', { fileName });
+}
+
+export async function searchArchivedTickets(boardId?: string, query?: string): Promise<ArchivedTicket[]> {
+  return invoke('search_archived_tickets', { boardId, query });
+}
+
+export async function restoreArchivedTicket(ticketId: string): Promise<Ticket> {
+  return invoke('restore_archived_ticket', { ticketId });
+}
+
+export async function runRetention(): Promise<RetentionReport> {
+  return invoke('run_retention');
+}
+
+export async function listTrash(boardId?: string): Promise<TrashEntry[]> {
+  return invoke('list_trash', { boardId });
+}
+
+export async function restoreTrash(trashId: string): Promise<TrashEntry> {
+  return invoke('restore_trash', { trashId });
+}
+
+export async function purgeTrash(trashId: string): Promise<void> {
+  return invoke('purge_trash', { trashId });
+}
+
 export async function getTickets(boardId: string): Promise<Ticket[]> {
   return invoke('get_tickets', { boardId });
 }
@@ -124,8 +243,13 @@ export async function moveTicket(
   return invoke('move_ticket', { ticketId, columnId });
 }
 
-export async function deleteTicket(ticketId: string): Promise<void> {
-  return invoke('delete_ticket', { ticketId });
+/** Moves the ticket to the trash unless `permanent`; returns the trash entry */
+export async function deleteTicket(ticketId: string, permanent = false): Promise<TrashEntry | null> {
+  return invoke('delete_ticket', { ticketId, permanent });
+}
+
+export async function getTicketHistory(ticketId: string): Promise<TicketHistoryEntry[]> {
+  return invoke('get_ticket_history', { ticketId });
 }
 
 export async function startAgentRun(
@@ -153,6 +277,59 @@ export async function cleanupStaleRuns(): Promise<number> {
   return invoke('cleanup_stale_runs');
 }
 
+/** Workflows interrupted by a restart that can resume where they stopped */
+export async function getInterruptedRuns(): Promise<WorkflowCheckpoint[]> {
+  return invoke('get_interrupted_runs');
+}
+
+/** Pause a running workflow once its current stage completes */
+export async function pauseAgentRun(runId: string): Promise<WorkflowCheckpoint> {
+  return invoke('pause_agent_run', { runId });
+}
+
+/** Resume a paused or interrupted workflow from its first incomplete stage */
+export async function resumeAgentRun(runId: string): Promise<WorkflowCheckpoint> {
+  return invoke('resume_agent_run', { runId });
+}
+
+/** Replace the plan a paused or stopped workflow continues with */
+export async function updateRunPlan(runId: string, planMd: string): Promise<WorkflowCheckpoint> {
+  return invoke('update_run_plan', { runId, planMd });
+}
+
+/** Checkpoint of a multi-stage run, or null for runs without one */
+export async function getRunCheckpoint(runId: string): Promise<WorkflowCheckpoint | null> {
+  return invoke('get_run_checkpoint', { runId });
+}
+
+/** Restart a stopped workflow from one of the stages it already reached */
+export async function rerunAgentRun(runId: string, stage: number): Promise<WorkflowCheckpoint> {
+  return invoke('rerun_agent_run', { runId, stage });
+}
+
+/** Restart a stopped workflow after the stage it stopped at */
+export async function skipAgentRunStage(runId: string): Promise<WorkflowCheckpoint> {
+  return invoke('skip_agent_run_stage', { runId });
+}
+
+/** Restart a stopped workflow with a custom stage run before `before` (default: where it stopped) */
+export async function insertAgentRunStage(
+  runId: string,
+  prompt: string,
+  name?: string,
+  before?: number,
+): Promise<WorkflowCheckpoint> {
+  return invoke('insert_agent_run_stage', { runId, prompt, name, before });
+}
+
+export async function getRecoverySettings(): Promise<RecoverySettings> {
+  return invoke('get_recovery_settings');
+}
+
+export async function setRecoverySettings(settings: RecoverySettings): Promise<RecoverySettings> {
+  return invoke('set_recovery_settings', { settings });
+}
+
 export async function getAgentRun(runId: string): Promise<AgentRun> {
   return invoke('get_agent_run', { runId });
 }
@@ -292,6 +469,75 @@ export async function setClaudeApiSettings(settings: ClaudeApiSettings): Promise
   return invoke('set_claude_api_settings', { settings });
 }
 
+// Saved workers
+import type {
+  WorkerDefinition,
+  UpdateWorkerDefinitionInput,
+  WorkerStatus,
+  RemoteWorker,
+  SchedulingPolicy,
+  QueueExplanation,
+  AgentType,
+  ConcurrencyLimits,
+  ConcurrencyStatus,
+} from '../types';
+
+/** Stop taking new tickets; the current one is finished */
+export async function pauseWorker(workerId: string): Promise<WorkerStatus> {
+  return invoke('pause_worker', { workerId });
+}
+
+export async function resumeWorker(workerId: string): Promise<WorkerStatus> {
+  return invoke('resume_worker', { workerId });
+}
+
+/** Finish the current ticket, then exit; a saved worker is disabled */
+export async function drainWorker(workerId: string): Promise<WorkerStatus> {
+  return invoke('drain_worker', { workerId });
+}
+
+export async function getWorkerDefinitions(): Promise<WorkerDefinition[]> {
+  return invoke('get_worker_definitions');
+}
+
+export async function getRemoteWorkers(): Promise<RemoteWorker[]> {
+  return invoke('get_remote_workers');
+}
+
+export async function getSchedulingPolicy(): Promise<SchedulingPolicy> {
+  return invoke('get_scheduling_policy');
+}
+
+export async function setSchedulingPolicy(policy: SchedulingPolicy): Promise<SchedulingPolicy> {
+  return invoke('set_scheduling_policy', { policy });
+}
+
+export async function getConcurrencyStatus(): Promise<ConcurrencyStatus> {
+  return invoke('get_concurrency_status');
+}
+
+/** Applies to waiting runs right away; running agents keep their slot */
+export async function setConcurrencyLimits(limits: ConcurrencyLimits): Promise<ConcurrencyStatus> {
+  return invoke('set_concurrency_limits', { limits });
+}
+
+/** The Ready queue in the order workers would take it, with reasons */
+export async function explainQueue(projectId?: string, agentType?: AgentType): Promise<QueueExplanation> {
+  return invoke('explain_queue', { projectId, agentType });
+}
+
+/** Restarts the worker if it is running; disabling stops it */
+export async function updateWorkerDefinition(
+  workerId: string,
+  input: UpdateWorkerDefinitionInput
+): Promise<WorkerDefinition> {
+  return invoke('update_worker_definition', { workerId, input });
+}
+
+export async function deleteWorkerDefinition(workerId: string): Promise<void> {
+  return invoke('delete_worker_definition', { workerId });
+}
+
 // Worker validation and commands
 import type { ValidationResult } from '../types';
 
@@ -336,8 +582,3 @@ export async function checkUserCommandsInstalled(agentType: string): Promise<boo
 export async function factoryReset(): Promise<void> {
   return invoke('factory_reset');
 }
-
-// Repair scratchpads table - fixes CHECK constraint issue
-export async function repairScratchpadsTable(): Promise<string> {
-  return invoke('repair_scratchpads_table');
-}
@@ -15,6 +15,9 @@ export interface Project {
   allowFileWrites: boolean;
   blockedPatterns: string[];
   
+  // Time limits by workflow stage, replacing the stage defaults
+  stageTimeouts: Record<string, StageTimeout>;
+  
   // General
   settings: Record<string, unknown>;
   
@@ -22,6 +25,12 @@ export interface Project {
   updatedAt: Date;
 }
 
+/** At softSecs the agent is asked to wrap up; at hardSecs it is killed */
+export interface StageTimeout {
+  softSecs: number;
+  hardSecs: number;
+}
+
 export interface CreateProjectInput {
   name: string;
   path: string;
@@ -34,6 +43,7 @@ export interface UpdateProjectInput {
   allowShellCommands?: boolean;
   allowFileWrites?: boolean;
   blockedPatterns?: string[];
+  stageTimeouts?: Record<string, StageTimeout>;
 }
 
 export interface Board {
@@ -44,12 +54,95 @@ export interface Board {
   updatedAt: Date;
 }
 
+/** Lifecycle state a column plays; `null` for custom columns */
+export type ColumnRole = 'backlog' | 'ready' | 'in_progress' | 'blocked' | 'review' | 'done';
+
 export interface Column {
   id: string;
   boardId: string;
   name: string;
   position: number;
   wipLimit?: number;
+  role?: ColumnRole | null;
+}
+
+export interface CreateColumnInput {
+  name: string;
+  role?: ColumnRole | null;
+  position?: number;
+  wipLimit?: number;
+}
+
+export interface UpdateColumnInput {
+  name?: string;
+  /** null turns the column into a custom column */
+  role?: ColumnRole | null;
+  /** 0 clears the limit */
+  wipLimit?: number;
+}
+
+export type CustomFieldType = 'text' | 'number' | 'date' | 'enum' | 'user';
+
+/** Values of a ticket's custom fields, keyed by field key; dates are YYYY-MM-DD */
+export type CustomFieldValues = Record<string, string | number>;
+
+/** A typed ticket field defined on a board */
+export interface CustomField {
+  id: string;
+  boardId: string;
+  key: string;
+  name: string;
+  fieldType: CustomFieldType;
+  /** Allowed values of an enum field */
+  options: string[];
+  required: boolean;
+  position: number;
+  createdAt: string;
+}
+
+export interface CreateCustomFieldInput {
+  key: string;
+  name: string;
+  fieldType: CustomFieldType;
+  options?: string[];
+  required?: boolean;
+}
+
+export interface UpdateCustomFieldInput {
+  name?: string;
+  options?: string[];
+  required?: boolean;
+}
+
+/** A label in a board's catalog; tickets refer to labels by name */
+export interface Label {
+  id: string;
+  boardId: string;
+  name: string;
+  /** #rrggbb */
+  color: string;
+  description: string;
+  ticketCount: number;
+  createdAt: string;
+}
+
+export interface CreateLabelInput {
+  name: string;
+  color?: string;
+  description?: string;
+}
+
+export interface UpdateLabelInput {
+  /** Renames the label on every ticket of the board */
+  name?: string;
+  color?: string;
+  description?: string;
+}
+
+/** Labels a worker requires (any of) or refuses when reserving tickets */
+export interface LabelFilter {
+  include: string[];
+  exclude: string[];
 }
 
 // Note: 'basic' workflow has been removed - all tickets now use multi_stage
@@ -83,6 +176,7 @@ export interface Ticket {
   dependsOnEpicId?: string;
   /** Link back to scratchpad that created this ticket */
   scratchpadId?: string;
+  customFields?: CustomFieldValues;
 }
 
 export type ReadinessCheck =
@@ -100,6 +194,89 @@ export interface Comment {
   metadata?: Record<string, unknown>;
 }
 
+export type ActorType = 'user' | 'agent' | 'system' | 'worker';
+
+export interface Actor {
+  actorType: ActorType;
+  actorId?: string;
+}
+
+/** Portable board export; ids are remapped when it is imported */
+export interface BoardBundle {
+  formatVersion: number;
+  exportedAt: string;
+  board: Board;
+  columns: Column[];
+  /** Includes archived tickets, which are listed in `archived` */
+  tickets: Ticket[];
+  archived: BundleArchiveEntry[];
+  tasks: Task[];
+  comments: Comment[];
+  scratchpads: Scratchpad[];
+  projects: Project[];
+  runs: AgentRun[];
+  events: AgentEvent[];
+}
+
+/** A ticket the retention policy archived, restored as archived on import */
+export interface BundleArchiveEntry {
+  ticketId: string;
+  archivedAt: string;
+}
+
+export interface BoardImport {
+  board: Board;
+  warnings: string[];
+}
+
+/** A copy of the database in the `backups` directory */
+export interface BackupInfo {
+  fileName: string;
+  /** `scheduled`, `manual`, or `pre-<operation>` for automatic snapshots */
+  reason: string;
+  createdAt: string;
+  sizeBytes: number;
+}
+
+/** A Done ticket moved off its board by the retention policy */
+export interface ArchivedTicket {
+  ticket: Ticket;
+  archivedAt: string;
+}
+
+export interface RetentionReport {
+  compactedRuns: number;
+  compactedEvents: number;
+  archivedTickets: number;
+  purgedTrash: number;
+  orphansRemoved: number;
+  vacuumed: boolean;
+}
+
+export type TrashItemType = 'ticket' | 'board' | 'scratchpad';
+
+/** A soft delete; restoring it brings back everything it hid */
+export interface TrashEntry {
+  id: string;
+  itemType: TrashItemType;
+  itemId: string;
+  name: string;
+  boardId?: string;
+  /** Tickets hidden by this delete, including an epic's children */
+  ticketCount: number;
+  deletedAt: string;
+}
+
+export interface TicketHistoryEntry {
+  id: string;
+  ticketId: string;
+  field: string;
+  oldValue?: string;
+  newValue?: string;
+  actor: Actor;
+  createdAt: string;
+}
+
 export type AgentType = 'cursor' | 'claude';
 export type RunStatus = 'queued' | 'running' | 'finished' | 'error' | 'aborted';
 
@@ -120,6 +297,37 @@ export interface AgentRun {
   stage?: string;
 }
 
+export type CheckpointState = 'active' | 'paused' | 'interrupted' | 'finished' | 'failed';
+
+/** Progress of a multi-stage workflow, saved after every completed stage */
+export interface WorkflowCheckpoint {
+  parentRunId: string;
+  ticketId: string;
+  taskId?: string;
+  agentType: AgentType;
+  worktreePath: string;
+  branchName?: string;
+  /** Index of the first stage not yet completed */
+  nextStage: number;
+  /** Name of that stage; missing once every stage completed */
+  nextStageName?: string;
+  /** Names of every stage of the workflow, in order */
+  stages: string[];
+  planMd?: string;
+  state: CheckpointState;
+  /** The workflow pauses once its current stage completes */
+  pauseRequested: boolean;
+  /** Last stage completed before pausing, while paused */
+  pausedAfter?: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface RecoverySettings {
+  /** Resume interrupted workflows at launch instead of waiting for the user */
+  autoResume: boolean;
+}
+
 export interface AgentEvent {
   id: string;
   runId: string;
@@ -166,16 +374,27 @@ export interface CreateTicketInput {
   isEpic?: boolean;
   /** The parent epic ID (when creating a child ticket) */
   epicId?: string;
+  customFields?: CustomFieldValues;
 }
 
 // Worker types
-export type WorkerState = 'idle' | 'running' | 'stopped';
+export type WorkerState =
+  | 'idle'
+  | 'running'
+  | 'paused'
+  | 'draining'
+  | 'off_schedule'
+  | 'waiting_for_slot'
+  | 'stopped';
 
 export interface WorkerStatus {
   id: string;
   agentType: AgentType;
   projectId?: string;
+  labels: LabelFilter;
   status: WorkerState;
+  /** Why a paused, draining or off-schedule worker takes no tickets, or what a waiting one waits for */
+  stateReason?: string;
   currentTicketId?: string;
   currentRunId?: string;
   ticketsProcessed: number;
@@ -183,6 +402,122 @@ export interface WorkerStatus {
   lastPollAt?: Date;
 }
 
+export type Weekday = 'Mon' | 'Tue' | 'Wed' | 'Thu' | 'Fri' | 'Sat' | 'Sun';
+
+/** Local-time window; an end before the start runs past midnight */
+export interface ScheduleWindow {
+  /** Days the window starts on; every day when empty */
+  days: Weekday[];
+  /** HH:MM */
+  start: string;
+  /** HH:MM */
+  end: string;
+}
+
+export interface WorkerSchedule {
+  windows: ScheduleWindow[];
+}
+
+/** A saved worker, started at launch while enabled */
+export interface WorkerDefinition {
+  id: string;
+  name: string;
+  agentType: AgentType;
+  projectId?: string;
+  labels: LabelFilter;
+  pollIntervalSecs: number;
+  agentTimeoutSecs: number;
+  lockDurationMins: number;
+  schedule?: WorkerSchedule;
+  enabled: boolean;
+  ticketsProcessed: number;
+  ticketsFailed: number;
+  lastTicketAt?: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+/** Caps on concurrent agent processes on this machine; missing entries mean no limit */
+export interface ConcurrencyLimits {
+  maxTotal?: number;
+  /** Keyed by agent type */
+  perAgent: Partial<Record<AgentType, number>>;
+  /** Keyed by model name */
+  perModel: Record<string, number>;
+}
+
+export interface ConcurrencyStatus {
+  limits: ConcurrencyLimits;
+  running: number;
+  runningPerAgent: Record<string, number>;
+  runningPerModel: Record<string, number>;
+  waiting: { runId: string; ticketId: string; reason: string }[];
+}
+
+/** How workers pick the next ticket from the Ready columns */
+export type SchedulingPolicy =
+  | { policy: 'strict_priority' }
+  | {
+      policy: 'fair_share';
+      boardWeights: Record<string, number>;
+      projectWeights: Record<string, number>;
+      windowHours: number;
+    }
+  | { policy: 'priority_aging'; hoursPerLevel: number }
+  | { policy: 'deadline_first'; field: string };
+
+/** One ticket in the ranked queue, with why it sits there */
+export interface QueueEntry {
+  ticketId: string;
+  title: string;
+  boardId: string;
+  boardName: string;
+  projectId?: string;
+  priority: Priority;
+  waitingSince: string;
+  effectivePriority?: Priority;
+  deadline?: string;
+  share?: number;
+  reason: string;
+}
+
+export interface QueueExplanation {
+  policy: SchedulingPolicy;
+  eligible: number;
+  next?: QueueEntry;
+  ranking: QueueEntry[];
+}
+
+/** A machine registered over the REST API that leases tickets */
+export interface RemoteWorker {
+  id: string;
+  name: string;
+  hostname?: string;
+  agentKinds: AgentType[];
+  projectIds: string[];
+  labels: LabelFilter;
+  version?: string;
+  /** Seen within the last two minutes */
+  online: boolean;
+  activeLeases: number;
+  ticketsProcessed: number;
+  registeredAt: string;
+  lastSeenAt: string;
+}
+
+export interface UpdateWorkerDefinitionInput {
+  name?: string;
+  /** Empty string clears the project filter */
+  projectId?: string;
+  labels?: LabelFilter;
+  pollIntervalSecs?: number;
+  agentTimeoutSecs?: number;
+  lockDurationMins?: number;
+  /** No windows removes the schedule */
+  schedule?: WorkerSchedule;
+  enabled?: boolean;
+}
+
 export interface WorkerQueueStatus {
   readyCount: number;
   inProgressCount: number;